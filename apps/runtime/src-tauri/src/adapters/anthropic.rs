@@ -1,5 +1,16 @@
-use crate::agent::types::{LLMResponse, StreamDelta, ToolCall};
+use crate::adapters::model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::adapters::prompt_cache::{split_cached_system_prompt, strip_prompt_cache_boundary};
+use crate::agent::compactor::is_compaction_summary_message;
+use crate::agent::structured_output::{StructuredOutputSpec, STRUCTURED_OUTPUT_TOOL_NAME};
+use crate::agent::types::{
+    LLMResponse, ReasoningSettings, ReasoningTrace, StreamDelta, TokenUsage, ToolCall,
+};
+use crate::model_transport::{ModelTransportKind, ResolvedModelTransport};
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use futures_util::StreamExt;
 use reqwest::{
     header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
@@ -7,40 +18,8 @@ use reqwest::{
 };
 use serde_json::{json, Value};
 
-fn mock_response_text(model: &str, messages: &[Value]) -> String {
-    let last_user = messages
-        .iter()
-        .rev()
-        .find_map(|message| {
-            if message["role"].as_str() == Some("user") {
-                if let Some(content) = message["content"].as_str() {
-                    return Some(content.trim().to_string()).filter(|content| !content.is_empty());
-                }
-                message["content"].as_array().and_then(|parts| {
-                    parts
-                        .iter()
-                        .filter_map(|part| part.get("text").and_then(Value::as_str))
-                        .map(str::trim)
-                        .find(|text| !text.is_empty())
-                        .map(str::to_string)
-                })
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "未提供任务".to_string());
-    format!("MOCK_RESPONSE [{}] {}", model, last_user)
-}
-
-fn is_mock_text_base_url(base_url: &str) -> bool {
-    base_url.trim().eq_ignore_ascii_case("http://mock")
-}
-
-fn is_mock_tool_loop_base_url(base_url: &str) -> bool {
-    base_url
-        .trim()
-        .eq_ignore_ascii_case("http://mock-tool-loop")
-}
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+const DEFAULT_THINKING_BUDGET_TOKENS: u32 = 2048;
 
 fn parse_tool_call_arguments(args_str: &str) -> Result<Value> {
     let trimmed = args_str.trim();
@@ -103,15 +82,93 @@ fn validate_anthropic_test_connection_response(status: StatusCode, body: &str) -
     Err(anyhow!("{}", anthropic_error_message(status, body)))
 }
 
+fn is_direct_anthropic(base_url: &str) -> bool {
+    base_url
+        .trim()
+        .trim_end_matches('/')
+        .to_ascii_lowercase()
+        .contains("api.anthropic.com/v1")
+}
+
 fn supports_extended_thinking(base_url: &str, model: &str) -> bool {
-    let normalized_base_url = base_url.trim().trim_end_matches('/').to_ascii_lowercase();
     let normalized_model = model.trim().to_ascii_lowercase();
-
-    let is_direct_anthropic = normalized_base_url.contains("api.anthropic.com/v1");
     let is_supported_model = normalized_model.starts_with("claude-sonnet-4")
         || normalized_model.starts_with("claude-opus-4");
 
-    is_direct_anthropic && is_supported_model
+    is_direct_anthropic(base_url) && is_supported_model
+}
+
+fn ephemeral_cache_control() -> Value {
+    json!({ "type": "ephemeral" })
+}
+
+/// 官方端点下把系统提示词拆成稳定前缀（带缓存断点）与易变后缀两个 text block
+fn build_anthropic_system(system_prompt: &str, prompt_caching: bool) -> Value {
+    if !prompt_caching {
+        return Value::String(strip_prompt_cache_boundary(system_prompt).into_owned());
+    }
+    let (stable, volatile) = split_cached_system_prompt(system_prompt);
+    let mut blocks = Vec::new();
+    if !stable.trim().is_empty() {
+        blocks.push(json!({
+            "type": "text",
+            "text": stable,
+            "cache_control": ephemeral_cache_control(),
+        }));
+    }
+    if !volatile.trim().is_empty() {
+        blocks.push(json!({ "type": "text", "text": volatile }));
+    }
+    Value::Array(blocks)
+}
+
+/// 工具定义整体作为缓存前缀：断点放在最后一个工具上
+fn mark_anthropic_tools_cached(mut tools: Vec<Value>) -> Vec<Value> {
+    if let Some(last) = tools.last_mut().and_then(Value::as_object_mut) {
+        last.insert("cache_control".to_string(), ephemeral_cache_control());
+    }
+    tools
+}
+
+/// 上下文压缩后的摘要消息在后续每轮都会原样重发，为其单独放置缓存断点
+fn mark_anthropic_compaction_summary_cached(mut messages: Vec<Value>) -> Vec<Value> {
+    let Some(message) = messages
+        .iter_mut()
+        .find(|m| is_compaction_summary_message(m))
+    else {
+        return messages;
+    };
+    if let Some(text) = message["content"].as_str().map(str::to_string) {
+        message["content"] = json!([{
+            "type": "text",
+            "text": text,
+            "cache_control": ephemeral_cache_control(),
+        }]);
+    } else if let Some(last) = message["content"]
+        .as_array_mut()
+        .and_then(|blocks| blocks.last_mut())
+        .and_then(Value::as_object_mut)
+    {
+        last.insert("cache_control".to_string(), ephemeral_cache_control());
+    }
+    messages
+}
+
+/// 显式开启推理时对任意 Anthropic 兼容端点生效；未配置时仅对官方 Claude 4 默认开启
+fn resolve_thinking_budget(
+    base_url: &str,
+    model: &str,
+    reasoning: &ReasoningSettings,
+) -> Option<u32> {
+    if reasoning.is_disabled() {
+        return None;
+    }
+    let allowed = reasoning.enabled == Some(true) || supports_extended_thinking(base_url, model);
+    allowed.then(|| {
+        reasoning
+            .thinking_budget_tokens()
+            .unwrap_or(DEFAULT_THINKING_BUDGET_TOKENS)
+    })
 }
 
 fn build_anthropic_request_body(
@@ -120,34 +177,107 @@ fn build_anthropic_request_body(
     system_prompt: &str,
     messages: Vec<Value>,
     tools: Vec<Value>,
+    reasoning: &ReasoningSettings,
 ) -> Value {
+    let thinking_budget = resolve_thinking_budget(base_url, model, reasoning);
+    // max_tokens 必须大于 budget_tokens，预算较大时为正文保留默认额度
+    let max_tokens = match thinking_budget {
+        Some(budget) if budget >= DEFAULT_MAX_TOKENS => budget + DEFAULT_MAX_TOKENS,
+        _ => DEFAULT_MAX_TOKENS,
+    };
+    let prompt_caching = is_direct_anthropic(base_url);
+    let (messages, tools) = if prompt_caching {
+        (
+            mark_anthropic_compaction_summary_cached(messages),
+            mark_anthropic_tools_cached(tools),
+        )
+    } else {
+        (messages, tools)
+    };
     let mut body = json!({
         "model": model,
-        "system": system_prompt,
+        "system": build_anthropic_system(system_prompt, prompt_caching),
         "messages": messages,
         "tools": tools,
-        "max_tokens": 4096,
+        "max_tokens": max_tokens,
         "stream": true,
     });
 
-    if supports_extended_thinking(base_url, model) {
+    if let Some(budget) = thinking_budget {
         body["thinking"] = json!({
             "type": "enabled",
-            "budget_tokens": 2048,
+            "budget_tokens": budget,
         });
     }
 
     body
 }
 
+/// 以工具形式声明输出 schema；没有其他工具且未开启 thinking 时强制调用该工具
+fn apply_anthropic_structured_output(body: &mut Value, structured_output: &StructuredOutputSpec) {
+    let force_tool =
+        body["tools"].as_array().is_none_or(Vec::is_empty) && body.get("thinking").is_none();
+    let tool = json!({
+        "name": STRUCTURED_OUTPUT_TOOL_NAME,
+        "description": "提交最终结构化结果。任务完成后必须调用此工具，参数即最终答案。",
+        "input_schema": structured_output.schema,
+    });
+    match body["tools"].as_array_mut() {
+        Some(tools) => tools.push(tool),
+        None => body["tools"] = json!([tool]),
+    }
+    if force_tool {
+        body["tool_choice"] = json!({
+            "type": "tool",
+            "name": STRUCTURED_OUTPUT_TOOL_NAME,
+        });
+    }
+}
+
+/// 取出结构化输出工具调用；与其他工具同时出现时丢弃，等模型在后续轮次重新提交
+fn take_anthropic_structured_output(state: &mut AnthropicStreamState) -> Option<String> {
+    let index = state
+        .tool_calls
+        .iter()
+        .position(|call| call.name == STRUCTURED_OUTPUT_TOOL_NAME)?;
+    let call = state.tool_calls.remove(index);
+    if !state.tool_calls.is_empty() {
+        return None;
+    }
+    Some(serde_json::to_string(&call.input).unwrap_or_default())
+}
+
 #[derive(Default)]
 struct AnthropicStreamState {
     tool_calls: Vec<ToolCall>,
     text_content: String,
     current_tool_call: Option<ToolCall>,
     current_tool_input: String,
+    current_thinking_block: Option<Value>,
+    reasoning: ReasoningTrace,
     stop_stream: bool,
     pending_line: String,
+    usage: Option<TokenUsage>,
+}
+
+/// `message_start` 携带输入与缓存用量，`message_delta` 携带累计输出用量；后到的字段覆盖先到的
+fn merge_anthropic_usage(usage: &Value, current: &mut Option<TokenUsage>) {
+    if !usage.is_object() {
+        return;
+    }
+    let entry = current.get_or_insert_with(TokenUsage::default);
+    if let Some(value) = usage["input_tokens"].as_u64() {
+        entry.input_tokens = value;
+    }
+    if let Some(value) = usage["output_tokens"].as_u64() {
+        entry.output_tokens = value;
+    }
+    if let Some(value) = usage["cache_read_input_tokens"].as_u64() {
+        entry.cache_read_tokens = value;
+    }
+    if let Some(value) = usage["cache_creation_input_tokens"].as_u64() {
+        entry.cache_write_tokens = value;
+    }
 }
 
 fn process_anthropic_sse_text(
@@ -174,6 +304,12 @@ fn process_anthropic_sse_text(
                 let event_type = v["type"].as_str().unwrap_or("");
 
                 match event_type {
+                    "message_start" => {
+                        merge_anthropic_usage(&v["message"]["usage"], &mut state.usage);
+                    }
+                    "message_delta" => {
+                        merge_anthropic_usage(&v["usage"], &mut state.usage);
+                    }
                     "content_block_start" => {
                         if v["content_block"]["type"] == "tool_use" {
                             state.current_tool_call = Some(ToolCall {
@@ -185,6 +321,14 @@ fn process_anthropic_sse_text(
                                 input: json!({}),
                             });
                             state.current_tool_input.clear();
+                        } else if v["content_block"]["type"] == "thinking" {
+                            state.current_thinking_block = Some(json!({
+                                "type": "thinking",
+                                "thinking": "",
+                                "signature": "",
+                            }));
+                        } else if v["content_block"]["type"] == "redacted_thinking" {
+                            state.current_thinking_block = Some(v["content_block"].clone());
                         }
                     }
                     "content_block_delta" => {
@@ -195,8 +339,25 @@ fn process_anthropic_sse_text(
                         } else if v["delta"]["type"] == "thinking_delta" {
                             let reasoning = v["delta"]["thinking"].as_str().unwrap_or("");
                             if !reasoning.is_empty() {
+                                state.reasoning.text.push_str(reasoning);
+                                if let Some(block) = state.current_thinking_block.as_mut() {
+                                    let thinking = format!(
+                                        "{}{reasoning}",
+                                        block["thinking"].as_str().unwrap_or_default()
+                                    );
+                                    block["thinking"] = Value::String(thinking);
+                                }
                                 on_token(StreamDelta::Reasoning(reasoning.to_string()));
                             }
+                        } else if v["delta"]["type"] == "signature_delta" {
+                            if let Some(block) = state.current_thinking_block.as_mut() {
+                                let signature = format!(
+                                    "{}{}",
+                                    block["signature"].as_str().unwrap_or_default(),
+                                    v["delta"]["signature"].as_str().unwrap_or_default()
+                                );
+                                block["signature"] = Value::String(signature);
+                            }
                         } else if v["delta"]["type"] == "input_json_delta" {
                             state
                                 .current_tool_input
@@ -216,6 +377,8 @@ fn process_anthropic_sse_text(
                                     };
                             }
                             state.tool_calls.push(call);
+                        } else if let Some(block) = state.current_thinking_block.take() {
+                            state.reasoning.blocks.push(block);
                         }
                     }
                     "message_stop" => {
@@ -231,8 +394,15 @@ fn process_anthropic_sse_text(
     Ok(())
 }
 
-fn finish_anthropic_stream(state: AnthropicStreamState) -> LLMResponse {
-    if !state.tool_calls.is_empty() {
+/// 取出流中累积的推理内容（含需回传的签名 thinking 块）
+fn take_anthropic_reasoning(state: &mut AnthropicStreamState) -> Option<ReasoningTrace> {
+    let reasoning = std::mem::take(&mut state.reasoning);
+    (!reasoning.is_empty()).then_some(reasoning)
+}
+
+fn finish_anthropic_stream(state: AnthropicStreamState) -> (LLMResponse, Option<TokenUsage>) {
+    let usage = state.usage;
+    let response = if !state.tool_calls.is_empty() {
         if !state.text_content.is_empty() {
             LLMResponse::TextWithToolCalls(state.text_content, state.tool_calls)
         } else {
@@ -240,32 +410,36 @@ fn finish_anthropic_stream(state: AnthropicStreamState) -> LLMResponse {
         }
     } else {
         LLMResponse::Text(state.text_content)
-    }
+    };
+    (response, usage)
 }
 
-pub async fn chat_stream_with_tools(
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    system_prompt: &str,
-    messages: Vec<Value>,
-    tools: Vec<Value>,
+/// 发送带工具定义的流式 Messages 请求，同时返回流中上报的 token 用量与推理内容
+pub async fn chat_stream_with_usage(
+    request: ModelChatRequest<'_>,
     mut on_token: impl FnMut(StreamDelta) + Send,
-) -> Result<crate::agent::types::LLMResponse> {
-    if is_mock_text_base_url(base_url) {
-        let mock_text = mock_response_text(model, &messages);
-        on_token(StreamDelta::Text(mock_text.clone()));
-        return Ok(LLMResponse::Text(mock_text));
-    }
-    if is_mock_tool_loop_base_url(base_url) {
-        return Err(anyhow!("达到最大迭代次数 8"));
-    }
-
+) -> Result<ModelChatResponse> {
+    let ModelChatRequest {
+        base_url,
+        api_key,
+        structured_output,
+        ..
+    } = request;
     let client = build_http_client()?;
     let url = anthropic_messages_url(base_url);
     let headers = build_anthropic_headers(api_key)?;
 
-    let body = build_anthropic_request_body(base_url, model, system_prompt, messages, tools);
+    let mut body = build_anthropic_request_body(
+        base_url,
+        request.model,
+        request.system_prompt,
+        request.messages,
+        request.tools,
+        &request.reasoning,
+    );
+    if let Some(spec) = structured_output {
+        apply_anthropic_structured_output(&mut body, spec);
+    }
 
     let resp = client
         .post(&url)
@@ -292,13 +466,22 @@ pub async fn chat_stream_with_tools(
         }
     }
 
-    Ok(finish_anthropic_stream(state))
+    if structured_output.is_some() {
+        if let Some(output) = take_anthropic_structured_output(&mut state) {
+            on_token(StreamDelta::Text(output.clone()));
+            state.text_content.push_str(&output);
+        }
+    }
+    let reasoning = take_anthropic_reasoning(&mut state);
+    let (response, usage) = finish_anthropic_stream(state);
+    Ok(ModelChatResponse {
+        response,
+        usage,
+        reasoning,
+    })
 }
 
 pub async fn test_connection(base_url: &str, api_key: &str, model: &str) -> Result<bool> {
-    if is_mock_text_base_url(base_url) || is_mock_tool_loop_base_url(base_url) {
-        return Ok(true);
-    }
     let client = build_http_client()?;
     let headers = build_anthropic_headers(api_key)?;
     let body = json!({
@@ -318,6 +501,41 @@ pub async fn test_connection(base_url: &str, api_key: &str, model: &str) -> Resu
     validate_anthropic_test_connection_response(status, &text)
 }
 
+/// Anthropic Messages 协议 adapter
+pub struct AnthropicMessagesAdapter;
+
+#[async_trait]
+impl ModelAdapter for AnthropicMessagesAdapter {
+    fn key(&self) -> &str {
+        "anthropic_messages"
+    }
+
+    fn supports(&self, transport: &ResolvedModelTransport, _base_url: &str) -> bool {
+        transport.kind == ModelTransportKind::AnthropicMessages
+    }
+
+    fn capabilities(&self, _transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        ModelAdapterCapabilities {
+            streaming: true,
+            tool_calling: true,
+            usage_reporting: true,
+            vision: true,
+        }
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        chat_stream_with_usage(request, on_token).await
+    }
+
+    async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool> {
+        test_connection(request.base_url, request.api_key, request.model).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,7 +549,7 @@ mod tests {
                 break;
             }
         }
-        Ok((finish_anthropic_stream(state), sink))
+        Ok((finish_anthropic_stream(state).0, sink))
     }
 
     #[test]
@@ -371,7 +589,7 @@ mod tests {
             state.stop_stream,
             "split message_stop event should stop stream"
         );
-        match finish_anthropic_stream(state) {
+        match finish_anthropic_stream(state).0 {
             LLMResponse::Text(text) => assert_eq!(text, "hello"),
             other => panic!("expected text response, got {other:?}"),
         }
@@ -400,6 +618,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn anthropic_usage_merges_message_start_and_delta_counts() {
+        let mut state = AnthropicStreamState::default();
+        for chunk in [
+            "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":120,\"cache_creation_input_tokens\":30,\"cache_read_input_tokens\":400,\"output_tokens\":1}}}\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"hi\"}}\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":42}}\n",
+            "data: {\"type\":\"message_stop\"}\n",
+        ] {
+            process_anthropic_sse_text(chunk, &mut state, &mut |_| {}).expect("parse chunk");
+        }
+
+        let (_, usage) = finish_anthropic_stream(state);
+        assert_eq!(
+            usage,
+            Some(TokenUsage {
+                input_tokens: 120,
+                output_tokens: 42,
+                cache_read_tokens: 400,
+                cache_write_tokens: 30,
+            })
+        );
+    }
+
     #[test]
     fn anthropic_request_body_enables_thinking_for_direct_claude_4_models_only() {
         let direct_body = build_anthropic_request_body(
@@ -408,6 +650,7 @@ mod tests {
             "system",
             vec![],
             vec![],
+            &ReasoningSettings::default(),
         );
         assert_eq!(
             direct_body["thinking"],
@@ -423,6 +666,7 @@ mod tests {
             "system",
             vec![],
             vec![],
+            &ReasoningSettings::default(),
         );
         assert!(third_party_body.get("thinking").is_none());
 
@@ -432,10 +676,190 @@ mod tests {
             "system",
             vec![],
             vec![],
+            &ReasoningSettings::default(),
         );
         assert!(older_claude_body.get("thinking").is_none());
     }
 
+    #[test]
+    fn anthropic_request_body_applies_reasoning_settings() {
+        let high_effort = build_anthropic_request_body(
+            "https://api.anthropic.com/v1",
+            "claude-opus-4-1",
+            "system",
+            vec![],
+            vec![],
+            &ReasoningSettings {
+                effort: Some(crate::agent::types::ReasoningEffort::High),
+                ..ReasoningSettings::default()
+            },
+        );
+        assert_eq!(high_effort["thinking"]["budget_tokens"], 16384);
+        assert_eq!(high_effort["max_tokens"], 16384 + 4096);
+
+        let disabled = build_anthropic_request_body(
+            "https://api.anthropic.com/v1",
+            "claude-sonnet-4-5-20250929",
+            "system",
+            vec![],
+            vec![],
+            &ReasoningSettings {
+                enabled: Some(false),
+                budget_tokens: Some(8000),
+                ..ReasoningSettings::default()
+            },
+        );
+        assert!(disabled.get("thinking").is_none());
+
+        let explicit_third_party = build_anthropic_request_body(
+            "https://api.minimaxi.com/anthropic",
+            "MiniMax-M2.5",
+            "system",
+            vec![],
+            vec![],
+            &ReasoningSettings {
+                enabled: Some(true),
+                budget_tokens: Some(3000),
+                ..ReasoningSettings::default()
+            },
+        );
+        assert_eq!(explicit_third_party["thinking"]["budget_tokens"], 3000);
+        assert_eq!(explicit_third_party["max_tokens"], 4096);
+    }
+
+    #[test]
+    fn anthropic_request_body_places_prompt_cache_breakpoints_on_direct_endpoint() {
+        let system_prompt = format!(
+            "稳定前缀\n\n{}\n\n今天: 2026-03-20",
+            runtime_chat_app::PROMPT_CACHE_BOUNDARY
+        );
+        let messages = vec![
+            json!({"role": "user", "content": "[对话已压缩。完整记录: /tmp/t.jsonl]\n\n## 摘要"}),
+            json!({"role": "assistant", "content": "已了解之前的对话上下文，准备继续工作。"}),
+            json!({"role": "user", "content": "继续"}),
+        ];
+        let tools = vec![
+            json!({"name": "list_dir", "input_schema": {"type": "object"}}),
+            json!({"name": "read_file", "input_schema": {"type": "object"}}),
+        ];
+
+        let body = build_anthropic_request_body(
+            "https://api.anthropic.com/v1",
+            "claude-3-5-haiku",
+            &system_prompt,
+            messages.clone(),
+            tools.clone(),
+            &ReasoningSettings::default(),
+        );
+        assert_eq!(
+            body["system"],
+            json!([
+                {"type": "text", "text": "稳定前缀", "cache_control": {"type": "ephemeral"}},
+                {"type": "text", "text": "今天: 2026-03-20"},
+            ])
+        );
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"]["type"], "ephemeral");
+        assert_eq!(
+            body["messages"][0]["content"][0]["cache_control"]["type"],
+            "ephemeral"
+        );
+        assert_eq!(body["messages"][2]["content"], "继续");
+
+        let proxied = build_anthropic_request_body(
+            "https://api.minimaxi.com/anthropic",
+            "MiniMax-M2.5",
+            &system_prompt,
+            messages,
+            tools,
+            &ReasoningSettings::default(),
+        );
+        assert_eq!(proxied["system"], "稳定前缀\n\n今天: 2026-03-20");
+        assert!(proxied["tools"][1].get("cache_control").is_none());
+        assert!(proxied["messages"][0]["content"].is_string());
+    }
+
+    #[test]
+    fn structured_output_is_declared_as_forced_tool_and_read_back_as_text() {
+        let spec = StructuredOutputSpec::from_schema(
+            "report",
+            Some(&json!({"type": "object", "properties": {"ok": {"type": "boolean"}}})),
+        )
+        .expect("spec");
+        let mut body = build_anthropic_request_body(
+            "https://api.anthropic.com/v1",
+            "claude-3-5-haiku",
+            "system",
+            vec![json!({"role": "user", "content": "hi"})],
+            vec![],
+            &ReasoningSettings::default(),
+        );
+        apply_anthropic_structured_output(&mut body, &spec);
+        assert_eq!(body["tools"][0]["name"], STRUCTURED_OUTPUT_TOOL_NAME);
+        assert_eq!(body["tools"][0]["input_schema"], spec.schema);
+        assert_eq!(body["tool_choice"]["name"], STRUCTURED_OUTPUT_TOOL_NAME);
+
+        let mut with_tools = build_anthropic_request_body(
+            "https://api.anthropic.com/v1",
+            "claude-3-5-haiku",
+            "system",
+            vec![json!({"role": "user", "content": "hi"})],
+            vec![json!({"name": "read_file", "input_schema": {"type": "object"}})],
+            &ReasoningSettings::default(),
+        );
+        apply_anthropic_structured_output(&mut with_tools, &spec);
+        assert_eq!(with_tools["tools"][1]["name"], STRUCTURED_OUTPUT_TOOL_NAME);
+        assert!(with_tools.get("tool_choice").is_none());
+
+        let mut state = AnthropicStreamState {
+            tool_calls: vec![ToolCall {
+                id: "toolu_1".to_string(),
+                name: STRUCTURED_OUTPUT_TOOL_NAME.to_string(),
+                input: json!({"ok": true}),
+            }],
+            ..AnthropicStreamState::default()
+        };
+        assert_eq!(
+            take_anthropic_structured_output(&mut state).as_deref(),
+            Some(r#"{"ok":true}"#)
+        );
+        assert!(state.tool_calls.is_empty());
+    }
+
+    #[test]
+    fn anthropic_stream_keeps_signed_thinking_blocks_for_tool_turns() {
+        let mut state = AnthropicStreamState::default();
+        for chunk in [
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"thinking\",\"thinking\":\"\"}}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"需要先\"}}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"读取文件\"}}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"sig-abc\"}}\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":0}\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"redacted_thinking\",\"data\":\"opaque\"}}\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":1}\n",
+            "data: {\"type\":\"content_block_start\",\"index\":2,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"read_file\"}}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":2,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"path\\\":\\\"a.md\\\"}\"}}\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":2}\n",
+            "data: {\"type\":\"message_stop\"}\n",
+        ] {
+            process_anthropic_sse_text(chunk, &mut state, &mut |_| {}).expect("parse chunk");
+        }
+
+        let reasoning = take_anthropic_reasoning(&mut state).expect("reasoning trace");
+        assert_eq!(reasoning.text, "需要先读取文件");
+        assert_eq!(
+            reasoning.blocks,
+            vec![
+                json!({"type": "thinking", "thinking": "需要先读取文件", "signature": "sig-abc"}),
+                json!({"type": "redacted_thinking", "data": "opaque"}),
+            ]
+        );
+        match finish_anthropic_stream(state).0 {
+            LLMResponse::ToolCalls(calls) => assert_eq!(calls[0].input["path"], "a.md"),
+            other => panic!("expected tool calls, got {other:?}"),
+        }
+    }
+
     #[test]
     fn anthropic_messages_url_supports_minimax_cn_root_path() {
         assert_eq!(
@@ -0,0 +1,677 @@
+use crate::adapters::model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::adapters::prompt_cache::strip_prompt_cache_boundary;
+use crate::agent::types::{LLMResponse, ReasoningSettings, StreamDelta, TokenUsage, ToolCall};
+use crate::model_transport::{ModelTransportKind, ResolvedModelTransport};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{OnceLock, RwLock};
+use uuid::Uuid;
+
+const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Gemini functionDeclarations 只接受 OpenAPI schema 子集，去掉其不认识的 JSON Schema 关键字
+const UNSUPPORTED_SCHEMA_KEYS: [&str; 4] = ["$schema", "additionalProperties", "$id", "$ref"];
+/// 进程内最多缓存的 thoughtSignature 数量，超出后淘汰最早的记录
+const MAX_CACHED_THOUGHT_SIGNATURES: usize = 4096;
+
+/// functionCall 分片上的 thoughtSignature 必须在下一轮原样回传，否则开启思考的模型会拒绝请求。
+/// 消息历史只保留 OpenAI 形状的 tool_calls，因此按工具调用 id 缓存在 adapter 内部。
+#[derive(Default)]
+struct ThoughtSignatureCache {
+    order: VecDeque<String>,
+    signatures: HashMap<String, String>,
+}
+
+fn thought_signatures() -> &'static RwLock<ThoughtSignatureCache> {
+    static CACHE: OnceLock<RwLock<ThoughtSignatureCache>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(ThoughtSignatureCache::default()))
+}
+
+fn remember_thought_signature(call_id: &str, signature: &str) {
+    let mut cache = thought_signatures()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if cache
+        .signatures
+        .insert(call_id.to_string(), signature.to_string())
+        .is_none()
+    {
+        cache.order.push_back(call_id.to_string());
+    }
+    while cache.order.len() > MAX_CACHED_THOUGHT_SIGNATURES {
+        if let Some(oldest) = cache.order.pop_front() {
+            cache.signatures.remove(&oldest);
+        }
+    }
+}
+
+fn cached_thought_signature(call_id: &str) -> Option<String> {
+    thought_signatures()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .signatures
+        .get(call_id)
+        .cloned()
+}
+
+fn build_http_client() -> Result<Client> {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(120))
+        .build()
+        .map_err(|e| anyhow!("构建 Gemini HTTP 客户端失败: {}", e))
+}
+
+fn gemini_base_url(base_url: &str) -> String {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        DEFAULT_GEMINI_BASE_URL.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn gemini_model_url(base_url: &str, model: &str, method: &str) -> String {
+    let model = model.trim().trim_start_matches("models/");
+    format!("{}/models/{}:{}", gemini_base_url(base_url), model, method)
+}
+
+fn sanitize_gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !UNSUPPORTED_SCHEMA_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), sanitize_gemini_schema(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sanitize_gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+fn gemini_tools_from_anthropic_defs(tools: &[Value]) -> Vec<Value> {
+    let declarations = tools
+        .iter()
+        .filter_map(|tool| {
+            let name = tool.get("name").and_then(Value::as_str)?;
+            let mut declaration = json!({
+                "name": name,
+                "description": tool.get("description").and_then(Value::as_str).unwrap_or_default(),
+            });
+            if let Some(schema) = tool.get("input_schema") {
+                declaration["parameters"] = sanitize_gemini_schema(schema);
+            }
+            Some(declaration)
+        })
+        .collect::<Vec<_>>();
+    if declarations.is_empty() {
+        Vec::new()
+    } else {
+        vec![json!({ "functionDeclarations": declarations })]
+    }
+}
+
+fn split_data_url(data_url: &str) -> Option<(String, String)> {
+    let rest = data_url.strip_prefix("data:")?;
+    let (meta, data) = rest.split_once(',')?;
+    let mime_type = meta.strip_suffix(";base64")?;
+    Some((mime_type.to_string(), data.to_string()))
+}
+
+fn inline_image_part(data: &str, fallback_mime_type: &str) -> Value {
+    let (mime_type, data) =
+        split_data_url(data).unwrap_or_else(|| (fallback_mime_type.to_string(), data.to_string()));
+    json!({ "inlineData": { "mimeType": mime_type, "data": data } })
+}
+
+fn attachment_text(attachment: &Value) -> Option<String> {
+    let name = attachment
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("attachment");
+    ["extractedText", "transcript", "summary"]
+        .iter()
+        .filter_map(|key| attachment.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|text| !text.is_empty())
+        .map(|text| format!("[附件 {name}]\n{text}"))
+}
+
+fn content_to_gemini_parts(content: &Value) -> Vec<Value> {
+    match content {
+        Value::String(text) if !text.trim().is_empty() => vec![json!({ "text": text })],
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("text") | Some("input_text") | Some("output_text") => {
+                    let text = part.get("text").and_then(Value::as_str)?;
+                    (!text.trim().is_empty()).then(|| json!({ "text": text }))
+                }
+                Some("image_url") => {
+                    let url = part
+                        .get("image_url")
+                        .and_then(|value| value.get("url"))
+                        .and_then(Value::as_str)?;
+                    split_data_url(url).map(|_| inline_image_part(url, "image/png"))
+                }
+                Some("attachment") => {
+                    let attachment = part.get("attachment")?;
+                    match attachment.get("kind").and_then(Value::as_str) {
+                        Some("image") => {
+                            let data = attachment
+                                .get("data")
+                                .or_else(|| attachment.get("value"))
+                                .and_then(Value::as_str)?;
+                            let mime_type = attachment
+                                .get("mimeType")
+                                .and_then(Value::as_str)
+                                .unwrap_or("image/png");
+                            Some(inline_image_part(data, mime_type))
+                        }
+                        _ => attachment_text(attachment).map(|text| json!({ "text": text })),
+                    }
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_function_arguments(arguments: &Value) -> Value {
+    match arguments {
+        Value::String(raw) => serde_json::from_str(raw).unwrap_or_else(|_| json!({})),
+        Value::Null => json!({}),
+        other => other.clone(),
+    }
+}
+
+fn push_gemini_content(contents: &mut Vec<Value>, role: &str, parts: Vec<Value>) {
+    if parts.is_empty() {
+        return;
+    }
+    // Gemini 要求同一轮的多个 functionResponse 合并在同一条 content 中
+    if let Some(last) = contents.last_mut() {
+        if last["role"].as_str() == Some(role) {
+            if let Some(existing) = last["parts"].as_array_mut() {
+                existing.extend(parts);
+                return;
+            }
+        }
+    }
+    contents.push(json!({ "role": role, "parts": parts }));
+}
+
+/// 将 OpenAI 形状的消息历史转换为 Gemini `contents`
+fn convert_messages_to_gemini_contents(messages: &[Value]) -> Vec<Value> {
+    let mut contents = Vec::new();
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    for message in messages {
+        match message["role"].as_str().unwrap_or_default() {
+            "assistant" => {
+                let mut parts = content_to_gemini_parts(&message["content"]);
+                for call in message["tool_calls"].as_array().into_iter().flatten() {
+                    let id = call["id"].as_str().unwrap_or_default();
+                    let name = call["function"]["name"].as_str().unwrap_or_default();
+                    tool_names.insert(id.to_string(), name.to_string());
+                    let mut part = json!({
+                        "functionCall": {
+                            "name": name,
+                            "args": parse_function_arguments(&call["function"]["arguments"]),
+                        }
+                    });
+                    if let Some(signature) = cached_thought_signature(id) {
+                        part["thoughtSignature"] = json!(signature);
+                    }
+                    parts.push(part);
+                }
+                push_gemini_content(&mut contents, "model", parts);
+            }
+            "tool" => {
+                let call_id = message["tool_call_id"].as_str().unwrap_or_default();
+                let name = tool_names
+                    .get(call_id)
+                    .cloned()
+                    .unwrap_or_else(|| call_id.to_string());
+                let output = match &message["content"] {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                push_gemini_content(
+                    &mut contents,
+                    "user",
+                    vec![json!({
+                        "functionResponse": {
+                            "name": name,
+                            "response": { "content": output },
+                        }
+                    })],
+                );
+            }
+            "user" => {
+                push_gemini_content(
+                    &mut contents,
+                    "user",
+                    content_to_gemini_parts(&message["content"]),
+                );
+            }
+            _ => {}
+        }
+    }
+    contents
+}
+
+/// 显式关闭时把思考预算置 0；仅在配置了推理参数时才下发 thinkingConfig
+fn build_gemini_thinking_config(reasoning: &ReasoningSettings) -> Option<Value> {
+    if reasoning.is_disabled() {
+        return Some(json!({ "thinkingBudget": 0 }));
+    }
+    if reasoning.is_default() {
+        return None;
+    }
+    let mut config = json!({ "includeThoughts": true });
+    if let Some(budget) = reasoning.thinking_budget_tokens() {
+        config["thinkingBudget"] = json!(budget);
+    }
+    Some(config)
+}
+
+fn build_gemini_request_body(
+    system_prompt: &str,
+    messages: &[Value],
+    tools: &[Value],
+    reasoning: &ReasoningSettings,
+) -> Value {
+    let mut body = json!({
+        "contents": convert_messages_to_gemini_contents(messages),
+    });
+    if let Some(thinking_config) = build_gemini_thinking_config(reasoning) {
+        body["generationConfig"] = json!({ "thinkingConfig": thinking_config });
+    }
+    if !system_prompt.trim().is_empty() {
+        body["systemInstruction"] = json!({ "parts": [{ "text": system_prompt }] });
+    }
+    let tools = gemini_tools_from_anthropic_defs(tools);
+    if !tools.is_empty() {
+        body["tools"] = Value::Array(tools);
+    }
+    body
+}
+
+fn gemini_usage_from_value(usage: &Value) -> Option<TokenUsage> {
+    let prompt = usage["promptTokenCount"].as_u64().unwrap_or(0);
+    let cached = usage["cachedContentTokenCount"].as_u64().unwrap_or(0);
+    let output = usage["candidatesTokenCount"].as_u64().unwrap_or(0)
+        + usage["thoughtsTokenCount"].as_u64().unwrap_or(0);
+    let usage = TokenUsage {
+        input_tokens: prompt.saturating_sub(cached),
+        output_tokens: output,
+        cache_read_tokens: cached,
+        cache_write_tokens: 0,
+    };
+    (!usage.is_empty()).then_some(usage)
+}
+
+#[derive(Default)]
+struct GeminiStreamState {
+    pending: String,
+    text_content: String,
+    tool_calls: Vec<ToolCall>,
+    usage: Option<TokenUsage>,
+}
+
+fn process_gemini_event(
+    event: &Value,
+    state: &mut GeminiStreamState,
+    on_token: &mut (impl FnMut(StreamDelta) + ?Sized),
+) -> Result<()> {
+    if let Some(error) = event.get("error") {
+        return Err(anyhow!("Gemini API error: {}", error));
+    }
+    if let Some(usage) = event.get("usageMetadata").and_then(gemini_usage_from_value) {
+        // usageMetadata 在每个分片中都是累计值，保留最后一次即可
+        state.usage = Some(usage);
+    }
+    let parts = event["candidates"][0]["content"]["parts"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for part in parts {
+        if let Some(call) = part.get("functionCall") {
+            let name = call["name"].as_str().unwrap_or_default().to_string();
+            // 未返回 id 时生成全局唯一的 id，避免跨轮次重复导致签名与工具结果错配
+            let id = call["id"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("gemini-call-{}", Uuid::new_v4()));
+            if let Some(signature) = part.get("thoughtSignature").and_then(Value::as_str) {
+                remember_thought_signature(&id, signature);
+            }
+            state.tool_calls.push(ToolCall {
+                id,
+                name,
+                input: parse_function_arguments(&call["args"]),
+            });
+        } else if let Some(text) = part.get("text").and_then(Value::as_str) {
+            if part["thought"].as_bool() == Some(true) {
+                on_token(StreamDelta::Reasoning(text.to_string()));
+            } else {
+                state.text_content.push_str(text);
+                on_token(StreamDelta::Text(text.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn process_gemini_sse_text(
+    text: &str,
+    state: &mut GeminiStreamState,
+    on_token: &mut (impl FnMut(StreamDelta) + ?Sized),
+) -> Result<()> {
+    state.pending.push_str(text);
+    while let Some(line_end) = state.pending.find('\n') {
+        let line = state.pending[..line_end].trim().to_string();
+        state.pending.drain(..=line_end);
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let data = data.trim();
+        if data.is_empty() || data == "[DONE]" {
+            continue;
+        }
+        let event: Value = serde_json::from_str(data)?;
+        process_gemini_event(&event, state, on_token)?;
+    }
+    Ok(())
+}
+
+fn finish_gemini_stream(state: GeminiStreamState) -> (LLMResponse, Option<TokenUsage>) {
+    let response = match (state.text_content.is_empty(), state.tool_calls.is_empty()) {
+        (_, true) => LLMResponse::Text(state.text_content),
+        (true, false) => LLMResponse::ToolCalls(state.tool_calls),
+        (false, false) => LLMResponse::TextWithToolCalls(state.text_content, state.tool_calls),
+    };
+    (response, state.usage)
+}
+
+/// Gemini `streamGenerateContent`（SSE）流式调用，支持 function calling 与内联图片
+pub async fn chat_stream_with_usage(
+    request: ModelChatRequest<'_>,
+    on_token: &mut (dyn FnMut(StreamDelta) + Send),
+) -> Result<(LLMResponse, Option<TokenUsage>)> {
+    let client = build_http_client()?;
+    let url = format!(
+        "{}?alt=sse",
+        gemini_model_url(request.base_url, request.model, "streamGenerateContent")
+    );
+    let body = build_gemini_request_body(
+        request.system_prompt,
+        &request.messages,
+        &request.tools,
+        &request.reasoning,
+    );
+    let resp = client
+        .post(&url)
+        .header("x-goog-api-key", request.api_key)
+        .json(&body)
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await?;
+        return Err(anyhow!("Gemini API error ({}): {}", status.as_u16(), text));
+    }
+
+    let mut stream = resp.bytes_stream();
+    let mut state = GeminiStreamState::default();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        process_gemini_sse_text(&String::from_utf8_lossy(&chunk), &mut state, on_token)?;
+    }
+    process_gemini_sse_text("\n", &mut state, on_token)?;
+
+    let (response, usage) = finish_gemini_stream(state);
+    if matches!(&response, LLMResponse::Text(text) if text.trim().is_empty()) {
+        return Err(anyhow!("Gemini API 返回了空响应，未生成可展示内容"));
+    }
+    Ok((response, usage))
+}
+
+pub async fn test_connection(base_url: &str, api_key: &str, model: &str) -> Result<bool> {
+    let client = build_http_client()?;
+    let resp = client
+        .post(gemini_model_url(base_url, model, "generateContent"))
+        .header("x-goog-api-key", api_key)
+        .json(&json!({
+            "contents": [{ "role": "user", "parts": [{ "text": "hi" }] }],
+            "generationConfig": { "maxOutputTokens": 10 },
+        }))
+        .send()
+        .await?;
+    let status = resp.status();
+    let text = resp.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("Gemini API error ({}): {}", status.as_u16(), text));
+    }
+    let body: Value = serde_json::from_str(&text)?;
+    Ok(body.get("candidates").is_some())
+}
+
+/// Google Gemini 原生 generateContent 协议 adapter
+pub struct GeminiAdapter;
+
+#[async_trait]
+impl ModelAdapter for GeminiAdapter {
+    fn key(&self) -> &str {
+        "gemini"
+    }
+
+    fn supports(&self, transport: &ResolvedModelTransport, _base_url: &str) -> bool {
+        transport.kind == ModelTransportKind::GeminiGenerateContent
+    }
+
+    fn capabilities(&self, _transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        ModelAdapterCapabilities {
+            streaming: true,
+            tool_calling: true,
+            usage_reporting: true,
+            vision: true,
+        }
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        let system_prompt = strip_prompt_cache_boundary(request.system_prompt);
+        let (response, usage) = chat_stream_with_usage(
+            ModelChatRequest {
+                system_prompt: &system_prompt,
+                ..request
+            },
+            on_token,
+        )
+        .await?;
+        Ok(ModelChatResponse {
+            response,
+            usage,
+            reasoning: None,
+        })
+    }
+
+    async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool> {
+        test_connection(request.base_url, request.api_key, request.model).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn gemini_request_body_maps_roles_tool_calls_and_inline_images() {
+        let messages = vec![
+            json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "看看这张图" },
+                    { "type": "image_url", "image_url": { "url": "data:image/jpeg;base64,QUJD" } }
+                ]
+            }),
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [
+                    { "id": "call-1", "type": "function", "function": { "name": "read_file", "arguments": "{\"path\":\"a.txt\"}" } },
+                    { "id": "call-2", "type": "function", "function": { "name": "list_dir", "arguments": "{}" } }
+                ]
+            }),
+            json!({ "role": "tool", "tool_call_id": "call-1", "content": "hello" }),
+            json!({ "role": "tool", "tool_call_id": "call-2", "content": "a.txt" }),
+        ];
+        let tools = vec![json!({
+            "name": "read_file",
+            "description": "读取文件",
+            "input_schema": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": { "path": { "type": "string" } }
+            }
+        })];
+
+        let body =
+            build_gemini_request_body("你是助手", &messages, &tools, &ReasoningSettings::default());
+
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "你是助手");
+        let contents = body["contents"].as_array().expect("contents");
+        assert_eq!(contents.len(), 3);
+        assert_eq!(
+            contents[0]["parts"][1]["inlineData"]["mimeType"],
+            "image/jpeg"
+        );
+        assert_eq!(contents[0]["parts"][1]["inlineData"]["data"], "QUJD");
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(
+            contents[1]["parts"][0]["functionCall"]["args"]["path"],
+            "a.txt"
+        );
+        assert_eq!(contents[2]["role"], "user");
+        assert_eq!(contents[2]["parts"].as_array().map(Vec::len), Some(2));
+        assert_eq!(
+            contents[2]["parts"][1]["functionResponse"]["name"],
+            "list_dir"
+        );
+        let declaration = &body["tools"][0]["functionDeclarations"][0];
+        assert_eq!(declaration["name"], "read_file");
+        assert!(declaration["parameters"]
+            .get("additionalProperties")
+            .is_none());
+        assert!(body.get("generationConfig").is_none());
+    }
+
+    #[test]
+    fn gemini_thinking_config_follows_reasoning_settings() {
+        let disabled = ReasoningSettings {
+            enabled: Some(false),
+            ..ReasoningSettings::default()
+        };
+        assert_eq!(
+            build_gemini_thinking_config(&disabled),
+            Some(json!({ "thinkingBudget": 0 }))
+        );
+
+        let budgeted = ReasoningSettings {
+            budget_tokens: Some(2048),
+            ..ReasoningSettings::default()
+        };
+        assert_eq!(
+            build_gemini_thinking_config(&budgeted),
+            Some(json!({ "includeThoughts": true, "thinkingBudget": 2048 }))
+        );
+    }
+
+    #[test]
+    fn gemini_stream_collects_text_function_calls_and_usage_across_split_chunks() {
+        let mut state = GeminiStreamState::default();
+        let mut deltas = Vec::new();
+        let mut on_token = |delta| deltas.push(delta);
+        let chunks = [
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"思考中\",\"thought\":true}]}}]}\n\ndata: {\"candidates\":[{\"content\":{\"parts\":[{\"te",
+            "xt\":\"我来读取\"}]}}]}\n\n",
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"functionCall\":{\"name\":\"read_file\",\"args\":{\"path\":\"a.txt\"}}}]},\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"promptTokenCount\":120,\"cachedContentTokenCount\":20,\"candidatesTokenCount\":8,\"thoughtsTokenCount\":4}}\n",
+        ];
+        for chunk in chunks {
+            process_gemini_sse_text(chunk, &mut state, &mut on_token).expect("chunk");
+        }
+
+        let (response, usage) = finish_gemini_stream(state);
+        match response {
+            LLMResponse::TextWithToolCalls(text, calls) => {
+                assert_eq!(text, "我来读取");
+                assert_eq!(calls.len(), 1);
+                assert_eq!(calls[0].name, "read_file");
+                assert_eq!(calls[0].input["path"], "a.txt");
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert_eq!(
+            usage,
+            Some(TokenUsage {
+                input_tokens: 100,
+                output_tokens: 12,
+                cache_read_tokens: 20,
+                cache_write_tokens: 0,
+            })
+        );
+        assert_eq!(deltas[0], StreamDelta::Reasoning("思考中".to_string()));
+    }
+
+    #[test]
+    fn gemini_function_call_thought_signatures_are_echoed_with_unique_ids() {
+        let mut state = GeminiStreamState::default();
+        let event = json!({
+            "candidates": [{
+                "content": {
+                    "parts": [
+                        { "functionCall": { "name": "read_file", "args": { "path": "a.txt" } }, "thoughtSignature": "sig-a" },
+                        { "functionCall": { "name": "read_file", "args": { "path": "b.txt" } } }
+                    ]
+                }
+            }]
+        });
+        process_gemini_event(&event, &mut state, &mut |_| {}).expect("event");
+        let mut next_turn = GeminiStreamState::default();
+        process_gemini_event(&event, &mut next_turn, &mut |_| {}).expect("event");
+
+        let ids = state
+            .tool_calls
+            .iter()
+            .chain(&next_turn.tool_calls)
+            .map(|call| call.id.clone())
+            .collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 4);
+
+        let messages = vec![json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": state.tool_calls.iter().map(|call| json!({
+                "id": call.id,
+                "type": "function",
+                "function": { "name": call.name, "arguments": call.input.to_string() }
+            })).collect::<Vec<_>>()
+        })];
+        let contents = convert_messages_to_gemini_contents(&messages);
+        assert_eq!(contents[0]["parts"][0]["thoughtSignature"], "sig-a");
+        assert!(contents[0]["parts"][1].get("thoughtSignature").is_none());
+    }
+}
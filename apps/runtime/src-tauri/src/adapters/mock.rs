@@ -0,0 +1,332 @@
+use crate::adapters::model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::agent::types::{LLMResponse, StreamDelta, TokenUsage, ToolCall};
+use crate::model_transport::ResolvedModelTransport;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const MOCK_TEXT_BASE_URL: &str = "http://mock";
+const MOCK_TOOL_LOOP_BASE_URL: &str = "http://mock-tool-loop";
+
+fn mock_response_text(model: &str, messages: &[Value]) -> String {
+    let last_user = messages
+        .iter()
+        .rev()
+        .find_map(|message| {
+            if message["role"].as_str() == Some("user") {
+                if let Some(content) = message["content"].as_str() {
+                    return Some(content.trim().to_string()).filter(|content| !content.is_empty());
+                }
+                message["content"].as_array().and_then(|parts| {
+                    parts
+                        .iter()
+                        .filter_map(|part| part.get("text").and_then(Value::as_str))
+                        .map(str::trim)
+                        .find(|text| !text.is_empty())
+                        .map(str::to_string)
+                })
+            } else {
+                None
+            }
+        })
+        .unwrap_or_else(|| "未提供任务".to_string());
+    format!("MOCK_RESPONSE [{}] {}", model, last_user)
+}
+
+fn mock_capabilities() -> ModelAdapterCapabilities {
+    ModelAdapterCapabilities {
+        streaming: true,
+        tool_calling: true,
+        usage_reporting: true,
+        vision: false,
+    }
+}
+
+/// 内置 mock 端点：`http://mock` 回显最后一条用户消息，
+/// `http://mock-tool-loop` 模拟达到最大迭代次数；与协议无关。
+pub struct MockModelAdapter;
+
+#[async_trait]
+impl ModelAdapter for MockModelAdapter {
+    fn key(&self) -> &str {
+        "mock"
+    }
+
+    fn supports(&self, _transport: &ResolvedModelTransport, base_url: &str) -> bool {
+        let base_url = base_url.trim();
+        base_url.eq_ignore_ascii_case(MOCK_TEXT_BASE_URL)
+            || base_url.eq_ignore_ascii_case(MOCK_TOOL_LOOP_BASE_URL)
+    }
+
+    fn capabilities(&self, _transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        mock_capabilities()
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        if request
+            .base_url
+            .trim()
+            .eq_ignore_ascii_case(MOCK_TOOL_LOOP_BASE_URL)
+        {
+            return Err(anyhow!("达到最大迭代次数 8"));
+        }
+        let mock_text = mock_response_text(request.model, &request.messages);
+        on_token(StreamDelta::Text(mock_text.clone()));
+        Ok(ModelChatResponse {
+            response: LLMResponse::Text(mock_text),
+            usage: None,
+            reasoning: None,
+        })
+    }
+
+    async fn test_connection(&self, _request: ModelConnectionRequest<'_>) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[derive(Debug)]
+pub enum ScriptedTurn {
+    Response {
+        response: LLMResponse,
+        usage: Option<TokenUsage>,
+    },
+    Error(String),
+}
+
+/// 脚本化 adapter 收到的一次请求，便于测试断言上下文
+#[derive(Debug, Clone)]
+pub struct ScriptedRequest {
+    pub model: String,
+    pub system_prompt: String,
+    pub messages: Vec<Value>,
+    pub tools: Vec<Value>,
+}
+
+/// 按顺序回放预设响应的 adapter，只处理注册时指定的 base_url。
+/// 用于测试多轮工具调用、用量上报与错误路径，无需真实模型服务。
+pub struct ScriptedModelAdapter {
+    base_url: String,
+    turns: Mutex<VecDeque<ScriptedTurn>>,
+    requests: Mutex<Vec<ScriptedRequest>>,
+}
+
+impl ScriptedModelAdapter {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim().to_string(),
+            turns: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn push_turn(&self, turn: ScriptedTurn) -> &Self {
+        self.turns
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push_back(turn);
+        self
+    }
+
+    pub fn push_text(&self, text: &str, usage: Option<TokenUsage>) -> &Self {
+        self.push_turn(ScriptedTurn::Response {
+            response: LLMResponse::Text(text.to_string()),
+            usage,
+        })
+    }
+
+    pub fn push_tool_calls(&self, tool_calls: Vec<ToolCall>, usage: Option<TokenUsage>) -> &Self {
+        self.push_turn(ScriptedTurn::Response {
+            response: LLMResponse::ToolCalls(tool_calls),
+            usage,
+        })
+    }
+
+    pub fn push_error(&self, message: &str) -> &Self {
+        self.push_turn(ScriptedTurn::Error(message.to_string()))
+    }
+
+    pub fn remaining_turns(&self) -> usize {
+        self.turns
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    pub fn recorded_requests(&self) -> Vec<ScriptedRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+#[async_trait]
+impl ModelAdapter for ScriptedModelAdapter {
+    fn key(&self) -> &str {
+        "scripted"
+    }
+
+    fn supports(&self, _transport: &ResolvedModelTransport, base_url: &str) -> bool {
+        base_url.trim().eq_ignore_ascii_case(&self.base_url)
+    }
+
+    fn capabilities(&self, _transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        mock_capabilities()
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(ScriptedRequest {
+                model: request.model.to_string(),
+                system_prompt: request.system_prompt.to_string(),
+                messages: request.messages,
+                tools: request.tools,
+            });
+        let turn = self
+            .turns
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop_front();
+        match turn {
+            Some(ScriptedTurn::Response { response, usage }) => {
+                match &response {
+                    LLMResponse::Text(text) | LLMResponse::TextWithToolCalls(text, _) => {
+                        on_token(StreamDelta::Text(text.clone()));
+                    }
+                    LLMResponse::ToolCalls(_) => {}
+                }
+                Ok(ModelChatResponse {
+                    response,
+                    usage,
+                    reasoning: None,
+                })
+            }
+            Some(ScriptedTurn::Error(message)) => Err(anyhow!(message)),
+            None => Err(anyhow!("脚本化模型响应已耗尽: {}", self.base_url)),
+        }
+    }
+
+    async fn test_connection(&self, _request: ModelConnectionRequest<'_>) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_transport::{ModelTransportKind, ResolvedModelTransport};
+    use serde_json::json;
+
+    fn transport() -> ResolvedModelTransport {
+        ResolvedModelTransport {
+            kind: ModelTransportKind::AnthropicMessages,
+            openai_compat: None,
+        }
+    }
+
+    fn request<'a>(
+        transport: &'a ResolvedModelTransport,
+        base_url: &'a str,
+    ) -> ModelChatRequest<'a> {
+        ModelChatRequest {
+            transport,
+            base_url,
+            api_key: "key",
+            model: "mock-model",
+            system_prompt: "system",
+            messages: vec![json!({"role": "user", "content": "列出文件"})],
+            tools: vec![],
+            reasoning: Default::default(),
+            structured_output: None,
+            usage_scope: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_adapter_echoes_last_user_message() {
+        let transport = transport();
+        let mut streamed = Vec::new();
+        let result = MockModelAdapter
+            .chat_stream(request(&transport, "http://mock"), &mut |delta| {
+                streamed.push(delta)
+            })
+            .await
+            .expect("mock response");
+
+        match result.response {
+            LLMResponse::Text(text) => assert_eq!(text, "MOCK_RESPONSE [mock-model] 列出文件"),
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert_eq!(streamed.len(), 1);
+        assert!(MockModelAdapter
+            .chat_stream(request(&transport, "http://mock-tool-loop"), &mut |_| {})
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn scripted_adapter_replays_turns_in_order_and_records_requests() {
+        let transport = transport();
+        let adapter = ScriptedModelAdapter::new("http://scripted-test");
+        adapter
+            .push_tool_calls(
+                vec![ToolCall {
+                    id: "call-1".to_string(),
+                    name: "list_dir".to_string(),
+                    input: json!({"path": "."}),
+                }],
+                Some(TokenUsage {
+                    input_tokens: 12,
+                    output_tokens: 3,
+                    ..TokenUsage::default()
+                }),
+            )
+            .push_text("完成", None)
+            .push_error("上游错误");
+
+        assert!(adapter.supports(&transport, " http://scripted-test "));
+        assert!(!adapter.supports(&transport, "http://mock"));
+
+        let first = adapter
+            .chat_stream(request(&transport, "http://scripted-test"), &mut |_| {})
+            .await
+            .expect("first turn");
+        assert!(matches!(first.response, LLMResponse::ToolCalls(ref calls) if calls.len() == 1));
+        assert_eq!(first.usage.map(|usage| usage.output_tokens), Some(3));
+
+        let second = adapter
+            .chat_stream(request(&transport, "http://scripted-test"), &mut |_| {})
+            .await
+            .expect("second turn");
+        assert!(matches!(second.response, LLMResponse::Text(ref text) if text == "完成"));
+
+        let third = adapter
+            .chat_stream(request(&transport, "http://scripted-test"), &mut |_| {})
+            .await;
+        assert_eq!(third.expect_err("scripted error").to_string(), "上游错误");
+        assert!(adapter
+            .chat_stream(request(&transport, "http://scripted-test"), &mut |_| {})
+            .await
+            .is_err());
+
+        assert_eq!(adapter.remaining_turns(), 0);
+        let requests = adapter.recorded_requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].system_prompt, "system");
+    }
+}
@@ -1,3 +1,13 @@
 pub mod anthropic;
 pub mod attachment_support;
+pub mod gemini;
+pub mod mock;
+pub mod model_adapter;
+pub mod ollama;
 pub mod openai;
+pub mod prompt_cache;
+
+pub use model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest, ModelUsageScope,
+};
@@ -0,0 +1,80 @@
+use crate::agent::structured_output::StructuredOutputSpec;
+use crate::agent::types::{
+    LLMResponse, ReasoningSettings, ReasoningTrace, StreamDelta, TokenUsage,
+};
+use crate::model_transport::ResolvedModelTransport;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+/// 单次模型调用的请求参数，所有 adapter 共用同一形状
+pub struct ModelChatRequest<'a> {
+    pub transport: &'a ResolvedModelTransport,
+    pub base_url: &'a str,
+    pub api_key: &'a str,
+    pub model: &'a str,
+    pub system_prompt: &'a str,
+    pub messages: Vec<Value>,
+    pub tools: Vec<Value>,
+    pub reasoning: ReasoningSettings,
+    /// 要求最终回答满足的 JSON Schema；adapter 尽量使用原生结构化输出能力
+    pub structured_output: Option<&'a StructuredOutputSpec>,
+    /// 有值时由 adapter 注册表把服务端上报的用量写入 model_usage_records
+    pub usage_scope: Option<ModelUsageScope<'a>>,
+}
+
+/// 模型调用的用量归属
+#[derive(Debug, Clone, Copy)]
+pub struct ModelUsageScope<'a> {
+    pub pool: &'a SqlitePool,
+    /// 不属于任何会话的调用（如翻译）为空字符串
+    pub session_id: &'a str,
+    pub run_id: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct ModelChatResponse {
+    pub response: LLMResponse,
+    /// 服务端上报的 token 用量；未上报时为 None
+    pub usage: Option<TokenUsage>,
+    /// 本次调用的推理内容；未产生推理时为 None
+    pub reasoning: Option<ReasoningTrace>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ModelConnectionRequest<'a> {
+    pub transport: &'a ResolvedModelTransport,
+    pub base_url: &'a str,
+    pub api_key: &'a str,
+    pub model: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ModelAdapterCapabilities {
+    pub streaming: bool,
+    pub tool_calling: bool,
+    pub usage_reporting: bool,
+    pub vision: bool,
+}
+
+/// 模型协议适配层。新增协议时实现该 trait 并在 `ModelAdapterRegistry` 中注册，
+/// 调用方（turn_executor / compactor 等）无需感知具体协议。
+#[async_trait]
+pub trait ModelAdapter: Send + Sync {
+    fn key(&self) -> &str;
+
+    /// 是否由该 adapter 处理给定的 transport 与 base_url
+    fn supports(&self, transport: &ResolvedModelTransport, base_url: &str) -> bool;
+
+    fn capabilities(&self, transport: &ResolvedModelTransport) -> ModelAdapterCapabilities;
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse>;
+
+    async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool>;
+}
@@ -0,0 +1,506 @@
+use crate::adapters::model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::adapters::prompt_cache::strip_prompt_cache_boundary;
+use crate::agent::types::{LLMResponse, ReasoningSettings, StreamDelta, TokenUsage, ToolCall};
+use crate::model_transport::{ModelTransportKind, ResolvedModelTransport};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+fn build_http_client(timeout_secs: u64) -> Result<Client> {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .build()
+        .map_err(|e| anyhow!("构建 Ollama HTTP 客户端失败: {}", e))
+}
+
+/// 统一为 Ollama 根地址：兼容用户填写的 `/v1`（OpenAI 兼容层）或 `/api` 后缀
+pub fn ollama_base_url(base_url: &str) -> String {
+    let mut trimmed = base_url.trim().trim_end_matches('/');
+    for suffix in ["/v1", "/api"] {
+        if let Some(stripped) = trimmed.strip_suffix(suffix) {
+            trimmed = stripped;
+        }
+    }
+    if trimmed.is_empty() {
+        DEFAULT_OLLAMA_BASE_URL.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn ollama_api_url(base_url: &str, path: &str) -> String {
+    format!("{}/api/{}", ollama_base_url(base_url), path)
+}
+
+fn strip_data_url_prefix(data: &str) -> &str {
+    data.split_once(";base64,")
+        .map(|(_, payload)| payload)
+        .unwrap_or(data)
+}
+
+/// Ollama 的消息 content 只接受纯文本，图片以 base64 放入 `images`
+fn split_ollama_content(content: &Value) -> (String, Vec<String>) {
+    match content {
+        Value::String(text) => (text.clone(), Vec::new()),
+        Value::Array(parts) => {
+            let mut texts = Vec::new();
+            let mut images = Vec::new();
+            for part in parts {
+                match part.get("type").and_then(Value::as_str) {
+                    Some("text") | Some("input_text") | Some("output_text") => {
+                        if let Some(text) = part.get("text").and_then(Value::as_str) {
+                            texts.push(text.to_string());
+                        }
+                    }
+                    Some("image_url") => {
+                        if let Some(url) = part
+                            .get("image_url")
+                            .and_then(|value| value.get("url"))
+                            .and_then(Value::as_str)
+                            .filter(|url| url.starts_with("data:"))
+                        {
+                            images.push(strip_data_url_prefix(url).to_string());
+                        }
+                    }
+                    Some("attachment") => {
+                        let Some(attachment) = part.get("attachment") else {
+                            continue;
+                        };
+                        if attachment.get("kind").and_then(Value::as_str) == Some("image") {
+                            if let Some(data) = attachment
+                                .get("data")
+                                .or_else(|| attachment.get("value"))
+                                .and_then(Value::as_str)
+                            {
+                                images.push(strip_data_url_prefix(data).to_string());
+                            }
+                        } else if let Some(text) = ["extractedText", "transcript", "summary"]
+                            .iter()
+                            .filter_map(|key| attachment.get(*key).and_then(Value::as_str))
+                            .find(|text| !text.trim().is_empty())
+                        {
+                            texts.push(text.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            (texts.join("\n"), images)
+        }
+        _ => (String::new(), Vec::new()),
+    }
+}
+
+fn convert_messages_to_ollama(system_prompt: &str, messages: &[Value]) -> Vec<Value> {
+    let mut converted = Vec::with_capacity(messages.len() + 1);
+    if !system_prompt.trim().is_empty() {
+        converted.push(json!({ "role": "system", "content": system_prompt }));
+    }
+    for message in messages {
+        let role = message["role"].as_str().unwrap_or("user");
+        let (content, images) = split_ollama_content(&message["content"]);
+        let mut item = json!({ "role": role, "content": content });
+        if !images.is_empty() {
+            item["images"] = json!(images);
+        }
+        if let Some(calls) = message["tool_calls"].as_array() {
+            item["tool_calls"] = Value::Array(
+                calls
+                    .iter()
+                    .map(|call| {
+                        let arguments = match &call["function"]["arguments"] {
+                            Value::String(raw) => {
+                                serde_json::from_str(raw).unwrap_or_else(|_| json!({}))
+                            }
+                            other => other.clone(),
+                        };
+                        json!({
+                            "function": {
+                                "name": call["function"]["name"],
+                                "arguments": arguments,
+                            }
+                        })
+                    })
+                    .collect(),
+            );
+        }
+        converted.push(item);
+    }
+    converted
+}
+
+fn ollama_tools_from_anthropic_defs(tools: &[Value]) -> Vec<Value> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            Some(json!({
+                "type": "function",
+                "function": {
+                    "name": tool.get("name").and_then(Value::as_str)?,
+                    "description": tool.get("description").and_then(Value::as_str).unwrap_or_default(),
+                    "parameters": tool.get("input_schema").cloned().unwrap_or_else(|| json!({ "type": "object" })),
+                }
+            }))
+        })
+        .collect()
+}
+
+fn build_ollama_chat_request_body(
+    model: &str,
+    system_prompt: &str,
+    messages: &[Value],
+    tools: &[Value],
+    reasoning: &ReasoningSettings,
+) -> Value {
+    let mut body = json!({
+        "model": model,
+        "messages": convert_messages_to_ollama(system_prompt, messages),
+        "stream": true,
+    });
+    // 只有支持思考的本地模型认识 `think`，未显式配置时不下发
+    if let Some(enabled) = reasoning.enabled {
+        body["think"] = json!(enabled);
+    }
+    let tools = ollama_tools_from_anthropic_defs(tools);
+    if !tools.is_empty() {
+        body["tools"] = Value::Array(tools);
+    }
+    body
+}
+
+#[derive(Default)]
+struct OllamaStreamState {
+    pending: String,
+    text_content: String,
+    tool_calls: Vec<ToolCall>,
+    usage: Option<TokenUsage>,
+}
+
+fn process_ollama_line(
+    line: &str,
+    state: &mut OllamaStreamState,
+    on_token: &mut (impl FnMut(StreamDelta) + ?Sized),
+) -> Result<()> {
+    let event: Value = serde_json::from_str(line)?;
+    if let Some(error) = event.get("error").and_then(Value::as_str) {
+        return Err(anyhow!("Ollama API error: {}", error));
+    }
+    let message = &event["message"];
+    if let Some(thinking) = message["thinking"].as_str().filter(|text| !text.is_empty()) {
+        on_token(StreamDelta::Reasoning(thinking.to_string()));
+    }
+    if let Some(text) = message["content"].as_str().filter(|text| !text.is_empty()) {
+        state.text_content.push_str(text);
+        on_token(StreamDelta::Text(text.to_string()));
+    }
+    for call in message["tool_calls"].as_array().into_iter().flatten() {
+        let name = call["function"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        state.tool_calls.push(ToolCall {
+            id: format!("ollama-call-{}-{}", state.tool_calls.len(), name),
+            name,
+            input: match &call["function"]["arguments"] {
+                Value::String(raw) => serde_json::from_str(raw).unwrap_or_else(|_| json!({})),
+                Value::Null => json!({}),
+                other => other.clone(),
+            },
+        });
+    }
+    if event["done"].as_bool() == Some(true) {
+        let usage = TokenUsage {
+            input_tokens: event["prompt_eval_count"].as_u64().unwrap_or(0),
+            output_tokens: event["eval_count"].as_u64().unwrap_or(0),
+            ..TokenUsage::default()
+        };
+        state.usage = (!usage.is_empty()).then_some(usage);
+    }
+    Ok(())
+}
+
+/// `/api/chat` 以 NDJSON 逐行返回，分片可能截断在行中间
+fn process_ollama_ndjson_text(
+    text: &str,
+    state: &mut OllamaStreamState,
+    on_token: &mut (impl FnMut(StreamDelta) + ?Sized),
+) -> Result<()> {
+    state.pending.push_str(text);
+    while let Some(line_end) = state.pending.find('\n') {
+        let line = state.pending[..line_end].trim().to_string();
+        state.pending.drain(..=line_end);
+        if !line.is_empty() {
+            process_ollama_line(&line, state, on_token)?;
+        }
+    }
+    Ok(())
+}
+
+fn finish_ollama_stream(state: OllamaStreamState) -> (LLMResponse, Option<TokenUsage>) {
+    let response = match (state.text_content.is_empty(), state.tool_calls.is_empty()) {
+        (_, true) => LLMResponse::Text(state.text_content),
+        (true, false) => LLMResponse::ToolCalls(state.tool_calls),
+        (false, false) => LLMResponse::TextWithToolCalls(state.text_content, state.tool_calls),
+    };
+    (response, state.usage)
+}
+
+/// Ollama 原生 `/api/chat` 流式调用；本地模型无需 API Key
+pub async fn chat_stream_with_usage(
+    request: ModelChatRequest<'_>,
+    on_token: &mut (dyn FnMut(StreamDelta) + Send),
+) -> Result<(LLMResponse, Option<TokenUsage>)> {
+    // 本地模型首次加载可能较慢，超时放宽
+    let client = build_http_client(600)?;
+    let body = build_ollama_chat_request_body(
+        request.model,
+        request.system_prompt,
+        &request.messages,
+        &request.tools,
+        &request.reasoning,
+    );
+    let resp = client
+        .post(ollama_api_url(request.base_url, "chat"))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| anyhow!("无法连接本地 Ollama 服务: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await?;
+        return Err(anyhow!("Ollama API error ({}): {}", status.as_u16(), text));
+    }
+
+    let mut stream = resp.bytes_stream();
+    let mut state = OllamaStreamState::default();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        process_ollama_ndjson_text(&String::from_utf8_lossy(&chunk), &mut state, on_token)?;
+    }
+    process_ollama_ndjson_text("\n", &mut state, on_token)?;
+    Ok(finish_ollama_stream(state))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub modified_at: String,
+}
+
+fn parse_ollama_tags(body: &str) -> Result<Vec<OllamaModelInfo>> {
+    #[derive(Deserialize)]
+    struct TagsResponse {
+        #[serde(default)]
+        models: Vec<OllamaModelInfo>,
+    }
+    let parsed: TagsResponse = serde_json::from_str(body)?;
+    Ok(parsed.models)
+}
+
+/// 列出本地已拉取的模型（`/api/tags`）
+pub async fn list_local_models(base_url: &str) -> Result<Vec<OllamaModelInfo>> {
+    let client = build_http_client(15)?;
+    let resp = client
+        .get(ollama_api_url(base_url, "tags"))
+        .send()
+        .await
+        .map_err(|e| anyhow!("无法连接本地 Ollama 服务: {}", e))?;
+    let status = resp.status();
+    let text = resp.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("Ollama API error ({}): {}", status.as_u16(), text));
+    }
+    parse_ollama_tags(&text)
+}
+
+/// 拉取模型（`/api/pull`，非流式，等待下载完成）
+pub async fn pull_model(base_url: &str, model: &str) -> Result<()> {
+    let client = build_http_client(3600)?;
+    let resp = client
+        .post(ollama_api_url(base_url, "pull"))
+        .json(&json!({ "model": model, "stream": false }))
+        .send()
+        .await
+        .map_err(|e| anyhow!("无法连接本地 Ollama 服务: {}", e))?;
+    let status = resp.status();
+    let text = resp.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!(
+            "拉取模型 {} 失败 ({}): {}",
+            model,
+            status.as_u16(),
+            text
+        ));
+    }
+    let body: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+    if let Some(error) = body.get("error").and_then(Value::as_str) {
+        return Err(anyhow!("拉取模型 {} 失败: {}", model, error));
+    }
+    Ok(())
+}
+
+pub async fn test_connection(base_url: &str, model: &str) -> Result<bool> {
+    let models = list_local_models(base_url).await?;
+    let model = model.trim();
+    Ok(model.is_empty()
+        || models
+            .iter()
+            .any(|item| item.name == model || item.name.strip_suffix(":latest") == Some(model)))
+}
+
+/// Ollama 原生 `/api/chat` 协议 adapter，用于离线/本地模型
+pub struct OllamaAdapter;
+
+#[async_trait]
+impl ModelAdapter for OllamaAdapter {
+    fn key(&self) -> &str {
+        "ollama"
+    }
+
+    fn supports(&self, transport: &ResolvedModelTransport, _base_url: &str) -> bool {
+        transport.kind == ModelTransportKind::OllamaChat
+    }
+
+    fn capabilities(&self, _transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        ModelAdapterCapabilities {
+            streaming: true,
+            tool_calling: true,
+            usage_reporting: true,
+            vision: true,
+        }
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        let system_prompt = strip_prompt_cache_boundary(request.system_prompt);
+        let (response, usage) = chat_stream_with_usage(
+            ModelChatRequest {
+                system_prompt: &system_prompt,
+                ..request
+            },
+            on_token,
+        )
+        .await?;
+        Ok(ModelChatResponse {
+            response,
+            usage,
+            reasoning: None,
+        })
+    }
+
+    async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool> {
+        test_connection(request.base_url, request.model).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ollama_base_url_strips_openai_and_api_suffixes() {
+        assert_eq!(ollama_base_url(""), "http://localhost:11434");
+        assert_eq!(
+            ollama_base_url("http://127.0.0.1:11434/v1/"),
+            "http://127.0.0.1:11434"
+        );
+        assert_eq!(
+            ollama_base_url("http://gpu-box:11434/api"),
+            "http://gpu-box:11434"
+        );
+    }
+
+    #[test]
+    fn ollama_request_body_moves_images_and_parses_tool_arguments() {
+        let messages = vec![
+            json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "描述图片" },
+                    { "type": "image_url", "image_url": { "url": "data:image/png;base64,QUJD" } }
+                ]
+            }),
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{ "id": "call-1", "type": "function", "function": { "name": "read_file", "arguments": "{\"path\":\"a.txt\"}" } }]
+            }),
+            json!({ "role": "tool", "tool_call_id": "call-1", "content": "hello" }),
+        ];
+
+        let body = build_ollama_chat_request_body(
+            "qwen2.5",
+            "系统",
+            &messages,
+            &[],
+            &ReasoningSettings::default(),
+        );
+        let converted = body["messages"].as_array().expect("messages");
+
+        assert_eq!(converted[0]["role"], "system");
+        assert_eq!(converted[1]["content"], "描述图片");
+        assert_eq!(converted[1]["images"][0], "QUJD");
+        assert_eq!(
+            converted[2]["tool_calls"][0]["function"]["arguments"]["path"],
+            "a.txt"
+        );
+        assert_eq!(converted[3]["role"], "tool");
+        assert!(body.get("tools").is_none());
+        assert!(body.get("think").is_none());
+    }
+
+    #[test]
+    fn ollama_ndjson_stream_collects_text_tool_calls_and_usage() {
+        let mut state = OllamaStreamState::default();
+        let mut deltas = Vec::new();
+        let mut on_token = |delta| deltas.push(delta);
+        for chunk in [
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"好的\"},\"done\":false}\n{\"message\":{\"role\":\"assist",
+            "ant\",\"content\":\"\",\"tool_calls\":[{\"function\":{\"name\":\"list_dir\",\"arguments\":{\"path\":\".\"}}}]},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"prompt_eval_count\":42,\"eval_count\":7}\n",
+        ] {
+            process_ollama_ndjson_text(chunk, &mut state, &mut on_token).expect("chunk");
+        }
+
+        let (response, usage) = finish_ollama_stream(state);
+        match response {
+            LLMResponse::TextWithToolCalls(text, calls) => {
+                assert_eq!(text, "好的");
+                assert_eq!(calls[0].name, "list_dir");
+                assert_eq!(calls[0].input["path"], ".");
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert_eq!(
+            usage.map(|usage| (usage.input_tokens, usage.output_tokens)),
+            Some((42, 7))
+        );
+        assert_eq!(deltas.len(), 1);
+    }
+
+    #[test]
+    fn ollama_tags_response_lists_local_models() {
+        let models = parse_ollama_tags(
+            r#"{"models":[{"name":"qwen2.5:7b","size":4700000000,"modified_at":"2026-01-01T00:00:00Z"},{"name":"llava:latest"}]}"#,
+        )
+        .expect("tags");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "qwen2.5:7b");
+        assert_eq!(models[1].size, 0);
+    }
+}
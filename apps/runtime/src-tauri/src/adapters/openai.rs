@@ -1,47 +1,21 @@
 use crate::adapters::attachment_support::openai_responses_attachment_support;
-use crate::agent::types::{LLMResponse, StreamDelta, ToolCall};
+use crate::adapters::model_adapter::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::adapters::prompt_cache::strip_prompt_cache_boundary;
+use crate::agent::structured_output::StructuredOutputSpec;
+use crate::agent::types::{
+    LLMResponse, ReasoningEffort, ReasoningSettings, StreamDelta, TokenUsage, ToolCall,
+};
 use crate::model_transport::{ModelTransportKind, ResolvedModelTransport};
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
-fn mock_response_text(model: &str, messages: &[Value]) -> String {
-    let last_user = messages
-        .iter()
-        .rev()
-        .find_map(|message| {
-            if message["role"].as_str() == Some("user") {
-                if let Some(content) = message["content"].as_str() {
-                    return Some(content.trim().to_string()).filter(|content| !content.is_empty());
-                }
-                message["content"].as_array().and_then(|parts| {
-                    parts
-                        .iter()
-                        .filter_map(|part| part.get("text").and_then(Value::as_str))
-                        .map(str::trim)
-                        .find(|text| !text.is_empty())
-                        .map(str::to_string)
-                })
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "未提供任务".to_string());
-    format!("MOCK_RESPONSE [{}] {}", model, last_user)
-}
-
-fn is_mock_text_base_url(base_url: &str) -> bool {
-    base_url.trim().eq_ignore_ascii_case("http://mock")
-}
-
-fn is_mock_tool_loop_base_url(base_url: &str) -> bool {
-    base_url
-        .trim()
-        .eq_ignore_ascii_case("http://mock-tool-loop")
-}
-
 fn is_mock_repeat_invalid_write_file_base_url(base_url: &str) -> bool {
     base_url
         .trim()
@@ -612,6 +586,33 @@ fn parse_openai_nonstream_response(body: &str) -> Result<LLMResponse> {
     ))
 }
 
+/// 同时兼容 Chat Completions（prompt/completion_tokens）与 Responses（input/output_tokens）的 usage 结构；
+/// OpenAI 的输入计数包含缓存命中部分，这里拆出来单独计入 `cache_read_tokens`。
+fn openai_usage_from_value(usage: &Value) -> Option<TokenUsage> {
+    if !usage.is_object() {
+        return None;
+    }
+    let input = usage["prompt_tokens"]
+        .as_u64()
+        .or_else(|| usage["input_tokens"].as_u64())?;
+    let output = usage["completion_tokens"]
+        .as_u64()
+        .or_else(|| usage["output_tokens"].as_u64())
+        .unwrap_or(0);
+    let cached = usage["prompt_tokens_details"]["cached_tokens"]
+        .as_u64()
+        .or_else(|| usage["input_tokens_details"]["cached_tokens"].as_u64())
+        .or_else(|| usage["prompt_cache_hit_tokens"].as_u64())
+        .unwrap_or(0)
+        .min(input);
+    Some(TokenUsage {
+        input_tokens: input - cached,
+        output_tokens: output,
+        cache_read_tokens: cached,
+        cache_write_tokens: 0,
+    })
+}
+
 fn llm_response_has_visible_output(response: &LLMResponse) -> bool {
     match response {
         LLMResponse::Text(text) => !text.trim().is_empty(),
@@ -636,7 +637,7 @@ async fn retry_openai_without_stream(
     api_key: &str,
     request_body: &Value,
     on_token: &mut impl FnMut(StreamDelta),
-) -> Result<LLMResponse> {
+) -> Result<(LLMResponse, Option<TokenUsage>)> {
     let mut fallback_body = request_body.clone();
     if let Some(object) = fallback_body.as_object_mut() {
         object.insert("stream".to_string(), Value::Bool(false));
@@ -658,6 +659,9 @@ async fn retry_openai_without_stream(
 
     let text = resp.text().await?;
     let response = parse_openai_nonstream_response(&text)?;
+    let usage = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|parsed| openai_usage_from_value(&parsed["usage"]));
     match &response {
         LLMResponse::Text(content) if !content.trim().is_empty() => {
             on_token(StreamDelta::Text(content.clone()));
@@ -667,7 +671,7 @@ async fn retry_openai_without_stream(
         }
         _ => {}
     }
-    Ok(response)
+    Ok((response, usage))
 }
 
 fn openai_responses_url(base_url: &str) -> String {
@@ -744,20 +748,44 @@ fn build_openai_chat_completion_messages(system_prompt: &str, messages: Vec<Valu
     request_messages
 }
 
+/// 仅在显式配置推理档位时下发，避免向不支持该参数的模型发送未知字段
+fn configured_reasoning_effort(reasoning: &ReasoningSettings) -> Option<&'static str> {
+    if reasoning.is_disabled() {
+        return None;
+    }
+    reasoning.effort.map(ReasoningEffort::as_str)
+}
+
 fn build_openai_responses_request_body(
     model: &str,
     system_prompt: &str,
     messages: &[Value],
     tools: &[Value],
+    reasoning: &ReasoningSettings,
 ) -> Result<Value> {
     validate_openai_responses_attachments(messages)?;
-    Ok(json!({
+    let mut body = json!({
         "model": model,
         "instructions": system_prompt,
         "input": convert_messages_to_responses_input(messages),
         "tools": openai_responses_tools_from_anthropic_defs(tools),
         "stream": true,
-    }))
+    });
+    if let Some(effort) = configured_reasoning_effort(reasoning) {
+        body["reasoning"] = json!({ "effort": effort });
+    }
+    Ok(body)
+}
+
+/// 部分 OpenAI 兼容端点不认识 stream_options，返回 400 时去掉后重试一次
+fn is_stream_options_rejection(
+    status: reqwest::StatusCode,
+    body: &Value,
+    error_text: &str,
+) -> bool {
+    status == reqwest::StatusCode::BAD_REQUEST
+        && body.get("stream_options").is_some()
+        && error_text.contains("stream_options")
 }
 
 fn build_openai_chat_completions_request_body(
@@ -766,6 +794,7 @@ fn build_openai_chat_completions_request_body(
     system_prompt: &str,
     messages: Vec<Value>,
     tools: &[Value],
+    reasoning: &ReasoningSettings,
 ) -> Value {
     let mut body = json!({
         "model": model,
@@ -773,6 +802,9 @@ fn build_openai_chat_completions_request_body(
         "tools": openai_tools_from_anthropic_defs(tools),
         "stream": true,
     });
+    if let Some(effort) = configured_reasoning_effort(reasoning) {
+        body["reasoning_effort"] = json!(effort);
+    }
 
     if transport
         .openai_compat
@@ -790,6 +822,43 @@ fn build_openai_chat_completions_request_body(
     body
 }
 
+/// 仅对支持 strict 模式的 endpoint 下发 json_schema，其余 endpoint 依赖系统 prompt 约束
+fn apply_openai_structured_output(
+    body: &mut Value,
+    transport: &ResolvedModelTransport,
+    structured_output: Option<&StructuredOutputSpec>,
+) {
+    let Some(spec) = structured_output else {
+        return;
+    };
+    if !transport
+        .openai_compat
+        .is_some_and(|features| features.supports_strict_mode)
+    {
+        return;
+    }
+    let strict = spec.is_strict_compatible();
+    if transport.kind == ModelTransportKind::OpenAiResponses {
+        body["text"] = json!({
+            "format": {
+                "type": "json_schema",
+                "name": spec.name,
+                "schema": spec.schema,
+                "strict": strict,
+            }
+        });
+    } else {
+        body["response_format"] = json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": spec.name,
+                "schema": spec.schema,
+                "strict": strict,
+            }
+        });
+    }
+}
+
 fn last_tool_message_content(messages: &[Value]) -> Option<String> {
     messages
         .iter()
@@ -941,6 +1010,7 @@ struct OpenAiStreamState {
     tool_calls_map: HashMap<u64, OpenAiResponseToolCall>,
     stop_stream: bool,
     pending_line: String,
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Default)]
@@ -1026,6 +1096,11 @@ fn process_openai_sse_text(
                             entry.arguments.push_str(delta);
                         }
                     }
+                    "response.completed" => {
+                        if let Some(usage) = openai_usage_from_value(&v["response"]["usage"]) {
+                            state.usage = Some(usage);
+                        }
+                    }
                     "response.function_call_arguments.done" | "response.output_item.done" => {
                         let item = &v["item"];
                         match item["type"].as_str().unwrap_or_default() {
@@ -1074,6 +1149,10 @@ fn process_openai_sse_text(
                         }
                     }
                     _ => {
+                        // include_usage 时最后一个 chunk 的 choices 为空，只携带 usage
+                        if let Some(usage) = openai_usage_from_value(&v["usage"]) {
+                            state.usage = Some(usage);
+                        }
                         if let Some(choice) = v["choices"].get(0) {
                             let delta = &choice["delta"];
 
@@ -1201,25 +1280,33 @@ fn finish_openai_stream(mut state: OpenAiStreamState) -> LLMResponse {
 /// 发送带 `tools` 和 `stream: true` 的请求，并解析增量 SSE delta 中的 tool_calls。
 ///
 /// 当 `finish_reason == "tool_calls"` 时返回 `LLMResponse::ToolCalls`，
-/// 否则返回 `LLMResponse::Text`。
-pub async fn chat_stream_with_tools(
-    transport: &ResolvedModelTransport,
-    base_url: &str,
-    api_key: &str,
-    model: &str,
-    system_prompt: &str,
-    messages: Vec<Value>,
-    tools: Vec<Value>,
+/// 否则返回 `LLMResponse::Text`；同时返回服务端上报的 token 用量（未上报时为 None）。
+pub async fn chat_stream_with_usage(
+    request: ModelChatRequest<'_>,
+    on_token: impl FnMut(StreamDelta) + Send,
+) -> Result<(LLMResponse, Option<TokenUsage>)> {
+    let mut usage = None;
+    let response = stream_chat_with_tools(request, on_token, &mut usage).await?;
+    Ok((response, usage))
+}
+
+async fn stream_chat_with_tools(
+    request: ModelChatRequest<'_>,
     mut on_token: impl FnMut(StreamDelta) + Send,
-) -> Result<crate::agent::types::LLMResponse> {
-    if is_mock_text_base_url(base_url) {
-        let mock_text = mock_response_text(model, &messages);
-        on_token(StreamDelta::Text(mock_text.clone()));
-        return Ok(LLMResponse::Text(mock_text));
-    }
-    if is_mock_tool_loop_base_url(base_url) {
-        return Err(anyhow!("达到最大迭代次数 8"));
-    }
+    usage: &mut Option<TokenUsage>,
+) -> Result<LLMResponse> {
+    let ModelChatRequest {
+        transport,
+        base_url,
+        api_key,
+        model,
+        system_prompt,
+        messages,
+        tools,
+        reasoning,
+        structured_output,
+        ..
+    } = request;
     if is_mock_repeat_invalid_write_file_base_url(base_url) {
         return Ok(LLMResponse::ToolCalls(vec![ToolCall {
             id: "mock-write-file-empty".to_string(),
@@ -1330,10 +1417,16 @@ pub async fn chat_stream_with_tools(
     }
 
     let client = build_http_client()?;
-    let (url, body) = match transport.kind {
+    let (url, mut body) = match transport.kind {
         ModelTransportKind::OpenAiResponses => (
             openai_responses_url(base_url),
-            build_openai_responses_request_body(model, system_prompt, &messages, &tools)?,
+            build_openai_responses_request_body(
+                model,
+                system_prompt,
+                &messages,
+                &tools,
+                &reasoning,
+            )?,
         ),
         ModelTransportKind::OpenAiCompletions => (
             openai_chat_completions_url(base_url),
@@ -1343,14 +1436,20 @@ pub async fn chat_stream_with_tools(
                 system_prompt,
                 messages,
                 &tools,
+                &reasoning,
             ),
         ),
-        ModelTransportKind::AnthropicMessages => {
-            return Err(anyhow!("OpenAI adapter 不支持 Anthropic transport"));
+        ModelTransportKind::AnthropicMessages
+        | ModelTransportKind::GeminiGenerateContent
+        | ModelTransportKind::OllamaChat => {
+            return Err(anyhow!(
+                "OpenAI adapter 不支持 {} transport",
+                transport.kind.api_format()
+            ));
         }
     };
-    let request_body = body.clone();
-    let resp = client
+    apply_openai_structured_output(&mut body, transport, structured_output);
+    let mut resp = client
         .post(&url)
         .bearer_auth(api_key)
         .header("content-type", "application/json")
@@ -1359,9 +1458,28 @@ pub async fn chat_stream_with_tools(
         .await?;
 
     if !resp.status().is_success() {
+        let status = resp.status();
         let text = resp.text().await?;
-        return Err(anyhow!("OpenAI API error: {}", text));
+        if !is_stream_options_rejection(status, &body, &text) {
+            return Err(anyhow!("OpenAI API error: {}", text));
+        }
+        eprintln!("[openai] endpoint rejected stream_options; retrying once without usage");
+        if let Some(object) = body.as_object_mut() {
+            object.remove("stream_options");
+        }
+        resp = client
+            .post(&url)
+            .bearer_auth(api_key)
+            .header("content-type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await?;
+            return Err(anyhow!("OpenAI API error: {}", text));
+        }
     }
+    let request_body = body.clone();
 
     let mut stream = resp.bytes_stream();
     let mut state = OpenAiStreamState::default();
@@ -1375,12 +1493,19 @@ pub async fn chat_stream_with_tools(
         }
     }
 
+    *usage = state.usage;
     let mut response = finish_openai_stream(state);
     if should_retry_openai_without_stream(transport, &response) {
         eprintln!("[openai] streamed response had no visible text; retrying once without stream");
-        response =
+        let (retried, retry_usage) =
             retry_openai_without_stream(&client, &url, api_key, &request_body, &mut on_token)
                 .await?;
+        response = retried;
+        if let Some(retry_usage) = retry_usage {
+            usage
+                .get_or_insert_with(TokenUsage::default)
+                .add(&retry_usage);
+        }
     }
 
     if !llm_response_has_visible_output(&response) {
@@ -1396,9 +1521,7 @@ pub async fn test_connection(
     api_key: &str,
     model: &str,
 ) -> Result<bool> {
-    if is_mock_text_base_url(base_url)
-        || is_mock_tool_loop_base_url(base_url)
-        || is_mock_repeat_invalid_write_file_base_url(base_url)
+    if is_mock_repeat_invalid_write_file_base_url(base_url)
         || is_mock_write_file_from_user_path_base_url(base_url)
         || is_mock_repeat_read_file_loop_base_url(base_url)
         || is_mock_list_dir_with_interleaved_move_failures_base_url(base_url)
@@ -1423,8 +1546,13 @@ pub async fn test_connection(
                 "max_tokens": 10
             }),
         ),
-        ModelTransportKind::AnthropicMessages => {
-            return Err(anyhow!("OpenAI adapter 不支持 Anthropic transport"));
+        ModelTransportKind::AnthropicMessages
+        | ModelTransportKind::GeminiGenerateContent
+        | ModelTransportKind::OllamaChat => {
+            return Err(anyhow!(
+                "OpenAI adapter 不支持 {} transport",
+                transport.kind.api_format()
+            ));
         }
     };
     let resp = client
@@ -1441,6 +1569,67 @@ pub async fn test_connection(
     validate_test_connection_response(&text)
 }
 
+/// OpenAI 兼容协议（Chat Completions / Responses）adapter
+pub struct OpenAiCompatAdapter;
+
+#[async_trait]
+impl ModelAdapter for OpenAiCompatAdapter {
+    fn key(&self) -> &str {
+        "openai_compat"
+    }
+
+    fn supports(&self, transport: &ResolvedModelTransport, _base_url: &str) -> bool {
+        matches!(
+            transport.kind,
+            ModelTransportKind::OpenAiCompletions | ModelTransportKind::OpenAiResponses
+        )
+    }
+
+    fn capabilities(&self, transport: &ResolvedModelTransport) -> ModelAdapterCapabilities {
+        let is_responses = transport.kind == ModelTransportKind::OpenAiResponses;
+        ModelAdapterCapabilities {
+            streaming: true,
+            tool_calling: true,
+            usage_reporting: is_responses
+                || transport
+                    .openai_compat
+                    .is_some_and(|features| features.supports_usage_in_streaming),
+            vision: is_responses && openai_responses_attachment_support().native_image,
+        }
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        let system_prompt = strip_prompt_cache_boundary(request.system_prompt);
+        let (response, usage) = chat_stream_with_usage(
+            ModelChatRequest {
+                system_prompt: &system_prompt,
+                ..request
+            },
+            on_token,
+        )
+        .await?;
+        Ok(ModelChatResponse {
+            response,
+            usage,
+            reasoning: None,
+        })
+    }
+
+    async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool> {
+        test_connection(
+            request.transport,
+            request.base_url,
+            request.api_key,
+            request.model,
+        )
+        .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1480,6 +1669,60 @@ mod tests {
         Ok(finish_openai_stream(state))
     }
 
+    fn parse_openai_usage_for_test(chunks: &[&str]) -> Option<TokenUsage> {
+        let mut state = OpenAiStreamState::default();
+        for chunk in chunks {
+            process_openai_sse_text(chunk, &mut state, &mut |_| {}).expect("parse chunk");
+            if state.stop_stream {
+                break;
+            }
+        }
+        state.usage
+    }
+
+    #[test]
+    fn chat_completions_usage_chunk_splits_cached_prompt_tokens() {
+        let usage = parse_openai_usage_for_test(&[
+            "data: {\"choices\":[{\"delta\":{\"content\":\"hello\"},\"finish_reason\":\"stop\"}]}\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":1000,\"completion_tokens\":50,\"prompt_tokens_details\":{\"cached_tokens\":800}}}\n",
+            "data: [DONE]\n",
+        ]);
+
+        assert_eq!(
+            usage,
+            Some(TokenUsage {
+                input_tokens: 200,
+                output_tokens: 50,
+                cache_read_tokens: 800,
+                cache_write_tokens: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn responses_completed_event_reports_usage() {
+        let usage = parse_openai_usage_for_test(&[
+            "event: response.completed\n",
+            "data: {\"type\":\"response.completed\",\"response\":{\"status\":\"completed\",\"usage\":{\"input_tokens\":300,\"output_tokens\":20,\"input_tokens_details\":{\"cached_tokens\":100}}}}\n",
+        ]);
+
+        assert_eq!(
+            usage,
+            Some(TokenUsage {
+                input_tokens: 200,
+                output_tokens: 20,
+                cache_read_tokens: 100,
+                cache_write_tokens: 0,
+            })
+        );
+        assert_eq!(
+            parse_openai_usage_for_test(&[
+                "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}],\"usage\":null}\n"
+            ]),
+            None
+        );
+    }
+
     #[test]
     fn done_marker_stops_processing_later_chunks() {
         let response = parse_openai_chunks_for_test(&[
@@ -1728,12 +1971,14 @@ mod tests {
                     }
                 }
             })],
+            &ReasoningSettings::default(),
         );
 
         assert_eq!(
             body["stream_options"]["include_usage"].as_bool(),
             Some(true)
         );
+        assert!(body.get("reasoning_effort").is_none());
     }
 
     #[test]
@@ -1751,11 +1996,129 @@ mod tests {
             "system prompt",
             vec![json!({ "role": "user", "content": "hello" })],
             &[],
+            &ReasoningSettings::default(),
         );
 
         assert!(body.get("stream_options").is_none());
     }
 
+    #[test]
+    fn stream_options_rejection_requires_bad_request_mentioning_the_field() {
+        let body = json!({ "stream": true, "stream_options": { "include_usage": true } });
+
+        assert!(is_stream_options_rejection(
+            reqwest::StatusCode::BAD_REQUEST,
+            &body,
+            r#"{"error":{"message":"Unrecognized request argument supplied: stream_options"}}"#,
+        ));
+        assert!(!is_stream_options_rejection(
+            reqwest::StatusCode::BAD_REQUEST,
+            &body,
+            r#"{"error":{"message":"invalid model"}}"#,
+        ));
+        assert!(!is_stream_options_rejection(
+            reqwest::StatusCode::UNAUTHORIZED,
+            &body,
+            "stream_options",
+        ));
+        assert!(!is_stream_options_rejection(
+            reqwest::StatusCode::BAD_REQUEST,
+            &json!({ "stream": true }),
+            "stream_options",
+        ));
+    }
+
+    #[test]
+    fn structured_output_uses_json_schema_only_for_strict_mode_endpoints() {
+        let spec = StructuredOutputSpec::from_schema(
+            "report",
+            Some(&json!({
+                "type": "object",
+                "required": ["ok"],
+                "additionalProperties": false,
+                "properties": { "ok": { "type": "boolean" } }
+            })),
+        )
+        .expect("spec");
+        let native = openai_transport_for_test(ModelTransportKind::OpenAiResponses, true);
+        let mut responses_body = json!({});
+        apply_openai_structured_output(&mut responses_body, &native, Some(&spec));
+        assert_eq!(responses_body["text"]["format"]["type"], "json_schema");
+        assert_eq!(responses_body["text"]["format"]["name"], "report");
+        assert_eq!(responses_body["text"]["format"]["strict"], true);
+
+        let completions = openai_transport_for_test(ModelTransportKind::OpenAiCompletions, true);
+        let mut completions_body = json!({});
+        apply_openai_structured_output(&mut completions_body, &completions, Some(&spec));
+        assert_eq!(
+            completions_body["response_format"]["json_schema"]["schema"]["required"][0],
+            "ok"
+        );
+
+        let generic = openai_transport_for_test(ModelTransportKind::OpenAiCompletions, false);
+        let mut generic_body = json!({});
+        apply_openai_structured_output(&mut generic_body, &generic, Some(&spec));
+        assert!(generic_body.get("response_format").is_none());
+    }
+
+    fn openai_transport_for_test(
+        kind: ModelTransportKind,
+        supports_strict_mode: bool,
+    ) -> ResolvedModelTransport {
+        ResolvedModelTransport {
+            kind,
+            openai_compat: Some(crate::model_transport::OpenAiCompatFeatures {
+                supports_developer_role: supports_strict_mode,
+                supports_usage_in_streaming: supports_strict_mode,
+                supports_strict_mode,
+            }),
+        }
+    }
+
+    #[test]
+    fn request_bodies_carry_configured_reasoning_effort() {
+        let reasoning = ReasoningSettings {
+            effort: Some(ReasoningEffort::High),
+            ..ReasoningSettings::default()
+        };
+        let responses_body = build_openai_responses_request_body(
+            "o4-mini",
+            "system prompt",
+            &[json!({ "role": "user", "content": "hello" })],
+            &[],
+            &reasoning,
+        )
+        .expect("responses body");
+        assert_eq!(responses_body["reasoning"]["effort"], "high");
+
+        let completions_body = build_openai_chat_completions_request_body(
+            &ResolvedModelTransport {
+                kind: ModelTransportKind::OpenAiCompletions,
+                openai_compat: None,
+            },
+            "o4-mini",
+            "system prompt",
+            vec![json!({ "role": "user", "content": "hello" })],
+            &[],
+            &reasoning,
+        );
+        assert_eq!(completions_body["reasoning_effort"], "high");
+
+        let disabled = ReasoningSettings {
+            enabled: Some(false),
+            ..reasoning
+        };
+        let disabled_body = build_openai_responses_request_body(
+            "o4-mini",
+            "system prompt",
+            &[json!({ "role": "user", "content": "hello" })],
+            &[],
+            &disabled,
+        )
+        .expect("responses body");
+        assert!(disabled_body.get("reasoning").is_none());
+    }
+
     #[test]
     fn convert_messages_to_responses_input_replays_tool_calls_and_outputs() {
         let messages = vec![
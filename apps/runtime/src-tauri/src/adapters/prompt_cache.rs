@@ -0,0 +1,49 @@
+use runtime_chat_app::PROMPT_CACHE_BOUNDARY;
+use std::borrow::Cow;
+
+/// 按缓存分界拆分系统提示词，返回（稳定前缀，易变后缀）；没有分界时整段视为稳定前缀
+pub fn split_cached_system_prompt(system_prompt: &str) -> (&str, &str) {
+    match system_prompt.split_once(PROMPT_CACHE_BOUNDARY) {
+        Some((stable, volatile)) => (stable.trim_end(), volatile.trim_start()),
+        None => (system_prompt, ""),
+    }
+}
+
+/// 去掉缓存分界标记。OpenAI / Gemini / Ollama 按请求前缀自动缓存，只需保持章节顺序稳定
+pub fn strip_prompt_cache_boundary(system_prompt: &str) -> Cow<'_, str> {
+    let Some((stable, volatile)) = system_prompt.split_once(PROMPT_CACHE_BOUNDARY) else {
+        return Cow::Borrowed(system_prompt);
+    };
+    let parts = [stable.trim_end(), volatile.trim_start()];
+    Cow::Owned(
+        parts
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_strip_cache_boundary() {
+        let prompt = format!("稳定前缀\n\n{PROMPT_CACHE_BOUNDARY}\n\n今天: 2026-03-20");
+
+        assert_eq!(
+            split_cached_system_prompt(&prompt),
+            ("稳定前缀", "今天: 2026-03-20")
+        );
+        assert_eq!(
+            strip_prompt_cache_boundary(&prompt),
+            "稳定前缀\n\n今天: 2026-03-20"
+        );
+        assert_eq!(split_cached_system_prompt("无分界"), ("无分界", ""));
+        assert!(matches!(
+            strip_prompt_cache_boundary("无分界"),
+            Cow::Borrowed("无分界")
+        ));
+    }
+}
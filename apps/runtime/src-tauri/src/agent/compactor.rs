@@ -1,10 +1,15 @@
-use crate::adapters;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind};
+use crate::adapters::{ModelChatRequest, ModelUsageScope};
+use crate::model_catalog::current_model_catalog;
+use crate::model_transport::resolve_model_transport;
+use crate::providers::model_adapters;
 use anyhow::Result;
+use runtime_executor_core::{
+    ContextBudget, AUTO_COMPACT_PERCENT, DEFAULT_TOKEN_BUDGET, MICRO_COMPACT_PERCENT,
+};
 use serde_json::{json, Value};
 use std::path::PathBuf;
 
-/// 自动压缩触发的 token 阈值
+/// 模型目录未收录该模型时，自动压缩触发的 token 阈值
 const AUTO_COMPACT_THRESHOLD: usize = 50_000;
 
 /// 摘要生成的系统提示词
@@ -39,9 +44,49 @@ const COMPACT_USER_PROMPT: &str = r#"请总结以下对话，确保连续性。
 对话内容：
 "#;
 
+/// 压缩摘要消息的固定前缀，后接完整记录路径
+const COMPACTION_SUMMARY_PREFIX: &str = "[对话已压缩。完整记录:";
+
+/// 按模型目录解析上下文窗口（已扣除最大输出）；目录未收录时返回 None
+pub fn context_budget_for_model(model: &str) -> Option<ContextBudget> {
+    current_model_catalog()
+        .lookup(model)
+        .and_then(|entry| {
+            entry.context_window.map(|context_window| {
+                ContextBudget::new(
+                    context_window as usize,
+                    entry.max_output_tokens.unwrap_or(0) as usize,
+                )
+            })
+        })
+        .filter(|budget| budget.input_window() > 0)
+}
+
+/// 自动压缩阈值：可用输入窗口的 `AUTO_COMPACT_PERCENT`
+pub fn auto_compact_threshold(model: &str) -> usize {
+    context_budget_for_model(model)
+        .map(|budget| budget.auto_compact_threshold())
+        .unwrap_or(AUTO_COMPACT_THRESHOLD)
+}
+
 /// 检查是否需要自动压缩
-pub fn needs_auto_compact(estimated_tokens: usize) -> bool {
-    estimated_tokens > AUTO_COMPACT_THRESHOLD
+pub fn needs_auto_compact(estimated_tokens: usize, model: &str) -> bool {
+    estimated_tokens > auto_compact_threshold(model)
+}
+
+/// 历史占用超过可用输入窗口的 `MICRO_COMPACT_PERCENT` 后才折叠旧工具结果
+pub fn needs_micro_compact(estimated_tokens: usize, model: &str) -> bool {
+    let threshold = context_budget_for_model(model)
+        .map(|budget| budget.micro_compact_threshold())
+        .unwrap_or(AUTO_COMPACT_THRESHOLD * MICRO_COMPACT_PERCENT / AUTO_COMPACT_PERCENT);
+    estimated_tokens > threshold
+}
+
+/// 硬裁剪的 token 预算：整个可用输入窗口，未收录的模型沿用默认预算
+pub fn trim_budget_for_model(model: &str) -> usize {
+    context_budget_for_model(model)
+        .map(|budget| budget.input_window())
+        .unwrap_or(DEFAULT_TOKEN_BUDGET)
 }
 
 /// 从压缩后的消息中提取适合 UI / 运行状态展示的摘要正文。
@@ -100,8 +145,16 @@ pub fn build_compaction_rehydration_context(messages: &[Value]) -> String {
     lines.join("\n")
 }
 
+/// 是否为 `auto_compact` 生成的摘要消息
+pub fn is_compaction_summary_message(message: &Value) -> bool {
+    message["role"].as_str() == Some("user")
+        && message["content"]
+            .as_str()
+            .is_some_and(|content| content.starts_with(COMPACTION_SUMMARY_PREFIX))
+}
+
 fn strip_compaction_transcript_header(content: &str) -> &str {
-    let Some(rest) = content.strip_prefix("[对话已压缩。完整记录:") else {
+    let Some(rest) = content.strip_prefix(COMPACTION_SUMMARY_PREFIX) else {
         return content;
     };
 
@@ -247,6 +300,7 @@ pub async fn auto_compact(
     model: &str,
     messages: &[Value],
     transcript_path: &str,
+    usage_scope: Option<ModelUsageScope<'_>>,
 ) -> Result<Vec<Value>> {
     // 将所有消息序列化为可读文本
     let conversation_text: String = messages
@@ -269,30 +323,24 @@ pub async fn auto_compact(
 
     // 调用 LLM 生成摘要（空工具列表，空回调）
     let transport = resolve_model_transport(api_format, base_url, None);
-    let response = if transport.kind == ModelTransportKind::AnthropicMessages {
-        adapters::anthropic::chat_stream_with_tools(
-            base_url,
-            api_key,
-            model,
-            COMPACT_SYSTEM_PROMPT,
-            summary_messages,
-            vec![],
-            |_| {},
+    let response = model_adapters()
+        .chat_stream(
+            ModelChatRequest {
+                transport: &transport,
+                base_url,
+                api_key,
+                model,
+                system_prompt: COMPACT_SYSTEM_PROMPT,
+                messages: summary_messages,
+                tools: vec![],
+                reasoning: super::types::ReasoningSettings::default(),
+                structured_output: None,
+                usage_scope,
+            },
+            &mut |_| {},
         )
         .await?
-    } else {
-        adapters::openai::chat_stream_with_tools(
-            &transport,
-            base_url,
-            api_key,
-            model,
-            COMPACT_SYSTEM_PROMPT,
-            summary_messages,
-            vec![],
-            |_| {},
-        )
-        .await?
-    };
+        .response;
 
     let summary = match response {
         super::types::LLMResponse::Text(text) => text,
@@ -313,8 +361,8 @@ pub async fn auto_compact(
         json!({
             "role": "user",
             "content": format!(
-                "[对话已压缩。完整记录: {}]\n\n{}",
-                transcript_path, summary_with_context
+                "{} {}]\n\n{}",
+                COMPACTION_SUMMARY_PREFIX, transcript_path, summary_with_context
             )
         }),
         json!({
@@ -331,15 +379,41 @@ mod tests {
 
     #[test]
     fn test_needs_auto_compact_below_threshold() {
-        assert!(!needs_auto_compact(0));
-        assert!(!needs_auto_compact(49_999));
-        assert!(!needs_auto_compact(50_000));
+        assert!(!needs_auto_compact(0, "unknown-model"));
+        assert!(!needs_auto_compact(49_999, "unknown-model"));
+        assert!(!needs_auto_compact(50_000, "unknown-model"));
     }
 
     #[test]
     fn test_needs_auto_compact_above_threshold() {
-        assert!(needs_auto_compact(50_001));
-        assert!(needs_auto_compact(100_000));
+        assert!(needs_auto_compact(50_001, "unknown-model"));
+        assert!(needs_auto_compact(100_000, "unknown-model"));
+    }
+
+    #[test]
+    fn test_auto_compact_threshold_follows_model_catalog() {
+        // deepseek-chat: (128_000 - 8_192) / 4 * 3
+        assert_eq!(auto_compact_threshold("deepseek-chat"), 89_856);
+        // llava:7b 的窗口很小，应更早压缩
+        assert!(needs_auto_compact(2_000, "llava:7b"));
+        assert!(!needs_auto_compact(100_000, "gemini-2.5-flash"));
+    }
+
+    #[test]
+    fn test_context_budget_follows_model_catalog() {
+        let budget = context_budget_for_model("deepseek-chat").expect("deepseek budget");
+        assert_eq!(budget.input_window(), 119_808);
+        assert_eq!(trim_budget_for_model("deepseek-chat"), 119_808);
+        assert!(context_budget_for_model("unknown-model").is_none());
+        assert_eq!(trim_budget_for_model("unknown-model"), DEFAULT_TOKEN_BUDGET);
+    }
+
+    #[test]
+    fn test_needs_micro_compact_uses_half_of_input_window() {
+        assert!(!needs_micro_compact(59_904, "deepseek-chat"));
+        assert!(needs_micro_compact(59_905, "deepseek-chat"));
+        assert!(!needs_micro_compact(33_333, "unknown-model"));
+        assert!(needs_micro_compact(33_334, "unknown-model"));
     }
 
     #[test]
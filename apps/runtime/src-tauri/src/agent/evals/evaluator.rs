@@ -51,7 +51,7 @@ pub fn evaluate_and_write_report(
             selected_runner.as_deref(),
         ),
         execution: evaluate_execution_assertions(scenario, run, &observations),
-        structured: evaluate_structured_assertions(scenario, &observations),
+        structured: evaluate_structured_assertions(scenario, run, &observations),
         tools: evaluate_tool_assertions(scenario, run),
         output: evaluate_output_assertions(scenario, run),
         thresholds: evaluate_thresholds(scenario, total_duration_ms, turn_count, tool_count),
@@ -102,6 +102,7 @@ pub fn evaluate_and_write_report(
         usage: EvalReportUsage {
             turn_count,
             tool_count,
+            tokens: run.token_usage,
         },
         assertions,
         metrics: build_requested_metrics(
@@ -261,12 +262,13 @@ fn run_completed(run: &HeadlessEvalRun) -> bool {
 
 fn evaluate_structured_assertions(
     scenario: &EvalScenario,
+    run: &HeadlessEvalRun,
     observations: &[ToolOutputObservation],
 ) -> String {
     let Some(structured) = scenario.expect.structured.as_ref() else {
         return "pass".to_string();
     };
-    let candidates = collect_structured_candidates(observations);
+    let candidates = collect_structured_candidates(run, observations);
     let expected = &structured.equals;
 
     let employee_ok =
@@ -444,6 +446,7 @@ fn build_requested_metrics(
                 .unwrap_or(Value::Null),
             "turn_count" => Value::from(turn_count),
             "tool_count" => Value::from(tool_count),
+            "total_tokens" => Value::from(run.token_usage.total_tokens()),
             "called_tools" => Value::Array(
                 collect_called_tool_names(run)
                     .into_iter()
@@ -537,8 +540,17 @@ fn collect_tool_output_observations(messages: &[Value]) -> Vec<ToolOutputObserva
     observations
 }
 
-fn collect_structured_candidates(observations: &[ToolOutputObservation]) -> Vec<Value> {
-    let mut candidates = Vec::new();
+/// 已通过 schema 校验的运行结果优先于工具输出
+fn collect_structured_candidates(
+    run: &HeadlessEvalRun,
+    observations: &[ToolOutputObservation],
+) -> Vec<Value> {
+    let mut candidates = run
+        .session_runs
+        .iter()
+        .rev()
+        .filter_map(|session_run| session_run.structured_output.clone())
+        .collect::<Vec<_>>();
     for observation in observations {
         if let Some(stdout_json) = observation.stdout_json.as_ref() {
             candidates.push(stdout_json.clone());
@@ -699,6 +711,7 @@ mod tests {
     use crate::agent::runtime::trace_builder::{
         RunTraceToolSummary, SessionRunEventSummary, SessionRunTrace, SessionRunTraceLifecycle,
     };
+    use crate::agent::types::TokenUsage;
     use crate::commands::models::RouteAttemptLog;
     use crate::commands::session_runs::SessionRunProjection;
     use crate::session_journal::SessionJournalState;
@@ -806,6 +819,7 @@ diagnostics:
                 task_continuation_mode: None,
                 task_continuation_source: None,
                 task_continuation_reason: None,
+                structured_output: None,
             }],
             route_attempt_logs: vec![RouteAttemptLog {
                 session_id: "session-1".to_string(),
@@ -945,6 +959,7 @@ diagnostics:
             session_markdown: "session markdown".to_string(),
             journal_state: SessionJournalState::default(),
             final_output: "谢涛在该时间窗内主要推进金川区域排水管网改造工程（一期）和土左2025老旧小区改造，并继续跟进排污通道图纸跟进。".to_string(),
+            token_usage: TokenUsage::default(),
         }
     }
 
@@ -1012,6 +1027,7 @@ diagnostics:
                 task_continuation_mode: None,
                 task_continuation_source: None,
                 task_continuation_reason: None,
+                structured_output: None,
             }],
             route_attempt_logs: vec![RouteAttemptLog {
                 session_id: "session-1".to_string(),
@@ -1151,6 +1167,7 @@ diagnostics:
             session_markdown: "session markdown".to_string(),
             journal_state: SessionJournalState::default(),
             final_output: "谢涛在该时间窗内主要推进金川区域排水管网改造工程（一期）和土左2025老旧小区改造，并继续跟进排污通道图纸跟进。".to_string(),
+            token_usage: TokenUsage::default(),
         }
     }
 
@@ -1209,6 +1226,26 @@ diagnostics:
         assert_eq!(outcome.report.assertions.structured, "fail");
     }
 
+    #[test]
+    fn evaluate_and_write_report_prefers_validated_run_structured_output() {
+        let temp = tempdir().expect("tempdir");
+        let config = test_config(temp.path());
+        let scenario = load_scenario();
+        let mut run = build_run(90_000, 5);
+        run.session_runs[0].structured_output = Some(json!({
+            "employee": "谢涛",
+            "start_date": "2026-03-30",
+            "end_date": "2026-04-04",
+            "daily_count": 6,
+            "plan_count": 6,
+            "report_count": 5
+        }));
+
+        let outcome = evaluate_and_write_report(&config, &scenario, &run).expect("evaluate report");
+
+        assert_eq!(outcome.report.assertions.structured, "pass");
+    }
+
     #[test]
     fn evaluate_and_write_report_accepts_nested_summary_fact_lengths() {
         let temp = tempdir().expect("tempdir");
@@ -1,3 +1,4 @@
+use crate::agent::types::TokenUsage;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeMap;
@@ -45,6 +46,8 @@ pub struct EvalReportTiming {
 pub struct EvalReportUsage {
     pub turn_count: u32,
     pub tool_count: u32,
+    #[serde(default)]
+    pub tokens: TokenUsage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -6,6 +6,7 @@ use crate::agent::runtime::{
 };
 use crate::agent::tools::new_responder;
 use crate::agent::tools::search_providers::cache::SearchCache;
+use crate::agent::types::TokenUsage;
 use crate::agent::{AgentExecutor, ToolRegistry};
 use crate::commands::chat::{
     create_session, send_message, AskUserPendingSessionState, AskUserState, CancelFlagState,
@@ -21,6 +22,7 @@ use crate::commands::session_runs::{
     export_session_run_trace_with_pool, list_session_runs_with_pool, SessionRunProjection,
 };
 use crate::commands::skills::{import_local_skills_to_pool, DbState};
+use crate::model_usage::{summarize_model_usage_with_pool, ModelUsageQuery};
 use crate::runtime_paths::RuntimePaths;
 use crate::session_journal::{SessionJournalState, SessionJournalStateHandle, SessionJournalStore};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
@@ -57,6 +59,7 @@ pub struct HeadlessEvalRun {
     pub session_markdown: String,
     pub journal_state: SessionJournalState,
     pub final_output: String,
+    pub token_usage: TokenUsage,
 }
 
 impl RealAgentEvalRunner {
@@ -210,6 +213,18 @@ impl RealAgentEvalRunner {
             .await
             .map_err(|e| format!("读取 session journal 失败: {e}"))?;
         let final_output = extract_final_output(&messages);
+        let token_usage = summarize_model_usage_with_pool(
+            &self.pool,
+            &ModelUsageQuery {
+                session_id: Some(session_id.clone()),
+                ..ModelUsageQuery::default()
+            },
+        )
+        .await?
+        .into_iter()
+        .next()
+        .map(|summary| summary.usage)
+        .unwrap_or_default();
 
         Ok(HeadlessEvalRun {
             scenario_id: scenario.id.clone(),
@@ -228,6 +243,7 @@ impl RealAgentEvalRunner {
             session_markdown,
             journal_state,
             final_output,
+            token_usage,
         })
     }
 
@@ -756,6 +772,7 @@ mod tests {
                     created_at: Utc::now(),
                     username_hint: None,
                     encrypted_verify: String::new(),
+                    ..Default::default()
                 },
             }],
             failed: Vec::new(),
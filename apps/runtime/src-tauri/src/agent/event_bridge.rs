@@ -91,6 +91,30 @@ pub(super) async fn append_run_guard_warning_event(
     .await
 }
 
+pub(super) async fn append_structured_output_event(
+    app: &AppHandle,
+    session_id: &str,
+    spec: &crate::agent::structured_output::StructuredOutputSpec,
+    output: Value,
+    repair_turns: usize,
+) -> Result<()> {
+    let Some(run_id) = resolve_current_session_run_id(app, session_id).await else {
+        return Ok(());
+    };
+
+    append_tool_run_event(
+        app,
+        session_id,
+        SessionRunEvent::StructuredOutputRecorded {
+            run_id,
+            schema_name: spec.name.clone(),
+            output,
+            repair_turns,
+        },
+    )
+    .await
+}
+
 pub fn build_skill_route_event(
     session_id: &str,
     route_run_id: &str,
@@ -1,7 +1,9 @@
 use super::permissions::PermissionMode;
 use super::registry::ToolRegistry;
-use super::run_guard::{RunBudgetPolicy, RunBudgetScope};
+use super::run_guard::{RunBudgetPolicy, RunBudgetScope, RunBudgetTracker, RunResourceLimits};
 use super::runtime::compaction_pipeline::RuntimeCompactionOutcome;
+use super::runtime::events::ToolConfirmResponder;
+use super::structured_output::StructuredOutputSpec;
 use super::system_prompts::SystemPromptBuilder;
 use super::types::StreamDelta;
 use crate::model_transport::ResolvedModelTransport;
@@ -35,6 +37,30 @@ impl AgentTurnExecutionError {
     }
 }
 
+/// 单轮 Agent 执行的模型、会话与运行参数
+#[derive(Default)]
+pub struct AgentTurnRequest<'a> {
+    pub api_format: &'a str,
+    pub base_url: &'a str,
+    pub api_key: &'a str,
+    pub model: &'a str,
+    pub skill_system_prompt: &'a str,
+    pub messages: Vec<Value>,
+    pub app_handle: Option<&'a AppHandle>,
+    pub session_id: Option<&'a str>,
+    pub allowed_tools: Option<&'a [String]>,
+    pub permission_mode: PermissionMode,
+    pub tool_confirm_tx: Option<ToolConfirmResponder>,
+    pub work_dir: Option<String>,
+    /// 覆盖执行器默认的最大轮次
+    pub max_iterations_override: Option<usize>,
+    pub resource_limits: RunResourceLimits,
+    pub structured_output: Option<StructuredOutputSpec>,
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+    pub route_node_timeout_secs: Option<u64>,
+    pub route_retry_count: Option<usize>,
+}
+
 pub struct AgentExecutor {
     pub(super) registry: Arc<ToolRegistry>,
     pub(super) max_iterations: usize,
@@ -69,93 +95,26 @@ impl AgentExecutor {
     /// 轮询 cancel_flag，直到收到取消信号
     pub async fn execute_turn(
         &self,
-        api_format: &str,
-        base_url: &str,
-        api_key: &str,
-        model: &str,
-        skill_system_prompt: &str,
-        messages: Vec<Value>,
+        request: AgentTurnRequest<'_>,
         on_token: impl Fn(StreamDelta) + Send + Clone,
-        app_handle: Option<&AppHandle>,
-        session_id: Option<&str>,
-        allowed_tools: Option<&[String]>,
-        permission_mode: PermissionMode,
-        tool_confirm_tx: Option<
-            std::sync::Arc<std::sync::Mutex<Option<std::sync::mpsc::Sender<bool>>>>,
-        >,
-        work_dir: Option<String>,
-        max_iterations_override: Option<usize>,
-        cancel_flag: Option<Arc<AtomicBool>>,
-        route_node_timeout_secs: Option<u64>,
-        route_retry_count: Option<usize>,
     ) -> Result<Vec<Value>> {
-        self.execute_turn_impl(
-            None,
-            api_format,
-            base_url,
-            api_key,
-            model,
-            skill_system_prompt,
-            messages,
-            on_token,
-            app_handle,
-            session_id,
-            allowed_tools,
-            permission_mode,
-            tool_confirm_tx,
-            work_dir,
-            max_iterations_override,
-            cancel_flag,
-            route_node_timeout_secs,
-            route_retry_count,
-        )
-        .await
-        .map(|outcome| outcome.messages)
-        .map_err(|error| error.error)
+        self.execute_turn_impl(None, request, &RunBudgetTracker::start(), on_token)
+            .await
+            .map(|outcome| outcome.messages)
+            .map_err(|error| error.error)
     }
 
     pub async fn execute_turn_with_transport(
         &self,
         transport_override: ResolvedModelTransport,
-        api_format: &str,
-        base_url: &str,
-        api_key: &str,
-        model: &str,
-        skill_system_prompt: &str,
-        messages: Vec<Value>,
+        request: AgentTurnRequest<'_>,
         on_token: impl Fn(StreamDelta) + Send + Clone,
-        app_handle: Option<&AppHandle>,
-        session_id: Option<&str>,
-        allowed_tools: Option<&[String]>,
-        permission_mode: PermissionMode,
-        tool_confirm_tx: Option<
-            std::sync::Arc<std::sync::Mutex<Option<std::sync::mpsc::Sender<bool>>>>,
-        >,
-        work_dir: Option<String>,
-        max_iterations_override: Option<usize>,
-        cancel_flag: Option<Arc<AtomicBool>>,
-        route_node_timeout_secs: Option<u64>,
-        route_retry_count: Option<usize>,
     ) -> Result<Vec<Value>> {
         self.execute_turn_with_transport_outcome(
             transport_override,
-            api_format,
-            base_url,
-            api_key,
-            model,
-            skill_system_prompt,
-            messages,
+            request,
+            &RunBudgetTracker::start(),
             on_token,
-            app_handle,
-            session_id,
-            allowed_tools,
-            permission_mode,
-            tool_confirm_tx,
-            work_dir,
-            max_iterations_override,
-            cancel_flag,
-            route_node_timeout_secs,
-            route_retry_count,
         )
         .await
         .map(|outcome| outcome.messages)
@@ -165,45 +124,15 @@ impl AgentExecutor {
     pub(crate) async fn execute_turn_with_transport_outcome(
         &self,
         transport_override: ResolvedModelTransport,
-        api_format: &str,
-        base_url: &str,
-        api_key: &str,
-        model: &str,
-        skill_system_prompt: &str,
-        messages: Vec<Value>,
+        request: AgentTurnRequest<'_>,
+        run_budget_tracker: &RunBudgetTracker,
         on_token: impl Fn(StreamDelta) + Send + Clone,
-        app_handle: Option<&AppHandle>,
-        session_id: Option<&str>,
-        allowed_tools: Option<&[String]>,
-        permission_mode: PermissionMode,
-        tool_confirm_tx: Option<
-            std::sync::Arc<std::sync::Mutex<Option<std::sync::mpsc::Sender<bool>>>>,
-        >,
-        work_dir: Option<String>,
-        max_iterations_override: Option<usize>,
-        cancel_flag: Option<Arc<AtomicBool>>,
-        route_node_timeout_secs: Option<u64>,
-        route_retry_count: Option<usize>,
     ) -> std::result::Result<AgentTurnExecutionOutcome, AgentTurnExecutionError> {
         self.execute_turn_impl(
             Some(transport_override),
-            api_format,
-            base_url,
-            api_key,
-            model,
-            skill_system_prompt,
-            messages,
+            request,
+            run_budget_tracker,
             on_token,
-            app_handle,
-            session_id,
-            allowed_tools,
-            permission_mode,
-            tool_confirm_tx,
-            work_dir,
-            max_iterations_override,
-            cancel_flag,
-            route_node_timeout_secs,
-            route_retry_count,
         )
         .await
     }
@@ -17,6 +17,7 @@ pub mod run_guard;
 pub mod runtime;
 pub mod safety;
 pub mod skill_config;
+pub mod structured_output;
 pub mod system_prompts;
 pub mod tool_manifest;
 pub mod tools;
@@ -24,11 +25,11 @@ pub mod tools;
 pub mod turn_executor;
 pub mod types;
 
-pub use executor::AgentExecutor;
+pub use executor::{AgentExecutor, AgentTurnRequest};
 pub use registry::ToolRegistry;
 pub use tool_manifest::{ToolCategory, ToolManifestEntry, ToolMetadata, ToolSource};
 pub use tools::*;
 pub use types::{
     AgentState, AgentStateEvent, BackgroundProcessEvent, LLMResponse, PathAccessPolicy, Tool,
-    ToolCall, ToolCallEvent, ToolContext, ToolResult,
+    TokenUsage, ToolCall, ToolCallEvent, ToolContext, ToolResult,
 };
@@ -35,7 +35,7 @@ impl ToolRegistry {
         registry.register(Arc::new(FileMoveTool));
         registry.register(Arc::new(FileCopyTool));
         registry.register(Arc::new(TodoWriteTool::new()));
-        registry.register(Arc::new(WebFetchTool));
+        registry.register(Arc::new(WebFetchTool::new()));
         registry.register(Arc::new(ExecTool::new()));
         registry.register(Arc::new(BashTool::new()));
         // L5 新增系统工具
@@ -105,27 +105,24 @@ impl ToolRegistry {
     }
 
     pub fn get_tool_definitions(&self) -> Vec<Value> {
-        self.tools
-            .read()
-            .unwrap()
-            .values()
-            .map(|t| {
-                json!({
-                    "name": t.name(),
-                    "description": t.description(),
-                    "input_schema": t.input_schema(),
-                })
-            })
-            .collect()
+        self.collect_tool_definitions(|_| true)
     }
 
     /// 返回仅包含白名单中工具的定义
     pub fn get_filtered_tool_definitions(&self, whitelist: &[String]) -> Vec<Value> {
-        self.tools
-            .read()
-            .unwrap()
+        self.collect_tool_definitions(|name| whitelist.iter().any(|w| w == name))
+    }
+
+    /// 按工具名排序输出定义，保证每轮请求的工具列表一致，便于命中提示词缓存
+    fn collect_tool_definitions(&self, include: impl Fn(&str) -> bool) -> Vec<Value> {
+        let tools = self.tools.read().unwrap();
+        let mut selected = tools
             .values()
-            .filter(|t| whitelist.iter().any(|w| w == t.name()))
+            .filter(|t| include(t.name()))
+            .collect::<Vec<_>>();
+        selected.sort_by(|a, b| a.name().cmp(b.name()));
+        selected
+            .into_iter()
             .map(|t| {
                 json!({
                     "name": t.name(),
@@ -169,6 +166,22 @@ mod tests {
         assert_eq!(registry.tool_names(), expected);
     }
 
+    #[test]
+    fn tool_definitions_are_sorted_by_name() {
+        let registry = ToolRegistry::with_standard_tools();
+        let names = registry
+            .get_filtered_tool_definitions(&["read_file".to_string(), "bash".to_string()])
+            .iter()
+            .filter_map(|def| def["name"].as_str().map(str::to_string))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["bash", "read_file"]);
+
+        let all = registry.get_tool_definitions();
+        assert!(all
+            .windows(2)
+            .all(|pair| pair[0]["name"].as_str() <= pair[1]["name"].as_str()));
+    }
+
     #[test]
     fn representative_standard_tools_publish_expected_metadata() {
         let registry = ToolRegistry::with_standard_tools();
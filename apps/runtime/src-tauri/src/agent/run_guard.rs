@@ -1,6 +1,11 @@
+use super::skill_config::SkillRunBudget;
+use super::types::TokenUsage;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 const RUN_STOP_REASON_PREFIX: &str = "__WORKCLAW_RUN_STOP__:";
+const DEFAULT_BUDGET_WARNING_RATIO: f64 = 0.8;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RunBudgetScope {
@@ -11,11 +16,81 @@ pub enum RunBudgetScope {
     BrowserHeavy,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RunBudgetPolicy {
     pub max_turns: usize,
     pub repeated_tool_call_limit: usize,
     pub no_progress_limit: usize,
+    pub resource_limits: RunResourceLimits,
+}
+
+/// 单次运行的资源上限，来自 Skill front matter 与员工配置；None 表示该维度不设限
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunResourceLimits {
+    pub max_input_tokens: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    /// 按单价表币种计的预估费用上限
+    pub max_cost: Option<f64>,
+    pub max_wall_clock_secs: Option<u64>,
+    pub max_tool_calls: Option<u64>,
+    /// 用量达到上限的该比例时先发出预警，默认 0.8
+    pub warning_ratio: Option<f64>,
+}
+
+impl RunResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_input_tokens.is_none()
+            && self.max_output_tokens.is_none()
+            && self.max_cost.is_none()
+            && self.max_wall_clock_secs.is_none()
+            && self.max_tool_calls.is_none()
+    }
+
+    /// 合并两份限额（如 Skill 与员工同时配置），逐项取更严格的一方
+    pub fn tighten(self, other: Self) -> Self {
+        fn min_option<T: PartialOrd>(left: Option<T>, right: Option<T>) -> Option<T> {
+            match (left, right) {
+                (Some(left), Some(right)) => Some(if right < left { right } else { left }),
+                (left, right) => left.or(right),
+            }
+        }
+        Self {
+            max_input_tokens: min_option(self.max_input_tokens, other.max_input_tokens),
+            max_output_tokens: min_option(self.max_output_tokens, other.max_output_tokens),
+            max_cost: min_option(self.max_cost, other.max_cost),
+            max_wall_clock_secs: min_option(self.max_wall_clock_secs, other.max_wall_clock_secs),
+            max_tool_calls: min_option(self.max_tool_calls, other.max_tool_calls),
+            // 无效的预警比例不参与比较，避免把另一方的有效配置挤掉
+            warning_ratio: min_option(
+                valid_warning_ratio(self.warning_ratio),
+                valid_warning_ratio(other.warning_ratio),
+            ),
+        }
+    }
+
+    fn effective_warning_ratio(&self) -> f64 {
+        valid_warning_ratio(self.warning_ratio).unwrap_or(DEFAULT_BUDGET_WARNING_RATIO)
+    }
+}
+
+fn valid_warning_ratio(ratio: Option<f64>) -> Option<f64> {
+    ratio.filter(|ratio| ratio.is_finite() && *ratio > 0.0 && *ratio < 1.0)
+}
+
+impl From<SkillRunBudget> for RunResourceLimits {
+    fn from(budget: SkillRunBudget) -> Self {
+        Self {
+            max_input_tokens: budget.max_input_tokens,
+            max_output_tokens: budget.max_output_tokens,
+            max_cost: budget
+                .max_cost
+                .filter(|cost| cost.is_finite() && *cost >= 0.0),
+            max_wall_clock_secs: budget.max_wall_clock_secs,
+            max_tool_calls: budget.max_tool_calls,
+            warning_ratio: budget.warning_ratio,
+        }
+    }
 }
 
 impl RunBudgetPolicy {
@@ -25,26 +100,31 @@ impl RunBudgetPolicy {
                 max_turns: 100,
                 repeated_tool_call_limit: 6,
                 no_progress_limit: 5,
+                resource_limits: RunResourceLimits::default(),
             },
             RunBudgetScope::Skill => Self {
                 max_turns: 100,
                 repeated_tool_call_limit: 6,
                 no_progress_limit: 5,
+                resource_limits: RunResourceLimits::default(),
             },
             RunBudgetScope::Employee => Self {
                 max_turns: 100,
                 repeated_tool_call_limit: 6,
                 no_progress_limit: 5,
+                resource_limits: RunResourceLimits::default(),
             },
             RunBudgetScope::SubAgent => Self {
                 max_turns: 100,
                 repeated_tool_call_limit: 5,
                 no_progress_limit: 4,
+                resource_limits: RunResourceLimits::default(),
             },
             RunBudgetScope::BrowserHeavy => Self {
                 max_turns: 100,
                 repeated_tool_call_limit: 8,
                 no_progress_limit: 6,
+                resource_limits: RunResourceLimits::default(),
             },
         }
     }
@@ -59,6 +139,11 @@ impl RunBudgetPolicy {
             None => default_policy,
         }
     }
+
+    pub fn with_resource_limits(mut self, resource_limits: RunResourceLimits) -> Self {
+        self.resource_limits = resource_limits;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,6 +159,7 @@ pub enum RunStopReasonKind {
     NoProgress,
     ToolFailureCircuitBreaker,
     ProtocolViolation,
+    BudgetExceeded,
 }
 
 impl RunStopReasonKind {
@@ -89,6 +175,7 @@ impl RunStopReasonKind {
             RunStopReasonKind::NoProgress => "no_progress",
             RunStopReasonKind::ToolFailureCircuitBreaker => "tool_failure_circuit_breaker",
             RunStopReasonKind::ProtocolViolation => "protocol_violation",
+            RunStopReasonKind::BudgetExceeded => "budget_exceeded",
         }
     }
 }
@@ -128,6 +215,23 @@ impl RunStopReason {
         self
     }
 
+    pub fn cancelled() -> Self {
+        Self::new(
+            RunStopReasonKind::Cancelled,
+            "任务已取消",
+            "用户取消了本轮任务。",
+        )
+    }
+
+    pub fn timeout(detail: impl Into<String>) -> Self {
+        Self::new(
+            RunStopReasonKind::Timeout,
+            "任务执行超时",
+            "执行时间超过上限，系统已自动停止。",
+        )
+        .with_detail(detail)
+    }
+
     pub fn max_turns(max_turns: usize) -> Self {
         Self::new(
             RunStopReasonKind::MaxTurns,
@@ -163,6 +267,24 @@ impl RunStopReason {
         )
         .with_detail(detail)
     }
+
+    pub fn budget_exceeded(detail: impl Into<String>) -> Self {
+        Self::new(
+            RunStopReasonKind::BudgetExceeded,
+            "任务达到资源预算上限",
+            "本轮任务的 token、费用、耗时或工具调用次数已达到预算上限，系统已自动停止。",
+        )
+        .with_detail(detail)
+    }
+
+    pub fn structured_output_invalid(detail: impl Into<String>) -> Self {
+        Self::new(
+            RunStopReasonKind::ProtocolViolation,
+            "最终结果不符合输出格式",
+            "模型多次修正后仍未输出满足 Skill 声明 JSON Schema 的结果，系统已停止本轮任务。",
+        )
+        .with_detail(detail)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -217,6 +339,15 @@ impl RunGuardWarning {
         )
         .with_detail(detail)
     }
+
+    pub fn budget_approaching(detail: impl Into<String>) -> Self {
+        Self::new(
+            RunStopReasonKind::BudgetExceeded,
+            "任务即将达到资源预算上限",
+            "本轮任务的资源用量已接近预算上限，达到上限后将自动停止。",
+        )
+        .with_detail(detail)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -262,10 +393,7 @@ impl ProgressEvaluation {
         }
     }
 
-    pub(crate) fn with_last_completed_step(
-        mut self,
-        last_completed_step: Option<String>,
-    ) -> Self {
+    pub(crate) fn with_last_completed_step(mut self, last_completed_step: Option<String>) -> Self {
         if let Some(step) = last_completed_step {
             if let Some(warning) = self.warning.as_mut() {
                 warning.last_completed_step = Some(step.clone());
@@ -278,6 +406,150 @@ impl ProgressEvaluation {
     }
 }
 
+/// 本轮运行已消耗的资源；输入 token 含缓存读写部分
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunResourceUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+    pub elapsed_secs: u64,
+    pub tool_calls: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunBudgetDimension {
+    InputTokens,
+    OutputTokens,
+    Cost,
+    WallClock,
+    ToolCalls,
+}
+
+impl RunBudgetDimension {
+    const ALL: [RunBudgetDimension; 5] = [
+        RunBudgetDimension::InputTokens,
+        RunBudgetDimension::OutputTokens,
+        RunBudgetDimension::Cost,
+        RunBudgetDimension::WallClock,
+        RunBudgetDimension::ToolCalls,
+    ];
+
+    /// 返回 (已用量, 上限)；未配置或上限为 0 的维度不参与判断
+    fn measure(self, limits: &RunResourceLimits, usage: &RunResourceUsage) -> Option<(f64, f64)> {
+        let (used, limit) = match self {
+            RunBudgetDimension::InputTokens => {
+                (usage.input_tokens as f64, limits.max_input_tokens? as f64)
+            }
+            RunBudgetDimension::OutputTokens => {
+                (usage.output_tokens as f64, limits.max_output_tokens? as f64)
+            }
+            RunBudgetDimension::Cost => (usage.cost, limits.max_cost?),
+            RunBudgetDimension::WallClock => (
+                usage.elapsed_secs as f64,
+                limits.max_wall_clock_secs? as f64,
+            ),
+            RunBudgetDimension::ToolCalls => {
+                (usage.tool_calls as f64, limits.max_tool_calls? as f64)
+            }
+        };
+        (limit > 0.0).then_some((used, limit))
+    }
+
+    fn describe(self, used: f64, limit: f64) -> String {
+        match self {
+            RunBudgetDimension::InputTokens => {
+                format!("输入 token 已用 {used:.0}，上限 {limit:.0}。")
+            }
+            RunBudgetDimension::OutputTokens => {
+                format!("输出 token 已用 {used:.0}，上限 {limit:.0}。")
+            }
+            RunBudgetDimension::Cost => format!("预估费用已达 {used:.4}，上限 {limit:.4}。"),
+            RunBudgetDimension::WallClock => {
+                format!("运行耗时已达 {used:.0} 秒，上限 {limit:.0} 秒。")
+            }
+            RunBudgetDimension::ToolCalls => {
+                format!("工具调用已执行 {used:.0} 次，上限 {limit:.0} 次。")
+            }
+        }
+    }
+}
+
+/// 累计单次运行的资源消耗，每个维度的预警只发一次。
+/// 同一次运行的候选模型重试共用一个 tracker，耗时从运行开始算起。
+#[derive(Debug)]
+pub struct RunBudgetTracker {
+    started_at: Instant,
+    state: Mutex<RunBudgetTrackerState>,
+}
+
+#[derive(Debug, Default)]
+struct RunBudgetTrackerState {
+    usage: RunResourceUsage,
+    warned: Vec<RunBudgetDimension>,
+}
+
+impl RunBudgetTracker {
+    pub fn start() -> Self {
+        Self {
+            started_at: Instant::now(),
+            state: Mutex::new(RunBudgetTrackerState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, RunBudgetTrackerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn record_model_usage(&self, usage: &TokenUsage, cost: Option<f64>) {
+        let mut state = self.state();
+        state.usage.input_tokens = state.usage.input_tokens.saturating_add(
+            usage
+                .input_tokens
+                .saturating_add(usage.cache_read_tokens)
+                .saturating_add(usage.cache_write_tokens),
+        );
+        state.usage.output_tokens = state
+            .usage
+            .output_tokens
+            .saturating_add(usage.output_tokens);
+        if let Some(cost) = cost.filter(|cost| cost.is_finite() && *cost > 0.0) {
+            state.usage.cost += cost;
+        }
+    }
+
+    pub fn record_tool_calls(&self, count: usize) {
+        let mut state = self.state();
+        state.usage.tool_calls = state.usage.tool_calls.saturating_add(count as u64);
+    }
+
+    pub fn usage(&self) -> RunResourceUsage {
+        RunResourceUsage {
+            elapsed_secs: self.started_at.elapsed().as_secs(),
+            ..self.state().usage
+        }
+    }
+
+    pub fn evaluate(&self, policy: &RunBudgetPolicy) -> ProgressEvaluation {
+        let usage = self.usage();
+        ProgressGuard::evaluate_budget(policy, &usage, &mut self.state().warned)
+    }
+
+    /// 距离耗时上限还剩多久；未设耗时上限时返回 None
+    pub fn remaining_wall_clock(&self, limits: &RunResourceLimits) -> Option<Duration> {
+        let limit = limits.max_wall_clock_secs.filter(|secs| *secs > 0)?;
+        Some(Duration::from_secs(limit).saturating_sub(self.started_at.elapsed()))
+    }
+
+    /// 模型流或工具批次被耗时上限打断时的停止原因
+    pub fn wall_clock_stop_reason(&self, limits: &RunResourceLimits) -> RunStopReason {
+        let limit = limits.max_wall_clock_secs.unwrap_or_default() as f64;
+        let used = (self.started_at.elapsed().as_secs() as f64).max(limit);
+        RunStopReason::budget_exceeded(RunBudgetDimension::WallClock.describe(used, limit))
+    }
+}
+
 pub struct ProgressGuard;
 
 impl ProgressGuard {
@@ -327,6 +599,55 @@ impl ProgressGuard {
         ProgressEvaluation::continue_running()
     }
 
+    /// 资源预算检查：任一维度达到上限即停止；越过预警比例时对尚未预警过的维度发出预警
+    pub fn evaluate_budget(
+        policy: &RunBudgetPolicy,
+        usage: &RunResourceUsage,
+        warned: &mut Vec<RunBudgetDimension>,
+    ) -> ProgressEvaluation {
+        let limits = &policy.resource_limits;
+        let measured = RunBudgetDimension::ALL
+            .iter()
+            .filter_map(|dimension| {
+                dimension
+                    .measure(limits, usage)
+                    .map(|(used, limit)| (*dimension, used, limit))
+            })
+            .collect::<Vec<_>>();
+
+        if let Some((dimension, used, limit)) = measured
+            .iter()
+            .find(|(_, used, limit)| used >= limit)
+            .copied()
+        {
+            return ProgressEvaluation {
+                warning: None,
+                stop_reason: Some(RunStopReason::budget_exceeded(
+                    dimension.describe(used, limit),
+                )),
+            };
+        }
+
+        let warning_ratio = limits.effective_warning_ratio();
+        if let Some((dimension, used, limit)) = measured
+            .iter()
+            .find(|(dimension, used, limit)| {
+                *used >= limit * warning_ratio && !warned.contains(dimension)
+            })
+            .copied()
+        {
+            warned.push(dimension);
+            return ProgressEvaluation {
+                warning: Some(RunGuardWarning::budget_approaching(
+                    dimension.describe(used, limit),
+                )),
+                stop_reason: None,
+            };
+        }
+
+        ProgressEvaluation::continue_running()
+    }
+
     fn repeated_identical_tool_call_warning(
         policy: &RunBudgetPolicy,
         history: &[ProgressFingerprint],
@@ -608,4 +929,136 @@ mod tests {
             RunStopReasonKind::NoProgress
         );
     }
+
+    #[test]
+    fn resource_limits_tighten_keeps_the_stricter_value_per_dimension() {
+        let skill = RunResourceLimits {
+            max_input_tokens: Some(50_000),
+            max_cost: Some(1.0),
+            ..RunResourceLimits::default()
+        };
+        let employee = RunResourceLimits {
+            max_input_tokens: Some(80_000),
+            max_cost: Some(0.2),
+            max_tool_calls: Some(30),
+            ..RunResourceLimits::default()
+        };
+
+        let merged = skill.tighten(employee);
+
+        assert_eq!(merged.max_input_tokens, Some(50_000));
+        assert_eq!(merged.max_cost, Some(0.2));
+        assert_eq!(merged.max_tool_calls, Some(30));
+        assert_eq!(merged.max_output_tokens, None);
+        assert!(RunResourceLimits::default().is_unlimited());
+    }
+
+    #[test]
+    fn resource_limits_tighten_ignores_invalid_warning_ratio() {
+        let skill = RunResourceLimits {
+            warning_ratio: Some(0.6),
+            ..RunResourceLimits::default()
+        };
+        let employee = RunResourceLimits {
+            warning_ratio: Some(0.0),
+            ..RunResourceLimits::default()
+        };
+
+        assert_eq!(skill.tighten(employee).warning_ratio, Some(0.6));
+        assert_eq!(employee.tighten(skill).warning_ratio, Some(0.6));
+    }
+
+    #[test]
+    fn budget_tracker_reports_remaining_wall_clock() {
+        let tracker = RunBudgetTracker::start();
+        assert!(tracker
+            .remaining_wall_clock(&RunResourceLimits::default())
+            .is_none());
+
+        let limits = RunResourceLimits {
+            max_wall_clock_secs: Some(60),
+            ..RunResourceLimits::default()
+        };
+        let remaining = tracker
+            .remaining_wall_clock(&limits)
+            .expect("wall clock limit");
+        assert!(remaining <= Duration::from_secs(60));
+        assert!(remaining > Duration::from_secs(50));
+        let stop_reason = tracker.wall_clock_stop_reason(&limits);
+        assert_eq!(stop_reason.kind, RunStopReasonKind::BudgetExceeded);
+        assert_eq!(
+            stop_reason.detail.as_deref(),
+            Some("运行耗时已达 60 秒，上限 60 秒。")
+        );
+    }
+
+    #[test]
+    fn token_budget_warns_once_before_budget_exceeded_stop() {
+        let policy = RunBudgetPolicy::for_scope(RunBudgetScope::Skill).with_resource_limits(
+            RunResourceLimits {
+                max_output_tokens: Some(1_000),
+                ..RunResourceLimits::default()
+            },
+        );
+        let mut warned = Vec::new();
+        let mut usage = RunResourceUsage {
+            output_tokens: 850,
+            ..RunResourceUsage::default()
+        };
+
+        let first = ProgressGuard::evaluate_budget(&policy, &usage, &mut warned);
+        assert!(first.stop_reason.is_none());
+        assert_eq!(
+            first.warning.unwrap().kind,
+            RunStopReasonKind::BudgetExceeded
+        );
+        let repeated = ProgressGuard::evaluate_budget(&policy, &usage, &mut warned);
+        assert!(repeated.warning.is_none());
+
+        usage.output_tokens = 1_000;
+        let stopped = ProgressGuard::evaluate_budget(&policy, &usage, &mut warned);
+        let stop_reason = stopped.stop_reason.expect("budget should stop the run");
+        assert_eq!(stop_reason.kind, RunStopReasonKind::BudgetExceeded);
+        assert_eq!(
+            stop_reason.detail.as_deref(),
+            Some("输出 token 已用 1000，上限 1000。")
+        );
+        let decoded = parse_run_stop_reason(&encode_run_stop_reason(&stop_reason)).unwrap();
+        assert_eq!(decoded.kind, RunStopReasonKind::BudgetExceeded);
+    }
+
+    #[test]
+    fn budget_tracker_counts_cached_input_cost_and_tool_calls() {
+        let policy = RunBudgetPolicy::for_scope(RunBudgetScope::Employee).with_resource_limits(
+            RunResourceLimits {
+                max_input_tokens: Some(10_000),
+                max_cost: Some(0.05),
+                max_tool_calls: Some(3),
+                ..RunResourceLimits::default()
+            },
+        );
+        let tracker = RunBudgetTracker::start();
+        tracker.record_model_usage(
+            &TokenUsage {
+                input_tokens: 1_000,
+                output_tokens: 100,
+                cache_read_tokens: 500,
+                cache_write_tokens: 0,
+            },
+            Some(0.01),
+        );
+        tracker.record_tool_calls(2);
+
+        let usage = tracker.usage();
+        assert_eq!(usage.input_tokens, 1_500);
+        assert_eq!(usage.tool_calls, 2);
+        assert!(tracker.evaluate(&policy).stop_reason.is_none());
+
+        tracker.record_tool_calls(1);
+        let evaluation = tracker.evaluate(&policy);
+        assert_eq!(
+            evaluation.stop_reason.unwrap().detail.as_deref(),
+            Some("工具调用已执行 3 次，上限 3 次。")
+        );
+    }
 }
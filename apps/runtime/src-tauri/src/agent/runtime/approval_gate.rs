@@ -4,14 +4,93 @@ use crate::agent::approval_flow::{
 };
 use crate::agent::types::ToolCall;
 use crate::approval_bus::{approval_bus_rollout_enabled_with_pool, ApprovalDecision};
-use crate::approval_rules::find_matching_approval_rule_with_pool;
+use crate::approval_rules::{
+    evaluate_approval_rules_with_pool, registered_approval_rule_pool, ApprovalRuleContext,
+    ApprovalRuleEvaluation,
+};
+use crate::commands::skills::DbState;
 use crate::session_journal::{SessionRunTaskContinuationSnapshot, SessionRunTaskIdentitySnapshot};
 use anyhow::Result;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::Sender;
-use std::sync::{Arc, Mutex};
-use tauri::AppHandle;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+
+fn unattended_sessions() -> &'static RwLock<HashSet<String>> {
+    static SESSIONS: OnceLock<RwLock<HashSet<String>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| RwLock::new(HashSet::new()))
+}
+
+/// 无人值守运行（如定时任务）期间登记会话：需要人工审批的工具调用直接拒绝，不等待审批。
+/// 守卫释放时取消登记。
+pub(crate) struct UnattendedSessionGuard {
+    session_id: String,
+}
+
+impl UnattendedSessionGuard {
+    pub(crate) fn register(session_id: &str) -> Self {
+        unattended_sessions()
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(session_id.to_string());
+        Self {
+            session_id: session_id.to_string(),
+        }
+    }
+}
+
+impl Drop for UnattendedSessionGuard {
+    fn drop(&mut self) {
+        unattended_sessions()
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.session_id);
+    }
+}
+
+pub(crate) fn is_unattended_session(session_id: &str) -> bool {
+    unattended_sessions()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .contains(session_id)
+}
+
+/// 在权限模式判定之后叠加审批规则：返回命中的规则求值结果，未命中时返回 None；
+/// 规则无法读取时返回 Err，调用方不能据此放行。
+pub(crate) async fn resolve_approval_rule_evaluation(
+    app_handle: Option<&AppHandle>,
+    session_id: Option<&str>,
+    call: &ToolCall,
+    work_dir: Option<&Path>,
+) -> Result<Option<ApprovalRuleEvaluation>, String> {
+    let pool = app_handle
+        .and_then(|app| app.try_state::<DbState>())
+        .map(|db| db.0.clone())
+        .or_else(registered_approval_rule_pool)
+        .ok_or_else(|| "审批规则数据库不可用".to_string())?;
+    let employee_id = match session_id {
+        Some(sid) => {
+            sqlx::query_scalar::<_, String>("SELECT employee_id FROM sessions WHERE id = ? LIMIT 1")
+                .bind(sid)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| format!("读取会话员工失败: {e}"))?
+        }
+        None => None,
+    };
+    let context = ApprovalRuleContext {
+        employee_id: employee_id
+            .as_deref()
+            .filter(|value| !value.trim().is_empty()),
+        session_id,
+        work_dir,
+    };
+    let evaluation =
+        evaluate_approval_rules_with_pool(&pool, &call.name, &call.input, context).await?;
+    Ok(Some(evaluation).filter(|evaluation| evaluation.effect.is_some()))
+}
 
 pub(crate) async fn gate_tool_approval(
     app_handle: Option<&AppHandle>,
@@ -31,26 +110,21 @@ pub(crate) async fn gate_tool_approval(
             .unwrap_or(true);
 
         if approval_bus_enabled {
-            match find_matching_approval_rule_with_pool(&runtime.pool, &call.name, &call.input)
-                .await
-            {
-                Ok(Some(_)) => Ok(Some(ApprovalDecision::AllowAlways)),
-                Ok(None) | Err(_) => request_tool_approval_and_wait(
-                    &runtime,
-                    Some(app),
-                    sid,
-                    persisted_run_id,
-                    task_identity,
-                    task_continuation,
-                    &call.name,
-                    &call.id,
-                    &call.input,
-                    work_dir,
-                    cancel_flag,
-                )
-                .await
-                .map(Some),
-            }
+            request_tool_approval_and_wait(
+                &runtime,
+                Some(app),
+                sid,
+                persisted_run_id,
+                task_identity,
+                task_continuation,
+                &call.name,
+                &call.id,
+                &call.input,
+                work_dir,
+                cancel_flag,
+            )
+            .await
+            .map(Some)
         } else {
             Ok(resolve_manual_confirmation(tool_confirm_tx)?)
         }
@@ -90,7 +164,7 @@ fn resolve_manual_confirmation(
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_manual_confirmation;
+    use super::{is_unattended_session, resolve_manual_confirmation, UnattendedSessionGuard};
     use crate::approval_bus::ApprovalDecision;
     use std::sync::mpsc::Sender;
     use std::sync::{Arc, Mutex};
@@ -142,4 +216,13 @@ mod tests {
         driver.join().expect("driver thread");
         assert_eq!(decision, ApprovalDecision::Deny);
     }
+
+    #[test]
+    fn unattended_session_guard_unregisters_on_drop() {
+        let guard = UnattendedSessionGuard::register("scheduled-session");
+        assert!(is_unattended_session("scheduled-session"));
+        assert!(!is_unattended_session("desktop-session"));
+        drop(guard);
+        assert!(!is_unattended_session("scheduled-session"));
+    }
 }
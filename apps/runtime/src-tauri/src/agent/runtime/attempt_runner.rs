@@ -6,10 +6,11 @@ use super::failover::{
 };
 use super::observability::RuntimeObservabilityState;
 use crate::agent::permissions::PermissionMode;
-use crate::agent::run_guard::parse_run_stop_reason;
+use crate::agent::run_guard::{parse_run_stop_reason, RunBudgetTracker, RunResourceLimits};
 use crate::agent::runtime::kernel::turn_state::TurnCompactionBoundary;
+use crate::agent::structured_output::StructuredOutputSpec;
 use crate::agent::types::{AgentStateEvent, StreamDelta};
-use crate::agent::AgentExecutor;
+use crate::agent::{AgentExecutor, AgentTurnRequest};
 use crate::diagnostics::{self, LogLevel, ManagedDiagnosticsState};
 use serde_json::Value;
 use std::sync::atomic::AtomicBool;
@@ -17,7 +18,7 @@ use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
 use super::runtime_io as chat_io;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind};
+use crate::model_transport::resolve_model_transport;
 
 pub(crate) type RouteExecutionOutcome = RuntimeFailoverOutcome;
 
@@ -39,6 +40,10 @@ pub(crate) struct RouteExecutionParams<'a> {
     pub tool_confirm_responder: ToolConfirmResponder,
     pub executor_work_dir: Option<String>,
     pub max_iterations: Option<usize>,
+    pub run_resource_limits: RunResourceLimits,
+    /// 同一次运行的所有候选尝试共用，预算不会因换模型重试而重置
+    pub run_budget_tracker: &'a RunBudgetTracker,
+    pub structured_output: Option<&'a StructuredOutputSpec>,
     pub cancel_flag: Arc<AtomicBool>,
     pub node_timeout_seconds: u64,
     pub route_retry_count: usize,
@@ -158,6 +163,13 @@ fn emit_reasoning_completed_if_needed(
     Some(duration_ms)
 }
 
+/// 记录首个流式输出的时间，作为路由健康度的延迟样本
+fn mark_first_delta(first_delta_at: &std::sync::Mutex<Option<std::time::Instant>>) {
+    if let Ok(mut first) = first_delta_at.lock() {
+        first.get_or_insert_with(std::time::Instant::now);
+    }
+}
+
 async fn execute_candidate_attempt(
     params: &RouteExecutionParams<'_>,
     candidate_api_format: &str,
@@ -178,6 +190,8 @@ async fn execute_candidate_attempt(
     let reasoning_started_at = Arc::new(std::sync::Mutex::new(None::<std::time::Instant>));
     let last_reasoning_at = Arc::new(std::sync::Mutex::new(None::<std::time::Instant>));
     let reasoning_completion_emitted = Arc::new(std::sync::Mutex::new(false));
+    let attempt_started_at = std::time::Instant::now();
+    let first_delta_at = Arc::new(std::sync::Mutex::new(None::<std::time::Instant>));
     let app_clone = params.app.clone();
     let session_id_clone = params.session_id.to_string();
     let streamed_text_clone = Arc::clone(&streamed_text);
@@ -185,6 +199,7 @@ async fn execute_candidate_attempt(
     let reasoning_started_at_clone = Arc::clone(&reasoning_started_at);
     let last_reasoning_at_clone = Arc::clone(&last_reasoning_at);
     let reasoning_completion_emitted_clone = Arc::clone(&reasoning_completion_emitted);
+    let first_delta_at_clone = Arc::clone(&first_delta_at);
 
     let transport = resolve_model_transport(
         candidate_api_format,
@@ -192,10 +207,7 @@ async fn execute_candidate_attempt(
         Some(candidate_provider_key).filter(|value| !value.trim().is_empty()),
     );
     let effective_api_format = if candidate_api_format.trim().is_empty() {
-        match transport.kind {
-            ModelTransportKind::AnthropicMessages => "anthropic",
-            ModelTransportKind::OpenAiCompletions | ModelTransportKind::OpenAiResponses => "openai",
-        }
+        transport.kind.api_format()
     } else {
         candidate_api_format
     };
@@ -219,18 +231,35 @@ async fn execute_candidate_attempt(
         }
     }
 
+    let turn_request = AgentTurnRequest {
+        api_format: effective_api_format,
+        base_url: candidate_base_url,
+        api_key: candidate_api_key,
+        model: candidate_model_name,
+        skill_system_prompt: params.system_prompt,
+        messages: params.messages.to_vec(),
+        app_handle: Some(params.app),
+        session_id: Some(params.session_id),
+        allowed_tools: effective_allowed_tools,
+        permission_mode: params.permission_mode,
+        tool_confirm_tx: Some(params.tool_confirm_responder.clone()),
+        work_dir: params.executor_work_dir.clone(),
+        max_iterations_override: params.max_iterations,
+        resource_limits: params.run_resource_limits,
+        structured_output: params.structured_output.cloned(),
+        cancel_flag: Some(params.cancel_flag.clone()),
+        route_node_timeout_secs: Some(params.node_timeout_seconds),
+        route_retry_count: Some(params.route_retry_count),
+    };
     let attempt = params
         .agent_executor
         .execute_turn_with_transport_outcome(
             transport,
-            effective_api_format,
-            candidate_base_url,
-            candidate_api_key,
-            candidate_model_name,
-            params.system_prompt,
-            params.messages.to_vec(),
+            turn_request,
+            params.run_budget_tracker,
             move |delta: StreamDelta| match delta {
                 StreamDelta::Text(token) => {
+                    mark_first_delta(&first_delta_at_clone);
                     let _ = emit_reasoning_completed_if_needed(
                         &app_clone,
                         &session_id_clone,
@@ -252,6 +281,7 @@ async fn execute_candidate_attempt(
                     );
                 }
                 StreamDelta::Reasoning(text) => {
+                    mark_first_delta(&first_delta_at_clone);
                     let emit_started = if let Ok(mut started) = reasoning_started_at_clone.lock() {
                         if started.is_none() {
                             *started = Some(std::time::Instant::now());
@@ -283,32 +313,33 @@ async fn execute_candidate_attempt(
                     );
                 }
             },
-            Some(params.app),
-            Some(params.session_id),
-            effective_allowed_tools,
-            params.permission_mode,
-            Some(params.tool_confirm_responder.clone()),
-            params.executor_work_dir.clone(),
-            params.max_iterations,
-            Some(params.cancel_flag.clone()),
-            Some(params.node_timeout_seconds),
-            Some(params.route_retry_count),
         )
         .await;
+    let latency_ms = first_delta_at
+        .lock()
+        .ok()
+        .and_then(|first| *first)
+        .unwrap_or_else(std::time::Instant::now)
+        .saturating_duration_since(attempt_started_at)
+        .as_millis() as u64;
 
     match attempt {
         Ok(turn_outcome) => {
             chat_io::record_route_attempt_log_with_pool(
                 params.db,
-                params.session_id,
-                params.requested_capability,
-                effective_api_format,
-                candidate_model_name,
-                attempt_idx + 1,
-                attempt_idx,
-                "ok",
-                true,
-                "",
+                chat_io::RouteAttemptLog {
+                    session_id: params.session_id,
+                    capability: params.requested_capability,
+                    api_format: effective_api_format,
+                    base_url: candidate_base_url,
+                    model_name: candidate_model_name,
+                    attempt_index: attempt_idx + 1,
+                    retry_index: attempt_idx,
+                    error_kind: "ok",
+                    success: true,
+                    error_message: "",
+                    latency_ms,
+                },
             )
             .await;
             let reasoning_duration_ms = emit_reasoning_completed_if_needed(
@@ -376,15 +407,19 @@ async fn execute_candidate_attempt(
                 .unwrap_or_else(|| err_text.clone());
             chat_io::record_route_attempt_log_with_pool(
                 params.db,
-                params.session_id,
-                params.requested_capability,
-                effective_api_format,
-                candidate_model_name,
-                attempt_idx + 1,
-                attempt_idx,
-                kind_text,
-                false,
-                &user_facing_error,
+                chat_io::RouteAttemptLog {
+                    session_id: params.session_id,
+                    capability: params.requested_capability,
+                    api_format: effective_api_format,
+                    base_url: candidate_base_url,
+                    model_name: candidate_model_name,
+                    attempt_index: attempt_idx + 1,
+                    retry_index: attempt_idx,
+                    error_kind: kind_text,
+                    success: false,
+                    error_message: &user_facing_error,
+                    latency_ms,
+                },
             )
             .await;
             if reasoning_started_at
@@ -1,6 +1,6 @@
 use super::runtime_io::insert_session_message_with_pool;
 #[cfg(test)]
-use crate::agent::runtime::runtime_io::finalize_run_success_with_pool;
+use crate::agent::runtime::runtime_io::{finalize_run_success_with_pool, RunSuccessOutput};
 use crate::agent::runtime::task_backend::{
     HiddenChildTaskBackendPreparationRequest, TaskBackendPreparationRequest,
     TaskBackendTokenCallback,
@@ -143,12 +143,11 @@ pub(crate) async fn finalize_hidden_child_session_success_with_messages(
         journal,
         &prepared.child_session_id,
         &prepared.run_id,
-        &final_text,
-        has_tool_calls,
-        &content,
-        "",
-        None,
-        None,
+        RunSuccessOutput {
+            has_tool_calls,
+            content: &content,
+            ..RunSuccessOutput::text(&final_text)
+        },
     )
     .await
     .map_err(anyhow::Error::msg)?;
@@ -1,7 +1,8 @@
 use super::observability::RuntimeObservability;
+use crate::adapters::ModelUsageScope;
 use crate::agent::compactor;
 use anyhow::Result;
-use runtime_executor_core::estimate_tokens;
+use runtime_executor_core::estimate_tokens_for_model;
 use serde_json::Value;
 use std::path::{Path, PathBuf};
 
@@ -11,6 +12,8 @@ pub(crate) struct RuntimeCompactionRequest<'a> {
     pub api_key: &'a str,
     pub model: &'a str,
     pub session_id: &'a str,
+    /// 摘要调用的用量归属；为空时不记账
+    pub usage_scope: Option<ModelUsageScope<'a>>,
     pub messages: &'a [Value],
     pub transcript_root: &'a Path,
     pub observability: Option<&'a RuntimeObservability>,
@@ -28,7 +31,8 @@ pub(crate) struct RuntimeCompactionOutcome {
 pub(crate) async fn maybe_auto_compact(
     request: RuntimeCompactionRequest<'_>,
 ) -> Result<Option<RuntimeCompactionOutcome>> {
-    if !compactor::needs_auto_compact(estimate_tokens(request.messages)) {
+    let tokens = estimate_tokens_for_model(request.messages, request.model);
+    if !compactor::needs_auto_compact(tokens, request.model) {
         return Ok(None);
     }
 
@@ -38,7 +42,7 @@ pub(crate) async fn maybe_auto_compact(
 pub(crate) async fn run_compaction(
     request: RuntimeCompactionRequest<'_>,
 ) -> Result<RuntimeCompactionOutcome> {
-    let original_tokens = estimate_tokens(request.messages);
+    let original_tokens = estimate_tokens_for_model(request.messages, request.model);
     let transcript_path = compactor::save_transcript(
         &request.transcript_root.to_path_buf(),
         request.session_id,
@@ -51,9 +55,10 @@ pub(crate) async fn run_compaction(
         request.model,
         request.messages,
         &transcript_path.to_string_lossy(),
+        request.usage_scope,
     )
     .await?;
-    let new_tokens = estimate_tokens(&compacted_messages);
+    let new_tokens = estimate_tokens_for_model(&compacted_messages, request.model);
     let summary = extract_compaction_summary(&compacted_messages);
     if let Some(observability) = request.observability {
         observability.record_compaction_run();
@@ -88,6 +93,7 @@ mod tests {
             api_key: "mock-key",
             model: "mock-model",
             session_id: "session-short",
+            usage_scope: None,
             messages: &messages,
             transcript_root: temp_dir.path(),
             observability: None,
@@ -113,6 +119,7 @@ mod tests {
             api_key: "mock-key",
             model: "mock-model",
             session_id: "session-compact",
+            usage_scope: None,
             messages: &messages,
             transcript_root: temp_dir.path(),
             observability: Some(&observability),
@@ -136,6 +143,7 @@ mod tests {
             api_key: "mock-key",
             model: "mock-model",
             session_id: "session-compact",
+            usage_scope: None,
             messages: &messages,
             transcript_root: temp_dir.path(),
             observability: None,
@@ -20,6 +20,7 @@ pub(crate) enum RuntimeFailoverErrorKind {
     MaxTurns,
     LoopDetected,
     NoProgress,
+    BudgetExceeded,
     Unknown,
 }
 
@@ -90,7 +91,7 @@ impl RuntimeFailover {
         let mut tool_exposure_expansion_reason: Option<String> = None;
         let mut compaction_boundary: Option<TurnCompactionBoundary> = None;
 
-        for (
+        'candidates: for (
             candidate_provider_key,
             candidate_api_format,
             candidate_base_url,
@@ -164,6 +165,10 @@ impl RuntimeFailover {
                 if let Some(on_error_kind) = params.on_error_kind.as_mut() {
                     on_error_kind(current_kind);
                 }
+                // 预算是按整轮任务计的，换候选模型只会继续消耗，直接结束
+                if current_kind == RuntimeFailoverErrorKind::BudgetExceeded {
+                    break 'candidates;
+                }
                 let retry_budget =
                     runtime_retry_budget_for_error(current_kind, params.per_candidate_retry_count);
                 if runtime_should_retry_same_candidate(current_kind) && attempt_idx < retry_budget {
@@ -212,6 +217,7 @@ fn runtime_failover_kind_from_key(key: &str) -> Option<RuntimeFailoverErrorKind>
         "max_turns" => RuntimeFailoverErrorKind::MaxTurns,
         "loop_detected" => RuntimeFailoverErrorKind::LoopDetected,
         "no_progress" => RuntimeFailoverErrorKind::NoProgress,
+        "budget_exceeded" => RuntimeFailoverErrorKind::BudgetExceeded,
         "unknown" => RuntimeFailoverErrorKind::Unknown,
         _ => return None,
     })
@@ -309,6 +315,7 @@ pub(crate) fn runtime_failover_error_kind_from_stop_reason_kind(
             RuntimeFailoverErrorKind::LoopDetected
         }
         RunStopReasonKind::NoProgress => RuntimeFailoverErrorKind::NoProgress,
+        RunStopReasonKind::BudgetExceeded => RuntimeFailoverErrorKind::BudgetExceeded,
         _ => RuntimeFailoverErrorKind::Unknown,
     }
 }
@@ -328,6 +335,7 @@ pub(crate) fn runtime_failover_error_kind_key(kind: RuntimeFailoverErrorKind) ->
         RuntimeFailoverErrorKind::MaxTurns => "max_turns",
         RuntimeFailoverErrorKind::LoopDetected => "loop_detected",
         RuntimeFailoverErrorKind::NoProgress => "no_progress",
+        RuntimeFailoverErrorKind::BudgetExceeded => "budget_exceeded",
         RuntimeFailoverErrorKind::Unknown => "unknown",
     }
 }
@@ -654,6 +662,68 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn execute_candidates_stops_on_budget_exceeded_instead_of_failing_over() {
+        let attempted_models = Arc::new(Mutex::new(Vec::new()));
+        let attempted_models_clone = Arc::clone(&attempted_models);
+        let route_candidates = vec![
+            (
+                String::new(),
+                "openai".to_string(),
+                "https://a.example".to_string(),
+                "model-a".to_string(),
+                "key-a".to_string(),
+            ),
+            (
+                String::new(),
+                "openai".to_string(),
+                "https://b.example".to_string(),
+                "model-b".to_string(),
+                "key-b".to_string(),
+            ),
+        ];
+
+        let outcome = RuntimeFailover::execute_candidates(RuntimeFailoverParams {
+            route_candidates: &route_candidates,
+            per_candidate_retry_count: 2,
+            on_same_candidate_retry: None,
+            on_error_kind: None,
+            attempt_once: Box::new(
+                move |_provider_key, _api_format, _base_url, model_name, _api_key, _attempt_idx| {
+                    attempted_models_clone
+                        .lock()
+                        .expect("attempted models lock")
+                        .push(model_name.to_string());
+                    Box::pin(async move {
+                        CandidateAttemptOutcome {
+                            final_messages: None,
+                            last_error: Some("budget".to_string()),
+                            last_error_kind: Some("budget_exceeded".to_string()),
+                            error_kind: Some(RuntimeFailoverErrorKind::BudgetExceeded),
+                            last_stop_reason: None,
+                            partial_text: String::new(),
+                            reasoning_text: String::new(),
+                            reasoning_duration_ms: None,
+                            tool_exposure_expanded: false,
+                            tool_exposure_expansion_reason: None,
+                            compaction_boundary: None,
+                        }
+                    })
+                },
+            ),
+        })
+        .await;
+
+        assert_eq!(
+            attempted_models
+                .lock()
+                .expect("attempted models lock")
+                .as_slice(),
+            &["model-a".to_string()]
+        );
+        assert_eq!(outcome.last_error_kind.as_deref(), Some("budget_exceeded"));
+    }
+
     #[test]
     fn runtime_failover_error_kind_helpers_stay_in_sync() {
         assert_eq!(
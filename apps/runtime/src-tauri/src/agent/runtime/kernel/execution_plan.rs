@@ -1,5 +1,5 @@
 use crate::agent::permissions::PermissionMode;
-use crate::agent::run_guard::RunStopReason;
+use crate::agent::run_guard::{RunResourceLimits, RunStopReason};
 use crate::agent::runtime::attempt_runner::RouteExecutionOutcome;
 use crate::agent::runtime::effective_tool_set::{
     EffectiveToolDecisionRecord, EffectiveToolPolicyInput, EffectiveToolSet,
@@ -13,6 +13,8 @@ use crate::agent::runtime::runtime_io::WorkspaceSkillRuntimeEntry;
 use crate::agent::runtime::skill_routing::index::SkillRouteIndex;
 use crate::agent::runtime::task_state::{TaskBackendKind, TaskIdentity, TaskKind, TaskSurfaceKind};
 use crate::agent::runtime::task_transition::{TaskContinuationMode, TaskContinuationSource};
+use crate::agent::structured_output::StructuredOutputSpec;
+use crate::route_health::ModelRouteDecisionRecord;
 use runtime_chat_app::ChatExecutionGuidance;
 use serde_json::Value;
 
@@ -63,6 +65,8 @@ pub(crate) struct ExecutionContext {
     pub runtime_default_tool_policy: EffectiveToolPolicyInput,
     pub executor_work_dir: Option<String>,
     pub max_iterations: Option<usize>,
+    pub run_resource_limits: RunResourceLimits,
+    pub structured_output: Option<StructuredOutputSpec>,
     pub max_call_depth: usize,
     pub node_timeout_seconds: u64,
     pub route_retry_count: usize,
@@ -102,6 +106,8 @@ impl Default for ExecutionContext {
             },
             executor_work_dir: None,
             max_iterations: None,
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
             max_call_depth: 0,
             node_timeout_seconds: 0,
             route_retry_count: 0,
@@ -192,6 +198,7 @@ pub(crate) struct TurnContext {
     pub messages: Vec<Value>,
     pub continuation_preference: Option<ContinuationPreference>,
     pub resource_context: Option<TurnResourceContext>,
+    pub route_decision: Option<ModelRouteDecisionRecord>,
 }
 
 impl TurnContext {
@@ -231,6 +238,7 @@ mod tests {
         ExecutionContext, ExecutionLane, ExecutionPlan, TurnContext,
     };
     use crate::agent::permissions::PermissionMode;
+    use crate::agent::run_guard::RunResourceLimits;
     use crate::agent::runtime::kernel::capability_snapshot::CapabilitySnapshot;
     use crate::agent::runtime::kernel::route_lane::RouteRunPlan;
     use crate::agent::runtime::kernel::session_profile::{
@@ -307,6 +315,8 @@ mod tests {
             },
             executor_work_dir: Some("E:/workspace/demo".to_string()),
             max_iterations: Some(12),
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
             max_call_depth: 4,
             node_timeout_seconds: 90,
             route_retry_count: 2,
@@ -394,6 +404,7 @@ mod tests {
                 },
             }),
             resource_context: None,
+            route_decision: None,
         };
 
         assert_eq!(turn_context.requested_capability, "chat");
@@ -1,6 +1,8 @@
 use crate::agent::browser_progress::BrowserProgressSnapshot;
 use crate::agent::context::build_tool_context_with_permission_mode;
-use crate::agent::run_guard::{ProgressFingerprint, RunBudgetPolicy, RunBudgetScope};
+use crate::agent::run_guard::{
+    ProgressFingerprint, RunBudgetPolicy, RunBudgetScope, RunBudgetTracker,
+};
 use crate::agent::runtime::attempt_runner::{execute_route_candidates, RouteExecutionParams};
 use crate::agent::runtime::events::ToolConfirmResponder;
 use crate::agent::runtime::kernel::execution_plan::{
@@ -97,6 +99,7 @@ pub(crate) async fn execute_execution_lane(
     .await?
     {
         RouteRunOutcome::OpenTask => {
+            let run_budget_tracker = RunBudgetTracker::start();
             let route_execution = execute_route_candidates(RouteExecutionParams {
                 app: params.app,
                 agent_executor: params.agent_executor.as_ref(),
@@ -114,6 +117,9 @@ pub(crate) async fn execute_execution_lane(
                 tool_confirm_responder: params.tool_confirm_responder,
                 executor_work_dir: params.execution_context.executor_work_dir.clone(),
                 max_iterations: params.execution_context.max_iterations,
+                run_resource_limits: params.execution_context.run_resource_limits,
+                run_budget_tracker: &run_budget_tracker,
+                structured_output: params.execution_context.structured_output.as_ref(),
                 cancel_flag: params.cancel_flag,
                 node_timeout_seconds: params.execution_context.node_timeout_seconds,
                 route_retry_count: params.execution_context.route_retry_count,
@@ -241,6 +247,7 @@ async fn maybe_direct_dispatch_workspace_vision(
 mod tests {
     use super::decorate_turn_state;
     use crate::agent::permissions::PermissionMode;
+    use crate::agent::run_guard::RunResourceLimits;
     use crate::agent::runtime::kernel::capability_snapshot::CapabilitySnapshot;
     use crate::agent::runtime::kernel::execution_plan::{
         ExecutionContext, ExecutionLane, ExecutionPlan,
@@ -273,6 +280,8 @@ mod tests {
             runtime_default_tool_policy: ExecutionContext::default().runtime_default_tool_policy,
             executor_work_dir: None,
             max_iterations: Some(4),
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
             max_call_depth: 2,
             node_timeout_seconds: 60,
             route_retry_count: 1,
@@ -101,12 +101,10 @@ impl OutcomeCommitter {
                     journal,
                     session_id,
                     run_id,
-                    &output,
-                    false,
-                    &output,
-                    "",
-                    None,
-                    Some(&turn_state),
+                    chat_io::RunSuccessOutput {
+                        turn_state: Some(&turn_state),
+                        ..chat_io::RunSuccessOutput::text(&output)
+                    },
                 )
                 .await?;
                 emit_stream_token(session_id, output, false, false);
@@ -259,12 +257,14 @@ impl OutcomeCommitter {
             journal,
             session_id,
             run_id,
-            &final_text,
-            has_tool_calls,
-            &content,
-            &reasoning_text,
-            reasoning_duration_ms,
-            Some(&turn_state),
+            chat_io::RunSuccessOutput {
+                final_text: &final_text,
+                has_tool_calls,
+                content: &content,
+                reasoning_text: &reasoning_text,
+                reasoning_duration_ms,
+                turn_state: Some(&turn_state),
+            },
         )
         .await;
 
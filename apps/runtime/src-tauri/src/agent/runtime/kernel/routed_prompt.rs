@@ -1,4 +1,6 @@
-use crate::agent::run_guard::{RunBudgetPolicy, RunBudgetScope};
+use crate::agent::run_guard::{
+    RunBudgetPolicy, RunBudgetScope, RunBudgetTracker, RunResourceLimits,
+};
 use crate::agent::runtime::attempt_runner::{
     execute_route_candidates, RouteExecutionOutcome, RouteExecutionParams,
 };
@@ -6,6 +8,7 @@ use crate::agent::runtime::events::ToolConfirmResponder;
 use crate::agent::runtime::kernel::execution_plan::ExecutionContext;
 use crate::agent::runtime::kernel::execution_plan::TurnContext;
 use crate::agent::runtime::tool_setup::{prepare_runtime_tools, ToolSetupParams};
+use crate::agent::structured_output::StructuredOutputSpec;
 use crate::agent::AgentExecutor;
 use runtime_chat_app::ChatExecutionPreparationService;
 use serde_json::Value;
@@ -13,13 +16,15 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tauri::AppHandle;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct PreparedRoutedPrompt {
     pub allowed_tools: Option<Vec<String>>,
     pub full_allowed_tools: Vec<String>,
     pub has_deferred_tools: bool,
     pub system_prompt: String,
     pub max_iterations: usize,
+    pub run_resource_limits: RunResourceLimits,
+    pub structured_output: Option<StructuredOutputSpec>,
 }
 
 #[derive(Clone)]
@@ -83,6 +88,18 @@ pub(crate) async fn prepare_routed_prompt(
     let execution_preparation_service = ChatExecutionPreparationService::new();
     let max_iterations =
         resolve_routed_prompt_max_iterations(params.skill_id, params.skill_max_iterations);
+    let skill_entry = params
+        .execution_context
+        .workspace_skill_entries
+        .iter()
+        .find(|entry| entry.skill_id == params.skill_id);
+    let run_resource_limits = skill_entry
+        .and_then(|entry| entry.config.budget)
+        .map(RunResourceLimits::from)
+        .unwrap_or_default();
+    let structured_output = skill_entry.and_then(|entry| {
+        StructuredOutputSpec::from_schema(&entry.name, entry.config.output_schema.as_ref())
+    });
 
     let prepared_runtime_tools = prepare_runtime_tools(ToolSetupParams {
         app: params.app,
@@ -132,12 +149,15 @@ pub(crate) async fn prepare_routed_prompt(
             .has_deferred_tools(),
         system_prompt: prepared_runtime_tools.system_prompt,
         max_iterations,
+        run_resource_limits,
+        structured_output,
     })
 }
 
 pub(crate) async fn execute_routed_prompt(
     params: RoutedPromptExecutionParams<'_>,
 ) -> RouteExecutionOutcome {
+    let run_budget_tracker = RunBudgetTracker::start();
     execute_route_candidates(RouteExecutionParams {
         app: params.app,
         agent_executor: params.agent_executor.as_ref(),
@@ -155,6 +175,9 @@ pub(crate) async fn execute_routed_prompt(
         tool_confirm_responder: params.tool_confirm_responder,
         executor_work_dir: params.execution_context.executor_work_dir.clone(),
         max_iterations: Some(params.prepared_prompt.max_iterations),
+        run_resource_limits: params.prepared_prompt.run_resource_limits,
+        run_budget_tracker: &run_budget_tracker,
+        structured_output: params.prepared_prompt.structured_output.as_ref(),
         cancel_flag: params.cancel_flag,
         node_timeout_seconds: params.execution_context.node_timeout_seconds,
         route_retry_count: params.execution_context.route_retry_count,
@@ -165,7 +188,7 @@ pub(crate) async fn execute_routed_prompt(
 #[cfg(test)]
 mod tests {
     use super::{resolve_routed_prompt_max_iterations, PreparedRoutedPrompt};
-    use crate::agent::run_guard::{RunBudgetPolicy, RunBudgetScope};
+    use crate::agent::run_guard::{RunBudgetPolicy, RunBudgetScope, RunResourceLimits};
 
     #[test]
     fn resolve_routed_prompt_max_iterations_uses_general_chat_budget_for_builtin_general() {
@@ -195,6 +218,8 @@ mod tests {
             has_deferred_tools: false,
             system_prompt: "Prompt".to_string(),
             max_iterations: 9,
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
         };
 
         assert_eq!(
@@ -6,7 +6,8 @@ use crate::agent::tools::{
     register_tool_alias, AskUserTool, BashKillTool, BashOutputTool, BashTool, ClawhubRecommendTool,
     ClawhubSearchTool, CompactTool, CuratorTool, DocumentAnalyzeTool, EmployeeManageTool,
     ExecKillTool, ExecOutputTool, ExecTool, GithubRepoDownloadTool, MemoryTool, ProcessManager,
-    SkillInvokeTool, SkillOsTool, TaskTool, ToolsetsTool, VisionAnalyzeTool, WebSearchTool,
+    SkillInvokeTool, SkillOsTool, TaskTool, ToolsetsTool, VisionAnalyzeTool, WebFetchTool,
+    WebSearchTool,
 };
 use crate::agent::{AgentExecutor, BackgroundProcessEvent, Tool, ToolContext, ToolRegistry};
 use crate::runtime_environment::runtime_paths_from_app;
@@ -379,6 +380,10 @@ pub(crate) async fn setup_runtime_tool_registry(
         .register(Arc::new(DocumentAnalyzeTool::new(runtime_paths)));
 
     let search_cache = params.app.state::<SearchCacheState>().0.clone();
+    params
+        .agent_executor
+        .registry()
+        .register(Arc::new(WebFetchTool::with_cache(Arc::clone(&search_cache))));
     let mut runtime_notes = Vec::new();
     if let Some((search_api_format, search_base_url, search_api_key, search_model_name)) =
         chat_io::load_default_search_provider_config_with_pool(params.db).await?
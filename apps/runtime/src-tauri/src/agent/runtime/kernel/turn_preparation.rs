@@ -7,7 +7,7 @@ use super::route_lane::{
 };
 use super::session_profile::{SessionExecutionProfile, SessionSurfaceKind};
 use crate::agent::permissions::PermissionMode;
-use crate::agent::run_guard::{RunBudgetPolicy, RunBudgetScope};
+use crate::agent::run_guard::{RunBudgetPolicy, RunBudgetScope, RunResourceLimits};
 use crate::agent::runtime::effective_tool_set::runtime_default_tool_policy_input;
 use crate::agent::runtime::kernel::capability_snapshot::CapabilitySnapshot;
 use crate::agent::runtime::kernel::context_bundle::ContextBundle;
@@ -22,8 +22,10 @@ use crate::agent::runtime::task_continuation::is_task_continuation_request;
 use crate::agent::runtime::task_transition::{TaskContinuationMode, TaskContinuationSource};
 use crate::agent::runtime::tool_setup::{prepare_runtime_tools, ToolSetupParams};
 use crate::agent::runtime::RuntimeTranscript;
+use crate::agent::structured_output::StructuredOutputSpec;
 use crate::agent::AgentExecutor;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind};
+use crate::model_transport::resolve_model_transport;
+use crate::route_health::plan_route_candidates_with_pool;
 use crate::runtime_environment::runtime_paths_from_app;
 use crate::session_journal::{SessionJournalState, SessionJournalStateHandle, SessionRunStatus};
 use runtime_chat_app::{ChatExecutionPreparationRequest, ChatExecutionPreparationService};
@@ -113,12 +115,7 @@ pub(crate) async fn prepare_local_turn(
                 Some(candidate.provider_key.as_str()).filter(|value| !value.trim().is_empty()),
             );
             let effective_api_format = if candidate.protocol_type.trim().is_empty() {
-                match transport.kind {
-                    ModelTransportKind::AnthropicMessages => "anthropic".to_string(),
-                    ModelTransportKind::OpenAiCompletions | ModelTransportKind::OpenAiResponses => {
-                        "openai".to_string()
-                    }
-                }
+                transport.kind.api_format().to_string()
             } else {
                 candidate.protocol_type.clone()
             };
@@ -143,11 +140,31 @@ pub(crate) async fn prepare_local_turn(
     }
 
     route_candidates.dedup();
+    let route_decision = match plan_route_candidates_with_pool(
+        params.db,
+        &requested_capability,
+        route_candidates.clone(),
+    )
+    .await
+    {
+        Ok((ordered, decision)) => {
+            route_candidates = ordered;
+            Some(decision)
+        }
+        Err(error) => {
+            eprintln!("[routing] 读取路由健康度失败，按配置顺序路由: {error}");
+            None
+        }
+    };
     eprintln!(
-        "[routing] capability={}, candidates={}, retry_per_candidate={}",
+        "[routing] capability={}, candidates={}, retry_per_candidate={}, decision={}",
         requested_capability,
         route_candidates.len(),
-        per_candidate_retry_count
+        per_candidate_retry_count,
+        route_decision
+            .as_ref()
+            .map(|decision| decision.decision_reason.as_str())
+            .unwrap_or("configured_order")
     );
 
     let (_, api_format, base_url, model_name, api_key) = route_candidates[0].clone();
@@ -219,6 +236,26 @@ pub(crate) async fn prepare_local_turn(
         .as_ref()
         .and_then(|selection| selection.max_iterations)
         .or(skill_config.max_iterations);
+    let run_resource_limits = explicit_skill_selection
+        .as_ref()
+        .and_then(|selection| selection.budget)
+        .or(skill_config.budget)
+        .map(RunResourceLimits::from)
+        .unwrap_or_default();
+    let structured_output = explicit_skill_selection
+        .as_ref()
+        .and_then(|selection| {
+            StructuredOutputSpec::from_schema(
+                &selection.skill_name,
+                selection.output_schema.as_ref(),
+            )
+        })
+        .or_else(|| {
+            StructuredOutputSpec::from_schema(
+                skill_config.name.as_deref().unwrap_or(skill_id.as_str()),
+                skill_config.output_schema.as_ref(),
+            )
+        });
     let budget_scope = if effective_skill_id
         .trim()
         .eq_ignore_ascii_case("builtin-general")
@@ -340,6 +377,8 @@ pub(crate) async fn prepare_local_turn(
         executor_work_dir: execution_preparation_service
             .resolve_executor_work_dir(&execution_guidance),
         max_iterations: Some(max_iter),
+        run_resource_limits,
+        structured_output,
         max_call_depth: chat_preparation.max_call_depth,
         node_timeout_seconds: chat_preparation.node_timeout_seconds,
         route_retry_count,
@@ -362,6 +401,7 @@ pub(crate) async fn prepare_local_turn(
             messages,
             continuation_preference,
             resource_context: Some(resource_context),
+            route_decision,
         },
         execution_context,
     ))
@@ -419,6 +459,7 @@ pub(crate) fn prepare_hidden_child_turn(
             messages,
             continuation_preference: None,
             resource_context: None,
+            route_decision: None,
         },
         ExecutionContext {
             session_profile: build_hidden_child_session_profile(),
@@ -436,6 +477,8 @@ pub(crate) fn prepare_hidden_child_turn(
             runtime_default_tool_policy: ExecutionContext::default().runtime_default_tool_policy,
             executor_work_dir: work_dir,
             max_iterations: Some(max_iterations.max(1)),
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
             max_call_depth: 0,
             node_timeout_seconds: 60,
             route_retry_count: 0,
@@ -503,6 +546,7 @@ pub(crate) fn prepare_employee_step_turn(
             })],
             continuation_preference: None,
             resource_context: None,
+            route_decision: None,
         },
         ExecutionContext {
             session_profile: build_employee_step_session_profile(),
@@ -520,6 +564,8 @@ pub(crate) fn prepare_employee_step_turn(
             runtime_default_tool_policy: ExecutionContext::default().runtime_default_tool_policy,
             executor_work_dir: work_dir,
             max_iterations: Some(max_iterations.max(1)),
+            run_resource_limits: RunResourceLimits::default(),
+            structured_output: None,
             max_call_depth: 0,
             node_timeout_seconds: 60,
             route_retry_count: 0,
@@ -804,7 +850,13 @@ fn is_recoverable_continuation_run(run: &crate::session_journal::SessionRunSnaps
         SessionRunStatus::Failed | SessionRunStatus::Cancelled
     ) || matches!(
         run.last_error_kind.as_deref(),
-        Some("max_turns" | "loop_detected" | "no_progress" | "tool_failure_circuit_breaker")
+        Some(
+            "max_turns"
+                | "loop_detected"
+                | "no_progress"
+                | "tool_failure_circuit_breaker"
+                | "budget_exceeded"
+        )
     )
 }
 
@@ -837,7 +889,12 @@ fn resolve_continuation_turn_policy(
     let should_clamp_retries = matches!(
         run.last_error_kind.as_deref(),
         Some(
-            "max_turns" | "loop_detected" | "no_progress" | "tool_failure_circuit_breaker" | "auth"
+            "max_turns"
+                | "loop_detected"
+                | "no_progress"
+                | "tool_failure_circuit_breaker"
+                | "budget_exceeded"
+                | "auth"
         )
     );
 
@@ -974,6 +1031,8 @@ fn resolve_explicit_prompt_following_skill(
             denied_tool_categories: parse_skill_denied_tool_categories(&entry.config),
             allowed_mcp_servers: skill_allowed_mcp_servers(entry),
             max_iterations: entry.config.max_iterations,
+            budget: entry.config.budget,
+            output_schema: entry.config.output_schema.clone(),
         })
         .collect::<Vec<_>>();
 
@@ -1008,7 +1067,7 @@ fn append_current_turn_message(messages: &mut Vec<Value>, current_turn: Value) {
     messages.push(current_turn);
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 struct ExplicitPromptSkillSelection {
     skill_id: String,
     skill_name: String,
@@ -1021,6 +1080,8 @@ struct ExplicitPromptSkillSelection {
     denied_tool_categories: Option<Vec<crate::agent::tool_manifest::ToolCategory>>,
     allowed_mcp_servers: Option<Vec<String>>,
     max_iterations: Option<usize>,
+    budget: Option<crate::agent::skill_config::SkillRunBudget>,
+    output_schema: Option<Value>,
 }
 
 #[cfg(test)]
@@ -4,6 +4,7 @@ use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 
 use super::effective_tool_set::EffectiveToolDecisionRecord;
+use crate::agent::types::TokenUsage;
 
 const DEFAULT_MAX_EVENTS: usize = 400;
 
@@ -85,9 +86,20 @@ pub struct RuntimeCompactionSnapshot {
     pub runs: u64,
 }
 
+/// 提示词缓存命中情况：`hit_calls` 为读取到缓存的模型调用次数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuntimePromptCacheSnapshot {
+    pub model_calls: u64,
+    pub hit_calls: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub uncached_input_tokens: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RuntimeFailoverSnapshot {
     pub errors_by_kind: BTreeMap<String, u64>,
+    pub route_decisions_by_reason: BTreeMap<String, u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -99,6 +111,7 @@ pub struct RuntimeObservabilitySnapshot {
     pub approvals: RuntimeApprovalsSnapshot,
     pub child_sessions: RuntimeChildSessionsSnapshot,
     pub compaction: RuntimeCompactionSnapshot,
+    pub prompt_cache: RuntimePromptCacheSnapshot,
     pub failover: RuntimeFailoverSnapshot,
     pub errors_by_kind: BTreeMap<String, u64>,
     pub latest_skill_route: Option<RuntimeLatestSkillRouteSnapshot>,
@@ -143,7 +156,11 @@ struct RuntimeObservabilityInner {
     approval_requests: u64,
     child_session_links: u64,
     compaction_runs: u64,
+    prompt_cache_usage: TokenUsage,
+    prompt_cache_model_calls: u64,
+    prompt_cache_hit_calls: u64,
     failover_errors_by_kind: BTreeMap<String, u64>,
+    route_decisions_by_reason: BTreeMap<String, u64>,
     started_at_by_run: HashMap<String, i64>,
     latest_skill_route: Option<RuntimeLatestSkillRouteSnapshot>,
 }
@@ -192,6 +209,18 @@ impl RuntimeObservability {
         inner.compaction_runs += 1;
     }
 
+    pub fn record_model_usage(&self, usage: &TokenUsage) {
+        let mut inner = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        inner.prompt_cache_model_calls += 1;
+        if usage.cache_read_tokens > 0 {
+            inner.prompt_cache_hit_calls += 1;
+        }
+        inner.prompt_cache_usage.add(usage);
+    }
+
     pub fn record_child_session_link(&self) {
         let mut inner = self
             .inner
@@ -262,8 +291,16 @@ impl RuntimeObservability {
             compaction: RuntimeCompactionSnapshot {
                 runs: inner.compaction_runs,
             },
+            prompt_cache: RuntimePromptCacheSnapshot {
+                model_calls: inner.prompt_cache_model_calls,
+                hit_calls: inner.prompt_cache_hit_calls,
+                cache_read_tokens: inner.prompt_cache_usage.cache_read_tokens,
+                cache_write_tokens: inner.prompt_cache_usage.cache_write_tokens,
+                uncached_input_tokens: inner.prompt_cache_usage.input_tokens,
+            },
             failover: RuntimeFailoverSnapshot {
                 errors_by_kind: inner.failover_errors_by_kind.clone(),
+                route_decisions_by_reason: inner.route_decisions_by_reason.clone(),
             },
             errors_by_kind: inner.error_counts_by_kind.clone(),
             latest_skill_route: inner.latest_skill_route.clone(),
@@ -314,6 +351,14 @@ impl RuntimeObservability {
                     "approval_requested" => {
                         inner.approval_requests += 1;
                     }
+                    "model_route_recorded" => {
+                        if let Some(reason) = event.warning_kind.as_deref() {
+                            let key = normalize_key(reason);
+                            if !key.is_empty() {
+                                *inner.route_decisions_by_reason.entry(key).or_insert(0) += 1;
+                            }
+                        }
+                    }
                     "skill_route_recorded" => {
                         if let (Some(route_latency_ms), Some(candidate_count)) =
                             (event.route_latency_ms, event.candidate_count)
@@ -390,6 +435,7 @@ mod tests {
         EffectiveToolDecisionRecord, EffectiveToolExclusion, EffectiveToolPolicySummary,
         EffectiveToolSetSource, ToolFilterReason, ToolLoadingPolicy,
     };
+    use crate::agent::types::TokenUsage;
 
     fn run_event(
         session_id: &str,
@@ -477,6 +523,31 @@ mod tests {
         assert!(snapshot.guard.warnings_by_kind.is_empty());
         assert!(snapshot.errors_by_kind.is_empty());
         assert!(snapshot.latest_skill_route.is_none());
+        assert_eq!(snapshot.prompt_cache.model_calls, 0);
+    }
+
+    #[test]
+    fn model_usage_updates_prompt_cache_stats() {
+        let subject = RuntimeObservability::new(16);
+        subject.record_model_usage(&TokenUsage {
+            input_tokens: 1_200,
+            output_tokens: 80,
+            cache_read_tokens: 0,
+            cache_write_tokens: 3_000,
+        });
+        subject.record_model_usage(&TokenUsage {
+            input_tokens: 150,
+            output_tokens: 60,
+            cache_read_tokens: 3_000,
+            cache_write_tokens: 0,
+        });
+
+        let prompt_cache = snapshot(&subject).prompt_cache;
+        assert_eq!(prompt_cache.model_calls, 2);
+        assert_eq!(prompt_cache.hit_calls, 1);
+        assert_eq!(prompt_cache.cache_read_tokens, 3_000);
+        assert_eq!(prompt_cache.cache_write_tokens, 3_000);
+        assert_eq!(prompt_cache.uncached_input_tokens, 1_350);
     }
 
     #[test]
@@ -513,6 +584,25 @@ mod tests {
         assert_eq!(snapshot.errors_by_kind.get("network"), Some(&1));
     }
 
+    #[test]
+    fn model_route_recorded_counts_decision_reasons() {
+        let subject = RuntimeObservability::new(16);
+        let mut skipped = run_event(
+            "session-1",
+            "run-1",
+            "model_route_recorded",
+            "2026-04-08T10:00:00Z",
+        );
+        skipped.warning_kind = Some("skipped_unhealthy".to_string());
+        subject.record_recent_event(RuntimeObservedEvent::SessionRun(skipped));
+
+        let failover = snapshot(&subject).failover;
+        assert_eq!(
+            failover.route_decisions_by_reason.get("skipped_unhealthy"),
+            Some(&1)
+        );
+    }
+
     #[test]
     fn skill_route_recorded_updates_latest_skill_route_snapshot() {
         let subject = RuntimeObservability::new(16);
@@ -281,15 +281,16 @@ impl ChatSettingsRepository for PoolChatSettingsRepository<'_> {
         .await
         .map_err(|e| format!("读取 Provider 配置失败: {e}"))?;
 
-        Ok(row.map(
-            |(provider_key, protocol_type, base_url, api_key)| ProviderConnectionSnapshot {
+        row.map(|(provider_key, protocol_type, base_url, api_key)| {
+            Ok(ProviderConnectionSnapshot {
                 provider_id: provider_id.to_string(),
                 provider_key,
                 protocol_type,
                 base_url,
-                api_key,
-            },
-        ))
+                api_key: crate::secret_store::reveal_secret(&api_key)?,
+            })
+        })
+        .transpose()
     }
 
     async fn load_session_model(&self, model_id: &str) -> Result<SessionModelSnapshot, String> {
@@ -307,7 +308,7 @@ impl ChatSettingsRepository for PoolChatSettingsRepository<'_> {
             api_format,
             base_url,
             model_name,
-            api_key,
+            api_key: crate::secret_store::reveal_secret(&api_key)?,
         })
     }
 
@@ -67,6 +67,8 @@ impl RunRegistry {
             }
             SessionRunEvent::AssistantChunkAppended { .. }
             | SessionRunEvent::SkillRouteRecorded { .. }
+            | SessionRunEvent::ModelRouteRecorded { .. }
+            | SessionRunEvent::StructuredOutputRecorded { .. }
             | SessionRunEvent::ToolStarted { .. }
             | SessionRunEvent::ToolCompleted { .. }
             | SessionRunEvent::ApprovalRequested { .. }
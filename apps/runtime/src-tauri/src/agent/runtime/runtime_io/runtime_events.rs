@@ -13,6 +13,7 @@ use crate::commands::im_host::{
     maybe_emit_registered_host_lifecycle_phase_for_session_with_pool,
     maybe_stop_registered_host_processing_for_session_with_pool,
 };
+use crate::route_health::ModelRouteDecisionRecord;
 use crate::session_journal::{SessionJournalStore, SessionRunEvent, SessionRunTurnStateSnapshot};
 use chrono::Utc;
 use serde_json::{json, Value};
@@ -42,32 +43,41 @@ pub(crate) async fn insert_session_message_with_pool(
     Ok(msg_id)
 }
 
+/// route_attempt_logs 中的一次模型路由尝试
+pub(crate) struct RouteAttemptLog<'a> {
+    pub session_id: &'a str,
+    pub capability: &'a str,
+    pub api_format: &'a str,
+    pub base_url: &'a str,
+    pub model_name: &'a str,
+    pub attempt_index: usize,
+    pub retry_index: usize,
+    pub error_kind: &'a str,
+    pub success: bool,
+    pub error_message: &'a str,
+    pub latency_ms: u64,
+}
+
 pub(crate) async fn record_route_attempt_log_with_pool(
     pool: &sqlx::SqlitePool,
-    session_id: &str,
-    capability: &str,
-    api_format: &str,
-    model_name: &str,
-    attempt_index: usize,
-    retry_index: usize,
-    error_kind: &str,
-    success: bool,
-    error_message: &str,
+    log: RouteAttemptLog<'_>,
 ) {
     let _ = sqlx::query(
-        "INSERT INTO route_attempt_logs (id, session_id, capability, api_format, model_name, attempt_index, retry_index, error_kind, success, error_message, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO route_attempt_logs (id, session_id, capability, api_format, base_url, model_name, attempt_index, retry_index, error_kind, success, error_message, latency_ms, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(Uuid::new_v4().to_string())
-    .bind(session_id)
-    .bind(capability)
-    .bind(api_format)
-    .bind(model_name)
-    .bind(attempt_index as i64)
-    .bind(retry_index as i64)
-    .bind(error_kind)
-    .bind(success)
-    .bind(error_message)
+    .bind(log.session_id)
+    .bind(log.capability)
+    .bind(log.api_format)
+    .bind(log.base_url)
+    .bind(log.model_name)
+    .bind(log.attempt_index as i64)
+    .bind(log.retry_index as i64)
+    .bind(log.error_kind)
+    .bind(log.success)
+    .bind(log.error_message)
+    .bind(log.latency_ms as i64)
     .bind(Utc::now().to_rfc3339())
     .execute(pool)
     .await;
@@ -121,6 +131,30 @@ pub(crate) async fn append_skill_route_recorded_with_pool(
     .await
 }
 
+pub(crate) async fn append_model_route_recorded_with_pool(
+    pool: &sqlx::SqlitePool,
+    journal: &SessionJournalStore,
+    session_id: &str,
+    run_id: &str,
+    decision: &ModelRouteDecisionRecord,
+) -> Result<(), String> {
+    append_session_run_event_with_pool(
+        pool,
+        journal,
+        session_id,
+        SessionRunEvent::ModelRouteRecorded {
+            run_id: run_id.to_string(),
+            capability: decision.capability.clone(),
+            candidate_count: decision.candidate_count,
+            skipped_count: decision.skipped_count,
+            selected_provider: decision.selected_provider.clone(),
+            selected_model: decision.selected_model.clone(),
+            decision_reason: decision.decision_reason.clone(),
+        },
+    )
+    .await
+}
+
 pub(crate) async fn append_run_failed_with_pool(
     pool: &sqlx::SqlitePool,
     journal: &SessionJournalStore,
@@ -259,7 +293,7 @@ fn attach_reasoning_to_content(
     reasoning_text: &str,
     reasoning_duration_ms: Option<u64>,
 ) -> String {
-    if reasoning_text.trim().is_empty() || has_tool_calls {
+    if reasoning_text.trim().is_empty() {
         return content.to_string();
     }
 
@@ -419,18 +453,47 @@ pub(crate) async fn persist_partial_assistant_message_for_run_with_pool(
     Ok(Some(msg_id))
 }
 
+/// 运行成功时写入会话的助手输出
+#[derive(Clone, Copy)]
+pub(crate) struct RunSuccessOutput<'a> {
+    pub final_text: &'a str,
+    pub has_tool_calls: bool,
+    /// 落库的助手消息内容，含工具调用时为结构化 JSON
+    pub content: &'a str,
+    pub reasoning_text: &'a str,
+    pub reasoning_duration_ms: Option<u64>,
+    pub turn_state: Option<&'a TurnStateSnapshot>,
+}
+
+impl<'a> RunSuccessOutput<'a> {
+    /// 纯文本回复，没有工具调用与推理内容
+    pub(crate) fn text(text: &'a str) -> Self {
+        Self {
+            final_text: text,
+            has_tool_calls: false,
+            content: text,
+            reasoning_text: "",
+            reasoning_duration_ms: None,
+            turn_state: None,
+        }
+    }
+}
+
 pub(crate) async fn finalize_run_success_with_pool(
     pool: &sqlx::SqlitePool,
     journal: &SessionJournalStore,
     session_id: &str,
     run_id: &str,
-    final_text: &str,
-    has_tool_calls: bool,
-    content: &str,
-    reasoning_text: &str,
-    reasoning_duration_ms: Option<u64>,
-    turn_state: Option<&TurnStateSnapshot>,
+    output: RunSuccessOutput<'_>,
 ) -> Result<(), String> {
+    let RunSuccessOutput {
+        final_text,
+        has_tool_calls,
+        content,
+        reasoning_text,
+        reasoning_duration_ms,
+        turn_state,
+    } = output;
     if !final_text.is_empty() {
         append_session_run_event_with_pool(
             pool,
@@ -490,7 +553,7 @@ mod run_guard_persistence_tests {
     use super::{
         append_run_guard_warning_with_pool, append_run_started_with_pool,
         append_run_stopped_with_pool, finalize_run_success_with_pool,
-        persist_partial_assistant_message_for_run_with_pool,
+        persist_partial_assistant_message_for_run_with_pool, RunSuccessOutput,
     };
     use crate::agent::run_guard::RunStopReason;
     use crate::commands::feishu_gateway::{
@@ -742,6 +805,7 @@ mod run_guard_persistence_tests {
                 input: json!({"command": "echo hi"}),
                 output: "hi".to_string(),
                 is_error: false,
+                checkpoint_id: None,
             })
             .expect("serialize tool completed"),
         )
@@ -843,12 +907,7 @@ mod run_guard_persistence_tests {
             &journal,
             "session-feishu-1",
             "run-feishu-1",
-            &"A".repeat(4000),
-            false,
-            &"A".repeat(4000),
-            "",
-            None,
-            None,
+            RunSuccessOutput::text(&"A".repeat(4000)),
         )
         .await
         .expect("finalize run success");
@@ -907,6 +966,7 @@ mod run_guard_persistence_tests {
                 input: json!({"skill_name": "dispatch-skill", "arguments": ["--employee", "xt"]}),
                 output: "{\"ok\":true}".to_string(),
                 is_error: false,
+                checkpoint_id: None,
             })
             .expect("serialize skill tool completed"),
         )
@@ -941,6 +1001,7 @@ mod run_guard_persistence_tests {
                 }),
                 output: "{\"ok\":true}".to_string(),
                 is_error: false,
+                checkpoint_id: None,
             })
             .expect("serialize exec tool completed"),
         )
@@ -973,7 +1034,7 @@ mod run_guard_persistence_tests {
     }
 
     #[test]
-    fn attach_reasoning_to_content_skips_tool_call_transcripts() {
+    fn attach_reasoning_to_content_keeps_tool_call_items() {
         let content = r#"{"text":"先检查目录","items":[{"type":"text","content":"先检查目录"},{"type":"tool_call","toolCall":{"id":"call-1","name":"list_dir","input":{"path":"."},"status":"completed"}}]}"#;
 
         let persisted = super::attach_reasoning_to_content(
@@ -985,7 +1046,15 @@ mod run_guard_persistence_tests {
         );
 
         let parsed: Value = serde_json::from_str(&persisted).expect("structured content");
-        assert!(parsed.get("reasoning").is_none());
+        assert_eq!(parsed["reasoning"]["content"], "先思考再调用工具");
+        assert_eq!(parsed["reasoning"]["duration_ms"], 760_000);
         assert_eq!(parsed["items"].as_array().map(|items| items.len()), Some(2));
     }
+
+    #[test]
+    fn attach_reasoning_to_content_ignores_blank_reasoning() {
+        let persisted = super::attach_reasoning_to_content("完成", "完成", false, "  ", None);
+
+        assert_eq!(persisted, "完成");
+    }
 }
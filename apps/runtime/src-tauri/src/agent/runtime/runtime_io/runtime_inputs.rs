@@ -133,6 +133,8 @@ pub(crate) async fn load_installed_skill_source_with_pool(
         &source_type,
     )
     .await?;
+    // 技能包的解锁凭据（用户名或团队口令）以密文保存
+    let username = crate::secret_store::reveal_secret(&username)?;
 
     Ok((manifest, username, pack_path, source_type))
 }
@@ -158,12 +160,21 @@ pub(crate) async fn load_session_history_with_pool(
 pub(crate) async fn load_default_search_provider_config_with_pool(
     pool: &sqlx::SqlitePool,
 ) -> Result<Option<(String, String, String, String)>, String> {
-    sqlx::query_as::<_, (String, String, String, String)>(
+    let row = sqlx::query_as::<_, (String, String, String, String)>(
         "SELECT api_format, base_url, api_key, model_name FROM model_configs WHERE api_format LIKE 'search_%' AND is_default = 1 LIMIT 1",
     )
     .fetch_optional(pool)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+    row.map(|(api_format, base_url, api_key, model_name)| {
+        Ok((
+            api_format,
+            base_url,
+            crate::secret_store::reveal_secret(&api_key)?,
+            model_name,
+        ))
+    })
+    .transpose()
 }
 
 #[cfg(test)]
@@ -264,6 +275,7 @@ mod tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         sqlx::query(
@@ -313,6 +325,7 @@ mod tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         sqlx::query(
@@ -1090,6 +1090,7 @@ pub async fn create_agent_skill_os_entry_with_pool(
         created_at: Utc::now(),
         username_hint: None,
         encrypted_verify: String::new(),
+        ..Default::default()
     };
     let manifest_json =
         serde_json::to_string(&manifest).map_err(|e| format!("序列化 skill manifest 失败: {e}"))?;
@@ -246,6 +246,26 @@ fn load_legacy_builtin_embedded_markdown(skill_id: &str) -> String {
         .to_string()
 }
 
+/// 运行时解包已安装的技能包。发布者信任在安装时确认，这里要求包内签名的发布者
+/// 与安装记录一致，避免安装后 pack 文件被替换为其他发布者或未签名的包。
+fn unpack_installed_skillpack(
+    pack_path: &str,
+    username: &str,
+    installed: &skillpack_rs::SkillManifest,
+) -> Result<skillpack_rs::unpack::UnpackedSkill, String> {
+    let unpacked = skillpack_rs::verify_and_unpack(pack_path, username)
+        .map_err(|e| format!("解包 Skill 失败: {}", e))?;
+    if unpacked.manifest.id != installed.id
+        || unpacked.manifest.publisher_key_id != installed.publisher_key_id
+    {
+        return Err(format!(
+            "技能包 {} 的发布者与安装时不一致，请重新安装",
+            installed.id
+        ));
+    }
+    Ok(unpacked)
+}
+
 pub fn resolve_workspace_skill_runtime_entry(
     skill_id: &str,
     manifest_json: &str,
@@ -277,8 +297,7 @@ pub fn resolve_workspace_skill_runtime_entry(
                 Some(markdown),
             )
         } else if source_policy.kind == SkillSourceKind::Skillpack {
-            let unpacked = skillpack_rs::verify_and_unpack(pack_path, username)
-                .map_err(|e| format!("解包 Skill 失败: {}", e))?;
+            let unpacked = unpack_installed_skillpack(pack_path, username, &manifest)?;
             let markdown = extract_skill_prompt_from_decrypted_files(&unpacked.files);
             (
                 normalize_workspace_skill_dir_name(skill_id),
@@ -419,13 +438,16 @@ pub async fn load_workspace_skill_runtime_entries_with_pool(
 
     let mut entries = Vec::new();
     for (skill_id, manifest_json, username, pack_path, source_type) in rows {
-        match resolve_workspace_skill_runtime_entry(
-            &skill_id,
-            &manifest_json,
-            &username,
-            &pack_path,
-            &source_type,
-        ) {
+        let resolved = crate::secret_store::reveal_secret(&username).and_then(|username| {
+            resolve_workspace_skill_runtime_entry(
+                &skill_id,
+                &manifest_json,
+                &username,
+                &pack_path,
+                &source_type,
+            )
+        });
+        match resolved {
             Ok(entry) => entries.push(entry),
             Err(err) => {
                 eprintln!(
@@ -468,18 +490,12 @@ pub fn load_skill_prompt(
     } else if source_policy.kind == SkillSourceKind::LegacyBuiltin {
         load_legacy_builtin_embedded_markdown(skill_id)
     } else if source_policy.kind == SkillSourceKind::Skillpack {
-        match skillpack_rs::verify_and_unpack(pack_path, username) {
+        let installed = serde_json::from_str::<skillpack_rs::SkillManifest>(manifest_json)
+            .map_err(|e| e.to_string())?;
+        match unpack_installed_skillpack(pack_path, username, &installed) {
             Ok(unpacked) => extract_skill_prompt_from_decrypted_files(&unpacked.files)
-                .unwrap_or_else(|| {
-                    serde_json::from_str::<skillpack_rs::SkillManifest>(manifest_json)
-                        .map(|m| m.description)
-                        .unwrap_or_default()
-                }),
-            Err(_) => {
-                let manifest: skillpack_rs::SkillManifest =
-                    serde_json::from_str(manifest_json).map_err(|e| e.to_string())?;
-                manifest.description
-            }
+                .unwrap_or(installed.description),
+            Err(_) => installed.description,
         }
     } else {
         return Err(format!(
@@ -592,6 +608,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -630,6 +647,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -657,6 +675,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -693,6 +712,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -738,6 +758,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
         let manifest_summary = SkillManifest {
             id: "feishu-pm-weekly-work-summary-local".to_string(),
@@ -750,6 +771,7 @@ mod workspace_skill_projection_tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let runtime_entry = resolve_workspace_skill_runtime_entry(
@@ -817,6 +839,7 @@ Use vendored assets.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -868,6 +891,7 @@ Prefer directory content.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -929,6 +953,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let entry = resolve_workspace_skill_runtime_entry(
@@ -982,6 +1007,9 @@ Run exec directly.
             username: "alice".to_string(),
             recommended_model: "gpt-4o".to_string(),
             output_path: output.to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
         })
         .unwrap();
 
@@ -1017,6 +1045,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let err = resolve_workspace_skill_runtime_entry(
@@ -1044,6 +1073,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         let err = load_skill_prompt(
@@ -1419,6 +1449,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
         let vendored_manifest = SkillManifest {
             id: "builtin-general".to_string(),
@@ -1431,6 +1462,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         sqlx::query(
@@ -1513,6 +1545,7 @@ Run exec directly.
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
 
         sqlx::query(
@@ -65,7 +65,19 @@ pub(crate) async fn append_session_run_event_with_pool(
             .await
             .map_err(|e| format!("写入 session run 启动投影失败: {e}"))?;
         }
-        SessionRunEvent::SkillRouteRecorded { .. } => {}
+        SessionRunEvent::SkillRouteRecorded { .. } | SessionRunEvent::ModelRouteRecorded { .. } => {
+        }
+        SessionRunEvent::StructuredOutputRecorded { run_id, output, .. } => {
+            sqlx::query(
+                "UPDATE session_runs SET structured_output_json = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(output.to_string())
+            .bind(&now)
+            .bind(&run_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("写入 session run 结构化输出失败: {e}"))?;
+        }
         SessionRunEvent::AssistantChunkAppended { run_id, chunk } => {
             sqlx::query(
                 "INSERT INTO session_runs (id, session_id, user_message_id, assistant_message_id, status, buffered_text, error_kind, error_message, created_at, updated_at)
@@ -277,6 +289,8 @@ fn event_type(event: &SessionRunEvent) -> &'static str {
         SessionRunEvent::TaskStatusChanged { .. } => "task_status_changed",
         SessionRunEvent::RunStarted { .. } => "run_started",
         SessionRunEvent::SkillRouteRecorded { .. } => "skill_route_recorded",
+        SessionRunEvent::ModelRouteRecorded { .. } => "model_route_recorded",
+        SessionRunEvent::StructuredOutputRecorded { .. } => "structured_output_recorded",
         SessionRunEvent::AssistantChunkAppended { .. } => "assistant_chunk_appended",
         SessionRunEvent::ToolStarted { .. } => "tool_started",
         SessionRunEvent::ToolCompleted { .. } => "tool_completed",
@@ -299,6 +313,8 @@ fn event_run_id(event: &SessionRunEvent) -> &str {
         | SessionRunEvent::TaskStatusChanged { run_id, .. }
         | SessionRunEvent::RunStarted { run_id, .. }
         | SessionRunEvent::SkillRouteRecorded { run_id, .. }
+        | SessionRunEvent::ModelRouteRecorded { run_id, .. }
+        | SessionRunEvent::StructuredOutputRecorded { run_id, .. }
         | SessionRunEvent::AssistantChunkAppended { run_id, .. }
         | SessionRunEvent::ToolStarted { run_id, .. }
         | SessionRunEvent::ToolCompleted { run_id, .. }
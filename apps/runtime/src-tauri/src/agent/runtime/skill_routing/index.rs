@@ -206,6 +206,7 @@ mod tests {
             config: SkillConfig {
                 name: Some(name.to_string()),
                 description: Some(description.to_string()),
+                version: None,
                 allowed_tools: allowed_tools_for_config,
                 denied_tools: None,
                 requires_toolsets: None,
@@ -217,6 +218,8 @@ mod tests {
                 denied_tool_categories: None,
                 model: None,
                 max_iterations,
+                budget: None,
+                output_schema: None,
                 argument_hint: None,
                 disable_model_invocation: invocation.disable_model_invocation,
                 user_invocable: invocation.user_invocable,
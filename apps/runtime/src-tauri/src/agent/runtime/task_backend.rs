@@ -1,5 +1,5 @@
-use crate::agent::AgentExecutor;
-use crate::agent::run_guard::parse_run_stop_reason;
+use crate::agent::{AgentExecutor, AgentTurnRequest};
+use crate::agent::run_guard::{parse_run_stop_reason, RunBudgetTracker};
 use crate::agent::runtime::attempt_runner::RouteExecutionOutcome;
 use crate::agent::runtime::events::ToolConfirmResponder;
 use crate::agent::runtime::kernel::execution_plan::{
@@ -357,6 +357,16 @@ async fn run_interactive_chat_backend(
         request.execution_context.tool_plan_record(),
     )
     .await?;
+    if let Some(route_decision) = request.turn_context.route_decision.as_ref() {
+        chat_io::append_model_route_recorded_with_pool(
+            request.db,
+            request.journal,
+            request.session_id,
+            request.run_id,
+            route_decision,
+        )
+        .await?;
+    }
 
     let mut turn_state = TurnStateSnapshot::default()
         .with_session_surface(request.execution_context.session_profile.surface)
@@ -407,16 +417,31 @@ async fn run_delegated_surface_backend(
     let streamed_text_for_callback = Arc::clone(&streamed_text);
     let callback = Arc::clone(&request.on_token);
 
+    let turn_request = AgentTurnRequest {
+        api_format,
+        base_url,
+        api_key,
+        model: model_name,
+        skill_system_prompt: &request.execution_context.system_prompt,
+        messages: request.turn_context.messages.clone(),
+        app_handle: request.app_handle.as_ref(),
+        session_id: Some(request.session_id),
+        allowed_tools: request.execution_context.allowed_tools(),
+        permission_mode: request.execution_context.permission_mode,
+        work_dir: request.execution_context.executor_work_dir.clone(),
+        max_iterations_override: request.execution_context.max_iterations,
+        resource_limits: request.execution_context.run_resource_limits,
+        structured_output: request.execution_context.structured_output.clone(),
+        route_node_timeout_secs: Some(request.execution_context.node_timeout_seconds),
+        route_retry_count: Some(request.execution_context.route_retry_count),
+        ..AgentTurnRequest::default()
+    };
     let route_execution = match request
         .agent_executor
         .execute_turn_with_transport_outcome(
             transport,
-            api_format,
-            base_url,
-            api_key,
-            model_name,
-            &request.execution_context.system_prompt,
-            request.turn_context.messages.clone(),
+            turn_request,
+            &RunBudgetTracker::start(),
             move |delta| {
                 if let StreamDelta::Text(token) = &delta {
                     if let Ok(mut buffer) = streamed_text_for_callback.lock() {
@@ -425,16 +450,6 @@ async fn run_delegated_surface_backend(
                 }
                 callback(delta);
             },
-            request.app_handle.as_ref(),
-            Some(request.session_id),
-            request.execution_context.allowed_tools(),
-            request.execution_context.permission_mode,
-            None,
-            request.execution_context.executor_work_dir.clone(),
-            request.execution_context.max_iterations,
-            None,
-            Some(request.execution_context.node_timeout_seconds),
-            Some(request.execution_context.route_retry_count),
         )
         .await
     {
@@ -5,7 +5,7 @@ use crate::agent::runtime::kernel::outcome_commit::{OutcomeCommitter, TerminalOu
 use crate::agent::runtime::kernel::turn_state::TurnStateSnapshot;
 use crate::agent::runtime::runtime_io::{
     append_partial_assistant_chunk_with_pool, append_run_failed_with_pool,
-    append_run_stopped_with_pool, finalize_run_success_with_pool,
+    append_run_stopped_with_pool, finalize_run_success_with_pool, RunSuccessOutput,
 };
 use crate::agent::runtime::task_execution::TaskExecutionOutcome;
 use crate::agent::runtime::task_lifecycle;
@@ -147,12 +147,14 @@ async fn finalize_delegated_success(
         context.journal,
         context.session_id,
         context.run_id,
-        output,
-        has_tool_calls,
-        content,
-        reasoning_text,
-        reasoning_duration_ms,
-        Some(turn_state),
+        RunSuccessOutput {
+            final_text: output,
+            has_tool_calls,
+            content,
+            reasoning_text,
+            reasoning_duration_ms,
+            turn_state: Some(turn_state),
+        },
     )
     .await?;
     task_lifecycle::finalize_after_terminal(
@@ -8,22 +8,36 @@ use crate::agent::permissions::{
 };
 use crate::agent::progress::{json_progress_signature, text_progress_signature};
 use crate::agent::registry::ToolRegistry;
-use crate::agent::run_guard::{encode_run_stop_reason, ProgressFingerprint, RunBudgetPolicy};
-use crate::agent::runtime::approval_gate::gate_tool_approval;
+use crate::agent::run_guard::{
+    encode_run_stop_reason, ProgressFingerprint, RunBudgetPolicy, RunStopReason,
+};
+use crate::agent::runtime::approval_gate::{
+    gate_tool_approval, is_unattended_session, resolve_approval_rule_evaluation,
+};
 use crate::agent::safety::classify_policy_blocked_tool_error;
 use crate::agent::types::{
-    AgentStateEvent, Tool, ToolCall, ToolCallEvent, ToolContext, ToolResult,
+    AgentStateEvent, Tool, ToolCall, ToolCallEvent, ToolCancellation, ToolContext, ToolExecution,
+    ToolProgressSink, ToolResult,
 };
+use crate::commands::skills::DbState;
+use crate::runtime_environment::runtime_paths_from_app;
 use crate::session_journal::{SessionRunEvent, SessionRunTaskContinuationSnapshot};
+use crate::workspace_checkpoints::{
+    capture_workspace_snapshot_blocking, checkpoint_store_root, is_workspace_mutating_tool,
+    record_workspace_checkpoint_with_pool, workspace_mutation_paths, WorkspaceCheckpointOrigin,
+    WorkspaceSnapshot,
+};
 use anyhow::{anyhow, Result};
+use futures_util::future::join_all;
 use runtime_executor_core::{
     extract_tool_call_parse_error, split_error_code_and_message, truncate_tool_output,
     update_tool_failure_streak, ToolFailureStreak, MAX_TOOL_OUTPUT_CHARS,
 };
 use serde_json::{json, Value};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 
 use super::effective_tool_set::{
     EffectiveToolPolicyInputSource, EffectiveToolSet, ToolFilterReason,
@@ -419,6 +433,21 @@ fn mcp_tool_matches_server(tool_name: &str, server_name: &str) -> bool {
         || tool_name.starts_with(&format!("mcp_{normalized_server}_"))
 }
 
+/// 审批规则读不到时无法确认是否命中 deny 规则，不能直接放行：能弹确认就改为确认，否则拒绝
+fn approval_rules_unavailable_decision(
+    decision: ToolPermissionDecision,
+    can_confirm: bool,
+    error: &str,
+) -> ToolPermissionDecision {
+    if !can_confirm {
+        return ToolPermissionDecision::deny(format!("无法校验审批规则，已拒绝此操作: {error}"));
+    }
+    if decision.is_allow() {
+        return ToolPermissionDecision::ask(format!("无法校验审批规则，需要确认: {error}"), None);
+    }
+    decision
+}
+
 async fn resolve_approval_outcome(
     ctx: &ToolDispatchContext<'_>,
     call: &ToolCall,
@@ -453,6 +482,7 @@ async fn emit_failed_completion(
             "tool-call-event",
             ToolCallEvent {
                 session_id: sid.to_string(),
+                call_id: Some(call.id.clone()),
                 tool_name: call.name.clone(),
                 tool_input: call.input.clone(),
                 tool_output: Some(message.clone()),
@@ -472,6 +502,7 @@ async fn emit_failed_completion(
                     input: call.input.clone(),
                     output: message.clone(),
                     is_error: true,
+                    checkpoint_id: None,
                 },
             )
             .await;
@@ -487,18 +518,17 @@ async fn emit_failed_completion(
 async fn emit_tool_completion(
     ctx: &ToolDispatchContext<'_>,
     call: &ToolCall,
-    node_id: &str,
-    skill_name: &str,
+    prepared: &PreparedToolCall,
     result: &str,
     is_error: bool,
-    is_skill_call: bool,
-    started_at: std::time::Instant,
+    checkpoint_id: Option<String>,
 ) {
     if let (Some(app), Some(sid)) = (ctx.app_handle, ctx.session_id) {
         let _ = app.emit(
             "tool-call-event",
             ToolCallEvent {
                 session_id: sid.to_string(),
+                call_id: Some(call.id.clone()),
                 tool_name: call.name.clone(),
                 tool_input: call.input.clone(),
                 tool_output: Some(result.to_string()),
@@ -522,13 +552,14 @@ async fn emit_tool_completion(
                     input: call.input.clone(),
                     output: result.to_string(),
                     is_error,
+                    checkpoint_id,
                 },
             )
             .await;
         }
 
-        if is_skill_call {
-            let duration_ms = started_at.elapsed().as_millis() as u64;
+        if prepared.is_skill_call {
+            let duration_ms = prepared.started_at.elapsed().as_millis() as u64;
             let parsed_error = if is_error {
                 Some(split_error_code_and_message(result))
             } else {
@@ -539,9 +570,9 @@ async fn emit_tool_completion(
                 build_skill_route_event(
                     sid,
                     ctx.route_run_id,
-                    node_id,
+                    &prepared.node_id,
                     None,
-                    skill_name,
+                    &prepared.skill_name,
                     1,
                     if is_error { "failed" } else { "completed" },
                     Some(duration_ms),
@@ -635,12 +666,200 @@ async fn guard_before_tool_call(
     Ok(())
 }
 
+struct PreparedToolCall {
+    node_id: String,
+    skill_name: String,
+    is_skill_call: bool,
+    started_at: std::time::Instant,
+}
+
+enum ToolCallPreparation {
+    Ready(PreparedToolCall),
+    Done(ToolDispatchOutcome),
+}
+
+/// 改动工作区的工具在执行前拍下的快照，执行后据此生成检查点
+struct PendingWorkspaceCheckpoint {
+    store_root: PathBuf,
+    before: WorkspaceSnapshot,
+}
+
+async fn begin_workspace_checkpoint(
+    ctx: &ToolDispatchContext<'_>,
+    call: &ToolCall,
+) -> Option<PendingWorkspaceCheckpoint> {
+    if !is_workspace_mutating_tool(&call.name) || ctx.persisted_run_id.is_none() {
+        return None;
+    }
+    let app = ctx.app_handle?;
+    let paths = workspace_mutation_paths(&call.name, &call.input, ctx.tool_ctx);
+    if paths.is_empty() {
+        return None;
+    }
+    let store_root = checkpoint_store_root(&runtime_paths_from_app(app).ok()?.root);
+    match capture_workspace_snapshot_blocking(store_root.clone(), paths).await {
+        Ok(before) => Some(PendingWorkspaceCheckpoint { store_root, before }),
+        Err(err) => {
+            eprintln!("[agent] 跳过工作区检查点 {}: {}", call.name, err);
+            None
+        }
+    }
+}
+
+async fn finish_workspace_checkpoint(
+    ctx: &ToolDispatchContext<'_>,
+    call: &ToolCall,
+    pending: Option<PendingWorkspaceCheckpoint>,
+) -> Option<String> {
+    let pending = pending?;
+    let (app, session_id, run_id) = (ctx.app_handle?, ctx.session_id?, ctx.persisted_run_id?);
+    let db_state = app.try_state::<DbState>()?;
+    let origin = WorkspaceCheckpointOrigin {
+        session_id,
+        run_id,
+        turn_index: ctx.iteration as i64,
+        call_id: &call.id,
+        tool_name: &call.name,
+        work_dir: ctx.tool_ctx.work_dir.as_deref(),
+    };
+    match record_workspace_checkpoint_with_pool(
+        &db_state.0,
+        &pending.store_root,
+        origin,
+        &pending.before,
+    )
+    .await
+    {
+        Ok(checkpoint_id) => checkpoint_id,
+        Err(err) => {
+            eprintln!("[agent] 记录工作区检查点失败 {}: {}", call.name, err);
+            None
+        }
+    }
+}
+
 pub(crate) async fn dispatch_tool_call(
     ctx: &ToolDispatchContext<'_>,
     state: &mut ToolDispatchState<'_>,
     call_index: usize,
     call: &ToolCall,
 ) -> Result<ToolDispatchOutcome> {
+    let prepared = match prepare_tool_call(ctx, state, call_index, call).await? {
+        ToolCallPreparation::Ready(prepared) => prepared,
+        ToolCallPreparation::Done(outcome) => return Ok(outcome),
+    };
+    let checkpoint = begin_workspace_checkpoint(ctx, call).await;
+    let (result, is_error) = execute_tool_call(ctx, call, prepared.is_skill_call).await;
+    let checkpoint_id = finish_workspace_checkpoint(ctx, call, checkpoint).await;
+    finish_tool_call(ctx, state, call, &prepared, result, is_error, checkpoint_id).await
+}
+
+/// 派发一轮模型返回的全部工具调用。连续的 `concurrency_safe` 工具会先逐个
+/// 通过守卫与审批，再并行执行；其余工具保持串行。
+pub(crate) async fn dispatch_tool_calls(
+    ctx: &ToolDispatchContext<'_>,
+    state: &mut ToolDispatchState<'_>,
+    calls: &[ToolCall],
+) -> Result<ToolDispatchOutcome> {
+    let mut call_index = 0;
+    while call_index < calls.len() {
+        let batch_len = concurrent_batch_len(ctx, &calls[call_index..]);
+        if batch_len <= 1 {
+            let outcome = dispatch_tool_call(ctx, state, call_index, &calls[call_index]).await?;
+            if matches!(outcome, ToolDispatchOutcome::Cancelled) {
+                return Ok(outcome);
+            }
+            call_index += 1;
+        } else {
+            let batch = &calls[call_index..call_index + batch_len];
+            let results_start = state.tool_results.len();
+            let mut ready = Vec::with_capacity(batch_len);
+            for (offset, call) in batch.iter().enumerate() {
+                match prepare_tool_call(ctx, state, call_index + offset, call).await? {
+                    ToolCallPreparation::Ready(prepared) => ready.push((call, prepared)),
+                    ToolCallPreparation::Done(ToolDispatchOutcome::Cancelled) => {
+                        return Ok(ToolDispatchOutcome::Cancelled);
+                    }
+                    ToolCallPreparation::Done(ToolDispatchOutcome::Continue) => {}
+                }
+            }
+            let checkpoints = join_all(
+                ready
+                    .iter()
+                    .map(|(call, _)| begin_workspace_checkpoint(ctx, call)),
+            )
+            .await;
+            let results = join_all(
+                ready
+                    .iter()
+                    .map(|(call, prepared)| execute_tool_call(ctx, call, prepared.is_skill_call)),
+            )
+            .await;
+            for (((call, prepared), (result, is_error)), checkpoint) in
+                ready.iter().zip(results).zip(checkpoints)
+            {
+                let checkpoint_id = finish_workspace_checkpoint(ctx, call, checkpoint).await;
+                finish_tool_call(ctx, state, call, prepared, result, is_error, checkpoint_id)
+                    .await?;
+            }
+            sort_results_by_call_order(&mut state.tool_results[results_start..], batch);
+            call_index += batch_len;
+        }
+
+        if state.repeated_failure_summary.is_some() {
+            break;
+        }
+    }
+    Ok(ToolDispatchOutcome::Continue)
+}
+
+/// 批次中被拒绝或超时的调用在准备阶段就写入了结果，这里按模型给出的调用顺序重排
+fn sort_results_by_call_order(results: &mut [ToolResult], calls: &[ToolCall]) {
+    results.sort_by_key(|result| {
+        calls
+            .iter()
+            .position(|call| call.id == result.tool_use_id)
+            .unwrap_or(calls.len())
+    });
+}
+
+fn concurrent_batch_len(ctx: &ToolDispatchContext<'_>, calls: &[ToolCall]) -> usize {
+    calls
+        .iter()
+        .take_while(|call| {
+            call.name != "skill"
+                && ctx
+                    .registry
+                    .get(&call.name)
+                    .is_some_and(|tool| tool.metadata().concurrency_safe)
+        })
+        .count()
+}
+
+fn tool_progress_sink(ctx: &ToolDispatchContext<'_>, call: &ToolCall) -> Option<ToolProgressSink> {
+    let (app, sid) = (ctx.app_handle?.clone(), ctx.session_id?.to_string());
+    let call = call.clone();
+    Some(Arc::new(move |partial: &str| {
+        let _ = app.emit(
+            "tool-call-event",
+            ToolCallEvent {
+                session_id: sid.clone(),
+                call_id: Some(call.id.clone()),
+                tool_name: call.name.clone(),
+                tool_input: call.input.clone(),
+                tool_output: Some(partial.to_string()),
+                status: "progress".to_string(),
+            },
+        );
+    }))
+}
+
+async fn prepare_tool_call(
+    ctx: &ToolDispatchContext<'_>,
+    state: &mut ToolDispatchState<'_>,
+    call_index: usize,
+    call: &ToolCall,
+) -> Result<ToolCallPreparation> {
     let skill_name = call
         .input
         .get("skill_name")
@@ -674,7 +893,7 @@ pub(crate) async fn dispatch_tool_call(
     if let Some(ref flag) = ctx.cancel_flag {
         if flag.load(Ordering::SeqCst) {
             eprintln!("[agent] 工具执行中被用户取消");
-            return Ok(ToolDispatchOutcome::Cancelled);
+            return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Cancelled));
         }
     }
 
@@ -707,6 +926,7 @@ pub(crate) async fn dispatch_tool_call(
             "tool-call-event",
             ToolCallEvent {
                 session_id: sid.to_string(),
+                call_id: Some(call.id.clone()),
                 tool_name: call.name.clone(),
                 tool_input: call.input.clone(),
                 tool_output: None,
@@ -730,7 +950,37 @@ pub(crate) async fn dispatch_tool_call(
         }
     }
 
-    let permission_decision = resolve_dispatch_permission_decision(ctx, call);
+    let mut permission_decision = resolve_dispatch_permission_decision(ctx, call);
+    if !permission_decision.is_deny() {
+        match resolve_approval_rule_evaluation(
+            ctx.app_handle,
+            ctx.session_id,
+            call,
+            ctx.tool_ctx.work_dir.as_deref(),
+        )
+        .await
+        {
+            // deny 规则对所有权限模式生效；allow 规则只免除确认，不放开被禁止的工具
+            Ok(Some(evaluation)) if evaluation.is_deny() => {
+                permission_decision = ToolPermissionDecision::deny(format!(
+                    "审批规则禁止此操作: {}",
+                    evaluation.explanation
+                ));
+            }
+            Ok(Some(evaluation)) => {
+                if evaluation.is_allow() && permission_decision.is_ask() {
+                    permission_decision = ToolPermissionDecision::allow();
+                }
+            }
+            Ok(None) => {}
+            Err(error) => {
+                let can_confirm = (ctx.app_handle.is_some() && ctx.session_id.is_some())
+                    || ctx.tool_confirm_tx.is_some();
+                permission_decision =
+                    approval_rules_unavailable_decision(permission_decision, can_confirm, &error);
+            }
+        }
+    }
     match permission_decision.action {
         ToolPermissionAction::Allow => {}
         ToolPermissionAction::Deny => {
@@ -743,7 +993,17 @@ pub(crate) async fn dispatch_tool_call(
                     .unwrap_or_else(|| format!("此 Skill 不允许使用工具: {}", call.name)),
             )
             .await;
-            return Ok(ToolDispatchOutcome::Continue);
+            return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Continue));
+        }
+        ToolPermissionAction::Ask if ctx.session_id.is_some_and(is_unattended_session) => {
+            emit_failed_completion(
+                ctx,
+                call,
+                state,
+                "无人值守运行无法人工审批，已拒绝此操作".to_string(),
+            )
+            .await;
+            return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Continue));
         }
         ToolPermissionAction::Ask => match resolve_approval_outcome(ctx, call).await? {
             ApprovalOutcome::TimedOut => {
@@ -751,28 +1011,41 @@ pub(crate) async fn dispatch_tool_call(
                     tool_use_id: call.id.clone(),
                     content: "工具确认超时，已取消此操作".to_string(),
                 });
-                return Ok(ToolDispatchOutcome::Continue);
+                return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Continue));
             }
             ApprovalOutcome::Failed(message) => {
                 emit_failed_completion(ctx, call, state, message).await;
-                return Ok(ToolDispatchOutcome::Continue);
+                return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Continue));
             }
             ApprovalOutcome::Allowed(decision) => {
                 if decision == crate::approval_bus::ApprovalDecision::Deny {
                     emit_failed_completion(ctx, call, state, "用户拒绝了此操作".to_string()).await;
-                    return Ok(ToolDispatchOutcome::Continue);
+                    return Ok(ToolCallPreparation::Done(ToolDispatchOutcome::Continue));
                 }
             }
         },
     }
 
+    Ok(ToolCallPreparation::Ready(PreparedToolCall {
+        node_id,
+        skill_name,
+        is_skill_call,
+        started_at,
+    }))
+}
+
+async fn execute_tool_call(
+    ctx: &ToolDispatchContext<'_>,
+    call: &ToolCall,
+    is_skill_call: bool,
+) -> (String, bool) {
     let max_attempts = if is_skill_call {
         ctx.route_retry_count + 1
     } else {
         1
     };
     let mut attempt = 0usize;
-    let (result, is_error) = loop {
+    loop {
         attempt += 1;
         let (result, is_error) = if let Some(parse_error) =
             extract_tool_call_parse_error(&call.input)
@@ -803,6 +1076,7 @@ pub(crate) async fn dispatch_tool_call(
                                     call,
                                     ctx.tool_ctx,
                                     ctx.cancel_flag.clone(),
+                                    tool_progress_sink(ctx, call),
                                     ctx.route_node_timeout_secs,
                                     is_skill_call,
                                 )
@@ -816,6 +1090,7 @@ pub(crate) async fn dispatch_tool_call(
                             call,
                             ctx.tool_ctx,
                             ctx.cancel_flag.clone(),
+                            tool_progress_sink(ctx, call),
                             ctx.route_node_timeout_secs,
                             is_skill_call,
                         )
@@ -843,21 +1118,21 @@ pub(crate) async fn dispatch_tool_call(
         if !is_error || attempt >= max_attempts {
             break (result, is_error);
         }
-    };
+    }
+}
 
+async fn finish_tool_call(
+    ctx: &ToolDispatchContext<'_>,
+    state: &mut ToolDispatchState<'_>,
+    call: &ToolCall,
+    prepared: &PreparedToolCall,
+    result: String,
+    is_error: bool,
+    checkpoint_id: Option<String>,
+) -> Result<ToolDispatchOutcome> {
     let result = truncate_tool_output(&result, MAX_TOOL_OUTPUT_CHARS);
 
-    emit_tool_completion(
-        ctx,
-        call,
-        &node_id,
-        &skill_name,
-        &result,
-        is_error,
-        is_skill_call,
-        started_at,
-    )
-    .await;
+    emit_tool_completion(ctx, call, prepared, &result, is_error, checkpoint_id).await;
 
     if is_error {
         if let Some(mut stop_reason) = classify_policy_blocked_tool_error(&call.name, &result) {
@@ -888,14 +1163,14 @@ async fn run_tool(
     call: &ToolCall,
     tool_ctx: &ToolContext,
     cancel_flag: Option<Arc<AtomicBool>>,
+    progress: Option<ToolProgressSink>,
     route_node_timeout_secs: u64,
     is_skill_call: bool,
 ) -> (String, bool) {
-    let tool_clone = Arc::clone(&tool);
-    let input_clone = call.input.clone();
-    let ctx_clone = tool_ctx.clone();
+    let cancellation = ToolCancellation::new();
+    let execution = ToolExecution::new(cancellation.clone(), progress);
     let mut handle =
-        tokio::task::spawn_blocking(move || tool_clone.execute(input_clone, &ctx_clone));
+        tokio::spawn(tool.execute_async(call.input.clone(), tool_ctx.clone(), execution));
 
     let outcome = if is_skill_call {
         tokio::select! {
@@ -915,10 +1190,14 @@ async fn run_tool(
     match outcome {
         BlockingToolOutcome::Completed(result) => result,
         BlockingToolOutcome::Cancelled => {
+            cancellation.cancel(RunStopReason::cancelled());
             let _ = handle.await;
             ("工具执行被用户取消".to_string(), true)
         }
         BlockingToolOutcome::TimedOut => {
+            cancellation.cancel(RunStopReason::timeout(format!(
+                "子 Skill 执行超过 {route_node_timeout_secs} 秒"
+            )));
             let _ = handle.await;
             ("TIMEOUT: 子 Skill 执行超时".to_string(), true)
         }
@@ -952,10 +1231,11 @@ async fn wait_for_cancel(cancel_flag: &Option<Arc<AtomicBool>>) {
 #[cfg(test)]
 mod tests {
     use super::{
-        dispatch_skill_command, dispatch_tool_call, resolve_dispatch_permission_decision, run_tool,
-        ToolDispatchContext, ToolDispatchOutcome, ToolDispatchState,
+        approval_rules_unavailable_decision, dispatch_skill_command, dispatch_tool_call,
+        dispatch_tool_calls, resolve_dispatch_permission_decision, run_tool, ToolDispatchContext,
+        ToolDispatchOutcome, ToolDispatchState,
     };
-    use crate::agent::permissions::{PermissionMode, ToolPermissionAction};
+    use crate::agent::permissions::{PermissionMode, ToolPermissionAction, ToolPermissionDecision};
     use crate::agent::registry::ToolRegistry;
     use crate::agent::run_guard::{parse_run_stop_reason, RunStopReasonKind};
     use crate::agent::runtime::effective_tool_set::{
@@ -964,7 +1244,12 @@ mod tests {
         EffectiveToolSourceCount, ToolFilterReason,
     };
     use crate::agent::runtime::runtime_io::WorkspaceSkillCommandSpec;
+    use crate::agent::tool_manifest::ToolMetadata;
     use crate::agent::types::{Tool, ToolCall, ToolContext};
+    use crate::commands::skills::DbState;
+    use crate::runtime_bootstrap::{default_runtime_root_bootstrap, RuntimeBootstrapLocation};
+    use crate::runtime_environment::{ManagedRuntimeEnvironment, RuntimeEnvironment};
+    use crate::runtime_paths::RuntimePaths;
     use anyhow::Result;
     use runtime_skill_core::{
         SkillCommandArgMode, SkillCommandDispatchKind, SkillCommandDispatchSpec,
@@ -975,6 +1260,7 @@ mod tests {
     use std::sync::mpsc::Sender;
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
+    use tauri::test::{mock_context, noop_assets};
     use tempfile::TempDir;
 
     struct BlockingTool {
@@ -988,6 +1274,38 @@ mod tests {
 
     struct EchoCommandTool;
 
+    /// 可并行的写文件工具，用来验证批量执行时的工作区检查点
+    struct ParallelWriteTool {
+        name: &'static str,
+    }
+
+    /// Waits briefly for a sibling call to start; reports whether it saw one.
+    struct RendezvousTool {
+        name: &'static str,
+        arrived: Arc<AtomicUsize>,
+    }
+
+    /// 工具派发会先读审批规则；读不到时没有确认通道的调用会被拒绝
+    async fn register_empty_approval_rules() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite");
+        sqlx::query(
+            "CREATE TABLE approval_rules (
+                id TEXT PRIMARY KEY, tool_name TEXT, fingerprint TEXT, source_approval_id TEXT,
+                created_by_surface TEXT, created_by_user TEXT, enabled INTEGER, effect TEXT,
+                match_kind TEXT, pattern TEXT, scope_kind TEXT, scope_value TEXT,
+                expires_at TEXT, note TEXT, created_at TEXT, updated_at TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .expect("create approval_rules");
+        crate::approval_rules::register_approval_rule_pool(pool);
+    }
+
     fn create_skill(root: &TempDir, name: &str, skill_md: &str) {
         let skill_dir = root.path().join(name);
         std::fs::create_dir_all(&skill_dir).expect("create skill dir");
@@ -1058,6 +1376,66 @@ mod tests {
         }
     }
 
+    impl Tool for ParallelWriteTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "writes a file and may run alongside siblings"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({})
+        }
+
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                concurrency_safe: true,
+                ..ToolMetadata::default()
+            }
+        }
+
+        fn execute(&self, input: Value, ctx: &ToolContext) -> Result<String> {
+            let path = ctx.check_path(input["path"].as_str().unwrap_or_default())?;
+            std::fs::write(path, input["content"].as_str().unwrap_or_default())?;
+            Ok("written".to_string())
+        }
+    }
+
+    impl Tool for RendezvousTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "waits for a concurrent sibling"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({})
+        }
+
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                read_only: true,
+                concurrency_safe: true,
+                ..ToolMetadata::default()
+            }
+        }
+
+        fn execute(&self, _input: Value, _ctx: &ToolContext) -> Result<String> {
+            self.arrived.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..100 {
+                if self.arrived.load(Ordering::SeqCst) >= 2 {
+                    return Ok("parallel".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok("sequential".to_string())
+        }
+    }
+
     async fn wait_for_started_flag(started: &AtomicBool) {
         for _ in 0..50 {
             if started.load(Ordering::SeqCst) {
@@ -1108,6 +1486,7 @@ mod tests {
             &call,
             &tool_ctx,
             Some(Arc::clone(&cancel_flag)),
+            None,
             0,
             false,
         ));
@@ -1148,7 +1527,7 @@ mod tests {
             input: json!({}),
         };
         let tool_ctx = ToolContext::default();
-        let mut run = Box::pin(run_tool(tool, &call, &tool_ctx, None, 0, true));
+        let mut run = Box::pin(run_tool(tool, &call, &tool_ctx, None, None, 0, true));
 
         tokio::select! {
             _ = &mut run => panic!("run_tool completed before the blocking task could be observed"),
@@ -1173,6 +1552,7 @@ mod tests {
 
     #[tokio::test(flavor = "current_thread")]
     async fn dispatch_tool_call_stops_repeated_identical_calls_before_executing_sixth_attempt() {
+        register_empty_approval_rules().await;
         let count = Arc::new(AtomicUsize::new(0));
         let registry = ToolRegistry::new();
         registry.register(Arc::new(CountingTool {
@@ -1253,6 +1633,290 @@ mod tests {
         assert_eq!(count.load(Ordering::SeqCst), 5);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn dispatch_tool_calls_runs_concurrency_safe_tools_in_parallel() {
+        register_empty_approval_rules().await;
+        let arrived = Arc::new(AtomicUsize::new(0));
+        let registry = ToolRegistry::new();
+        for name in ["rendezvous_a", "rendezvous_b"] {
+            registry.register(Arc::new(RendezvousTool {
+                name,
+                arrived: Arc::clone(&arrived),
+            }));
+        }
+        let calls = ["rendezvous_a", "rendezvous_b"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ToolCall {
+                id: format!("call-{index}"),
+                name: name.to_string(),
+                input: json!({ "index": index }),
+            })
+            .collect::<Vec<_>>();
+        let tool_ctx = ToolContext::default();
+        let mut tool_results = Vec::new();
+        let mut repeated_failure_summary = None;
+        let mut tool_failure_streak = None;
+        let mut tool_call_history = Vec::new();
+        let mut tool_result_history = Vec::new();
+        let mut latest_browser_progress = None;
+        let dispatch_context = ToolDispatchContext {
+            registry: &registry,
+            app_handle: None,
+            session_id: None,
+            persisted_run_id: None,
+            active_task_identity: None,
+            active_task_kind: None,
+            active_task_surface: None,
+            active_task_backend: None,
+            active_task_continuation_mode: None,
+            active_task_continuation_source: None,
+            active_task_continuation_reason: None,
+            allowed_tools: None,
+            effective_tool_plan: None,
+            permission_mode: PermissionMode::Unrestricted,
+            tool_ctx: &tool_ctx,
+            tool_confirm_tx: None,
+            cancel_flag: None,
+            route_run_id: "route-parallel",
+            route_node_timeout_secs: 5,
+            route_retry_count: 0,
+            iteration: 1,
+            run_budget_policy: crate::agent::run_guard::RunBudgetPolicy::for_scope(
+                crate::agent::run_guard::RunBudgetScope::GeneralChat,
+            ),
+        };
+        let mut dispatch_state = ToolDispatchState {
+            tool_results: &mut tool_results,
+            repeated_failure_summary: &mut repeated_failure_summary,
+            tool_failure_streak: &mut tool_failure_streak,
+            tool_call_history: &mut tool_call_history,
+            tool_result_history: &mut tool_result_history,
+            latest_browser_progress: &mut latest_browser_progress,
+        };
+
+        let outcome = dispatch_tool_calls(&dispatch_context, &mut dispatch_state, &calls)
+            .await
+            .expect("dispatch batch");
+
+        assert!(matches!(outcome, ToolDispatchOutcome::Continue));
+        let ids = tool_results
+            .iter()
+            .map(|result| result.tool_use_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["call-0", "call-1"]);
+        assert!(tool_results
+            .iter()
+            .all(|result| result.content == "parallel"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn dispatch_tool_calls_records_checkpoints_for_concurrent_workspace_writes() {
+        register_empty_approval_rules().await;
+        let runtime_root = TempDir::new().expect("runtime root");
+        let work_dir = TempDir::new().expect("work dir");
+        let runtime_paths = RuntimePaths::new(runtime_root.path().to_path_buf());
+        let pool = crate::db::init_db_at_runtime_paths(&runtime_paths)
+            .await
+            .expect("init db");
+        let app = tauri::Builder::default()
+            .manage(ManagedRuntimeEnvironment(Arc::new(RuntimeEnvironment {
+                bootstrap_location: RuntimeBootstrapLocation {
+                    bootstrap_dir: runtime_root.path().join("bootstrap"),
+                    bootstrap_path: runtime_root
+                        .path()
+                        .join("bootstrap")
+                        .join("runtime-root.json"),
+                },
+                bootstrap: default_runtime_root_bootstrap(runtime_root.path()),
+                paths: runtime_paths,
+            })))
+            .manage(DbState(pool.clone()))
+            .build(mock_context(noop_assets()))
+            .expect("build test app");
+        let app_handle = app.handle().clone();
+
+        let registry = ToolRegistry::new();
+        for name in ["write_file", "edit"] {
+            registry.register(Arc::new(ParallelWriteTool { name }));
+        }
+        let calls = ["write_file", "edit"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ToolCall {
+                id: format!("call-{index}"),
+                name: name.to_string(),
+                input: json!({ "path": format!("note-{index}.txt"), "content": "hello" }),
+            })
+            .collect::<Vec<_>>();
+        let tool_ctx = ToolContext {
+            work_dir: Some(work_dir.path().to_path_buf()),
+            ..ToolContext::default()
+        };
+        let mut tool_results = Vec::new();
+        let mut repeated_failure_summary = None;
+        let mut tool_failure_streak = None;
+        let mut tool_call_history = Vec::new();
+        let mut tool_result_history = Vec::new();
+        let mut latest_browser_progress = None;
+        let dispatch_context = ToolDispatchContext {
+            registry: &registry,
+            app_handle: Some(&app_handle),
+            session_id: Some("session-checkpoint"),
+            persisted_run_id: Some("run-checkpoint"),
+            active_task_identity: None,
+            active_task_kind: None,
+            active_task_surface: None,
+            active_task_backend: None,
+            active_task_continuation_mode: None,
+            active_task_continuation_source: None,
+            active_task_continuation_reason: None,
+            allowed_tools: None,
+            effective_tool_plan: None,
+            permission_mode: PermissionMode::Unrestricted,
+            tool_ctx: &tool_ctx,
+            tool_confirm_tx: None,
+            cancel_flag: None,
+            route_run_id: "route-checkpoint",
+            route_node_timeout_secs: 5,
+            route_retry_count: 0,
+            iteration: 1,
+            run_budget_policy: crate::agent::run_guard::RunBudgetPolicy::for_scope(
+                crate::agent::run_guard::RunBudgetScope::GeneralChat,
+            ),
+        };
+        let mut dispatch_state = ToolDispatchState {
+            tool_results: &mut tool_results,
+            repeated_failure_summary: &mut repeated_failure_summary,
+            tool_failure_streak: &mut tool_failure_streak,
+            tool_call_history: &mut tool_call_history,
+            tool_result_history: &mut tool_result_history,
+            latest_browser_progress: &mut latest_browser_progress,
+        };
+
+        dispatch_tool_calls(&dispatch_context, &mut dispatch_state, &calls)
+            .await
+            .expect("dispatch batch");
+
+        assert!(tool_results
+            .iter()
+            .all(|result| result.content == "written"));
+        let checkpoints: Vec<(String, String)> = sqlx::query_as(
+            "SELECT call_id, tool_name FROM workspace_checkpoints
+             WHERE run_id = 'run-checkpoint'
+             ORDER BY call_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("load checkpoints");
+        assert_eq!(
+            checkpoints,
+            vec![
+                ("call-0".to_string(), "write_file".to_string()),
+                ("call-1".to_string(), "edit".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn dispatch_tool_calls_keeps_results_in_call_order_when_batch_call_is_denied() {
+        register_empty_approval_rules().await;
+        let arrived = Arc::new(AtomicUsize::new(0));
+        let registry = ToolRegistry::new();
+        for name in ["rendezvous_a", "rendezvous_b", "rendezvous_c"] {
+            registry.register(Arc::new(RendezvousTool {
+                name,
+                arrived: Arc::clone(&arrived),
+            }));
+        }
+        let calls = ["rendezvous_a", "rendezvous_c", "rendezvous_b"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ToolCall {
+                id: format!("call-{index}"),
+                name: name.to_string(),
+                input: json!({ "index": index }),
+            })
+            .collect::<Vec<_>>();
+        let allowed_tools = ["rendezvous_a".to_string(), "rendezvous_b".to_string()];
+        let tool_ctx = ToolContext::default();
+        let mut tool_results = Vec::new();
+        let mut repeated_failure_summary = None;
+        let mut tool_failure_streak = None;
+        let mut tool_call_history = Vec::new();
+        let mut tool_result_history = Vec::new();
+        let mut latest_browser_progress = None;
+        let dispatch_context = ToolDispatchContext {
+            registry: &registry,
+            app_handle: None,
+            session_id: None,
+            persisted_run_id: None,
+            active_task_identity: None,
+            active_task_kind: None,
+            active_task_surface: None,
+            active_task_backend: None,
+            active_task_continuation_mode: None,
+            active_task_continuation_source: None,
+            active_task_continuation_reason: None,
+            allowed_tools: Some(&allowed_tools),
+            effective_tool_plan: None,
+            permission_mode: PermissionMode::Unrestricted,
+            tool_ctx: &tool_ctx,
+            tool_confirm_tx: None,
+            cancel_flag: None,
+            route_run_id: "route-parallel-order",
+            route_node_timeout_secs: 5,
+            route_retry_count: 0,
+            iteration: 1,
+            run_budget_policy: crate::agent::run_guard::RunBudgetPolicy::for_scope(
+                crate::agent::run_guard::RunBudgetScope::GeneralChat,
+            ),
+        };
+        let mut dispatch_state = ToolDispatchState {
+            tool_results: &mut tool_results,
+            repeated_failure_summary: &mut repeated_failure_summary,
+            tool_failure_streak: &mut tool_failure_streak,
+            tool_call_history: &mut tool_call_history,
+            tool_result_history: &mut tool_result_history,
+            latest_browser_progress: &mut latest_browser_progress,
+        };
+
+        dispatch_tool_calls(&dispatch_context, &mut dispatch_state, &calls)
+            .await
+            .expect("dispatch batch");
+
+        let ids = tool_results
+            .iter()
+            .map(|result| result.tool_use_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["call-0", "call-1", "call-2"]);
+        assert_eq!(tool_results[0].content, "parallel");
+        assert_ne!(tool_results[1].content, "parallel");
+        assert_eq!(tool_results[2].content, "parallel");
+    }
+
+    #[test]
+    fn unavailable_approval_rules_never_allow_without_confirmation() {
+        let asked =
+            approval_rules_unavailable_decision(ToolPermissionDecision::allow(), true, "db");
+        assert_eq!(asked.action, ToolPermissionAction::Ask);
+
+        let denied =
+            approval_rules_unavailable_decision(ToolPermissionDecision::allow(), false, "db");
+        assert_eq!(denied.action, ToolPermissionAction::Deny);
+        assert!(denied
+            .reason
+            .unwrap_or_default()
+            .contains("无法校验审批规则"));
+
+        let still_asked = approval_rules_unavailable_decision(
+            ToolPermissionDecision::ask("需要确认", None),
+            true,
+            "db",
+        );
+        assert_eq!(still_asked.reason.as_deref(), Some("需要确认"));
+    }
+
     #[test]
     fn resolve_dispatch_permission_decision_denies_disallowed_tool_before_execution() {
         let registry = ToolRegistry::new();
@@ -1396,6 +2060,7 @@ mod tests {
 
     #[tokio::test(flavor = "current_thread")]
     async fn dispatch_tool_call_rejected_approval_does_not_execute_file_delete() {
+        register_empty_approval_rules().await;
         let registry = ToolRegistry::with_standard_tools();
         let workspace = TempDir::new().expect("workspace temp dir");
         let target_dir = workspace.path().join("keep-me");
@@ -1473,6 +2138,7 @@ mod tests {
 
     #[tokio::test(flavor = "current_thread")]
     async fn dispatch_skill_command_routes_raw_args_to_allowed_tool() {
+        register_empty_approval_rules().await;
         let registry = ToolRegistry::new();
         registry.register(Arc::new(EchoCommandTool));
         let tool_ctx = ToolContext::default();
@@ -1525,6 +2191,7 @@ mod tests {
 
     #[tokio::test(flavor = "current_thread")]
     async fn dispatch_skill_command_rejects_blocked_target_tool() {
+        register_empty_approval_rules().await;
         let registry = ToolRegistry::new();
         registry.register(Arc::new(EchoCommandTool));
         let tool_ctx = ToolContext::default();
@@ -1577,6 +2244,7 @@ mod tests {
 
     #[tokio::test(flavor = "current_thread")]
     async fn dispatch_tool_call_bridges_skill_dispatchable_resolution_into_exec_tool() {
+        register_empty_approval_rules().await;
         let skills_root = TempDir::new().expect("temp dir");
         create_skill(
             &skills_root,
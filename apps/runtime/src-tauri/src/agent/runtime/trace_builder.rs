@@ -364,6 +364,61 @@ fn summarize_event(
             is_error: Some(false),
             parse_warning: None,
         },
+        SessionRunEvent::ModelRouteRecorded {
+            candidate_count,
+            skipped_count,
+            selected_provider,
+            selected_model,
+            decision_reason,
+            ..
+        } => SessionRunEventSummary {
+            session_id: record.session_id.clone(),
+            run_id: record.run_id.clone(),
+            event_type: record.event_type.clone(),
+            created_at: record.created_at.clone(),
+            status: Some(selected_model),
+            tool_name: None,
+            call_id: None,
+            approval_id: None,
+            warning_kind: Some(decision_reason),
+            error_kind: None,
+            message: Some(selected_provider),
+            detail: Some(format!(
+                "candidate_count={candidate_count}, skipped_count={skipped_count}"
+            )),
+            irreversible: None,
+            last_completed_step: None,
+            child_session_id: None,
+            is_error: Some(false),
+            parse_warning: None,
+        },
+        SessionRunEvent::StructuredOutputRecorded {
+            schema_name,
+            output,
+            repair_turns,
+            ..
+        } => SessionRunEventSummary {
+            session_id: record.session_id.clone(),
+            run_id: record.run_id.clone(),
+            event_type: record.event_type.clone(),
+            created_at: record.created_at.clone(),
+            status: Some("validated".to_string()),
+            tool_name: None,
+            call_id: None,
+            approval_id: None,
+            warning_kind: None,
+            error_kind: None,
+            message: Some(schema_name),
+            detail: Some(format!(
+                "repair_turns={repair_turns}, output={}",
+                truncate_text(&output.to_string(), 160)
+            )),
+            irreversible: None,
+            last_completed_step: None,
+            child_session_id: None,
+            is_error: Some(false),
+            parse_warning: None,
+        },
         SessionRunEvent::AssistantChunkAppended { chunk, .. } => SessionRunEventSummary {
             session_id: record.session_id.clone(),
             run_id: record.run_id.clone(),
@@ -1097,6 +1152,7 @@ mod tests {
                         input: json!({ "path": "README.md" }),
                         output: "README loaded".to_string(),
                         is_error: false,
+                        checkpoint_id: None,
                     },
                 ),
                 stored_event(
@@ -5,7 +5,7 @@ const SYNTHETIC_TOOL_RESULT_TEXT: &str = "[已执行]";
 
 pub(crate) fn repair_outbound_messages(messages: Vec<Value>, api_format: &str) -> Vec<Value> {
     match api_format {
-        "openai" => repair_openai_outbound_messages(messages),
+        "openai" | "gemini" | "ollama" => repair_openai_outbound_messages(messages),
         "anthropic" => repair_anthropic_outbound_messages(messages),
         _ => messages,
     }
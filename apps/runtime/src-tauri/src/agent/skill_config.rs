@@ -1,5 +1,5 @@
 pub use runtime_skill_core::{
     McpServerDep, OpenClawSkillMetadata, OpenClawSkillMetadataRequires,
     SkillCommandArgMode, SkillCommandDispatchKind, SkillCommandDispatchSpec, SkillConfig,
-    SkillInvocationPolicy,
+    SkillInvocationPolicy, SkillRunBudget,
 };
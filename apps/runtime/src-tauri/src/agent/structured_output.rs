@@ -0,0 +1,420 @@
+use serde_json::{Map, Value};
+
+/// Anthropic 通过强制调用该工具产出结构化结果
+pub const STRUCTURED_OUTPUT_TOOL_NAME: &str = "submit_structured_output";
+/// 校验失败后最多追加的修复轮数
+pub const MAX_STRUCTURED_OUTPUT_REPAIR_TURNS: usize = 2;
+
+const MAX_SCHEMA_NAME_LEN: usize = 64;
+const MAX_REPORTED_ERRORS: usize = 8;
+
+/// Skill 声明的最终答案 JSON Schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredOutputSpec {
+    /// 传给 OpenAI `json_schema.name` 的名称，仅含字母、数字、`_`、`-`
+    pub name: String,
+    pub schema: Value,
+}
+
+impl StructuredOutputSpec {
+    pub fn from_schema(skill_name: &str, schema: Option<&Value>) -> Option<Self> {
+        let schema = schema.filter(|schema| schema.is_object())?.clone();
+        let mut name = skill_name
+            .trim()
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_SCHEMA_NAME_LEN)
+            .collect::<String>();
+        if name.trim_matches('_').is_empty() {
+            name = "skill_output".to_string();
+        }
+        Some(Self { name, schema })
+    }
+
+    /// OpenAI strict 模式要求每个 object 都关闭额外字段并把全部属性列为必填
+    pub fn is_strict_compatible(&self) -> bool {
+        schema_is_strict_compatible(&self.schema)
+    }
+
+    /// 追加到系统 prompt 末尾，保持前缀稳定以命中 prompt cache
+    pub fn instructions(&self) -> String {
+        let schema = serde_json::to_string_pretty(&self.schema).unwrap_or_default();
+        format!(
+            "## 结构化输出\n任务完成后，最终回答必须是且仅是一个满足以下 JSON Schema 的 JSON 值，不要添加解释文字或 Markdown 代码块：\n```json\n{schema}\n```"
+        )
+    }
+
+    /// 从最终回答中解析 JSON 并按 schema 校验
+    pub fn validate_answer(&self, answer: &str) -> Result<Value, Vec<String>> {
+        let candidate = extract_json_candidate(answer);
+        let value = serde_json::from_str::<Value>(candidate)
+            .map_err(|e| vec![format!("最终回答不是合法 JSON: {e}")])?;
+        let mut errors = Vec::new();
+        validate_value(&value, &self.schema, "$", &mut errors);
+        if errors.is_empty() {
+            Ok(value)
+        } else {
+            errors.truncate(MAX_REPORTED_ERRORS);
+            Err(errors)
+        }
+    }
+}
+
+/// 校验失败时发给模型的修复提示
+pub fn structured_output_repair_prompt(errors: &[String]) -> String {
+    let details = errors
+        .iter()
+        .map(|error| format!("- {error}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "上一条最终回答未通过结构化输出校验：\n{details}\n请修正后只输出满足 JSON Schema 的 JSON，不要添加其他文字。"
+    )
+}
+
+fn extract_json_candidate(answer: &str) -> &str {
+    let trimmed = answer.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = rest
+        .split_once('\n')
+        .map(|(_, body)| body)
+        .unwrap_or_default();
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+fn schema_is_strict_compatible(schema: &Value) -> bool {
+    let Some(object) = schema.as_object() else {
+        return true;
+    };
+    if let Some(properties) = object.get("properties").and_then(Value::as_object) {
+        if object.get("additionalProperties") != Some(&Value::Bool(false)) {
+            return false;
+        }
+        let required = object
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        if properties
+            .keys()
+            .any(|key| !required.contains(&key.as_str()))
+        {
+            return false;
+        }
+        if !properties.values().all(schema_is_strict_compatible) {
+            return false;
+        }
+    }
+    ["items", "anyOf", "oneOf", "allOf"]
+        .iter()
+        .filter_map(|key| object.get(*key))
+        .all(|nested| match nested {
+            Value::Array(items) => items.iter().all(schema_is_strict_compatible),
+            other => schema_is_strict_compatible(other),
+        })
+}
+
+/// 支持常用的 JSON Schema 子集：type / enum / const / required / properties /
+/// additionalProperties / items / 长度与数值范围 / anyOf / oneOf / allOf
+fn validate_value(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            Value::String(kind) => type_matches(value, kind),
+            Value::Array(kinds) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|kind| type_matches(value, kind)),
+            _ => true,
+        };
+        if !matches {
+            errors.push(format!(
+                "{path}: 类型应为 {expected}，实际为 {}",
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!(
+                "{path}: 取值必须是 {}",
+                Value::Array(options.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{path}: 取值必须是 {expected}"));
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(object, schema, path, errors),
+        Value::Array(items) => {
+            check_bound(schema, "minItems", items.len(), path, "元素数", errors);
+            check_bound(schema, "maxItems", items.len(), path, "元素数", errors);
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item, item_schema, &format!("{path}[{index}]"), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count();
+            check_bound(schema, "minLength", len, path, "长度", errors);
+            check_bound(schema, "maxLength", len, path, "长度", errors);
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < minimum {
+                    errors.push(format!("{path}: 不能小于 {minimum}"));
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > maximum {
+                    errors.push(format!("{path}: 不能大于 {maximum}"));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(variants) = schema.get("allOf").and_then(Value::as_array) {
+        for variant in variants {
+            validate_value(value, variant, path, errors);
+        }
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        let Some(variants) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let matched = variants
+            .iter()
+            .filter(|variant| {
+                let mut variant_errors = Vec::new();
+                validate_value(value, variant, path, &mut variant_errors);
+                variant_errors.is_empty()
+            })
+            .count();
+        if matched == 0 || (exactly_one && matched > 1) {
+            errors.push(format!("{path}: 不满足 {keyword} 中的候选 schema"));
+        }
+    }
+}
+
+fn validate_object(
+    object: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!("{path}: 缺少必填字段 {key}"));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, item) in object {
+        let item_path = format!("{path}.{key}");
+        match (
+            properties.and_then(|props| props.get(key)),
+            schema.get("additionalProperties"),
+        ) {
+            (Some(property_schema), _) => validate_value(item, property_schema, &item_path, errors),
+            (None, Some(Value::Bool(false))) => {
+                errors.push(format!("{item_path}: 不允许出现额外字段"))
+            }
+            (None, Some(extra_schema @ Value::Object(_))) => {
+                validate_value(item, extra_schema, &item_path, errors)
+            }
+            (None, _) => {}
+        }
+    }
+}
+
+fn check_bound(
+    schema: &Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    label: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(bound) = schema.get(keyword).and_then(Value::as_u64) else {
+        return;
+    };
+    let actual = actual as u64;
+    if keyword.starts_with("min") && actual < bound {
+        errors.push(format!("{path}: {label}不能少于 {bound}"));
+    } else if keyword.starts_with("max") && actual > bound {
+        errors.push(format!("{path}: {label}不能超过 {bound}"));
+    }
+}
+
+fn type_matches(value: &Value, kind: &str) -> bool {
+    match kind {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report_spec() -> StructuredOutputSpec {
+        StructuredOutputSpec::from_schema(
+            "日报 report",
+            Some(&json!({
+                "type": "object",
+                "required": ["employee", "count", "status"],
+                "additionalProperties": false,
+                "properties": {
+                    "employee": { "type": "string", "minLength": 1 },
+                    "count": { "type": "integer", "minimum": 0 },
+                    "status": { "enum": ["ok", "blocked"] },
+                    "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+                }
+            })),
+        )
+        .expect("spec")
+    }
+
+    #[test]
+    fn spec_name_is_sanitized_for_provider_schema_names() {
+        assert_eq!(report_spec().name, "___report");
+        let fallback = StructuredOutputSpec::from_schema("日报", Some(&json!({"type": "object"})))
+            .expect("spec");
+        assert_eq!(fallback.name, "skill_output");
+        assert!(StructuredOutputSpec::from_schema("x", Some(&json!("string"))).is_none());
+        assert!(StructuredOutputSpec::from_schema("x", None).is_none());
+    }
+
+    #[test]
+    fn validate_answer_accepts_fenced_json_and_reports_errors() {
+        let spec = report_spec();
+        let value = spec
+            .validate_answer("```json\n{\"employee\":\"张三\",\"count\":3,\"status\":\"ok\"}\n```")
+            .expect("valid answer");
+        assert_eq!(value["count"], 3);
+
+        let errors = spec
+            .validate_answer(
+                r#"{"employee":"","count":-1.5,"status":"done","tags":["a","b","c"],"extra":1}"#,
+            )
+            .expect_err("invalid answer");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.employee") && e.contains("长度")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.count") && e.contains("integer")));
+        assert!(errors.iter().any(|e| e.contains("$.status")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.tags") && e.contains("元素数")));
+        assert!(errors.iter().any(|e| e.contains("$.extra")));
+
+        let errors = spec.validate_answer("完成了").expect_err("not json");
+        assert!(errors[0].contains("不是合法 JSON"));
+        let errors = spec
+            .validate_answer(r#"{"employee":"a","count":1}"#)
+            .expect_err("missing field");
+        assert_eq!(errors, vec!["$: 缺少必填字段 status".to_string()]);
+    }
+
+    #[test]
+    fn any_of_and_one_of_require_matching_variants() {
+        let spec = StructuredOutputSpec::from_schema(
+            "union",
+            Some(&json!({
+                "type": "object",
+                "properties": {
+                    "value": { "anyOf": [{ "type": "string" }, { "type": "number" }] },
+                    "pick": { "oneOf": [{ "type": "integer" }, { "type": "number" }] }
+                }
+            })),
+        )
+        .expect("spec");
+        assert!(spec.validate_answer(r#"{"value":"x","pick":1.5}"#).is_ok());
+        let errors = spec
+            .validate_answer(r#"{"value":true,"pick":2}"#)
+            .expect_err("invalid");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.value") && e.contains("anyOf")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.pick") && e.contains("oneOf")));
+    }
+
+    #[test]
+    fn strict_compatibility_requires_closed_objects_with_all_fields_required() {
+        assert!(!report_spec().is_strict_compatible());
+        let strict = StructuredOutputSpec::from_schema(
+            "strict",
+            Some(&json!({
+                "type": "object",
+                "required": ["items"],
+                "additionalProperties": false,
+                "properties": {
+                    "items": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["id"],
+                            "additionalProperties": false,
+                            "properties": { "id": { "type": "string" } }
+                        }
+                    }
+                }
+            })),
+        )
+        .expect("spec");
+        assert!(strict.is_strict_compatible());
+    }
+
+    #[test]
+    fn repair_prompt_lists_validation_errors() {
+        let prompt = structured_output_repair_prompt(&["$: 缺少必填字段 status".to_string()]);
+        assert!(prompt.contains("- $: 缺少必填字段 status"));
+        assert!(report_spec().instructions().contains("\"employee\""));
+    }
+}
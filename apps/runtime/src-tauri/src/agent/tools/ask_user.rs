@@ -145,7 +145,10 @@ impl Tool for AskUserTool {
         // 阻塞等待用户响应（最多 5 分钟）
         let response = rx
             .recv_timeout(std::time::Duration::from_secs(300))
-            .map_err(|_| anyhow!("等待用户响应超时（5 分钟）"))?;
+            .map_err(|error| match error {
+                mpsc::RecvTimeoutError::Timeout => anyhow!("等待用户响应超时（5 分钟）"),
+                mpsc::RecvTimeoutError::Disconnected => anyhow!("提问已被取消，未获得用户回答"),
+            })?;
 
         // 清理 responder
         {
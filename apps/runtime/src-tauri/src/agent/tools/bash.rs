@@ -2,11 +2,12 @@ use crate::agent::tool_manifest::{ToolCategory, ToolMetadata, ToolSource};
 use crate::agent::tools::process_manager::ProcessManager;
 use crate::agent::tools::tool_result;
 use crate::agent::types::{Tool, ToolContext};
+use crate::shell_sandbox::{build_shell_command, ShellSandboxRequest};
 use crate::windows_process::hide_console_window;
 use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
 use std::io::Read;
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 use wait_timeout::ChildExt;
@@ -170,8 +171,14 @@ impl Tool for BashTool {
         let background = input["background"].as_bool().unwrap_or(false);
         if background {
             if let Some(ref pm) = self.process_manager {
-                let work_dir = ctx.work_dir.as_deref();
-                let handle = pm.spawn_handle(command, work_dir)?;
+                let (shell, flag) = Self::get_shell();
+                let handle = pm.spawn_sandboxed_handle(ShellSandboxRequest {
+                    shell,
+                    shell_args: &[flag],
+                    command,
+                    work_dir: ctx.work_dir.as_deref(),
+                    writable_paths: ctx.task_temp_dir.iter().cloned().collect(),
+                })?;
                 return tool_result::success(
                     self.name(),
                     format!("后台进程已启动，process_id: {}", handle.id),
@@ -181,6 +188,7 @@ impl Tool for BashTool {
                             "background": true,
                             "process_id": handle.id,
                             "output_file_path": handle.output_file_path.to_string_lossy().to_string(),
+                            "sandbox": handle.sandbox,
                         }),
                         ctx,
                     ),
@@ -197,20 +205,21 @@ impl Tool for BashTool {
 
         let (shell, flag) = Self::get_shell();
 
-        // 使用 spawn 启动子进程，以便后续进行超时控制
-        let mut cmd = Command::new(shell);
-        cmd.arg(flag)
-            .arg(command)
+        // 使用 spawn 启动子进程，以便后续进行超时控制；开启沙箱时由沙箱接管 cwd 与隔离
+        let mut sandboxed = build_shell_command(ShellSandboxRequest {
+            shell,
+            shell_args: &[flag],
+            command,
+            work_dir: ctx.work_dir.as_deref(),
+            writable_paths: ctx.task_temp_dir.iter().cloned().collect(),
+        })?;
+        sandboxed
+            .command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        // 如果 ToolContext 指定了工作目录，设置子进程的 cwd
-        if let Some(ref wd) = ctx.work_dir {
-            cmd.current_dir(wd);
-        }
-
-        hide_console_window(&mut cmd);
-        let mut child = cmd.spawn()?;
+        hide_console_window(&mut sandboxed.command);
+        let mut child = sandboxed.spawn()?;
 
         // 等待子进程完成或超时
         match child.wait_timeout(timeout)? {
@@ -237,6 +246,7 @@ impl Tool for BashTool {
                                 "exit_code": status.code().unwrap_or(-1),
                                 "timed_out": false,
                                 "background": false,
+                                "sandbox": sandboxed.details.clone(),
                                 "stdout": stdout_str,
                                 "stderr": stderr_str,
                             }),
@@ -253,6 +263,7 @@ impl Tool for BashTool {
                                 "exit_code": status.code().unwrap_or(0),
                                 "timed_out": false,
                                 "background": false,
+                                "sandbox": sandboxed.details.clone(),
                                 "stdout": stdout_str,
                                 "stderr": stderr_str,
                             }),
@@ -276,6 +287,7 @@ impl Tool for BashTool {
                             "exit_code": Value::Null,
                             "timed_out": true,
                             "background": false,
+                            "sandbox": sandboxed.details.clone(),
                             "stdout": "",
                             "stderr": "",
                         }),
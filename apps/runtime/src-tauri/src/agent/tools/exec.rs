@@ -2,11 +2,12 @@ use crate::agent::tool_manifest::{ToolCategory, ToolMetadata, ToolSource};
 use crate::agent::tools::process_manager::ProcessManager;
 use crate::agent::tools::tool_result;
 use crate::agent::types::{Tool, ToolContext};
+use crate::shell_sandbox::{build_shell_command, ShellSandboxRequest};
 use crate::windows_process::hide_console_window;
 use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
 use std::io::Read;
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 use wait_timeout::ChildExt;
@@ -111,9 +112,14 @@ impl Tool for ExecTool {
         let background = input["background"].as_bool().unwrap_or(false);
         if background {
             if let Some(ref pm) = self.process_manager {
-                let work_dir = ctx.work_dir.as_deref();
                 let (shell, shell_args, shell_label) = Self::get_shell();
-                let handle = pm.spawn_with_shell_handle(command, work_dir, shell, shell_args)?;
+                let handle = pm.spawn_sandboxed_handle(ShellSandboxRequest {
+                    shell,
+                    shell_args,
+                    command,
+                    work_dir: ctx.work_dir.as_deref(),
+                    writable_paths: ctx.task_temp_dir.iter().cloned().collect(),
+                })?;
                 return tool_result::success(
                     self.name(),
                     format!("后台进程已启动，process_id: {}", handle.id),
@@ -123,6 +129,7 @@ impl Tool for ExecTool {
                             "background": true,
                             "process_id": handle.id,
                             "output_file_path": handle.output_file_path.to_string_lossy().to_string(),
+                            "sandbox": handle.sandbox,
                         }),
                         ctx,
                         shell_label,
@@ -136,18 +143,20 @@ impl Tool for ExecTool {
         let timeout = Duration::from_millis(timeout_ms);
         let (shell, shell_args, shell_label) = Self::get_shell();
 
-        let mut cmd = Command::new(shell);
-        cmd.args(shell_args)
-            .arg(command)
+        let mut sandboxed = build_shell_command(ShellSandboxRequest {
+            shell,
+            shell_args,
+            command,
+            work_dir: ctx.work_dir.as_deref(),
+            writable_paths: ctx.task_temp_dir.iter().cloned().collect(),
+        })?;
+        sandboxed
+            .command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        if let Some(ref wd) = ctx.work_dir {
-            cmd.current_dir(wd);
-        }
-
-        hide_console_window(&mut cmd);
-        let mut child = cmd.spawn()?;
+        hide_console_window(&mut sandboxed.command);
+        let mut child = sandboxed.spawn()?;
 
         match child.wait_timeout(timeout)? {
             Some(status) => {
@@ -172,6 +181,7 @@ impl Tool for ExecTool {
                                 "exit_code": status.code().unwrap_or(-1),
                                 "timed_out": false,
                                 "background": false,
+                                "sandbox": sandboxed.details.clone(),
                                 "stdout": stdout_str,
                                 "stderr": stderr_str,
                             }),
@@ -189,6 +199,7 @@ impl Tool for ExecTool {
                                 "exit_code": status.code().unwrap_or(0),
                                 "timed_out": false,
                                 "background": false,
+                                "sandbox": sandboxed.details.clone(),
                                 "stdout": stdout_str,
                                 "stderr": stderr_str,
                             }),
@@ -212,6 +223,7 @@ impl Tool for ExecTool {
                             "exit_code": Value::Null,
                             "timed_out": true,
                             "background": false,
+                            "sandbox": sandboxed.details.clone(),
                             "stdout": "",
                             "stderr": "",
                         }),
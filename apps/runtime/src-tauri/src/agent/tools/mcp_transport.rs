@@ -0,0 +1,698 @@
+use super::native_mcp::{NativeMcpServerConfig, NativeMcpTransport};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{timeout, Duration};
+
+const MCP_PROTOCOL_VERSION: &str = "2025-03-26";
+pub(crate) const MCP_TIMEOUT: Duration = Duration::from_secs(15);
+const MCP_SESSION_ID_HEADER: &str = "mcp-session-id";
+const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+
+type PendingResponse = oneshot::Sender<Result<Value>>;
+
+/// 请求未能送达服务端（会话已关闭或发送失败），可以安全地在新会话上重试
+#[derive(Debug)]
+pub(crate) struct McpRequestNotDelivered(String);
+
+impl std::fmt::Display for McpRequestNotDelivered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for McpRequestNotDelivered {}
+
+/// 把服务端消息分发给等待中的请求；服务端发来的请求交给应答任务，其余通知转给会话监听者
+struct JsonRpcRouter {
+    pending: Mutex<HashMap<u64, PendingResponse>>,
+    notifications: mpsc::UnboundedSender<Value>,
+    server_requests: mpsc::UnboundedSender<Value>,
+    closed: AtomicBool,
+}
+
+impl JsonRpcRouter {
+    fn new(
+        notifications: mpsc::UnboundedSender<Value>,
+        server_requests: mpsc::UnboundedSender<Value>,
+    ) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            notifications,
+            server_requests,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    fn register(&self, id: u64) -> oneshot::Receiver<Result<Value>> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+        receiver
+    }
+
+    fn forget(&self, id: u64) {
+        self.pending.lock().unwrap().remove(&id);
+    }
+
+    fn route(&self, message: Value) {
+        if let Some(batch) = message.as_array() {
+            for item in batch {
+                self.route(item.clone());
+            }
+            return;
+        }
+        let id = message.get("id").and_then(Value::as_u64);
+        let is_response = message.get("result").is_some() || message.get("error").is_some();
+        match id {
+            Some(id) if is_response => {
+                let Some(sender) = self.pending.lock().unwrap().remove(&id) else {
+                    return;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(anyhow!("{error}")),
+                    None => message
+                        .get("result")
+                        .cloned()
+                        .ok_or_else(|| anyhow!("missing JSON-RPC result for response id {id}")),
+                };
+                let _ = sender.send(result);
+            }
+            _ if message.get("method").is_some() && message.get("id").is_some() => {
+                let _ = self.server_requests.send(message);
+            }
+            _ if message.get("method").is_some() => {
+                let _ = self.notifications.send(message);
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&self, reason: &str) {
+        self.closed.store(true, Ordering::SeqCst);
+        for (_, sender) in self.pending.lock().unwrap().drain() {
+            let _ = sender.send(Err(anyhow!("{reason}")));
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+trait McpTransport: Send + Sync {
+    async fn send(&self, message: Value) -> Result<()>;
+    async fn close(&self);
+}
+
+/// 服务端请求的应答：只支持 ping；未声明的能力（roots、sampling 等）回 Method not found
+fn server_request_reply(request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    match request.get("method").and_then(Value::as_str) {
+        Some("ping") => json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+        method => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": JSONRPC_METHOD_NOT_FOUND,
+                "message": format!("Method not found: {}", method.unwrap_or_default()),
+            },
+        }),
+    }
+}
+
+/// 只持有传输的弱引用：会话释放后传输随之关闭，路由器释放时请求通道结束，任务退出
+async fn answer_server_requests(
+    transport: Weak<dyn McpTransport>,
+    mut requests: mpsc::UnboundedReceiver<Value>,
+) {
+    while let Some(request) = requests.recv().await {
+        let Some(transport) = transport.upgrade() else {
+            return;
+        };
+        if let Err(error) = transport.send(server_request_reply(&request)).await {
+            eprintln!("[mcp] failed to answer server request: {error}");
+        }
+    }
+}
+
+/// 一个已完成 initialize 握手的长连接 MCP 会话
+pub(crate) struct McpSession {
+    router: Arc<JsonRpcRouter>,
+    transport: Arc<dyn McpTransport>,
+    next_id: AtomicU64,
+    capabilities: Value,
+}
+
+impl McpSession {
+    /// 建立连接并完成握手；返回的通知通道收到服务端推送的 notifications/*
+    pub(crate) async fn connect(
+        server: &NativeMcpServerConfig,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<Value>)> {
+        let (notifications, receiver) = mpsc::unbounded_channel();
+        let (server_requests, server_request_receiver) = mpsc::unbounded_channel();
+        let router = Arc::new(JsonRpcRouter::new(notifications, server_requests));
+        let transport: Arc<dyn McpTransport> = match server.transport {
+            NativeMcpTransport::Stdio => {
+                Arc::new(StdioTransport::spawn(server, Arc::clone(&router))?)
+            }
+            NativeMcpTransport::StreamableHttp => {
+                Arc::new(StreamableHttpTransport::new(server, Arc::clone(&router))?)
+            }
+            NativeMcpTransport::Sse => {
+                Arc::new(SseTransport::connect(server, Arc::clone(&router)).await?)
+            }
+        };
+        tokio::spawn(answer_server_requests(
+            Arc::downgrade(&transport),
+            server_request_receiver,
+        ));
+
+        let mut session = Self {
+            router,
+            transport,
+            next_id: AtomicU64::new(1),
+            capabilities: Value::Null,
+        };
+        let initialized = session
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "workclaw-runtime",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            )
+            .await?;
+        session.capabilities = initialized
+            .get("capabilities")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        session
+            .notify("notifications/initialized", json!({}))
+            .await?;
+        Ok((session, receiver))
+    }
+
+    pub(crate) fn supports(&self, capability: &str) -> bool {
+        self.capabilities.get(capability).is_some()
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.router.is_closed()
+    }
+
+    pub(crate) async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        if self.router.is_closed() {
+            return Err(McpRequestNotDelivered("MCP session is closed".to_string()).into());
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let receiver = self.router.register(id);
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        if let Err(error) = self.transport.send(message).await {
+            self.router.forget(id);
+            return Err(McpRequestNotDelivered(error.to_string()).into());
+        }
+        match timeout(MCP_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(anyhow!("MCP session closed before response id {id}")),
+            Err(_) => {
+                self.router.forget(id);
+                Err(anyhow!("timed out waiting for MCP response id {id}"))
+            }
+        }
+    }
+
+    pub(crate) async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.transport
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+            }))
+            .await
+    }
+
+    pub(crate) async fn close(&self) {
+        self.transport.close().await;
+        self.router.close("MCP session closed");
+    }
+}
+
+struct StdioTransport {
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    child: tokio::sync::Mutex<Child>,
+}
+
+impl StdioTransport {
+    fn spawn(server: &NativeMcpServerConfig, router: Arc<JsonRpcRouter>) -> Result<Self> {
+        let mut command = Command::new(&server.command);
+        command
+            .args(&server.args)
+            .envs(&server.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        let mut child = command.spawn().map_err(|e| {
+            anyhow!(
+                "failed to spawn MCP server command '{}' for {}: {e}",
+                server.command,
+                server.name
+            )
+        })?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("failed to open MCP server stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("failed to open MCP server stdout"))?;
+
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                match read_stdio_message(&mut reader).await {
+                    Ok(Some(message)) => router.route(message),
+                    Ok(None) => {
+                        router.close("MCP server closed stdout");
+                        return;
+                    }
+                    Err(error) => {
+                        router.close(&format!("MCP stdio read failed: {error}"));
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            stdin: tokio::sync::Mutex::new(stdin),
+            child: tokio::sync::Mutex::new(child),
+        })
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioTransport {
+    async fn send(&self, message: Value) -> Result<()> {
+        let body = serde_json::to_vec(&message)?;
+        let header = format!("Content-Length: {}\r\n\r\n", body.len());
+        let mut stdin = self.stdin.lock().await;
+        timeout(MCP_TIMEOUT, stdin.write_all(header.as_bytes()))
+            .await
+            .map_err(|_| anyhow!("timed out writing MCP JSON-RPC header"))??;
+        timeout(MCP_TIMEOUT, stdin.write_all(&body))
+            .await
+            .map_err(|_| anyhow!("timed out writing MCP JSON-RPC body"))??;
+        timeout(MCP_TIMEOUT, stdin.flush())
+            .await
+            .map_err(|_| anyhow!("timed out flushing MCP JSON-RPC request"))??;
+        Ok(())
+    }
+
+    async fn close(&self) {
+        let _ = self.stdin.lock().await.shutdown().await;
+        let mut child = self.child.lock().await;
+        if timeout(Duration::from_secs(1), child.wait()).await.is_err() {
+            let _ = child.kill().await;
+            let _ = child.wait().await;
+        }
+    }
+}
+
+/// 读取一条 stdio 消息，兼容 Content-Length 分帧与按行分隔的 JSON
+async fn read_stdio_message(reader: &mut BufReader<ChildStdout>) -> Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    loop {
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = String::from_utf8_lossy(&line);
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if content_length.is_none() && trimmed.starts_with('{') {
+            return Ok(Some(serde_json::from_str(trimmed)?));
+        }
+        if let Some((name, value)) = trimmed.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|e| anyhow!("invalid MCP Content-Length: {e}"))?,
+                );
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| anyhow!("missing MCP Content-Length"))?;
+    let mut body = vec![0_u8; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body).map_err(|e| {
+        anyhow!("failed to parse MCP JSON-RPC message: {e}")
+    })?))
+}
+
+fn build_http_client(server: &NativeMcpServerConfig) -> Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &server.headers {
+        headers.insert(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow!("invalid MCP header name '{name}': {e}"))?,
+            reqwest::header::HeaderValue::from_str(value)
+                .map_err(|e| anyhow!("invalid MCP header value for '{name}': {e}"))?,
+        );
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .connect_timeout(MCP_TIMEOUT)
+        .build()
+        .map_err(|e| anyhow!("failed to build MCP HTTP client: {e}"))
+}
+
+fn server_url(server: &NativeMcpServerConfig) -> Result<reqwest::Url> {
+    let url = server
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| anyhow!("MCP server {} has no url", server.name))?;
+    reqwest::Url::parse(url).map_err(|e| anyhow!("invalid MCP server url '{url}': {e}"))
+}
+
+/// Streamable HTTP：每条消息单独 POST，响应可能是 JSON 或 SSE 流
+struct StreamableHttpTransport {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    session_id: Mutex<Option<String>>,
+    router: Arc<JsonRpcRouter>,
+}
+
+impl StreamableHttpTransport {
+    fn new(server: &NativeMcpServerConfig, router: Arc<JsonRpcRouter>) -> Result<Self> {
+        Ok(Self {
+            client: build_http_client(server)?,
+            url: server_url(server)?,
+            session_id: Mutex::new(None),
+            router,
+        })
+    }
+}
+
+#[async_trait]
+impl McpTransport for StreamableHttpTransport {
+    async fn send(&self, message: Value) -> Result<()> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(&message);
+        if let Some(session_id) = self.session_id.lock().unwrap().clone() {
+            request = request.header(MCP_SESSION_ID_HEADER, session_id);
+        }
+        let response = timeout(MCP_TIMEOUT, request.send())
+            .await
+            .map_err(|_| anyhow!("timed out sending MCP HTTP request"))??;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            if status == reqwest::StatusCode::NOT_FOUND && self.session_id.lock().unwrap().is_some()
+            {
+                self.router.close("MCP HTTP session expired");
+            }
+            return Err(anyhow!("MCP HTTP request failed with {status}: {body}"));
+        }
+        if let Some(session_id) = response
+            .headers()
+            .get(MCP_SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.lock().unwrap() = Some(session_id.to_string());
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if content_type.starts_with("text/event-stream") {
+            let router = Arc::clone(&self.router);
+            tokio::spawn(async move {
+                let mut decoder = SseDecoder::default();
+                let mut stream = response.bytes_stream();
+                while let Some(Ok(chunk)) = stream.next().await {
+                    for event in decoder.push(&String::from_utf8_lossy(&chunk)) {
+                        if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                            router.route(message);
+                        }
+                    }
+                }
+            });
+        } else if content_type.starts_with("application/json") {
+            let body = response.bytes().await?;
+            if !body.is_empty() {
+                self.router.route(serde_json::from_slice(&body)?);
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&self) {
+        let session_id = self.session_id.lock().unwrap().take();
+        if let Some(session_id) = session_id {
+            let _ = self
+                .client
+                .delete(self.url.clone())
+                .header(MCP_SESSION_ID_HEADER, session_id)
+                .send()
+                .await;
+        }
+    }
+}
+
+/// 旧版 HTTP+SSE：GET 建立事件流，服务端通过 `endpoint` 事件告知 POST 地址
+struct SseTransport {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    reader: tokio::task::JoinHandle<()>,
+}
+
+impl SseTransport {
+    async fn connect(server: &NativeMcpServerConfig, router: Arc<JsonRpcRouter>) -> Result<Self> {
+        let client = build_http_client(server)?;
+        let url = server_url(server)?;
+        let response = timeout(
+            MCP_TIMEOUT,
+            client
+                .get(url.clone())
+                .header(reqwest::header::ACCEPT, "text/event-stream")
+                .send(),
+        )
+        .await
+        .map_err(|_| anyhow!("timed out connecting to MCP SSE stream"))??;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "MCP SSE connection failed with {}",
+                response.status()
+            ));
+        }
+
+        let (endpoint_tx, endpoint_rx) = oneshot::channel::<String>();
+        let reader = tokio::spawn(async move {
+            let mut endpoint_tx = Some(endpoint_tx);
+            let mut decoder = SseDecoder::default();
+            let mut stream = response.bytes_stream();
+            while let Some(Ok(chunk)) = stream.next().await {
+                for event in decoder.push(&String::from_utf8_lossy(&chunk)) {
+                    if event.event == "endpoint" {
+                        if let Some(sender) = endpoint_tx.take() {
+                            let _ = sender.send(event.data.trim().to_string());
+                        }
+                    } else if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                        router.route(message);
+                    }
+                }
+            }
+            router.close("MCP SSE stream ended");
+        });
+
+        let endpoint = match timeout(MCP_TIMEOUT, endpoint_rx).await {
+            Ok(Ok(endpoint)) => url
+                .join(&endpoint)
+                .map_err(|e| anyhow!("invalid MCP SSE endpoint '{endpoint}': {e}"))?,
+            _ => {
+                reader.abort();
+                return Err(anyhow!("MCP SSE server did not announce an endpoint"));
+            }
+        };
+
+        Ok(Self {
+            client,
+            endpoint,
+            reader,
+        })
+    }
+}
+
+#[async_trait]
+impl McpTransport for SseTransport {
+    async fn send(&self, message: Value) -> Result<()> {
+        let response = timeout(
+            MCP_TIMEOUT,
+            self.client
+                .post(self.endpoint.clone())
+                .json(&message)
+                .send(),
+        )
+        .await
+        .map_err(|_| anyhow!("timed out sending MCP SSE message"))??;
+        if !response.status().is_success() {
+            return Err(anyhow!("MCP SSE message failed with {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn close(&self) {
+        self.reader.abort();
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// 增量解析 text/event-stream
+#[derive(Debug, Default)]
+pub(crate) struct SseDecoder {
+    buffer: String,
+}
+
+impl SseDecoder {
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<SseEvent> {
+        self.buffer.push_str(&chunk.replace("\r\n", "\n"));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let block = self.buffer[..end].to_string();
+            self.buffer.drain(..end + 2);
+            let mut event = SseEvent {
+                event: "message".to_string(),
+                data: String::new(),
+            };
+            let mut data_lines = Vec::new();
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    event.event = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data_lines.push(value.strip_prefix(' ').unwrap_or(value));
+                }
+            }
+            if data_lines.is_empty() {
+                continue;
+            }
+            event.data = data_lines.join("\n");
+            events.push(event);
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{server_request_reply, JsonRpcRouter, SseDecoder};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn sse_decoder_handles_split_chunks_and_named_events() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder
+            .push("event: endpoint\r\ndata: /messages?s")
+            .is_empty());
+        let events = decoder.push("=1\r\n\r\ndata: {\"a\":\ndata: 1}\n\n: ping\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "endpoint");
+        assert_eq!(events[0].data, "/messages?s=1");
+        assert_eq!(events[1].event, "message");
+        assert_eq!(events[1].data, "{\"a\":\n1}");
+    }
+
+    #[tokio::test]
+    async fn router_resolves_pending_requests_and_forwards_notifications() {
+        let (notifications, mut receiver) = mpsc::unbounded_channel();
+        let (server_requests, mut request_receiver) = mpsc::unbounded_channel();
+        let router = JsonRpcRouter::new(notifications, server_requests);
+        let pending = router.register(7);
+
+        router.route(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/tools/list_changed",
+        }));
+        router.route(json!({"jsonrpc": "2.0", "id": "srv-1", "method": "roots/list"}));
+        router.route(json!({"jsonrpc": "2.0", "id": 7, "result": {"ok": true}}));
+
+        assert_eq!(
+            pending.await.expect("response").expect("result"),
+            json!({"ok": true})
+        );
+        assert_eq!(
+            receiver.recv().await.expect("notification")["method"],
+            json!("notifications/tools/list_changed")
+        );
+        assert_eq!(
+            request_receiver.recv().await.expect("server request")["method"],
+            json!("roots/list")
+        );
+        assert!(receiver.try_recv().is_err());
+
+        let dropped = router.register(8);
+        router.close("gone");
+        assert!(dropped.await.expect("closed response").is_err());
+        assert!(router.is_closed());
+    }
+
+    #[test]
+    fn server_requests_get_pong_or_method_not_found() {
+        let pong = server_request_reply(&json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}));
+        assert_eq!(pong, json!({"jsonrpc": "2.0", "id": 3, "result": {}}));
+
+        let unsupported = server_request_reply(&json!({
+            "jsonrpc": "2.0",
+            "id": "srv-1",
+            "method": "sampling/createMessage",
+        }));
+        assert_eq!(unsupported["id"], json!("srv-1"));
+        assert_eq!(unsupported["error"]["code"], json!(-32601));
+        assert!(unsupported.get("result").is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::{Value, json};
 use sha2::{Digest, Sha256};
@@ -6,9 +7,10 @@ use sqlx::SqlitePool;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::agent::types::{Tool, ToolContext};
+use crate::agent::types::{Tool, ToolContext, ToolExecution};
 
 /// 持久内存工具 - 跨会话的知识存储
 ///
@@ -19,18 +21,21 @@ use crate::agent::types::{Tool, ToolContext};
 ///
 /// ```rust
 /// use std::path::PathBuf;
+/// use std::sync::Arc;
 /// use runtime_lib::agent::tools::MemoryTool;
-/// use runtime_lib::agent::types::{Tool, ToolContext};
+/// use runtime_lib::agent::types::{Tool, ToolContext, ToolExecution};
 /// use serde_json::json;
 ///
-/// let tool = MemoryTool::new(PathBuf::from("/tmp/memory"));
-/// let ctx = ToolContext::default();
-/// let result = tool.execute(json!({
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let tool = Arc::new(MemoryTool::new(PathBuf::from("/tmp/memory")));
+/// let result = tool.execute_async(json!({
 ///     "action": "write",
 ///     "key": "greeting",
 ///     "content": "你好，世界！"
-/// }), &ctx).unwrap();
+/// }), ToolContext::default(), ToolExecution::default()).await.unwrap();
 /// assert!(result.contains("已写入"));
+/// # }
 /// ```
 pub struct MemoryTool {
     memory_dir: PathBuf,
@@ -87,17 +92,6 @@ impl MemoryTool {
         self
     }
 
-    fn block_on<T, F>(&self, fut: F) -> Result<T>
-    where
-        F: std::future::Future<Output = std::result::Result<T, String>>,
-    {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .map_err(|err| anyhow!("构建 memory tool runtime 失败: {err}"))?;
-        rt.block_on(fut).map_err(|err| anyhow!(err))
-    }
-
     fn profile_memory_path(&self) -> PathBuf {
         self.memory_dir.join("MEMORY.md")
     }
@@ -333,7 +327,7 @@ impl MemoryTool {
         Ok(())
     }
 
-    fn record_memory_growth_event(
+    async fn record_memory_growth_event(
         &self,
         input: &Value,
         ctx: &ToolContext,
@@ -388,28 +382,28 @@ impl MemoryTool {
         let event_id = format!("gr_mem_{}", record.version_id);
         let event_type = Self::memory_growth_event_type(input, action);
         let created_at = Utc::now().to_rfc3339();
-        self.block_on(async move {
-            Self::ensure_growth_events_schema(&config.pool).await?;
-            sqlx::query(
-                "INSERT OR REPLACE INTO growth_events (
+        Self::ensure_growth_events_schema(&config.pool)
+            .await
+            .map_err(|err| anyhow!(err))?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO growth_events (
                     id, profile_id, session_id, event_type, target_type, target_id,
                     summary, evidence_json, created_at
                  ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            )
-            .bind(event_id)
-            .bind(config.profile_id)
-            .bind(session_id)
-            .bind(event_type)
-            .bind(target_type)
-            .bind(target_id)
-            .bind(summary)
-            .bind(evidence_json)
-            .bind(created_at)
-            .execute(&config.pool)
-            .await
-            .map_err(|e| format!("写入 memory growth event 失败: {e}"))?;
-            Ok(())
-        })
+        )
+        .bind(event_id)
+        .bind(config.profile_id)
+        .bind(session_id)
+        .bind(event_type)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(summary)
+        .bind(evidence_json)
+        .bind(created_at)
+        .execute(&config.pool)
+        .await
+        .map_err(|e| anyhow!("写入 memory growth event 失败: {e}"))?;
+        Ok(())
     }
 
     fn memory_growth_event_type(input: &Value, action: &str) -> String {
@@ -474,7 +468,7 @@ impl MemoryTool {
         Ok(fs::read_to_string(snapshot_path)?)
     }
 
-    fn rollback_version(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn rollback_version(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         if input["confirm"].as_bool() != Some(true) {
             return Ok("rollback 是高风险操作，需要 confirm=true".to_string());
         }
@@ -499,14 +493,15 @@ impl MemoryTool {
             Some(&content),
             Some(&version_id),
         )?;
-        self.record_memory_growth_event(input, ctx, "rollback", &path, &record)?;
+        self.record_memory_growth_event(input, ctx, "rollback", &path, &record)
+            .await?;
         Ok(format!(
             "已回滚 {label} 到 {version_id}，新版本 {}",
             record.version_id
         ))
     }
 
-    fn search_profile_sessions(&self, input: &Value) -> Result<String> {
+    async fn search_profile_sessions(&self, input: &Value) -> Result<String> {
         let query = input["query"]
             .as_str()
             .or_else(|| input["content"].as_str())
@@ -519,7 +514,7 @@ impl MemoryTool {
             .profile_session_search
             .clone()
             .ok_or_else(|| anyhow!("当前 memory tool 未配置 Profile Session Search"))?;
-        let rows = self.block_on(
+        let rows =
             crate::agent::runtime::runtime_io::search_profile_session_index_with_filters_with_pool(
                 &config.pool,
                 &config.profile_id,
@@ -535,8 +530,9 @@ impl MemoryTool {
                     skill_id: input["skill_id"].as_str().map(str::to_string),
                     source: input["source"].as_str().map(str::to_string),
                 },
-            ),
-        )?;
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
         if rows.is_empty() {
             return Ok("Profile Session Search 未找到相关历史经验".to_string());
         }
@@ -565,6 +561,7 @@ impl MemoryTool {
     }
 }
 
+#[async_trait]
 impl Tool for MemoryTool {
     fn name(&self) -> &str {
         "memory"
@@ -685,7 +682,22 @@ impl Tool for MemoryTool {
         })
     }
 
-    fn execute(&self, input: Value, ctx: &ToolContext) -> Result<String> {
+    fn execute(&self, _input: Value, _ctx: &ToolContext) -> Result<String> {
+        Err(anyhow!("memory 工具需要通过 execute_async 调用"))
+    }
+
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        ctx: ToolContext,
+        _execution: ToolExecution,
+    ) -> Result<String> {
+        self.run(input, &ctx).await
+    }
+}
+
+impl MemoryTool {
+    async fn run(&self, input: Value, ctx: &ToolContext) -> Result<String> {
         let action = input["action"]
             .as_str()
             .ok_or_else(|| anyhow!("缺少 action 参数"))?;
@@ -720,7 +732,8 @@ impl Tool for MemoryTool {
                 fs::write(&path, &next)?;
                 let record =
                     self.persist_memory_version(&input, "add", &path, Some(&next), None)?;
-                self.record_memory_growth_event(&input, ctx, "add", &path, &record)?;
+                self.record_memory_growth_event(&input, ctx, "add", &path, &record)
+                    .await?;
                 Ok(format!("已追加 {label}"))
             }
             "replace" => {
@@ -736,7 +749,8 @@ impl Tool for MemoryTool {
                 fs::write(&path, &next)?;
                 let record =
                     self.persist_memory_version(&input, "replace", &path, Some(&next), None)?;
-                self.record_memory_growth_event(&input, ctx, "replace", &path, &record)?;
+                self.record_memory_growth_event(&input, ctx, "replace", &path, &record)
+                    .await?;
                 Ok(format!("已替换 {label}"))
             }
             "remove" => {
@@ -748,7 +762,8 @@ impl Tool for MemoryTool {
                     fs::remove_file(&path)?;
                 }
                 let record = self.persist_memory_version(&input, "remove", &path, None, None)?;
-                self.record_memory_growth_event(&input, ctx, "remove", &path, &record)?;
+                self.record_memory_growth_event(&input, ctx, "remove", &path, &record)
+                    .await?;
                 Ok(format!("已移除 {label}"))
             }
             "history" => {
@@ -760,8 +775,8 @@ impl Tool for MemoryTool {
             }
             "versions" => self.list_versions(&input),
             "view_version" => self.view_version(&input),
-            "rollback" => self.rollback_version(&input, ctx),
-            "search" => self.search_profile_sessions(&input),
+            "rollback" => self.rollback_version(&input, ctx).await,
+            "search" => self.search_profile_sessions(&input).await,
             "read" => {
                 let key = input["key"]
                     .as_str()
@@ -22,6 +22,7 @@ mod github_repo;
 mod glob_tool;
 mod grep_tool;
 mod list_dir;
+mod mcp_transport;
 mod memory_tool;
 mod native_mcp;
 mod open_in_folder;
@@ -61,7 +62,8 @@ pub use grep_tool::GrepTool;
 pub use list_dir::ListDirTool;
 pub use memory_tool::MemoryTool;
 pub use native_mcp::{
-    list_native_mcp_tools, NativeMcpServerConfig, NativeMcpTool, NativeMcpToolDefinition,
+    list_native_mcp_tools, McpServerHandle, McpServerHealth, McpServerStatus, McpSessionManager,
+    NativeMcpServerConfig, NativeMcpTool, NativeMcpToolDefinition, NativeMcpTransport,
 };
 pub use open_in_folder::OpenInFolderTool;
 pub use process_manager::ProcessManager;
@@ -1,22 +1,58 @@
+use super::mcp_transport::{McpRequestNotDelivered, McpSession};
 use crate::agent::tool_manifest::{ToolCategory, ToolMetadata, ToolSource};
-use crate::agent::types::{Tool, ToolContext};
+use crate::agent::types::{Tool, ToolContext, ToolExecution};
+use crate::agent::ToolRegistry;
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, ChildStdout, Command};
-use tokio::time::{timeout, Duration};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::mpsc;
 
-const MCP_PROTOCOL_VERSION: &str = "2025-03-26";
-const MCP_TIMEOUT: Duration = Duration::from_secs(15);
+const MCP_RECONNECT_BASE_SECS: i64 = 1;
+const MCP_RECONNECT_MAX_SECS: i64 = 60;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NativeMcpTransport {
+    #[default]
+    Stdio,
+    StreamableHttp,
+    Sse,
+}
+
+impl NativeMcpTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stdio => "stdio",
+            Self::StreamableHttp => "streamable_http",
+            Self::Sse => "sse",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "" | "stdio" => Ok(Self::Stdio),
+            "streamable_http" | "http" => Ok(Self::StreamableHttp),
+            "sse" => Ok(Self::Sse),
+            other => Err(anyhow!("unsupported MCP transport '{other}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct NativeMcpServerConfig {
+    pub id: String,
     pub name: String,
+    pub transport: NativeMcpTransport,
     pub command: String,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
+    pub url: Option<String>,
+    pub headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,11 +62,483 @@ pub struct NativeMcpToolDefinition {
     pub input_schema: Value,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpServerStatus {
+    Connecting,
+    Connected,
+    Disconnected,
+    BackingOff,
+}
+
+impl McpServerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Connecting => "connecting",
+            Self::Connected => "connected",
+            Self::Disconnected => "disconnected",
+            Self::BackingOff => "backing_off",
+        }
+    }
+}
+
+/// 单个 MCP 服务的连接健康度，供诊断面板与设置页展示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpServerHealth {
+    pub server_id: String,
+    pub server_name: String,
+    pub transport: NativeMcpTransport,
+    pub status: McpServerStatus,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub connected_at: Option<String>,
+    pub last_success_at: Option<String>,
+    pub next_retry_at: Option<String>,
+    pub tool_count: usize,
+}
+
+#[derive(Debug, Default)]
+struct McpHealthState {
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    connected_at: Option<DateTime<Utc>>,
+    last_success_at: Option<DateTime<Utc>>,
+    next_retry_at: Option<DateTime<Utc>>,
+    tool_count: usize,
+}
+
+/// 一个 `mcp_servers` 记录对应的长连接，断线后按指数退避重连
+pub struct McpServerHandle {
+    config: NativeMcpServerConfig,
+    runtime: Handle,
+    registry: Weak<ToolRegistry>,
+    session: tokio::sync::Mutex<Option<Arc<McpSession>>>,
+    health: Mutex<McpHealthState>,
+}
+
+impl McpServerHandle {
+    fn new(config: NativeMcpServerConfig, registry: Weak<ToolRegistry>) -> Self {
+        Self {
+            config,
+            runtime: Handle::current(),
+            registry,
+            session: tokio::sync::Mutex::new(None),
+            health: Mutex::new(McpHealthState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &NativeMcpServerConfig {
+        &self.config
+    }
+
+    fn tool_prefix(&self) -> String {
+        format!("mcp_{}_", self.config.name)
+    }
+
+    pub fn health(&self) -> McpServerHealth {
+        let state = self.health.lock().unwrap();
+        let connected = self
+            .session
+            .try_lock()
+            .map(|session| session.as_ref().is_some_and(|session| !session.is_closed()))
+            // 锁被占用说明正在连接或请求中
+            .unwrap_or(true);
+        let status = if connected && state.connected_at.is_some() {
+            McpServerStatus::Connected
+        } else if state
+            .next_retry_at
+            .is_some_and(|retry_at| retry_at > Utc::now())
+        {
+            McpServerStatus::BackingOff
+        } else if state.connected_at.is_none() && state.consecutive_failures == 0 {
+            McpServerStatus::Connecting
+        } else {
+            McpServerStatus::Disconnected
+        };
+        McpServerHealth {
+            server_id: self.config.id.clone(),
+            server_name: self.config.name.clone(),
+            transport: self.config.transport,
+            status,
+            consecutive_failures: state.consecutive_failures,
+            last_error: state.last_error.clone(),
+            connected_at: state.connected_at.map(|at| at.to_rfc3339()),
+            last_success_at: state.last_success_at.map(|at| at.to_rfc3339()),
+            next_retry_at: state.next_retry_at.map(|at| at.to_rfc3339()),
+            tool_count: state.tool_count,
+        }
+    }
+
+    async fn session(self: &Arc<Self>) -> Result<Arc<McpSession>> {
+        let mut slot = self.session.lock().await;
+        if let Some(session) = slot.as_ref() {
+            if !session.is_closed() {
+                return Ok(Arc::clone(session));
+            }
+        }
+        if let Some(stale) = slot.take() {
+            stale.close().await;
+        }
+
+        if let Some(retry_at) = self.health.lock().unwrap().next_retry_at {
+            let now = Utc::now();
+            if retry_at > now {
+                return Err(anyhow!(
+                    "native MCP server {} is backing off for {}s after connection failures",
+                    self.config.name,
+                    (retry_at - now).num_seconds().max(1)
+                ));
+            }
+        }
+
+        match McpSession::connect(&self.config).await {
+            Ok((session, notifications)) => {
+                let session = Arc::new(session);
+                {
+                    let mut health = self.health.lock().unwrap();
+                    health.consecutive_failures = 0;
+                    health.last_error = None;
+                    health.next_retry_at = None;
+                    health.connected_at = Some(Utc::now());
+                }
+                self.spawn_notification_listener(notifications);
+                *slot = Some(Arc::clone(&session));
+                Ok(session)
+            }
+            Err(error) => {
+                let message = format!(
+                    "native MCP connection failed for {}: {error}",
+                    self.config.name
+                );
+                let mut health = self.health.lock().unwrap();
+                health.consecutive_failures += 1;
+                health.last_error = Some(message.clone());
+                health.connected_at = None;
+                let backoff = (MCP_RECONNECT_BASE_SECS << (health.consecutive_failures - 1).min(6))
+                    .min(MCP_RECONNECT_MAX_SECS);
+                health.next_retry_at = Some(Utc::now() + chrono::Duration::seconds(backoff));
+                Err(anyhow!(message))
+            }
+        }
+    }
+
+    /// 发送请求；请求未送达且会话已断开时立即重连并重试一次
+    pub async fn request(self: &Arc<Self>, method: &str, params: Value) -> Result<Value> {
+        let mut retried = false;
+        loop {
+            let session = self.session().await?;
+            match session.request(method, params.clone()).await {
+                Ok(result) => {
+                    self.health.lock().unwrap().last_success_at = Some(Utc::now());
+                    return Ok(result);
+                }
+                Err(error)
+                    if !retried
+                        && session.is_closed()
+                        && error.downcast_ref::<McpRequestNotDelivered>().is_some() =>
+                {
+                    retried = true;
+                    self.health.lock().unwrap().last_error = Some(error.to_string());
+                }
+                Err(error) => {
+                    self.health.lock().unwrap().last_error = Some(error.to_string());
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    async fn supports(self: &Arc<Self>, capability: &str) -> Result<bool> {
+        Ok(self.session().await?.supports(capability))
+    }
+
+    pub async fn list_tools(self: &Arc<Self>) -> Result<Vec<NativeMcpToolDefinition>> {
+        let mut definitions = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let response = self
+                .request("tools/list", params)
+                .await
+                .map_err(|e| anyhow!("native MCP list failed for {}: {e}", self.config.name))?;
+            definitions.extend(parse_tool_definitions(&self.config, &response)?);
+            cursor = response
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                return Ok(definitions);
+            }
+        }
+    }
+
+    pub async fn call_tool(self: &Arc<Self>, tool_name: &str, arguments: Value) -> Result<String> {
+        let response = self
+            .request(
+                "tools/call",
+                json!({
+                    "name": tool_name,
+                    "arguments": arguments,
+                }),
+            )
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "native MCP call failed for {}.{}: {e}",
+                    self.config.name,
+                    tool_name
+                )
+            })?;
+        Ok(mcp_call_result_to_string(response))
+    }
+
+    /// 按服务端当前的工具列表与能力重建注册表中的 `mcp_{server}_*` 工具
+    pub async fn sync_tools(self: &Arc<Self>) -> Result<usize> {
+        let registry = self
+            .registry
+            .upgrade()
+            .ok_or_else(|| anyhow!("tool registry dropped"))?;
+        let prefix = self.tool_prefix();
+        let mut registered = HashSet::new();
+
+        for tool in self.list_tools().await? {
+            let full_name = format!("{prefix}{}", tool.name);
+            registry.register(Arc::new(NativeMcpTool::new(
+                full_name.clone(),
+                tool.description,
+                tool.input_schema,
+                Arc::clone(self),
+                tool.name,
+            )));
+            registered.insert(full_name);
+        }
+
+        for (capability, kinds) in [
+            (
+                "resources",
+                [
+                    McpCapabilityKind::ListResources,
+                    McpCapabilityKind::ReadResource,
+                ],
+            ),
+            (
+                "prompts",
+                [McpCapabilityKind::ListPrompts, McpCapabilityKind::GetPrompt],
+            ),
+        ] {
+            if !self.supports(capability).await? {
+                continue;
+            }
+            for kind in kinds {
+                let full_name = format!("{prefix}{}", kind.suffix());
+                if registered.contains(&full_name) {
+                    // 服务端同名工具优先
+                    continue;
+                }
+                registry.register(Arc::new(McpCapabilityTool {
+                    tool_name: full_name.clone(),
+                    kind,
+                    server: Arc::clone(self),
+                }));
+                registered.insert(full_name);
+            }
+        }
+
+        for stale in registry.tools_with_prefix(&prefix) {
+            if !registered.contains(&stale) {
+                registry.unregister(&stale);
+            }
+        }
+
+        let count = registered.len();
+        self.health.lock().unwrap().tool_count = count;
+        Ok(count)
+    }
+
+    fn spawn_notification_listener(
+        self: &Arc<Self>,
+        mut notifications: mpsc::UnboundedReceiver<Value>,
+    ) {
+        let handle = Arc::downgrade(self);
+        self.runtime.spawn(async move {
+            while let Some(notification) = notifications.recv().await {
+                let Some(handle) = handle.upgrade() else {
+                    return;
+                };
+                if notification.get("method").and_then(Value::as_str)
+                    != Some("notifications/tools/list_changed")
+                {
+                    continue;
+                }
+                if let Err(error) = handle.sync_tools().await {
+                    eprintln!(
+                        "[mcp] failed to refresh tools for {}: {error}",
+                        handle.config.name
+                    );
+                }
+            }
+        });
+    }
+
+    /// 同步接口的桥接：在独立线程上阻塞等待附着时的运行时完成请求
+    fn block_on<T, F, Fut>(self: &Arc<Self>, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<McpServerHandle>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        if self.runtime.runtime_flavor() == RuntimeFlavor::CurrentThread {
+            // 单线程运行时在被阻塞时无法驱动长连接，退回一次性连接
+            let config = self.config.clone();
+            let registry = self.registry.clone();
+            return std::thread::spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|e| anyhow!("native MCP call failed: failed to start runtime: {e}"))?;
+                runtime.block_on(async move {
+                    let handle = Arc::new(McpServerHandle::new(config, registry));
+                    let result = call(Arc::clone(&handle)).await;
+                    handle.close().await;
+                    result
+                })
+            })
+            .join()
+            .map_err(|_| anyhow!("native MCP call failed: worker thread panicked"))?;
+        }
+        let runtime = self.runtime.clone();
+        let handle = Arc::clone(self);
+        std::thread::spawn(move || runtime.block_on(call(handle)))
+            .join()
+            .map_err(|_| anyhow!("native MCP call failed: worker thread panicked"))?
+    }
+
+    async fn close(&self) {
+        if let Some(session) = self.session.lock().await.take() {
+            session.close().await;
+        }
+    }
+}
+
+/// 按 `mcp_servers.id` 管理所有长连接
+#[derive(Default)]
+pub struct McpSessionManager {
+    servers: Mutex<HashMap<String, Arc<McpServerHandle>>>,
+}
+
+impl McpSessionManager {
+    pub fn global() -> &'static McpSessionManager {
+        static MANAGER: OnceLock<McpSessionManager> = OnceLock::new();
+        MANAGER.get_or_init(McpSessionManager::default)
+    }
+
+    /// 建立会话并注册工具；同 id 的旧连接会被替换。
+    /// 首次连接失败时保留句柄并在后台按退避节奏重试，调用方可用 `detach` 放弃
+    pub async fn attach(
+        &'static self,
+        registry: &Arc<ToolRegistry>,
+        config: NativeMcpServerConfig,
+    ) -> Result<usize> {
+        let handle = Arc::new(McpServerHandle::new(config, Arc::downgrade(registry)));
+        let previous = self
+            .servers
+            .lock()
+            .unwrap()
+            .insert(handle.config.id.clone(), Arc::clone(&handle));
+        if let Some(previous) = previous {
+            previous.close().await;
+        }
+        match handle.sync_tools().await {
+            Ok(count) => Ok(count),
+            Err(error) => {
+                self.spawn_background_retry(&handle);
+                Err(error)
+            }
+        }
+    }
+
+    fn spawn_background_retry(&'static self, handle: &Arc<McpServerHandle>) {
+        let handle = Arc::downgrade(handle);
+        tokio::spawn(async move {
+            loop {
+                let Some(current) = handle.upgrade() else {
+                    return;
+                };
+                let attached = self
+                    .get(&current.config.id)
+                    .is_some_and(|attached| Arc::ptr_eq(&attached, &current));
+                if !attached {
+                    return;
+                }
+                let wait = current
+                    .health
+                    .lock()
+                    .unwrap()
+                    .next_retry_at
+                    .and_then(|retry_at| (retry_at - Utc::now()).to_std().ok())
+                    .unwrap_or_default()
+                    .max(std::time::Duration::from_secs(
+                        MCP_RECONNECT_BASE_SECS as u64,
+                    ));
+                drop(current);
+                tokio::time::sleep(wait).await;
+
+                let Some(current) = handle.upgrade() else {
+                    return;
+                };
+                if !self
+                    .get(&current.config.id)
+                    .is_some_and(|attached| Arc::ptr_eq(&attached, &current))
+                {
+                    return;
+                }
+                if current.sync_tools().await.is_ok() {
+                    eprintln!(
+                        "[mcp] reconnected native MCP server {}",
+                        current.config.name
+                    );
+                    return;
+                }
+            }
+        });
+    }
+
+    pub async fn detach(&self, server_id: &str) {
+        let handle = self.servers.lock().unwrap().remove(server_id);
+        if let Some(handle) = handle {
+            handle.close().await;
+        }
+    }
+
+    pub fn get(&self, server_id: &str) -> Option<Arc<McpServerHandle>> {
+        self.servers.lock().unwrap().get(server_id).cloned()
+    }
+
+    pub fn health(&self) -> Vec<McpServerHealth> {
+        let handles = self
+            .servers
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut health = handles
+            .iter()
+            .map(|handle| handle.health())
+            .collect::<Vec<_>>();
+        health.sort_by(|left, right| left.server_name.cmp(&right.server_name));
+        health
+    }
+}
+
 pub struct NativeMcpTool {
     tool_name: String,
     tool_description: String,
     input_schema: Value,
-    server: NativeMcpServerConfig,
+    server: Arc<McpServerHandle>,
     mcp_tool_name: String,
 }
 
@@ -39,7 +547,7 @@ impl NativeMcpTool {
         tool_name: String,
         tool_description: String,
         input_schema: Value,
-        server: NativeMcpServerConfig,
+        server: Arc<McpServerHandle>,
         mcp_tool_name: String,
     ) -> Self {
         Self {
@@ -52,6 +560,7 @@ impl NativeMcpTool {
     }
 }
 
+#[async_trait]
 impl Tool for NativeMcpTool {
     fn name(&self) -> &str {
         &self.tool_name
@@ -66,17 +575,27 @@ impl Tool for NativeMcpTool {
     }
 
     fn execute(&self, input: Value, _ctx: &ToolContext) -> Result<String> {
-        let server = self.server.clone();
         let tool_name = self.mcp_tool_name.clone();
-        std::thread::spawn(move || {
-            let runtime = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .map_err(|e| anyhow!("native MCP call failed: failed to start runtime: {e}"))?;
-            runtime.block_on(call_native_mcp_tool(&server, &tool_name, input))
-        })
-        .join()
-        .map_err(|_| anyhow!("native MCP call failed: worker thread panicked"))?
+        self.server
+            .block_on(move |server| async move { server.call_tool(&tool_name, input).await })
+    }
+
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        _ctx: ToolContext,
+        execution: ToolExecution,
+    ) -> Result<String> {
+        execution.report_progress(&format!("正在调用 MCP 服务 {}", self.server.config.name));
+        tokio::select! {
+            result = self.server.call_tool(&self.mcp_tool_name, input) => result,
+            reason = execution.cancellation.cancelled() => Err(anyhow!(
+                "native MCP call cancelled for {}.{}: {}",
+                self.server.config.name,
+                self.mcp_tool_name,
+                reason.message
+            )),
+        }
     }
 
     fn metadata(&self) -> ToolMetadata {
@@ -93,18 +612,192 @@ impl Tool for NativeMcpTool {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum McpCapabilityKind {
+    ListResources,
+    ReadResource,
+    ListPrompts,
+    GetPrompt,
+}
+
+impl McpCapabilityKind {
+    fn suffix(&self) -> &'static str {
+        match self {
+            Self::ListResources => "list_resources",
+            Self::ReadResource => "read_resource",
+            Self::ListPrompts => "list_prompts",
+            Self::GetPrompt => "get_prompt",
+        }
+    }
+}
+
+/// 把 MCP 的 resources/* 与 prompts/* 暴露成只读工具
+struct McpCapabilityTool {
+    tool_name: String,
+    kind: McpCapabilityKind,
+    server: Arc<McpServerHandle>,
+}
+
+impl McpCapabilityTool {
+    async fn run(
+        server: Arc<McpServerHandle>,
+        kind: McpCapabilityKind,
+        input: Value,
+    ) -> Result<String> {
+        let name = &server.config.name;
+        match kind {
+            McpCapabilityKind::ListResources | McpCapabilityKind::ListPrompts => {
+                let (method, key) = if kind == McpCapabilityKind::ListResources {
+                    ("resources/list", "resources")
+                } else {
+                    ("prompts/list", "prompts")
+                };
+                let params = match input.get("cursor").and_then(Value::as_str) {
+                    Some(cursor) => json!({ "cursor": cursor }),
+                    None => json!({}),
+                };
+                let response = server
+                    .request(method, params)
+                    .await
+                    .map_err(|e| anyhow!("native MCP {method} failed for {name}: {e}"))?;
+                let mut output = json!({ key: response.get(key).cloned().unwrap_or(json!([])) });
+                if let Some(cursor) = response.get("nextCursor") {
+                    output["nextCursor"] = cursor.clone();
+                }
+                Ok(serde_json::to_string_pretty(&output)?)
+            }
+            McpCapabilityKind::ReadResource => {
+                let uri = input
+                    .get("uri")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("缺少 uri 参数"))?;
+                let response = server
+                    .request("resources/read", json!({ "uri": uri }))
+                    .await
+                    .map_err(|e| anyhow!("native MCP resources/read failed for {name}: {e}"))?;
+                Ok(mcp_resource_contents_to_string(&response))
+            }
+            McpCapabilityKind::GetPrompt => {
+                let prompt = input
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("缺少 name 参数"))?;
+                let arguments = input.get("arguments").cloned().unwrap_or(json!({}));
+                let response = server
+                    .request(
+                        "prompts/get",
+                        json!({ "name": prompt, "arguments": arguments }),
+                    )
+                    .await
+                    .map_err(|e| anyhow!("native MCP prompts/get failed for {name}: {e}"))?;
+                Ok(mcp_prompt_messages_to_string(&response))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpCapabilityTool {
+    fn name(&self) -> &str {
+        &self.tool_name
+    }
+
+    fn description(&self) -> &str {
+        match self.kind {
+            McpCapabilityKind::ListResources => "列出该 MCP 服务提供的资源（resources/list）",
+            McpCapabilityKind::ReadResource => "按 uri 读取该 MCP 服务的资源内容（resources/read）",
+            McpCapabilityKind::ListPrompts => "列出该 MCP 服务提供的提示词模板（prompts/list）",
+            McpCapabilityKind::GetPrompt => {
+                "按名称与参数展开该 MCP 服务的提示词模板（prompts/get）"
+            }
+        }
+    }
+
+    fn input_schema(&self) -> Value {
+        match self.kind {
+            McpCapabilityKind::ListResources | McpCapabilityKind::ListPrompts => json!({
+                "type": "object",
+                "properties": {
+                    "cursor": {"type": "string", "description": "上一页返回的 nextCursor"}
+                }
+            }),
+            McpCapabilityKind::ReadResource => json!({
+                "type": "object",
+                "properties": {
+                    "uri": {"type": "string", "description": "资源 uri"}
+                },
+                "required": ["uri"]
+            }),
+            McpCapabilityKind::GetPrompt => json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "提示词模板名称"},
+                    "arguments": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "模板参数"
+                    }
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    fn execute(&self, input: Value, _ctx: &ToolContext) -> Result<String> {
+        let kind = self.kind;
+        self.server
+            .block_on(move |server| Self::run(server, kind, input))
+    }
+
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        _ctx: ToolContext,
+        execution: ToolExecution,
+    ) -> Result<String> {
+        tokio::select! {
+            result = Self::run(Arc::clone(&self.server), self.kind, input) => result,
+            reason = execution.cancellation.cancelled() => Err(anyhow!(
+                "native MCP {} cancelled for {}: {}",
+                self.kind.suffix(),
+                self.server.config.name,
+                reason.message
+            )),
+        }
+    }
+
+    fn metadata(&self) -> ToolMetadata {
+        ToolMetadata {
+            display_name: Some(self.tool_name.clone()),
+            category: ToolCategory::Integration,
+            read_only: true,
+            destructive: false,
+            concurrency_safe: true,
+            open_world: true,
+            requires_approval: false,
+            source: ToolSource::Mcp,
+        }
+    }
+}
+
+/// 一次性连接并列出工具，不进入会话管理
 pub async fn list_native_mcp_tools(
     server: &NativeMcpServerConfig,
 ) -> Result<Vec<NativeMcpToolDefinition>> {
-    let mut session = NativeMcpSession::connect(server)
+    let (session, _notifications) = McpSession::connect(server)
         .await
         .map_err(|e| anyhow!("native MCP connection failed for {}: {e}", server.name))?;
-    let response = session
-        .request("tools/list", json!({}))
-        .await
-        .map_err(|e| anyhow!("native MCP list failed for {}: {e}", server.name))?;
+    let response = session.request("tools/list", json!({})).await;
     session.close().await;
+    let response =
+        response.map_err(|e| anyhow!("native MCP list failed for {}: {e}", server.name))?;
+    parse_tool_definitions(server, &response)
+}
 
+fn parse_tool_definitions(
+    server: &NativeMcpServerConfig,
+    response: &Value,
+) -> Result<Vec<NativeMcpToolDefinition>> {
     let tools = response
         .get("tools")
         .and_then(Value::as_array)
@@ -137,34 +830,6 @@ pub async fn list_native_mcp_tools(
         .collect())
 }
 
-pub async fn call_native_mcp_tool(
-    server: &NativeMcpServerConfig,
-    tool_name: &str,
-    arguments: Value,
-) -> Result<String> {
-    let mut session = NativeMcpSession::connect(server)
-        .await
-        .map_err(|e| anyhow!("native MCP connection failed for {}: {e}", server.name))?;
-    let response = session
-        .request(
-            "tools/call",
-            json!({
-                "name": tool_name,
-                "arguments": arguments,
-            }),
-        )
-        .await
-        .map_err(|e| {
-            anyhow!(
-                "native MCP call failed for {}.{}: {e}",
-                server.name,
-                tool_name
-            )
-        })?;
-    session.close().await;
-    Ok(mcp_call_result_to_string(response))
-}
-
 fn default_input_schema() -> Value {
     json!({"type": "object", "properties": {}})
 }
@@ -192,161 +857,99 @@ fn mcp_call_result_to_string(value: Value) -> String {
     serde_json::to_string(&value).unwrap_or_default()
 }
 
-struct NativeMcpSession {
-    child: Child,
-    stdin: ChildStdin,
-    stdout: BufReader<ChildStdout>,
-    next_id: u64,
+fn mcp_resource_contents_to_string(value: &Value) -> String {
+    let Some(contents) = value.get("contents").and_then(Value::as_array) else {
+        return serde_json::to_string(value).unwrap_or_default();
+    };
+    contents
+        .iter()
+        .map(|item| {
+            let uri = item.get("uri").and_then(Value::as_str).unwrap_or_default();
+            if let Some(text) = item.get("text").and_then(Value::as_str) {
+                return format!("[{uri}]\n{text}");
+            }
+            let mime = item
+                .get("mimeType")
+                .and_then(Value::as_str)
+                .unwrap_or("application/octet-stream");
+            let size = item
+                .get("blob")
+                .and_then(Value::as_str)
+                .map(|blob| blob.len() * 3 / 4)
+                .unwrap_or_default();
+            format!("[{uri}] 二进制资源 {mime}，约 {size} 字节")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
 
-impl NativeMcpSession {
-    async fn connect(server: &NativeMcpServerConfig) -> Result<Self> {
-        let mut command = Command::new(&server.command);
-        command
-            .args(&server.args)
-            .envs(&server.env)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .kill_on_drop(true);
-
-        let mut child = command.spawn().map_err(|e| {
-            anyhow!(
-                "failed to spawn MCP server command '{}' for {}: {e}",
-                server.command,
-                server.name
-            )
-        })?;
-        let stdin = child
-            .stdin
-            .take()
-            .ok_or_else(|| anyhow!("failed to open MCP server stdin"))?;
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| anyhow!("failed to open MCP server stdout"))?;
-
-        let mut session = Self {
-            child,
-            stdin,
-            stdout: BufReader::new(stdout),
-            next_id: 1,
-        };
-
-        session
-            .request(
-                "initialize",
-                json!({
-                    "protocolVersion": MCP_PROTOCOL_VERSION,
-                    "capabilities": {},
-                    "clientInfo": {
-                        "name": "workclaw-runtime",
-                        "version": env!("CARGO_PKG_VERSION"),
-                    },
-                }),
-            )
-            .await?;
-        session
-            .notify("notifications/initialized", json!({}))
-            .await?;
-        Ok(session)
-    }
-
-    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
-        let id = self.next_id;
-        self.next_id += 1;
-        let request = json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "method": method,
-            "params": params,
-        });
-        self.write_message(request).await?;
-        self.read_response(id).await
+fn mcp_prompt_messages_to_string(value: &Value) -> String {
+    let Some(messages) = value.get("messages").and_then(Value::as_array) else {
+        return serde_json::to_string(value).unwrap_or_default();
+    };
+    let mut lines = Vec::new();
+    if let Some(description) = value.get("description").and_then(Value::as_str) {
+        lines.push(description.to_string());
     }
-
-    async fn notify(&mut self, method: &str, params: Value) -> Result<()> {
-        self.write_message(json!({
-            "jsonrpc": "2.0",
-            "method": method,
-            "params": params,
-        }))
-        .await
+    for message in messages {
+        let role = message
+            .get("role")
+            .and_then(Value::as_str)
+            .unwrap_or("user");
+        let content = message.get("content").cloned().unwrap_or(Value::Null);
+        let text = match content.get("text").and_then(Value::as_str) {
+            Some(text) => text.to_string(),
+            None => match content.get("resource") {
+                Some(resource) => mcp_resource_contents_to_string(&json!({
+                    "contents": [resource]
+                })),
+                None => serde_json::to_string(&content).unwrap_or_default(),
+            },
+        };
+        lines.push(format!("{role}: {text}"));
     }
+    lines.join("\n")
+}
 
-    async fn write_message(&mut self, message: Value) -> Result<()> {
-        let body = serde_json::to_vec(&message)?;
-        let header = format!("Content-Length: {}\r\n\r\n", body.len());
-        timeout(MCP_TIMEOUT, self.stdin.write_all(header.as_bytes()))
-            .await
-            .map_err(|_| anyhow!("timed out writing MCP JSON-RPC header"))??;
-        timeout(MCP_TIMEOUT, self.stdin.write_all(&body))
-            .await
-            .map_err(|_| anyhow!("timed out writing MCP JSON-RPC body"))??;
-        timeout(MCP_TIMEOUT, self.stdin.flush())
-            .await
-            .map_err(|_| anyhow!("timed out flushing MCP JSON-RPC request"))??;
-        Ok(())
-    }
+#[cfg(test)]
+mod tests {
+    use super::{
+        mcp_prompt_messages_to_string, mcp_resource_contents_to_string, NativeMcpTransport,
+    };
+    use serde_json::json;
 
-    async fn read_response(&mut self, id: u64) -> Result<Value> {
-        loop {
-            let message = self.read_message(id).await?;
-            if message.get("id").and_then(Value::as_u64) != Some(id) {
-                continue;
-            }
-            if let Some(error) = message.get("error") {
-                return Err(anyhow!("{error}"));
-            }
-            return message
-                .get("result")
-                .cloned()
-                .ok_or_else(|| anyhow!("missing JSON-RPC result for response id {id}"));
-        }
+    #[test]
+    fn transport_parse_accepts_known_values() {
+        assert_eq!(
+            NativeMcpTransport::parse("").unwrap(),
+            NativeMcpTransport::Stdio
+        );
+        assert_eq!(
+            NativeMcpTransport::parse("streamable_http").unwrap(),
+            NativeMcpTransport::StreamableHttp
+        );
+        assert_eq!(
+            NativeMcpTransport::parse("sse").unwrap(),
+            NativeMcpTransport::Sse
+        );
+        assert!(NativeMcpTransport::parse("websocket").is_err());
     }
 
-    async fn read_message(&mut self, id: u64) -> Result<Value> {
-        let mut content_length: Option<usize> = None;
-        loop {
-            let mut line = Vec::new();
-            let bytes = timeout(MCP_TIMEOUT, self.stdout.read_until(b'\n', &mut line))
-                .await
-                .map_err(|_| anyhow!("timed out waiting for MCP header for response id {id}"))??;
-            if bytes == 0 {
-                return Err(anyhow!("MCP server closed stdout before response id {id}"));
-            }
-            let line = String::from_utf8_lossy(&line);
-            let trimmed = line.trim_end_matches(['\r', '\n']);
-            if trimmed.is_empty() {
-                break;
-            }
-            if let Some((name, value)) = trimmed.split_once(':') {
-                if name.eq_ignore_ascii_case("content-length") {
-                    content_length = Some(value.trim().parse::<usize>().map_err(|e| {
-                        anyhow!("invalid MCP Content-Length for response id {id}: {e}")
-                    })?);
-                }
-            }
-        }
-
-        let length = content_length
-            .ok_or_else(|| anyhow!("missing MCP Content-Length for response id {id}"))?;
-        let mut body = vec![0_u8; length];
-        timeout(MCP_TIMEOUT, self.stdout.read_exact(&mut body))
-            .await
-            .map_err(|_| anyhow!("timed out reading MCP body for response id {id}"))??;
-        serde_json::from_slice(&body)
-            .map_err(|e| anyhow!("failed to parse MCP JSON-RPC response for id {id}: {e}"))
-    }
+    #[test]
+    fn resource_and_prompt_results_render_as_text() {
+        let resources = mcp_resource_contents_to_string(&json!({
+            "contents": [
+                {"uri": "file:///a.md", "text": "hello"},
+                {"uri": "file:///b.png", "mimeType": "image/png", "blob": "AAAA"}
+            ]
+        }));
+        assert!(resources.contains("[file:///a.md]\nhello"));
+        assert!(resources.contains("image/png，约 3 字节"));
 
-    async fn close(&mut self) {
-        let _ = self.stdin.shutdown().await;
-        if timeout(Duration::from_secs(1), self.child.wait())
-            .await
-            .is_err()
-        {
-            let _ = self.child.kill().await;
-            let _ = self.child.wait().await;
-        }
+        let prompt = mcp_prompt_messages_to_string(&json!({
+            "description": "review",
+            "messages": [{"role": "user", "content": {"type": "text", "text": "look"}}]
+        }));
+        assert_eq!(prompt, "review\nuser: look");
     }
 }
@@ -1,14 +1,17 @@
+use crate::shell_sandbox::{build_shell_command, ShellSandboxRequest};
 use crate::windows_process::hide_console_window;
 use anyhow::{anyhow, Result};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// 后台进程的输出快照
 #[derive(Debug, Clone)]
@@ -26,6 +29,8 @@ pub struct ProcessOutput {
 pub struct ProcessHandle {
     pub id: String,
     pub output_file_path: PathBuf,
+    /// 启动时生效的沙箱配置，写入工具结果 details
+    pub sandbox: Value,
 }
 
 /// 后台进程完成通知。
@@ -123,6 +128,21 @@ impl ProcessManager {
         shell: &str,
         shell_args: &[&str],
     ) -> Result<ProcessHandle> {
+        self.spawn_sandboxed_handle(ShellSandboxRequest {
+            shell,
+            shell_args,
+            command,
+            work_dir,
+            writable_paths: Vec::new(),
+        })
+    }
+
+    /// 按当前 shell 沙箱配置启动后台进程；沙箱设置了运行时长上限时到点强制终止
+    pub fn spawn_sandboxed_handle(
+        &self,
+        request: ShellSandboxRequest<'_>,
+    ) -> Result<ProcessHandle> {
+        let command = request.command;
         let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
         let output_file_path = background_output_file_path(&id)?;
         let output_file = Arc::new(Mutex::new(open_background_output_file(&output_file_path)?));
@@ -131,18 +151,14 @@ impl ProcessManager {
         let output_file_path_for_completion = output_file_path.clone();
         let completion_notifier = self.completion_notifier.clone();
 
-        let mut cmd = Command::new(shell);
-        cmd.args(shell_args)
-            .arg(command)
+        let mut sandboxed = build_shell_command(request)?;
+        sandboxed
+            .command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        if let Some(wd) = work_dir {
-            cmd.current_dir(wd);
-        }
-
-        hide_console_window(&mut cmd);
-        let mut child = cmd.spawn()?;
+        hide_console_window(&mut sandboxed.command);
+        let mut child = sandboxed.spawn()?;
         let pid = child.id();
 
         let stdout_buf: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
@@ -236,6 +252,16 @@ impl ProcessManager {
             }
         });
 
+        if let Some(wall_time) = sandboxed.wall_time {
+            let _ = wall_time_reaper().send(WallTimeDeadline {
+                pid,
+                wall_time,
+                deadline: Instant::now() + wall_time,
+                exit_status: Arc::clone(&exit_status),
+                stderr_buf: Arc::clone(&stderr_buf),
+            });
+        }
+
         let bg_process = BackgroundProcess {
             command: command.to_string(),
             pid,
@@ -252,6 +278,7 @@ impl ProcessManager {
         Ok(ProcessHandle {
             id,
             output_file_path,
+            sandbox: sandboxed.details,
         })
     }
 
@@ -375,6 +402,65 @@ impl ProcessManager {
     }
 }
 
+/// 沙箱后台进程的运行时长上限
+struct WallTimeDeadline {
+    pid: u32,
+    wall_time: Duration,
+    deadline: Instant,
+    exit_status: Arc<Mutex<Option<i32>>>,
+    stderr_buf: Arc<Mutex<Vec<String>>>,
+}
+
+impl WallTimeDeadline {
+    fn exited(&self) -> bool {
+        self.exit_status.lock().unwrap().is_some()
+    }
+
+    fn expire(self) {
+        if self.exited() {
+            return;
+        }
+        self.stderr_buf.lock().unwrap().push(format!(
+            "[沙箱] 超过运行时长上限 {} 秒，进程已终止",
+            self.wall_time.as_secs()
+        ));
+        let _ = ProcessManager::kill_process_by_pid(self.pid);
+    }
+}
+
+/// 所有后台进程共用一个计时线程，按最近的截止时间休眠
+fn wall_time_reaper() -> &'static mpsc::Sender<WallTimeDeadline> {
+    static REAPER: OnceLock<mpsc::Sender<WallTimeDeadline>> = OnceLock::new();
+    REAPER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || run_wall_time_reaper(receiver));
+        sender
+    })
+}
+
+fn run_wall_time_reaper(receiver: mpsc::Receiver<WallTimeDeadline>) {
+    let mut pending: Vec<WallTimeDeadline> = Vec::new();
+    loop {
+        pending.retain(|entry| !entry.exited());
+        let received = match pending.iter().map(|entry| entry.deadline).min() {
+            Some(deadline) => {
+                receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(entry) => pending.push(entry),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        let now = Instant::now();
+        let (expired, waiting): (Vec<_>, Vec<_>) =
+            pending.drain(..).partition(|entry| entry.deadline <= now);
+        pending = waiting;
+        expired.into_iter().for_each(WallTimeDeadline::expire);
+    }
+}
+
 fn background_output_file_path(id: &str) -> Result<PathBuf> {
     let dir = std::env::temp_dir().join("workclaw-background-processes");
     fs::create_dir_all(&dir)?;
@@ -33,6 +33,7 @@ impl Tool for ReadFileTool {
         ToolMetadata {
             category: ToolCategory::File,
             read_only: true,
+            concurrency_safe: true,
             ..ToolMetadata::default()
         }
     }
@@ -1,25 +1,44 @@
-/// 搜索结果 LRU + TTL 缓存
+/// 搜索结果 / 网页内容的 LRU + TTL 缓存
 ///
-/// 缓存键格式：`"{provider}:{query_lowercase}:{count}"`
+/// 缓存键格式：搜索为 `"{provider}:{query_lowercase}:{count}"`，其他内容为 `"{namespace}:{key}"`
 /// - TTL 到期的条目在下次访问时被清除
 /// - 超出 max_size 时按插入顺序（最旧的）淘汰
 /// - 使用内部 Mutex 实现线程安全，支持 Arc<SearchCache> 共享
+/// - 可选磁盘层：条目同时写入 `{dir}/{sha256(key)}.json`，重启后仍可命中；
+///   启用时及每次写入后清理过期文件，并按 max_size 与 `MAX_DISK_BYTES` 淘汰最旧文件
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use super::SearchItem;
 
+/// 磁盘缓存目录的字节上限
+const MAX_DISK_BYTES: u64 = 64 * 1024 * 1024;
+
 /// 单条缓存记录
 struct CacheEntry {
-    /// 缓存的搜索结果
-    items: Vec<SearchItem>,
+    /// 缓存内容（JSON）
+    payload: Value,
     /// 写入时刻
     created_at: Instant,
     /// 插入序号，用于 LRU 淘汰排序
     seq: u64,
 }
 
+/// 磁盘缓存文件内容
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    /// 写入时间（Unix 毫秒）
+    stored_at_ms: u64,
+    payload: Value,
+}
+
 /// 缓存内部可变状态
 struct CacheState {
     /// 缓存存储
@@ -36,6 +55,15 @@ pub struct SearchCache {
     ttl: Duration,
     /// 最大缓存条目数
     max_size: usize,
+    /// 磁盘缓存目录，None 表示仅内存缓存
+    disk_dir: Option<PathBuf>,
+}
+
+fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 impl SearchCache {
@@ -52,9 +80,17 @@ impl SearchCache {
             }),
             ttl,
             max_size,
+            disk_dir: None,
         }
     }
 
+    /// 启用磁盘缓存层，条目写入 `dir` 目录，并清理上次运行遗留的过期文件
+    pub fn with_disk_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.disk_dir = Some(dir.into());
+        self.sweep_disk();
+        self
+    }
+
     /// 构造缓存键
     fn make_key(provider: &str, query: &str, count: usize) -> String {
         format!("{}:{}:{}", provider, query.to_lowercase(), count)
@@ -62,28 +98,71 @@ impl SearchCache {
 
     /// 查询缓存，未命中或已过期则返回 None
     pub fn get(&self, provider: &str, query: &str, count: usize) -> Option<Vec<SearchItem>> {
-        let key = Self::make_key(provider, query, count);
-        let mut state = self.state.lock().unwrap();
-        if let Some(entry) = state.store.get(&key) {
-            if entry.created_at.elapsed() < self.ttl {
-                return Some(entry.items.clone());
-            }
-            // TTL 过期，移除条目
-            state.store.remove(&key);
-        }
-        None
+        let payload = self.get_entry(&Self::make_key(provider, query, count))?;
+        serde_json::from_value(payload).ok()
     }
 
     /// 写入缓存，若超出 max_size 则淘汰最旧条目
     pub fn put(&self, provider: &str, query: &str, count: usize, items: Vec<SearchItem>) {
-        let key = Self::make_key(provider, query, count);
+        if let Ok(payload) = serde_json::to_value(items) {
+            self.put_entry(Self::make_key(provider, query, count), payload);
+        }
+    }
+
+    /// 按命名空间查询任意 JSON 内容（如 web_fetch 的网页正文）
+    pub fn get_json(&self, namespace: &str, key: &str) -> Option<Value> {
+        self.get_entry(&format!("{}:{}", namespace, key))
+    }
+
+    /// 按命名空间写入任意 JSON 内容
+    pub fn put_json(&self, namespace: &str, key: &str, payload: Value) {
+        self.put_entry(format!("{}:{}", namespace, key), payload);
+    }
+
+    fn get_entry(&self, key: &str) -> Option<Value> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(entry) = state.store.get(key) {
+                if entry.created_at.elapsed() < self.ttl {
+                    return Some(entry.payload.clone());
+                }
+                // TTL 过期，移除条目
+                state.store.remove(key);
+            }
+        }
+
+        // 内存未命中时回落到磁盘层，命中后回填内存（保留原写入时间）
+        let (payload, age) = self.read_disk(key)?;
+        let mut state = self.state.lock().unwrap();
+        let created_at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+        Self::insert_entry(
+            &mut state,
+            self.max_size,
+            key.to_string(),
+            payload.clone(),
+            created_at,
+        );
+        Some(payload)
+    }
+
+    fn put_entry(&self, key: String, payload: Value) {
+        self.write_disk(&key, &payload);
         let mut state = self.state.lock().unwrap();
+        Self::insert_entry(&mut state, self.max_size, key, payload, Instant::now());
+    }
 
+    fn insert_entry(
+        state: &mut CacheState,
+        max_size: usize,
+        key: String,
+        payload: Value,
+        created_at: Instant,
+    ) {
         // 若 key 已存在则先移除（后面重新插入以刷新 seq）
         state.store.remove(&key);
 
         // 超出容量时淘汰插入序号最小（最旧）的条目
-        if state.store.len() >= self.max_size {
+        if state.store.len() >= max_size {
             if let Some(oldest_key) = state
                 .store
                 .iter()
@@ -99,13 +178,108 @@ impl SearchCache {
         state.store.insert(
             key,
             CacheEntry {
-                items,
-                created_at: Instant::now(),
+                payload,
+                created_at,
                 seq,
             },
         );
     }
 
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        let dir = self.disk_dir.as_ref()?;
+        let digest = Sha256::digest(key.as_bytes());
+        let name = digest
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        Some(dir.join(format!("{}.json", name)))
+    }
+
+    /// 读取磁盘条目，返回内容与已存在时长；过期或损坏的文件直接删除
+    fn read_disk(&self, key: &str) -> Option<(Value, Duration)> {
+        let path = self.disk_path(key)?;
+        let raw = std::fs::read(&path).ok()?;
+        let entry = match serde_json::from_slice::<DiskEntry>(&raw) {
+            Ok(entry) if entry.key == key => entry,
+            _ => {
+                let _ = std::fs::remove_file(&path);
+                return None;
+            }
+        };
+        let age = Duration::from_millis(unix_now_ms().saturating_sub(entry.stored_at_ms));
+        if age >= self.ttl {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some((entry.payload, age))
+    }
+
+    /// 磁盘缓存是尽力而为的，写入失败不影响调用方
+    fn write_disk(&self, key: &str, payload: &Value) {
+        let Some(path) = self.disk_path(key) else {
+            return;
+        };
+        let entry = DiskEntry {
+            key: key.to_string(),
+            stored_at_ms: unix_now_ms(),
+            payload: payload.clone(),
+        };
+        let Ok(raw) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let tmp = path.with_extension("json.tmp");
+        if std::fs::write(&tmp, raw).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        self.sweep_disk();
+    }
+
+    /// 删除过期文件，并从最旧的文件开始淘汰，直到条目数不超过 max_size、
+    /// 总大小不超过 `MAX_DISK_BYTES`。以文件修改时间作为写入时间
+    fn sweep_disk(&self) {
+        let Some(dir) = self.disk_dir.as_ref() else {
+            return;
+        };
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut files = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            let age = modified.elapsed().unwrap_or_default();
+            if age >= self.ttl {
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+            // 写入中的临时文件不计入容量
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                files.push((modified, metadata.len(), path));
+            }
+        }
+
+        files.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+        let mut kept_bytes = 0u64;
+        for (index, (_, len, path)) in files.into_iter().enumerate() {
+            if index < self.max_size && kept_bytes + len <= MAX_DISK_BYTES {
+                kept_bytes += len;
+            } else {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
     /// 返回当前缓存条目数
     #[allow(dead_code)]
     pub fn len(&self) -> usize {
@@ -228,4 +402,73 @@ mod tests {
         let result2 = cache.get("brave", "RUST LANGUAGE", 5);
         assert!(result2.is_some(), "全大写查询词也应命中缓存");
     }
+
+    #[test]
+    fn test_disk_layer_survives_new_instance() {
+        // 开启磁盘层后，新实例（模拟重启）仍能命中搜索与 JSON 条目
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SearchCache::new(Duration::from_secs(60), 100).with_disk_dir(dir.path());
+        cache.put("brave", "rust language", 5, make_items(2));
+        cache.put_json(
+            "web_fetch",
+            "https://example.com/",
+            serde_json::json!({"title": "示例"}),
+        );
+
+        let reopened = SearchCache::new(Duration::from_secs(60), 100).with_disk_dir(dir.path());
+        assert_eq!(reopened.get("brave", "Rust Language", 5).unwrap().len(), 2);
+        assert_eq!(
+            reopened
+                .get_json("web_fetch", "https://example.com/")
+                .unwrap()["title"],
+            "示例"
+        );
+        assert!(reopened
+            .get_json("web_fetch", "https://example.com/other")
+            .is_none());
+        assert!(reopened.get_json("other", "https://example.com/").is_none());
+    }
+
+    #[test]
+    fn test_disk_layer_expiry_removes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SearchCache::new(Duration::from_secs(60), 100).with_disk_dir(dir.path());
+        cache.put_json(
+            "web_fetch",
+            "https://example.com/",
+            serde_json::json!("正文"),
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        std::thread::sleep(Duration::from_millis(10));
+        let expired = SearchCache::new(Duration::from_millis(1), 100).with_disk_dir(dir.path());
+        assert!(expired
+            .get_json("web_fetch", "https://example.com/")
+            .is_none());
+        assert_eq!(
+            std::fs::read_dir(dir.path()).unwrap().count(),
+            0,
+            "过期文件应被删除"
+        );
+    }
+
+    #[test]
+    fn test_disk_layer_evicts_oldest_files_and_sweeps_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_count = || std::fs::read_dir(dir.path()).unwrap().count();
+        let cache = SearchCache::new(Duration::from_secs(60), 2).with_disk_dir(dir.path());
+        for query in ["query_a", "query_b", "query_c"] {
+            cache.put("brave", query, 5, make_items(1));
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(file_count(), 2, "磁盘条目数应不超过 max_size");
+
+        let reopened = SearchCache::new(Duration::from_secs(60), 2).with_disk_dir(dir.path());
+        assert!(reopened.get("brave", "query_a", 5).is_none());
+        assert!(reopened.get("brave", "query_c", 5).is_some());
+
+        // 启用磁盘层时即清理过期文件，无需等到读取
+        let _expired = SearchCache::new(Duration::from_millis(1), 2).with_disk_dir(dir.path());
+        assert_eq!(file_count(), 0, "过期文件应在启动时被清理");
+    }
 }
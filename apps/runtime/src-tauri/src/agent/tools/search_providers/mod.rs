@@ -43,7 +43,7 @@ pub struct SearchResponse {
 }
 
 /// 单条搜索结果
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SearchItem {
     /// 结果标题
     pub title: String,
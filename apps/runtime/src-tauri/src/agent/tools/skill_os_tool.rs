@@ -1,9 +1,11 @@
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::{Value, json};
 use sqlx::SqlitePool;
+use std::sync::Arc;
 
-use crate::agent::types::{Tool, ToolContext};
+use crate::agent::types::{Tool, ToolContext, ToolExecution};
 
 pub struct SkillOsTool {
     pool: SqlitePool,
@@ -41,21 +43,10 @@ impl SkillOsTool {
         Self { pool }
     }
 
-    fn block_on<T, F>(&self, fut: F) -> Result<T>
-    where
-        F: std::future::Future<Output = std::result::Result<T, String>>,
-    {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .map_err(|err| anyhow!("构建 skills tool runtime 失败: {err}"))?;
-        rt.block_on(fut).map_err(|err| anyhow!(err))
-    }
-
-    fn list_skills(&self) -> Result<String> {
-        let items = self.block_on(
-            crate::agent::runtime::runtime_io::list_skill_os_index_with_pool(&self.pool),
-        )?;
+    async fn list_skills(&self) -> Result<String> {
+        let items = crate::agent::runtime::runtime_io::list_skill_os_index_with_pool(&self.pool)
+            .await
+            .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skills_list",
             "items": items
@@ -63,21 +54,22 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 Skill OS index 失败: {err}"))
     }
 
-    fn view_skill(&self, input: &Value) -> Result<String> {
+    async fn view_skill(&self, input: &Value) -> Result<String> {
         let skill_id = input["skill_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_view 操作缺少 skill_id 参数"))?;
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(&self.pool, skill_id),
-        )?;
+        let view =
+            crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(&self.pool, skill_id)
+                .await
+                .map_err(anyhow::Error::msg)?;
         let Some(view) = view else {
             return Err(anyhow!("Skill 不存在: {skill_id}"));
         };
-        self.block_on(
-            crate::agent::runtime::runtime_io::record_skill_os_usage_with_pool(
-                &self.pool, skill_id, "view",
-            ),
-        )?;
+        crate::agent::runtime::runtime_io::record_skill_os_usage_with_pool(
+            &self.pool, skill_id, "view",
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_view",
             "skill": view
@@ -170,12 +162,11 @@ impl SkillOsTool {
         .map(|value| value.unwrap_or_default())
     }
 
-    fn profile_id_for_context(&self, ctx: &ToolContext) -> Result<String> {
-        self.block_on(Self::resolve_profile_id_for_session(
-            &self.pool,
-            ctx.session_id.as_deref(),
-        ))
-        .map(|profile_id| profile_id.trim().to_string())
+    async fn profile_id_for_context(&self, ctx: &ToolContext) -> Result<String> {
+        Self::resolve_profile_id_for_session(&self.pool, ctx.session_id.as_deref())
+            .await
+            .map_err(anyhow::Error::msg)
+            .map(|profile_id| profile_id.trim().to_string())
     }
 
     async fn record_growth_event(
@@ -216,7 +207,7 @@ impl SkillOsTool {
         Ok(id)
     }
 
-    fn patch_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn patch_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         let skill_id = input["skill_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_patch 操作缺少 skill_id 参数"))?;
@@ -224,30 +215,27 @@ impl SkillOsTool {
             .as_str()
             .ok_or_else(|| anyhow!("skill_patch 操作缺少 content 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let before = self
-            .block_on(
-                crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(
-                    &self.pool, skill_id,
-                ),
-            )?
-            .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
+        let before =
+            crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(&self.pool, skill_id)
+                .await
+                .map_err(anyhow::Error::msg)?
+                .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
         let diff = Self::line_diff(&before.content, content);
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::patch_skill_os_entry_with_pool(
-                &self.pool, skill_id, content, summary,
-            ),
-        )?;
-        let version_id = self
-            .block_on(
-                crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                    &self.pool, skill_id, 1,
-                ),
-            )?
-            .into_iter()
-            .next()
-            .map(|version| version.version_id)
-            .unwrap_or_default();
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let view = crate::agent::runtime::runtime_io::patch_skill_os_entry_with_pool(
+            &self.pool, skill_id, content, summary,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
+        let version_id = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool, skill_id, 1,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .next()
+        .map(|version| version.version_id)
+        .unwrap_or_default();
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_patch",
@@ -258,7 +246,9 @@ impl SkillOsTool {
                 "source_type": view.entry.source.raw_source_type,
                 "diff": diff
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_patch",
             "skill": view,
@@ -269,37 +259,36 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_patch 结果失败: {err}"))
     }
 
-    fn create_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn create_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         let name = input["name"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_create 操作缺少 name 参数"))?;
         let description = input["description"].as_str().unwrap_or_default();
         let content = normalized_skill_create_content(input, name, description)?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let profile_id = self.profile_id_for_context(ctx)?;
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::create_agent_skill_os_entry_with_pool(
-                &self.pool,
-                &profile_id,
-                name,
-                description,
-                &content,
-                summary,
-            ),
-        )?;
-        let version_id = self
-            .block_on(
-                crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                    &self.pool,
-                    &view.entry.skill_id,
-                    1,
-                ),
-            )?
-            .into_iter()
-            .next()
-            .map(|version| version.version_id)
-            .unwrap_or_default();
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let profile_id = self.profile_id_for_context(ctx).await?;
+        let view = crate::agent::runtime::runtime_io::create_agent_skill_os_entry_with_pool(
+            &self.pool,
+            &profile_id,
+            name,
+            description,
+            &content,
+            summary,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
+        let version_id = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool,
+            &view.entry.skill_id,
+            1,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .next()
+        .map(|version| version.version_id)
+        .unwrap_or_default();
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_create",
@@ -310,7 +299,9 @@ impl SkillOsTool {
                 "source_type": view.entry.source.raw_source_type,
                 "created_path": ""
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_create",
             "skill": view,
@@ -320,7 +311,7 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_create 结果失败: {err}"))
     }
 
-    fn archive_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn archive_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         if input["confirm"].as_bool() != Some(true) {
             return Ok("skill_archive 是高风险操作，需要 confirm=true".to_string());
         }
@@ -328,22 +319,21 @@ impl SkillOsTool {
             .as_str()
             .ok_or_else(|| anyhow!("skill_archive 操作缺少 skill_id 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::archive_skill_os_entry_with_pool(
-                &self.pool, skill_id, summary,
-            ),
-        )?;
-        let version_id = self
-            .block_on(
-                crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                    &self.pool, skill_id, 1,
-                ),
-            )?
-            .into_iter()
-            .next()
-            .map(|version| version.version_id)
-            .unwrap_or_default();
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let view = crate::agent::runtime::runtime_io::archive_skill_os_entry_with_pool(
+            &self.pool, skill_id, summary,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
+        let version_id = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool, skill_id, 1,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .next()
+        .map(|version| version.version_id)
+        .unwrap_or_default();
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_archive",
@@ -353,7 +343,9 @@ impl SkillOsTool {
                 "version_id": version_id,
                 "source_type": view.entry.source.raw_source_type
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_archive",
             "skill": view,
@@ -363,27 +355,26 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_archive 结果失败: {err}"))
     }
 
-    fn restore_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn restore_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         let skill_id = input["skill_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_restore 操作缺少 skill_id 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::restore_skill_os_entry_with_pool(
-                &self.pool, skill_id, summary,
-            ),
-        )?;
-        let version_id = self
-            .block_on(
-                crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                    &self.pool, skill_id, 1,
-                ),
-            )?
-            .into_iter()
-            .next()
-            .map(|version| version.version_id)
-            .unwrap_or_default();
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let view = crate::agent::runtime::runtime_io::restore_skill_os_entry_with_pool(
+            &self.pool, skill_id, summary,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
+        let version_id = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool, skill_id, 1,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .next()
+        .map(|version| version.version_id)
+        .unwrap_or_default();
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_restore",
@@ -393,7 +384,9 @@ impl SkillOsTool {
                 "version_id": version_id,
                 "source_type": view.entry.source.raw_source_type
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_restore",
             "skill": view,
@@ -403,7 +396,7 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_restore 结果失败: {err}"))
     }
 
-    fn delete_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn delete_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         if input["confirm"].as_bool() != Some(true) {
             return Ok("skill_delete 是高风险操作，需要 confirm=true".to_string());
         }
@@ -411,12 +404,13 @@ impl SkillOsTool {
             .as_str()
             .ok_or_else(|| anyhow!("skill_delete 操作缺少 skill_id 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let (view, version, removed_path, removed_files) = self.block_on(
+        let (view, version, removed_path, removed_files) =
             crate::agent::runtime::runtime_io::delete_skill_os_entry_with_pool(
                 &self.pool, skill_id, summary,
-            ),
-        )?;
-        let growth_event_id = self.block_on(Self::record_growth_event(
+            )
+            .await
+            .map_err(anyhow::Error::msg)?;
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_delete",
@@ -428,7 +422,9 @@ impl SkillOsTool {
                 "removed_path": removed_path,
                 "removed_files": removed_files
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_delete",
             "skill": view,
@@ -440,16 +436,16 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_delete 结果失败: {err}"))
     }
 
-    fn list_versions(&self, input: &Value) -> Result<String> {
+    async fn list_versions(&self, input: &Value) -> Result<String> {
         let skill_id = input["skill_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_versions 操作缺少 skill_id 参数"))?;
         let limit = input["limit"].as_i64().unwrap_or(20);
-        let items = self.block_on(
-            crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                &self.pool, skill_id, limit,
-            ),
-        )?;
+        let items = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool, skill_id, limit,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_versions",
             "items": items
@@ -457,18 +453,18 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_versions 结果失败: {err}"))
     }
 
-    fn view_version(&self, input: &Value) -> Result<String> {
+    async fn view_version(&self, input: &Value) -> Result<String> {
         let skill_id = input["skill_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_view_version 操作缺少 skill_id 参数"))?;
         let version_id = input["version_id"]
             .as_str()
             .ok_or_else(|| anyhow!("skill_view_version 操作缺少 version_id 参数"))?;
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::view_skill_os_version_with_pool(
-                &self.pool, skill_id, version_id,
-            ),
-        )?;
+        let view = crate::agent::runtime::runtime_io::view_skill_os_version_with_pool(
+            &self.pool, skill_id, version_id,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         let Some(view) = view else {
             return Err(anyhow!("skill version 不存在: {version_id}"));
         };
@@ -479,7 +475,7 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_view_version 结果失败: {err}"))
     }
 
-    fn rollback_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn rollback_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         if input["confirm"].as_bool() != Some(true) {
             return Ok("skill_rollback 是高风险操作，需要 confirm=true".to_string());
         }
@@ -490,20 +486,18 @@ impl SkillOsTool {
             .as_str()
             .ok_or_else(|| anyhow!("skill_rollback 操作缺少 version_id 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let before = self
-            .block_on(
-                crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(
-                    &self.pool, skill_id,
-                ),
-            )?
-            .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
-        let view = self.block_on(
-            crate::agent::runtime::runtime_io::rollback_skill_os_entry_with_pool(
-                &self.pool, skill_id, version_id, summary,
-            ),
-        )?;
+        let before =
+            crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(&self.pool, skill_id)
+                .await
+                .map_err(anyhow::Error::msg)?
+                .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
+        let view = crate::agent::runtime::runtime_io::rollback_skill_os_entry_with_pool(
+            &self.pool, skill_id, version_id, summary,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         let diff = Self::line_diff(&before.content, &view.content);
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_rollback",
@@ -514,7 +508,9 @@ impl SkillOsTool {
                 "source_type": view.entry.source.raw_source_type,
                 "diff": diff
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_rollback",
             "skill": view,
@@ -525,7 +521,7 @@ impl SkillOsTool {
         .map_err(|err| anyhow!("序列化 skill_rollback 结果失败: {err}"))
     }
 
-    fn reset_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
+    async fn reset_skill(&self, input: &Value, ctx: &ToolContext) -> Result<String> {
         if input["confirm"].as_bool() != Some(true) {
             return Ok("skill_reset 是高风险操作，需要 confirm=true".to_string());
         }
@@ -533,30 +529,28 @@ impl SkillOsTool {
             .as_str()
             .ok_or_else(|| anyhow!("skill_reset 操作缺少 skill_id 参数"))?;
         let summary = input["summary"].as_str().unwrap_or_default();
-        let before = self
-            .block_on(
-                crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(
-                    &self.pool, skill_id,
-                ),
-            )?
-            .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
-        let (view, reset_to_version_id) = self.block_on(
+        let before =
+            crate::agent::runtime::runtime_io::view_skill_os_entry_with_pool(&self.pool, skill_id)
+                .await
+                .map_err(anyhow::Error::msg)?
+                .ok_or_else(|| anyhow!("Skill 不存在: {skill_id}"))?;
+        let (view, reset_to_version_id) =
             crate::agent::runtime::runtime_io::reset_skill_os_entry_with_pool(
                 &self.pool, skill_id, summary,
-            ),
-        )?;
+            )
+            .await
+            .map_err(anyhow::Error::msg)?;
         let diff = Self::line_diff(&before.content, &view.content);
-        let version_id = self
-            .block_on(
-                crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
-                    &self.pool, skill_id, 1,
-                ),
-            )?
-            .into_iter()
-            .next()
-            .map(|version| version.version_id)
-            .unwrap_or_default();
-        let growth_event_id = self.block_on(Self::record_growth_event(
+        let version_id = crate::agent::runtime::runtime_io::list_skill_os_versions_with_pool(
+            &self.pool, skill_id, 1,
+        )
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .next()
+        .map(|version| version.version_id)
+        .unwrap_or_default();
+        let growth_event_id = Self::record_growth_event(
             &self.pool,
             ctx,
             "skill_reset",
@@ -568,7 +562,9 @@ impl SkillOsTool {
                 "source_type": view.entry.source.raw_source_type,
                 "diff": diff
             }),
-        ))?;
+        )
+        .await
+        .map_err(anyhow::Error::msg)?;
         serde_json::to_string_pretty(&json!({
             "action": "skill_reset",
             "skill": view,
@@ -581,6 +577,7 @@ impl SkillOsTool {
     }
 }
 
+#[async_trait]
 impl Tool for SkillOsTool {
     fn name(&self) -> &str {
         "skills"
@@ -640,19 +637,28 @@ impl Tool for SkillOsTool {
         })
     }
 
-    fn execute(&self, input: Value, _ctx: &ToolContext) -> Result<String> {
+    fn execute(&self, _input: Value, _ctx: &ToolContext) -> Result<String> {
+        Err(anyhow!("skills 工具需要通过 execute_async 调用"))
+    }
+
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        ctx: ToolContext,
+        _execution: ToolExecution,
+    ) -> Result<String> {
         match input["action"].as_str().unwrap_or_default() {
-            "skills_list" => self.list_skills(),
-            "skill_view" => self.view_skill(&input),
-            "skill_create" => self.create_skill(&input, _ctx),
-            "skill_patch" => self.patch_skill(&input, _ctx),
-            "skill_archive" => self.archive_skill(&input, _ctx),
-            "skill_restore" => self.restore_skill(&input, _ctx),
-            "skill_delete" => self.delete_skill(&input, _ctx),
-            "skill_versions" => self.list_versions(&input),
-            "skill_view_version" => self.view_version(&input),
-            "skill_rollback" => self.rollback_skill(&input, _ctx),
-            "skill_reset" => self.reset_skill(&input, _ctx),
+            "skills_list" => self.list_skills().await,
+            "skill_view" => self.view_skill(&input).await,
+            "skill_create" => self.create_skill(&input, &ctx).await,
+            "skill_patch" => self.patch_skill(&input, &ctx).await,
+            "skill_archive" => self.archive_skill(&input, &ctx).await,
+            "skill_restore" => self.restore_skill(&input, &ctx).await,
+            "skill_delete" => self.delete_skill(&input, &ctx).await,
+            "skill_versions" => self.list_versions(&input).await,
+            "skill_view_version" => self.view_version(&input).await,
+            "skill_rollback" => self.rollback_skill(&input, &ctx).await,
+            "skill_reset" => self.reset_skill(&input, &ctx).await,
             action => Err(anyhow!("未知 skills 操作: {}", action)),
         }
     }
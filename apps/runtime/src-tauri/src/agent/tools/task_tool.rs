@@ -4,7 +4,7 @@ use crate::agent::runtime::child_session_runtime::{
     run_hidden_child_session, ChildSessionRunRequest,
 };
 use crate::agent::types::{StreamDelta, Tool, ToolContext};
-use crate::agent::{AgentExecutor, ToolRegistry};
+use crate::agent::{AgentExecutor, AgentTurnRequest, ToolRegistry};
 use crate::session_journal::SessionJournalStore;
 use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
@@ -149,23 +149,22 @@ impl TaskTool {
 
         sub_executor
             .execute_turn(
-                &api_format,
-                &base_url,
-                &api_key,
-                &model,
-                &system_prompt,
-                messages,
+                AgentTurnRequest {
+                    api_format: &api_format,
+                    base_url: &base_url,
+                    api_key: &api_key,
+                    model: &model,
+                    skill_system_prompt: &system_prompt,
+                    messages,
+                    app_handle: app_handle.as_ref(),
+                    session_id: session_id.as_deref(),
+                    allowed_tools: allowed_tools.as_deref(),
+                    permission_mode: PermissionMode::Unrestricted,
+                    work_dir,
+                    max_iterations_override: Some(max_iter),
+                    ..AgentTurnRequest::default()
+                },
                 on_token,
-                app_handle.as_ref(),
-                session_id.as_deref(),
-                allowed_tools.as_deref(),
-                PermissionMode::Unrestricted,
-                None,
-                work_dir,
-                Some(max_iter),
-                None,
-                None,
-                None,
             )
             .await
     }
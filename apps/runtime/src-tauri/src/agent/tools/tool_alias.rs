@@ -1,6 +1,7 @@
 use crate::agent::registry::ToolRegistry;
-use crate::agent::types::{Tool, ToolContext};
+use crate::agent::types::{Tool, ToolContext, ToolExecution};
 use anyhow::Result;
+use async_trait::async_trait;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -9,6 +10,7 @@ struct ToolAlias {
     inner: Arc<dyn Tool>,
 }
 
+#[async_trait]
 impl Tool for ToolAlias {
     fn name(&self) -> &str {
         &self.alias
@@ -25,6 +27,17 @@ impl Tool for ToolAlias {
     fn execute(&self, input: Value, ctx: &ToolContext) -> Result<String> {
         self.inner.execute(input, ctx)
     }
+
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        ctx: ToolContext,
+        execution: ToolExecution,
+    ) -> Result<String> {
+        Arc::clone(&self.inner)
+            .execute_async(input, ctx, execution)
+            .await
+    }
 }
 
 pub fn register_tool_alias(registry: &ToolRegistry, alias: &str, inner: Arc<dyn Tool>) {
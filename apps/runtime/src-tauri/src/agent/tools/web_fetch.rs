@@ -1,12 +1,69 @@
 use anyhow::{anyhow, Result};
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::agent::tool_manifest::{ToolCategory, ToolMetadata};
+use crate::agent::tools::search_providers::cache::SearchCache;
 use crate::agent::types::{Tool, ToolContext};
-use runtime_executor_core::truncate_tool_output;
+use crate::web_fetch_policy::{current_web_fetch_policy, WebFetchPolicy};
 
-/// 获取指定 URL 的内容，自动清洗 HTML 标签
-pub struct WebFetchTool;
+#[path = "web_fetch/charset.rs"]
+mod charset;
+#[path = "web_fetch/extract.rs"]
+mod extract;
+#[path = "web_fetch/guard.rs"]
+mod guard;
+#[path = "web_fetch/robots.rs"]
+mod robots;
+
+/// 单次返回的默认字符数
+const DEFAULT_MAX_CHARS: usize = 20_000;
+/// 单次返回的字符数上限
+const MAX_CHARS_LIMIT: usize = 100_000;
+const PAGE_CACHE_NAMESPACE: &str = "web_fetch";
+const ROBOTS_CACHE_NAMESPACE: &str = "web_fetch_robots";
+
+/// 获取指定 URL 的网页正文，转换为 Markdown 并支持分页读取
+pub struct WebFetchTool {
+    /// 与 web_search 共享的缓存，分页读取同一网页时不重复请求
+    cache: Option<Arc<SearchCache>>,
+}
+
+impl WebFetchTool {
+    pub fn new() -> Self {
+        Self { cache: None }
+    }
+
+    pub fn with_cache(cache: Arc<SearchCache>) -> Self {
+        Self { cache: Some(cache) }
+    }
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 抓取并提取后的网页，按 URL 缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FetchedPage {
+    final_url: String,
+    title: Option<String>,
+    charset: String,
+    content: String,
+}
+
+enum FetchOutcome {
+    Page(FetchedPage),
+    /// 非文本资源，直接返回提示
+    Unsupported(String),
+}
 
 impl Tool for WebFetchTool {
     fn name(&self) -> &str {
@@ -14,7 +71,7 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "获取指定 URL 的网页内容，自动移除 HTML 标签并返回纯文本"
+        "获取指定 URL 的网页正文，提取为保留标题、列表、链接和表格的 Markdown。长网页可用 offset/max_chars 分页读取。默认不允许访问本机和内网地址"
     }
 
     fn input_schema(&self) -> Value {
@@ -24,6 +81,14 @@ impl Tool for WebFetchTool {
                 "url": {
                     "type": "string",
                     "description": "要获取的 URL 地址"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "从正文的第几个字符开始返回，默认 0。用于继续读取长网页"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": format!("本次最多返回的字符数，默认 {DEFAULT_MAX_CHARS}，上限 {MAX_CHARS_LIMIT}")
                 }
             },
             "required": ["url"]
@@ -43,48 +108,214 @@ impl Tool for WebFetchTool {
         let url = input["url"]
             .as_str()
             .ok_or_else(|| anyhow!("缺少 url 参数"))?;
+        let url = Url::parse(url.trim()).map_err(|e| anyhow!("URL 格式无效: {}", e))?;
+        let offset = input["offset"].as_u64().unwrap_or(0) as usize;
+        let max_chars = input["max_chars"]
+            .as_u64()
+            .map(|value| (value as usize).clamp(1, MAX_CHARS_LIMIT))
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        let policy = current_web_fetch_policy();
+        // 先按当前策略校验地址，缓存里的页面不能绕过后来收紧的访问限制
+        guard::check_url(&url, &policy)?;
+        let cache = self.cache.as_deref().filter(|_| policy.cache_enabled);
+        let cached = cache
+            .and_then(|cache| cache.get_json(PAGE_CACHE_NAMESPACE, url.as_str()))
+            .and_then(|payload| serde_json::from_value::<FetchedPage>(payload).ok());
+        let page = match cached {
+            Some(page) => page,
+            None => match fetch_page(&url, &policy, cache)? {
+                FetchOutcome::Page(page) => {
+                    if let (Some(cache), Ok(payload)) = (cache, serde_json::to_value(&page)) {
+                        cache.put_json(PAGE_CACHE_NAMESPACE, url.as_str(), payload);
+                    }
+                    page
+                }
+                FetchOutcome::Unsupported(message) => return Ok(message),
+            },
+        };
+
+        paginate(&page, offset, max_chars)
+    }
+}
 
-        // 构建带超时的 HTTP 客户端
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+fn build_client(
+    policy: &WebFetchPolicy,
+    target: &guard::CheckedTarget,
+) -> Result<reqwest::blocking::Client> {
+    Ok(reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(policy.timeout_secs))
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent(policy.user_agent.clone())
+        // 走代理时由代理解析主机名，已校验的地址就失效了，因此直连
+        .no_proxy()
+        // 固定使用已校验过的解析结果，避免请求时 DNS 被重绑定到内网地址
+        .resolve_to_addrs(&target.host, &target.addrs)
+        .build()?)
+}
 
-        let resp = client
-            .get(url)
-            .header("User-Agent", "WorkClaw/1.0")
-            .send()?;
+/// 手动跟随重定向，每一跳都重新做地址校验与 robots.txt 检查
+fn fetch_page(
+    url: &Url,
+    policy: &WebFetchPolicy,
+    cache: Option<&SearchCache>,
+) -> Result<FetchOutcome> {
+    let mut current = url.clone();
+    for _ in 0..=policy.max_redirects {
+        let target = guard::check_url(&current, policy)?;
+        let client = build_client(policy, &target)?;
+        if policy.respect_robots_txt {
+            ensure_robots_allowed(&client, &current, policy, cache)?;
+        }
 
+        let resp = client.get(current.clone()).send()?;
         let status = resp.status();
+        if status.is_redirection() {
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| anyhow!("HTTP {} 重定向缺少 Location", status))?;
+            current = current
+                .join(location)
+                .map_err(|e| anyhow!("重定向地址无效 {}: {}", location, e))?;
+            continue;
+        }
         if !status.is_success() {
             return Err(anyhow!("HTTP 请求失败: {}", status));
         }
+        return read_page(resp, current, policy);
+    }
+    Err(anyhow!("重定向次数超过上限 {}", policy.max_redirects))
+}
+
+fn read_page(
+    resp: reqwest::blocking::Response,
+    final_url: Url,
+    policy: &WebFetchPolicy,
+) -> Result<FetchOutcome> {
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !content_type_is_text_like(&content_type) {
+        let normalized_content_type = if content_type.trim().is_empty() {
+            "application/octet-stream"
+        } else {
+            content_type.trim()
+        };
+        return Ok(FetchOutcome::Unsupported(format!(
+            "该 URL 返回的是非文本资源（content-type: {normalized_content_type}），无法按网页正文读取。请改用支持图片/二进制资源的工具。"
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    resp.take(policy.max_body_bytes).read_to_end(&mut bytes)?;
+    let (body, charset) = charset::decode_body(&bytes, &content_type);
 
-        let content_type = resp
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok())
-            .unwrap_or("")
-            .to_string();
-        if !content_type_is_text_like(&content_type) {
-            let normalized_content_type = if content_type.trim().is_empty() {
-                "application/octet-stream"
-            } else {
-                content_type.trim()
-            };
-            return Ok(truncate_tool_output(
-                &format!(
-                    "该 URL 返回的是非文本资源（content-type: {normalized_content_type}），无法按网页正文读取。请改用支持图片/二进制资源的工具。"
-                ),
-                30_000,
-            ));
+    let is_html = content_type.to_ascii_lowercase().contains("html")
+        || (content_type.trim().is_empty() && body.trim_start().starts_with('<'));
+    let (title, content) = if is_html {
+        let page = extract::extract_main_content(&body, Some(&final_url));
+        let content = if page.markdown.trim().is_empty() {
+            strip_html_tags(&body)
+        } else {
+            page.markdown
+        };
+        (page.title, content)
+    } else {
+        (None, body.trim().to_string())
+    };
+
+    Ok(FetchOutcome::Page(FetchedPage {
+        final_url: final_url.to_string(),
+        title,
+        charset: charset.to_string(),
+        content,
+    }))
+}
+
+/// robots.txt 取不到（4xx、5xx 或网络错误）时按允许处理
+fn ensure_robots_allowed(
+    client: &reqwest::blocking::Client,
+    url: &Url,
+    policy: &WebFetchPolicy,
+    cache: Option<&SearchCache>,
+) -> Result<()> {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return Ok(());
+    };
+    let origin = robots_url.as_str().to_string();
+    let robots_txt = match cache.and_then(|cache| cache.get_json(ROBOTS_CACHE_NAMESPACE, &origin)) {
+        Some(Value::String(text)) => text,
+        _ => {
+            let text = client
+                .get(robots_url)
+                .send()
+                .ok()
+                .filter(|resp| resp.status().is_success())
+                .and_then(|resp| resp.text().ok())
+                .unwrap_or_default();
+            if let Some(cache) = cache {
+                cache.put_json(ROBOTS_CACHE_NAMESPACE, &origin, Value::String(text.clone()));
+            }
+            text
         }
+    };
+
+    let path_and_query = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    if robots::robots_allows(&robots_txt, &policy.user_agent, &path_and_query) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} 的 robots.txt 不允许 {} 抓取该页面",
+            url.host_str().unwrap_or_default(),
+            policy.user_agent
+        ))
+    }
+}
 
-        let body = resp.text()?;
-        // 清洗 HTML 标签，提取纯文本内容
-        let cleaned = strip_html_tags(&body);
+/// 按字符切分正文；未读完时在末尾提示下一次的 offset
+fn paginate(page: &FetchedPage, offset: usize, max_chars: usize) -> Result<String> {
+    let total = page.content.chars().count();
+    if offset > 0 && offset >= total {
+        return Err(anyhow!(
+            "offset {} 超出正文长度（共 {} 字符）",
+            offset,
+            total
+        ));
+    }
+    let chunk = page
+        .content
+        .chars()
+        .skip(offset)
+        .take(max_chars)
+        .collect::<String>();
+    let end = offset + chunk.chars().count();
 
-        Ok(truncate_tool_output(&cleaned, 30_000))
+    let mut output = String::new();
+    if let Some(title) = page.title.as_deref().filter(|title| !title.is_empty()) {
+        output.push_str(&format!("标题: {}\n", title));
+    }
+    output.push_str(&format!("来源: {}\n", page.final_url));
+    let paged = offset > 0 || end < total;
+    if paged {
+        output.push_str(&format!(
+            "范围: 第 {}-{} 字符，共 {} 字符\n",
+            offset, end, total
+        ));
+    }
+    output.push('\n');
+    output.push_str(&chunk);
+    if end < total {
+        output.push_str(&format!("\n\n[正文未完，继续读取请使用 offset={}]", end));
     }
+    Ok(output)
 }
 
 /// 移除 HTML script/style 标签及所有 HTML 标签，压缩多余空行
@@ -137,7 +368,14 @@ fn content_type_is_text_like(content_type: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{content_type_is_text_like, strip_html_tags};
+    use super::{
+        content_type_is_text_like, paginate, strip_html_tags, FetchedPage, WebFetchTool,
+        PAGE_CACHE_NAMESPACE,
+    };
+    use crate::agent::tools::search_providers::cache::SearchCache;
+    use crate::agent::types::{Tool, ToolContext};
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn strip_html_tags_removes_markup_and_script_blocks() {
@@ -155,4 +393,51 @@ mod tests {
         assert!(!content_type_is_text_like("image/jpeg"));
         assert!(!content_type_is_text_like("application/pdf"));
     }
+
+    fn page(content: &str) -> FetchedPage {
+        FetchedPage {
+            final_url: "https://example.com/long".to_string(),
+            title: Some("长文".to_string()),
+            charset: "UTF-8".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn paginate_returns_ranges_and_next_offset_hint() {
+        let long = page("一二三四五六七八九十");
+
+        let first = paginate(&long, 0, 4).unwrap();
+        assert!(first.starts_with("标题: 长文\n来源: https://example.com/long\n"));
+        assert!(first.contains("范围: 第 0-4 字符，共 10 字符"));
+        assert!(first.contains("\n\n一二三四\n\n[正文未完，继续读取请使用 offset=4]"));
+
+        let last = paginate(&long, 8, 4).unwrap();
+        assert!(last.contains("范围: 第 8-10 字符，共 10 字符"));
+        assert!(last.ends_with("九十"));
+
+        assert!(paginate(&long, 10, 4).is_err());
+    }
+
+    #[test]
+    fn paginate_short_page_has_no_range_header() {
+        let output = paginate(&page("短文"), 0, 100).unwrap();
+        assert_eq!(output, "标题: 长文\n来源: https://example.com/long\n\n短文");
+    }
+
+    #[test]
+    fn cached_pages_are_still_checked_against_the_policy() {
+        let cache = Arc::new(SearchCache::new(Duration::from_secs(60), 16));
+        let url = "http://127.0.0.1:8080/admin";
+        cache.put_json(
+            PAGE_CACHE_NAMESPACE,
+            url,
+            serde_json::to_value(page("内网页面")).unwrap(),
+        );
+
+        let error = WebFetchTool::with_cache(cache)
+            .execute(serde_json::json!({ "url": url }), &ToolContext::default())
+            .expect_err("private address must be rejected");
+        assert!(!error.to_string().contains("内网页面"));
+    }
 }
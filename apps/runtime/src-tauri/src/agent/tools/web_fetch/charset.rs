@@ -0,0 +1,91 @@
+use encoding_rs::{Encoding, GB18030, UTF_8};
+
+/// 按 BOM、Content-Type、`<meta charset>` 的顺序确定编码并解码。
+/// 都没有声明时，合法 UTF-8 按 UTF-8 处理，否则按 GB18030（兼容 GBK/GB2312）解码。
+pub(super) fn decode_body(bytes: &[u8], content_type: &str) -> (String, &'static str) {
+    let encoding = Encoding::for_bom(bytes)
+        .map(|(encoding, _)| encoding)
+        .or_else(|| charset_from_content_type(content_type))
+        .or_else(|| sniff_meta_charset(bytes))
+        .unwrap_or_else(|| {
+            if std::str::from_utf8(bytes).is_ok() {
+                UTF_8
+            } else {
+                GB18030
+            }
+        });
+    let (text, actual, _) = encoding.decode(bytes);
+    (text.into_owned(), actual.name())
+}
+
+fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(
+            value
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .as_bytes(),
+        )
+    })
+}
+
+/// 只看文档开头 4KB，匹配 `<meta charset="gbk">` 与 `<meta http-equiv content="...; charset=gbk">`
+fn sniff_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]).to_ascii_lowercase();
+    let mut rest = head.as_str();
+    while let Some(index) = rest.find("<meta") {
+        rest = &rest[index + 5..];
+        let tag = &rest[..rest.find('>').unwrap_or(rest.len())];
+        if let Some(position) = tag.find("charset") {
+            let Some(value) = tag[position + "charset".len()..]
+                .trim_start()
+                .strip_prefix('=')
+            else {
+                continue;
+            };
+            let value = value.trim_start().trim_start_matches(['"', '\'']);
+            let end = value
+                .find(|c: char| c == '"' || c == '\'' || c == ';' || c == '/' || c.is_whitespace())
+                .unwrap_or(value.len());
+            if let Some(encoding) = Encoding::for_label(&value.as_bytes()[..end]) {
+                return Some(encoding);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_body;
+    use encoding_rs::GBK;
+
+    #[test]
+    fn decodes_gbk_from_header_meta_or_fallback() {
+        let (gbk_bytes, _, _) = GBK.encode("<p>中文网页</p>");
+
+        let (text, encoding) = decode_body(&gbk_bytes, "text/html; charset=GBK");
+        assert_eq!(text, "<p>中文网页</p>");
+        assert_eq!(encoding, "GBK");
+
+        let mut with_meta = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=gb2312\"></head>".to_vec();
+        with_meta.extend_from_slice(&gbk_bytes);
+        let (text, _) = decode_body(&with_meta, "text/html");
+        assert!(text.ends_with("<p>中文网页</p>"));
+
+        let (text, encoding) = decode_body(&gbk_bytes, "text/html");
+        assert_eq!(text, "<p>中文网页</p>");
+        assert_eq!(encoding, "gb18030");
+    }
+
+    #[test]
+    fn keeps_utf8_when_undeclared() {
+        let (text, encoding) = decode_body("<p>标题</p>".as_bytes(), "");
+        assert_eq!(text, "<p>标题</p>");
+        assert_eq!(encoding, "UTF-8");
+    }
+}
@@ -0,0 +1,615 @@
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+use regex::Regex;
+use reqwest::Url;
+use std::sync::OnceLock;
+
+/// 正文提取结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ExtractedPage {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// 与正文无关、直接丢弃的标签
+const NOISE_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object",
+    "embed", "form", "button", "select", "input", "textarea", "nav", "footer", "aside", "dialog",
+];
+
+const NOISE_ROLES: &[&str] = &[
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "dialog",
+    "search",
+];
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "body",
+    "center",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "ul",
+];
+
+/// 正文候选至少要有这么多字符，否则继续尝试下一种定位方式
+const MIN_CONTENT_CHARS: usize = 200;
+
+fn negative_attr_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(^|[\s_-])(comments?|sidebar|side-bar|footer|menu|navbar|nav|breadcrumbs?|share|sharing|social|related|recommend(ed)?|advert(isement)?|ads?|banner|popup|modal|cookie|subscribe|newsletter|sponsor)([\s_-]|$)",
+        )
+        .expect("valid negative attr regex")
+    })
+}
+
+fn positive_attr_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)article|content|main|post|entry|story|body-text")
+            .expect("valid positive attr regex")
+    })
+}
+
+fn tag_name(node: &NodeRef) -> Option<String> {
+    node.as_element()
+        .map(|element| element.name.local.to_string())
+}
+
+fn attr(node: &NodeRef, name: &str) -> Option<String> {
+    node.as_element()
+        .and_then(|element| element.attributes.borrow().get(name).map(str::to_string))
+}
+
+fn char_len(text: &str) -> usize {
+    text.trim().chars().count()
+}
+
+/// 提取网页正文并转换为 Markdown，保留标题、列表、链接、表格与代码块
+pub(super) fn extract_main_content(html: &str, base_url: Option<&Url>) -> ExtractedPage {
+    let document = kuchikiki::parse_html().one(html).document_node;
+    let title = page_title(&document);
+    strip_noise(&document);
+    let root = pick_content_root(&document);
+    let renderer = MarkdownRenderer { base_url };
+    let mut blocks = Vec::new();
+    renderer.blocks(&root, &mut blocks);
+    ExtractedPage {
+        title,
+        markdown: blocks.join("\n\n"),
+    }
+}
+
+fn page_title(document: &NodeRef) -> Option<String> {
+    let og_title = document
+        .select("meta[property='og:title']")
+        .ok()
+        .and_then(|mut matches| matches.next())
+        .and_then(|meta| attr(meta.as_node(), "content"));
+    let first_text = |selector: &str| {
+        document
+            .select(selector)
+            .ok()
+            .and_then(|mut matches| matches.next())
+            .map(|node| {
+                collapse_whitespace(&node.text_contents())
+                    .trim()
+                    .to_string()
+            })
+    };
+    og_title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .or_else(|| first_text("title").filter(|title| !title.is_empty()))
+        .or_else(|| first_text("h1").filter(|title| !title.is_empty()))
+}
+
+fn is_noise(node: &NodeRef) -> bool {
+    let Some(tag) = tag_name(node) else {
+        return matches!(node.data(), kuchikiki::NodeData::Comment(_));
+    };
+    if NOISE_TAGS.contains(&tag.as_str()) {
+        return true;
+    }
+    if attr(node, "hidden").is_some()
+        || attr(node, "aria-hidden").as_deref() == Some("true")
+        || attr(node, "role").is_some_and(|role| NOISE_ROLES.contains(&role.trim()))
+    {
+        return true;
+    }
+    if let Some(style) = attr(node, "style") {
+        let style = style.to_ascii_lowercase().replace(' ', "");
+        if style.contains("display:none") || style.contains("visibility:hidden") {
+            return true;
+        }
+    }
+    if matches!(tag.as_str(), "body" | "html" | "main" | "article") {
+        return false;
+    }
+    let class_and_id = format!(
+        "{} {}",
+        attr(node, "class").unwrap_or_default(),
+        attr(node, "id").unwrap_or_default()
+    );
+    negative_attr_re().is_match(&class_and_id) && !positive_attr_re().is_match(&class_and_id)
+}
+
+fn strip_noise(document: &NodeRef) {
+    let noisy = document.descendants().filter(is_noise).collect::<Vec<_>>();
+    for node in noisy {
+        node.detach();
+    }
+}
+
+/// 依次尝试 `<article>`、`<main>`，最后按段落密度打分选出正文容器
+fn pick_content_root(document: &NodeRef) -> NodeRef {
+    for selector in ["article", "main, [role='main']"] {
+        let best = document.select(selector).ok().and_then(|matches| {
+            matches
+                .map(|node| node.as_node().clone())
+                .max_by_key(|node| char_len(&node.text_contents()))
+        });
+        if let Some(node) = best.filter(|node| char_len(&node.text_contents()) >= MIN_CONTENT_CHARS)
+        {
+            return node;
+        }
+    }
+
+    let mut scores: Vec<(NodeRef, f64)> = Vec::new();
+    let mut add_score = |node: NodeRef, score: f64| {
+        if let Some(entry) = scores.iter_mut().find(|(candidate, _)| *candidate == node) {
+            entry.1 += score;
+        } else {
+            scores.push((node, score));
+        }
+    };
+    if let Ok(paragraphs) = document.select("p, pre, td, blockquote") {
+        for paragraph in paragraphs {
+            let text = paragraph.as_node().text_contents();
+            let len = char_len(&text);
+            if len < 25 {
+                continue;
+            }
+            let commas = text.matches([',', '，', '、', '。']).count() as f64;
+            let score = 1.0 + commas + (len as f64 / 100.0).min(3.0);
+            if let Some(parent) = paragraph.as_node().parent() {
+                if let Some(grandparent) = parent.parent() {
+                    add_score(grandparent, score / 2.0);
+                }
+                add_score(parent, score);
+            }
+        }
+    }
+    let best = scores
+        .into_iter()
+        .filter(|(node, _)| node.as_element().is_some())
+        .map(|(node, score)| {
+            let adjusted = score * (1.0 - link_density(&node));
+            (node, adjusted)
+        })
+        .max_by(|left, right| left.1.total_cmp(&right.1))
+        .map(|(node, _)| node);
+
+    best.or_else(|| {
+        document
+            .select("body")
+            .ok()
+            .and_then(|mut matches| matches.next())
+            .map(|body| body.as_node().clone())
+    })
+    .unwrap_or_else(|| document.clone())
+}
+
+fn link_density(node: &NodeRef) -> f64 {
+    let total = char_len(&node.text_contents());
+    if total == 0 {
+        return 1.0;
+    }
+    let linked = node
+        .select("a")
+        .map(|links| {
+            links
+                .map(|link| char_len(&link.as_node().text_contents()))
+                .sum::<usize>()
+        })
+        .unwrap_or(0);
+    (linked as f64 / total as f64).min(1.0)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+/// 按行整理行内文本：每行压缩空白并去掉首尾空格，丢弃空行
+fn tidy_inline(text: &str) -> String {
+    text.split('\n')
+        .map(|line| collapse_whitespace(line).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 给行内片段加上 Markdown 标记，首尾空白留在标记外侧
+fn wrap_inline(inner: &str, marker: &str) -> String {
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        return inner.to_string();
+    }
+    let leading = if inner.starts_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    let trailing = if inner.ends_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    format!("{leading}{marker}{trimmed}{marker}{trailing}")
+}
+
+struct MarkdownRenderer<'a> {
+    base_url: Option<&'a Url>,
+}
+
+impl MarkdownRenderer<'_> {
+    fn resolve_url(&self, raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        if raw.is_empty()
+            || raw.starts_with('#')
+            || raw.to_ascii_lowercase().starts_with("javascript:")
+        {
+            return None;
+        }
+        match self.base_url {
+            Some(base) => base.join(raw).ok().map(|url| url.to_string()),
+            None => Some(raw.to_string()),
+        }
+    }
+
+    /// 渲染容器的子节点：连续的行内内容合并为一段，块级元素单独成块
+    fn blocks(&self, node: &NodeRef, out: &mut Vec<String>) {
+        let mut inline = String::new();
+        for child in node.children() {
+            match tag_name(&child) {
+                Some(tag) if BLOCK_TAGS.contains(&tag.as_str()) => {
+                    push_paragraph(&mut inline, out);
+                    self.block(&child, &tag, out);
+                }
+                _ => inline.push_str(&self.inline(&child)),
+            }
+        }
+        push_paragraph(&mut inline, out);
+    }
+
+    fn block(&self, node: &NodeRef, tag: &str, out: &mut Vec<String>) {
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                let text = tidy_inline(&self.inline_children(node)).replace('\n', " ");
+                if !text.is_empty() {
+                    out.push(format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            "p" | "dt" | "dd" | "figcaption" | "summary" | "address" => {
+                let mut text = self.inline_children(node);
+                push_paragraph(&mut text, out);
+            }
+            "ul" | "ol" => {
+                let list = self.list(node, tag == "ol", 0);
+                if !list.is_empty() {
+                    out.push(list);
+                }
+            }
+            "li" => {
+                let text = tidy_inline(&self.inline_children(node));
+                if !text.is_empty() {
+                    out.push(format!("- {text}"));
+                }
+            }
+            "pre" => {
+                let code = node.text_contents();
+                let code = code.trim_matches('\n');
+                if !code.trim().is_empty() {
+                    out.push(format!("```{}\n{}\n```", code_language(node), code));
+                }
+            }
+            "blockquote" => {
+                let mut inner = Vec::new();
+                self.blocks(node, &mut inner);
+                if !inner.is_empty() {
+                    let quoted = inner
+                        .join("\n\n")
+                        .lines()
+                        .map(|line| {
+                            if line.is_empty() {
+                                ">".to_string()
+                            } else {
+                                format!("> {line}")
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    out.push(quoted);
+                }
+            }
+            "table" => match self.table(node) {
+                Some(table) => out.push(table),
+                None => self.blocks(node, out),
+            },
+            "hr" => out.push("---".to_string()),
+            _ => self.blocks(node, out),
+        }
+    }
+
+    fn inline_children(&self, node: &NodeRef) -> String {
+        node.children().map(|child| self.inline(&child)).collect()
+    }
+
+    fn inline(&self, node: &NodeRef) -> String {
+        if let Some(text) = node.as_text() {
+            return collapse_whitespace(&text.borrow());
+        }
+        let Some(tag) = tag_name(node) else {
+            return String::new();
+        };
+        match tag.as_str() {
+            "br" => "\n".to_string(),
+            "a" => {
+                let text = self.inline_children(node);
+                let label = tidy_inline(&text).replace('\n', " ");
+                if label.is_empty() {
+                    return String::new();
+                }
+                match attr(node, "href").and_then(|href| self.resolve_url(&href)) {
+                    Some(href) => {
+                        let leading = if text.starts_with(' ') { " " } else { "" };
+                        let trailing = if text.ends_with(' ') { " " } else { "" };
+                        format!("{leading}[{label}]({href}){trailing}")
+                    }
+                    None => text,
+                }
+            }
+            "strong" | "b" => wrap_inline(&self.inline_children(node), "**"),
+            "em" | "i" => wrap_inline(&self.inline_children(node), "*"),
+            "del" | "s" | "strike" => wrap_inline(&self.inline_children(node), "~~"),
+            "code" | "kbd" | "samp" => {
+                let code = collapse_whitespace(&node.text_contents());
+                if code.trim().is_empty() {
+                    code
+                } else {
+                    format!("`{}`", code.trim())
+                }
+            }
+            "img" => {
+                let alt = attr(node, "alt").unwrap_or_default();
+                match attr(node, "src").and_then(|src| self.resolve_url(&src)) {
+                    Some(src) if !alt.trim().is_empty() => format!("![{}]({src})", alt.trim()),
+                    _ => String::new(),
+                }
+            }
+            // 行内元素里嵌套了块级元素时只取文字，并用换行隔开
+            tag if BLOCK_TAGS.contains(&tag) => format!("\n{}\n", self.inline_children(node)),
+            _ => self.inline_children(node),
+        }
+    }
+
+    fn list(&self, node: &NodeRef, ordered: bool, depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+        let mut lines = Vec::new();
+        let mut index = 1;
+        for item in node.children() {
+            if tag_name(&item).as_deref() != Some("li") {
+                continue;
+            }
+            let mut text = String::new();
+            let mut nested = Vec::new();
+            for child in item.children() {
+                match tag_name(&child).as_deref() {
+                    Some(tag @ ("ul" | "ol")) => {
+                        let sublist = self.list(&child, tag == "ol", depth + 1);
+                        if !sublist.is_empty() {
+                            nested.push(sublist);
+                        }
+                    }
+                    _ => text.push_str(&self.inline(&child)),
+                }
+            }
+            let text = tidy_inline(&text).replace('\n', " ");
+            if text.is_empty() && nested.is_empty() {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{index}.")
+            } else {
+                "-".to_string()
+            };
+            lines.push(format!("{indent}{marker} {text}").trim_end().to_string());
+            lines.extend(nested);
+            index += 1;
+        }
+        lines.join("\n")
+    }
+
+    /// 单列或单行的表格通常只是排版用途，按普通块渲染
+    fn table(&self, node: &NodeRef) -> Option<String> {
+        let rows = node
+            .select("tr")
+            .ok()?
+            .map(|row| {
+                row.as_node()
+                    .children()
+                    .filter(|cell| matches!(tag_name(cell).as_deref(), Some("th" | "td")))
+                    .map(|cell| {
+                        tidy_inline(&self.inline_children(&cell))
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if rows.len() < 2 || width < 2 {
+            return None;
+        }
+        let render_row = |cells: &[String]| {
+            let mut padded = cells.to_vec();
+            padded.resize(width, String::new());
+            format!("| {} |", padded.join(" | "))
+        };
+        let mut lines = vec![render_row(&rows[0])];
+        lines.push(format!("|{}", " --- |".repeat(width)));
+        lines.extend(rows[1..].iter().map(|row| render_row(row)));
+        Some(lines.join("\n"))
+    }
+}
+
+fn push_paragraph(buffer: &mut String, out: &mut Vec<String>) {
+    let text = tidy_inline(buffer);
+    if !text.is_empty() {
+        out.push(text);
+    }
+    buffer.clear();
+}
+
+fn code_language(pre: &NodeRef) -> String {
+    let classes = std::iter::once(attr(pre, "class"))
+        .chain(
+            pre.select("code")
+                .ok()
+                .and_then(|mut matches| matches.next())
+                .map(|code| attr(code.as_node(), "class")),
+        )
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    classes
+        .split_whitespace()
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_main_content;
+    use reqwest::Url;
+
+    const ARTICLE_PAGE: &str = r#"<!doctype html>
+<html><head><title>示例文章 - 站点</title><style>.x{}</style></head>
+<body>
+  <nav><a href="/">首页</a><a href="/about">关于</a></nav>
+  <div class="sidebar"><p>推荐阅读：这是一段侧边栏内容，不应该出现在正文里，即使它足够长。</p></div>
+  <article>
+    <h1>Rust 异步入门</h1>
+    <p>本文介绍 <strong>async/await</strong> 的基本用法，并给出<a href="/docs/tokio">Tokio 文档</a>链接，帮助你快速上手。</p>
+    <h2>要点</h2>
+    <ul><li>Future 是惰性的<ul><li>需要执行器驱动</li></ul></li><li>使用 <code>tokio::spawn</code> 并发</li></ul>
+    <ol><li>安装</li><li>运行</li></ol>
+    <table><tr><th>运行时</th><th>特点</th></tr><tr><td>Tokio</td><td>多线程 | 生态完善</td></tr></table>
+    <pre><code class="language-rust">fn main() {
+    println!("hi");
+}</code></pre>
+    <blockquote><p>引用内容</p></blockquote>
+    <p>结尾段落，补充一些文字让正文长度超过阈值，确保 article 会被选中作为正文容器。</p>
+  </article>
+  <footer>版权所有</footer>
+  <script>track()</script>
+</body></html>"#;
+
+    #[test]
+    fn extracts_article_as_markdown() {
+        let base = Url::parse("https://blog.example.com/posts/1").unwrap();
+        let page = extract_main_content(ARTICLE_PAGE, Some(&base));
+
+        assert_eq!(page.title.as_deref(), Some("示例文章 - 站点"));
+        let md = page.markdown;
+        assert!(md.starts_with("# Rust 异步入门"), "{md}");
+        assert!(md.contains("本文介绍 **async/await** 的基本用法"), "{md}");
+        assert!(
+            md.contains("[Tokio 文档](https://blog.example.com/docs/tokio)"),
+            "{md}"
+        );
+        assert!(md.contains("## 要点"), "{md}");
+        assert!(
+            md.contains("- Future 是惰性的\n  - 需要执行器驱动\n- 使用 `tokio::spawn` 并发"),
+            "{md}"
+        );
+        assert!(md.contains("1. 安装\n2. 运行"), "{md}");
+        assert!(
+            md.contains("| 运行时 | 特点 |\n| --- | --- |\n| Tokio | 多线程 \\| 生态完善 |"),
+            "{md}"
+        );
+        assert!(
+            md.contains("```rust\nfn main() {\n    println!(\"hi\");\n}\n```"),
+            "{md}"
+        );
+        assert!(md.contains("> 引用内容"), "{md}");
+        for noise in ["首页", "侧边栏", "版权所有", "track()", ".x{}"] {
+            assert!(!md.contains(noise), "{noise} leaked into {md}");
+        }
+    }
+
+    #[test]
+    fn scores_paragraph_dense_container_without_article_tag() {
+        let html = r#"<html><body>
+            <div id="menu"><a href="/a">链接一</a> <a href="/b">链接二</a> <a href="/c">链接三</a></div>
+            <div class="links"><p><a href="/x">一个只有链接的段落，长度也超过二十五个字符，用来干扰打分。</a></p></div>
+            <div class="story-body">
+              <p>第一段正文，包含逗号，句号。这里是足够长的文字内容，用于测试打分逻辑。</p>
+              <p>第二段正文，同样包含逗号，句号。继续补充文字内容，让这个容器得分最高。</p>
+            </div>
+        </body></html>"#;
+        let page = extract_main_content(html, None);
+        assert!(page.markdown.starts_with("第一段正文"), "{}", page.markdown);
+        assert!(page.markdown.contains("\n\n第二段正文"));
+        assert!(!page.markdown.contains("链接"));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::web_fetch_policy::WebFetchPolicy;
+use anyhow::{anyhow, Result};
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+
+/// 通过检查的请求目标；发请求时把域名固定解析到这些地址，防止 DNS 重绑定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CheckedTarget {
+    pub host: String,
+    pub addrs: Vec<SocketAddr>,
+}
+
+/// 按主机名规则拒绝的本地/内网域名后缀
+const PRIVATE_HOST_SUFFIXES: &[&str] = &[".localhost", ".local", ".internal", ".lan", ".home.arpa"];
+
+/// 校验 URL 是否允许访问：仅 http/https，且所有解析结果都是公网地址，
+/// 或命中 allowed_private_hosts。每一跳重定向都要重新校验。
+pub(super) fn check_url(url: &Url, policy: &WebFetchPolicy) -> Result<CheckedTarget> {
+    check_url_with_resolver(url, policy, |host, port| {
+        Ok((host, port).to_socket_addrs()?.collect())
+    })
+}
+
+pub(super) fn check_url_with_resolver(
+    url: &Url,
+    policy: &WebFetchPolicy,
+    resolve: impl Fn(&str, u16) -> std::io::Result<Vec<SocketAddr>>,
+) -> Result<CheckedTarget> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(anyhow!("仅支持 http/https URL: {}", url));
+    }
+    let host = url
+        .host_str()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| anyhow!("URL 缺少主机名: {}", url))?
+        .to_ascii_lowercase();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| anyhow!("无法确定端口: {}", url))?;
+
+    let literal_ip = host.parse::<IpAddr>().ok();
+    let addrs = match literal_ip {
+        Some(ip) => vec![SocketAddr::new(ip, port)],
+        None => resolve(&host, port).map_err(|e| anyhow!("解析主机 {} 失败: {}", host, e))?,
+    };
+    if addrs.is_empty() {
+        return Err(anyhow!("解析主机 {} 失败: 没有可用地址", host));
+    }
+
+    if let Some(rule) = policy.blocked_hosts.iter().find(|rule| {
+        addrs
+            .iter()
+            .any(|addr| host_rule_matches(rule, &host, addr.ip()))
+    }) {
+        return Err(anyhow!("web_fetch 策略禁止访问 {}（规则 {}）", host, rule));
+    }
+
+    let allowed = |ip: IpAddr| {
+        policy
+            .allowed_private_hosts
+            .iter()
+            .any(|rule| host_rule_matches(rule, &host, ip))
+    };
+    let private_name = host == "localhost"
+        || PRIVATE_HOST_SUFFIXES
+            .iter()
+            .any(|suffix| host.ends_with(suffix));
+    if let Some(addr) = addrs
+        .iter()
+        .find(|addr| (private_name || !is_public_ip(addr.ip())) && !allowed(addr.ip()))
+    {
+        return Err(anyhow!(
+            "拒绝访问非公网地址 {}（{}）。如需访问本机或内网服务，请在 web_fetch 策略的 allowed_private_hosts 中放行",
+            host,
+            addr.ip()
+        ));
+    }
+
+    Ok(CheckedTarget { host, addrs })
+}
+
+/// 主机规则：主机名、`*.example.com`、IP 或 CIDR
+pub(super) fn host_rule_matches(rule: &str, host: &str, ip: IpAddr) -> bool {
+    let rule = rule.trim().to_ascii_lowercase();
+    if rule.is_empty() {
+        return false;
+    }
+    if let Some(suffix) = rule.strip_prefix("*.") {
+        return host == suffix || host.ends_with(&format!(".{suffix}"));
+    }
+    if let Some((network, prefix)) = rule.split_once('/') {
+        let (Ok(network), Ok(prefix)) = (network.parse::<IpAddr>(), prefix.parse::<u32>()) else {
+            return false;
+        };
+        return cidr_contains(network, prefix, ip);
+    }
+    if let Ok(rule_ip) = rule.parse::<IpAddr>() {
+        return canonical_ip(rule_ip) == canonical_ip(ip);
+    }
+    rule == host
+}
+
+fn cidr_contains(network: IpAddr, prefix: u32, ip: IpAddr) -> bool {
+    match (canonical_ip(network), canonical_ip(ip)) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// IPv4 映射 / NAT64 地址按内嵌的 IPv4 判断
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => embedded_ipv4(v6).map(IpAddr::V4).unwrap_or(ip),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let tail = || Ipv4Addr::from((u32::from(segments[6]) << 16) | u32::from(segments[7]));
+    match segments {
+        // ::ffff:a.b.c.d
+        [0, 0, 0, 0, 0, 0xffff, _, _] => Some(tail()),
+        // 64:ff9b::a.b.c.d
+        [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(tail()),
+        _ => None,
+    }
+}
+
+pub(super) fn is_public_ip(ip: IpAddr) -> bool {
+    match canonical_ip(ip) {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => is_public_ipv6(v6),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(a == 0
+        || a == 10
+        || a == 127
+        || (a == 100 && (64..=127).contains(&b))
+        || (a == 169 && b == 254)
+        || (a == 172 && (16..=31).contains(&b))
+        || (a == 192 && b == 0 && (c == 0 || c == 2))
+        || (a == 192 && b == 168)
+        || (a == 198 && (b == 18 || b == 19))
+        || (a == 198 && b == 51 && c == 100)
+        || (a == 203 && b == 0 && c == 113)
+        || a >= 224)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        // ::a.b.c.d（已废弃的 IPv4 兼容地址）
+        || ip.segments()[..6].iter().all(|segment| *segment == 0)
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xff00) == 0xff00
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)
+        || first == 0x0100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_to(
+        ips: &'static [&'static str],
+    ) -> impl Fn(&str, u16) -> std::io::Result<Vec<SocketAddr>> {
+        move |_, port| {
+            Ok(ips
+                .iter()
+                .map(|ip| SocketAddr::new(ip.parse().unwrap(), port))
+                .collect())
+        }
+    }
+
+    fn check(
+        url: &str,
+        policy: &WebFetchPolicy,
+        ips: &'static [&'static str],
+    ) -> Result<CheckedTarget> {
+        check_url_with_resolver(&Url::parse(url).unwrap(), policy, resolve_to(ips))
+    }
+
+    #[test]
+    fn rejects_private_loopback_and_link_local_targets() {
+        let policy = WebFetchPolicy::default();
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.20.0.1/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fe80::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:127.0.0.1]/",
+        ] {
+            assert!(check(url, &policy, &[]).is_err(), "{url} should be blocked");
+        }
+        assert!(check("http://localhost:8080/", &policy, &["127.0.0.1"]).is_err());
+        assert!(check("file:///etc/passwd", &policy, &[]).is_err());
+    }
+
+    #[test]
+    fn rejects_public_names_that_resolve_to_private_addresses() {
+        let policy = WebFetchPolicy::default();
+        let err = check(
+            "https://rebind.example.com/",
+            &policy,
+            &["93.184.216.34", "10.0.0.5"],
+        )
+        .expect_err("mixed resolution must be rejected");
+        assert!(err.to_string().contains("10.0.0.5"));
+    }
+
+    #[test]
+    fn allows_public_targets_and_pins_resolved_addresses() {
+        let target = check(
+            "https://example.com/page",
+            &WebFetchPolicy::default(),
+            &["93.184.216.34"],
+        )
+        .expect("public host");
+        assert_eq!(target.host, "example.com");
+        assert_eq!(target.addrs, vec!["93.184.216.34:443".parse().unwrap()]);
+    }
+
+    #[test]
+    fn allowlist_and_blocklist_rules_apply() {
+        let policy = WebFetchPolicy {
+            allowed_private_hosts: vec![
+                "localhost".to_string(),
+                "192.168.10.0/24".to_string(),
+                "*.corp.example.com".to_string(),
+            ],
+            blocked_hosts: vec!["*.ads.example.com".to_string()],
+            ..WebFetchPolicy::default()
+        };
+        assert!(check("http://localhost:3000/", &policy, &["127.0.0.1"]).is_ok());
+        assert!(check("http://192.168.10.7/", &policy, &[]).is_ok());
+        assert!(check("http://192.168.11.7/", &policy, &[]).is_err());
+        assert!(check("http://wiki.corp.example.com/", &policy, &["10.2.3.4"]).is_ok());
+        assert!(check("https://x.ads.example.com/", &policy, &["93.184.216.34"]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+/// robots.txt 中的一组规则：若干 User-agent 行加其后的 Allow/Disallow 规则
+#[derive(Default)]
+struct RobotsGroup {
+    agents: Vec<String>,
+    /// (是否为 Allow, 路径模式)
+    rules: Vec<(bool, String)>,
+}
+
+/// 按 robots.txt 判断路径是否允许抓取（RFC 9309）：
+/// 优先使用与 User-Agent 产品名匹配的分组，否则使用 `*` 分组；
+/// 最长匹配的规则生效，长度相同时 Allow 优先。
+pub(super) fn robots_allows(robots_txt: &str, user_agent: &str, path_and_query: &str) -> bool {
+    let product = user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let mut groups: Vec<RobotsGroup> = Vec::new();
+    let mut collecting_agents = false;
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+        match key.as_str() {
+            "user-agent" => {
+                if !collecting_agents {
+                    groups.push(RobotsGroup::default());
+                    collecting_agents = true;
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(value.to_ascii_lowercase());
+                }
+            }
+            "allow" | "disallow" => {
+                collecting_agents = false;
+                if let Some(group) = groups.last_mut() {
+                    if !value.is_empty() {
+                        group.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let matching_groups = |wildcard: bool| {
+        groups
+            .iter()
+            .filter(|group| {
+                group.agents.iter().any(|agent| {
+                    if wildcard {
+                        agent == "*"
+                    } else {
+                        !product.is_empty() && agent != "*" && product.contains(agent.as_str())
+                    }
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut selected = matching_groups(false);
+    if selected.is_empty() {
+        selected = matching_groups(true);
+    }
+    let rules = selected.into_iter().flat_map(|group| group.rules.iter());
+
+    let best = rules
+        .filter(|(_, pattern)| robots_pattern_matches(pattern, path_and_query))
+        .max_by_key(|(allow, pattern)| (pattern.len(), *allow));
+    match best {
+        Some((allow, _)) => *allow,
+        None => true,
+    }
+}
+
+/// 支持 `*` 通配与结尾 `$` 锚定
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(stripped) => (stripped, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let remaining = parts.collect::<Vec<_>>();
+    if remaining.is_empty() {
+        return !anchored || rest.is_empty();
+    }
+    for (index, part) in remaining.iter().enumerate() {
+        let is_last = index + 1 == remaining.len();
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::robots_allows;
+
+    const ROBOTS: &str = "\
+User-agent: *
+Disallow: /private/
+Allow: /private/public-note
+Disallow: /*.pdf$
+
+User-agent: WorkClaw
+User-agent: OtherBot
+Disallow: /no-agents/
+
+User-agent: QuietBot
+Disallow:
+";
+
+    #[test]
+    fn specific_agent_group_overrides_wildcard_group() {
+        assert!(!robots_allows(ROBOTS, "WorkClaw/1.0", "/no-agents/page"));
+        assert!(robots_allows(ROBOTS, "WorkClaw/1.0", "/private/secret"));
+        assert!(robots_allows(ROBOTS, "QuietBot/3", "/private/secret"));
+    }
+
+    #[test]
+    fn longest_match_wins_and_wildcards_apply() {
+        let ua = "SomeFetcher/2.0";
+        assert!(!robots_allows(ROBOTS, ua, "/private/secret"));
+        assert!(robots_allows(ROBOTS, ua, "/private/public-note"));
+        assert!(!robots_allows(ROBOTS, ua, "/files/report.pdf"));
+        assert!(robots_allows(ROBOTS, ua, "/files/report.pdf?download=1"));
+        assert!(robots_allows(ROBOTS, ua, "/blog/post"));
+        assert!(robots_allows("", ua, "/anything"));
+    }
+}
@@ -7,58 +7,162 @@ use super::browser_progress::BrowserProgressSnapshot;
 #[cfg(test)]
 use super::context::build_tool_context;
 use super::context::build_tool_context_with_permission_mode;
-use super::event_bridge::{append_run_guard_warning_event, resolve_current_session_run_id};
+use super::event_bridge::{
+    append_run_guard_warning_event, append_structured_output_event, resolve_current_session_run_id,
+};
 #[cfg(test)]
 use super::execution_caps::detect_execution_caps;
-use super::executor::{AgentExecutor, AgentTurnExecutionError, AgentTurnExecutionOutcome};
-use super::permissions::PermissionMode;
+use super::executor::{
+    AgentExecutor, AgentTurnExecutionError, AgentTurnExecutionOutcome, AgentTurnRequest,
+};
 use super::run_guard::{
-    encode_run_stop_reason, ProgressFingerprint, RunBudgetPolicy, RunBudgetScope, RunStopReason,
+    encode_run_stop_reason, ProgressFingerprint, RunBudgetPolicy, RunBudgetScope, RunBudgetTracker,
+    RunResourceLimits, RunStopReason,
 };
 #[cfg(test)]
 use super::safety::classify_policy_blocked_tool_error;
-use super::types::{AgentStateEvent, LLMResponse, StreamDelta};
-use crate::adapters;
+use super::structured_output::{
+    structured_output_repair_prompt, MAX_STRUCTURED_OUTPUT_REPAIR_TURNS,
+};
+use super::types::{AgentStateEvent, LLMResponse, ReasoningSettings, StreamDelta, TokenUsage};
+use crate::adapters::{ModelChatRequest, ModelUsageScope};
 use crate::agent::runtime::RuntimeObservabilityState;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind, ResolvedModelTransport};
+use crate::commands::skills::DbState;
+use crate::model_reasoning::{resolve_reasoning_settings_with_pool, CHAT_REASONING_CAPABILITY};
+use crate::model_transport::{resolve_model_transport, ResolvedModelTransport};
+use crate::model_usage::{estimate_model_cost, resolve_model_price_with_pool, ModelPriceRecord};
+use crate::providers::model_adapters;
+use crate::run_budgets::load_session_run_budget_with_pool;
 use crate::runtime_environment::runtime_paths_from_app;
 use anyhow::anyhow;
 use runtime_executor_core::{
-    estimate_tokens, micro_compact, trim_messages, ToolFailureStreak, DEFAULT_TOKEN_BUDGET,
+    micro_compact, tokenizer_for_model, trim_messages_with, Tokenizer, ToolFailureStreak,
 };
 use serde_json::{json, Value};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 
+/// provider 未返回用量时按本地分词器估算，避免 token 与费用预算因此失效
+fn estimate_turn_usage(
+    tokenizer: &dyn Tokenizer,
+    prompt_tokens: usize,
+    response: &LLMResponse,
+) -> TokenUsage {
+    let (text, tool_calls) = match response {
+        LLMResponse::Text(text) => (text.as_str(), &[][..]),
+        LLMResponse::ToolCalls(calls) => ("", calls.as_slice()),
+        LLMResponse::TextWithToolCalls(text, calls) => (text.as_str(), calls.as_slice()),
+    };
+    let output_tokens = tokenizer.count_text(text)
+        + tool_calls
+            .iter()
+            .map(|call| {
+                tokenizer.count_text(&call.name) + tokenizer.count_text(&call.input.to_string())
+            })
+            .sum::<usize>();
+    TokenUsage {
+        input_tokens: prompt_tokens as u64,
+        output_tokens: output_tokens as u64,
+        ..TokenUsage::default()
+    }
+}
+
+/// 在剩余耗时预算内等待；未设耗时上限时直接等待，超时返回耗时预算耗尽的停止原因
+async fn within_wall_clock_budget<T>(
+    tracker: &RunBudgetTracker,
+    limits: &RunResourceLimits,
+    future: impl std::future::Future<Output = T>,
+) -> std::result::Result<T, RunStopReason> {
+    match tracker.remaining_wall_clock(limits) {
+        Some(remaining) => tokio::time::timeout(remaining, future)
+            .await
+            .map_err(|_| tracker.wall_clock_stop_reason(limits)),
+        None => Ok(future.await),
+    }
+}
+
+/// 合并会话所属员工的运行预算；配置了费用上限时预取当前模型单价用于实时折算
+async fn resolve_turn_run_budget(
+    app: &AppHandle,
+    session_id: &str,
+    base_url: &str,
+    api_format: &str,
+    model: &str,
+    resource_limits: RunResourceLimits,
+) -> (RunResourceLimits, Option<ModelPriceRecord>) {
+    let Some(db) = app.try_state::<DbState>() else {
+        return (resource_limits, None);
+    };
+    let resource_limits = match load_session_run_budget_with_pool(&db.0, session_id).await {
+        Ok(employee_limits) => resource_limits.tighten(employee_limits),
+        Err(err) => {
+            eprintln!("[agent] 读取员工运行预算失败: {}", err);
+            resource_limits
+        }
+    };
+    if resource_limits.max_cost.is_none() {
+        return (resource_limits, None);
+    }
+    let price = resolve_model_price_with_pool(&db.0, base_url, api_format, model)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("[agent] 读取模型单价失败: {}", err);
+            None
+        });
+    (resource_limits, price)
+}
+
+/// 读取对话路由与模型配置上的推理设置；读取失败时沿用 adapter 默认行为
+async fn resolve_turn_reasoning(app: &AppHandle, base_url: &str, model: &str) -> ReasoningSettings {
+    let Some(db) = app.try_state::<DbState>() else {
+        return ReasoningSettings::default();
+    };
+    resolve_reasoning_settings_with_pool(&db.0, CHAT_REASONING_CAPABILITY, base_url, model)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("[agent] 读取推理设置失败: {}", err);
+            ReasoningSettings::default()
+        })
+}
+
 impl AgentExecutor {
     pub(super) async fn execute_turn_impl(
         &self,
         transport_override: Option<ResolvedModelTransport>,
-        api_format: &str,
-        base_url: &str,
-        api_key: &str,
-        model: &str,
-        skill_system_prompt: &str,
-        mut messages: Vec<Value>,
+        request: AgentTurnRequest<'_>,
+        run_budget_tracker: &RunBudgetTracker,
         on_token: impl Fn(StreamDelta) + Send + Clone,
-        app_handle: Option<&AppHandle>,
-        session_id: Option<&str>,
-        allowed_tools: Option<&[String]>,
-        permission_mode: PermissionMode,
-        tool_confirm_tx: Option<
-            std::sync::Arc<std::sync::Mutex<Option<std::sync::mpsc::Sender<bool>>>>,
-        >,
-        work_dir: Option<String>,
-        max_iterations_override: Option<usize>,
-        cancel_flag: Option<Arc<AtomicBool>>,
-        route_node_timeout_secs: Option<u64>,
-        route_retry_count: Option<usize>,
     ) -> std::result::Result<AgentTurnExecutionOutcome, AgentTurnExecutionError> {
+        let AgentTurnRequest {
+            api_format,
+            base_url,
+            api_key,
+            model,
+            skill_system_prompt,
+            mut messages,
+            app_handle,
+            session_id,
+            allowed_tools,
+            permission_mode,
+            tool_confirm_tx,
+            work_dir,
+            max_iterations_override,
+            resource_limits,
+            structured_output,
+            cancel_flag,
+            route_node_timeout_secs,
+            route_retry_count,
+        } = request;
         // 组合系统级 prompt 和 Skill prompt
         let system_prompt = self.system_prompt_builder.build(skill_system_prompt);
+        let system_prompt = match structured_output.as_ref() {
+            Some(spec) => format!("{system_prompt}\n\n{}", spec.instructions()),
+            None => system_prompt,
+        };
+        let tokenizer = tokenizer_for_model(model);
+        let mut structured_output_repair_turns = 0;
         let mut compaction_outcome: Option<
             super::runtime::compaction_pipeline::RuntimeCompactionOutcome,
         > = None;
@@ -71,7 +175,19 @@ impl AgentExecutor {
         )
         .map_err(|error| AgentTurnExecutionError::from_error(error, compaction_outcome.clone()))?;
         let max_iterations = max_iterations_override.unwrap_or(self.max_iterations);
-        let mut run_budget_policy = RunBudgetPolicy::for_scope(RunBudgetScope::GeneralChat);
+        let (resource_limits, model_price) = match (app_handle, session_id) {
+            (Some(app), Some(sid)) => {
+                resolve_turn_run_budget(app, sid, base_url, api_format, model, resource_limits)
+                    .await
+            }
+            _ => (resource_limits, None),
+        };
+        let reasoning = match app_handle {
+            Some(app) => resolve_turn_reasoning(app, base_url, model).await,
+            None => ReasoningSettings::default(),
+        };
+        let mut run_budget_policy = RunBudgetPolicy::for_scope(RunBudgetScope::GeneralChat)
+            .with_resource_limits(resource_limits);
         run_budget_policy.max_turns = max_iterations;
         let route_node_timeout_secs = route_node_timeout_secs.unwrap_or(60).clamp(5, 600);
         let route_retry_count = route_retry_count.unwrap_or(0).clamp(0, 2);
@@ -82,6 +198,18 @@ impl AgentExecutor {
         } else {
             None
         };
+        // 用量由 adapter 注册表按会话与运行记账，覆盖本轮及自动压缩的模型调用
+        let usage_pool = app_handle
+            .and_then(|app| app.try_state::<DbState>())
+            .map(|db| db.0.clone());
+        let usage_scope = usage_pool
+            .as_ref()
+            .zip(session_id)
+            .map(|(pool, session_id)| ModelUsageScope {
+                pool,
+                session_id,
+                run_id: persisted_run_id.as_deref(),
+            });
         let mut tool_failure_streak: Option<ToolFailureStreak> = None;
         let mut tool_call_history: Vec<ProgressFingerprint> = Vec::new();
         let mut tool_result_history: Vec<ProgressFingerprint> = Vec::new();
@@ -127,6 +255,25 @@ impl AgentExecutor {
                     compaction_outcome.clone(),
                 ));
             }
+
+            let budget_evaluation = run_budget_tracker.evaluate(&run_budget_policy);
+            if let Some(warning) = budget_evaluation.warning {
+                if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                    let _ = append_run_guard_warning_event(app, sid, &warning).await;
+                }
+            }
+            if let Some(stop_reason) = budget_evaluation.stop_reason {
+                if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                    let _ = app.emit(
+                        "agent-state-event",
+                        AgentStateEvent::stopped(sid, iteration, &stop_reason),
+                    );
+                }
+                return Err(AgentTurnExecutionError::from_error(
+                    anyhow!(encode_run_stop_reason(&stop_reason)),
+                    compaction_outcome.clone(),
+                ));
+            }
             iteration += 1;
 
             eprintln!("[agent] Iteration {}/{}", iteration, max_iterations);
@@ -141,8 +288,8 @@ impl AgentExecutor {
 
             // 自动压缩检查（仅在第二轮及之后，避免首轮触发）
             if iteration > 1 {
-                let tokens = estimate_tokens(&messages);
-                if super::compactor::needs_auto_compact(tokens) {
+                let tokens = tokenizer.count_messages(&messages);
+                if super::compactor::needs_auto_compact(tokens, model) {
                     eprintln!("[agent] Token 数 {} 超过阈值，触发自动压缩", tokens);
                     if let (Some(app), Some(sid)) = (app_handle, session_id) {
                         let _ = app.emit(
@@ -172,6 +319,7 @@ impl AgentExecutor {
                                 api_key,
                                 model,
                                 session_id: sid,
+                                usage_scope,
                                 messages: &messages,
                                 transcript_root: &transcript_dir,
                                 observability: runtime_observability.as_deref(),
@@ -222,41 +370,82 @@ impl AgentExecutor {
                 None => self.registry.get_tool_definitions(),
             };
 
-            // 上下文压缩：Layer 1 微压缩 + token 预算裁剪
-            let compacted = micro_compact(&messages, 3);
-            let trimmed = trim_messages(&compacted, DEFAULT_TOKEN_BUDGET);
+            // 上下文压缩：按模型窗口占比决定 Layer 1 微压缩，再扣除系统 prompt 后做 token 预算裁剪
+            let system_prompt_tokens = tokenizer.count_text(&system_prompt);
+            let history_tokens = tokenizer.count_messages(&messages);
+            let compacted = if super::compactor::needs_micro_compact(
+                history_tokens + system_prompt_tokens,
+                model,
+            ) {
+                micro_compact(&messages, 3)
+            } else {
+                messages.clone()
+            };
+            let trim_budget =
+                super::compactor::trim_budget_for_model(model).saturating_sub(system_prompt_tokens);
+            let trimmed = trim_messages_with(&compacted, trim_budget, tokenizer);
 
             // 调用 LLM（使用组合后的系统 prompt）
             let transport = transport_override
                 .clone()
                 .unwrap_or_else(|| resolve_model_transport(api_format, base_url, None));
-            let response_result = if transport.kind == ModelTransportKind::AnthropicMessages {
-                adapters::anthropic::chat_stream_with_tools(
-                    base_url,
-                    api_key,
-                    model,
-                    &system_prompt,
-                    trimmed.clone(),
-                    tools,
-                    on_token.clone(),
-                )
-                .await
-            } else {
-                adapters::openai::chat_stream_with_tools(
-                    &transport,
-                    base_url,
-                    api_key,
-                    model,
-                    &system_prompt,
-                    trimmed.clone(),
-                    tools,
-                    on_token.clone(),
-                )
-                .await
+            let mut stream_on_token = on_token.clone();
+            let prompt_tokens = system_prompt_tokens + tokenizer.count_messages(&trimmed);
+            let response_result = within_wall_clock_budget(
+                run_budget_tracker,
+                &run_budget_policy.resource_limits,
+                model_adapters().chat_stream(
+                    ModelChatRequest {
+                        transport: &transport,
+                        base_url,
+                        api_key,
+                        model,
+                        system_prompt: &system_prompt,
+                        messages: trimmed.clone(),
+                        tools,
+                        reasoning,
+                        structured_output: structured_output.as_ref(),
+                        usage_scope,
+                    },
+                    &mut stream_on_token,
+                ),
+            )
+            .await;
+            let response_result = match response_result {
+                Ok(result) => result.map(|chat| (chat.response, chat.usage, chat.reasoning)),
+                Err(stop_reason) => {
+                    if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                        let _ = app.emit(
+                            "agent-state-event",
+                            AgentStateEvent::stopped(sid, iteration, &stop_reason),
+                        );
+                    }
+                    return Err(AgentTurnExecutionError::from_error(
+                        anyhow!(encode_run_stop_reason(&stop_reason)),
+                        compaction_outcome.clone(),
+                    ));
+                }
             };
 
-            let response = match response_result {
-                Ok(response) => response,
+            let (response, reasoning_trace) = match response_result {
+                Ok((response, usage, reasoning_trace)) => {
+                    let budget_usage =
+                        usage.filter(|usage| !usage.is_empty()).unwrap_or_else(|| {
+                            estimate_turn_usage(tokenizer, prompt_tokens, &response)
+                        });
+                    run_budget_tracker.record_model_usage(
+                        &budget_usage,
+                        model_price
+                            .as_ref()
+                            .map(|price| estimate_model_cost(&budget_usage, price)),
+                    );
+                    if let (Some(usage), Some(app)) = (usage, app_handle) {
+                        if let Some(observability) = app.try_state::<RuntimeObservabilityState>() {
+                            observability.0.record_model_usage(&usage);
+                        }
+                    }
+                    (response, reasoning_trace)
+                }
                 Err(err) => {
                     if let (Some(app), Some(sid)) = (app_handle, session_id) {
                         let _ = app.emit(
@@ -274,6 +463,57 @@ impl AgentExecutor {
             // 处理响应
             match response {
                 LLMResponse::Text(content) => {
+                    // Skill 声明了输出 schema 时先校验，不通过则追加修复轮
+                    if let Some(spec) = structured_output.as_ref() {
+                        match spec.validate_answer(&content) {
+                            Ok(output) => {
+                                if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                                    let _ = append_structured_output_event(
+                                        app,
+                                        sid,
+                                        spec,
+                                        output,
+                                        structured_output_repair_turns,
+                                    )
+                                    .await;
+                                }
+                            }
+                            Err(errors)
+                                if structured_output_repair_turns
+                                    < MAX_STRUCTURED_OUTPUT_REPAIR_TURNS =>
+                            {
+                                structured_output_repair_turns += 1;
+                                eprintln!(
+                                    "[agent] 结构化输出校验失败，发起第 {} 次修复: {:?}",
+                                    structured_output_repair_turns, errors
+                                );
+                                messages.push(json!({
+                                    "role": "assistant",
+                                    "content": content
+                                }));
+                                messages.push(json!({
+                                    "role": "user",
+                                    "content": structured_output_repair_prompt(&errors)
+                                }));
+                                continue;
+                            }
+                            Err(errors) => {
+                                let stop_reason =
+                                    RunStopReason::structured_output_invalid(errors.join("; "));
+                                if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                                    let _ = app.emit(
+                                        "agent-state-event",
+                                        AgentStateEvent::stopped(sid, iteration, &stop_reason),
+                                    );
+                                }
+                                return Err(AgentTurnExecutionError::from_error(
+                                    anyhow!(encode_run_stop_reason(&stop_reason)),
+                                    compaction_outcome.clone(),
+                                ));
+                            }
+                        }
+                    }
+
                     // 纯文本响应 - 结束循环
                     messages.push(json!({
                         "role": "assistant",
@@ -350,58 +590,68 @@ impl AgentExecutor {
                         iteration,
                         run_budget_policy,
                     };
-                    for (call_index, call) in tool_calls.iter().enumerate() {
-                        let mut dispatch_state = super::runtime::tool_dispatch::ToolDispatchState {
-                            tool_results: &mut tool_results,
-                            repeated_failure_summary: &mut repeated_failure_summary,
-                            tool_failure_streak: &mut tool_failure_streak,
-                            tool_call_history: &mut tool_call_history,
-                            tool_result_history: &mut tool_result_history,
-                            latest_browser_progress: &mut latest_browser_progress,
-                        };
-                        match super::runtime::tool_dispatch::dispatch_tool_call(
+                    let mut dispatch_state = super::runtime::tool_dispatch::ToolDispatchState {
+                        tool_results: &mut tool_results,
+                        repeated_failure_summary: &mut repeated_failure_summary,
+                        tool_failure_streak: &mut tool_failure_streak,
+                        tool_call_history: &mut tool_call_history,
+                        tool_result_history: &mut tool_result_history,
+                        latest_browser_progress: &mut latest_browser_progress,
+                    };
+                    let dispatch_result = within_wall_clock_budget(
+                        run_budget_tracker,
+                        &run_budget_policy.resource_limits,
+                        super::runtime::tool_dispatch::dispatch_tool_calls(
                             &dispatch_context,
                             &mut dispatch_state,
-                            call_index,
-                            call,
-                        )
-                        .await
-                        .map_err(|error| {
-                            AgentTurnExecutionError::from_error(error, compaction_outcome.clone())
-                        })? {
-                            super::runtime::tool_dispatch::ToolDispatchOutcome::Cancelled => {
-                                if let (Some(app), Some(sid)) = (app_handle, session_id) {
-                                    let _ = app.emit(
-                                        "agent-state-event",
-                                        AgentStateEvent::basic(
-                                            sid,
-                                            "finished",
-                                            Some("用户取消".to_string()),
-                                            iteration,
-                                        ),
-                                    );
-                                }
-                                messages.push(json!({
-                                    "role": "assistant",
-                                    "content": "任务已被取消。"
-                                }));
-                                return Ok(AgentTurnExecutionOutcome {
-                                    messages,
-                                    compaction_outcome,
-                                });
-                            }
-                            super::runtime::tool_dispatch::ToolDispatchOutcome::Continue => {}
+                            &tool_calls,
+                        ),
+                    )
+                    .await
+                    .unwrap_or_else(|stop_reason| {
+                        if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                            let _ = app.emit(
+                                "agent-state-event",
+                                AgentStateEvent::stopped(sid, iteration, &stop_reason),
+                            );
                         }
-
-                        if repeated_failure_summary.is_some() {
-                            break;
+                        Err(anyhow!(encode_run_stop_reason(&stop_reason)))
+                    });
+                    match dispatch_result.map_err(|error| {
+                        AgentTurnExecutionError::from_error(error, compaction_outcome.clone())
+                    })? {
+                        super::runtime::tool_dispatch::ToolDispatchOutcome::Cancelled => {
+                            if let (Some(app), Some(sid)) = (app_handle, session_id) {
+                                let _ = app.emit(
+                                    "agent-state-event",
+                                    AgentStateEvent::basic(
+                                        sid,
+                                        "finished",
+                                        Some("用户取消".to_string()),
+                                        iteration,
+                                    ),
+                                );
+                            }
+                            messages.push(json!({
+                                "role": "assistant",
+                                "content": "任务已被取消。"
+                            }));
+                            return Ok(AgentTurnExecutionOutcome {
+                                messages,
+                                compaction_outcome,
+                            });
                         }
+                        super::runtime::tool_dispatch::ToolDispatchOutcome::Continue => {}
                     }
+                    run_budget_tracker.record_tool_calls(tool_calls.len());
 
                     // 添加工具调用和结果到消息历史（包含伴随文本）
                     if api_format == "anthropic" {
-                        // Anthropic 格式: assistant 消息包含 text block + tool_use blocks
-                        let mut content_blocks: Vec<Value> = vec![];
+                        // Anthropic 格式: assistant 消息包含 thinking blocks + text block + tool_use blocks；
+                        // 开启 extended thinking 时，带签名的 thinking 块必须原样回传，否则下一轮会被拒绝
+                        let mut content_blocks: Vec<Value> = reasoning_trace
+                            .map(|trace| trace.blocks)
+                            .unwrap_or_default();
                         if !companion_text.is_empty() {
                             content_blocks.push(json!({"type": "text", "text": companion_text}));
                         }
@@ -1,10 +1,13 @@
 use super::path_access::is_sensitive_path;
 use super::tool_manifest::ToolMetadata;
 use crate::agent::run_guard::RunStopReason;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PathAccessPolicy {
@@ -57,7 +60,7 @@ pub struct FileTaskCaps {
 }
 
 impl ToolContext {
-    fn normalize_for_scope_check(path: &Path) -> anyhow::Result<PathBuf> {
+    pub(crate) fn normalize_for_scope_check(path: &Path) -> anyhow::Result<PathBuf> {
         if path.exists() {
             return Ok(path.canonicalize()?);
         }
@@ -139,6 +142,86 @@ impl ToolContext {
     }
 }
 
+/// 工具取消信号，记录触发取消的 RunStopReason
+#[derive(Debug, Clone)]
+pub struct ToolCancellation {
+    sender: Arc<watch::Sender<Option<RunStopReason>>>,
+}
+
+impl Default for ToolCancellation {
+    fn default() -> Self {
+        let (sender, _) = watch::channel(None);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+}
+
+impl ToolCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 触发取消；只有第一次调用的原因会被保留
+    pub fn cancel(&self, reason: RunStopReason) {
+        self.sender.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason);
+            true
+        });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.sender.borrow().is_some()
+    }
+
+    pub fn reason(&self) -> Option<RunStopReason> {
+        self.sender.borrow().clone()
+    }
+
+    /// 等待取消发生，返回取消原因
+    pub async fn cancelled(&self) -> RunStopReason {
+        let mut receiver = self.sender.subscribe();
+        loop {
+            if let Some(reason) = receiver.borrow_and_update().clone() {
+                return reason;
+            }
+            if receiver.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+/// 接收工具的增量输出
+pub type ToolProgressSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// 单次工具调用的运行时句柄：取消信号 + 进度回调
+#[derive(Clone, Default)]
+pub struct ToolExecution {
+    pub cancellation: ToolCancellation,
+    progress: Option<ToolProgressSink>,
+}
+
+impl ToolExecution {
+    pub fn new(cancellation: ToolCancellation, progress: Option<ToolProgressSink>) -> Self {
+        Self {
+            cancellation,
+            progress,
+        }
+    }
+
+    /// 推送部分输出，前端会以 `progress` 状态的 ToolCallEvent 展示
+    pub fn report_progress(&self, partial: &str) {
+        if let Some(progress) = &self.progress {
+            progress(partial);
+        }
+    }
+}
+
+#[async_trait]
 pub trait Tool: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
@@ -150,6 +233,24 @@ pub trait Tool: Send + Sync {
     fn structured_output(&self, _input: &Value, _ctx: &ToolContext) -> Result<Option<Value>> {
         Ok(None)
     }
+
+    /// 调度器使用的异步入口。默认在阻塞线程池里调用 `execute`，
+    /// 原生异步工具可覆盖此方法以响应取消并推送部分输出。
+    async fn execute_async(
+        self: Arc<Self>,
+        input: Value,
+        ctx: ToolContext,
+        _execution: ToolExecution,
+    ) -> Result<String>
+    where
+        Self: 'static,
+    {
+        match tokio::task::spawn_blocking(move || self.execute(input, &ctx)).await {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(err) => Err(anyhow!("工具执行线程异常: {err}")),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -198,12 +299,119 @@ pub enum LLMResponse {
     TextWithToolCalls(String, Vec<ToolCall>),
 }
 
+/// 单次模型调用的 token 用量。`input_tokens` 不含缓存命中部分，
+/// 缓存读写分别计入 `cache_read_tokens` / `cache_write_tokens`，便于按不同单价计费。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn is_empty(&self) -> bool {
+        self.total_tokens() == 0
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_write_tokens
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamDelta {
     Text(String),
     Reasoning(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// 未显式配置思考预算时，按档位换算 Anthropic `budget_tokens`
+    pub fn default_budget_tokens(self) -> u32 {
+        match self {
+            Self::Low => 1024,
+            Self::Medium => 4096,
+            Self::High => 16384,
+        }
+    }
+}
+
+/// 推理（extended thinking / reasoning effort）设置。字段为 None 时沿用 adapter 默认行为。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u32>,
+}
+
+impl ReasoningSettings {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.enabled == Some(false)
+    }
+
+    /// 以 self 为准，未配置的字段回退到 fallback
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            enabled: self.enabled.or(fallback.enabled),
+            effort: self.effort.or(fallback.effort),
+            budget_tokens: self.budget_tokens.or(fallback.budget_tokens),
+        }
+    }
+
+    /// 显式配置的思考预算；显式关闭推理时为 None
+    pub fn thinking_budget_tokens(&self) -> Option<u32> {
+        if self.is_disabled() {
+            return None;
+        }
+        self.budget_tokens
+            .or_else(|| self.effort.map(ReasoningEffort::default_budget_tokens))
+    }
+}
+
+/// 单次模型调用产生的推理内容
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReasoningTrace {
+    pub text: String,
+    /// Anthropic 返回的 thinking / redacted_thinking 块（含签名），工具调用轮次需原样回传
+    pub blocks: Vec<Value>,
+}
+
+impl ReasoningTrace {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.blocks.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub enum AgentState {
     Thinking,
@@ -215,10 +423,12 @@ pub enum AgentState {
 #[derive(serde::Serialize, Clone, Debug)]
 pub struct ToolCallEvent {
     pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<String>,
     pub tool_name: String,
     pub tool_input: Value,
     pub tool_output: Option<String>,
-    pub status: String, // "started" | "completed" | "error"
+    pub status: String, // "started" | "progress" | "completed" | "error"
 }
 
 #[derive(serde::Serialize, Clone, Debug)]
@@ -294,15 +504,19 @@ impl AgentStateEvent {
 #[cfg(test)]
 mod tests {
     use super::{
-        AgentStateEvent, BackgroundProcessEvent, PathAccessPolicy, ToolCallEvent, ToolContext,
+        AgentStateEvent, BackgroundProcessEvent, PathAccessPolicy, Tool, ToolCallEvent,
+        ToolCancellation, ToolContext, ToolExecution,
     };
+    use crate::agent::run_guard::{RunStopReason, RunStopReasonKind};
     use serde_json::{Value, json};
+    use std::sync::{Arc, Mutex};
     use tempfile::tempdir;
 
     #[test]
     fn tool_call_event_serializes_expected_shape() {
         let event = ToolCallEvent {
             session_id: "sess-1".to_string(),
+            call_id: None,
             tool_name: "read_file".to_string(),
             tool_input: json!({"path":"README.md"}),
             tool_output: Some("ok".to_string()),
@@ -322,6 +536,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn progress_event_includes_call_id() {
+        let event = ToolCallEvent {
+            session_id: "sess-1".to_string(),
+            call_id: Some("call-7".to_string()),
+            tool_name: "mcp_fs_read".to_string(),
+            tool_input: json!({}),
+            tool_output: Some("partial".to_string()),
+            status: "progress".to_string(),
+        };
+
+        let value = serde_json::to_value(event).expect("serialize event");
+        assert_eq!(value["call_id"], json!("call-7"));
+        assert_eq!(value["status"], json!("progress"));
+    }
+
+    struct EchoTool;
+
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "echo input"
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({})
+        }
+
+        fn execute(&self, input: Value, _ctx: &ToolContext) -> anyhow::Result<String> {
+            Ok(input["text"].as_str().unwrap_or_default().to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_tools_run_through_default_async_shim() {
+        let tool: Arc<dyn Tool> = Arc::new(EchoTool);
+        let output = tool
+            .execute_async(
+                json!({"text": "hi"}),
+                ToolContext::default(),
+                ToolExecution::default(),
+            )
+            .await
+            .expect("execute");
+        assert_eq!(output, "hi");
+    }
+
+    #[tokio::test]
+    async fn cancellation_keeps_first_reason_and_wakes_waiters() {
+        let cancellation = ToolCancellation::new();
+        let waiter = {
+            let cancellation = cancellation.clone();
+            tokio::spawn(async move { cancellation.cancelled().await })
+        };
+        cancellation.cancel(RunStopReason::cancelled());
+        cancellation.cancel(RunStopReason::loop_detected("later"));
+
+        let reason = waiter.await.expect("join waiter");
+        assert_eq!(reason.kind, RunStopReasonKind::Cancelled);
+        assert!(cancellation.is_cancelled());
+        assert_eq!(
+            cancellation.reason().map(|reason| reason.kind),
+            Some(RunStopReasonKind::Cancelled)
+        );
+    }
+
+    #[test]
+    fn execution_forwards_progress_to_sink() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = Arc::clone(&seen);
+        let execution = ToolExecution::new(
+            ToolCancellation::new(),
+            Some(Arc::new(move |partial: &str| {
+                sink_seen.lock().unwrap().push(partial.to_string());
+            })),
+        );
+        execution.report_progress("10%");
+        execution.report_progress("20%");
+        assert_eq!(*seen.lock().unwrap(), vec!["10%", "20%"]);
+    }
+
     #[test]
     fn background_process_event_serializes_expected_shape() {
         let event = BackgroundProcessEvent {
@@ -1,3 +1,4 @@
+use crate::agent::types::ToolExecution;
 use crate::agent::{ToolContext, ToolRegistry};
 use crate::approval_rules::persist_allow_always_rule_with_tx;
 use crate::commands::session_runs::append_session_run_event_with_pool;
@@ -446,7 +447,10 @@ pub async fn recover_approved_pending_work_with_pool(
                     execution_caps: None,
                     file_task_caps: None,
                 };
-                match tool.execute(payload.input.clone(), &ctx) {
+                match tool
+                    .execute_async(payload.input.clone(), ctx, ToolExecution::default())
+                    .await
+                {
                     Ok(output) => (output, false),
                     Err(error) => (error.to_string(), true),
                 }
@@ -470,6 +474,7 @@ pub async fn recover_approved_pending_work_with_pool(
                     input: payload.input.clone(),
                     output: tool_result.0.clone(),
                     is_error: tool_result.1,
+                    checkpoint_id: None,
                 },
             )
             .await?;
@@ -752,6 +757,7 @@ mod tests {
             input: payload.input.clone(),
             output: "already counted".to_string(),
             is_error: false,
+            checkpoint_id: None,
         };
         sqlx::query(
             "INSERT INTO session_run_events (id, run_id, session_id, event_type, payload_json, created_at)
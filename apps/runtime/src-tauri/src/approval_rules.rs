@@ -1,9 +1,20 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use runtime_policy::{
+    ApprovalRuleEffect, ApprovalRuleMatchKind, ApprovalRuleScope, ApprovalRuleSpec,
+    ApprovalRuleTarget,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{FromRow, Sqlite, SqlitePool, Transaction};
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
 use uuid::Uuid;
 
+const APPROVAL_RULE_COLUMNS: &str =
+    "id, tool_name, fingerprint, source_approval_id, created_by_surface,
+                created_by_user, enabled, effect, match_kind, pattern, scope_kind, scope_value,
+                expires_at, note, created_at, updated_at";
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
 pub struct ApprovalRuleRecord {
     pub id: String,
@@ -13,10 +24,114 @@ pub struct ApprovalRuleRecord {
     pub created_by_surface: String,
     pub created_by_user: String,
     pub enabled: i64,
+    /// allow | deny；deny 规则优先于 allow 规则和会话权限模式
+    pub effect: String,
+    /// fingerprint | command_glob | command_regex | path_glob
+    pub match_kind: String,
+    pub pattern: String,
+    /// global | employee | session | workspace
+    pub scope_kind: String,
+    pub scope_value: String,
+    /// RFC3339（UTC），为空表示永不过期
+    pub expires_at: String,
+    pub note: String,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl ApprovalRuleRecord {
+    fn spec(&self) -> Option<ApprovalRuleSpec> {
+        let match_kind = ApprovalRuleMatchKind::from_key(&self.match_kind)?;
+        let pattern = if match_kind == ApprovalRuleMatchKind::Fingerprint {
+            self.fingerprint.clone()
+        } else {
+            self.pattern.clone()
+        };
+        Some(ApprovalRuleSpec {
+            tool_name: self.tool_name.clone(),
+            effect: ApprovalRuleEffect::from_key(&self.effect)?,
+            match_kind,
+            pattern,
+            scope: ApprovalRuleScope::from_parts(&self.scope_kind, &self.scope_value)?,
+        })
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        let expires_at = self.expires_at.trim();
+        !expires_at.is_empty()
+            && DateTime::parse_from_rfc3339(expires_at)
+                .map(|value| value.with_timezone(&Utc) <= now)
+                .unwrap_or(true)
+    }
+
+    /// 越具体的作用域越先解释，便于在多条规则同时命中时给出最贴切的那一条
+    fn scope_rank(&self) -> u8 {
+        match self.scope_kind.as_str() {
+            "session" => 0,
+            "workspace" => 1,
+            "employee" => 2,
+            _ => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalRuleInput {
+    pub tool_name: String,
+    pub effect: String,
+    pub match_kind: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub scope_kind: String,
+    #[serde(default)]
+    pub scope_value: String,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// 规则匹配所需的调用上下文，作用域规则据此判断是否生效
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApprovalRuleContext<'a> {
+    pub employee_id: Option<&'a str>,
+    pub session_id: Option<&'a str>,
+    pub work_dir: Option<&'a Path>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ApprovalRuleEvaluation {
+    /// 命中规则的效果（allow / deny），未命中时为 None
+    pub effect: Option<String>,
+    pub matched_rule: Option<ApprovalRuleRecord>,
+    /// 同时命中、但被优先级更高的规则覆盖的规则
+    pub shadowed_rules: Vec<ApprovalRuleRecord>,
+    pub explanation: String,
+}
+
+impl ApprovalRuleEvaluation {
+    pub fn is_deny(&self) -> bool {
+        self.effect.as_deref() == Some("deny")
+    }
+
+    pub fn is_allow(&self) -> bool {
+        self.effect.as_deref() == Some("allow")
+    }
+}
+
+/// 试运行入参：只做规则解释，不会执行工具
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalRuleProbe {
+    pub tool_name: String,
+    pub input: Value,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub employee_id: Option<String>,
+    #[serde(default)]
+    pub work_dir: Option<String>,
+}
+
 #[derive(Debug, Clone, FromRow)]
 struct ApprovalRuleSourceRow {
     tool_name: String,
@@ -29,40 +144,276 @@ struct ApprovalRuleSourceRow {
 pub async fn list_approval_rules_with_pool(
     pool: &SqlitePool,
 ) -> Result<Vec<ApprovalRuleRecord>, String> {
-    sqlx::query_as::<_, ApprovalRuleRecord>(
-        "SELECT id, tool_name, fingerprint, source_approval_id, created_by_surface,
-                created_by_user, enabled, created_at, updated_at
+    sqlx::query_as::<_, ApprovalRuleRecord>(&format!(
+        "SELECT {APPROVAL_RULE_COLUMNS}
          FROM approval_rules
-         ORDER BY created_at ASC, id ASC",
-    )
+         ORDER BY created_at ASC, id ASC"
+    ))
     .fetch_all(pool)
     .await
     .map_err(|e| format!("读取 approval rules 失败: {e}"))
 }
 
+/// 在所有启用且未过期的规则中求值：deny 优先于 allow，同效果下作用域越具体越优先。
+fn approval_rule_pool_slot() -> &'static RwLock<Option<SqlitePool>> {
+    static SLOT: OnceLock<RwLock<Option<SqlitePool>>> = OnceLock::new();
+    SLOT.get_or_init(|| RwLock::new(None))
+}
+
+/// 登记审批规则所在的数据库，没有 AppHandle 的运行（群组协作步骤等）也据此校验规则
+pub fn register_approval_rule_pool(pool: SqlitePool) {
+    *approval_rule_pool_slot()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(pool);
+}
+
+pub(crate) fn registered_approval_rule_pool() -> Option<SqlitePool> {
+    approval_rule_pool_slot()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+pub async fn evaluate_approval_rules_with_pool(
+    pool: &SqlitePool,
+    tool_name: &str,
+    input: &Value,
+    context: ApprovalRuleContext<'_>,
+) -> Result<ApprovalRuleEvaluation, String> {
+    let rules = sqlx::query_as::<_, ApprovalRuleRecord>(&format!(
+        "SELECT {APPROVAL_RULE_COLUMNS}
+         FROM approval_rules
+         WHERE enabled = 1
+         ORDER BY created_at ASC, id ASC"
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("匹配 approval rule 失败: {e}"))?;
+    Ok(evaluate_approval_rules(
+        rules,
+        tool_name,
+        input,
+        context,
+        Utc::now(),
+    ))
+}
+
+fn evaluate_approval_rules(
+    rules: Vec<ApprovalRuleRecord>,
+    tool_name: &str,
+    input: &Value,
+    context: ApprovalRuleContext<'_>,
+    now: DateTime<Utc>,
+) -> ApprovalRuleEvaluation {
+    let target = ApprovalRuleTarget {
+        tool_name,
+        input,
+        employee_id: context.employee_id,
+        session_id: context.session_id,
+        work_dir: context.work_dir,
+    };
+    let mut matched = rules
+        .into_iter()
+        .filter(|rule| !rule.is_expired(now))
+        .filter(|rule| rule.spec().is_some_and(|spec| spec.matches(&target)))
+        .collect::<Vec<_>>();
+    matched.sort_by_key(|rule| (rule.effect != "deny", rule.scope_rank()));
+
+    let mut matched = matched.into_iter();
+    let Some(rule) = matched.next() else {
+        return ApprovalRuleEvaluation {
+            effect: None,
+            matched_rule: None,
+            shadowed_rules: Vec::new(),
+            explanation: format!("没有规则命中 {tool_name}，按会话权限模式处理"),
+        };
+    };
+    let scope = if rule.scope_value.is_empty() {
+        rule.scope_kind.clone()
+    } else {
+        format!("{}={}", rule.scope_kind, rule.scope_value)
+    };
+    let pattern = if rule.match_kind == "fingerprint" {
+        &rule.fingerprint
+    } else {
+        &rule.pattern
+    };
+    let explanation = format!(
+        "{} 规则 {}（{} `{}`，作用域 {}）命中 {}",
+        rule.effect, rule.id, rule.match_kind, pattern, scope, tool_name
+    );
+    ApprovalRuleEvaluation {
+        effect: Some(rule.effect.clone()),
+        matched_rule: Some(rule),
+        shadowed_rules: matched.collect(),
+        explanation,
+    }
+}
+
+/// 试运行：给定 session 时从会话补全员工和工作目录
+pub async fn explain_approval_rule_match_with_pool(
+    pool: &SqlitePool,
+    probe: ApprovalRuleProbe,
+) -> Result<ApprovalRuleEvaluation, String> {
+    let session_id = probe
+        .session_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let session = match session_id {
+        Some(session_id) => sqlx::query_as::<_, (String, String)>(
+            "SELECT employee_id, work_dir FROM sessions WHERE id = ? LIMIT 1",
+        )
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("读取会话失败: {e}"))?,
+        None => None,
+    };
+    let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+    let employee_id = non_empty(probe.employee_id)
+        .or_else(|| non_empty(session.as_ref().map(|(employee_id, _)| employee_id.clone())));
+    let work_dir = non_empty(probe.work_dir)
+        .or_else(|| non_empty(session.as_ref().map(|(_, work_dir)| work_dir.clone())));
+    evaluate_approval_rules_with_pool(
+        pool,
+        &probe.tool_name,
+        &probe.input,
+        ApprovalRuleContext {
+            employee_id: employee_id.as_deref(),
+            session_id,
+            work_dir: work_dir.as_deref().map(Path::new),
+        },
+    )
+    .await
+}
+
 pub async fn find_matching_approval_rule_with_pool(
     pool: &SqlitePool,
     tool_name: &str,
     input: &Value,
 ) -> Result<Option<ApprovalRuleRecord>, String> {
-    let normalized_tool_name = runtime_policy::normalize_tool_name(tool_name);
-    let Some(fingerprint) = runtime_policy::approval_rule_fingerprint(&normalized_tool_name, input)
-    else {
-        return Ok(None);
+    let evaluation =
+        evaluate_approval_rules_with_pool(pool, tool_name, input, ApprovalRuleContext::default())
+            .await?;
+    Ok(if evaluation.is_allow() {
+        evaluation.matched_rule
+    } else {
+        None
+    })
+}
+
+fn normalize_expires_at(expires_at: Option<&str>) -> Result<String, String> {
+    let Some(raw) = expires_at.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(String::new());
     };
+    let parsed = DateTime::parse_from_rfc3339(raw)
+        .map_err(|e| format!("过期时间格式无效（需要 RFC3339）: {e}"))?
+        .with_timezone(&Utc);
+    if parsed <= Utc::now() {
+        return Err("过期时间必须晚于当前时间".to_string());
+    }
+    Ok(parsed.to_rfc3339())
+}
 
-    sqlx::query_as::<_, ApprovalRuleRecord>(
-        "SELECT id, tool_name, fingerprint, source_approval_id, created_by_surface,
-                created_by_user, enabled, created_at, updated_at
-         FROM approval_rules
-         WHERE enabled = 1 AND tool_name = ? AND fingerprint = ?
-         LIMIT 1",
+/// 新建或更新一条手工规则；相同的工具、模式、效果和作用域会合并为一条
+pub async fn save_approval_rule_with_pool(
+    pool: &SqlitePool,
+    input: ApprovalRuleInput,
+    created_by_surface: &str,
+) -> Result<ApprovalRuleRecord, String> {
+    let effect = ApprovalRuleEffect::from_key(&input.effect)
+        .ok_or_else(|| format!("不支持的规则效果: {}", input.effect))?;
+    let match_kind = ApprovalRuleMatchKind::from_key(&input.match_kind)
+        .ok_or_else(|| format!("不支持的匹配方式: {}", input.match_kind))?;
+    let scope = ApprovalRuleScope::from_parts(&input.scope_kind, &input.scope_value)
+        .ok_or_else(|| format!("作用域无效: {} {}", input.scope_kind, input.scope_value))?;
+    let tool_name = match input.tool_name.trim() {
+        runtime_policy::ANY_TOOL => runtime_policy::ANY_TOOL.to_string(),
+        other => runtime_policy::normalize_tool_name(other),
+    };
+    let spec = ApprovalRuleSpec {
+        tool_name: tool_name.clone(),
+        effect,
+        match_kind,
+        pattern: input.pattern.trim().to_string(),
+        scope,
+    };
+    spec.validate()?;
+    let expires_at = normalize_expires_at(input.expires_at.as_deref())?;
+    let fingerprint = spec.storage_fingerprint();
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query(
+        "INSERT INTO approval_rules (
+            id, tool_name, fingerprint, source_approval_id, created_by_surface,
+            created_by_user, enabled, effect, match_kind, pattern, scope_kind, scope_value,
+            expires_at, note, created_at, updated_at
+         ) VALUES (?, ?, ?, '', ?, '', 1, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(tool_name, fingerprint) DO UPDATE SET
+            enabled = 1,
+            expires_at = excluded.expires_at,
+            note = excluded.note,
+            updated_at = excluded.updated_at",
     )
-    .bind(&normalized_tool_name)
+    .bind(Uuid::new_v4().to_string())
+    .bind(&tool_name)
     .bind(&fingerprint)
-    .fetch_optional(pool)
+    .bind(created_by_surface)
+    .bind(effect.as_key())
+    .bind(match_kind.as_key())
+    .bind(&spec.pattern)
+    .bind(spec.scope.kind_key())
+    .bind(spec.scope.value())
+    .bind(&expires_at)
+    .bind(input.note.trim())
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
     .await
-    .map_err(|e| format!("匹配 approval rule 失败: {e}"))
+    .map_err(|e| format!("写入 approval rule 失败: {e}"))?;
+
+    sqlx::query_as::<_, ApprovalRuleRecord>(&format!(
+        "SELECT {APPROVAL_RULE_COLUMNS}
+         FROM approval_rules
+         WHERE tool_name = ? AND fingerprint = ?
+         LIMIT 1"
+    ))
+    .bind(&tool_name)
+    .bind(&fingerprint)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("读取 approval rule 失败: {e}"))
+}
+
+pub async fn set_approval_rule_enabled_with_pool(
+    pool: &SqlitePool,
+    rule_id: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    let result = sqlx::query("UPDATE approval_rules SET enabled = ?, updated_at = ? WHERE id = ?")
+        .bind(if enabled { 1_i64 } else { 0_i64 })
+        .bind(Utc::now().to_rfc3339())
+        .bind(rule_id.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("更新 approval rule 失败: {e}"))?;
+    if result.rows_affected() == 0 {
+        return Err(format!("approval rule 不存在: {rule_id}"));
+    }
+    Ok(())
+}
+
+pub async fn delete_approval_rule_with_pool(
+    pool: &SqlitePool,
+    rule_id: &str,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM approval_rules WHERE id = ?")
+        .bind(rule_id.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("删除 approval rule 失败: {e}"))?;
+    Ok(())
 }
 
 pub async fn persist_allow_always_rule_with_pool(
@@ -114,13 +465,14 @@ pub async fn persist_allow_always_rule_with_tx(
     sqlx::query(
         "INSERT INTO approval_rules (
             id, tool_name, fingerprint, source_approval_id, created_by_surface,
-            created_by_user, enabled, created_at, updated_at
-         ) VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+            created_by_user, enabled, effect, match_kind, pattern, created_at, updated_at
+         ) VALUES (?, ?, ?, ?, ?, ?, 1, 'allow', 'fingerprint', '', ?, ?)
          ON CONFLICT(tool_name, fingerprint) DO UPDATE SET
             source_approval_id = excluded.source_approval_id,
             created_by_surface = excluded.created_by_surface,
             created_by_user = excluded.created_by_user,
             enabled = 1,
+            expires_at = '',
             updated_at = excluded.updated_at",
     )
     .bind(Uuid::new_v4().to_string())
@@ -135,16 +487,83 @@ pub async fn persist_allow_always_rule_with_tx(
     .await
     .map_err(|e| format!("写入 approval rule 失败: {e}"))?;
 
-    sqlx::query_as::<_, ApprovalRuleRecord>(
-        "SELECT id, tool_name, fingerprint, source_approval_id, created_by_surface,
-                created_by_user, enabled, created_at, updated_at
+    sqlx::query_as::<_, ApprovalRuleRecord>(&format!(
+        "SELECT {APPROVAL_RULE_COLUMNS}
          FROM approval_rules
          WHERE tool_name = ? AND fingerprint = ?
-         LIMIT 1",
-    )
+         LIMIT 1"
+    ))
     .bind(&normalized_tool_name)
     .bind(&fingerprint)
     .fetch_optional(&mut **tx)
     .await
     .map_err(|e| format!("读取 approval rule 失败: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ApprovalRuleContext, ApprovalRuleRecord, evaluate_approval_rules};
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use std::path::Path;
+
+    fn rule(id: &str, effect: &str, scope_kind: &str, scope_value: &str) -> ApprovalRuleRecord {
+        ApprovalRuleRecord {
+            id: id.to_string(),
+            tool_name: "write_file".to_string(),
+            fingerprint: id.to_string(),
+            source_approval_id: String::new(),
+            created_by_surface: "desktop".to_string(),
+            created_by_user: String::new(),
+            enabled: 1,
+            effect: effect.to_string(),
+            match_kind: "path_glob".to_string(),
+            pattern: "**/*.md".to_string(),
+            scope_kind: scope_kind.to_string(),
+            scope_value: scope_value.to_string(),
+            expires_at: String::new(),
+            note: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn evaluation_prefers_deny_then_narrowest_scope_and_skips_expired() {
+        let input = json!({ "path": "docs/guide.md" });
+        let context = ApprovalRuleContext {
+            employee_id: Some("writer"),
+            session_id: Some("session-1"),
+            work_dir: Some(Path::new("/home/me/project")),
+        };
+        let now = Utc::now();
+
+        let allow_rules = vec![
+            rule("global-allow", "allow", "global", ""),
+            rule("workspace-allow", "allow", "workspace", "/home/me/project"),
+            rule("other-employee", "allow", "employee", "reviewer"),
+        ];
+        let evaluation = evaluate_approval_rules(allow_rules, "write_file", &input, context, now);
+        assert!(evaluation.is_allow());
+        assert_eq!(
+            evaluation.matched_rule.map(|rule| rule.id).as_deref(),
+            Some("workspace-allow")
+        );
+        assert_eq!(evaluation.shadowed_rules.len(), 1);
+
+        let mut expired_deny = rule("expired-deny", "deny", "session", "session-1");
+        expired_deny.expires_at = (now - Duration::minutes(1)).to_rfc3339();
+        let rules = vec![
+            rule("session-allow", "allow", "session", "session-1"),
+            expired_deny,
+            rule("employee-deny", "deny", "employee", "writer"),
+        ];
+        let evaluation = evaluate_approval_rules(rules, "write_file", &input, context, now);
+        assert!(evaluation.is_deny());
+        assert_eq!(
+            evaluation.matched_rule.map(|rule| rule.id).as_deref(),
+            Some("employee-deny")
+        );
+        assert_eq!(evaluation.shadowed_rules.len(), 1);
+    }
+}
@@ -0,0 +1,16 @@
+fn main() {
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(error) => {
+            eprintln!("[workclaw] build tokio runtime: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    let args = std::env::args().skip(1).collect();
+    let code = runtime.block_on(runtime_lib::headless_cli::run(args));
+    std::process::exit(code);
+}
@@ -5,6 +5,12 @@ use super::im_host::{
 };
 use super::skills::DbState;
 use crate::approval_bus::{ApprovalDecision, ApprovalResolveResult, PendingApprovalRecord};
+use crate::approval_rules::{
+    ApprovalRuleEvaluation, ApprovalRuleInput, ApprovalRuleProbe, ApprovalRuleRecord,
+    delete_approval_rule_with_pool, explain_approval_rule_match_with_pool,
+    list_approval_rules_with_pool, save_approval_rule_with_pool,
+    set_approval_rule_enabled_with_pool,
+};
 use crate::commands::openclaw_plugins::im_host_contract::ImReplyLifecyclePhase;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -166,3 +172,40 @@ pub async fn resolve_approval(
 
     Ok(result)
 }
+
+#[tauri::command]
+pub async fn list_approval_rules(
+    db: State<'_, DbState>,
+) -> Result<Vec<ApprovalRuleRecord>, String> {
+    list_approval_rules_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_approval_rule(
+    input: ApprovalRuleInput,
+    db: State<'_, DbState>,
+) -> Result<ApprovalRuleRecord, String> {
+    save_approval_rule_with_pool(&db.0, input, "desktop").await
+}
+
+#[tauri::command]
+pub async fn set_approval_rule_enabled(
+    rule_id: String,
+    enabled: bool,
+    db: State<'_, DbState>,
+) -> Result<(), String> {
+    set_approval_rule_enabled_with_pool(&db.0, &rule_id, enabled).await
+}
+
+#[tauri::command]
+pub async fn delete_approval_rule(rule_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    delete_approval_rule_with_pool(&db.0, &rule_id).await
+}
+
+#[tauri::command]
+pub async fn explain_approval_rule_match(
+    probe: ApprovalRuleProbe,
+    db: State<'_, DbState>,
+) -> Result<ApprovalRuleEvaluation, String> {
+    explain_approval_rule_match_with_pool(&db.0, probe).await
+}
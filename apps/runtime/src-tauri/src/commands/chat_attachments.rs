@@ -12,6 +12,7 @@ use std::process::Command;
 use zip::ZipArchive;
 
 use crate::agent::runtime::repo::PoolChatSettingsRepository;
+use crate::model_catalog::current_model_catalog;
 use crate::model_transport::{ModelTransportKind, resolve_model_transport};
 use crate::runtime_paths::RuntimePaths;
 
@@ -193,8 +194,13 @@ async fn resolve_capability_route_candidate(
 
     let mut provider_targets = vec![(policy.primary_provider_id, policy.primary_model)];
     provider_targets.extend(parse_fallback_chain_targets(&policy.fallback_chain_json));
+    let catalog = current_model_catalog();
 
     for (provider_id, preferred_model) in provider_targets {
+        // 模型目录明确声明不支持该能力的模型直接跳过；未收录的模型仍交给 provider 判断
+        if catalog.supports(&preferred_model, capability) == Some(false) {
+            continue;
+        }
         let Some(provider) = repo.get_provider_connection(&provider_id).await? else {
             continue;
         };
@@ -714,7 +720,9 @@ async fn request_visual_summary_with_candidate(
                 image_data_urls,
             ),
         ),
-        ModelTransportKind::AnthropicMessages => return Ok(None),
+        ModelTransportKind::AnthropicMessages
+        | ModelTransportKind::GeminiGenerateContent
+        | ModelTransportKind::OllamaChat => return Ok(None),
     };
 
     let response = client
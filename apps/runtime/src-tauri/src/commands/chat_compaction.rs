@@ -1,4 +1,5 @@
 use super::chat_session_io;
+use crate::adapters::ModelUsageScope;
 use crate::agent::runtime::compaction_pipeline::{run_compaction, RuntimeCompactionRequest};
 use std::path::Path;
 
@@ -22,6 +23,11 @@ pub async fn compact_context_with_pool(
         api_key: &api_key,
         model: &model_name,
         session_id,
+        usage_scope: Some(ModelUsageScope {
+            pool,
+            session_id,
+            run_id: None,
+        }),
         messages: &messages,
         transcript_root,
         observability: None,
@@ -26,12 +26,7 @@ pub(crate) async fn maybe_handle_team_entry_pre_execution_with_pool(
         journal,
         session_id,
         &run_id,
-        &group_run.final_report,
-        false,
-        &group_run.final_report,
-        "",
-        None,
-        None,
+        RunSuccessOutput::text(&group_run.final_report),
     )
     .await?;
 
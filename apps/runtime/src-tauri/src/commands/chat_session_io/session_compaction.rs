@@ -44,6 +44,7 @@ pub(crate) async fn load_compaction_inputs_with_pool(
         .fetch_one(pool)
         .await
         .map_err(|e| e.to_string())?;
+    let api_key = crate::secret_store::reveal_secret(&api_key)?;
 
     Ok((messages, api_format, base_url, api_key, model_name))
 }
@@ -231,6 +231,10 @@ fn render_export_message_content(
 
     let mut sections: Vec<String> = Vec::new();
     if let Ok(parsed) = serde_json::from_str::<Value>(content) {
+        if let Some(reasoning_section) = render_export_reasoning(parsed.get("reasoning")) {
+            sections.push(reasoning_section);
+        }
+
         let final_text = parsed["text"].as_str().unwrap_or("").trim();
         if !final_text.is_empty() {
             sections.push(final_text.to_string());
@@ -284,6 +288,19 @@ fn render_export_message_content(
     }
 }
 
+fn render_export_reasoning(reasoning: Option<&Value>) -> Option<String> {
+    let text = reasoning?["content"]
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())?;
+    let mut lines = vec!["**思考过程**".to_string()];
+    lines.extend(
+        text.lines()
+            .map(|line| format!("> {}", line).trim_end().to_string()),
+    );
+    Some(lines.join("\n"))
+}
+
 fn push_unique_export_section(sections: &mut Vec<String>, section: String) {
     if !section.trim().is_empty() && !sections.iter().any(|existing| existing == &section) {
         sections.push(section);
@@ -822,12 +839,24 @@ fn export_status_label(status: &SessionRunStatus) -> &'static str {
 
 #[cfg(test)]
 mod tests {
-    use super::render_recovered_run_sections;
+    use super::{render_export_message_content, render_recovered_run_sections};
     use crate::session_journal::{
         SessionJournalState, SessionRunSnapshot, SessionRunStatus, SessionRunTaskIdentitySnapshot,
     };
     use std::collections::{HashMap, HashSet};
 
+    #[test]
+    fn export_message_content_includes_reasoning_before_answer() {
+        let content = r#"{"text":"目录里有 3 个文件","reasoning":{"status":"completed","duration_ms":1200,"content":"先列目录\n再统计"}}"#;
+
+        let rendered = render_export_message_content("assistant", content, None, &[]);
+
+        assert_eq!(
+            rendered,
+            "**思考过程**\n> 先列目录\n> 再统计\n\n目录里有 3 个文件"
+        );
+    }
+
     #[test]
     fn recovered_run_sections_include_task_identity_lines() {
         let output = render_recovered_run_sections(
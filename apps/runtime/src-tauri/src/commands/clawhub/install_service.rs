@@ -83,6 +83,7 @@ pub async fn install_clawhub_skill(
         created_at: Utc::now(),
         username_hint: None,
         encrypted_verify: String::new(),
+        ..Default::default()
     };
     ensure_skill_display_name_available(pool, &manifest.name, &manifest.id).await?;
 
@@ -3,10 +3,12 @@ use reqwest::Client;
 use serde_json::Value;
 use sqlx::SqlitePool;
 
-use crate::agent::types::LLMResponse;
+use crate::adapters::{ModelChatRequest, ModelUsageScope};
+use crate::agent::types::{LLMResponse, ReasoningSettings};
 use crate::commands::models::resolve_default_usable_model_id_with_pool;
 use crate::commands::runtime_preferences::get_runtime_preferences_with_pool;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind};
+use crate::model_transport::resolve_model_transport;
+use crate::providers::model_adapters;
 
 #[derive(Debug, Clone)]
 struct TranslationModelConfig {
@@ -161,20 +163,17 @@ async fn load_translation_model(
         None
     };
 
-    preferred
-        .or(primary)
-        .or(fallback)
-        .map(
-            |(api_format, base_url, model_name, api_key)| TranslationModelConfig {
-                api_format,
-                base_url,
-                model_name,
-                api_key,
-            },
-        )
+    let (api_format, base_url, model_name, api_key) = preferred.or(primary).or(fallback)?;
+    Some(TranslationModelConfig {
+        api_format,
+        base_url,
+        model_name,
+        api_key: crate::secret_store::reveal_secret(&api_key).ok()?,
+    })
 }
 
 async fn translate_text_via_model(
+    pool: &SqlitePool,
     model: &TranslationModelConfig,
     text: &str,
     target_lang: &str,
@@ -187,35 +186,29 @@ async fn translate_text_via_model(
         "content": user_prompt
     })];
     let transport = resolve_model_transport(&model.api_format, &model.base_url, None);
-    let response = match transport.kind {
-        ModelTransportKind::AnthropicMessages => {
-            crate::adapters::anthropic::chat_stream_with_tools(
-                &model.base_url,
-                &model.api_key,
-                &model.model_name,
-                "You are a professional translation assistant.",
-                messages,
-                vec![],
-                |_| {},
-            )
-            .await
-            .map_err(|e| e.to_string())?
-        }
-        ModelTransportKind::OpenAiCompletions | ModelTransportKind::OpenAiResponses => {
-            crate::adapters::openai::chat_stream_with_tools(
-                &transport,
-                &model.base_url,
-                &model.api_key,
-                &model.model_name,
-                "You are a professional translation assistant.",
+    let response = model_adapters()
+        .chat_stream(
+            ModelChatRequest {
+                transport: &transport,
+                base_url: &model.base_url,
+                api_key: &model.api_key,
+                model: &model.model_name,
+                system_prompt: "You are a professional translation assistant.",
                 messages,
-                vec![],
-                |_| {},
-            )
-            .await
-            .map_err(|e| e.to_string())?
-        }
-    };
+                tools: vec![],
+                reasoning: ReasoningSettings::default(),
+                structured_output: None,
+                usage_scope: Some(ModelUsageScope {
+                    pool,
+                    session_id: "",
+                    run_id: None,
+                }),
+            },
+            &mut |_| {},
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        .response;
 
     let translated = match response {
         LLMResponse::Text(v) => v,
@@ -296,7 +289,7 @@ pub async fn translate_texts_with_preferences_with_pool(
         }
 
         let translated = if let Some(model) = model_cfg.as_ref() {
-            match translate_text_via_model(model, &clean, &target_lang).await {
+            match translate_text_via_model(pool, model, &clean, &target_lang).await {
                 Ok(v) if !v.trim().is_empty() => v,
                 _ if allow_free => {
                     match translate_text_via_google(&client, &clean, &target_lang).await {
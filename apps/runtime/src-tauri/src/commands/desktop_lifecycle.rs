@@ -165,6 +165,7 @@ mod tests {
         CrashSummaryInfo, DesktopDiagnosticsExportPayload, DesktopDiagnosticsStatus,
     };
     use super::{build_desktop_environment_summary, clear_directory_contents};
+    use crate::agent::tools::{McpServerHealth, McpServerStatus, NativeMcpTransport};
     use tempfile::tempdir;
 
     #[test]
@@ -200,6 +201,18 @@ mod tests {
                 message: "panic occurred".to_string(),
                 run_id: Some("run-0".to_string()),
             }),
+            mcp_servers: vec![McpServerHealth {
+                server_id: "mcp-1".to_string(),
+                server_name: "docs".to_string(),
+                transport: NativeMcpTransport::StreamableHttp,
+                status: McpServerStatus::BackingOff,
+                consecutive_failures: 2,
+                last_error: Some("connection refused".to_string()),
+                connected_at: None,
+                last_success_at: None,
+                next_retry_at: Some("2026-03-13T10:00:02Z".to_string()),
+                tool_count: 0,
+            }],
         };
 
         let summary = build_desktop_environment_summary(
@@ -214,6 +227,9 @@ mod tests {
         assert!(summary.contains("Diagnostics Audit"));
         assert!(summary.contains("Abnormal Previous Run: yes"));
         assert!(summary.contains("Latest Crash: 2026-03-13T10:00:00Z panic occurred"));
+        assert!(summary.contains(
+            "MCP Servers: docs (streamable_http, backing_off, 0 tools, error: connection refused)"
+        ));
     }
 
     #[test]
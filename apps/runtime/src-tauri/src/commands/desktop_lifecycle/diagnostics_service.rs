@@ -3,8 +3,10 @@ use super::types::{
     FrontendDiagnosticPayload,
 };
 use crate::agent::runtime::RuntimeObservabilityState;
+use crate::agent::tools::McpSessionManager;
 use crate::commands::session_runs::export_session_run_trace_with_pool;
 use crate::diagnostics::{self};
+use crate::secret_store::redact_secrets;
 use sqlx::SqlitePool;
 use std::fs;
 use std::io::Write;
@@ -42,6 +44,7 @@ pub(crate) fn build_diagnostics_status(
         abnormal_previous_run: state.abnormal_previous_run.was_abnormal_exit,
         last_clean_exit_at: read_last_clean_exit_at(&state.paths),
         latest_crash,
+        mcp_servers: McpSessionManager::global().health(),
     })
 }
 
@@ -52,7 +55,7 @@ pub(crate) fn build_desktop_environment_summary(
     diagnostics_status: &DesktopDiagnosticsStatus,
 ) -> String {
     format!(
-        "# Environment Summary\n\n- Version: {version}\n- Platform: {platform}\n- Runtime Root: {runtime_root_dir}\n- Diagnostics: {}\n- Diagnostics Logs: {}\n- Diagnostics Audit: {}\n- Diagnostics Crashes: {}\n- Diagnostics Exports: {}\n- Current Run ID: {}\n- Abnormal Previous Run: {}\n- Last Clean Exit: {}\n- Latest Crash: {}\n",
+        "# Environment Summary\n\n- Version: {version}\n- Platform: {platform}\n- Runtime Root: {runtime_root_dir}\n- Diagnostics: {}\n- Diagnostics Logs: {}\n- Diagnostics Audit: {}\n- Diagnostics Crashes: {}\n- Diagnostics Exports: {}\n- Current Run ID: {}\n- Abnormal Previous Run: {}\n- Last Clean Exit: {}\n- Latest Crash: {}\n- MCP Servers: {}\n",
         diagnostics_status.diagnostics_dir,
         diagnostics_status.logs_dir,
         diagnostics_status.audit_dir,
@@ -72,7 +75,33 @@ pub(crate) fn build_desktop_environment_summary(
             .latest_crash
             .as_ref()
             .map(|crash| format!("{} {}", crash.timestamp, crash.message))
-            .unwrap_or_else(|| "none".to_string())
+            .unwrap_or_else(|| "none".to_string()),
+        if diagnostics_status.mcp_servers.is_empty() {
+            "none".to_string()
+        } else {
+            diagnostics_status
+                .mcp_servers
+                .iter()
+                .map(|server| {
+                    let status = server.status.as_str();
+                    match &server.last_error {
+                        Some(error) => format!(
+                            "{} ({}, {status}, {} tools, error: {error})",
+                            server.server_name,
+                            server.transport.as_str(),
+                            server.tool_count
+                        ),
+                        None => format!(
+                            "{} ({}, {status}, {} tools)",
+                            server.server_name,
+                            server.transport.as_str(),
+                            server.tool_count
+                        ),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("; ")
+        }
     )
 }
 
@@ -130,7 +159,8 @@ pub(crate) fn export_diagnostics_bundle(
     let mut add_text = |name: &str, content: &str| -> Result<(), String> {
         zip.start_file(name, options)
             .map_err(|e| format!("写入诊断包文件 {} 失败: {}", name, e))?;
-        zip.write_all(content.as_bytes())
+        // 诊断包会被发给他人排查问题，导出前再统一抹一遍凭据（含旧版本写下的日志）
+        zip.write_all(redact_secrets(content).as_bytes())
             .map_err(|e| format!("写入诊断包内容 {} 失败: {}", name, e))
     };
 
@@ -1,3 +1,4 @@
+use crate::agent::tools::McpServerHealth;
 use serde::Serialize;
 use std::path::PathBuf;
 
@@ -33,6 +34,7 @@ pub struct DesktopDiagnosticsStatus {
     pub abnormal_previous_run: bool,
     pub last_clean_exit_at: Option<String>,
     pub latest_crash: Option<CrashSummaryInfo>,
+    pub mcp_servers: Vec<McpServerHealth>,
 }
 
 #[derive(Debug, Clone)]
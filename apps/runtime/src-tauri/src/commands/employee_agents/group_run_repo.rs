@@ -406,12 +406,16 @@ pub(crate) async fn find_model_config_row(
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(row.map(|record| ModelConfigRow {
-        api_format: record.try_get(0).expect("model config api_format"),
-        base_url: record.try_get(1).expect("model config base_url"),
-        model_name: record.try_get(2).expect("model config model_name"),
-        api_key: record.try_get(3).expect("model config api_key"),
-    }))
+    row.map(|record| {
+        let api_key: String = record.try_get(3).expect("model config api_key");
+        Ok(ModelConfigRow {
+            api_format: record.try_get(0).expect("model config api_format"),
+            base_url: record.try_get(1).expect("model config base_url"),
+            model_name: record.try_get(2).expect("model config model_name"),
+            api_key: crate::secret_store::reveal_secret(&api_key)?,
+        })
+    })
+    .transpose()
 }
 
 pub(crate) async fn insert_session_message(
@@ -87,9 +87,12 @@ pub(crate) async fn list_agent_employee_rows(
             feishu_app_id: row
                 .try_get("feishu_app_id")
                 .expect("employee row feishu_app_id"),
-            feishu_app_secret: row
-                .try_get("feishu_app_secret")
-                .expect("employee row feishu_app_secret"),
+            // 本机密钥变更后无法解密时按未配置处理，由用户重新填写，不影响员工列表加载
+            feishu_app_secret: crate::secret_store::reveal_secret(
+                &row.try_get::<String, _>("feishu_app_secret")
+                    .expect("employee row feishu_app_secret"),
+            )
+            .unwrap_or_default(),
             primary_skill_id: row
                 .try_get("primary_skill_id")
                 .expect("employee row primary_skill_id"),
@@ -168,6 +171,7 @@ pub(crate) async fn upsert_agent_employee_record(
     tx: &mut Transaction<'_, Sqlite>,
     input: &UpsertAgentEmployeeRecordInput<'_>,
 ) -> Result<(), String> {
+    let feishu_app_secret = crate::secret_store::seal_secret(input.feishu_app_secret)?;
     sqlx::query(
         r#"
         INSERT INTO agent_employees (
@@ -201,7 +205,7 @@ pub(crate) async fn upsert_agent_employee_record(
     .bind(input.persona)
     .bind(input.feishu_open_id)
     .bind(input.feishu_app_id)
-    .bind(input.feishu_app_secret)
+    .bind(&feishu_app_secret)
     .bind(input.primary_skill_id)
     .bind(input.default_work_dir)
     .bind(input.openclaw_agent_id)
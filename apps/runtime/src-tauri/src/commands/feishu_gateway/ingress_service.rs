@@ -68,7 +68,9 @@ pub async fn validate_feishu_auth_with_pool(
             .fetch_optional(pool)
             .await
             .map_err(|e| e.to_string())?;
-    let expected = configured.map(|(v,)| v).unwrap_or_default();
+    let expected = crate::secret_store::reveal_secret(
+        &configured.map(|(v,)| v).unwrap_or_default(),
+    )?;
     if expected.trim().is_empty() {
         return Ok(());
     }
@@ -108,7 +110,7 @@ pub(crate) async fn list_enabled_employee_feishu_connections_with_pool(
             employee_id,
             name: name.trim().to_string(),
             app_id: app_id.trim().to_string(),
-            app_secret: app_secret.trim().to_string(),
+            app_secret: crate::secret_store::reveal_secret(app_secret.trim())?,
         });
     }
     Ok(result)
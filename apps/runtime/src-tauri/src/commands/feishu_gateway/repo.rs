@@ -8,10 +8,20 @@ pub async fn get_app_setting(pool: &SqlitePool, key: &str) -> Result<Option<Stri
             .fetch_optional(pool)
             .await
             .map_err(|e| e.to_string())?;
-    Ok(row.map(|(v,)| v))
+    match row {
+        Some((value,)) if crate::secret_store::is_secret_setting_key(key) => {
+            crate::secret_store::reveal_secret(&value).map(Some)
+        }
+        row => Ok(row.map(|(v,)| v)),
+    }
 }
 
 pub async fn set_app_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    let value = if crate::secret_store::is_secret_setting_key(key) {
+        crate::secret_store::seal_secret(value)?
+    } else {
+        value.to_string()
+    };
     sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
         .bind(key)
         .bind(value)
@@ -66,7 +76,7 @@ pub async fn resolve_feishu_app_credentials(
     .map_err(|e| e.to_string())?;
 
     if let Some((id, secret)) = employee_creds {
-        return Ok((Some(id), Some(secret)));
+        return Ok((Some(id), Some(crate::secret_store::reveal_secret(&secret)?)));
     }
 
     // Backward compatibility: legacy global settings fallback.
@@ -79,6 +79,7 @@ pub(crate) use interactive_messages::{
     load_approval_resolution_notification_with_pool,
 };
 pub(crate) use lifecycle::{
+    dispatch_im_reply_to_delivery_route_with_pool,
     emit_registered_lifecycle_phase_for_session_with_pool,
     lookup_channel_thread_for_session_with_pool,
     lookup_latest_inbox_message_id_for_thread_with_pool, lookup_session_delivery_route_with_pool,
@@ -1,5 +1,5 @@
 use super::contract::{ImReplyDeliveryPlan, ImReplyLifecyclePhase};
-use crate::im::find_channel_delivery_route_by_session_id;
+use crate::im::{find_channel_delivery_route_by_session_id, ChannelDeliveryRoute};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
@@ -366,6 +366,53 @@ pub(crate) async fn maybe_dispatch_registered_im_session_reply_with_pool(
     }
 }
 
+/// 直接按渠道投递路由发送文本，不要求会话已绑定到该 IM 会话（例如定时任务把结果推送到指定群）
+pub(crate) async fn dispatch_im_reply_to_delivery_route_with_pool(
+    pool: &SqlitePool,
+    route: &ChannelDeliveryRoute,
+    session_id: &str,
+    text: &str,
+) -> Result<bool, String> {
+    let normalized_text = text.trim();
+    let thread_id = route.reply_target.trim();
+    if normalized_text.is_empty() || thread_id.is_empty() {
+        return Ok(false);
+    }
+
+    match route.channel.trim() {
+        "feishu" => {
+            let plan = crate::commands::feishu_gateway::build_feishu_reply_plan(
+                &Uuid::new_v4().to_string(),
+                session_id,
+                thread_id,
+                normalized_text,
+            );
+            let account_id = Some(route.account_id.trim().to_string())
+                .filter(|account_id| !account_id.is_empty());
+            crate::commands::feishu_gateway::execute_registered_feishu_reply_plan_with_pool(
+                pool, &plan, account_id,
+            )
+            .await?;
+            Ok(true)
+        }
+        "wecom" => {
+            let plan = ImReplyDeliveryPlan {
+                logical_reply_id: Uuid::new_v4().to_string(),
+                session_id: session_id.trim().to_string(),
+                channel: "wecom".to_string(),
+                thread_id: thread_id.to_string(),
+                chunks: super::chunk_planner::plan_text_chunks(normalized_text, 1800),
+            };
+            crate::commands::wecom_gateway::execute_registered_wecom_reply_plan_with_pool(
+                pool, &plan, None,
+            )
+            .await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
 pub(crate) async fn lookup_latest_inbox_message_id_for_thread_with_pool(
     pool: &SqlitePool,
     source: &str,
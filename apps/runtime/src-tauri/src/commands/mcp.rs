@@ -1,5 +1,5 @@
 use super::skills::DbState;
-use crate::agent::tools::{list_native_mcp_tools, NativeMcpServerConfig, NativeMcpTool};
+use crate::agent::tools::{McpSessionManager, NativeMcpServerConfig, NativeMcpTransport};
 use crate::agent::ToolRegistry;
 use chrono::Utc;
 use serde_json::{json, Value};
@@ -8,63 +8,82 @@ use std::sync::Arc;
 use tauri::State;
 use uuid::Uuid;
 
+const MASKED_SECRET_VALUE: &str = "******";
+
+/// 为服务建立长连接会话并注册其工具；连接失败时会话管理器会在后台按退避重试
 pub async fn register_native_mcp_server_tools(
     registry: Arc<ToolRegistry>,
     server: NativeMcpServerConfig,
 ) -> Result<usize, String> {
-    let tools = list_native_mcp_tools(&server)
+    McpSessionManager::global()
+        .attach(&registry, server)
         .await
-        .map_err(|e| e.to_string())?;
-    let mut registered = 0;
-
-    for tool in tools {
-        let full_name = format!("mcp_{}_{}", server.name, tool.name);
-        registry.register(Arc::new(NativeMcpTool::new(
-            full_name,
-            tool.description,
-            tool.input_schema,
-            server.clone(),
-            tool.name,
-        )));
-        registered += 1;
+        .map_err(|e| e.to_string())
+}
+
+/// env 与 headers 里常带访问令牌，非空时整体加密后入库
+fn seal_secret_map(map: &HashMap<String, String>) -> Result<String, String> {
+    if map.is_empty() {
+        return Ok("{}".to_string());
     }
+    let json = serde_json::to_string(map).map_err(|e| e.to_string())?;
+    crate::secret_store::seal_secret(&json)
+}
 
-    Ok(registered)
+fn reveal_secret_map(stored: &str) -> Result<HashMap<String, String>, String> {
+    let json = crate::secret_store::reveal_secret(stored)?;
+    Ok(serde_json::from_str(&json).unwrap_or_default())
 }
 
-pub async fn add_mcp_server_with_registry(
+/// 列表只回显 header 名称，值统一打码，避免把令牌送回前端
+fn mask_secret_map(map: HashMap<String, String>) -> HashMap<String, String> {
+    map.into_keys()
+        .map(|key| (key, MASKED_SECRET_VALUE.to_string()))
+        .collect()
+}
+
+async fn insert_and_attach_mcp_server(
     pool: &sqlx::SqlitePool,
     registry: Arc<ToolRegistry>,
-    name: String,
-    command: String,
-    args: Vec<String>,
-    env: HashMap<String, String>,
+    mut server: NativeMcpServerConfig,
 ) -> Result<String, String> {
+    match server.transport {
+        NativeMcpTransport::Stdio if server.command.trim().is_empty() => {
+            return Err("stdio MCP 服务需要填写启动命令".to_string());
+        }
+        NativeMcpTransport::StreamableHttp | NativeMcpTransport::Sse
+            if server.url.as_deref().unwrap_or_default().trim().is_empty() =>
+        {
+            return Err("远程 MCP 服务需要填写 URL".to_string());
+        }
+        _ => {}
+    }
+
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
+    server.id = id.clone();
+    let sealed_env = seal_secret_map(&server.env)?;
+    let sealed_headers = seal_secret_map(&server.headers)?;
 
     // 保存到数据库
     sqlx::query(
-        "INSERT INTO mcp_servers (id, name, command, args, env, enabled, created_at) VALUES (?, ?, ?, ?, ?, 1, ?)"
+        "INSERT INTO mcp_servers (id, name, command, args, env, transport, url, headers, enabled, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?)"
     )
     .bind(&id)
-    .bind(&name)
-    .bind(&command)
-    .bind(serde_json::to_string(&args).unwrap_or_default())
-    .bind(serde_json::to_string(&env).unwrap_or_default())
+    .bind(&server.name)
+    .bind(&server.command)
+    .bind(serde_json::to_string(&server.args).unwrap_or_default())
+    .bind(&sealed_env)
+    .bind(server.transport.as_str())
+    .bind(server.url.clone().unwrap_or_default())
+    .bind(&sealed_headers)
     .bind(&now)
     .execute(pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    let server = NativeMcpServerConfig {
-        name,
-        command,
-        args,
-        env,
-    };
-
     if let Err(error) = register_native_mcp_server_tools(registry, server).await {
+        McpSessionManager::global().detach(&id).await;
         let _ = sqlx::query("DELETE FROM mcp_servers WHERE id = ?")
             .bind(&id)
             .execute(pool)
@@ -75,18 +94,71 @@ pub async fn add_mcp_server_with_registry(
     Ok(id)
 }
 
+pub async fn add_mcp_server_with_registry(
+    pool: &sqlx::SqlitePool,
+    registry: Arc<ToolRegistry>,
+    name: String,
+    command: String,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+) -> Result<String, String> {
+    let server = NativeMcpServerConfig {
+        name,
+        command,
+        args,
+        env,
+        ..Default::default()
+    };
+    insert_and_attach_mcp_server(pool, registry, server).await
+}
+
+pub async fn add_remote_mcp_server_with_registry(
+    pool: &sqlx::SqlitePool,
+    registry: Arc<ToolRegistry>,
+    name: String,
+    transport: NativeMcpTransport,
+    url: String,
+    headers: HashMap<String, String>,
+) -> Result<String, String> {
+    let server = NativeMcpServerConfig {
+        name,
+        transport,
+        url: Some(url),
+        headers,
+        ..Default::default()
+    };
+    insert_and_attach_mcp_server(pool, registry, server).await
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_mcp_server(
     name: String,
     command: String,
     args: Vec<String>,
     env: std::collections::HashMap<String, String>,
+    transport: Option<String>,
+    url: Option<String>,
+    headers: Option<HashMap<String, String>>,
     db: State<'_, DbState>,
     registry: State<'_, Arc<ToolRegistry>>,
 ) -> Result<String, String> {
+    let transport = NativeMcpTransport::parse(transport.as_deref().unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+    if transport != NativeMcpTransport::Stdio {
+        return add_remote_mcp_server_with_registry(
+            &db.0,
+            Arc::clone(registry.inner()),
+            name,
+            transport,
+            url.unwrap_or_default(),
+            headers.unwrap_or_default(),
+        )
+        .await;
+    }
     add_mcp_server_with_registry(
         &db.0,
-        Arc::clone(&registry.inner()),
+        Arc::clone(registry.inner()),
         name,
         command,
         args,
@@ -97,26 +169,37 @@ pub async fn add_mcp_server(
 
 #[tauri::command]
 pub async fn list_mcp_servers(db: State<'_, DbState>) -> Result<Vec<Value>, String> {
-    let rows = sqlx::query_as::<_, (String, String, String, String, String, i32, String)>(
-        "SELECT id, name, command, args, env, enabled, created_at FROM mcp_servers ORDER BY created_at DESC"
+    list_mcp_servers_with_pool(&db.0).await
+}
+
+pub async fn list_mcp_servers_with_pool(pool: &sqlx::SqlitePool) -> Result<Vec<Value>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, String, String, String, String, i32, String)>(
+        "SELECT id, name, command, args, env, transport, url, headers, enabled, created_at FROM mcp_servers ORDER BY created_at DESC"
     )
-    .fetch_all(&db.0)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
 
+    let manager = McpSessionManager::global();
     Ok(rows
         .iter()
-        .map(|(id, name, command, args, env, enabled, created_at)| {
-            json!({
-                "id": id,
-                "name": name,
-                "command": command,
-                "args": serde_json::from_str::<Value>(args).unwrap_or(json!([])),
-                "env": serde_json::from_str::<Value>(env).unwrap_or(json!({})),
-                "enabled": enabled == &1,
-                "created_at": created_at,
-            })
-        })
+        .map(
+            |(id, name, command, args, env, transport, url, headers, enabled, created_at)| {
+                json!({
+                    "id": id,
+                    "name": name,
+                    "command": command,
+                    "args": serde_json::from_str::<Value>(args).unwrap_or(json!([])),
+                    "env": reveal_secret_map(env).unwrap_or_default(),
+                    "transport": transport,
+                    "url": url,
+                    "headers": mask_secret_map(reveal_secret_map(headers).unwrap_or_default()),
+                    "enabled": enabled == &1,
+                    "created_at": created_at,
+                    "health": manager.get(id).map(|handle| handle.health()),
+                })
+            },
+        )
         .collect())
 }
 
@@ -132,7 +215,8 @@ pub async fn remove_mcp_server_with_registry(
         .await
         .map_err(|e| e.to_string())?;
 
-    // 从 registry 反注册所有该服务器的工具
+    // 断开长连接并从 registry 反注册所有该服务器的工具
+    McpSessionManager::global().detach(&id).await;
     let prefix = format!("mcp_{}_", name);
     let tool_names = registry.tools_with_prefix(&prefix);
     for tool_name in tool_names {
@@ -155,29 +239,49 @@ pub async fn remove_mcp_server(
     db: State<'_, DbState>,
     registry: State<'_, Arc<ToolRegistry>>,
 ) -> Result<(), String> {
-    remove_mcp_server_with_registry(&db.0, Arc::clone(&registry.inner()), id).await
+    remove_mcp_server_with_registry(&db.0, Arc::clone(registry.inner()), id).await
 }
 
 pub async fn restore_saved_mcp_servers_with_registry(
     pool: &sqlx::SqlitePool,
     registry: Arc<ToolRegistry>,
 ) -> Result<usize, String> {
-    let servers = sqlx::query_as::<_, (String, String, String, String)>(
-        "SELECT name, command, args, env FROM mcp_servers WHERE enabled = 1",
+    let servers = sqlx::query_as::<_, (String, String, String, String, String, String, String, String)>(
+        "SELECT id, name, transport, command, args, env, url, headers FROM mcp_servers WHERE enabled = 1",
     )
     .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
 
     let mut restored = 0;
-    for (name, command, args_json, env_json) in servers {
+    for (id, name, transport, command, args_json, env_json, url, headers_json) in servers {
+        let transport = match NativeMcpTransport::parse(&transport) {
+            Ok(transport) => transport,
+            Err(error) => {
+                eprintln!("[mcp] skipped native MCP server {name}: {error}");
+                continue;
+            }
+        };
         let args: Vec<String> = serde_json::from_str(&args_json).unwrap_or_default();
-        let env: HashMap<String, String> = serde_json::from_str(&env_json).unwrap_or_default();
+        let (env, headers) = match (
+            reveal_secret_map(&env_json),
+            reveal_secret_map(&headers_json),
+        ) {
+            (Ok(env), Ok(headers)) => (env, headers),
+            (Err(error), _) | (_, Err(error)) => {
+                eprintln!("[mcp] skipped native MCP server {name}: {error}");
+                continue;
+            }
+        };
         let server = NativeMcpServerConfig {
+            id,
             name: name.clone(),
+            transport,
             command,
             args,
             env,
+            url: Some(url).filter(|url| !url.is_empty()),
+            headers,
         };
 
         match register_native_mcp_server_tools(Arc::clone(&registry), server).await {
@@ -186,7 +290,9 @@ pub async fn restore_saved_mcp_servers_with_registry(
                 eprintln!("[mcp] restored native MCP server tool registration for {name}");
             }
             Err(error) => {
-                eprintln!("[mcp] failed to restore native MCP server {name}: {error}");
+                eprintln!(
+                    "[mcp] failed to restore native MCP server {name}, retrying in background: {error}"
+                );
             }
         }
     }
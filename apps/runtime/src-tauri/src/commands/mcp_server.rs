@@ -0,0 +1,330 @@
+use super::skills::DbState;
+use crate::mcp_server::{
+    AppMcpServerBackend, McpHttpServer, McpServerDispatcher, MCP_HTTP_PATH, MCP_SERVER_NAME,
+    MCP_STDIO_FLAG, MCP_TOKEN_ENV, MCP_URL_ENV,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+const MCP_SERVER_ENABLED_KEY: &str = "mcp_server_enabled";
+const MCP_SERVER_PORT_KEY: &str = "mcp_server_port";
+const MCP_SERVER_TOKEN_KEY: &str = "mcp_server_token";
+pub const DEFAULT_MCP_SERVER_PORT: u16 = 47821;
+const MIN_MCP_SERVER_PORT: u16 = 1024;
+
+#[derive(Default)]
+struct McpServerRuntime {
+    server: Option<McpHttpServer>,
+    last_error: Option<String>,
+}
+
+/// 本机 MCP 服务端的运行状态（在 lib.rs 中注册）
+#[derive(Clone, Default)]
+pub struct McpServerRuntimeState(Arc<Mutex<McpServerRuntime>>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    pub running: bool,
+    pub url: String,
+    pub last_error: Option<String>,
+    /// 可直接粘贴到 MCP 客户端配置中的 stdio 启动配置
+    pub client_config: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpServerSettingsInput {
+    pub enabled: bool,
+    pub port: Option<u16>,
+}
+
+async fn write_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn write_token_setting(pool: &SqlitePool, token: &str) -> Result<(), String> {
+    let sealed = crate::secret_store::seal_secret(token)?;
+    write_setting(pool, MCP_SERVER_TOKEN_KEY, &sealed).await
+}
+
+fn generate_mcp_server_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// 读取服务端配置；首次读取时生成并保存访问令牌
+pub async fn load_mcp_server_config_with_pool(
+    pool: &SqlitePool,
+) -> Result<McpServerConfig, String> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT key, value FROM app_settings WHERE key IN (?, ?, ?)",
+    )
+    .bind(MCP_SERVER_ENABLED_KEY)
+    .bind(MCP_SERVER_PORT_KEY)
+    .bind(MCP_SERVER_TOKEN_KEY)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut config = McpServerConfig {
+        enabled: false,
+        port: DEFAULT_MCP_SERVER_PORT,
+        token: String::new(),
+    };
+    for (key, value) in rows {
+        match key.as_str() {
+            MCP_SERVER_ENABLED_KEY => config.enabled = value.trim() == "true",
+            MCP_SERVER_PORT_KEY => {
+                config.port = value
+                    .trim()
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|port| *port >= MIN_MCP_SERVER_PORT)
+                    .unwrap_or(DEFAULT_MCP_SERVER_PORT)
+            }
+            MCP_SERVER_TOKEN_KEY => {
+                config.token = crate::secret_store::reveal_secret(value.trim())?
+            }
+            _ => {}
+        }
+    }
+    if config.token.is_empty() {
+        config.token = generate_mcp_server_token();
+        write_token_setting(pool, &config.token).await?;
+    }
+    Ok(config)
+}
+
+pub async fn save_mcp_server_config_with_pool(
+    pool: &SqlitePool,
+    input: McpServerSettingsInput,
+) -> Result<McpServerConfig, String> {
+    if let Some(port) = input.port {
+        if port < MIN_MCP_SERVER_PORT {
+            return Err(format!("MCP 服务端口需在 {MIN_MCP_SERVER_PORT}-65535 之间"));
+        }
+        write_setting(pool, MCP_SERVER_PORT_KEY, &port.to_string()).await?;
+    }
+    write_setting(
+        pool,
+        MCP_SERVER_ENABLED_KEY,
+        if input.enabled { "true" } else { "false" },
+    )
+    .await?;
+    load_mcp_server_config_with_pool(pool).await
+}
+
+pub async fn regenerate_mcp_server_token_with_pool(
+    pool: &SqlitePool,
+) -> Result<McpServerConfig, String> {
+    write_token_setting(pool, &generate_mcp_server_token()).await?;
+    load_mcp_server_config_with_pool(pool).await
+}
+
+/// 按配置停止并（在启用时）重新启动本机 HTTP 端点
+async fn apply_mcp_server_config(
+    app: &AppHandle,
+    state: &McpServerRuntimeState,
+    config: &McpServerConfig,
+) {
+    let mut runtime = state.0.lock().await;
+    if let Some(server) = runtime.server.take() {
+        server.shutdown().await;
+    }
+    runtime.last_error = None;
+    if !config.enabled {
+        return;
+    }
+    let dispatcher = Arc::new(McpServerDispatcher::new(Arc::new(
+        AppMcpServerBackend::new(app.clone()),
+    )));
+    match McpHttpServer::bind(config.port, config.token.clone(), dispatcher).await {
+        Ok(server) => runtime.server = Some(server),
+        Err(error) => {
+            eprintln!("[mcp-server] {error}");
+            runtime.last_error = Some(error);
+        }
+    }
+}
+
+async fn build_mcp_server_settings(
+    state: &McpServerRuntimeState,
+    config: McpServerConfig,
+) -> McpServerSettings {
+    let runtime = state.0.lock().await;
+    let url = runtime
+        .server
+        .as_ref()
+        .map(McpHttpServer::url)
+        .unwrap_or_else(|| format!("http://127.0.0.1:{}{}", config.port, MCP_HTTP_PATH));
+    let command = std::env::current_exe()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let client_config = json!({
+        "mcpServers": {
+            MCP_SERVER_NAME: {
+                "command": command,
+                "args": [MCP_STDIO_FLAG],
+                "env": {
+                    MCP_URL_ENV: &url,
+                    MCP_TOKEN_ENV: &config.token,
+                },
+            },
+        },
+    });
+    McpServerSettings {
+        enabled: config.enabled,
+        port: config.port,
+        token: config.token,
+        running: runtime.server.is_some(),
+        url,
+        last_error: runtime.last_error.clone(),
+        client_config,
+    }
+}
+
+/// 应用启动时按已保存的配置启动 MCP 服务端
+pub fn restore_mcp_server(app: AppHandle, pool: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        let config = match load_mcp_server_config_with_pool(&pool).await {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("[mcp-server] failed to load settings: {error}");
+                return;
+            }
+        };
+        if !config.enabled {
+            return;
+        }
+        let state = app.state::<McpServerRuntimeState>().inner().clone();
+        apply_mcp_server_config(&app, &state, &config).await;
+    });
+}
+
+#[tauri::command]
+pub async fn get_mcp_server_settings(
+    db: State<'_, DbState>,
+    state: State<'_, McpServerRuntimeState>,
+) -> Result<McpServerSettings, String> {
+    let config = load_mcp_server_config_with_pool(&db.0).await?;
+    Ok(build_mcp_server_settings(&state, config).await)
+}
+
+#[tauri::command]
+pub async fn save_mcp_server_settings(
+    input: McpServerSettingsInput,
+    app: AppHandle,
+    db: State<'_, DbState>,
+    state: State<'_, McpServerRuntimeState>,
+) -> Result<McpServerSettings, String> {
+    let config = save_mcp_server_config_with_pool(&db.0, input).await?;
+    apply_mcp_server_config(&app, &state, &config).await;
+    Ok(build_mcp_server_settings(&state, config).await)
+}
+
+#[tauri::command]
+pub async fn regenerate_mcp_server_token(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    state: State<'_, McpServerRuntimeState>,
+) -> Result<McpServerSettings, String> {
+    let config = regenerate_mcp_server_token_with_pool(&db.0).await?;
+    apply_mcp_server_config(&app, &state, &config).await;
+    Ok(build_mcp_server_settings(&state, config).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_memory_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("create sqlite memory pool");
+        sqlx::query(
+            "CREATE TABLE app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .expect("create app_settings table");
+        pool
+    }
+
+    #[tokio::test]
+    async fn mcp_server_config_defaults_to_disabled_with_a_persisted_token() {
+        let pool = setup_memory_pool().await;
+
+        let first = load_mcp_server_config_with_pool(&pool)
+            .await
+            .expect("load config");
+        assert!(!first.enabled);
+        assert_eq!(first.port, DEFAULT_MCP_SERVER_PORT);
+        assert_eq!(first.token.len(), 64);
+
+        let second = load_mcp_server_config_with_pool(&pool)
+            .await
+            .expect("reload config");
+        assert_eq!(second.token, first.token);
+
+        let rotated = regenerate_mcp_server_token_with_pool(&pool)
+            .await
+            .expect("rotate token");
+        assert_ne!(rotated.token, first.token);
+    }
+
+    #[tokio::test]
+    async fn save_mcp_server_config_rejects_privileged_ports() {
+        let pool = setup_memory_pool().await;
+
+        let error = save_mcp_server_config_with_pool(
+            &pool,
+            McpServerSettingsInput {
+                enabled: true,
+                port: Some(80),
+            },
+        )
+        .await
+        .expect_err("privileged port should be rejected");
+        assert!(error.contains("1024"));
+
+        let saved = save_mcp_server_config_with_pool(
+            &pool,
+            McpServerSettingsInput {
+                enabled: true,
+                port: Some(48000),
+            },
+        )
+        .await
+        .expect("save config");
+        assert!(saved.enabled);
+        assert_eq!(saved.port, 48000);
+    }
+}
@@ -24,13 +24,23 @@ pub mod im_host;
 pub mod im_ingress;
 pub mod im_routing;
 pub mod mcp;
+pub mod mcp_server;
+pub mod model_catalog;
+pub mod model_reasoning;
+pub mod model_usage;
 pub mod models;
 pub mod models_repo;
 pub mod openclaw_gateway;
 pub mod openclaw_plugins;
 pub mod packaging;
+pub mod route_health;
+pub mod run_budgets;
 pub mod runtime_preferences;
+pub mod scheduled_jobs;
 pub mod session_runs;
+pub mod shell_sandbox;
 pub mod skills;
+pub mod web_fetch_policy;
 pub mod wecom_gateway;
+pub mod workspace_checkpoints;
 pub mod workspace_files;
@@ -0,0 +1,40 @@
+use super::skills::DbState;
+use crate::model_catalog::{
+    current_model_catalog, delete_model_catalog_override_with_pool,
+    list_model_catalog_overrides_with_pool, save_model_catalog_override_with_pool,
+    ModelCatalogOverride,
+};
+use runtime_routing_core::{ModelCatalog, ModelCatalogEntry};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_model_catalog() -> Result<ModelCatalog, String> {
+    Ok(current_model_catalog().as_ref().clone())
+}
+
+#[tauri::command]
+pub async fn list_model_catalog_overrides(
+    db: State<'_, DbState>,
+) -> Result<Vec<ModelCatalogOverride>, String> {
+    list_model_catalog_overrides_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_model_catalog_override(
+    entry: ModelCatalogEntry,
+    db: State<'_, DbState>,
+) -> Result<ModelCatalog, String> {
+    save_model_catalog_override_with_pool(&db.0, entry)
+        .await
+        .map(|catalog| catalog.as_ref().clone())
+}
+
+#[tauri::command]
+pub async fn delete_model_catalog_override(
+    model: String,
+    db: State<'_, DbState>,
+) -> Result<ModelCatalog, String> {
+    delete_model_catalog_override_with_pool(&db.0, &model)
+        .await
+        .map(|catalog| catalog.as_ref().clone())
+}
@@ -0,0 +1,41 @@
+use super::skills::DbState;
+use crate::agent::types::ReasoningSettings;
+use crate::model_reasoning::{
+    get_model_reasoning_settings_with_pool, get_route_reasoning_settings_with_pool,
+    save_model_reasoning_settings_with_pool, save_route_reasoning_settings_with_pool,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_model_reasoning_settings(
+    model_id: String,
+    db: State<'_, DbState>,
+) -> Result<ReasoningSettings, String> {
+    get_model_reasoning_settings_with_pool(&db.0, &model_id).await
+}
+
+#[tauri::command]
+pub async fn save_model_reasoning_settings(
+    model_id: String,
+    settings: ReasoningSettings,
+    db: State<'_, DbState>,
+) -> Result<ReasoningSettings, String> {
+    save_model_reasoning_settings_with_pool(&db.0, &model_id, settings).await
+}
+
+#[tauri::command]
+pub async fn get_route_reasoning_settings(
+    capability: String,
+    db: State<'_, DbState>,
+) -> Result<ReasoningSettings, String> {
+    get_route_reasoning_settings_with_pool(&db.0, &capability).await
+}
+
+#[tauri::command]
+pub async fn save_route_reasoning_settings(
+    capability: String,
+    settings: ReasoningSettings,
+    db: State<'_, DbState>,
+) -> Result<ReasoningSettings, String> {
+    save_route_reasoning_settings_with_pool(&db.0, &capability, settings).await
+}
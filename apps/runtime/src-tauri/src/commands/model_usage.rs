@@ -0,0 +1,33 @@
+use super::skills::DbState;
+use crate::model_usage::{
+    delete_model_price_with_pool, list_model_prices_with_pool, save_model_price_with_pool,
+    summarize_model_usage_with_pool, ModelPriceInput, ModelPriceRecord, ModelUsageQuery,
+    ModelUsageSummary,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_model_usage_summary(
+    query: ModelUsageQuery,
+    db: State<'_, DbState>,
+) -> Result<Vec<ModelUsageSummary>, String> {
+    summarize_model_usage_with_pool(&db.0, &query).await
+}
+
+#[tauri::command]
+pub async fn list_model_prices(db: State<'_, DbState>) -> Result<Vec<ModelPriceRecord>, String> {
+    list_model_prices_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_model_price(
+    input: ModelPriceInput,
+    db: State<'_, DbState>,
+) -> Result<ModelPriceRecord, String> {
+    save_model_price_with_pool(&db.0, input).await
+}
+
+#[tauri::command]
+pub async fn delete_model_price(price_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    delete_model_price_with_pool(&db.0, &price_id).await
+}
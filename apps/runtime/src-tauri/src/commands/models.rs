@@ -3,15 +3,17 @@ use super::models_repo::{
     RuntimeProviderHealthProbe,
 };
 use super::skills::DbState;
+use crate::adapters::{ollama, ModelConnectionRequest};
+use crate::model_catalog::current_model_catalog;
 use crate::model_errors::{
     build_failed_connection_test_result, build_success_connection_test_result,
     ModelConnectionTestResult,
 };
 use crate::model_transport::{resolve_model_transport, ModelTransportKind};
-use runtime_models_app::ModelsAppService;
-use runtime_routing_core::{
-    filter_models_by_capability, recommended_models_for_provider, CapabilityRouteTemplateInfo,
-};
+use crate::providers::model_adapters;
+use chrono::Utc;
+use runtime_models_app::{ModelsAppService, ModelsReadRepository};
+use runtime_routing_core::CapabilityRouteTemplateInfo;
 use sqlx::SqlitePool;
 use tauri::State;
 
@@ -100,10 +102,93 @@ pub async fn list_provider_models_from_pool(
     provider_id: &str,
     capability: Option<&str>,
 ) -> Result<Vec<String>, String> {
-    let service = ModelsAppService::new(PoolModelsRepository::new(db), NullProviderCatalog);
+    refresh_local_models_before_listing(db, provider_id).await;
+    let service = ModelsAppService::new(PoolModelsRepository::new(db), NullProviderCatalog)
+        .with_model_catalog(current_model_catalog());
     service.list_provider_models(provider_id, capability).await
 }
 
+const LOCAL_MODEL_CATALOG_TTL_SECONDS: i64 = 3600;
+
+/// Ollama Provider（原生或 OpenAI 兼容接入）返回其服务地址，其余 Provider 返回 None
+async fn load_ollama_provider_base_url(
+    repo: &PoolModelsRepository<'_>,
+    provider_id: &str,
+) -> Result<Option<String>, String> {
+    let provider_key = repo.get_provider_key(provider_id).await?;
+    let Some(info) = repo.get_provider_connection_info(provider_id).await? else {
+        return Ok(None);
+    };
+    let transport = resolve_model_transport(
+        &info.protocol_type,
+        &info.base_url,
+        Some(provider_key.as_str()),
+    );
+    let is_ollama = transport.kind == ModelTransportKind::OllamaChat
+        || provider_key.trim().eq_ignore_ascii_case("ollama");
+    Ok(is_ollama.then_some(info.base_url))
+}
+
+/// 从本地 Ollama 服务发现已拉取的模型，并写入 model_catalog_cache
+pub async fn discover_local_provider_models_with_pool(
+    db: &SqlitePool,
+    provider_id: &str,
+) -> Result<Vec<String>, String> {
+    let repo = PoolModelsRepository::new(db);
+    let base_url = load_ollama_provider_base_url(&repo, provider_id)
+        .await?
+        .ok_or_else(|| "仅 Ollama Provider 支持本地模型发现".to_string())?;
+    let mut models = ollama::list_local_models(&base_url)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|model| model.name)
+        .collect::<Vec<_>>();
+    models.sort();
+    repo.replace_model_catalog_cache(
+        provider_id,
+        &models,
+        &Utc::now().to_rfc3339(),
+        LOCAL_MODEL_CATALOG_TTL_SECONDS,
+    )
+    .await?;
+    Ok(models)
+}
+
+/// 拉取模型到本地 Ollama，完成后刷新模型目录
+pub async fn pull_local_provider_model_with_pool(
+    db: &SqlitePool,
+    provider_id: &str,
+    model: &str,
+) -> Result<Vec<String>, String> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("模型名称不能为空".to_string());
+    }
+    let repo = PoolModelsRepository::new(db);
+    let base_url = load_ollama_provider_base_url(&repo, provider_id)
+        .await?
+        .ok_or_else(|| "仅 Ollama Provider 支持拉取本地模型".to_string())?;
+    ollama::pull_model(&base_url, model)
+        .await
+        .map_err(|e| e.to_string())?;
+    discover_local_provider_models_with_pool(db, provider_id).await
+}
+
+/// 本地 Provider 的模型列表以实际拉取结果为准；服务未启动时回退到推荐列表
+async fn refresh_local_models_before_listing(db: &SqlitePool, provider_id: &str) {
+    let repo = PoolModelsRepository::new(db);
+    if !matches!(
+        load_ollama_provider_base_url(&repo, provider_id).await,
+        Ok(Some(_))
+    ) {
+        return;
+    }
+    if let Err(err) = discover_local_provider_models_with_pool(db, provider_id).await {
+        eprintln!("[models] 本地模型发现失败，使用缓存或推荐列表: {}", err);
+    }
+}
+
 #[tauri::command]
 pub async fn get_routing_settings(db: State<'_, DbState>) -> Result<RoutingSettings, String> {
     let service = ModelsAppService::new(PoolModelsRepository::new(&db.0), NullProviderCatalog);
@@ -203,8 +288,9 @@ pub async fn list_provider_recommended_models(
     provider_key: String,
     capability: Option<String>,
 ) -> Result<Vec<String>, String> {
-    Ok(filter_models_by_capability(
-        recommended_models_for_provider(&provider_key),
+    let catalog = current_model_catalog();
+    Ok(catalog.filter_models_by_capability(
+        catalog.recommended_models_for_provider(&provider_key),
         capability.as_deref(),
     ))
 }
@@ -215,10 +301,24 @@ pub async fn list_provider_models(
     capability: Option<String>,
     db: State<'_, DbState>,
 ) -> Result<Vec<String>, String> {
-    let service = ModelsAppService::new(PoolModelsRepository::new(&db.0), NullProviderCatalog);
-    service
-        .list_provider_models(&provider_id, capability.as_deref())
-        .await
+    list_provider_models_from_pool(&db.0, &provider_id, capability.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn discover_local_provider_models(
+    provider_id: String,
+    db: State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    discover_local_provider_models_with_pool(&db.0, &provider_id).await
+}
+
+#[tauri::command]
+pub async fn pull_local_provider_model(
+    provider_id: String,
+    model: String,
+    db: State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    pull_local_provider_model_with_pool(&db.0, &provider_id, &model).await
 }
 
 #[tauri::command]
@@ -337,14 +437,16 @@ pub async fn list_model_configs(db: State<'_, DbState>) -> Result<Vec<ModelConfi
     Ok(rows
         .into_iter()
         .map(
-            |(id, name, api_format, base_url, model_name, is_default, supports_vision)| ModelConfig {
-                id,
-                name,
-                api_format,
-                base_url,
-                model_name,
-                is_default,
-                supports_vision,
+            |(id, name, api_format, base_url, model_name, is_default, supports_vision)| {
+                ModelConfig {
+                    id,
+                    name,
+                    api_format,
+                    base_url,
+                    model_name,
+                    is_default,
+                    supports_vision,
+                }
             },
         )
         .collect())
@@ -360,7 +462,7 @@ pub async fn get_model_api_key(model_id: String, db: State<'_, DbState>) -> Resu
         .map_err(|e| e.to_string())?;
 
     match row {
-        Some((key,)) => Ok(key),
+        Some((key,)) => crate::secret_store::reveal_secret(&key),
         None => Err("配置不存在".to_string()),
     }
 }
@@ -382,18 +484,14 @@ pub async fn test_connection_cmd(
     api_key: String,
 ) -> Result<ModelConnectionTestResult, String> {
     let transport = resolve_model_transport(&config.api_format, &config.base_url, None);
-    let connection_result = if transport.kind == ModelTransportKind::AnthropicMessages {
-        crate::adapters::anthropic::test_connection(&config.base_url, &api_key, &config.model_name)
-            .await
-    } else {
-        crate::adapters::openai::test_connection(
-            &transport,
-            &config.base_url,
-            &api_key,
-            &config.model_name,
-        )
-        .await
-    };
+    let connection_result = model_adapters()
+        .test_connection(ModelConnectionRequest {
+            transport: &transport,
+            base_url: &config.base_url,
+            api_key: &api_key,
+            model: &config.model_name,
+        })
+        .await;
 
     let result = match connection_result {
         Ok(true) => build_success_connection_test_result(),
@@ -417,14 +515,16 @@ pub async fn list_search_configs(db: State<'_, DbState>) -> Result<Vec<ModelConf
     Ok(rows
         .into_iter()
         .map(
-            |(id, name, api_format, base_url, model_name, is_default, supports_vision)| ModelConfig {
-                id,
-                name,
-                api_format,
-                base_url,
-                model_name,
-                is_default,
-                supports_vision,
+            |(id, name, api_format, base_url, model_name, is_default, supports_vision)| {
+                ModelConfig {
+                    id,
+                    name,
+                    api_format,
+                    base_url,
+                    model_name,
+                    is_default,
+                    supports_vision,
+                }
             },
         )
         .collect())
@@ -1,6 +1,7 @@
 use super::models::ProviderPluginInfo;
-use crate::model_transport::{resolve_model_transport, ModelTransportKind};
-use crate::providers::ProviderRegistry;
+use crate::adapters::ModelConnectionRequest;
+use crate::model_transport::resolve_model_transport;
+use crate::providers::{model_adapters, ProviderRegistry};
 use async_trait::async_trait;
 use runtime_models_app::{ProviderCatalog, ProviderHealthProbe};
 use sqlx::SqlitePool;
@@ -88,14 +89,14 @@ impl ProviderHealthProbe for RuntimeProviderHealthProbe {
         model: &str,
     ) -> Result<bool, String> {
         let transport = resolve_model_transport(protocol_type, base_url, None);
-        if transport.kind == ModelTransportKind::AnthropicMessages {
-            crate::adapters::anthropic::test_connection(base_url, api_key, model)
-                .await
-                .map_err(|e| e.to_string())
-        } else {
-            crate::adapters::openai::test_connection(&transport, base_url, api_key, model)
-                .await
-                .map_err(|e| e.to_string())
-        }
+        model_adapters()
+            .test_connection(ModelConnectionRequest {
+                transport: &transport,
+                base_url,
+                api_key,
+                model,
+            })
+            .await
+            .map_err(|e| e.to_string())
     }
 }
@@ -36,9 +36,7 @@ async fn query_first_non_search_model_id(
     }
 }
 
-async fn query_current_default_model_id(
-    db: &sqlx::SqlitePool,
-) -> Result<Option<String>, String> {
+async fn query_current_default_model_id(db: &sqlx::SqlitePool) -> Result<Option<String>, String> {
     sqlx::query_scalar::<_, String>(
         "SELECT id
          FROM model_configs
@@ -119,6 +117,7 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
             config.id.clone()
         };
         let now = chrono::Utc::now().to_rfc3339();
+        let api_key_encrypted = crate::secret_store::seal_secret(&config.api_key_encrypted)?;
         sqlx::query(
             "INSERT OR REPLACE INTO provider_configs (id, provider_key, display_name, protocol_type, base_url, auth_type, api_key_encrypted, org_id, extra_json, enabled, created_at, updated_at)
              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE((SELECT created_at FROM provider_configs WHERE id = ?), ?), ?)",
@@ -129,7 +128,7 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
         .bind(&config.protocol_type)
         .bind(&config.base_url)
         .bind(&config.auth_type)
-        .bind(&config.api_key_encrypted)
+        .bind(&api_key_encrypted)
         .bind(&config.org_id)
         .bind(&config.extra_json)
         .bind(config.enabled)
@@ -152,8 +151,17 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
         } else {
             config.id.clone()
         };
+        let api_key = crate::secret_store::seal_secret(&api_key)?;
         sqlx::query(
-            "INSERT OR REPLACE INTO model_configs (id, name, api_format, base_url, model_name, is_default, api_key, supports_vision) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO model_configs (id, name, api_format, base_url, model_name, is_default, api_key, supports_vision) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                api_format = excluded.api_format,
+                base_url = excluded.base_url,
+                model_name = excluded.model_name,
+                is_default = excluded.is_default,
+                api_key = excluded.api_key,
+                supports_vision = excluded.supports_vision",
         )
         .bind(&id)
         .bind(&config.name)
@@ -178,8 +186,7 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
         .await
         .map_err(|e| format!("读取 Provider 配置失败: {e}"))?;
 
-        Ok(rows
-            .into_iter()
+        rows.into_iter()
             .map(
                 |(
                     id,
@@ -192,20 +199,22 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
                     org_id,
                     extra_json,
                     enabled,
-                )| ProviderConfig {
-                    id,
-                    provider_key,
-                    display_name,
-                    protocol_type,
-                    base_url,
-                    auth_type,
-                    api_key_encrypted,
-                    org_id,
-                    extra_json,
-                    enabled,
+                )| {
+                    Ok(ProviderConfig {
+                        id,
+                        provider_key,
+                        display_name,
+                        protocol_type,
+                        base_url,
+                        auth_type,
+                        api_key_encrypted: crate::secret_store::reveal_secret(&api_key_encrypted)?,
+                        org_id,
+                        extra_json,
+                        enabled,
+                    })
                 },
             )
-            .collect())
+            .collect()
     }
 
     async fn delete_provider_config(&self, provider_id: &str) -> Result<(), String> {
@@ -261,8 +270,15 @@ impl ModelsConfigRepository for PoolModelsRepository<'_> {
         policy: CapabilityRoutingPolicy,
     ) -> Result<(), String> {
         sqlx::query(
-            "INSERT OR REPLACE INTO routing_policies (capability, primary_provider_id, primary_model, fallback_chain_json, timeout_ms, retry_count, enabled)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO routing_policies (capability, primary_provider_id, primary_model, fallback_chain_json, timeout_ms, retry_count, enabled)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(capability) DO UPDATE SET
+                primary_provider_id = excluded.primary_provider_id,
+                primary_model = excluded.primary_model,
+                fallback_chain_json = excluded.fallback_chain_json,
+                timeout_ms = excluded.timeout_ms,
+                retry_count = excluded.retry_count,
+                enabled = excluded.enabled",
         )
         .bind(&policy.capability)
         .bind(&policy.primary_provider_id)
@@ -294,14 +294,15 @@ impl ModelsReadRepository for PoolModelsRepository<'_> {
         .await
         .map_err(|e| format!("读取 Provider 配置失败: {e}"))?;
 
-        Ok(row.map(
-            |(protocol_type, base_url, api_key)| ProviderConnectionInfo {
+        row.map(|(protocol_type, base_url, api_key)| {
+            Ok(ProviderConnectionInfo {
                 provider_id: provider_id.to_string(),
                 protocol_type,
                 base_url,
-                api_key,
-            },
-        ))
+                api_key: crate::secret_store::reveal_secret(&api_key)?,
+            })
+        })
+        .transpose()
     }
 }
 
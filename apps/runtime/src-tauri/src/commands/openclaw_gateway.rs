@@ -162,7 +162,9 @@ pub async fn validate_openclaw_auth_with_pool(
     .await
     .map_err(|e| e.to_string())?;
 
-    let expected = configured.map(|(v,)| v).unwrap_or_default();
+    let expected = crate::secret_store::reveal_secret(
+        &configured.map(|(v,)| v).unwrap_or_default(),
+    )?;
     if expected.trim().is_empty() {
         return Ok(());
     }
@@ -2,7 +2,10 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_yaml::{Mapping, Value};
 use skillpack_rs::pack::parse_front_matter;
-use skillpack_rs::{pack, FrontMatter, PackConfig};
+use skillpack_rs::{
+    add_recipient, pack, pack_delta, read_manifest, revoke_recipient, FrontMatter, PackConfig,
+    PublisherKey, Recipient, SkillManifest,
+};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Read, Write};
@@ -24,6 +27,15 @@ pub struct WorkClawDirSummary {
     pub tags: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaPackSummary {
+    pub skill_id: String,
+    pub version: String,
+    pub base_version: String,
+    pub changed_files: Vec<String>,
+    pub removed_files: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndustryPackSkillEntry {
     pub slug: String,
@@ -337,30 +349,178 @@ pub async fn update_skill_dir_tags(dir_path: String, tags: Vec<String>) -> Resul
     Ok(())
 }
 
+fn parse_signing_key(signing_key: Option<String>) -> Result<Option<PublisherKey>, String> {
+    signing_key
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| PublisherKey::from_base64(&key))
+        .transpose()
+        .map_err(|e| format!("签名密钥无效: {}", e))
+}
+
+/// 团队口令以标签作为提示写入清单，未填写标签时使用 "team"
+fn team_recipient(label: Option<String>, passphrase: Option<String>) -> Option<Recipient> {
+    let passphrase = passphrase.filter(|p| !p.trim().is_empty())?;
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "team".to_string());
+    Some(Recipient::Team { label, passphrase })
+}
+
+/// 导出技能包的参数，前端以 `{ request: {...} }` 传入
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackSkillRequest {
+    pub dir_path: String,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub author: String,
+    pub username: String,
+    pub recommended_model: String,
+    pub output_path: String,
+    pub signing_key: Option<String>,
+    /// 除打包人以外可以解锁技能包的用户名
+    pub recipient_usernames: Option<Vec<String>>,
+    pub team_label: Option<String>,
+    pub team_passphrase: Option<String>,
+    /// 发布新版本时沿用上一版本的技能 ID
+    pub skill_id: Option<String>,
+}
+
 #[tauri::command]
-pub async fn pack_skill(
-    dir_path: String,
-    name: String,
-    description: String,
-    version: String,
-    author: String,
-    username: String,
-    recommended_model: String,
-    output_path: String,
-) -> Result<(), String> {
+pub async fn pack_skill(request: PackSkillRequest) -> Result<(), String> {
+    let signing_key = parse_signing_key(request.signing_key)?;
+    let username = request.username;
+    let mut recipients: Vec<Recipient> = request
+        .recipient_usernames
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty() && *u != username)
+        .map(Recipient::User)
+        .collect();
+    recipients.extend(team_recipient(request.team_label, request.team_passphrase));
     let config = PackConfig {
-        dir_path,
-        name,
-        description,
-        version,
-        author,
+        dir_path: request.dir_path,
+        name: request.name,
+        description: request.description,
+        version: request.version,
+        author: request.author,
         username,
-        recommended_model,
-        output_path,
+        recommended_model: request.recommended_model,
+        output_path: request.output_path,
+        signing_key,
+        recipients,
+        skill_id: request.skill_id,
     };
     pack(&config).map_err(|e| format!("打包失败: {}", e))
 }
 
+/// 导出增量包的参数，前端以 `{ request: {...} }` 传入
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackSkillDeltaRequest {
+    pub dir_path: String,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub author: String,
+    pub username: String,
+    pub recommended_model: String,
+    pub output_path: String,
+    pub signing_key: Option<String>,
+    /// 上一版本完整技能包的路径
+    pub base_pack_path: String,
+}
+
+/// 基于上一版本的完整技能包导出增量包，只携带新增或变更的文件；
+/// 技能 ID、内容密钥与接收方沿用上一版本，`username` 需能解锁上一版本。
+#[tauri::command]
+pub async fn pack_skill_delta(request: PackSkillDeltaRequest) -> Result<DeltaPackSummary, String> {
+    let base_pack_path = request.base_pack_path;
+    let base = read_manifest(&base_pack_path).map_err(|e| format!("读取上一版本失败: {}", e))?;
+    let signing_key = parse_signing_key(request.signing_key)?;
+    let username = request.username;
+    let config = PackConfig {
+        dir_path: request.dir_path,
+        name: request.name,
+        description: request.description,
+        version: request.version,
+        author: request.author,
+        username: username.clone(),
+        recommended_model: request.recommended_model,
+        output_path: request.output_path,
+        signing_key,
+        recipients: vec![],
+        skill_id: Some(base.id.clone()),
+    };
+    let summary = pack_delta(&config, &base_pack_path, &username)
+        .map_err(|e| format!("增量打包失败: {}", e))?;
+    Ok(DeltaPackSummary {
+        skill_id: summary.manifest.id,
+        version: summary.manifest.version,
+        base_version: base.version,
+        changed_files: summary.changed_files,
+        removed_files: summary.removed_files,
+    })
+}
+
+/// 给已发布的技能包增加一个用户或团队口令，`credential` 需能解锁该包；
+/// 已签名的包需要提供原签名密钥重新签名。
+#[tauri::command]
+pub async fn add_skillpack_recipient(
+    pack_path: String,
+    credential: String,
+    recipient_username: Option<String>,
+    team_label: Option<String>,
+    team_passphrase: Option<String>,
+    signing_key: Option<String>,
+) -> Result<SkillManifest, String> {
+    let recipient = match recipient_username
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+    {
+        Some(username) => Recipient::User(username),
+        None => team_recipient(team_label, team_passphrase)
+            .ok_or_else(|| "请填写要添加的用户名或团队口令".to_string())?,
+    };
+    let signing_key = parse_signing_key(signing_key)?;
+    add_recipient(&pack_path, &credential, &recipient, signing_key.as_ref())
+        .map_err(|e| format!("添加接收方失败: {}", e))
+}
+
+/// 按用户名或团队标签移除接收方。已解锁过的人仍可能留有内容密钥，彻底收回需重新打包。
+#[tauri::command]
+pub async fn revoke_skillpack_recipient(
+    pack_path: String,
+    hint: String,
+    signing_key: Option<String>,
+) -> Result<SkillManifest, String> {
+    let signing_key = parse_signing_key(signing_key)?;
+    revoke_recipient(&pack_path, hint.trim(), signing_key.as_ref())
+        .map_err(|e| format!("移除接收方失败: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublisherKeyInfo {
+    /// 私钥仅用于打包签名，不应分发
+    pub signing_key: String,
+    /// 分发给安装方加入受信任发布者列表
+    pub public_key: String,
+    pub key_id: String,
+}
+
+#[tauri::command]
+pub async fn generate_publisher_key() -> Result<PublisherKeyInfo, String> {
+    let key = PublisherKey::generate();
+    Ok(PublisherKeyInfo {
+        signing_key: key.to_base64(),
+        public_key: key.public_key_base64(),
+        key_id: key.key_id(),
+    })
+}
+
 #[tauri::command]
 pub async fn pack_industry_bundle(
     skill_dirs: Vec<String>,
@@ -0,0 +1,24 @@
+use super::skills::DbState;
+use crate::route_health::{
+    get_route_load_balance_enabled_with_pool, list_route_health_with_pool,
+    set_route_load_balance_enabled_with_pool, RouteHealthEntry,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_route_health(db: State<'_, DbState>) -> Result<Vec<RouteHealthEntry>, String> {
+    list_route_health_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn get_route_load_balance_enabled(db: State<'_, DbState>) -> Result<bool, String> {
+    get_route_load_balance_enabled_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn set_route_load_balance_enabled(
+    enabled: bool,
+    db: State<'_, DbState>,
+) -> Result<(), String> {
+    set_route_load_balance_enabled_with_pool(&db.0, enabled).await
+}
@@ -0,0 +1,23 @@
+use super::skills::DbState;
+use crate::agent::run_guard::RunResourceLimits;
+use crate::run_budgets::{
+    get_employee_run_budget_with_pool, save_employee_run_budget_with_pool, EmployeeRunBudget,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_employee_run_budget(
+    employee_id: String,
+    db: State<'_, DbState>,
+) -> Result<Option<EmployeeRunBudget>, String> {
+    get_employee_run_budget_with_pool(&db.0, &employee_id).await
+}
+
+#[tauri::command]
+pub async fn save_employee_run_budget(
+    employee_id: String,
+    limits: RunResourceLimits,
+    db: State<'_, DbState>,
+) -> Result<Option<EmployeeRunBudget>, String> {
+    save_employee_run_budget_with_pool(&db.0, &employee_id, limits).await
+}
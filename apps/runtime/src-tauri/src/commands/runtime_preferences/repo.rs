@@ -7,10 +7,20 @@ pub(crate) async fn get_app_setting(pool: &SqlitePool, key: &str) -> Result<Opti
             .fetch_optional(pool)
             .await
             .map_err(|e| e.to_string())?;
-    Ok(row.map(|(v,)| v))
+    match row {
+        Some((value,)) if crate::secret_store::is_secret_setting_key(key) => {
+            crate::secret_store::reveal_secret(&value).map(Some)
+        }
+        row => Ok(row.map(|(v,)| v)),
+    }
 }
 
 pub(crate) async fn set_app_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    let value = if crate::secret_store::is_secret_setting_key(key) {
+        crate::secret_store::seal_secret(value)?
+    } else {
+        value.to_string()
+    };
     sqlx::query(
         "INSERT INTO app_settings (key, value) VALUES (?, ?)
          ON CONFLICT(key) DO UPDATE SET value = excluded.value",
@@ -0,0 +1,83 @@
+use super::skills::DbState;
+use crate::im::{list_channel_delivery_routes, ChannelDeliveryRoute};
+use crate::scheduled_jobs::{
+    delete_scheduled_job_with_pool, list_scheduled_job_runs_with_pool,
+    list_scheduled_jobs_with_pool, preview_cron_schedule as preview_cron_fire_times,
+    run_scheduled_job_now as start_scheduled_job_run, save_scheduled_job_with_pool,
+    set_scheduled_job_enabled_with_pool, ScheduledJob, ScheduledJobInput, ScheduledJobRun,
+};
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+const DEFAULT_RUN_HISTORY_LIMIT: i64 = 50;
+const DEFAULT_CRON_PREVIEW_COUNT: usize = 5;
+
+#[tauri::command]
+pub async fn list_scheduled_jobs(db: State<'_, DbState>) -> Result<Vec<ScheduledJob>, String> {
+    list_scheduled_jobs_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_scheduled_job(
+    input: ScheduledJobInput,
+    db: State<'_, DbState>,
+) -> Result<ScheduledJob, String> {
+    save_scheduled_job_with_pool(&db.0, input).await
+}
+
+#[tauri::command]
+pub async fn delete_scheduled_job(job_id: String, db: State<'_, DbState>) -> Result<(), String> {
+    delete_scheduled_job_with_pool(&db.0, &job_id).await
+}
+
+#[tauri::command]
+pub async fn set_scheduled_job_enabled(
+    job_id: String,
+    enabled: bool,
+    db: State<'_, DbState>,
+) -> Result<ScheduledJob, String> {
+    set_scheduled_job_enabled_with_pool(&db.0, &job_id, enabled).await
+}
+
+#[tauri::command]
+pub async fn run_scheduled_job_now(
+    app: AppHandle,
+    job_id: String,
+    db: State<'_, DbState>,
+) -> Result<ScheduledJobRun, String> {
+    start_scheduled_job_run(app, db.0.clone(), &job_id).await
+}
+
+#[tauri::command]
+pub async fn list_scheduled_job_runs(
+    job_id: Option<String>,
+    limit: Option<i64>,
+    db: State<'_, DbState>,
+) -> Result<Vec<ScheduledJobRun>, String> {
+    list_scheduled_job_runs_with_pool(
+        &db.0,
+        job_id.as_deref(),
+        limit.unwrap_or(DEFAULT_RUN_HISTORY_LIMIT),
+    )
+    .await
+}
+
+/// 可作为结果投递目标的 IM 会话
+#[tauri::command]
+pub async fn list_scheduled_job_delivery_routes(
+    db: State<'_, DbState>,
+) -> Result<Vec<ChannelDeliveryRoute>, String> {
+    list_channel_delivery_routes(&db.0).await
+}
+
+#[tauri::command]
+pub async fn preview_cron_schedule(
+    cron_expr: String,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    preview_cron_fire_times(
+        &cron_expr,
+        count.unwrap_or(DEFAULT_CRON_PREVIEW_COUNT).clamp(1, 20),
+        Utc::now(),
+    )
+}
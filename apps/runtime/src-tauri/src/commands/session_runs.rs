@@ -10,6 +10,7 @@ use crate::session_journal::{
 };
 use chrono::Utc;
 use serde::Serialize;
+use serde_json::Value;
 use sqlx::SqlitePool;
 use tauri::State;
 use uuid::Uuid;
@@ -42,6 +43,9 @@ pub struct SessionRunProjection {
     pub task_continuation_source: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub task_continuation_reason: Option<String>,
+    /// 通过 Skill 输出 schema 校验的最终结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -100,6 +104,7 @@ pub async fn list_session_runs_with_pool(
             Option<String>,
             String,
             String,
+            String,
         ),
     >(
         "SELECT id, session_id, user_message_id,
@@ -107,7 +112,7 @@ pub async fn list_session_runs_with_pool(
                 status, buffered_text,
                 NULLIF(error_kind, '') AS error_kind,
                 NULLIF(error_message, '') AS error_message,
-                created_at, updated_at
+                created_at, updated_at, structured_output_json
          FROM session_runs
          WHERE session_id = ?
          ORDER BY created_at ASC, id ASC",
@@ -131,6 +136,7 @@ pub async fn list_session_runs_with_pool(
                 error_message,
                 created_at,
                 updated_at,
+                structured_output_json,
             )| SessionRunProjection {
                 id,
                 session_id,
@@ -150,6 +156,7 @@ pub async fn list_session_runs_with_pool(
                 task_continuation_mode: None,
                 task_continuation_source: None,
                 task_continuation_reason: None,
+                structured_output: serde_json::from_str(&structured_output_json).ok(),
             },
         )
         .collect())
@@ -376,7 +383,19 @@ pub async fn append_session_run_event_with_pool(
             .await
             .map_err(|e| format!("写入 session run 启动投影失败: {e}"))?;
         }
-        SessionRunEvent::SkillRouteRecorded { .. } => {}
+        SessionRunEvent::SkillRouteRecorded { .. } | SessionRunEvent::ModelRouteRecorded { .. } => {
+        }
+        SessionRunEvent::StructuredOutputRecorded { run_id, output, .. } => {
+            sqlx::query(
+                "UPDATE session_runs SET structured_output_json = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(output.to_string())
+            .bind(&now)
+            .bind(&run_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("写入 session run 结构化输出失败: {e}"))?;
+        }
         SessionRunEvent::AssistantChunkAppended { run_id, chunk } => {
             sqlx::query(
                 "INSERT INTO session_runs (id, session_id, user_message_id, assistant_message_id, status, buffered_text, error_kind, error_message, created_at, updated_at)
@@ -588,6 +607,8 @@ fn event_type(event: &SessionRunEvent) -> &'static str {
         SessionRunEvent::TaskStatusChanged { .. } => "task_status_changed",
         SessionRunEvent::RunStarted { .. } => "run_started",
         SessionRunEvent::SkillRouteRecorded { .. } => "skill_route_recorded",
+        SessionRunEvent::ModelRouteRecorded { .. } => "model_route_recorded",
+        SessionRunEvent::StructuredOutputRecorded { .. } => "structured_output_recorded",
         SessionRunEvent::AssistantChunkAppended { .. } => "assistant_chunk_appended",
         SessionRunEvent::ToolStarted { .. } => "tool_started",
         SessionRunEvent::ToolCompleted { .. } => "tool_completed",
@@ -610,6 +631,8 @@ fn event_run_id(event: &SessionRunEvent) -> &str {
         | SessionRunEvent::TaskStatusChanged { run_id, .. }
         | SessionRunEvent::RunStarted { run_id, .. }
         | SessionRunEvent::SkillRouteRecorded { run_id, .. }
+        | SessionRunEvent::ModelRouteRecorded { run_id, .. }
+        | SessionRunEvent::StructuredOutputRecorded { run_id, .. }
         | SessionRunEvent::AssistantChunkAppended { run_id, .. }
         | SessionRunEvent::ToolStarted { run_id, .. }
         | SessionRunEvent::ToolCompleted { run_id, .. }
@@ -988,6 +1011,7 @@ mod tests {
                 input: json!({ "path": "README.md" }),
                 output: "README loaded".to_string(),
                 is_error: false,
+                checkpoint_id: None,
             })
             .expect("serialize tool_completed"),
             "2026-03-27T01:00:02Z",
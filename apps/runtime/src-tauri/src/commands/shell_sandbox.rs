@@ -0,0 +1,20 @@
+use super::skills::DbState;
+use crate::shell_sandbox::{
+    load_shell_sandbox_profile_with_pool, save_shell_sandbox_profile_with_pool, ShellSandboxProfile,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_shell_sandbox_profile(
+    db: State<'_, DbState>,
+) -> Result<ShellSandboxProfile, String> {
+    load_shell_sandbox_profile_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_shell_sandbox_profile(
+    profile: ShellSandboxProfile,
+    db: State<'_, DbState>,
+) -> Result<ShellSandboxProfile, String> {
+    save_shell_sandbox_profile_with_pool(&db.0, profile).await
+}
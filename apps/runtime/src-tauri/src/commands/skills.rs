@@ -1,6 +1,7 @@
+use crate::runtime_environment::runtime_paths_from_app;
 use chrono::Utc;
 use serde_json::{Value, json};
-use skillpack_rs::SkillManifest;
+use skillpack_rs::{SkillManifest, TrustedPublisher};
 use sqlx::SqlitePool;
 use tauri::State;
 
@@ -19,14 +20,21 @@ mod industry_bundle_service;
 #[path = "skills/runtime_status_service.rs"]
 mod runtime_status_service;
 
+#[path = "skills/trust_service.rs"]
+mod trust_service;
+
 pub use industry_bundle_service::{
     check_industry_bundle_update_from_pool, install_industry_bundle_to_pool,
 };
 pub use local_skill_service::{
     create_local_skill_in_dir, ensure_skill_display_name_available, import_local_skill_to_pool,
-    import_local_skills_to_pool, render_local_skill_preview_in_dir,
+    import_local_skills_to_pool, install_skill_to_pool, render_local_skill_preview_in_dir,
 };
 pub use runtime_status_service::get_skill_runtime_environment_status_with_pool;
+pub use trust_service::{
+    UNTRUSTED_SKILLPACK_ERROR_PREFIX, add_trusted_publisher_at, list_trusted_publishers_at,
+    load_trusted_keyring, remove_trusted_publisher_at,
+};
 pub use types::{
     DbState, ImportResult, IndustryBundleUpdateCheck, IndustryInstallResult,
     InstalledSkillListItem, InstalledSkillSummary, LocalImportBatchResult, LocalImportFailedItem,
@@ -78,9 +86,57 @@ pub async fn create_local_skill(
 pub async fn install_skill(
     pack_path: String,
     username: String,
+    allow_untrusted: Option<bool>,
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
 ) -> Result<SkillManifest, String> {
-    local_skill_service::install_skill(pack_path, username, &db.0).await
+    let runtime_paths = runtime_paths_from_app(&app)?;
+    let keyring = load_trusted_keyring(&runtime_paths.trusted_publishers_path)?;
+    local_skill_service::install_skill_to_pool(
+        pack_path,
+        username,
+        &keyring,
+        allow_untrusted.unwrap_or(false),
+        &runtime_paths.cache_dir.join("skillpacks"),
+        &db.0,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn list_trusted_publishers(
+    app: tauri::AppHandle,
+) -> Result<Vec<TrustedPublisher>, String> {
+    list_trusted_publishers_at(&runtime_paths_from_app(&app)?.trusted_publishers_path)
+}
+
+#[tauri::command]
+pub async fn add_trusted_publisher(
+    name: String,
+    public_key: String,
+    app: tauri::AppHandle,
+) -> Result<TrustedPublisher, String> {
+    add_trusted_publisher_at(
+        &runtime_paths_from_app(&app)?.trusted_publishers_path,
+        &name,
+        &public_key,
+    )
+}
+
+#[tauri::command]
+pub async fn remove_trusted_publisher(
+    key_id: String,
+    app: tauri::AppHandle,
+) -> Result<bool, String> {
+    remove_trusted_publisher_at(
+        &runtime_paths_from_app(&app)?.trusted_publishers_path,
+        &key_id,
+    )
+}
+
+#[tauri::command]
+pub async fn preview_skillpack(pack_path: String) -> Result<SkillManifest, String> {
+    skillpack_rs::read_manifest(&pack_path).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
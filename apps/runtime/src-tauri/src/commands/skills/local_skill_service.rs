@@ -1,15 +1,19 @@
 use super::helpers::{
-    build_local_skill_id, merge_tags, normalize_display_name, read_skill_markdown_with_fallback,
-    render_local_skill_markdown, sanitize_slug,
+    build_local_skill_id, compare_semver, merge_tags, normalize_display_name,
+    read_skill_markdown_with_fallback, render_local_skill_markdown, sanitize_slug,
 };
+use super::trust_service::ensure_skillpack_trusted;
 use super::types::{
     ImportResult, LocalImportBatchResult, LocalImportFailedItem, LocalImportInstalledItem,
     LocalSkillPreview,
 };
 use crate::runtime_environment::runtime_paths_from_app;
 use chrono::Utc;
-use skillpack_rs::{SkillManifest, verify_and_unpack};
+use skillpack_rs::{
+    SkillManifest, TrustedKeyring, UnpackLimits, apply_delta, read_manifest, unpack_to_dir,
+};
 use sqlx::SqlitePool;
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
@@ -142,16 +146,55 @@ pub fn create_local_skill_in_dir(
     Ok(skill_dir.to_string_lossy().to_string())
 }
 
-pub async fn install_skill(
+pub async fn install_skill_to_pool(
     pack_path: String,
     username: String,
+    keyring: &TrustedKeyring,
+    allow_untrusted: bool,
+    pack_cache_dir: &Path,
     pool: &SqlitePool,
 ) -> Result<SkillManifest, String> {
-    let unpacked = verify_and_unpack(&pack_path, &username).map_err(|e| e.to_string())?;
+    let pack_path = resolve_delta_pack(&pack_path, pack_cache_dir, pool).await?;
+    let unpacked = verify_skillpack_on_disk(&pack_path, &username, keyring, pack_cache_dir)?;
+    ensure_skillpack_trusted(&unpacked.signature, allow_untrusted)?;
     ensure_skill_display_name_available(pool, &unpacked.manifest.name, &unpacked.manifest.id)
         .await?;
 
     let manifest_json = serde_json::to_string(&unpacked.manifest).map_err(|e| e.to_string())?;
+    // 用户名或团队口令是解锁技能包的凭据，只以密文落库
+    let sealed_credential = crate::secret_store::seal_secret(&username)?;
+
+    let existing: Option<(String,)> =
+        sqlx::query_as("SELECT manifest FROM installed_skills WHERE id = ?")
+            .bind(&unpacked.manifest.id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Some((existing_json,)) = existing {
+        if let Ok(existing_manifest) = serde_json::from_str::<SkillManifest>(&existing_json) {
+            if compare_semver(&unpacked.manifest.version, &existing_manifest.version)
+                == Ordering::Less
+            {
+                return Err(format!(
+                    "已安装更高版本 {}，无法降级到 {}",
+                    existing_manifest.version, unpacked.manifest.version
+                ));
+            }
+        }
+        // Upgrade in place: installed_at, last_used_at and everything keyed by
+        // the skill id (employee bindings, skill OS versions, memory) are kept.
+        sqlx::query(
+            "UPDATE installed_skills SET manifest = ?, username = ?, pack_path = ?, source_type = 'encrypted' WHERE id = ?",
+        )
+        .bind(&manifest_json)
+        .bind(&sealed_credential)
+        .bind(&pack_path)
+        .bind(&unpacked.manifest.id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        return Ok(unpacked.manifest);
+    }
 
     let now = Utc::now().to_rfc3339();
     sqlx::query(
@@ -160,7 +203,7 @@ pub async fn install_skill(
     .bind(&unpacked.manifest.id)
     .bind(&manifest_json)
     .bind(&now)
-    .bind(&username)
+    .bind(&sealed_credential)
     .bind(&pack_path)
     .execute(pool)
     .await
@@ -169,6 +212,67 @@ pub async fn install_skill(
     Ok(unpacked.manifest)
 }
 
+/// 逐个文件解密到缓存目录下的临时目录完成校验（签名、哈希、大小与路径），
+/// 避免把整个技能包读进内存；校验完成后临时目录随即删除，明文不留在磁盘上。
+fn verify_skillpack_on_disk(
+    pack_path: &str,
+    username: &str,
+    keyring: &TrustedKeyring,
+    pack_cache_dir: &Path,
+) -> Result<skillpack_rs::UnpackReport, String> {
+    std::fs::create_dir_all(pack_cache_dir)
+        .map_err(|e| format!("创建技能包缓存目录失败: {}", e))?;
+    let scratch = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(pack_cache_dir)
+        .map_err(|e| format!("创建技能包校验目录失败: {}", e))?;
+    unpack_to_dir(
+        pack_path,
+        username,
+        &scratch.path().join("skill"),
+        keyring,
+        &UnpackLimits::default(),
+        |_| {},
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Delta packs are merged with the installed base pack into a full pack under
+/// `pack_cache_dir`, which then serves as the base for the next delta; full
+/// packs are returned unchanged.
+async fn resolve_delta_pack(
+    pack_path: &str,
+    pack_cache_dir: &Path,
+    pool: &SqlitePool,
+) -> Result<String, String> {
+    let manifest = read_manifest(pack_path).map_err(|e| e.to_string())?;
+    let Some(base_version) = manifest.delta_base_version.as_deref() else {
+        return Ok(pack_path.to_string());
+    };
+    let base: Option<(String,)> = sqlx::query_as(
+        "SELECT pack_path FROM installed_skills WHERE id = ? AND source_type = 'encrypted'",
+    )
+    .bind(&manifest.id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let Some((base_pack_path,)) = base else {
+        return Err(format!("增量包需要先安装基础版本 {}", base_version));
+    };
+    std::fs::create_dir_all(pack_cache_dir)
+        .map_err(|e| format!("创建技能包缓存目录失败: {}", e))?;
+    let full_path = pack_cache_dir
+        .join(format!(
+            "{}-{}.skillpack",
+            sanitize_slug(&manifest.id),
+            sanitize_slug(&manifest.version)
+        ))
+        .to_string_lossy()
+        .to_string();
+    apply_delta(&base_pack_path, pack_path, &full_path).map_err(|e| e.to_string())?;
+    Ok(full_path)
+}
+
 pub async fn import_local_skill_to_pool(
     dir_path: String,
     pool: &SqlitePool,
@@ -204,6 +308,7 @@ pub async fn import_local_skill_to_pool(
         created_at: Utc::now(),
         username_hint: None,
         encrypted_verify: String::new(),
+        ..Default::default()
     };
     ensure_skill_display_name_available(pool, &manifest.name, &skill_id).await?;
 
@@ -405,6 +510,7 @@ pub async fn refresh_local_skill(
         created_at: existing_manifest.created_at,
         username_hint: existing_manifest.username_hint,
         encrypted_verify: existing_manifest.encrypted_verify,
+        ..Default::default()
     };
 
     let manifest_json = serde_json::to_string(&manifest).map_err(|e| e.to_string())?;
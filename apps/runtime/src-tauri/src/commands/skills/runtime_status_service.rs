@@ -496,6 +496,7 @@ mod tests {
             created_at: Utc::now(),
             username_hint: None,
             encrypted_verify: String::new(),
+            ..Default::default()
         };
         let manifest_json = serde_json::to_string(&manifest).expect("serialize manifest");
         sqlx::query(
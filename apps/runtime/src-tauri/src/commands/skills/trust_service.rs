@@ -0,0 +1,64 @@
+use skillpack_rs::{SignatureReport, TrustStatus, TrustedKeyring, TrustedPublisher};
+use std::path::Path;
+
+/// 安装前需要用户显式确认的技能包会以该前缀报错，前端据此弹出确认
+pub const UNTRUSTED_SKILLPACK_ERROR_PREFIX: &str = "UNTRUSTED_SKILLPACK:";
+
+pub fn load_trusted_keyring(path: &Path) -> Result<TrustedKeyring, String> {
+    TrustedKeyring::load(path).map_err(|e| format!("读取受信任发布者列表失败: {}", e))
+}
+
+pub fn list_trusted_publishers_at(path: &Path) -> Result<Vec<TrustedPublisher>, String> {
+    Ok(load_trusted_keyring(path)?.publishers)
+}
+
+pub fn add_trusted_publisher_at(
+    path: &Path,
+    name: &str,
+    public_key: &str,
+) -> Result<TrustedPublisher, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("发布者名称不能为空".to_string());
+    }
+    let mut keyring = load_trusted_keyring(path)?;
+    let key_id = keyring
+        .add(name, public_key)
+        .map_err(|e| format!("发布者公钥无效: {}", e))?;
+    keyring
+        .save(path)
+        .map_err(|e| format!("保存受信任发布者列表失败: {}", e))?;
+    keyring
+        .find(&key_id)
+        .cloned()
+        .ok_or_else(|| format!("发布者未写入: {}", key_id))
+}
+
+pub fn remove_trusted_publisher_at(path: &Path, key_id: &str) -> Result<bool, String> {
+    let mut keyring = load_trusted_keyring(path)?;
+    if !keyring.remove(key_id.trim()) {
+        return Ok(false);
+    }
+    keyring
+        .save(path)
+        .map_err(|e| format!("保存受信任发布者列表失败: {}", e))?;
+    Ok(true)
+}
+
+/// 未签名或签名者不在本机信任列表中的技能包，只有用户显式确认后才允许安装
+pub fn ensure_skillpack_trusted(
+    signature: &SignatureReport,
+    allow_untrusted: bool,
+) -> Result<(), String> {
+    if allow_untrusted {
+        return Ok(());
+    }
+    match signature.status {
+        TrustStatus::Trusted => Ok(()),
+        TrustStatus::Unsigned => Err(format!("{UNTRUSTED_SKILLPACK_ERROR_PREFIX}unsigned")),
+        TrustStatus::Untrusted => Err(format!(
+            "{UNTRUSTED_SKILLPACK_ERROR_PREFIX}untrusted:{}",
+            signature.key_id.as_deref().unwrap_or_default()
+        )),
+    }
+}
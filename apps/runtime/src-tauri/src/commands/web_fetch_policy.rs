@@ -0,0 +1,18 @@
+use super::skills::DbState;
+use crate::web_fetch_policy::{
+    load_web_fetch_policy_with_pool, save_web_fetch_policy_with_pool, WebFetchPolicy,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_web_fetch_policy(db: State<'_, DbState>) -> Result<WebFetchPolicy, String> {
+    load_web_fetch_policy_with_pool(&db.0).await
+}
+
+#[tauri::command]
+pub async fn save_web_fetch_policy(
+    policy: WebFetchPolicy,
+    db: State<'_, DbState>,
+) -> Result<WebFetchPolicy, String> {
+    save_web_fetch_policy_with_pool(&db.0, policy).await
+}
@@ -0,0 +1,93 @@
+use super::skills::DbState;
+use crate::runtime_environment::runtime_paths_from_app;
+use crate::workspace_checkpoints::{
+    checkpoint_store_root, diff_workspace_checkpoints_with_pool,
+    list_workspace_checkpoints_with_pool, revert_workspace_checkpoints_with_pool,
+    WorkspaceCheckpoint, WorkspaceFileDiff, WorkspaceRevertResult,
+};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+fn store_root_from_app(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(checkpoint_store_root(&runtime_paths_from_app(app)?.root))
+}
+
+#[tauri::command]
+pub async fn list_workspace_checkpoints(
+    run_id: String,
+    turn_index: Option<i64>,
+    db: State<'_, DbState>,
+) -> Result<Vec<WorkspaceCheckpoint>, String> {
+    list_workspace_checkpoints_with_pool(&db.0, &run_id, turn_index).await
+}
+
+#[tauri::command]
+pub async fn get_workspace_checkpoint_diff(
+    app: AppHandle,
+    run_id: String,
+    turn_index: Option<i64>,
+    db: State<'_, DbState>,
+) -> Result<Vec<WorkspaceFileDiff>, String> {
+    let store_root = store_root_from_app(&app)?;
+    diff_workspace_checkpoints_with_pool(&db.0, &store_root, &run_id, turn_index).await
+}
+
+#[tauri::command]
+pub async fn revert_workspace_run(
+    app: AppHandle,
+    run_id: String,
+    force: Option<bool>,
+    db: State<'_, DbState>,
+) -> Result<WorkspaceRevertResult, String> {
+    let store_root = store_root_from_app(&app)?;
+    revert_workspace_checkpoints_with_pool(
+        &db.0,
+        &store_root,
+        &run_id,
+        None,
+        None,
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn revert_workspace_turn(
+    app: AppHandle,
+    run_id: String,
+    turn_index: i64,
+    force: Option<bool>,
+    db: State<'_, DbState>,
+) -> Result<WorkspaceRevertResult, String> {
+    let store_root = store_root_from_app(&app)?;
+    revert_workspace_checkpoints_with_pool(
+        &db.0,
+        &store_root,
+        &run_id,
+        Some(turn_index),
+        None,
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn revert_workspace_file(
+    app: AppHandle,
+    run_id: String,
+    path: String,
+    turn_index: Option<i64>,
+    force: Option<bool>,
+    db: State<'_, DbState>,
+) -> Result<WorkspaceRevertResult, String> {
+    let store_root = store_root_from_app(&app)?;
+    revert_workspace_checkpoints_with_pool(
+        &db.0,
+        &store_root,
+        &run_id,
+        turn_index,
+        Some(&path),
+        force.unwrap_or(false),
+    )
+    .await
+}
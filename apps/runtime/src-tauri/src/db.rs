@@ -51,6 +51,10 @@ pub async fn init_db_at_runtime_paths(runtime_paths: &RuntimePaths) -> Result<Sq
     apply_legacy_migrations(&pool).await?;
     apply_current_schema(&pool).await?;
     seed_runtime_defaults(&pool, &runtime_paths.root).await?;
+    // 密钥文件不可用时不阻断启动，已有的明文凭据仍可读取
+    if let Err(error) = crate::secret_store::migrate_plaintext_secrets_with_pool(&pool).await {
+        eprintln!("[secret-store] 加密已保存的凭据失败: {error}");
+    }
 
     Ok(pool)
 }
@@ -108,6 +108,50 @@ pub(super) async fn apply_legacy_migrations(pool: &SqlitePool) -> Result<()> {
     )
     .execute(pool)
     .await;
+    let _ = sqlx::query(
+        "ALTER TABLE model_configs ADD COLUMN reasoning_json TEXT NOT NULL DEFAULT '{}'",
+    )
+    .execute(pool)
+    .await;
+    let _ = sqlx::query(
+        "ALTER TABLE routing_policies ADD COLUMN reasoning_json TEXT NOT NULL DEFAULT '{}'",
+    )
+    .execute(pool)
+    .await;
+    let _ =
+        sqlx::query("ALTER TABLE route_attempt_logs ADD COLUMN base_url TEXT NOT NULL DEFAULT ''")
+            .execute(pool)
+            .await;
+    let _ = sqlx::query(
+        "ALTER TABLE route_attempt_logs ADD COLUMN latency_ms INTEGER NOT NULL DEFAULT 0",
+    )
+    .execute(pool)
+    .await;
+
+    let _ =
+        sqlx::query("ALTER TABLE mcp_servers ADD COLUMN transport TEXT NOT NULL DEFAULT 'stdio'")
+            .execute(pool)
+            .await;
+    let _ = sqlx::query("ALTER TABLE mcp_servers ADD COLUMN url TEXT NOT NULL DEFAULT ''")
+        .execute(pool)
+        .await;
+    let _ = sqlx::query("ALTER TABLE mcp_servers ADD COLUMN headers TEXT NOT NULL DEFAULT '{}'")
+        .execute(pool)
+        .await;
+
+    for column in [
+        "effect TEXT NOT NULL DEFAULT 'allow'",
+        "match_kind TEXT NOT NULL DEFAULT 'fingerprint'",
+        "pattern TEXT NOT NULL DEFAULT ''",
+        "scope_kind TEXT NOT NULL DEFAULT 'global'",
+        "scope_value TEXT NOT NULL DEFAULT ''",
+        "expires_at TEXT NOT NULL DEFAULT ''",
+        "note TEXT NOT NULL DEFAULT ''",
+    ] {
+        let _ = sqlx::query(&format!("ALTER TABLE approval_rules ADD COLUMN {column}"))
+            .execute(pool)
+            .await;
+    }
 
     let _ = sqlx::query(
         "ALTER TABLE sessions ADD COLUMN permission_mode TEXT NOT NULL DEFAULT 'accept_edits'",
@@ -140,6 +184,11 @@ pub(super) async fn apply_legacy_migrations(pool: &SqlitePool) -> Result<()> {
     )
     .execute(pool)
     .await;
+    let _ = sqlx::query(
+        "ALTER TABLE session_runs ADD COLUMN structured_output_json TEXT NOT NULL DEFAULT ''",
+    )
+    .execute(pool)
+    .await;
 
     let _ = sqlx::query(
         "ALTER TABLE agent_employees ADD COLUMN feishu_app_id TEXT NOT NULL DEFAULT ''",
@@ -1,3 +1,4 @@
+use crate::secret_store::redact_secrets;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -154,7 +155,7 @@ pub fn write_log_record(
         message,
         context,
     };
-    let json = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    let json = redact_secrets(&serde_json::to_string(&record).map_err(|e| e.to_string())?);
     append_jsonl_record(&path, &json, "打开诊断日志失败")
 }
 
@@ -175,7 +176,7 @@ pub fn write_audit_record(
         message,
         context,
     };
-    let json = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    let json = redact_secrets(&serde_json::to_string(&record).map_err(|e| e.to_string())?);
     append_jsonl_record(&path, &json, "打开审计日志失败")
 }
 
@@ -483,6 +484,27 @@ mod tests {
         assert!(content.contains("\"message\":\"startup complete\""));
     }
 
+    #[test]
+    fn redacts_credentials_in_log_records() {
+        let dir = tempdir().expect("temp dir");
+        let paths = diagnostics_root(dir.path());
+
+        let log_path = write_log_record(
+            &paths,
+            LogLevel::Error,
+            "model",
+            "request_failed",
+            "401 for key sk-abcdefghijklmnopqrstuvwx",
+            Some(json!({"api_key":"plain-secret","model":"gpt-4o"})),
+        )
+        .expect("write log record");
+
+        let content = std::fs::read_to_string(log_path).expect("read log");
+        assert!(!content.contains("sk-abcdefghijklmnopqrstuvwx"));
+        assert!(!content.contains("plain-secret"));
+        assert!(content.contains("\"model\":\"gpt-4o\""));
+    }
+
     #[test]
     fn writes_jsonl_audit_record() {
         let dir = tempdir().expect("temp dir");
@@ -0,0 +1,82 @@
+//! `workclaw` 命令行：在没有图形界面的服务器上使用与桌面端相同的数据库和运行时根目录，
+//! 运行员工/技能对话、查看导出会话、安装技能包以及管理模型配置。
+
+mod app_backend;
+mod args;
+mod backend;
+mod interaction;
+mod subcommands;
+
+pub use args::{parse_args, CliArgs, CliCommand, USAGE};
+
+use app_backend::AppCliBackend;
+use backend::CliBackend;
+use std::sync::Arc;
+
+/// 命令行退出码：0 成功，1 执行失败，2 参数错误
+pub async fn run(raw_args: Vec<String>) -> i32 {
+    let args = match parse_args(raw_args) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("[workclaw] {error}\n\n{USAGE}");
+            return 2;
+        }
+    };
+    if args.command == CliCommand::Help {
+        println!("{USAGE}");
+        return 0;
+    }
+
+    let result = match AppCliBackend::open(args.runtime_root).await {
+        // app 需要在命令执行期间保持存活
+        Ok((_app, backend)) => execute(Arc::new(backend), args.command).await,
+        Err(error) => Err(error),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("[workclaw] {error}");
+            1
+        }
+    }
+}
+
+async fn execute(backend: Arc<dyn CliBackend>, command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Help => Ok(()),
+        CliCommand::Run(run) => subcommands::run_prompt(backend, run).await,
+        CliCommand::SessionsList { limit, json } => {
+            subcommands::list_sessions(backend.as_ref(), limit, json).await
+        }
+        CliCommand::SessionsExport {
+            session_id,
+            format,
+            output,
+        } => {
+            subcommands::export_session(backend.as_ref(), &session_id, format, output.as_deref())
+                .await
+        }
+        CliCommand::SkillsList { json } => {
+            subcommands::list_installed_skills(backend.as_ref(), json).await
+        }
+        CliCommand::SkillsInstall {
+            pack_path,
+            username,
+            allow_untrusted,
+        } => {
+            subcommands::install_skillpack(backend.as_ref(), &pack_path, &username, allow_untrusted)
+                .await
+        }
+        CliCommand::SkillsImport { dir_path } => {
+            subcommands::import_skill_dir(backend.as_ref(), &dir_path).await
+        }
+        CliCommand::ModelsList { json } => subcommands::list_models(backend.as_ref(), json).await,
+        CliCommand::ModelsAdd(model) => subcommands::add_model(backend.as_ref(), model).await,
+        CliCommand::ModelsDefault { model_id } => {
+            subcommands::set_default_model(backend.as_ref(), &model_id).await
+        }
+        CliCommand::ModelsRemove { model_id } => {
+            subcommands::remove_model(backend.as_ref(), &model_id).await
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::backend::{CliBackend, CliEventHandler, CliListenerId, CliSessionRequest};
+use crate::agent::AgentExecutor;
+use crate::approval_bus::ApprovalDecision;
+use crate::commands::approvals::resolve_approval;
+use crate::commands::chat::{
+    create_session, send_message, ApprovalManagerState, AskUserPendingSessionState, AskUserState,
+    CancelFlagState, PendingApprovalBridgeState, SendMessagePart, SendMessageRequest,
+};
+use crate::commands::chat_control::answer_user_question;
+use crate::commands::mcp::restore_saved_mcp_servers_with_registry;
+use crate::commands::models::{list_model_configs, ModelConfig};
+use crate::commands::skills::{install_skill, list_skills, DbState, InstalledSkillListItem};
+use crate::runtime_environment::{
+    initialize_headless_runtime_environment, ManagedRuntimeEnvironment,
+};
+use crate::session_journal::{SessionJournalStateHandle, SessionJournalStore};
+use async_trait::async_trait;
+use skillpack_rs::SkillManifest;
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::test::{mock_context, noop_assets};
+use tauri::{AppHandle, Listener, Manager, Wry};
+
+/// 无窗口的桌面端运行时：与桌面端共用数据库、会话 journal 与工具注册表初始化逻辑。
+/// `tauri::App` 由调用方持有并在命令执行期间保持存活，后端只持有 `AppHandle`。
+pub struct AppCliBackend {
+    app: AppHandle,
+    pool: SqlitePool,
+    journal: Arc<SessionJournalStore>,
+}
+
+impl AppCliBackend {
+    pub async fn open(runtime_root: Option<PathBuf>) -> Result<(tauri::App<Wry>, Self), String> {
+        let environment = initialize_headless_runtime_environment(runtime_root)?;
+
+        let mut app = build_headless_app()?;
+        let pool = crate::db::init_db_at_runtime_paths(&environment.paths)
+            .await
+            .map_err(|e| {
+                format!(
+                    "初始化数据库失败（{}）: {e}",
+                    environment.paths.database.db_path.display()
+                )
+            })?;
+        let paths = environment.paths.clone();
+        app.manage(ManagedRuntimeEnvironment(Arc::new(environment)));
+
+        let handles = crate::initialize_runtime_state(&mut app, pool.clone(), &paths);
+        crate::reload_persisted_runtime_settings(&pool).await;
+        if let Err(error) =
+            restore_saved_mcp_servers_with_registry(&pool, Arc::clone(&handles.registry)).await
+        {
+            eprintln!("[mcp] 恢复 MCP 服务器失败: {error}");
+        }
+        let journal = app.state::<SessionJournalStateHandle>().0.clone();
+
+        let backend = Self {
+            app: app.handle().clone(),
+            pool,
+            journal,
+        };
+        Ok((app, backend))
+    }
+}
+
+#[async_trait]
+impl CliBackend for AppCliBackend {
+    fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    fn journal(&self) -> &SessionJournalStore {
+        &self.journal
+    }
+
+    fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.app.state::<CancelFlagState>().0.clone()
+    }
+
+    async fn create_session(&self, request: CliSessionRequest) -> Result<String, String> {
+        create_session(
+            self.app.clone(),
+            request.skill_id,
+            request.model_id,
+            request.work_dir,
+            request.employee_id,
+            None,
+            request.permission_mode,
+            request.session_mode,
+            None,
+            self.app.state::<DbState>(),
+        )
+        .await
+    }
+
+    async fn send_message(
+        &self,
+        session_id: &str,
+        prompt: String,
+        max_iterations: Option<usize>,
+    ) -> Result<(), String> {
+        // 完整的运行 future 层级很深，装箱后再放进 async_trait 生成的 future
+        Box::pin(send_message(
+            self.app.clone(),
+            SendMessageRequest {
+                session_id: session_id.to_string(),
+                parts: vec![SendMessagePart::Text { text: prompt }],
+                max_iterations,
+            },
+            self.app.state::<DbState>(),
+            self.app.state::<Arc<AgentExecutor>>(),
+            self.app.state::<SessionJournalStateHandle>(),
+            self.app.state::<CancelFlagState>(),
+        ))
+        .await
+    }
+
+    fn listen(&self, event: &'static str, handler: CliEventHandler) -> CliListenerId {
+        self.app
+            .listen_any(event, move |event| handler(event.payload()))
+    }
+
+    fn unlisten(&self, listener: CliListenerId) {
+        self.app.unlisten(listener);
+    }
+
+    async fn resolve_approval(
+        &self,
+        approval_id: String,
+        decision: ApprovalDecision,
+    ) -> Result<(), String> {
+        resolve_approval(
+            self.app.clone(),
+            approval_id,
+            decision,
+            "cli".to_string(),
+            None,
+            self.app.state::<DbState>(),
+            self.app.state::<ApprovalManagerState>(),
+            self.app.state::<PendingApprovalBridgeState>(),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn answer_question(&self, answer: String) -> Result<(), String> {
+        answer_user_question(
+            answer,
+            self.app.clone(),
+            self.app.state::<AskUserState>(),
+            self.app.state::<AskUserPendingSessionState>(),
+        )
+        .await
+    }
+
+    fn abandon_question(&self) {
+        self.app
+            .state::<AskUserState>()
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+    }
+
+    async fn list_skills(&self) -> Result<Vec<InstalledSkillListItem>, String> {
+        list_skills(self.app.state::<DbState>()).await
+    }
+
+    async fn install_skill(
+        &self,
+        pack_path: String,
+        username: String,
+        allow_untrusted: bool,
+    ) -> Result<SkillManifest, String> {
+        install_skill(
+            pack_path,
+            username,
+            Some(allow_untrusted),
+            self.app.clone(),
+            self.app.state::<DbState>(),
+        )
+        .await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelConfig>, String> {
+        list_model_configs(self.app.state::<DbState>()).await
+    }
+}
+
+/// 构建不创建窗口的 Tauri 应用。Linux 上 Wry 初始化仍依赖 GTK 与显示服务，
+/// 没有桌面环境的服务器需要通过 `xvfb-run workclaw ...` 提供虚拟显示。
+fn build_headless_app() -> Result<tauri::App<Wry>, String> {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let built =
+        std::panic::catch_unwind(|| tauri::Builder::default().build(mock_context(noop_assets())));
+    std::panic::set_hook(previous_hook);
+
+    match built {
+        Ok(Ok(app)) => Ok(app),
+        Ok(Err(error)) => Err(format!(
+            "创建 headless runtime app 失败: {error}{}",
+            display_hint()
+        )),
+        Err(_) => Err(format!("创建 headless runtime app 失败{}", display_hint())),
+    }
+}
+
+fn display_hint() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "\n提示: 当前环境可能没有可用的显示服务，可安装 xvfb 后使用 `xvfb-run workclaw ...` 运行"
+    } else {
+        ""
+    }
+}
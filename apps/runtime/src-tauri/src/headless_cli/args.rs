@@ -0,0 +1,553 @@
+use std::path::PathBuf;
+
+/// workclaw 命令行参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    /// 覆盖运行时根目录；默认与桌面端共用同一个根目录和数据库
+    pub runtime_root: Option<PathBuf>,
+    pub command: CliCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Help,
+    Run(RunArgs),
+    SessionsList {
+        limit: usize,
+        json: bool,
+    },
+    SessionsExport {
+        session_id: String,
+        format: ExportFormat,
+        output: Option<PathBuf>,
+    },
+    SkillsList {
+        json: bool,
+    },
+    SkillsInstall {
+        pack_path: String,
+        username: String,
+        /// 显式允许安装未签名或签名者不在信任列表中的技能包
+        allow_untrusted: bool,
+    },
+    SkillsImport {
+        dir_path: String,
+    },
+    ModelsList {
+        json: bool,
+    },
+    ModelsAdd(ModelAddArgs),
+    ModelsDefault {
+        model_id: String,
+    },
+    ModelsRemove {
+        model_id: String,
+    },
+}
+
+/// 工具审批的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalMode {
+    /// 终端交互时逐条询问，非交互时拒绝
+    Ask,
+    ApproveAll,
+    DenyAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunArgs {
+    /// None 表示从标准输入读取提示词
+    pub prompt: Option<String>,
+    pub employee: Option<String>,
+    pub skill: Option<String>,
+    pub model: Option<String>,
+    /// 继续已有会话，而不是新建会话
+    pub session: Option<String>,
+    pub work_dir: Option<PathBuf>,
+    pub permission_mode: Option<String>,
+    pub approval: ApprovalMode,
+    /// 预先提供的 ask_user 回答，按提问顺序依次使用
+    pub answers: Vec<String>,
+    pub max_iterations: Option<usize>,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelAddArgs {
+    pub name: String,
+    pub api_format: String,
+    pub base_url: String,
+    pub model_name: String,
+    /// 从环境变量读取 API Key，避免密钥出现在 shell 历史和进程列表里
+    pub api_key_env: Option<String>,
+    /// 从标准输入读取 API Key
+    pub api_key_stdin: bool,
+    pub is_default: bool,
+    pub supports_vision: bool,
+}
+
+pub const USAGE: &str = "\
+用法: workclaw [--root <dir>] <命令>
+
+命令:
+  run [选项] <提示词...>          运行一次对话，提示词为 - 或省略时从标准输入读取
+      --employee <id>             使用指定员工（员工 ID、编号或名称）
+      --skill <id>                使用指定技能（默认 builtin-general 或员工主技能）
+      --model <id>                使用指定模型配置（默认使用默认模型）
+      --session <id>              在已有会话中继续对话
+      --work-dir <dir>            工作目录（默认当前目录或员工默认目录）
+      --permission-mode <mode>    standard | full_access
+      --yes                       自动批准所有工具审批
+      --deny                      自动拒绝所有工具审批
+      --answer <text>             预先回答 ask_user 提问，可重复，按顺序使用；
+                                  非交互运行时没有可用回答会终止本次运行
+      --max-iterations <n>        最大迭代轮数
+      --json                      不流式输出，结束后输出 JSON 结果
+  sessions list [--limit <n>] [--json]
+  sessions export <session_id> [--format markdown|json] [--output <file>]
+  skills list [--json]
+  skills install <pack.skillpack> --username <name> [--allow-untrusted]
+      --allow-untrusted           允许安装未签名或发布者不受信任的技能包
+  skills import <dir>
+  models list [--json]
+  models add --name <name> --api-format <openai|anthropic|...> --base-url <url> --model <model>
+             (--api-key-env <VAR> | --api-key-stdin) [--default] [--vision]
+  models default <model_id>
+  models remove <model_id>
+
+全局选项:
+  --root <dir>                    指定运行时根目录（默认与桌面端相同）
+  -h, --help                      显示帮助";
+
+/// 按顺序读取参数；`flag` 取值缺失时给出统一的错误信息
+struct ArgCursor {
+    args: Vec<String>,
+    index: usize,
+}
+
+impl ArgCursor {
+    fn next(&mut self) -> Option<String> {
+        let value = self.args.get(self.index).cloned();
+        if value.is_some() {
+            self.index += 1;
+        }
+        value
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.next()
+            .filter(|value| !value.starts_with("--"))
+            .ok_or_else(|| format!("{flag} 缺少值"))
+    }
+
+    fn positional(&mut self, name: &str) -> Result<String, String> {
+        self.next()
+            .filter(|value| !value.starts_with("--"))
+            .ok_or_else(|| format!("缺少 {name}"))
+    }
+}
+
+pub fn parse_args(args: Vec<String>) -> Result<CliArgs, String> {
+    let mut cursor = ArgCursor { args, index: 0 };
+    let mut runtime_root = None;
+    let command = loop {
+        let Some(arg) = cursor.next() else {
+            break CliCommand::Help;
+        };
+        match arg.as_str() {
+            "--root" => runtime_root = Some(PathBuf::from(cursor.value("--root")?)),
+            "--help" | "-h" | "help" => break CliCommand::Help,
+            "run" => break CliCommand::Run(parse_run(&mut cursor)?),
+            "sessions" => break parse_sessions(&mut cursor)?,
+            "skills" => break parse_skills(&mut cursor)?,
+            "models" => break parse_models(&mut cursor)?,
+            other => return Err(format!("未知命令: {other}")),
+        }
+    };
+    Ok(CliArgs {
+        runtime_root,
+        command,
+    })
+}
+
+fn parse_run(cursor: &mut ArgCursor) -> Result<RunArgs, String> {
+    let mut run = RunArgs {
+        prompt: None,
+        employee: None,
+        skill: None,
+        model: None,
+        session: None,
+        work_dir: None,
+        permission_mode: None,
+        approval: ApprovalMode::Ask,
+        answers: Vec::new(),
+        max_iterations: None,
+        json: false,
+    };
+    let mut words = Vec::new();
+    while let Some(arg) = cursor.next() {
+        match arg.as_str() {
+            "--employee" => run.employee = Some(cursor.value("--employee")?),
+            "--skill" => run.skill = Some(cursor.value("--skill")?),
+            "--model" => run.model = Some(cursor.value("--model")?),
+            "--session" => run.session = Some(cursor.value("--session")?),
+            "--work-dir" => run.work_dir = Some(PathBuf::from(cursor.value("--work-dir")?)),
+            "--permission-mode" => {
+                let mode = cursor.value("--permission-mode")?;
+                if !matches!(mode.as_str(), "standard" | "full_access") {
+                    return Err(format!("不支持的权限模式: {mode}"));
+                }
+                run.permission_mode = Some(mode);
+            }
+            "--yes" | "-y" => run.approval = ApprovalMode::ApproveAll,
+            "--deny" => run.approval = ApprovalMode::DenyAll,
+            "--answer" => run.answers.push(cursor.value("--answer")?),
+            "--max-iterations" => {
+                let raw = cursor.value("--max-iterations")?;
+                let value = raw
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|value| *value > 0)
+                    .ok_or_else(|| format!("--max-iterations 必须是正整数: {raw}"))?;
+                run.max_iterations = Some(value);
+            }
+            "--json" => run.json = true,
+            "--" => {
+                words.extend(std::iter::from_fn(|| cursor.next()));
+            }
+            flag if flag.starts_with("--") => return Err(format!("未知参数: {flag}")),
+            _ => words.push(arg),
+        }
+    }
+    let overrides_session_setup = run.employee.is_some()
+        || run.skill.is_some()
+        || run.model.is_some()
+        || run.work_dir.is_some()
+        || run.permission_mode.is_some();
+    if run.session.is_some() && overrides_session_setup {
+        return Err(
+            "--session 不能与 --employee/--skill/--model/--work-dir/--permission-mode 同时使用"
+                .to_string(),
+        );
+    }
+    run.prompt = match words.as_slice() {
+        [] => None,
+        [single] if single == "-" => None,
+        _ => Some(words.join(" ")),
+    };
+    Ok(run)
+}
+
+fn parse_sessions(cursor: &mut ArgCursor) -> Result<CliCommand, String> {
+    match cursor.positional("sessions 子命令")?.as_str() {
+        "list" => {
+            let mut limit = 20;
+            let mut json = false;
+            while let Some(arg) = cursor.next() {
+                match arg.as_str() {
+                    "--limit" => {
+                        let raw = cursor.value("--limit")?;
+                        limit = raw
+                            .parse::<usize>()
+                            .map_err(|_| format!("--limit 必须是整数: {raw}"))?;
+                    }
+                    "--json" => json = true,
+                    other => return Err(format!("未知参数: {other}")),
+                }
+            }
+            Ok(CliCommand::SessionsList { limit, json })
+        }
+        "export" => {
+            let session_id = cursor.positional("session_id")?;
+            let mut format = ExportFormat::Markdown;
+            let mut output = None;
+            while let Some(arg) = cursor.next() {
+                match arg.as_str() {
+                    "--format" => {
+                        format = match cursor.value("--format")?.as_str() {
+                            "markdown" | "md" => ExportFormat::Markdown,
+                            "json" => ExportFormat::Json,
+                            other => return Err(format!("不支持的导出格式: {other}")),
+                        }
+                    }
+                    "--output" | "-o" => output = Some(PathBuf::from(cursor.value("--output")?)),
+                    other => return Err(format!("未知参数: {other}")),
+                }
+            }
+            Ok(CliCommand::SessionsExport {
+                session_id,
+                format,
+                output,
+            })
+        }
+        other => Err(format!("未知 sessions 子命令: {other}")),
+    }
+}
+
+fn parse_skills(cursor: &mut ArgCursor) -> Result<CliCommand, String> {
+    match cursor.positional("skills 子命令")?.as_str() {
+        "list" => Ok(CliCommand::SkillsList {
+            json: parse_json_flag(cursor)?,
+        }),
+        "install" => {
+            let pack_path = cursor.positional("skillpack 路径")?;
+            let mut username = None;
+            let mut allow_untrusted = false;
+            while let Some(arg) = cursor.next() {
+                match arg.as_str() {
+                    "--username" => username = Some(cursor.value("--username")?),
+                    "--allow-untrusted" => allow_untrusted = true,
+                    other => return Err(format!("未知参数: {other}")),
+                }
+            }
+            Ok(CliCommand::SkillsInstall {
+                pack_path,
+                username: username.ok_or_else(|| "缺少 --username".to_string())?,
+                allow_untrusted,
+            })
+        }
+        "import" => {
+            let dir_path = cursor.positional("技能目录")?;
+            if let Some(extra) = cursor.next() {
+                return Err(format!("未知参数: {extra}"));
+            }
+            Ok(CliCommand::SkillsImport { dir_path })
+        }
+        other => Err(format!("未知 skills 子命令: {other}")),
+    }
+}
+
+fn parse_models(cursor: &mut ArgCursor) -> Result<CliCommand, String> {
+    match cursor.positional("models 子命令")?.as_str() {
+        "list" => Ok(CliCommand::ModelsList {
+            json: parse_json_flag(cursor)?,
+        }),
+        "add" => {
+            let mut name = None;
+            let mut api_format = None;
+            let mut base_url = None;
+            let mut model_name = None;
+            let mut api_key_env = None;
+            let mut api_key_stdin = false;
+            let mut is_default = false;
+            let mut supports_vision = false;
+            while let Some(arg) = cursor.next() {
+                match arg.as_str() {
+                    "--name" => name = Some(cursor.value("--name")?),
+                    "--api-format" => api_format = Some(cursor.value("--api-format")?),
+                    "--base-url" => base_url = Some(cursor.value("--base-url")?),
+                    "--model" => model_name = Some(cursor.value("--model")?),
+                    "--api-key-env" => api_key_env = Some(cursor.value("--api-key-env")?),
+                    "--api-key-stdin" => api_key_stdin = true,
+                    "--default" => is_default = true,
+                    "--vision" => supports_vision = true,
+                    other => return Err(format!("未知参数: {other}")),
+                }
+            }
+            if api_key_stdin == api_key_env.is_some() {
+                return Err("需要且只能指定 --api-key-env 或 --api-key-stdin 之一".to_string());
+            }
+            let model_name = model_name.ok_or_else(|| "缺少 --model".to_string())?;
+            Ok(CliCommand::ModelsAdd(ModelAddArgs {
+                name: name.unwrap_or_else(|| model_name.clone()),
+                api_format: api_format.ok_or_else(|| "缺少 --api-format".to_string())?,
+                base_url: base_url.ok_or_else(|| "缺少 --base-url".to_string())?,
+                model_name,
+                api_key_env,
+                api_key_stdin,
+                is_default,
+                supports_vision,
+            }))
+        }
+        "default" => Ok(CliCommand::ModelsDefault {
+            model_id: cursor.positional("model_id")?,
+        }),
+        "remove" => Ok(CliCommand::ModelsRemove {
+            model_id: cursor.positional("model_id")?,
+        }),
+        other => Err(format!("未知 models 子命令: {other}")),
+    }
+}
+
+fn parse_json_flag(cursor: &mut ArgCursor) -> Result<bool, String> {
+    let mut json = false;
+    while let Some(arg) = cursor.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            other => return Err(format!("未知参数: {other}")),
+        }
+    }
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn parse_run_collects_flags_answers_and_prompt_words() {
+        let parsed = parse(&[
+            "--root",
+            "/srv/workclaw",
+            "run",
+            "--employee",
+            "pm",
+            "--yes",
+            "--answer",
+            "选项A",
+            "--answer",
+            "继续",
+            "--max-iterations",
+            "8",
+            "整理",
+            "本周周报",
+        ])
+        .expect("parse run");
+
+        assert_eq!(parsed.runtime_root, Some(PathBuf::from("/srv/workclaw")));
+        let CliCommand::Run(run) = parsed.command else {
+            panic!("expected run command");
+        };
+        assert_eq!(run.employee.as_deref(), Some("pm"));
+        assert_eq!(run.approval, ApprovalMode::ApproveAll);
+        assert_eq!(run.answers, vec!["选项A".to_string(), "继续".to_string()]);
+        assert_eq!(run.max_iterations, Some(8));
+        assert_eq!(run.prompt.as_deref(), Some("整理 本周周报"));
+    }
+
+    #[test]
+    fn parse_run_reads_stdin_prompt_and_rejects_bad_flags() {
+        let CliCommand::Run(run) = parse(&["run", "--json", "-"]).unwrap().command else {
+            panic!("expected run command");
+        };
+        assert!(run.prompt.is_none());
+        assert!(run.json);
+
+        let CliCommand::Run(run) = parse(&["run", "--", "--not-a-flag"]).unwrap().command else {
+            panic!("expected run command");
+        };
+        assert_eq!(run.prompt.as_deref(), Some("--not-a-flag"));
+
+        assert!(parse(&["run", "--model"]).is_err());
+        assert!(parse(&["run", "--permission-mode", "yolo", "hi"]).is_err());
+        assert!(parse(&["run", "--employee", "pm", "--session", "s1", "hi"]).is_err());
+        assert!(parse(&["run", "--unknown", "hi"]).is_err());
+    }
+
+    #[test]
+    fn parse_management_subcommands() {
+        assert_eq!(
+            parse(&["sessions", "export", "s1", "--format", "json", "-o", "out.json"])
+                .unwrap()
+                .command,
+            CliCommand::SessionsExport {
+                session_id: "s1".to_string(),
+                format: ExportFormat::Json,
+                output: Some(PathBuf::from("out.json")),
+            }
+        );
+        assert_eq!(
+            parse(&["skills", "install", "a.skillpack", "--username", "alice"])
+                .unwrap()
+                .command,
+            CliCommand::SkillsInstall {
+                pack_path: "a.skillpack".to_string(),
+                username: "alice".to_string(),
+                allow_untrusted: false,
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "skills",
+                "install",
+                "a.skillpack",
+                "--allow-untrusted",
+                "--username",
+                "alice"
+            ])
+            .unwrap()
+            .command,
+            CliCommand::SkillsInstall {
+                pack_path: "a.skillpack".to_string(),
+                username: "alice".to_string(),
+                allow_untrusted: true,
+            }
+        );
+        let CliCommand::ModelsAdd(model) = parse(&[
+            "models",
+            "add",
+            "--api-format",
+            "openai",
+            "--base-url",
+            "https://api.example.com/v1",
+            "--model",
+            "gpt-x",
+            "--api-key-env",
+            "EXAMPLE_KEY",
+            "--default",
+        ])
+        .unwrap()
+        .command
+        else {
+            panic!("expected models add");
+        };
+        assert_eq!(model.name, "gpt-x");
+        assert_eq!(model.api_key_env.as_deref(), Some("EXAMPLE_KEY"));
+        assert!(model.is_default);
+
+        assert!(parse(&[
+            "models",
+            "add",
+            "--api-format",
+            "openai",
+            "--base-url",
+            "u",
+            "--model",
+            "m"
+        ])
+        .is_err());
+        assert!(parse(&[
+            "models",
+            "add",
+            "--api-format",
+            "openai",
+            "--base-url",
+            "u",
+            "--model",
+            "m",
+            "--api-key",
+            "sk-plain"
+        ])
+        .is_err());
+        let CliCommand::ModelsAdd(model) = parse(&[
+            "models",
+            "add",
+            "--api-format",
+            "openai",
+            "--base-url",
+            "u",
+            "--model",
+            "m",
+            "--api-key-stdin",
+        ])
+        .unwrap()
+        .command
+        else {
+            panic!("expected models add");
+        };
+        assert!(model.api_key_stdin);
+        assert_eq!(model.api_key_env, None);
+        assert_eq!(parse(&[]).unwrap().command, CliCommand::Help);
+        assert!(parse(&["deploy"]).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::approval_bus::ApprovalDecision;
+use crate::commands::models::ModelConfig;
+use crate::commands::skills::InstalledSkillListItem;
+use crate::session_journal::SessionJournalStore;
+use async_trait::async_trait;
+use skillpack_rs::SkillManifest;
+use sqlx::SqlitePool;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// 事件监听句柄，用于 `CliBackend::unlisten`
+pub type CliListenerId = u32;
+
+/// 运行时事件回调，参数为事件的 JSON 负载
+pub type CliEventHandler = Box<dyn Fn(&str) + Send + 'static>;
+
+/// 新建会话的参数，字段含义与 `create_session` 命令一致
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliSessionRequest {
+    pub skill_id: String,
+    pub model_id: String,
+    pub work_dir: Option<String>,
+    pub employee_id: Option<String>,
+    pub permission_mode: Option<String>,
+    pub session_mode: Option<String>,
+}
+
+/// workclaw 命令行背后的数据与执行能力。
+///
+/// 参数解析、终端交互与输出只依赖这个 trait，不接触 Tauri 运行时类型；
+/// 桌面端实现把运行交给 `send_message`，与桌面会话共用权限模式、工具策略和审批总线。
+#[async_trait]
+pub trait CliBackend: Send + Sync {
+    fn pool(&self) -> &SqlitePool;
+    fn journal(&self) -> &SessionJournalStore;
+    /// 当前运行的取消标记，Ctrl-C 与无法回答的提问都通过它终止运行
+    fn cancel_flag(&self) -> Arc<AtomicBool>;
+
+    async fn create_session(&self, request: CliSessionRequest) -> Result<String, String>;
+    async fn send_message(
+        &self,
+        session_id: &str,
+        prompt: String,
+        max_iterations: Option<usize>,
+    ) -> Result<(), String>;
+
+    /// 监听运行时广播的事件（stream-token、approval-created 等）
+    fn listen(&self, event: &'static str, handler: CliEventHandler) -> CliListenerId;
+    fn unlisten(&self, listener: CliListenerId);
+    async fn resolve_approval(
+        &self,
+        approval_id: String,
+        decision: ApprovalDecision,
+    ) -> Result<(), String>;
+    async fn answer_question(&self, answer: String) -> Result<(), String>;
+    /// 丢弃等待中的 ask_user 应答通道，让提问立即结束
+    fn abandon_question(&self);
+
+    async fn list_skills(&self) -> Result<Vec<InstalledSkillListItem>, String>;
+    async fn install_skill(
+        &self,
+        pack_path: String,
+        username: String,
+        allow_untrusted: bool,
+    ) -> Result<SkillManifest, String>;
+    async fn list_models(&self) -> Result<Vec<ModelConfig>, String>;
+}
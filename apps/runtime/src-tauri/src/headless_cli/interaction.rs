@@ -0,0 +1,566 @@
+use super::args::ApprovalMode;
+use super::backend::{CliBackend, CliListenerId};
+use crate::approval_bus::ApprovalDecision;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Deserialize)]
+struct StreamTokenPayload {
+    session_id: String,
+    token: String,
+    #[serde(default)]
+    sub_agent: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallPayload {
+    tool_name: String,
+    status: String,
+    #[serde(default)]
+    tool_output: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApprovalPayload {
+    approval_id: String,
+    tool_name: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    impact: Option<String>,
+    #[serde(default)]
+    irreversible: bool,
+    #[serde(default)]
+    tool_input: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct AskUserPayload {
+    question: String,
+    #[serde(default)]
+    options: Vec<String>,
+}
+
+enum PendingPrompt {
+    Approval(ApprovalPayload),
+    Question(AskUserPayload),
+}
+
+pub struct InteractionOptions {
+    pub session_id: String,
+    pub approval: ApprovalMode,
+    pub answers: Vec<String>,
+    /// 为 false 时不把回复 token 写到标准输出（--json 模式）
+    pub stream_output: bool,
+}
+
+/// 一次 `workclaw run` 期间挂在运行时上的事件监听与应答任务
+pub struct SessionInteraction {
+    backend: Arc<dyn CliBackend>,
+    listeners: Vec<CliListenerId>,
+    responder: tokio::task::JoinHandle<()>,
+    streamed: Arc<AtomicBool>,
+    ends_with_newline: Arc<AtomicBool>,
+    unanswered_question: Arc<Mutex<Option<String>>>,
+}
+
+impl SessionInteraction {
+    pub fn attach(backend: Arc<dyn CliBackend>, options: InteractionOptions) -> Self {
+        let streamed = Arc::new(AtomicBool::new(false));
+        let ends_with_newline = Arc::new(AtomicBool::new(true));
+        let unanswered_question = Arc::new(Mutex::new(None));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut listeners = Vec::new();
+
+        {
+            let session_id = options.session_id.clone();
+            let streamed = Arc::clone(&streamed);
+            let ends_with_newline = Arc::clone(&ends_with_newline);
+            let stream_output = options.stream_output;
+            listeners.push(backend.listen(
+                "stream-token",
+                Box::new(move |payload| {
+                    let Ok(payload) = serde_json::from_str::<StreamTokenPayload>(payload) else {
+                        return;
+                    };
+                    if !stream_output
+                        || payload.sub_agent
+                        || payload.session_id != session_id
+                        || payload.token.is_empty()
+                    {
+                        return;
+                    }
+                    let mut stdout = std::io::stdout().lock();
+                    let _ = stdout.write_all(payload.token.as_bytes());
+                    let _ = stdout.flush();
+                    streamed.store(true, Ordering::SeqCst);
+                    ends_with_newline.store(payload.token.ends_with('\n'), Ordering::SeqCst);
+                }),
+            ));
+        }
+        listeners.push(backend.listen(
+            "tool-call-event",
+            Box::new(|payload| {
+                let Ok(payload) = serde_json::from_str::<ToolCallPayload>(payload) else {
+                    return;
+                };
+                match payload.status.as_str() {
+                    "started" => eprintln!("[工具] {} 开始执行", payload.tool_name),
+                    "error" => eprintln!(
+                        "[工具] {} 执行失败: {}",
+                        payload.tool_name,
+                        payload.tool_output.unwrap_or_default()
+                    ),
+                    _ => {}
+                }
+            }),
+        ));
+        // 子代理可能在其它会话中请求审批或提问，这里不按会话过滤：一个 CLI 进程只驱动一次运行
+        {
+            let sender = sender.clone();
+            listeners.push(backend.listen(
+                "approval-created",
+                Box::new(move |payload| {
+                    if let Ok(payload) = serde_json::from_str::<ApprovalPayload>(payload) {
+                        let _ = sender.send(PendingPrompt::Approval(payload));
+                    }
+                }),
+            ));
+        }
+        listeners.push(backend.listen(
+            "ask-user-event",
+            Box::new(move |payload| {
+                if let Ok(payload) = serde_json::from_str::<AskUserPayload>(payload) {
+                    let _ = sender.send(PendingPrompt::Question(payload));
+                }
+            }),
+        ));
+
+        let responder = tokio::spawn(respond_to_prompts(
+            Arc::clone(&backend),
+            receiver,
+            options.approval,
+            options.answers.into(),
+            std::io::stdin().is_terminal(),
+            Arc::clone(&unanswered_question),
+        ));
+
+        Self {
+            backend,
+            listeners,
+            responder,
+            streamed,
+            ends_with_newline,
+            unanswered_question,
+        }
+    }
+
+    /// 是否已有回复内容流式写到标准输出
+    pub fn streamed(&self) -> bool {
+        self.streamed.load(Ordering::SeqCst)
+    }
+
+    /// 因没有可用回答而终止运行的 ask_user 提问
+    pub fn unanswered_question(&self) -> Option<String> {
+        self.unanswered_question
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn finish(self) {
+        for listener in self.listeners {
+            self.backend.unlisten(listener);
+        }
+        self.responder.abort();
+        if self.streamed.load(Ordering::SeqCst) && !self.ends_with_newline.load(Ordering::SeqCst) {
+            println!();
+        }
+    }
+}
+
+async fn respond_to_prompts(
+    backend: Arc<dyn CliBackend>,
+    mut receiver: mpsc::UnboundedReceiver<PendingPrompt>,
+    approval_mode: ApprovalMode,
+    mut answers: VecDeque<String>,
+    interactive: bool,
+    unanswered_question: Arc<Mutex<Option<String>>>,
+) {
+    while let Some(prompt) = receiver.recv().await {
+        let result = match prompt {
+            PendingPrompt::Approval(approval) => {
+                let decision = decide_approval(&approval, approval_mode, interactive).await;
+                backend
+                    .resolve_approval(approval.approval_id, decision)
+                    .await
+            }
+            PendingPrompt::Question(question) => {
+                match decide_answer(&question, &mut answers, interactive).await {
+                    Some(answer) => backend.answer_question(answer).await,
+                    None => {
+                        abort_unanswered_question(
+                            backend.as_ref(),
+                            question.question,
+                            &unanswered_question,
+                        );
+                        Ok(())
+                    }
+                }
+            }
+        };
+        if let Err(error) = result {
+            eprintln!("[workclaw] 提交回复失败: {error}");
+        }
+    }
+}
+
+async fn decide_approval(
+    approval: &ApprovalPayload,
+    mode: ApprovalMode,
+    interactive: bool,
+) -> ApprovalDecision {
+    let title = approval
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("执行 {}", approval.tool_name));
+    match mode {
+        ApprovalMode::ApproveAll => {
+            eprintln!("[审批] 已自动批准: {title}");
+            return ApprovalDecision::AllowOnce;
+        }
+        ApprovalMode::DenyAll => {
+            eprintln!("[审批] 已自动拒绝: {title}");
+            return ApprovalDecision::Deny;
+        }
+        ApprovalMode::Ask if !interactive => {
+            eprintln!("[审批] 非交互模式下已拒绝: {title}（可使用 --yes 自动批准）");
+            return ApprovalDecision::Deny;
+        }
+        ApprovalMode::Ask => {}
+    }
+
+    eprintln!("\n[审批] {title}");
+    if let Some(summary) = approval.summary.as_deref().filter(|s| !s.is_empty()) {
+        eprintln!("  说明: {summary}");
+    }
+    if let Some(impact) = approval.impact.as_deref().filter(|s| !s.is_empty()) {
+        eprintln!("  影响: {impact}");
+    }
+    if approval.irreversible {
+        eprintln!("  注意: 该操作不可撤销");
+    }
+    if !approval.tool_input.is_null() {
+        eprintln!("  参数: {}", approval.tool_input);
+    }
+    loop {
+        let Some(reply) = prompt_line("  是否允许？[y] 允许一次 / [a] 总是允许 / [n] 拒绝: ").await
+        else {
+            return ApprovalDecision::Deny;
+        };
+        match parse_approval_reply(&reply) {
+            Some(decision) => return decision,
+            None => eprintln!("  无法识别的回复: {}", reply.trim()),
+        }
+    }
+}
+
+/// 没有回答可用时终止本次运行：取消执行，并丢弃 ask_user 的应答通道让等待立即结束
+fn abort_unanswered_question(
+    backend: &dyn CliBackend,
+    question: String,
+    unanswered_question: &Mutex<Option<String>>,
+) {
+    *unanswered_question
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(question);
+    backend.cancel_flag().store(true, Ordering::SeqCst);
+    backend.abandon_question();
+}
+
+/// 依次使用预置回答、终端输入；两者都没有时返回 None
+async fn decide_answer(
+    question: &AskUserPayload,
+    answers: &mut VecDeque<String>,
+    interactive: bool,
+) -> Option<String> {
+    eprintln!("\n[提问] {}", question.question);
+    for (index, option) in question.options.iter().enumerate() {
+        eprintln!("  {}. {option}", index + 1);
+    }
+    if let Some(answer) = answers.pop_front() {
+        let answer = resolve_option_reply(&answer, &question.options);
+        eprintln!("  使用预置回答: {answer}");
+        return Some(answer);
+    }
+    if !interactive {
+        eprintln!("  非交互模式下没有可用回答，终止运行（可使用 --answer 预先提供回答）");
+        return None;
+    }
+    loop {
+        let reply = prompt_line("  回答（可输入选项序号）: ").await?;
+        let answer = resolve_option_reply(&reply, &question.options);
+        if !answer.is_empty() {
+            return Some(answer);
+        }
+    }
+}
+
+/// 在阻塞线程上读取一行终端输入；标准输入关闭时返回 None
+async fn prompt_line(prompt: &'static str) -> Option<String> {
+    tokio::task::spawn_blocking(move || {
+        eprint!("{prompt}");
+        let _ = std::io::stderr().flush();
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// 首次 Ctrl-C 请求取消当前运行，再次 Ctrl-C 直接退出
+pub fn spawn_ctrl_c_handler(cancel_flag: Arc<AtomicBool>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interrupted = false;
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if interrupted {
+                std::process::exit(130);
+            }
+            interrupted = true;
+            cancel_flag.store(true, Ordering::SeqCst);
+            eprintln!("\n[workclaw] 正在取消当前运行，再次按 Ctrl-C 立即退出");
+        }
+    })
+}
+
+pub(crate) fn parse_approval_reply(reply: &str) -> Option<ApprovalDecision> {
+    match reply.trim().to_lowercase().as_str() {
+        "y" | "yes" | "是" | "允许" => Some(ApprovalDecision::AllowOnce),
+        "a" | "always" | "总是" | "总是允许" => Some(ApprovalDecision::AllowAlways),
+        "" | "n" | "no" | "否" | "拒绝" => Some(ApprovalDecision::Deny),
+        _ => None,
+    }
+}
+
+/// 回复为 1 起始的选项序号时换成对应选项，否则原样（去掉首尾空白）作为回答
+pub(crate) fn resolve_option_reply(reply: &str, options: &[String]) -> String {
+    let reply = reply.trim();
+    reply
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|index| options.get(index))
+        .cloned()
+        .unwrap_or_else(|| reply.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_approval_reply, resolve_option_reply, InteractionOptions, SessionInteraction,
+    };
+    use crate::approval_bus::ApprovalDecision;
+    use crate::commands::models::ModelConfig;
+    use crate::commands::skills::InstalledSkillListItem;
+    use crate::headless_cli::args::ApprovalMode;
+    use crate::headless_cli::backend::{
+        CliBackend, CliEventHandler, CliListenerId, CliSessionRequest,
+    };
+    use crate::session_journal::SessionJournalStore;
+    use async_trait::async_trait;
+    use skillpack_rs::SkillManifest;
+    use sqlx::SqlitePool;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+
+    /// 记录交互层提交的回复，并把测试注入的事件分发给监听器
+    struct FakeBackend {
+        pool: SqlitePool,
+        journal: SessionJournalStore,
+        cancel_flag: Arc<AtomicBool>,
+        listeners: Mutex<Vec<(CliListenerId, &'static str, CliEventHandler)>>,
+        replies: Mutex<Vec<String>>,
+    }
+
+    impl FakeBackend {
+        fn new() -> Self {
+            Self {
+                pool: sqlx::sqlite::SqlitePoolOptions::new()
+                    .connect_lazy("sqlite::memory:")
+                    .expect("lazy pool"),
+                journal: SessionJournalStore::new(PathBuf::from("journal")),
+                cancel_flag: Arc::new(AtomicBool::new(false)),
+                listeners: Mutex::new(Vec::new()),
+                replies: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn emit(&self, event: &str, payload: &str) {
+            for (_, name, handler) in self.listeners.lock().unwrap().iter() {
+                if *name == event {
+                    handler(payload);
+                }
+            }
+        }
+
+        fn replies(&self) -> Vec<String> {
+            self.replies.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl CliBackend for FakeBackend {
+        fn pool(&self) -> &SqlitePool {
+            &self.pool
+        }
+
+        fn journal(&self) -> &SessionJournalStore {
+            &self.journal
+        }
+
+        fn cancel_flag(&self) -> Arc<AtomicBool> {
+            Arc::clone(&self.cancel_flag)
+        }
+
+        async fn create_session(&self, _request: CliSessionRequest) -> Result<String, String> {
+            Err("unused".to_string())
+        }
+
+        async fn send_message(
+            &self,
+            _session_id: &str,
+            _prompt: String,
+            _max_iterations: Option<usize>,
+        ) -> Result<(), String> {
+            Err("unused".to_string())
+        }
+
+        fn listen(&self, event: &'static str, handler: CliEventHandler) -> CliListenerId {
+            let mut listeners = self.listeners.lock().unwrap();
+            let id = listeners.len() as CliListenerId;
+            listeners.push((id, event, handler));
+            id
+        }
+
+        fn unlisten(&self, listener: CliListenerId) {
+            self.listeners
+                .lock()
+                .unwrap()
+                .retain(|(id, _, _)| *id != listener);
+        }
+
+        async fn resolve_approval(
+            &self,
+            approval_id: String,
+            decision: ApprovalDecision,
+        ) -> Result<(), String> {
+            self.replies
+                .lock()
+                .unwrap()
+                .push(format!("approval:{approval_id}:{decision:?}"));
+            Ok(())
+        }
+
+        async fn answer_question(&self, answer: String) -> Result<(), String> {
+            self.replies
+                .lock()
+                .unwrap()
+                .push(format!("answer:{answer}"));
+            Ok(())
+        }
+
+        fn abandon_question(&self) {}
+
+        async fn list_skills(&self) -> Result<Vec<InstalledSkillListItem>, String> {
+            Ok(Vec::new())
+        }
+
+        async fn install_skill(
+            &self,
+            _pack_path: String,
+            _username: String,
+            _allow_untrusted: bool,
+        ) -> Result<SkillManifest, String> {
+            Err("unused".to_string())
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelConfig>, String> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn interaction_replies_to_prompts_through_the_backend() {
+        let backend = Arc::new(FakeBackend::new());
+        let interaction = SessionInteraction::attach(
+            backend.clone(),
+            InteractionOptions {
+                session_id: "session-1".to_string(),
+                approval: ApprovalMode::DenyAll,
+                answers: vec!["2".to_string()],
+                stream_output: false,
+            },
+        );
+
+        backend.emit(
+            "approval-created",
+            r#"{"approval_id":"approval-1","tool_name":"bash"}"#,
+        );
+        backend.emit(
+            "ask-user-event",
+            r#"{"question":"如何处理旧版本？","options":["保留旧版本","覆盖"]}"#,
+        );
+        for _ in 0..100 {
+            if backend.replies().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            backend.replies(),
+            vec![
+                "approval:approval-1:Deny".to_string(),
+                "answer:覆盖".to_string()
+            ]
+        );
+
+        interaction.finish();
+        assert!(backend.listeners.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn approval_replies_default_to_deny() {
+        assert_eq!(
+            parse_approval_reply(" Y\n"),
+            Some(ApprovalDecision::AllowOnce)
+        );
+        assert_eq!(
+            parse_approval_reply("always"),
+            Some(ApprovalDecision::AllowAlways)
+        );
+        assert_eq!(parse_approval_reply("\n"), Some(ApprovalDecision::Deny));
+        assert_eq!(parse_approval_reply("拒绝"), Some(ApprovalDecision::Deny));
+        assert_eq!(parse_approval_reply("maybe"), None);
+    }
+
+    #[test]
+    fn option_index_replies_map_to_option_text() {
+        let options = vec!["保留旧版本".to_string(), "覆盖".to_string()];
+        assert_eq!(resolve_option_reply("2\n", &options), "覆盖");
+        assert_eq!(resolve_option_reply("3", &options), "3");
+        assert_eq!(resolve_option_reply("0", &options), "0");
+        assert_eq!(resolve_option_reply(" 自定义回答 ", &options), "自定义回答");
+    }
+}
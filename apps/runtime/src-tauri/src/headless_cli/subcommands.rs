@@ -0,0 +1,392 @@
+use super::args::{ExportFormat, ModelAddArgs, RunArgs};
+use super::backend::{CliBackend, CliSessionRequest};
+use super::interaction::{spawn_ctrl_c_handler, InteractionOptions, SessionInteraction};
+use crate::commands::chat_policy::permission_mode_label_for_display;
+use crate::commands::chat_session_commands::export_session_markdown_with_pool;
+use crate::commands::chat_session_io::{
+    get_messages_with_pool, list_sessions_with_pool, write_export_file_to_path,
+};
+use crate::commands::employee_agents::{list_agent_employees_with_pool, AgentEmployee};
+use crate::commands::models::{
+    delete_model_config_with_pool, resolve_default_usable_model_id_with_pool,
+    save_model_config_with_pool, set_default_model_with_pool, ModelConfig,
+};
+use crate::commands::skills::{import_local_skills_to_pool, UNTRUSTED_SKILLPACK_ERROR_PREFIX};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DEFAULT_SKILL_ID: &str = "builtin-general";
+
+pub async fn run_prompt(backend: Arc<dyn CliBackend>, args: RunArgs) -> Result<(), String> {
+    let prompt = match args.prompt.clone() {
+        Some(prompt) => prompt,
+        None => read_prompt_from_stdin()?,
+    };
+    if prompt.trim().is_empty() {
+        return Err("提示词为空".to_string());
+    }
+
+    let session_id = match args.session.clone() {
+        Some(session_id) => {
+            ensure_session_exists(backend.pool(), &session_id).await?;
+            session_id
+        }
+        None => create_run_session(backend.as_ref(), &args).await?,
+    };
+    eprintln!("[workclaw] 会话: {session_id}");
+
+    let cancel_flag = backend.cancel_flag();
+    cancel_flag.store(false, std::sync::atomic::Ordering::SeqCst);
+    let ctrl_c = spawn_ctrl_c_handler(cancel_flag);
+    let interaction = SessionInteraction::attach(
+        Arc::clone(&backend),
+        InteractionOptions {
+            session_id: session_id.clone(),
+            approval: args.approval,
+            answers: args.answers.clone(),
+            stream_output: !args.json,
+        },
+    );
+
+    let execution = backend
+        .send_message(&session_id, prompt, args.max_iterations)
+        .await;
+    ctrl_c.abort();
+    let streamed = interaction.streamed();
+    let unanswered_question = interaction.unanswered_question();
+    interaction.finish();
+    let execution = match unanswered_question {
+        Some(question) => Err(format!(
+            "非交互模式下无法回答提问「{question}」，请使用 --answer 预先提供回答"
+        )),
+        None => execution,
+    };
+
+    let messages = get_messages_with_pool(backend.pool(), &session_id).await?;
+    let final_output = extract_final_output(&messages);
+    if args.json {
+        let report = json!({
+            "session_id": session_id,
+            "final_output": final_output,
+            "error": execution.as_ref().err(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        );
+    } else if !streamed && !final_output.is_empty() {
+        println!("{final_output}");
+    }
+    execution
+}
+
+async fn create_run_session(backend: &dyn CliBackend, args: &RunArgs) -> Result<String, String> {
+    let employee = match args.employee.as_deref() {
+        Some(query) => Some(find_employee(backend.pool(), query).await?),
+        None => None,
+    };
+    let skill_id = args
+        .skill
+        .clone()
+        .or_else(|| {
+            employee
+                .as_ref()
+                .map(|employee| employee.primary_skill_id.trim().to_string())
+                .filter(|skill_id| !skill_id.is_empty())
+        })
+        .unwrap_or_else(|| DEFAULT_SKILL_ID.to_string());
+    let model_id = match args.model.clone() {
+        Some(model_id) => model_id,
+        None => resolve_default_usable_model_id_with_pool(backend.pool())
+            .await?
+            .ok_or_else(|| {
+                "没有可用的模型配置，请先使用 `workclaw models add` 添加模型".to_string()
+            })?,
+    };
+    let work_dir = match args.work_dir.clone() {
+        Some(dir) => Some(dir),
+        None => employee
+            .as_ref()
+            .map(|employee| employee.default_work_dir.trim())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok()),
+    };
+    let session_mode = if employee.is_some() {
+        "employee_direct"
+    } else {
+        "general"
+    };
+
+    backend
+        .create_session(CliSessionRequest {
+            skill_id,
+            model_id,
+            work_dir: work_dir.map(|dir| dir.to_string_lossy().to_string()),
+            employee_id: employee.map(|employee| employee.employee_id),
+            permission_mode: args.permission_mode.clone(),
+            session_mode: Some(session_mode.to_string()),
+        })
+        .await
+}
+
+/// 按员工记录 ID、员工编号或名称查找员工
+async fn find_employee(pool: &sqlx::SqlitePool, query: &str) -> Result<AgentEmployee, String> {
+    let employees = list_agent_employees_with_pool(pool).await?;
+    let query = query.trim();
+    employees
+        .into_iter()
+        .find(|employee| {
+            employee.id == query
+                || employee.employee_id.eq_ignore_ascii_case(query)
+                || employee.name == query
+        })
+        .ok_or_else(|| format!("未找到员工: {query}"))
+}
+
+async fn ensure_session_exists(pool: &sqlx::SqlitePool, session_id: &str) -> Result<(), String> {
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    exists
+        .map(|_| ())
+        .ok_or_else(|| format!("会话不存在: {session_id}"))
+}
+
+fn read_prompt_from_stdin() -> Result<String, String> {
+    let mut prompt = String::new();
+    std::io::stdin()
+        .read_to_string(&mut prompt)
+        .map_err(|e| format!("读取标准输入失败: {e}"))?;
+    Ok(prompt)
+}
+
+/// 只读取第一行，兼容 `echo $KEY | workclaw models add ...` 与交互式粘贴
+fn read_api_key_from_stdin() -> Result<String, String> {
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| format!("读取标准输入失败: {e}"))?;
+    Ok(line.trim().to_string())
+}
+
+fn extract_final_output(messages: &[Value]) -> String {
+    messages
+        .iter()
+        .rev()
+        .find_map(|message| {
+            if message.get("role").and_then(Value::as_str) != Some("assistant") {
+                return None;
+            }
+            message
+                .get("content")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_default()
+}
+
+pub async fn list_sessions(
+    backend: &dyn CliBackend,
+    limit: usize,
+    json: bool,
+) -> Result<(), String> {
+    let mut sessions =
+        list_sessions_with_pool(backend.pool(), permission_mode_label_for_display).await?;
+    sessions.truncate(limit);
+    if json {
+        return print_json(&sessions);
+    }
+    for session in &sessions {
+        let title = Some(session_field(session, "display_title"))
+            .filter(|title| !title.is_empty())
+            .unwrap_or(session_field(session, "title"));
+        let owner = Some(session_field(session, "employee_name"))
+            .filter(|name| !name.is_empty())
+            .unwrap_or(session_field(session, "skill_id"));
+        println!(
+            "{}\t{}\t{}\t{}",
+            session_field(session, "id"),
+            session_field(session, "created_at"),
+            owner,
+            title
+        );
+    }
+    Ok(())
+}
+
+fn session_field<'a>(session: &'a Value, key: &str) -> &'a str {
+    session.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+pub async fn export_session(
+    backend: &dyn CliBackend,
+    session_id: &str,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    ensure_session_exists(backend.pool(), session_id).await?;
+    let content = match format {
+        ExportFormat::Markdown => {
+            export_session_markdown_with_pool(backend.pool(), session_id, Some(backend.journal()))
+                .await?
+        }
+        ExportFormat::Json => {
+            let messages = get_messages_with_pool(backend.pool(), session_id).await?;
+            serde_json::to_string_pretty(&json!({
+                "session_id": session_id,
+                "messages": messages,
+            }))
+            .map_err(|e| e.to_string())?
+        }
+    };
+    match output {
+        Some(path) => {
+            write_export_file_to_path(&path.to_string_lossy(), &content)?;
+            eprintln!("[workclaw] 已导出到 {}", path.display());
+        }
+        None => println!("{content}"),
+    }
+    Ok(())
+}
+
+pub async fn list_installed_skills(backend: &dyn CliBackend, json: bool) -> Result<(), String> {
+    let skills = backend.list_skills().await?;
+    if json {
+        return print_json(&skills);
+    }
+    for skill in &skills {
+        println!(
+            "{}\t{}\t{}\t{}",
+            skill.manifest.id, skill.manifest.version, skill.source_type, skill.manifest.name
+        );
+    }
+    Ok(())
+}
+
+pub async fn install_skillpack(
+    backend: &dyn CliBackend,
+    pack_path: &str,
+    username: &str,
+    allow_untrusted: bool,
+) -> Result<(), String> {
+    if allow_untrusted {
+        eprintln!("[workclaw] 已允许安装未签名或发布者不受信任的技能包");
+    }
+    let manifest = backend
+        .install_skill(pack_path.to_string(), username.to_string(), allow_untrusted)
+        .await
+        .map_err(describe_untrusted_install_error)?;
+    println!(
+        "已安装技能: {} ({} {})",
+        manifest.name, manifest.id, manifest.version
+    );
+    Ok(())
+}
+
+/// 把桌面端使用的信任错误码换成命令行提示
+fn describe_untrusted_install_error(error: String) -> String {
+    let Some(reason) = error.strip_prefix(UNTRUSTED_SKILLPACK_ERROR_PREFIX) else {
+        return error;
+    };
+    let detail = match reason.strip_prefix("untrusted:") {
+        Some(key_id) => format!("技能包签名者 {key_id} 不在信任列表中"),
+        None => "技能包未签名".to_string(),
+    };
+    format!("{detail}，确认来源可信后可使用 --allow-untrusted 安装")
+}
+
+pub async fn import_skill_dir(backend: &dyn CliBackend, dir_path: &str) -> Result<(), String> {
+    let result = import_local_skills_to_pool(dir_path.to_string(), backend.pool(), &[]).await?;
+    for item in &result.installed {
+        println!("已导入技能: {} ({})", item.manifest.name, item.manifest.id);
+    }
+    for item in &result.failed {
+        eprintln!(
+            "导入失败: {} ({}): {}",
+            item.name_hint, item.dir_path, item.error
+        );
+    }
+    if !result.missing_mcp.is_empty() {
+        eprintln!("缺少 MCP 服务器: {}", result.missing_mcp.join(", "));
+    }
+    if result.installed.is_empty() {
+        return Err("没有导入任何技能".to_string());
+    }
+    Ok(())
+}
+
+pub async fn list_models(backend: &dyn CliBackend, json: bool) -> Result<(), String> {
+    let models = backend.list_models().await?;
+    if json {
+        return print_json(&models);
+    }
+    for model in &models {
+        println!(
+            "{}{}\t{}\t{}\t{}\t{}",
+            if model.is_default { "* " } else { "  " },
+            model.id,
+            model.api_format,
+            model.model_name,
+            model.base_url,
+            model.name
+        );
+    }
+    Ok(())
+}
+
+pub async fn add_model(backend: &dyn CliBackend, args: ModelAddArgs) -> Result<(), String> {
+    let api_key = match &args.api_key_env {
+        Some(env_name) => {
+            std::env::var(env_name).map_err(|_| format!("环境变量 {env_name} 未设置"))?
+        }
+        None if args.api_key_stdin => read_api_key_from_stdin()?,
+        None => return Err("缺少 API Key".to_string()),
+    };
+    if api_key.trim().is_empty() {
+        return Err("API Key 为空".to_string());
+    }
+    let model_id = save_model_config_with_pool(
+        backend.pool(),
+        ModelConfig {
+            id: String::new(),
+            name: args.name,
+            api_format: args.api_format,
+            base_url: args.base_url,
+            model_name: args.model_name,
+            is_default: false,
+            supports_vision: args.supports_vision,
+        },
+        api_key,
+    )
+    .await?;
+    if args.is_default {
+        set_default_model_with_pool(backend.pool(), &model_id).await?;
+    }
+    println!("{model_id}");
+    Ok(())
+}
+
+pub async fn set_default_model(backend: &dyn CliBackend, model_id: &str) -> Result<(), String> {
+    set_default_model_with_pool(backend.pool(), model_id).await?;
+    eprintln!("[workclaw] 默认模型已设置为 {model_id}");
+    Ok(())
+}
+
+pub async fn remove_model(backend: &dyn CliBackend, model_id: &str) -> Result<(), String> {
+    delete_model_config_with_pool(backend.pool(), model_id).await?;
+    eprintln!("[workclaw] 已删除模型配置 {model_id}");
+    Ok(())
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
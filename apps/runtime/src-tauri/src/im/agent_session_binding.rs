@@ -36,7 +36,7 @@ pub struct AgentConversationBindingUpsert<'a> {
     pub updated_at: &'a str,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
 pub struct ChannelDeliveryRoute {
     pub session_key: String,
     pub channel: String,
@@ -193,6 +193,46 @@ pub async fn find_channel_delivery_route(
     }))
 }
 
+/// 列出已知的渠道投递路由，按最近使用时间倒序（供定时任务等选择结果推送目标）
+pub async fn list_channel_delivery_routes(
+    pool: &SqlitePool,
+) -> Result<Vec<ChannelDeliveryRoute>, String> {
+    if !table_exists(pool, "channel_delivery_routes").await? {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query_as::<_, (String, String, String, String, String, String)>(
+        "SELECT session_key,
+                channel,
+                account_id,
+                conversation_id,
+                reply_target,
+                updated_at
+         FROM channel_delivery_routes
+         WHERE TRIM(reply_target) <> ''
+         ORDER BY updated_at DESC, session_key ASC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(session_key, channel, account_id, conversation_id, reply_target, updated_at)| {
+                ChannelDeliveryRoute {
+                    session_key,
+                    channel,
+                    account_id,
+                    conversation_id,
+                    reply_target,
+                    updated_at,
+                }
+            },
+        )
+        .collect())
+}
+
 pub async fn upsert_channel_delivery_route(
     pool: &SqlitePool,
     input: &ChannelDeliveryRouteUpsert<'_>,
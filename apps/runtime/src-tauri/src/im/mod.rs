@@ -27,7 +27,7 @@ pub use agent_session_runtime::{
 pub use conversation_binding_store::{
     find_agent_conversation_binding, find_agent_conversation_binding_for_candidates,
     find_channel_delivery_route, find_channel_delivery_route_by_session_id,
-    upsert_agent_conversation_binding, upsert_channel_delivery_route,
+    list_channel_delivery_routes, upsert_agent_conversation_binding, upsert_channel_delivery_route,
 };
 pub use conversation_id::{build_conversation_id, build_parent_conversation_candidates};
 pub use conversation_surface::{ImConversationScope, ImConversationSurface, ImPeerKind};
@@ -1,4 +1,8 @@
-mod adapters;
+// 让与集成测试共用的测试辅助代码在 lib 单元测试中也能以 runtime_lib:: 引用本 crate
+#[cfg(test)]
+extern crate self as runtime_lib;
+
+pub mod adapters;
 pub mod agent;
 pub(crate) mod agent_catalog;
 pub(crate) mod agent_core;
@@ -10,19 +14,32 @@ pub mod commands;
 mod db;
 mod diagnostics;
 pub(crate) mod employee_runtime_adapter;
+#[cfg(feature = "headless-cli")]
+pub mod headless_cli;
 pub mod im;
+pub mod mcp_server;
+pub mod model_catalog;
 mod model_errors;
-mod model_transport;
+pub mod model_reasoning;
+pub mod model_transport;
+pub mod model_usage;
 pub(crate) mod profile_runtime;
 pub mod providers;
+pub mod route_health;
+pub mod run_budgets;
 mod runtime_bootstrap;
 mod runtime_environment;
 mod runtime_paths;
 mod runtime_root_migration;
+pub mod scheduled_jobs;
+pub mod secret_store;
 pub mod session_journal;
+pub mod shell_sandbox;
 pub mod sidecar;
 pub mod team_templates;
+pub mod web_fetch_policy;
 mod windows_process;
+pub mod workspace_checkpoints;
 
 use agent::runtime::{
     RunRegistry, RunRegistryState, RuntimeObservability, RuntimeObservabilityState,
@@ -110,6 +127,7 @@ fn initialize_runtime_state(
     runtime_paths: &runtime_paths::RuntimePaths,
 ) -> ManagedRuntimeHandles {
     app.manage(DbState(pool.clone()));
+    approval_rules::register_approval_rule_pool(pool.clone());
 
     let registry = Arc::new(ToolRegistry::with_standard_tools());
     let agent_executor = Arc::new(AgentExecutor::new(Arc::clone(&registry)));
@@ -130,7 +148,10 @@ fn initialize_runtime_state(
     let cancel_flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
     app.manage(CancelFlagState(cancel_flag));
 
-    let search_cache = Arc::new(SearchCache::new(std::time::Duration::from_secs(900), 100));
+    let search_cache = Arc::new(
+        SearchCache::new(std::time::Duration::from_secs(900), 100)
+            .with_disk_dir(runtime_paths.cache_dir.join("web")),
+    );
     app.manage(SearchCacheState(search_cache));
 
     let runtime_observability = Arc::new(RuntimeObservability::default());
@@ -162,9 +183,11 @@ fn initialize_runtime_state(
     app.manage(ImChannelHostRuntimeState::default());
     app.manage(OpenClawPluginFeishuRuntimeState::default());
     app.manage(commands::openclaw_plugins::OpenClawLarkInstallerSessionState::default());
+    app.manage(commands::mcp_server::McpServerRuntimeState::default());
     app.manage(
         commands::employee_agents::curator_scheduler::EmployeeCuratorSchedulerState::default(),
     );
+    app.manage(scheduled_jobs::ScheduledJobSchedulerState::default());
 
     ManagedRuntimeHandles {
         registry,
@@ -191,6 +214,19 @@ fn apply_startup_preferences(app: &mut tauri::App, pool: &sqlx::SqlitePool) {
     }
 }
 
+/// 加载持久化在数据库中的进程级配置（模型目录覆盖、shell 沙箱、web_fetch 策略）
+async fn reload_persisted_runtime_settings(pool: &sqlx::SqlitePool) {
+    if let Err(error) = model_catalog::reload_model_catalog_with_pool(pool).await {
+        eprintln!("[model-catalog] 加载模型目录覆盖失败，使用内置目录: {error}");
+    }
+    if let Err(error) = shell_sandbox::reload_shell_sandbox_profile_with_pool(pool).await {
+        eprintln!("[shell-sandbox] 加载 shell 沙箱设置失败，沙箱保持关闭: {error}");
+    }
+    if let Err(error) = web_fetch_policy::reload_web_fetch_policy_with_pool(pool).await {
+        eprintln!("[web-fetch] 加载 web_fetch 策略失败，使用默认策略: {error}");
+    }
+}
+
 async fn bootstrap_sidecar(sidecar_manager: Arc<SidecarManager>) {
     for _ in 0..20 {
         if sidecar_manager.health_check().await.is_ok() {
@@ -376,12 +412,14 @@ pub fn run() {
             let handles = initialize_runtime_state(app, pool.clone(), &runtime_environment.paths);
             let journal_store = app.state::<SessionJournalStateHandle>().0.clone();
             apply_startup_preferences(app, &pool);
+            tauri::async_runtime::block_on(reload_persisted_runtime_settings(&pool));
             spawn_approval_recovery_bootstrap(
                 pool.clone(),
                 journal_store,
                 Arc::clone(&handles.registry),
             );
             restore_saved_mcp_servers(pool.clone(), Arc::clone(&handles.registry));
+            commands::mcp_server::restore_mcp_server(app.handle().clone(), pool.clone());
             let curator_scheduler_state = app
                 .state::<
                     commands::employee_agents::curator_scheduler::EmployeeCuratorSchedulerState,
@@ -393,6 +431,13 @@ pub fn run() {
                 curator_scheduler_state,
                 runtime_environment.paths.root.clone(),
             );
+            scheduled_jobs::spawn_scheduled_job_scheduler(
+                app.handle().clone(),
+                pool.clone(),
+                app.state::<scheduled_jobs::ScheduledJobSchedulerState>()
+                    .inner()
+                    .clone(),
+            );
             tauri::async_runtime::spawn({
                 let pool = pool.clone();
                 let runtime_state = app
@@ -438,6 +483,10 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::skills::install_skill,
+            commands::skills::preview_skillpack,
+            commands::skills::list_trusted_publishers,
+            commands::skills::add_trusted_publisher,
+            commands::skills::remove_trusted_publisher,
             commands::skills::import_local_skill,
             commands::skills::install_industry_bundle,
             commands::skills::check_industry_bundle_update,
@@ -484,6 +533,8 @@ pub fn run() {
             commands::models::test_all_provider_health,
             commands::models::list_provider_recommended_models,
             commands::models::list_provider_models,
+            commands::models::discover_local_provider_models,
+            commands::models::pull_local_provider_model,
             commands::models::list_capability_route_templates,
             commands::models::apply_capability_route_template,
             commands::models::list_recent_route_attempt_logs,
@@ -546,6 +597,45 @@ pub fn run() {
             commands::session_runs::export_session_run_trace,
             commands::approvals::list_pending_approvals,
             commands::approvals::resolve_approval,
+            commands::approvals::list_approval_rules,
+            commands::approvals::save_approval_rule,
+            commands::approvals::set_approval_rule_enabled,
+            commands::approvals::delete_approval_rule,
+            commands::approvals::explain_approval_rule_match,
+            commands::model_usage::get_model_usage_summary,
+            commands::model_usage::list_model_prices,
+            commands::model_usage::save_model_price,
+            commands::model_usage::delete_model_price,
+            commands::run_budgets::get_employee_run_budget,
+            commands::run_budgets::save_employee_run_budget,
+            commands::model_catalog::get_model_catalog,
+            commands::model_catalog::list_model_catalog_overrides,
+            commands::model_catalog::save_model_catalog_override,
+            commands::model_catalog::delete_model_catalog_override,
+            commands::model_reasoning::get_model_reasoning_settings,
+            commands::model_reasoning::save_model_reasoning_settings,
+            commands::model_reasoning::get_route_reasoning_settings,
+            commands::model_reasoning::save_route_reasoning_settings,
+            commands::route_health::list_route_health,
+            commands::route_health::get_route_load_balance_enabled,
+            commands::route_health::set_route_load_balance_enabled,
+            commands::shell_sandbox::get_shell_sandbox_profile,
+            commands::shell_sandbox::save_shell_sandbox_profile,
+            commands::workspace_checkpoints::list_workspace_checkpoints,
+            commands::workspace_checkpoints::get_workspace_checkpoint_diff,
+            commands::workspace_checkpoints::revert_workspace_run,
+            commands::workspace_checkpoints::revert_workspace_turn,
+            commands::workspace_checkpoints::revert_workspace_file,
+            commands::scheduled_jobs::list_scheduled_jobs,
+            commands::scheduled_jobs::save_scheduled_job,
+            commands::scheduled_jobs::delete_scheduled_job,
+            commands::scheduled_jobs::set_scheduled_job_enabled,
+            commands::scheduled_jobs::run_scheduled_job_now,
+            commands::scheduled_jobs::list_scheduled_job_runs,
+            commands::scheduled_jobs::list_scheduled_job_delivery_routes,
+            commands::scheduled_jobs::preview_cron_schedule,
+            commands::web_fetch_policy::get_web_fetch_policy,
+            commands::web_fetch_policy::save_web_fetch_policy,
             commands::chat_control::answer_user_question,
             commands::chat_control::confirm_tool_execution,
             commands::chat_control::cancel_agent,
@@ -620,6 +710,9 @@ pub fn run() {
             commands::mcp::add_mcp_server,
             commands::mcp::list_mcp_servers,
             commands::mcp::remove_mcp_server,
+            commands::mcp_server::get_mcp_server_settings,
+            commands::mcp_server::save_mcp_server_settings,
+            commands::mcp_server::regenerate_mcp_server_token,
             commands::dialog::select_directory,
             commands::dialog::open_external_url,
             commands::workspace_files::list_workspace_files,
@@ -628,6 +721,10 @@ pub fn run() {
             commands::packaging::scan_workclaw_dirs,
             commands::packaging::update_skill_dir_tags,
             commands::packaging::pack_skill,
+            commands::packaging::generate_publisher_key,
+            commands::packaging::add_skillpack_recipient,
+            commands::packaging::revoke_skillpack_recipient,
+            commands::packaging::pack_skill_delta,
             commands::packaging::pack_industry_bundle,
             commands::packaging::read_industry_bundle_manifest,
             commands::packaging::unpack_industry_bundle,
@@ -2,5 +2,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if runtime_lib::mcp_server::is_stdio_bridge_invocation(std::env::args()) {
+        std::process::exit(runtime_lib::mcp_server::run_stdio_bridge_from_env());
+    }
     runtime_lib::run();
 }
@@ -0,0 +1,20 @@
+//! 把 WorkClaw 自身作为 MCP 服务端暴露给外部客户端（IDE、其他智能体）。
+//!
+//! 桌面端在本机回环地址上提供 Streamable HTTP 端点；`runtime --mcp-stdio` 则作为
+//! stdio 服务端把消息转发到该端点，供只支持 stdio 的客户端使用。
+
+mod app_backend;
+mod http;
+mod protocol;
+mod stdio_bridge;
+
+pub(crate) use app_backend::AppMcpServerBackend;
+pub use http::{McpHttpServer, MCP_HTTP_PATH};
+pub use protocol::{
+    ExposedEmployee, ExposedSkill, ExternalTurnOutcome, ExternalTurnRequest, ExternalTurnTarget,
+    McpServerBackend, McpServerDispatcher, MCP_SERVER_NAME, MCP_SERVER_PROTOCOL_VERSION,
+};
+pub use stdio_bridge::{
+    is_stdio_bridge_invocation, run_stdio_bridge, run_stdio_bridge_from_env, MCP_STDIO_FLAG,
+    MCP_TOKEN_ENV, MCP_URL_ENV,
+};
@@ -0,0 +1,234 @@
+use super::protocol::{
+    ExposedEmployee, ExposedSkill, ExternalTurnOutcome, ExternalTurnRequest, ExternalTurnTarget,
+    McpServerBackend,
+};
+use crate::agent::runtime::runtime_io::{
+    extract_assistant_text_content, insert_session_message_with_pool,
+    search_profile_session_index_with_pool,
+};
+use crate::agent::runtime::{SessionAdmissionGateState, SessionRuntime};
+use crate::agent::AgentExecutor;
+use crate::commands::chat::ToolConfirmState;
+use crate::commands::skills::DbState;
+use crate::session_journal::SessionJournalStateHandle;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// 外部调用方创建的会话固定使用标准权限模式，工具仍按 runtime_policy 分级并走桌面端审批。
+const EXTERNAL_SESSION_PERMISSION_MODE: &str = "standard";
+const FALLBACK_SKILL_ID: &str = "builtin-general";
+
+pub(crate) struct AppMcpServerBackend {
+    app: AppHandle,
+}
+
+impl AppMcpServerBackend {
+    pub(crate) fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    fn pool(&self) -> sqlx::SqlitePool {
+        self.app.state::<DbState>().0.clone()
+    }
+}
+
+#[async_trait]
+impl McpServerBackend for AppMcpServerBackend {
+    async fn list_skills(&self) -> Result<Vec<ExposedSkill>, String> {
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT id, manifest, COALESCE(source_type, 'encrypted')
+             FROM installed_skills
+             ORDER BY installed_at DESC",
+        )
+        .fetch_all(&self.pool())
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, manifest_json, source_type)| {
+                match serde_json::from_str::<skillpack_rs::SkillManifest>(&manifest_json) {
+                    Ok(manifest) => ExposedSkill {
+                        id,
+                        name: manifest.name,
+                        description: manifest.description,
+                        version: manifest.version,
+                        tags: manifest.tags,
+                        source_type,
+                    },
+                    Err(_) => ExposedSkill {
+                        name: id.clone(),
+                        id,
+                        description: String::new(),
+                        version: String::new(),
+                        tags: Vec::new(),
+                        source_type,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    async fn list_employees(&self) -> Result<Vec<ExposedEmployee>, String> {
+        let employees =
+            crate::commands::employee_agents::list_agent_employees_with_pool(&self.pool()).await?;
+        Ok(employees
+            .into_iter()
+            .map(|employee| ExposedEmployee {
+                employee_id: employee.employee_id,
+                name: employee.name,
+                role_id: employee.role_id,
+                persona: employee.persona,
+                primary_skill_id: employee.primary_skill_id,
+                skill_ids: employee.skill_ids,
+                enabled: employee.enabled,
+                is_default: employee.is_default,
+            })
+            .collect())
+    }
+
+    async fn search_sessions(
+        &self,
+        employee_id: &str,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<Value>, String> {
+        let pool = self.pool();
+        let profile =
+            crate::profile_runtime::resolve_profile_for_alias_with_pool(&pool, employee_id)
+                .await?
+                .ok_or_else(|| format!("员工不存在: {employee_id}"))?;
+        let rows = search_profile_session_index_with_pool(&pool, &profile.profile_id, query, limit)
+            .await?;
+        rows.into_iter()
+            .map(|row| serde_json::to_value(row).map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    async fn run_turn(
+        &self,
+        request: ExternalTurnRequest,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<ExternalTurnOutcome, String> {
+        let pool = self.pool();
+        let model_id = crate::commands::models::resolve_default_usable_model_id_with_pool(&pool)
+            .await?
+            .ok_or_else(|| "没有可用的模型配置，请先在 WorkClaw 中配置模型".to_string())?;
+
+        let (skill_id, work_dir, employee_id, session_mode) = match &request.target {
+            ExternalTurnTarget::Skill { skill_id } => {
+                let installed = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM installed_skills WHERE id = ?",
+                )
+                .bind(skill_id)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+                if installed == 0 {
+                    return Err(format!("技能不存在: {skill_id}"));
+                }
+                (skill_id.clone(), None, None, "general")
+            }
+            ExternalTurnTarget::Employee { employee_id } => {
+                let employee =
+                    crate::commands::employee_agents::list_agent_employees_with_pool(&pool)
+                        .await?
+                        .into_iter()
+                        .find(|employee| {
+                            employee.employee_id.eq_ignore_ascii_case(employee_id)
+                                || employee.id == *employee_id
+                        })
+                        .ok_or_else(|| format!("员工不存在: {employee_id}"))?;
+                if !employee.enabled {
+                    return Err(format!("员工已停用: {employee_id}"));
+                }
+                let skill_id = if employee.primary_skill_id.trim().is_empty() {
+                    FALLBACK_SKILL_ID.to_string()
+                } else {
+                    employee.primary_skill_id.clone()
+                };
+                let work_dir = Some(employee.default_work_dir.trim().to_string())
+                    .filter(|dir| !dir.is_empty());
+                (
+                    skill_id,
+                    work_dir,
+                    Some(employee.employee_id.clone()),
+                    "employee_direct",
+                )
+            }
+        };
+
+        let title = format!(
+            "MCP: {}",
+            request.prompt.chars().take(40).collect::<String>()
+        );
+        let session_id = crate::commands::chat::create_session_with_pool(
+            &pool,
+            skill_id,
+            model_id,
+            work_dir,
+            employee_id,
+            Some(title),
+            Some(EXTERNAL_SESSION_PERMISSION_MODE.to_string()),
+            Some(session_mode.to_string()),
+            None,
+        )
+        .await?;
+
+        let admission_gate = self
+            .app
+            .try_state::<SessionAdmissionGateState>()
+            .ok_or_else(|| "SessionAdmissionGateState unavailable".to_string())?;
+        let _admission_lease = admission_gate
+            .0
+            .try_acquire(&session_id)
+            .map_err(|conflict| conflict.to_string())?;
+
+        let parts = vec![json!({ "type": "text", "text": request.prompt })];
+        let parts_json = serde_json::to_string(&parts).map_err(|e| e.to_string())?;
+        let msg_id = insert_session_message_with_pool(
+            &pool,
+            &session_id,
+            "user",
+            &request.prompt,
+            Some(&parts_json),
+        )
+        .await?;
+
+        let agent_executor = self.app.state::<Arc<AgentExecutor>>().inner().clone();
+        let journal = self.app.state::<SessionJournalStateHandle>().0.clone();
+        let tool_confirm_responder = self.app.state::<ToolConfirmState>().0.clone();
+        SessionRuntime::run_send_message(
+            &self.app,
+            &agent_executor,
+            &pool,
+            journal.as_ref(),
+            &session_id,
+            &msg_id,
+            &request.prompt,
+            &parts,
+            None,
+            cancel_flag,
+            tool_confirm_responder,
+        )
+        .await?;
+
+        let content = sqlx::query_scalar::<_, String>(
+            "SELECT content FROM messages
+             WHERE session_id = ? AND role = 'assistant'
+             ORDER BY created_at DESC
+             LIMIT 1",
+        )
+        .bind(&session_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+        Ok(ExternalTurnOutcome {
+            session_id,
+            output: extract_assistant_text_content(&content),
+        })
+    }
+}
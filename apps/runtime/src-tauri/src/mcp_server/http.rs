@@ -0,0 +1,345 @@
+use super::protocol::McpServerDispatcher;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+pub const MCP_HTTP_PATH: &str = "/mcp";
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(30);
+const LOOPBACK_ORIGINS: &[&str] = &["http://127.0.0.1", "http://localhost", "http://[::1]"];
+
+/// 只监听回环地址的最小 HTTP/1.1 服务，按 MCP Streamable HTTP 的 JSON 响应模式处理 POST。
+///
+/// 每个连接只处理一个请求，不提供 GET 推送流。
+pub struct McpHttpServer {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl McpHttpServer {
+    /// `port` 为 0 时由系统分配端口。
+    pub async fn bind(
+        port: u16,
+        token: String,
+        dispatcher: Arc<McpServerDispatcher>,
+    ) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|err| format!("MCP 服务监听 127.0.0.1:{port} 失败: {err}"))?;
+        let local_addr = listener.local_addr().map_err(|err| err.to_string())?;
+        let token = Arc::new(token);
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        eprintln!("[mcp-server] accept failed: {err}");
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        continue;
+                    }
+                };
+                let token = Arc::clone(&token);
+                let dispatcher = Arc::clone(&dispatcher);
+                tokio::spawn(async move {
+                    if let Err(err) = serve_connection(stream, &token, &dispatcher).await {
+                        eprintln!("[mcp-server] connection failed: {err}");
+                    }
+                });
+            }
+        });
+        Ok(Self { local_addr, task })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}{}", self.local_addr, MCP_HTTP_PATH)
+    }
+
+    /// 停止监听并等待监听任务退出，保证端口在返回后可以重新绑定。
+    pub async fn shutdown(mut self) {
+        self.task.abort();
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for McpHttpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum HttpReadError {
+    Closed,
+    BadRequest(&'static str),
+    TooLarge,
+}
+
+async fn serve_connection(
+    mut stream: TcpStream,
+    token: &str,
+    dispatcher: &McpServerDispatcher,
+) -> std::io::Result<()> {
+    let request = match tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request(&mut stream)).await
+    {
+        Ok(Ok(request)) => request,
+        Ok(Err(HttpReadError::Closed)) | Err(_) => return Ok(()),
+        Ok(Err(HttpReadError::BadRequest(reason))) => {
+            return write_response(&mut stream, 400, "Bad Request", &[], reason.as_bytes()).await;
+        }
+        Ok(Err(HttpReadError::TooLarge)) => {
+            return write_response(&mut stream, 413, "Payload Too Large", &[], b"").await;
+        }
+    };
+
+    let path = request.path.split('?').next().unwrap_or_default();
+    if path != MCP_HTTP_PATH {
+        return write_response(&mut stream, 404, "Not Found", &[], b"").await;
+    }
+    if !is_allowed_origin(request.header("origin")) {
+        return write_response(&mut stream, 403, "Forbidden", &[], b"origin not allowed").await;
+    }
+    if !is_authorized(request.header("authorization"), token) {
+        return write_response(
+            &mut stream,
+            401,
+            "Unauthorized",
+            &[("WWW-Authenticate", "Bearer")],
+            b"",
+        )
+        .await;
+    }
+    if request.method != "POST" {
+        return write_response(
+            &mut stream,
+            405,
+            "Method Not Allowed",
+            &[("Allow", "POST")],
+            b"",
+        )
+        .await;
+    }
+
+    match dispatcher.handle_bytes(&request.body).await {
+        Some(response) => {
+            let body = serde_json::to_vec(&response).unwrap_or_default();
+            write_response(
+                &mut stream,
+                200,
+                "OK",
+                &[("Content-Type", "application/json")],
+                &body,
+            )
+            .await
+        }
+        None => write_response(&mut stream, 202, "Accepted", &[], b"").await,
+    }
+}
+
+async fn read_request<R: AsyncRead + Unpin>(reader: &mut R) -> Result<HttpRequest, HttpReadError> {
+    let mut buffer = Vec::with_capacity(1024);
+    let header_end = loop {
+        if let Some(pos) = find_header_end(&buffer) {
+            break pos;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err(HttpReadError::TooLarge);
+        }
+        let mut chunk = [0u8; 4096];
+        let read = reader
+            .read(&mut chunk)
+            .await
+            .map_err(|_| HttpReadError::Closed)?;
+        if read == 0 {
+            return Err(if buffer.is_empty() {
+                HttpReadError::Closed
+            } else {
+                HttpReadError::BadRequest("incomplete request head")
+            });
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buffer[..header_end])
+        .map_err(|_| HttpReadError::BadRequest("request head is not utf-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line
+        .next()
+        .ok_or(HttpReadError::BadRequest("missing method"))?
+        .to_string();
+    let path = request_line
+        .next()
+        .ok_or(HttpReadError::BadRequest("missing path"))?
+        .to_string();
+    let headers = lines
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_once(':')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .ok_or(HttpReadError::BadRequest("malformed header"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut request = HttpRequest {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    if request.header("transfer-encoding").is_some() {
+        return Err(HttpReadError::BadRequest(
+            "chunked bodies are not supported",
+        ));
+    }
+    let content_length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| HttpReadError::BadRequest("invalid content-length"))?,
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Err(HttpReadError::TooLarge);
+    }
+
+    let mut body = buffer.split_off(header_end + 4);
+    if body.len() < content_length {
+        let mut rest = vec![0u8; content_length - body.len()];
+        reader
+            .read_exact(&mut rest)
+            .await
+            .map_err(|_| HttpReadError::BadRequest("incomplete request body"))?;
+        body.extend_from_slice(&rest);
+    }
+    body.truncate(content_length);
+    request.body = body;
+    Ok(request)
+}
+
+fn find_header_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// 浏览器发起的请求必须来自本机页面，防止 DNS rebinding 访问本地端口。
+fn is_allowed_origin(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    LOOPBACK_ORIGINS.iter().any(|allowed| {
+        origin
+            .strip_prefix(allowed)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+    })
+}
+
+fn is_authorized(header: Option<&str>, token: &str) -> bool {
+    let Some(provided) = header.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    constant_time_eq(provided.trim().as_bytes(), token.as_bytes())
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() || right.is_empty() {
+        return false;
+    }
+    left.iter()
+        .zip(right)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+async fn write_response<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    status: u16,
+    reason: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_request_parses_head_and_body() {
+        let raw = b"POST /mcp HTTP/1.1\r\nHost: 127.0.0.1\r\nAuthorization: Bearer abc\r\nContent-Length: 7\r\n\r\n{\"a\":1}";
+        let mut reader: &[u8] = raw;
+        let request = read_request(&mut reader).await.expect("parse request");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/mcp");
+        assert_eq!(request.header("authorization"), Some("Bearer abc"));
+        assert_eq!(request.body, b"{\"a\":1}");
+    }
+
+    #[tokio::test]
+    async fn read_request_rejects_oversized_and_chunked_bodies() {
+        let raw = format!(
+            "POST /mcp HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        let mut reader: &[u8] = raw.as_bytes();
+        assert_eq!(
+            read_request(&mut reader).await.unwrap_err(),
+            HttpReadError::TooLarge
+        );
+
+        let mut reader: &[u8] = b"POST /mcp HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert!(matches!(
+            read_request(&mut reader).await.unwrap_err(),
+            HttpReadError::BadRequest(_)
+        ));
+    }
+
+    #[test]
+    fn origin_and_token_checks_only_accept_loopback_and_exact_token() {
+        assert!(is_allowed_origin(None));
+        assert!(is_allowed_origin(Some("http://localhost:5173")));
+        assert!(is_allowed_origin(Some("http://127.0.0.1")));
+        assert!(!is_allowed_origin(Some("http://localhost.evil.com")));
+        assert!(!is_allowed_origin(Some("https://example.com")));
+
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer secre"), "secret"));
+        assert!(!is_authorized(Some("secret"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+        assert!(!is_authorized(Some("Bearer "), ""));
+    }
+}
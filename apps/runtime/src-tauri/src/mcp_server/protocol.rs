@@ -0,0 +1,457 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub const MCP_SERVER_NAME: &str = crate::branding_generated::BRAND_KEY;
+pub const MCP_SERVER_PROTOCOL_VERSION: &str = "2025-03-26";
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+const DEFAULT_SEARCH_LIMIT: i64 = 10;
+const MAX_SEARCH_LIMIT: i64 = 50;
+const MAX_PROMPT_CHARS: usize = 20_000;
+
+const JSONRPC_PARSE_ERROR: i64 = -32700;
+const JSONRPC_INVALID_REQUEST: i64 = -32600;
+const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+const JSONRPC_INVALID_PARAMS: i64 = -32602;
+const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+
+/// 对外暴露的技能摘要（只包含可公开的清单字段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExposedSkill {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub tags: Vec<String>,
+    pub source_type: String,
+}
+
+/// 对外暴露的智能体员工摘要（不包含渠道凭据等敏感字段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExposedEmployee {
+    pub employee_id: String,
+    pub name: String,
+    pub role_id: String,
+    pub persona: String,
+    pub primary_skill_id: String,
+    pub skill_ids: Vec<String>,
+    pub enabled: bool,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalTurnTarget {
+    Skill { skill_id: String },
+    Employee { employee_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalTurnRequest {
+    pub target: ExternalTurnTarget,
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalTurnOutcome {
+    pub session_id: String,
+    pub output: String,
+}
+
+/// MCP 服务端背后的数据与执行能力。
+///
+/// 桌面端实现会把运行请求交给 `SessionRuntime`，因此外部调用方与桌面会话共用
+/// 同一套权限模式、工具策略和审批总线，不能绕过 runtime_policy。
+#[async_trait]
+pub trait McpServerBackend: Send + Sync {
+    async fn list_skills(&self) -> Result<Vec<ExposedSkill>, String>;
+    async fn list_employees(&self) -> Result<Vec<ExposedEmployee>, String>;
+    async fn search_sessions(
+        &self,
+        employee_id: &str,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<Value>, String>;
+    async fn run_turn(
+        &self,
+        request: ExternalTurnRequest,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<ExternalTurnOutcome, String>;
+}
+
+/// MCP JSON-RPC 分发器，跟踪进行中的运行以响应 `notifications/cancelled`。
+pub struct McpServerDispatcher {
+    backend: Arc<dyn McpServerBackend>,
+    in_flight: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl McpServerDispatcher {
+    pub fn new(backend: Arc<dyn McpServerBackend>) -> Self {
+        Self {
+            backend,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 处理单条消息或批量消息；纯通知没有响应时返回 `None`。
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(items) => {
+                if items.is_empty() {
+                    return Some(error_response(
+                        Value::Null,
+                        JSONRPC_INVALID_REQUEST,
+                        "empty batch",
+                    ));
+                }
+                let mut responses = Vec::new();
+                for item in items {
+                    if let Some(response) = self.handle_single(item).await {
+                        responses.push(response);
+                    }
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            other => self.handle_single(other).await,
+        }
+    }
+
+    pub async fn handle_bytes(&self, body: &[u8]) -> Option<Value> {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(message) => self.handle(message).await,
+            Err(err) => Some(error_response(
+                Value::Null,
+                JSONRPC_PARSE_ERROR,
+                &format!("parse error: {err}"),
+            )),
+        }
+    }
+
+    async fn handle_single(&self, message: Value) -> Option<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // 客户端发来的 response（例如对 ping 的回复）无需处理
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            let id = message.get("id").cloned().unwrap_or(Value::Null);
+            return Some(error_response(
+                id,
+                JSONRPC_INVALID_REQUEST,
+                "missing method",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            self.handle_notification(method, &params);
+            return None;
+        };
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&id, &params).await,
+            "resources/list" => self.list_resources().await,
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": resource_templates() })),
+            "resources/read" => self.read_resource(&params).await,
+            _ => Err((
+                JSONRPC_METHOD_NOT_FOUND,
+                format!("method not found: {method}"),
+            )),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn handle_notification(&self, method: &str, params: &Value) {
+        if method != "notifications/cancelled" {
+            return;
+        }
+        let Some(request_id) = params.get("requestId") else {
+            return;
+        };
+        let key = request_key(request_id);
+        if let Some(flag) = self.in_flight.lock().expect("mcp in-flight lock").get(&key) {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    async fn call_tool(&self, id: &Value, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (JSONRPC_INVALID_PARAMS, "missing tool name".to_string()))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        let outcome = match name {
+            "workclaw_list_skills" => self
+                .backend
+                .list_skills()
+                .await
+                .and_then(|skills| to_pretty_json(&skills)),
+            "workclaw_list_employees" => self
+                .backend
+                .list_employees()
+                .await
+                .and_then(|employees| to_pretty_json(&employees)),
+            "workclaw_search_sessions" => {
+                let employee_id = required_str(&arguments, "employee_id")?;
+                let query = required_str(&arguments, "query")?;
+                let limit = arguments
+                    .get("limit")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                    .clamp(1, MAX_SEARCH_LIMIT);
+                self.backend
+                    .search_sessions(&employee_id, &query, limit)
+                    .await
+                    .and_then(|rows| to_pretty_json(&rows))
+            }
+            "workclaw_run_skill" => {
+                let request = ExternalTurnRequest {
+                    target: ExternalTurnTarget::Skill {
+                        skill_id: required_str(&arguments, "skill_id")?,
+                    },
+                    prompt: required_prompt(&arguments)?,
+                };
+                self.run_turn(id, request).await
+            }
+            "workclaw_ask_employee" => {
+                let request = ExternalTurnRequest {
+                    target: ExternalTurnTarget::Employee {
+                        employee_id: required_str(&arguments, "employee_id")?,
+                    },
+                    prompt: required_prompt(&arguments)?,
+                };
+                self.run_turn(id, request).await
+            }
+            _ => {
+                return Err((JSONRPC_INVALID_PARAMS, format!("unknown tool: {name}")));
+            }
+        };
+        Ok(match outcome {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+            Err(error) => {
+                json!({ "content": [{ "type": "text", "text": error }], "isError": true })
+            }
+        })
+    }
+
+    async fn run_turn(&self, id: &Value, request: ExternalTurnRequest) -> Result<String, String> {
+        let key = request_key(id);
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        self.in_flight
+            .lock()
+            .expect("mcp in-flight lock")
+            .insert(key.clone(), Arc::clone(&cancel_flag));
+        let outcome = self.backend.run_turn(request, cancel_flag).await;
+        self.in_flight
+            .lock()
+            .expect("mcp in-flight lock")
+            .remove(&key);
+        let outcome = outcome?;
+        Ok(format!(
+            "{}\n\n(session_id: {})",
+            outcome.output.trim(),
+            outcome.session_id
+        ))
+    }
+
+    async fn list_resources(&self) -> Result<Value, (i64, String)> {
+        let skills = self
+            .backend
+            .list_skills()
+            .await
+            .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+        let mut resources = vec![
+            json!({
+                "uri": "workclaw://skills",
+                "name": "Installed skills",
+                "mimeType": "application/json",
+            }),
+            json!({
+                "uri": "workclaw://employees",
+                "name": "Agent employees",
+                "mimeType": "application/json",
+            }),
+        ];
+        resources.extend(skills.iter().map(|skill| {
+            json!({
+                "uri": format!("workclaw://skills/{}", skill.id),
+                "name": skill.name,
+                "description": skill.description,
+                "mimeType": "application/json",
+            })
+        }));
+        Ok(json!({ "resources": resources }))
+    }
+
+    async fn read_resource(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = required_str(params, "uri")?;
+        let text: Result<String, String> = if uri == "workclaw://skills" {
+            let skills = self
+                .backend
+                .list_skills()
+                .await
+                .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+            to_pretty_json(&skills)
+        } else if uri == "workclaw://employees" {
+            let employees = self
+                .backend
+                .list_employees()
+                .await
+                .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+            to_pretty_json(&employees)
+        } else if let Some(skill_id) = uri.strip_prefix("workclaw://skills/") {
+            let skills = self
+                .backend
+                .list_skills()
+                .await
+                .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+            let skill = skills
+                .into_iter()
+                .find(|skill| skill.id == skill_id)
+                .ok_or_else(|| (JSONRPC_INVALID_PARAMS, format!("resource not found: {uri}")))?;
+            to_pretty_json(&skill)
+        } else {
+            return Err((JSONRPC_INVALID_PARAMS, format!("resource not found: {uri}")));
+        };
+        let text = text.map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+        Ok(json!({
+            "contents": [{ "uri": uri, "mimeType": "application/json", "text": text }]
+        }))
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let protocol_version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) {
+        requested
+    } else {
+        MCP_SERVER_PROTOCOL_VERSION
+    };
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "subscribe": false, "listChanged": false },
+        },
+        "serverInfo": {
+            "name": MCP_SERVER_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "WorkClaw 本地智能体。运行技能或询问员工时，工具审批会在 WorkClaw 桌面端弹出，需要用户在桌面端确认。",
+    })
+}
+
+fn tool_definitions() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "workclaw_list_skills",
+            "description": "List the skills installed in WorkClaw.",
+            "inputSchema": { "type": "object", "properties": {} },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "workclaw_list_employees",
+            "description": "List WorkClaw agent employees (without channel credentials).",
+            "inputSchema": { "type": "object", "properties": {} },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "workclaw_search_sessions",
+            "description": "Full-text search over an employee's past WorkClaw sessions.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "employee_id": { "type": "string", "description": "Employee id or alias" },
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT },
+                },
+                "required": ["employee_id", "query"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "workclaw_run_skill",
+            "description": "Run an installed WorkClaw skill in a new session in the default workspace. Tool approvals are confirmed in the WorkClaw desktop app.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "skill_id": { "type": "string" },
+                    "prompt": { "type": "string" },
+                },
+                "required": ["skill_id", "prompt"],
+            },
+            "annotations": { "readOnlyHint": false, "openWorldHint": true },
+        }),
+        json!({
+            "name": "workclaw_ask_employee",
+            "description": "Send a task to a WorkClaw agent employee in a new session in the employee's workspace. Tool approvals are confirmed in the WorkClaw desktop app.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "employee_id": { "type": "string" },
+                    "prompt": { "type": "string" },
+                },
+                "required": ["employee_id", "prompt"],
+            },
+            "annotations": { "readOnlyHint": false, "openWorldHint": true },
+        }),
+    ]
+}
+
+fn resource_templates() -> Vec<Value> {
+    vec![json!({
+        "uriTemplate": "workclaw://skills/{skill_id}",
+        "name": "Installed skill",
+        "mimeType": "application/json",
+    })]
+}
+
+fn required_str(value: &Value, key: &str) -> Result<String, (i64, String)> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| (JSONRPC_INVALID_PARAMS, format!("missing argument: {key}")))
+}
+
+fn required_prompt(arguments: &Value) -> Result<String, (i64, String)> {
+    let prompt = required_str(arguments, "prompt")?;
+    if prompt.chars().count() > MAX_PROMPT_CHARS {
+        return Err((
+            JSONRPC_INVALID_PARAMS,
+            format!("prompt exceeds {MAX_PROMPT_CHARS} characters"),
+        ));
+    }
+    Ok(prompt)
+}
+
+fn request_key(id: &Value) -> String {
+    match id {
+        Value::String(value) => format!("s:{value}"),
+        other => format!("v:{other}"),
+    }
+}
+
+fn to_pretty_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|err| err.to_string())
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
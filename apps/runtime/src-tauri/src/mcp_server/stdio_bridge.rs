@@ -0,0 +1,305 @@
+use serde_json::{json, Value};
+use std::io::{BufRead, Read, Write};
+use std::sync::{mpsc, Arc, Mutex};
+
+/// `runtime --mcp-stdio` 以 stdio MCP 服务端的身份运行，把消息转发给正在运行的桌面端。
+pub const MCP_STDIO_FLAG: &str = "--mcp-stdio";
+pub const MCP_URL_ENV: &str = "WORKCLAW_MCP_URL";
+pub const MCP_TOKEN_ENV: &str = "WORKCLAW_MCP_TOKEN";
+/// 单条消息上限，防止异常的 Content-Length 或超长行耗尽内存
+const MAX_STDIO_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+/// 并发转发请求的工作线程数，队列满时暂停读取 stdin
+const STDIO_WORKER_COUNT: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StdioFraming {
+    Ndjson,
+    ContentLength,
+}
+
+pub fn is_stdio_bridge_invocation<I: IntoIterator<Item = String>>(args: I) -> bool {
+    args.into_iter().skip(1).any(|arg| arg == MCP_STDIO_FLAG)
+}
+
+/// 从环境变量读取本地 MCP 端点，返回进程退出码。
+pub fn run_stdio_bridge_from_env() -> i32 {
+    let url = std::env::var(MCP_URL_ENV).unwrap_or_default();
+    let token = std::env::var(MCP_TOKEN_ENV).unwrap_or_default();
+    if url.trim().is_empty() || token.trim().is_empty() {
+        eprintln!(
+            "[mcp-stdio] {MCP_URL_ENV} and {MCP_TOKEN_ENV} must be set; copy them from the WorkClaw MCP server settings"
+        );
+        return 2;
+    }
+    let stdin = std::io::stdin();
+    let stdout = Arc::new(Mutex::new(std::io::stdout()));
+    match run_stdio_bridge(url.trim(), token.trim(), stdin.lock(), stdout) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("[mcp-stdio] {err}");
+            1
+        }
+    }
+}
+
+/// 逐条读取 stdin 上的 JSON-RPC 消息（NDJSON 或 Content-Length 帧），POST 到本地端点，
+/// 再按请求使用的帧格式写回响应。请求交给固定数量的工作线程转发；通知在读取线程上
+/// 直接转发，工作线程都被长时间运行的工具调用占满时 `notifications/cancelled` 也能送达。
+pub fn run_stdio_bridge<R, W>(
+    url: &str,
+    token: &str,
+    mut reader: R,
+    writer: Arc<Mutex<W>>,
+) -> Result<(), String>
+where
+    R: BufRead,
+    W: Write + Send + 'static,
+{
+    let client = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()
+        .map_err(|err| format!("create http client failed: {err}"))?;
+    let (sender, receiver) = mpsc::sync_channel::<(Vec<u8>, StdioFraming)>(STDIO_WORKER_COUNT);
+    let receiver = Mutex::new(receiver);
+    std::thread::scope(|scope| {
+        for _ in 0..STDIO_WORKER_COUNT {
+            let client = client.clone();
+            let writer = Arc::clone(&writer);
+            let receiver = &receiver;
+            scope.spawn(move || loop {
+                let next = receiver
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .recv();
+                let Ok((message, framing)) = next else {
+                    break;
+                };
+                if let Some(response) = forward_message(&client, url, token, &message) {
+                    let _ = write_stdio_message(&writer, &response, framing);
+                }
+            });
+        }
+        let result = loop {
+            let (message, framing) = match read_stdio_message(&mut reader) {
+                Ok(Some(message)) => message,
+                Ok(None) => break Ok(()),
+                Err(err) => break Err(err),
+            };
+            if is_notification(&message) {
+                forward_message(&client, url, token, &message);
+                continue;
+            }
+            if sender.send((message, framing)).is_err() {
+                break Ok(());
+            }
+        };
+        // 关闭队列让工作线程处理完剩余请求后退出
+        drop(sender);
+        result
+    })
+}
+
+fn is_notification(message: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(message)
+        .ok()
+        .is_some_and(|value| value.get("method").is_some() && value.get("id").is_none())
+}
+
+fn forward_message(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    token: &str,
+    message: &[u8],
+) -> Option<Vec<u8>> {
+    let result = client
+        .post(url)
+        .bearer_auth(token)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json, text/event-stream")
+        .body(message.to_vec())
+        .send()
+        .and_then(|response| {
+            let status = response.status();
+            response.bytes().map(|body| (status, body))
+        });
+    let error = match result {
+        Ok((status, _)) if status == reqwest::StatusCode::ACCEPTED => return None,
+        Ok((status, body)) if status.is_success() => return Some(body.to_vec()),
+        Ok((status, body)) => format!(
+            "WorkClaw MCP endpoint returned {status}: {}",
+            String::from_utf8_lossy(&body)
+        ),
+        Err(err) => format!("WorkClaw is not reachable, is the desktop app running? {err}"),
+    };
+    // 只有带 id 的请求需要回错误，否则客户端会一直等待
+    let id = serde_json::from_slice::<Value>(message)
+        .ok()
+        .and_then(|value| value.get("id").cloned())?;
+    serde_json::to_vec(&json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32603, "message": error },
+    }))
+    .ok()
+}
+
+fn read_stdio_message<R: BufRead>(
+    reader: &mut R,
+) -> Result<Option<(Vec<u8>, StdioFraming)>, String> {
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .by_ref()
+            .take(MAX_STDIO_MESSAGE_BYTES as u64 + 1)
+            .read_line(&mut line)
+            .map_err(|err| format!("read stdin failed: {err}"))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if read > MAX_STDIO_MESSAGE_BYTES {
+            return Err(format!(
+                "stdin message exceeds {MAX_STDIO_MESSAGE_BYTES} bytes"
+            ));
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = trimmed.split_once(':') else {
+            return Ok(Some((trimmed.as_bytes().to_vec(), StdioFraming::Ndjson)));
+        };
+        if !name.trim().eq_ignore_ascii_case("content-length") {
+            return Ok(Some((trimmed.as_bytes().to_vec(), StdioFraming::Ndjson)));
+        }
+        let length = value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("invalid Content-Length header: {trimmed}"))?;
+        if length > MAX_STDIO_MESSAGE_BYTES {
+            return Err(format!(
+                "Content-Length {length} exceeds {MAX_STDIO_MESSAGE_BYTES} bytes"
+            ));
+        }
+        // 跳过剩余的帧头直到空行
+        loop {
+            let mut header = String::new();
+            let read = reader
+                .by_ref()
+                .take(MAX_STDIO_MESSAGE_BYTES as u64)
+                .read_line(&mut header)
+                .map_err(|err| format!("read stdin failed: {err}"))?;
+            if read == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0u8; length];
+        reader
+            .read_exact(&mut body)
+            .map_err(|err| format!("read framed message failed: {err}"))?;
+        return Ok(Some((body, StdioFraming::ContentLength)));
+    }
+}
+
+fn write_stdio_message<W: Write>(
+    writer: &Mutex<W>,
+    body: &[u8],
+    framing: StdioFraming,
+) -> std::io::Result<()> {
+    let mut writer = writer.lock().expect("mcp stdio writer lock");
+    match framing {
+        StdioFraming::Ndjson => {
+            writer.write_all(body)?;
+            writer.write_all(b"\n")?;
+        }
+        StdioFraming::ContentLength => {
+            write!(writer, "Content-Length: {}\r\n\r\n", body.len())?;
+            writer.write_all(body)?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_stdio_message_accepts_ndjson_and_content_length_frames() {
+        let body = r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#;
+        let input = format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}}\n\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut reader = Cursor::new(input.into_bytes());
+
+        let (first, framing) = read_stdio_message(&mut reader).unwrap().unwrap();
+        assert_eq!(framing, StdioFraming::Ndjson);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&first).unwrap()["id"],
+            json!(1)
+        );
+
+        let (second, framing) = read_stdio_message(&mut reader).unwrap().unwrap();
+        assert_eq!(framing, StdioFraming::ContentLength);
+        assert_eq!(second, body.as_bytes());
+
+        assert!(read_stdio_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_stdio_message_rejects_oversized_frames() {
+        let input = format!("Content-Length: {}\r\n\r\n", MAX_STDIO_MESSAGE_BYTES + 1);
+        let mut reader = Cursor::new(input.into_bytes());
+        let err = read_stdio_message(&mut reader).expect_err("oversized frame");
+        assert!(err.contains("exceeds"));
+
+        let mut reader = Cursor::new(vec![b'x'; MAX_STDIO_MESSAGE_BYTES + 2]);
+        let err = read_stdio_message(&mut reader).expect_err("oversized line");
+        assert!(err.contains("exceeds"));
+    }
+
+    #[test]
+    fn notifications_are_told_apart_from_requests() {
+        assert!(is_notification(
+            br#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}"#
+        ));
+        assert!(!is_notification(
+            br#"{"jsonrpc":"2.0","id":1,"method":"tools/call"}"#
+        ));
+        assert!(!is_notification(br#"{"jsonrpc":"2.0","id":1,"result":{}}"#));
+    }
+
+    #[test]
+    fn stdio_bridge_flag_is_only_read_from_arguments() {
+        assert!(is_stdio_bridge_invocation([
+            "runtime".to_string(),
+            MCP_STDIO_FLAG.to_string()
+        ]));
+        assert!(!is_stdio_bridge_invocation([MCP_STDIO_FLAG.to_string()]));
+    }
+
+    #[test]
+    fn unreachable_endpoint_answers_requests_with_jsonrpc_error() {
+        let client = reqwest::blocking::Client::new();
+        let response = forward_message(
+            &client,
+            "http://127.0.0.1:9/mcp",
+            "token",
+            br#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#,
+        )
+        .expect("error response for request");
+        let response: Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response["id"], json!(7));
+        assert_eq!(response["error"]["code"], json!(-32603));
+
+        assert!(forward_message(
+            &client,
+            "http://127.0.0.1:9/mcp",
+            "token",
+            br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use runtime_routing_core::{ModelCatalog, ModelCatalogEntry};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalogOverride {
+    pub entry: ModelCatalogEntry,
+    pub updated_at: String,
+}
+
+fn shared_model_catalog() -> &'static RwLock<Arc<ModelCatalog>> {
+    static CATALOG: OnceLock<RwLock<Arc<ModelCatalog>>> = OnceLock::new();
+    CATALOG.get_or_init(|| RwLock::new(Arc::new(ModelCatalog::bundled().clone())))
+}
+
+/// 进程内生效的模型目录：内置目录叠加 SQLite 中的用户覆盖
+pub fn current_model_catalog() -> Arc<ModelCatalog> {
+    shared_model_catalog()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+fn replace_model_catalog(catalog: ModelCatalog) {
+    *shared_model_catalog()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(catalog);
+}
+
+pub async fn list_model_catalog_overrides_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<ModelCatalogOverride>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT model, entry_json, updated_at FROM model_catalog_overrides ORDER BY model ASC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取模型目录覆盖失败: {e}"))?;
+
+    rows.into_iter()
+        .map(|(model, entry_json, updated_at)| {
+            let entry = serde_json::from_str::<ModelCatalogEntry>(&entry_json)
+                .map_err(|e| format!("模型目录覆盖 {model} 格式无效: {e}"))?;
+            Ok(ModelCatalogOverride { entry, updated_at })
+        })
+        .collect()
+}
+
+/// 重新加载用户覆盖并刷新进程内目录，返回生效后的目录
+pub async fn reload_model_catalog_with_pool(
+    pool: &SqlitePool,
+) -> Result<Arc<ModelCatalog>, String> {
+    let overrides = list_model_catalog_overrides_with_pool(pool).await?;
+    let catalog = ModelCatalog::bundled()
+        .clone()
+        .with_overrides(overrides.into_iter().map(|item| item.entry));
+    replace_model_catalog(catalog);
+    Ok(current_model_catalog())
+}
+
+pub async fn save_model_catalog_override_with_pool(
+    pool: &SqlitePool,
+    mut entry: ModelCatalogEntry,
+) -> Result<Arc<ModelCatalog>, String> {
+    entry.model = entry.model.trim().to_string();
+    if entry.model.is_empty() || entry.model == "*" {
+        return Err("模型名不能为空".to_string());
+    }
+    if entry.context_window == Some(0) {
+        return Err("上下文窗口必须大于 0".to_string());
+    }
+    if let (Some(context_window), Some(max_output_tokens)) =
+        (entry.context_window, entry.max_output_tokens)
+    {
+        if max_output_tokens >= context_window {
+            return Err("最大输出长度必须小于上下文窗口".to_string());
+        }
+    }
+    let entry_json =
+        serde_json::to_string(&entry).map_err(|e| format!("序列化模型目录覆盖失败: {e}"))?;
+    sqlx::query(
+        "INSERT OR REPLACE INTO model_catalog_overrides (model, entry_json, updated_at)
+         VALUES (?, ?, ?)",
+    )
+    .bind(entry.model.to_ascii_lowercase())
+    .bind(entry_json)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("保存模型目录覆盖失败: {e}"))?;
+    reload_model_catalog_with_pool(pool).await
+}
+
+pub async fn delete_model_catalog_override_with_pool(
+    pool: &SqlitePool,
+    model: &str,
+) -> Result<Arc<ModelCatalog>, String> {
+    sqlx::query("DELETE FROM model_catalog_overrides WHERE model = ?")
+        .bind(model.trim().to_ascii_lowercase())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("删除模型目录覆盖失败: {e}"))?;
+    reload_model_catalog_with_pool(pool).await
+}
@@ -0,0 +1,142 @@
+use crate::agent::types::ReasoningSettings;
+use sqlx::SqlitePool;
+
+/// 对话路由使用的能力键，与 routing_policies.capability 对应
+pub const CHAT_REASONING_CAPABILITY: &str = "chat";
+
+fn parse_reasoning_json(raw: &str) -> ReasoningSettings {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+fn encode_reasoning_json(settings: &ReasoningSettings) -> Result<String, String> {
+    serde_json::to_string(settings).map_err(|e| format!("序列化推理设置失败: {e}"))
+}
+
+fn validate_reasoning_settings(settings: &ReasoningSettings) -> Result<(), String> {
+    if settings.budget_tokens.is_some_and(|budget| budget < 1024) {
+        return Err("思考预算不能少于 1024 tokens".to_string());
+    }
+    Ok(())
+}
+
+pub async fn get_model_reasoning_settings_with_pool(
+    pool: &SqlitePool,
+    model_id: &str,
+) -> Result<ReasoningSettings, String> {
+    let raw = sqlx::query_scalar::<_, String>(
+        "SELECT reasoning_json FROM model_configs WHERE id = ? LIMIT 1",
+    )
+    .bind(model_id.trim())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("读取模型推理设置失败: {e}"))?
+    .ok_or_else(|| "模型配置不存在".to_string())?;
+    Ok(parse_reasoning_json(&raw))
+}
+
+pub async fn save_model_reasoning_settings_with_pool(
+    pool: &SqlitePool,
+    model_id: &str,
+    settings: ReasoningSettings,
+) -> Result<ReasoningSettings, String> {
+    validate_reasoning_settings(&settings)?;
+    let result = sqlx::query("UPDATE model_configs SET reasoning_json = ? WHERE id = ?")
+        .bind(encode_reasoning_json(&settings)?)
+        .bind(model_id.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("保存模型推理设置失败: {e}"))?;
+    if result.rows_affected() == 0 {
+        return Err("模型配置不存在".to_string());
+    }
+    Ok(settings)
+}
+
+/// 读取能力路由上的推理设置；未配置路由策略时返回默认值
+pub async fn get_route_reasoning_settings_with_pool(
+    pool: &SqlitePool,
+    capability: &str,
+) -> Result<ReasoningSettings, String> {
+    let raw = sqlx::query_scalar::<_, String>(
+        "SELECT reasoning_json FROM routing_policies WHERE capability = ? LIMIT 1",
+    )
+    .bind(capability.trim())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("读取路由推理设置失败: {e}"))?;
+    Ok(raw.as_deref().map(parse_reasoning_json).unwrap_or_default())
+}
+
+pub async fn save_route_reasoning_settings_with_pool(
+    pool: &SqlitePool,
+    capability: &str,
+    settings: ReasoningSettings,
+) -> Result<ReasoningSettings, String> {
+    validate_reasoning_settings(&settings)?;
+    let result = sqlx::query("UPDATE routing_policies SET reasoning_json = ? WHERE capability = ?")
+        .bind(encode_reasoning_json(&settings)?)
+        .bind(capability.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("保存路由推理设置失败: {e}"))?;
+    if result.rows_affected() == 0 {
+        return Err("请先保存该能力的路由策略".to_string());
+    }
+    Ok(settings)
+}
+
+/// 解析本轮调用的推理设置：路由策略优先，未配置的字段回退到匹配的模型配置
+pub async fn resolve_reasoning_settings_with_pool(
+    pool: &SqlitePool,
+    capability: &str,
+    base_url: &str,
+    model_name: &str,
+) -> Result<ReasoningSettings, String> {
+    let route = get_route_reasoning_settings_with_pool(pool, capability).await?;
+    let model_raw = sqlx::query_scalar::<_, String>(
+        "SELECT reasoning_json FROM model_configs
+         WHERE base_url = ? AND model_name = ?
+         ORDER BY is_default DESC
+         LIMIT 1",
+    )
+    .bind(base_url)
+    .bind(model_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("读取模型推理设置失败: {e}"))?;
+    let model = model_raw
+        .as_deref()
+        .map(parse_reasoning_json)
+        .unwrap_or_default();
+    Ok(route.or(model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::types::ReasoningEffort;
+
+    #[test]
+    fn reasoning_json_roundtrip_ignores_unset_fields() {
+        let settings = ReasoningSettings {
+            effort: Some(ReasoningEffort::High),
+            ..ReasoningSettings::default()
+        };
+        let raw = encode_reasoning_json(&settings).expect("encode");
+        assert_eq!(raw, r#"{"effort":"high"}"#);
+        assert_eq!(parse_reasoning_json(&raw), settings);
+        assert_eq!(
+            parse_reasoning_json("not json"),
+            ReasoningSettings::default()
+        );
+    }
+
+    #[test]
+    fn reasoning_budget_below_minimum_is_rejected() {
+        let settings = ReasoningSettings {
+            budget_tokens: Some(512),
+            ..ReasoningSettings::default()
+        };
+        assert!(validate_reasoning_settings(&settings).is_err());
+    }
+}
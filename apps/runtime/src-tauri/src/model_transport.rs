@@ -5,6 +5,20 @@ pub enum ModelTransportKind {
     AnthropicMessages,
     OpenAiCompletions,
     OpenAiResponses,
+    GeminiGenerateContent,
+    OllamaChat,
+}
+
+impl ModelTransportKind {
+    /// 协议对应的 api_format；消息历史只区分 anthropic 与其余（OpenAI 形状）
+    pub fn api_format(self) -> &'static str {
+        match self {
+            Self::AnthropicMessages => "anthropic",
+            Self::OpenAiCompletions | Self::OpenAiResponses => "openai",
+            Self::GeminiGenerateContent => "gemini",
+            Self::OllamaChat => "ollama",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -49,6 +63,26 @@ fn is_anthropic_base_url(base_url: &str) -> bool {
         .any(|segment| segment.eq_ignore_ascii_case("anthropic"))
 }
 
+fn is_native_gemini_base_url(base_url: &str) -> bool {
+    let Ok(url) = Url::parse(base_url.trim()) else {
+        return false;
+    };
+    let host = url.host_str().unwrap_or_default();
+    // Gemini 的 OpenAI 兼容端点挂在 /openai 路径下，仍走 OpenAI 协议
+    host.eq_ignore_ascii_case("generativelanguage.googleapis.com")
+        && !url
+            .path()
+            .split('/')
+            .any(|segment| segment.eq_ignore_ascii_case("openai"))
+}
+
+fn is_provider_key(provider_key: Option<&str>, expected: &str) -> bool {
+    provider_key
+        .map(str::trim)
+        .map(|value| value.eq_ignore_ascii_case(expected))
+        .unwrap_or(false)
+}
+
 fn is_openai_api_base_url(base_url: &str) -> bool {
     let trimmed = base_url.trim();
     if trimmed.is_empty() {
@@ -100,19 +134,15 @@ fn build_openai_compat_features(base_url: &str) -> OpenAiCompatFeatures {
             supports_usage_in_streaming: true,
             supports_strict_mode: true,
         },
+        // 未知端点也请求流式用量；不支持 stream_options 的端点由 adapter 回退重试
         OpenAiCompatEndpointFamily::ModelStudioNative
-        | OpenAiCompatEndpointFamily::MoonshotNative => OpenAiCompatFeatures {
+        | OpenAiCompatEndpointFamily::MoonshotNative
+        | OpenAiCompatEndpointFamily::OpenRouter
+        | OpenAiCompatEndpointFamily::Generic => OpenAiCompatFeatures {
             supports_developer_role: false,
             supports_usage_in_streaming: true,
             supports_strict_mode: false,
         },
-        OpenAiCompatEndpointFamily::OpenRouter | OpenAiCompatEndpointFamily::Generic => {
-            OpenAiCompatFeatures {
-                supports_developer_role: false,
-                supports_usage_in_streaming: false,
-                supports_strict_mode: false,
-            }
-        }
     }
 }
 
@@ -137,12 +167,30 @@ pub fn resolve_model_transport(
     base_url: &str,
     provider_key: Option<&str>,
 ) -> ResolvedModelTransport {
-    if api_format.trim().eq_ignore_ascii_case("anthropic") || is_anthropic_base_url(base_url) {
+    let api_format = api_format.trim();
+    if api_format.eq_ignore_ascii_case("anthropic") || is_anthropic_base_url(base_url) {
         return ResolvedModelTransport {
             kind: ModelTransportKind::AnthropicMessages,
             openai_compat: None,
         };
     }
+    if api_format.eq_ignore_ascii_case("gemini")
+        || (api_format.is_empty()
+            && (is_provider_key(provider_key, "gemini") || is_native_gemini_base_url(base_url)))
+    {
+        return ResolvedModelTransport {
+            kind: ModelTransportKind::GeminiGenerateContent,
+            openai_compat: None,
+        };
+    }
+    if api_format.eq_ignore_ascii_case("ollama")
+        || (api_format.is_empty() && is_provider_key(provider_key, "ollama"))
+    {
+        return ResolvedModelTransport {
+            kind: ModelTransportKind::OllamaChat,
+            openai_compat: None,
+        };
+    }
 
     let kind = if has_non_openai_provider_key(provider_key) {
         ModelTransportKind::OpenAiCompletions
@@ -228,7 +276,7 @@ mod tests {
             resolved.openai_compat,
             Some(OpenAiCompatFeatures {
                 supports_developer_role: false,
-                supports_usage_in_streaming: false,
+                supports_usage_in_streaming: true,
                 supports_strict_mode: false,
             })
         );
@@ -251,7 +299,7 @@ mod tests {
             resolved.openai_compat,
             Some(OpenAiCompatFeatures {
                 supports_developer_role: false,
-                supports_usage_in_streaming: false,
+                supports_usage_in_streaming: true,
                 supports_strict_mode: false,
             })
         );
@@ -292,4 +340,39 @@ mod tests {
         assert_eq!(resolved.kind, ModelTransportKind::AnthropicMessages);
         assert_eq!(resolved.openai_compat, None);
     }
+
+    #[test]
+    fn gemini_api_format_and_native_host_use_generate_content_transport() {
+        let explicit = resolve_model_transport(
+            "gemini",
+            "https://generativelanguage.googleapis.com/v1beta",
+            Some("gemini"),
+        );
+        assert_eq!(explicit.kind, ModelTransportKind::GeminiGenerateContent);
+        assert_eq!(explicit.openai_compat, None);
+
+        let inferred =
+            resolve_model_transport("", "https://generativelanguage.googleapis.com/v1beta", None);
+        assert_eq!(inferred.kind, ModelTransportKind::GeminiGenerateContent);
+
+        let compat = resolve_model_transport(
+            "",
+            "https://generativelanguage.googleapis.com/v1beta/openai",
+            None,
+        );
+        assert_eq!(compat.kind, ModelTransportKind::OpenAiCompletions);
+    }
+
+    #[test]
+    fn ollama_uses_native_chat_transport_unless_openai_format_requested() {
+        let native = resolve_model_transport("ollama", "http://localhost:11434", None);
+        assert_eq!(native.kind, ModelTransportKind::OllamaChat);
+        assert_eq!(native.kind.api_format(), "ollama");
+
+        let by_provider = resolve_model_transport("", "http://localhost:11434", Some("ollama"));
+        assert_eq!(by_provider.kind, ModelTransportKind::OllamaChat);
+
+        let compat = resolve_model_transport("openai", "http://localhost:11434/v1", Some("ollama"));
+        assert_eq!(compat.kind, ModelTransportKind::OpenAiCompletions);
+    }
 }
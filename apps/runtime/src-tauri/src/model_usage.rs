@@ -0,0 +1,525 @@
+use crate::agent::types::TokenUsage;
+use crate::model_catalog::current_model_catalog;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const MODEL_PRICE_COLUMNS: &str =
+    "id, provider_key, model_pattern, input_per_million, output_per_million,
+                cache_read_per_million, cache_write_per_million, currency, updated_at";
+
+/// 群组运行与其成员会话的对应关系：入口会话、主会话以及每个步骤派生的会话
+const GROUP_RUN_SESSIONS_CTE: &str = "WITH group_run_sessions(run_id, session_id) AS (
+        SELECT run_id, session_id FROM group_run_steps WHERE session_id <> ''
+        UNION SELECT id, session_id FROM group_runs WHERE session_id <> ''
+        UNION SELECT id, entry_session_id FROM group_runs WHERE entry_session_id <> ''
+    ) ";
+
+#[derive(Debug, Clone)]
+pub struct ModelUsageRecordInput<'a> {
+    pub session_id: &'a str,
+    pub run_id: Option<&'a str>,
+    pub base_url: &'a str,
+    pub api_format: &'a str,
+    pub model_name: &'a str,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct ModelPriceRecord {
+    pub id: String,
+    /// 为空表示适用于所有 provider
+    pub provider_key: String,
+    /// 精确模型名，或以 `*` 结尾的前缀（如 `gpt-4o*`）
+    pub model_pattern: String,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub cache_read_per_million: f64,
+    pub cache_write_per_million: f64,
+    pub currency: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelPriceInput {
+    #[serde(default)]
+    pub provider_key: String,
+    pub model_pattern: String,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    #[serde(default)]
+    pub cache_read_per_million: f64,
+    #[serde(default)]
+    pub cache_write_per_million: f64,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelUsageGroupBy {
+    #[default]
+    Session,
+    Employee,
+    GroupRun,
+    Provider,
+    Model,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelUsageQuery {
+    #[serde(default)]
+    pub group_by: ModelUsageGroupBy,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub employee_id: Option<String>,
+    #[serde(default)]
+    pub group_run_id: Option<String>,
+    #[serde(default)]
+    pub provider_key: Option<String>,
+    /// RFC3339，包含边界
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ModelUsageSummary {
+    pub key: String,
+    pub call_count: i64,
+    pub usage: TokenUsage,
+    pub total_tokens: u64,
+    /// 按币种汇总的费用；没有配置单价的调用不计入
+    pub cost_by_currency: BTreeMap<String, f64>,
+    pub unpriced_calls: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct UsageAggregateRow {
+    group_key: String,
+    provider_key: String,
+    model_name: String,
+    call_count: i64,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_read_tokens: i64,
+    cache_write_tokens: i64,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn normalize_base_url(base_url: &str) -> String {
+    base_url.trim().trim_end_matches('/').to_string()
+}
+
+/// 优先使用已配置 provider 的 key，找不到时退化为 base_url 的主机名
+async fn resolve_provider_key(pool: &SqlitePool, base_url: &str, api_format: &str) -> String {
+    let normalized = normalize_base_url(base_url);
+    let configured = sqlx::query_scalar::<_, String>(
+        "SELECT provider_key FROM provider_configs
+         WHERE RTRIM(TRIM(base_url), '/') = ?
+         ORDER BY enabled DESC, updated_at DESC
+         LIMIT 1",
+    )
+    .bind(&normalized)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .filter(|value| !value.trim().is_empty());
+    if let Some(provider_key) = configured {
+        return provider_key;
+    }
+    reqwest::Url::parse(&normalized)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| api_format.trim().to_string())
+}
+
+/// 记录一次模型调用的 token 用量；员工与 provider 在写入时快照，便于后续按维度汇总
+pub async fn record_model_usage_with_pool(
+    pool: &SqlitePool,
+    input: ModelUsageRecordInput<'_>,
+) -> Result<String, String> {
+    let employee_id =
+        sqlx::query_scalar::<_, String>("SELECT employee_id FROM sessions WHERE id = ? LIMIT 1")
+            .bind(input.session_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("读取会话员工失败: {e}"))?
+            .unwrap_or_default();
+    let provider_key = resolve_provider_key(pool, input.base_url, input.api_format).await;
+    let id = Uuid::new_v4().to_string();
+
+    sqlx::query(
+        "INSERT INTO model_usage_records (
+            id, session_id, run_id, employee_id, provider_key, api_format, model_name,
+            input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, created_at
+         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(input.session_id)
+    .bind(input.run_id.unwrap_or_default())
+    .bind(employee_id.trim())
+    .bind(&provider_key)
+    .bind(input.api_format)
+    .bind(input.model_name)
+    .bind(input.usage.input_tokens as i64)
+    .bind(input.usage.output_tokens as i64)
+    .bind(input.usage.cache_read_tokens as i64)
+    .bind(input.usage.cache_write_tokens as i64)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("写入模型用量失败: {e}"))?;
+    Ok(id)
+}
+
+pub async fn list_model_prices_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<ModelPriceRecord>, String> {
+    sqlx::query_as::<_, ModelPriceRecord>(&format!(
+        "SELECT {MODEL_PRICE_COLUMNS}
+         FROM model_prices
+         ORDER BY provider_key ASC, model_pattern ASC"
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取模型单价失败: {e}"))
+}
+
+/// 新增或覆盖单价；同一 provider 与模型模式只保留一条
+pub async fn save_model_price_with_pool(
+    pool: &SqlitePool,
+    input: ModelPriceInput,
+) -> Result<ModelPriceRecord, String> {
+    let model_pattern = input.model_pattern.trim().to_string();
+    if model_pattern.is_empty() {
+        return Err("模型名称不能为空".to_string());
+    }
+    let prices = [
+        input.input_per_million,
+        input.output_per_million,
+        input.cache_read_per_million,
+        input.cache_write_per_million,
+    ];
+    if prices
+        .iter()
+        .any(|price| !price.is_finite() || *price < 0.0)
+    {
+        return Err("单价必须是非负数".to_string());
+    }
+    let provider_key = input.provider_key.trim().to_string();
+    let currency = input
+        .currency
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or("USD")
+        .to_ascii_uppercase();
+
+    sqlx::query(
+        "INSERT INTO model_prices (
+            id, provider_key, model_pattern, input_per_million, output_per_million,
+            cache_read_per_million, cache_write_per_million, currency, updated_at
+         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(provider_key, model_pattern) DO UPDATE SET
+            input_per_million = excluded.input_per_million,
+            output_per_million = excluded.output_per_million,
+            cache_read_per_million = excluded.cache_read_per_million,
+            cache_write_per_million = excluded.cache_write_per_million,
+            currency = excluded.currency,
+            updated_at = excluded.updated_at",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&provider_key)
+    .bind(&model_pattern)
+    .bind(input.input_per_million)
+    .bind(input.output_per_million)
+    .bind(input.cache_read_per_million)
+    .bind(input.cache_write_per_million)
+    .bind(&currency)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("保存模型单价失败: {e}"))?;
+
+    sqlx::query_as::<_, ModelPriceRecord>(&format!(
+        "SELECT {MODEL_PRICE_COLUMNS}
+         FROM model_prices
+         WHERE provider_key = ? AND model_pattern = ?
+         LIMIT 1"
+    ))
+    .bind(&provider_key)
+    .bind(&model_pattern)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("读取模型单价失败: {e}"))
+}
+
+pub async fn delete_model_price_with_pool(pool: &SqlitePool, price_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM model_prices WHERE id = ?")
+        .bind(price_id.trim())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("删除模型单价失败: {e}"))?;
+    Ok(())
+}
+
+/// 匹配优先级：指定 provider 优先于通配，精确模型名优先于前缀，前缀越长越优先
+fn find_model_price<'a>(
+    prices: &'a [ModelPriceRecord],
+    provider_key: &str,
+    model_name: &str,
+) -> Option<&'a ModelPriceRecord> {
+    let model_name = model_name.trim().to_ascii_lowercase();
+    prices
+        .iter()
+        .filter_map(|price| {
+            let provider_specific = !price.provider_key.is_empty();
+            if provider_specific && !price.provider_key.eq_ignore_ascii_case(provider_key) {
+                return None;
+            }
+            let pattern = price.model_pattern.trim().to_ascii_lowercase();
+            let (exact, prefix_len) = match pattern.strip_suffix('*') {
+                Some(prefix) if model_name.starts_with(prefix) => (false, prefix.len()),
+                Some(_) => return None,
+                None if pattern == model_name => (true, pattern.len()),
+                None => return None,
+            };
+            Some(((provider_specific, exact, prefix_len), price))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, price)| price)
+}
+
+/// 单价表未配置时，退回模型目录中的参考价；id 以 `catalog:` 开头以便区分
+fn catalog_model_price(model_name: &str) -> Option<ModelPriceRecord> {
+    let catalog = current_model_catalog();
+    let entry = catalog.lookup(model_name)?;
+    let pricing = entry.pricing.as_ref()?;
+    Some(ModelPriceRecord {
+        id: format!("catalog:{}", entry.model),
+        provider_key: String::new(),
+        model_pattern: entry.model.clone(),
+        input_per_million: pricing.input_per_million,
+        output_per_million: pricing.output_per_million,
+        cache_read_per_million: pricing.cache_read_per_million,
+        cache_write_per_million: pricing.cache_write_per_million,
+        currency: pricing.currency.trim().to_ascii_uppercase(),
+        updated_at: String::new(),
+    })
+}
+
+/// 查找某个模型当前生效的单价，供运行预算实时折算费用
+pub async fn resolve_model_price_with_pool(
+    pool: &SqlitePool,
+    base_url: &str,
+    api_format: &str,
+    model_name: &str,
+) -> Result<Option<ModelPriceRecord>, String> {
+    let provider_key = resolve_provider_key(pool, base_url, api_format).await;
+    let prices = list_model_prices_with_pool(pool).await?;
+    Ok(find_model_price(&prices, &provider_key, model_name)
+        .cloned()
+        .or_else(|| catalog_model_price(model_name)))
+}
+
+pub fn estimate_model_cost(usage: &TokenUsage, price: &ModelPriceRecord) -> f64 {
+    (usage.input_tokens as f64 * price.input_per_million
+        + usage.output_tokens as f64 * price.output_per_million
+        + usage.cache_read_tokens as f64 * price.cache_read_per_million
+        + usage.cache_write_tokens as f64 * price.cache_write_per_million)
+        / 1_000_000.0
+}
+
+fn push_usage_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, query: &'a ModelUsageQuery) {
+    if let Some(session_id) = non_empty(&query.session_id) {
+        builder.push(" AND u.session_id = ").push_bind(session_id);
+    }
+    if let Some(employee_id) = non_empty(&query.employee_id) {
+        builder.push(" AND u.employee_id = ").push_bind(employee_id);
+    }
+    if let Some(provider_key) = non_empty(&query.provider_key) {
+        builder
+            .push(" AND u.provider_key = ")
+            .push_bind(provider_key);
+    }
+    if let Some(group_run_id) = non_empty(&query.group_run_id) {
+        builder
+            .push(" AND u.session_id IN (SELECT session_id FROM group_run_sessions WHERE run_id = ")
+            .push_bind(group_run_id)
+            .push(")");
+    }
+    if let Some(since) = non_empty(&query.since) {
+        builder.push(" AND u.created_at >= ").push_bind(since);
+    }
+    if let Some(until) = non_empty(&query.until) {
+        builder.push(" AND u.created_at <= ").push_bind(until);
+    }
+}
+
+/// 按会话 / 员工 / 群组运行 / provider / 模型汇总用量，费用按当前单价表折算
+pub async fn summarize_model_usage_with_pool(
+    pool: &SqlitePool,
+    query: &ModelUsageQuery,
+) -> Result<Vec<ModelUsageSummary>, String> {
+    let (key_expr, join) = match query.group_by {
+        ModelUsageGroupBy::Session => ("u.session_id", ""),
+        ModelUsageGroupBy::Employee => ("u.employee_id", ""),
+        ModelUsageGroupBy::Provider => ("u.provider_key", ""),
+        ModelUsageGroupBy::Model => ("u.model_name", ""),
+        ModelUsageGroupBy::GroupRun => (
+            "g.run_id",
+            " JOIN group_run_sessions g ON g.session_id = u.session_id",
+        ),
+    };
+    let mut builder = QueryBuilder::<Sqlite>::new(GROUP_RUN_SESSIONS_CTE);
+    builder
+        .push("SELECT ")
+        .push(key_expr)
+        .push(
+            " AS group_key,
+                u.provider_key,
+                u.model_name,
+                COUNT(*) AS call_count,
+                COALESCE(SUM(u.input_tokens), 0) AS input_tokens,
+                COALESCE(SUM(u.output_tokens), 0) AS output_tokens,
+                COALESCE(SUM(u.cache_read_tokens), 0) AS cache_read_tokens,
+                COALESCE(SUM(u.cache_write_tokens), 0) AS cache_write_tokens
+             FROM model_usage_records u",
+        )
+        .push(join)
+        .push(" WHERE 1 = 1");
+    push_usage_filters(&mut builder, query);
+    builder.push(" GROUP BY group_key, u.provider_key, u.model_name");
+
+    let rows = builder
+        .build_query_as::<UsageAggregateRow>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("汇总模型用量失败: {e}"))?;
+    let prices = list_model_prices_with_pool(pool).await?;
+    Ok(fold_usage_rows(rows, &prices))
+}
+
+fn fold_usage_rows(
+    rows: Vec<UsageAggregateRow>,
+    prices: &[ModelPriceRecord],
+) -> Vec<ModelUsageSummary> {
+    let mut summaries: BTreeMap<String, ModelUsageSummary> = BTreeMap::new();
+    for row in rows {
+        let usage = TokenUsage {
+            input_tokens: row.input_tokens.max(0) as u64,
+            output_tokens: row.output_tokens.max(0) as u64,
+            cache_read_tokens: row.cache_read_tokens.max(0) as u64,
+            cache_write_tokens: row.cache_write_tokens.max(0) as u64,
+        };
+        let summary = summaries
+            .entry(row.group_key.clone())
+            .or_insert_with(|| ModelUsageSummary {
+                key: row.group_key.clone(),
+                call_count: 0,
+                usage: TokenUsage::default(),
+                total_tokens: 0,
+                cost_by_currency: BTreeMap::new(),
+                unpriced_calls: 0,
+            });
+        summary.call_count += row.call_count;
+        summary.usage.add(&usage);
+        summary.total_tokens = summary.usage.total_tokens();
+        let price = find_model_price(prices, &row.provider_key, &row.model_name)
+            .cloned()
+            .or_else(|| catalog_model_price(&row.model_name));
+        match price {
+            Some(price) => {
+                *summary
+                    .cost_by_currency
+                    .entry(price.currency.clone())
+                    .or_insert(0.0) += estimate_model_cost(&usage, &price);
+            }
+            None => summary.unpriced_calls += row.call_count,
+        }
+    }
+    let mut summaries = summaries.into_values().collect::<Vec<_>>();
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.total_tokens));
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catalog_model_price, estimate_model_cost, find_model_price, ModelPriceRecord};
+    use crate::agent::types::TokenUsage;
+
+    fn price(provider_key: &str, model_pattern: &str, input: f64) -> ModelPriceRecord {
+        ModelPriceRecord {
+            id: format!("{provider_key}:{model_pattern}"),
+            provider_key: provider_key.to_string(),
+            model_pattern: model_pattern.to_string(),
+            input_per_million: input,
+            output_per_million: input * 4.0,
+            cache_read_per_million: input / 10.0,
+            cache_write_per_million: input * 1.25,
+            currency: "USD".to_string(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn price_lookup_prefers_specific_provider_then_exact_model_then_longest_prefix() {
+        let prices = vec![
+            price("", "gpt-4o*", 2.5),
+            price("", "gpt-4o-mini*", 0.15),
+            price("openai", "gpt-4o-mini", 0.2),
+            price("azure", "gpt-4o*", 3.0),
+        ];
+
+        let pick = |provider: &str, model: &str| {
+            find_model_price(&prices, provider, model).map(|price| price.id.clone())
+        };
+        assert_eq!(
+            pick("openai", "gpt-4o-mini").as_deref(),
+            Some("openai:gpt-4o-mini")
+        );
+        assert_eq!(
+            pick("deepseek", "GPT-4o-mini-2024").as_deref(),
+            Some(":gpt-4o-mini*")
+        );
+        assert_eq!(
+            pick("azure", "gpt-4o-mini").as_deref(),
+            Some("azure:gpt-4o*")
+        );
+        assert_eq!(pick("openai", "gpt-4o").as_deref(), Some(":gpt-4o*"));
+        assert_eq!(pick("openai", "o3"), None);
+    }
+
+    #[test]
+    fn catalog_pricing_is_used_when_no_price_is_configured() {
+        let price = catalog_model_price("gpt-4o-mini").expect("catalog price");
+        assert_eq!(price.id, "catalog:gpt-4o-mini");
+        assert_eq!(price.currency, "USD");
+        assert!(price.input_per_million > 0.0);
+        assert!(catalog_model_price("mystery-model").is_none());
+    }
+
+    #[test]
+    fn cost_applies_separate_cache_prices() {
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 500_000,
+            cache_read_tokens: 2_000_000,
+            cache_write_tokens: 0,
+        };
+        let cost = estimate_model_cost(&usage, &price("", "claude-sonnet-4*", 3.0));
+        assert!((cost - (3.0 + 6.0 + 0.6)).abs() < 1e-9);
+    }
+}
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RouteTarget {
     pub provider_id: String,
@@ -20,6 +22,25 @@ pub enum RouteFailureKind {
     Unknown,
 }
 
+impl RouteFailureKind {
+    /// 由路由日志中的 error_kind 还原；非供应商侧的错误（上下文超限、策略拦截等）返回 None
+    pub fn from_error_kind_key(key: &str) -> Option<Self> {
+        match key {
+            "auth" | "billing" => Some(Self::Auth),
+            "rate_limit" => Some(Self::RateLimit),
+            "timeout" => Some(Self::Timeout),
+            "network" => Some(Self::Network),
+            "unknown" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// 鉴权与限流是账号级问题，同一供应商下的其他模型通常同样不可用
+    fn affects_whole_provider(self) -> bool {
+        matches!(self, Self::Auth | Self::RateLimit)
+    }
+}
+
 impl RoutingPolicy {
     pub fn ordered_targets(&self) -> Vec<RouteTarget> {
         let mut targets = Vec::with_capacity(1 + self.fallbacks.len());
@@ -34,12 +55,402 @@ pub fn route_with_fallback(
     failure_kind: Option<RouteFailureKind>,
 ) -> Option<RouteTarget> {
     let ordered = policy.ordered_targets();
-    match failure_kind {
-        None => ordered.first().cloned(),
-        Some(RouteFailureKind::Auth)
-        | Some(RouteFailureKind::RateLimit)
-        | Some(RouteFailureKind::Timeout)
-        | Some(RouteFailureKind::Network)
-        | Some(RouteFailureKind::Unknown) => ordered.get(1).cloned(),
+    let Some(kind) = failure_kind else {
+        return ordered.first().cloned();
+    };
+    if kind.affects_whole_provider() {
+        return ordered
+            .iter()
+            .skip(1)
+            .find(|target| target.provider_id != policy.primary.provider_id)
+            .cloned();
+    }
+    ordered.get(1).cloned()
+}
+
+/// 熔断与健康判定参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBreakerPolicy {
+    /// 连续失败达到该次数后打开熔断
+    pub failure_threshold: usize,
+    /// 熔断打开时长，过后进入半开状态，允许降级重试
+    pub open_secs: i64,
+    /// 最近一次限流后的冷却时长
+    pub rate_limit_cooldown_secs: i64,
+    /// 样本足够时错误率达到该值视为降级
+    pub degraded_error_rate: f64,
+    pub min_samples: usize,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            open_secs: 120,
+            rate_limit_cooldown_secs: 60,
+            degraded_error_rate: 0.5,
+            min_samples: 4,
+        }
+    }
+}
+
+/// 单次路由尝试的健康样本；latency_ms 为首个流式输出的耗时，0 表示未知
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteHealthSample {
+    pub success: bool,
+    pub failure_kind: Option<RouteFailureKind>,
+    pub latency_ms: u64,
+    pub age_secs: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteHealthStatus {
+    Healthy,
+    Degraded,
+    RateLimited,
+    CircuitOpen,
+}
+
+impl RouteHealthStatus {
+    pub fn is_usable(self) -> bool {
+        matches!(self, Self::Healthy | Self::Degraded)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteHealth {
+    pub attempts: usize,
+    pub failures: usize,
+    pub error_rate: f64,
+    pub p50_latency_ms: Option<u64>,
+    pub p95_latency_ms: Option<u64>,
+    pub consecutive_failures: usize,
+    pub recently_rate_limited: bool,
+    pub status: RouteHealthStatus,
+}
+
+impl RouteHealth {
+    /// 由按时间倒序排列的样本计算滚动健康度
+    pub fn from_samples(samples: &[RouteHealthSample], policy: &CircuitBreakerPolicy) -> Self {
+        let attempts = samples.len();
+        let failures = samples.iter().filter(|sample| !sample.success).count();
+        let error_rate = if attempts == 0 {
+            0.0
+        } else {
+            failures as f64 / attempts as f64
+        };
+        let consecutive_failures = samples.iter().take_while(|sample| !sample.success).count();
+        let recently_rate_limited =
+            samples
+                .iter()
+                .take_while(|sample| !sample.success)
+                .any(|sample| {
+                    sample.failure_kind == Some(RouteFailureKind::RateLimit)
+                        && sample.age_secs < policy.rate_limit_cooldown_secs
+                });
+        let mut latencies = samples
+            .iter()
+            .filter(|sample| sample.success && sample.latency_ms > 0)
+            .map(|sample| sample.latency_ms)
+            .collect::<Vec<_>>();
+        latencies.sort_unstable();
+
+        let latest_failure_age = samples.first().map(|sample| sample.age_secs);
+        let circuit_tripped = consecutive_failures >= policy.failure_threshold;
+        let status =
+            if circuit_tripped && latest_failure_age.is_some_and(|age| age < policy.open_secs) {
+                RouteHealthStatus::CircuitOpen
+            } else if recently_rate_limited {
+                RouteHealthStatus::RateLimited
+            } else if circuit_tripped
+                || (attempts >= policy.min_samples && error_rate >= policy.degraded_error_rate)
+            {
+                RouteHealthStatus::Degraded
+            } else {
+                RouteHealthStatus::Healthy
+            };
+
+        Self {
+            attempts,
+            failures,
+            error_rate,
+            p50_latency_ms: percentile(&latencies, 50),
+            p95_latency_ms: percentile(&latencies, 95),
+            consecutive_failures,
+            recently_rate_limited,
+            status,
+        }
+    }
+}
+
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteDecisionReason {
+    ConfiguredOrder,
+    SkippedUnhealthy,
+    DemotedDegraded,
+    LatencyBalanced,
+    AllUnhealthy,
+}
+
+impl RouteDecisionReason {
+    pub fn as_key(self) -> &'static str {
+        match self {
+            Self::ConfiguredOrder => "configured_order",
+            Self::SkippedUnhealthy => "skipped_unhealthy",
+            Self::DemotedDegraded => "demoted_degraded",
+            Self::LatencyBalanced => "latency_balanced",
+            Self::AllUnhealthy => "all_unhealthy",
+        }
+    }
+}
+
+/// 路由决策：order 为候选下标的尝试顺序，skipped 为因熔断或限流冷却被跳过的候选
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteDecision {
+    pub order: Vec<usize>,
+    pub skipped: Vec<usize>,
+    pub reason: RouteDecisionReason,
+}
+
+/// 按健康度重排候选：跳过熔断/限流中的目标，降级目标排在健康目标之后；
+/// 开启负载均衡时，同一模型的等价目标按 p50 延迟排序。全部不可用时保持配置顺序。
+pub fn plan_route_order(
+    targets: &[RouteTarget],
+    health: &[Option<RouteHealth>],
+    load_balance: bool,
+) -> RouteDecision {
+    let status_of = |index: usize| {
+        health
+            .get(index)
+            .and_then(|entry| entry.as_ref())
+            .map(|entry| entry.status)
+            .unwrap_or(RouteHealthStatus::Healthy)
+    };
+    let configured = (0..targets.len()).collect::<Vec<_>>();
+    let (usable, skipped): (Vec<usize>, Vec<usize>) = configured
+        .iter()
+        .partition(|index| status_of(**index).is_usable());
+    if usable.is_empty() {
+        return RouteDecision {
+            order: configured,
+            skipped: Vec::new(),
+            reason: RouteDecisionReason::AllUnhealthy,
+        };
+    }
+
+    let (healthy, degraded): (Vec<usize>, Vec<usize>) = usable
+        .iter()
+        .partition(|index| status_of(**index) == RouteHealthStatus::Healthy);
+    let healthy_count = healthy.len();
+    let mut order = healthy;
+    order.extend(degraded);
+    let demoted = order != usable;
+    let balanced =
+        load_balance && balance_equivalent_targets(targets, health, &mut order[..healthy_count]);
+
+    let reason = if !skipped.is_empty() {
+        RouteDecisionReason::SkippedUnhealthy
+    } else if demoted {
+        RouteDecisionReason::DemotedDegraded
+    } else if balanced {
+        RouteDecisionReason::LatencyBalanced
+    } else {
+        RouteDecisionReason::ConfiguredOrder
+    };
+    RouteDecision {
+        order,
+        skipped,
+        reason,
+    }
+}
+
+/// 同一模型的等价目标在各自位置内按 p50 延迟升序重排，无延迟数据的排在后面
+fn balance_equivalent_targets(
+    targets: &[RouteTarget],
+    health: &[Option<RouteHealth>],
+    order: &mut [usize],
+) -> bool {
+    let latency_of = |index: usize| {
+        health
+            .get(index)
+            .and_then(|entry| entry.as_ref())
+            .and_then(|entry| entry.p50_latency_ms)
+            .unwrap_or(u64::MAX)
+    };
+    let original = order.to_vec();
+    let mut visited = vec![false; order.len()];
+    for start in 0..order.len() {
+        if visited[start] {
+            continue;
+        }
+        let model = &targets[original[start]].model;
+        let slots = (start..order.len())
+            .filter(|slot| &targets[original[*slot]].model == model)
+            .collect::<Vec<_>>();
+        let mut members = slots.iter().map(|slot| original[*slot]).collect::<Vec<_>>();
+        members.sort_by_key(|index| latency_of(*index));
+        for (slot, member) in slots.iter().zip(members) {
+            visited[*slot] = true;
+            order[*slot] = member;
+        }
+    }
+    order != original.as_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(provider_id: &str, model: &str) -> RouteTarget {
+        RouteTarget {
+            provider_id: provider_id.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    fn failure(kind: RouteFailureKind, age_secs: i64) -> RouteHealthSample {
+        RouteHealthSample {
+            success: false,
+            failure_kind: Some(kind),
+            latency_ms: 0,
+            age_secs,
+        }
+    }
+
+    fn success(latency_ms: u64, age_secs: i64) -> RouteHealthSample {
+        RouteHealthSample {
+            success: true,
+            failure_kind: None,
+            latency_ms,
+            age_secs,
+        }
+    }
+
+    fn health(samples: &[RouteHealthSample]) -> Option<RouteHealth> {
+        Some(RouteHealth::from_samples(
+            samples,
+            &CircuitBreakerPolicy::default(),
+        ))
+    }
+
+    #[test]
+    fn auth_failure_skips_targets_on_the_same_provider() {
+        let policy = RoutingPolicy {
+            capability: "chat".to_string(),
+            primary: target("deepseek", "deepseek-chat"),
+            fallbacks: vec![
+                target("deepseek", "deepseek-reasoner"),
+                target("qwen", "qwen-max"),
+            ],
+        };
+
+        let auth = route_with_fallback(&policy, Some(RouteFailureKind::Auth)).expect("auth");
+        assert_eq!(auth.provider_id, "qwen");
+        let timeout =
+            route_with_fallback(&policy, Some(RouteFailureKind::Timeout)).expect("timeout");
+        assert_eq!(timeout.model, "deepseek-reasoner");
+    }
+
+    #[test]
+    fn health_tracks_error_rate_latency_and_circuit_state() {
+        let policy = CircuitBreakerPolicy::default();
+        let samples = [
+            success(800, 10),
+            failure(RouteFailureKind::Timeout, 20),
+            success(400, 30),
+            success(1200, 40),
+        ];
+        let stats = RouteHealth::from_samples(&samples, &policy);
+        assert_eq!(stats.attempts, 4);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.p50_latency_ms, Some(800));
+        assert_eq!(stats.p95_latency_ms, Some(1200));
+        assert_eq!(stats.status, RouteHealthStatus::Healthy);
+
+        let tripped = [
+            failure(RouteFailureKind::Network, 5),
+            failure(RouteFailureKind::Network, 15),
+            failure(RouteFailureKind::Timeout, 25),
+        ];
+        assert_eq!(
+            RouteHealth::from_samples(&tripped, &policy).status,
+            RouteHealthStatus::CircuitOpen
+        );
+        let half_open = [
+            failure(RouteFailureKind::Network, 500),
+            failure(RouteFailureKind::Network, 510),
+            failure(RouteFailureKind::Timeout, 520),
+        ];
+        assert_eq!(
+            RouteHealth::from_samples(&half_open, &policy).status,
+            RouteHealthStatus::Degraded
+        );
+        let rate_limited = [failure(RouteFailureKind::RateLimit, 10), success(300, 60)];
+        assert_eq!(
+            RouteHealth::from_samples(&rate_limited, &policy).status,
+            RouteHealthStatus::RateLimited
+        );
+    }
+
+    #[test]
+    fn plan_route_order_skips_open_circuits_and_demotes_degraded_targets() {
+        let targets = [target("a", "m1"), target("b", "m2"), target("c", "m3")];
+        let open = [
+            failure(RouteFailureKind::Network, 1),
+            failure(RouteFailureKind::Network, 2),
+            failure(RouteFailureKind::Network, 3),
+        ];
+        let decision = plan_route_order(&targets, &[health(&open), None, None], false);
+        assert_eq!(decision.order, vec![1, 2]);
+        assert_eq!(decision.skipped, vec![0]);
+        assert_eq!(decision.reason, RouteDecisionReason::SkippedUnhealthy);
+
+        let flaky = [
+            failure(RouteFailureKind::Timeout, 1),
+            success(500, 2),
+            failure(RouteFailureKind::Timeout, 3),
+            failure(RouteFailureKind::Unknown, 4),
+        ];
+        let decision = plan_route_order(&targets, &[health(&flaky), None, None], false);
+        assert_eq!(decision.order, vec![1, 2, 0]);
+        assert_eq!(decision.reason, RouteDecisionReason::DemotedDegraded);
+
+        let decision = plan_route_order(
+            &targets,
+            &[health(&open), health(&open), health(&open)],
+            false,
+        );
+        assert_eq!(decision.order, vec![0, 1, 2]);
+        assert_eq!(decision.reason, RouteDecisionReason::AllUnhealthy);
+    }
+
+    #[test]
+    fn load_balance_prefers_faster_equivalent_target() {
+        let targets = [
+            target("a", "deepseek-chat"),
+            target("b", "qwen-max"),
+            target("c", "deepseek-chat"),
+        ];
+        let slow = [success(2_000, 1), success(2_200, 2)];
+        let fast = [success(300, 1), success(350, 2)];
+        let health = [health(&slow), None, health(&fast)];
+
+        let decision = plan_route_order(&targets, &health, true);
+        assert_eq!(decision.order, vec![2, 1, 0]);
+        assert_eq!(decision.reason, RouteDecisionReason::LatencyBalanced);
+
+        let decision = plan_route_order(&targets, &health, false);
+        assert_eq!(decision.order, vec![0, 1, 2]);
+        assert_eq!(decision.reason, RouteDecisionReason::ConfiguredOrder);
     }
 }
@@ -0,0 +1,40 @@
+use super::traits::ProviderPlugin;
+
+pub struct GeminiProvider {
+    key: &'static str,
+    name: &'static str,
+}
+
+impl GeminiProvider {
+    pub fn new() -> Self {
+        Self {
+            key: "gemini",
+            name: "Google Gemini",
+        }
+    }
+}
+
+impl Default for GeminiProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderPlugin for GeminiProvider {
+    fn key(&self) -> &str {
+        self.key
+    }
+
+    fn display_name(&self) -> &str {
+        self.name
+    }
+
+    fn capabilities(&self) -> Vec<String> {
+        vec![
+            "chat".to_string(),
+            "tool_calling".to_string(),
+            "vision".to_string(),
+            "long_context".to_string(),
+        ]
+    }
+}
@@ -1,12 +1,20 @@
 pub mod anthropic_compat;
 pub mod capability_router;
 pub mod deepseek;
+pub mod gemini;
 pub mod moonshot;
+pub mod ollama;
 pub mod openai_compat;
 pub mod qwen;
 pub mod registry;
 pub mod traits;
 
-pub use capability_router::{route_with_fallback, RouteFailureKind, RouteTarget, RoutingPolicy};
-pub use registry::ProviderRegistry;
+pub use capability_router::{
+    plan_route_order, route_with_fallback, CircuitBreakerPolicy, RouteDecision,
+    RouteDecisionReason, RouteFailureKind, RouteHealth, RouteHealthSample, RouteHealthStatus,
+    RouteTarget, RoutingPolicy,
+};
+pub use registry::{
+    model_adapters, register_model_adapter, ModelAdapterRegistry, ProviderRegistry,
+};
 pub use traits::ProviderPlugin;
@@ -0,0 +1,40 @@
+use super::traits::ProviderPlugin;
+
+pub struct OllamaProvider {
+    key: &'static str,
+    name: &'static str,
+}
+
+impl OllamaProvider {
+    pub fn new() -> Self {
+        Self {
+            key: "ollama",
+            name: "Ollama（本地模型）",
+        }
+    }
+}
+
+impl Default for OllamaProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderPlugin for OllamaProvider {
+    fn key(&self) -> &str {
+        self.key
+    }
+
+    fn display_name(&self) -> &str {
+        self.name
+    }
+
+    fn capabilities(&self) -> Vec<String> {
+        vec![
+            "chat".to_string(),
+            "tool_calling".to_string(),
+            "vision".to_string(),
+            "local".to_string(),
+        ]
+    }
+}
@@ -1,9 +1,26 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{anyhow, Result};
+
+use crate::adapters::anthropic::AnthropicMessagesAdapter;
+use crate::adapters::gemini::GeminiAdapter;
+use crate::adapters::mock::MockModelAdapter;
+use crate::adapters::ollama::OllamaAdapter;
+use crate::adapters::openai::OpenAiCompatAdapter;
+use crate::adapters::{
+    ModelAdapter, ModelAdapterCapabilities, ModelChatRequest, ModelChatResponse,
+    ModelConnectionRequest,
+};
+use crate::agent::types::StreamDelta;
+use crate::model_transport::ResolvedModelTransport;
+use crate::model_usage::{record_model_usage_with_pool, ModelUsageRecordInput};
 
 use super::anthropic_compat::AnthropicCompatProvider;
 use super::deepseek::DeepSeekProvider;
+use super::gemini::GeminiProvider;
 use super::moonshot::MoonshotProvider;
+use super::ollama::OllamaProvider;
 use super::qwen::QwenProvider;
 use super::traits::ProviderPlugin;
 
@@ -43,6 +60,119 @@ impl ProviderRegistry {
         registry.register(Arc::new(QwenProvider::new()));
         registry.register(Arc::new(MoonshotProvider::new()));
         registry.register(Arc::new(AnthropicCompatProvider::new()));
+        registry.register(Arc::new(GeminiProvider::new()));
+        registry.register(Arc::new(OllamaProvider::new()));
         registry
     }
 }
+
+/// 模型协议 adapter 注册表。后注册的 adapter 优先匹配，
+/// 因此 mock / 脚本化 adapter 可以按 base_url 覆盖协议 adapter。
+#[derive(Default, Clone)]
+pub struct ModelAdapterRegistry {
+    adapters: Vec<Arc<dyn ModelAdapter>>,
+}
+
+impl ModelAdapterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_builtin_adapters() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(OpenAiCompatAdapter));
+        registry.register(Arc::new(AnthropicMessagesAdapter));
+        registry.register(Arc::new(GeminiAdapter));
+        registry.register(Arc::new(OllamaAdapter));
+        registry.register(Arc::new(MockModelAdapter));
+        registry
+    }
+
+    pub fn register(&mut self, adapter: Arc<dyn ModelAdapter>) {
+        self.adapters.push(adapter);
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.adapters
+            .iter()
+            .map(|adapter| adapter.key().to_string())
+            .collect()
+    }
+
+    pub fn resolve(
+        &self,
+        transport: &ResolvedModelTransport,
+        base_url: &str,
+    ) -> Result<Arc<dyn ModelAdapter>> {
+        self.adapters
+            .iter()
+            .rev()
+            .find(|adapter| adapter.supports(transport, base_url))
+            .map(Arc::clone)
+            .ok_or_else(|| anyhow!("没有可处理该模型协议的 adapter: {:?}", transport.kind))
+    }
+
+    pub fn capabilities(
+        &self,
+        transport: &ResolvedModelTransport,
+        base_url: &str,
+    ) -> Result<ModelAdapterCapabilities> {
+        Ok(self.resolve(transport, base_url)?.capabilities(transport))
+    }
+
+    /// 分发模型调用；请求带用量归属时在这里统一记账，压缩、翻译等旁路调用也会被计入
+    pub async fn chat_stream(
+        &self,
+        request: ModelChatRequest<'_>,
+        on_token: &mut (dyn FnMut(StreamDelta) + Send),
+    ) -> Result<ModelChatResponse> {
+        let adapter = self.resolve(request.transport, request.base_url)?;
+        let (usage_scope, base_url, model) = (request.usage_scope, request.base_url, request.model);
+        let api_format = request.transport.kind.api_format();
+        let response = adapter.chat_stream(request, on_token).await?;
+        if let (Some(scope), Some(usage)) = (usage_scope, response.usage) {
+            if let Err(err) = record_model_usage_with_pool(
+                scope.pool,
+                ModelUsageRecordInput {
+                    session_id: scope.session_id,
+                    run_id: scope.run_id,
+                    base_url,
+                    api_format,
+                    model_name: model,
+                    usage,
+                },
+            )
+            .await
+            {
+                eprintln!("[model-usage] 记录模型用量失败: {}", err);
+            }
+        }
+        Ok(response)
+    }
+
+    pub async fn test_connection(&self, request: ModelConnectionRequest<'_>) -> Result<bool> {
+        let adapter = self.resolve(request.transport, request.base_url)?;
+        adapter.test_connection(request).await
+    }
+}
+
+fn shared_model_adapter_registry() -> &'static RwLock<ModelAdapterRegistry> {
+    static REGISTRY: OnceLock<RwLock<ModelAdapterRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(ModelAdapterRegistry::with_builtin_adapters()))
+}
+
+/// 进程内共享的 adapter 注册表快照，模型调用统一经此分发
+pub fn model_adapters() -> ModelAdapterRegistry {
+    shared_model_adapter_registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// 向共享注册表追加 adapter（新协议或测试用的脚本化 adapter）
+pub fn register_model_adapter(adapter: Arc<dyn ModelAdapter>) {
+    shared_model_adapter_registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .register(adapter);
+}
@@ -0,0 +1,211 @@
+use crate::providers::capability_router::{
+    plan_route_order, CircuitBreakerPolicy, RouteFailureKind, RouteHealth, RouteHealthSample,
+    RouteTarget,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// app_settings 中的负载均衡开关：开启后同一模型的等价目标按延迟优先
+pub const ROUTE_LOAD_BALANCE_SETTING_KEY: &str = "route_load_balance";
+
+const ROUTE_HEALTH_WINDOW_HOURS: i64 = 24;
+const ROUTE_HEALTH_SAMPLE_LIMIT: i64 = 50;
+
+/// 路由候选：(provider_key, api_format, base_url, model_name, api_key)
+pub type RouteCandidate = (String, String, String, String, String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteHealthEntry {
+    pub base_url: String,
+    pub model_name: String,
+    pub health: RouteHealth,
+}
+
+/// 本轮模型路由决策，随会话事件落盘
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelRouteDecisionRecord {
+    pub capability: String,
+    pub candidate_count: usize,
+    pub skipped_count: usize,
+    pub selected_provider: String,
+    pub selected_model: String,
+    pub decision_reason: String,
+}
+
+fn parse_health_sample(
+    success: bool,
+    error_kind: &str,
+    latency_ms: i64,
+    created_at: &str,
+    now: DateTime<Utc>,
+) -> Option<RouteHealthSample> {
+    let failure_kind = if success {
+        None
+    } else {
+        // 上下文超限、策略拦截等不是供应商的问题，不计入健康度
+        Some(RouteFailureKind::from_error_kind_key(error_kind)?)
+    };
+    let recorded_at = DateTime::parse_from_rfc3339(created_at).ok()?;
+    Some(RouteHealthSample {
+        success,
+        failure_kind,
+        latency_ms: latency_ms.max(0) as u64,
+        age_secs: (now - recorded_at.with_timezone(&Utc)).num_seconds().max(0),
+    })
+}
+
+/// 读取某个供应商端点 + 模型最近的尝试记录并计算健康度；没有样本时返回 None
+pub async fn load_route_health_with_pool(
+    pool: &SqlitePool,
+    base_url: &str,
+    model_name: &str,
+) -> Result<Option<RouteHealth>, String> {
+    let now = Utc::now();
+    let cutoff = (now - Duration::hours(ROUTE_HEALTH_WINDOW_HOURS)).to_rfc3339();
+    let rows = sqlx::query_as::<_, (bool, String, i64, String)>(
+        "SELECT CAST(success AS BOOLEAN), error_kind, latency_ms, created_at
+         FROM route_attempt_logs
+         WHERE base_url = ? AND model_name = ? AND created_at >= ?
+         ORDER BY created_at DESC
+         LIMIT ?",
+    )
+    .bind(base_url)
+    .bind(model_name)
+    .bind(cutoff)
+    .bind(ROUTE_HEALTH_SAMPLE_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取路由健康度失败: {e}"))?;
+
+    let samples = rows
+        .iter()
+        .filter_map(|(success, error_kind, latency_ms, created_at)| {
+            parse_health_sample(*success, error_kind, *latency_ms, created_at, now)
+        })
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(RouteHealth::from_samples(
+        &samples,
+        &CircuitBreakerPolicy::default(),
+    )))
+}
+
+pub async fn list_route_health_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<RouteHealthEntry>, String> {
+    let cutoff = (Utc::now() - Duration::hours(ROUTE_HEALTH_WINDOW_HOURS)).to_rfc3339();
+    let targets = sqlx::query_as::<_, (String, String)>(
+        "SELECT DISTINCT base_url, model_name
+         FROM route_attempt_logs
+         WHERE base_url != '' AND created_at >= ?
+         ORDER BY base_url, model_name",
+    )
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取路由健康度失败: {e}"))?;
+
+    let mut entries = Vec::with_capacity(targets.len());
+    for (base_url, model_name) in targets {
+        if let Some(health) = load_route_health_with_pool(pool, &base_url, &model_name).await? {
+            entries.push(RouteHealthEntry {
+                base_url,
+                model_name,
+                health,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+pub async fn get_route_load_balance_enabled_with_pool(pool: &SqlitePool) -> Result<bool, String> {
+    let value =
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ? LIMIT 1")
+            .bind(ROUTE_LOAD_BALANCE_SETTING_KEY)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("读取路由负载均衡设置失败: {e}"))?;
+    Ok(value.as_deref().map(str::trim) == Some("true"))
+}
+
+pub async fn set_route_load_balance_enabled_with_pool(
+    pool: &SqlitePool,
+    enabled: bool,
+) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+        .bind(ROUTE_LOAD_BALANCE_SETTING_KEY)
+        .bind(enabled.to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("保存路由负载均衡设置失败: {e}"))?;
+    Ok(())
+}
+
+/// 按滚动健康度重排本轮路由候选，返回重排后的候选与决策记录
+pub async fn plan_route_candidates_with_pool(
+    pool: &SqlitePool,
+    capability: &str,
+    candidates: Vec<RouteCandidate>,
+) -> Result<(Vec<RouteCandidate>, ModelRouteDecisionRecord), String> {
+    let targets = candidates
+        .iter()
+        .map(|(_, _, base_url, model_name, _)| RouteTarget {
+            provider_id: base_url.clone(),
+            model: model_name.clone(),
+        })
+        .collect::<Vec<_>>();
+    let mut health = Vec::with_capacity(targets.len());
+    for target in &targets {
+        health.push(load_route_health_with_pool(pool, &target.provider_id, &target.model).await?);
+    }
+    let load_balance = get_route_load_balance_enabled_with_pool(pool).await?;
+    let decision = plan_route_order(&targets, &health, load_balance);
+
+    let mut slots = candidates.into_iter().map(Some).collect::<Vec<_>>();
+    let ordered = decision
+        .order
+        .iter()
+        .filter_map(|index| slots.get_mut(*index).and_then(Option::take))
+        .collect::<Vec<_>>();
+    let (selected_provider, selected_model) = ordered
+        .first()
+        .map(|(provider_key, _, base_url, model_name, _)| {
+            let provider = if provider_key.trim().is_empty() {
+                base_url
+            } else {
+                provider_key
+            };
+            (provider.clone(), model_name.clone())
+        })
+        .unwrap_or_default();
+    let record = ModelRouteDecisionRecord {
+        capability: capability.to_string(),
+        candidate_count: targets.len(),
+        skipped_count: decision.skipped.len(),
+        selected_provider,
+        selected_model,
+        decision_reason: decision.reason.as_key().to_string(),
+    };
+    Ok((ordered, record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_provider_failures_are_ignored_in_health_samples() {
+        let now = Utc::now();
+        let created_at = (now - Duration::seconds(30)).to_rfc3339();
+
+        let sample =
+            parse_health_sample(false, "rate_limit", 0, &created_at, now).expect("rate limit");
+        assert_eq!(sample.failure_kind, Some(RouteFailureKind::RateLimit));
+        assert_eq!(sample.age_secs, 30);
+        assert!(parse_health_sample(false, "context_overflow", 0, &created_at, now).is_none());
+        assert!(parse_health_sample(true, "ok", 420, "not a date", now).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::agent::run_guard::RunResourceLimits;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmployeeRunBudget {
+    pub employee_id: String,
+    pub limits: RunResourceLimits,
+    pub updated_at: String,
+}
+
+#[derive(Debug, FromRow)]
+struct EmployeeRunBudgetRow {
+    employee_id: String,
+    max_input_tokens: Option<i64>,
+    max_output_tokens: Option<i64>,
+    max_cost: Option<f64>,
+    max_wall_clock_secs: Option<i64>,
+    max_tool_calls: Option<i64>,
+    warning_ratio: Option<f64>,
+    updated_at: String,
+}
+
+impl From<EmployeeRunBudgetRow> for EmployeeRunBudget {
+    fn from(row: EmployeeRunBudgetRow) -> Self {
+        let to_u64 = |value: Option<i64>| value.and_then(|value| u64::try_from(value).ok());
+        Self {
+            employee_id: row.employee_id,
+            limits: RunResourceLimits {
+                max_input_tokens: to_u64(row.max_input_tokens),
+                max_output_tokens: to_u64(row.max_output_tokens),
+                max_cost: row.max_cost,
+                max_wall_clock_secs: to_u64(row.max_wall_clock_secs),
+                max_tool_calls: to_u64(row.max_tool_calls),
+                warning_ratio: row.warning_ratio,
+            },
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+fn validate_limits(limits: &RunResourceLimits) -> Result<(), String> {
+    if limits
+        .max_cost
+        .is_some_and(|cost| !cost.is_finite() || cost < 0.0)
+    {
+        return Err("费用上限必须是非负数".to_string());
+    }
+    if limits
+        .warning_ratio
+        .is_some_and(|ratio| !ratio.is_finite() || ratio <= 0.0 || ratio >= 1.0)
+    {
+        return Err("预警比例必须介于 0 与 1 之间".to_string());
+    }
+    Ok(())
+}
+
+pub async fn get_employee_run_budget_with_pool(
+    pool: &SqlitePool,
+    employee_id: &str,
+) -> Result<Option<EmployeeRunBudget>, String> {
+    let row = sqlx::query_as::<_, EmployeeRunBudgetRow>(
+        "SELECT employee_id, max_input_tokens, max_output_tokens, max_cost,
+                max_wall_clock_secs, max_tool_calls, warning_ratio, updated_at
+         FROM employee_run_budgets
+         WHERE employee_id = ?
+         LIMIT 1",
+    )
+    .bind(employee_id.trim())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("读取员工运行预算失败: {e}"))?;
+    Ok(row.map(EmployeeRunBudget::from))
+}
+
+/// 保存员工运行预算；所有维度都不设限时删除该配置
+pub async fn save_employee_run_budget_with_pool(
+    pool: &SqlitePool,
+    employee_id: &str,
+    limits: RunResourceLimits,
+) -> Result<Option<EmployeeRunBudget>, String> {
+    let employee_id = employee_id.trim();
+    if employee_id.is_empty() {
+        return Err("员工编号不能为空".to_string());
+    }
+    validate_limits(&limits)?;
+    if limits.is_unlimited() {
+        sqlx::query("DELETE FROM employee_run_budgets WHERE employee_id = ?")
+            .bind(employee_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("删除员工运行预算失败: {e}"))?;
+        return Ok(None);
+    }
+
+    let to_i64 = |value: Option<u64>| value.map(|value| i64::try_from(value).unwrap_or(i64::MAX));
+    sqlx::query(
+        "INSERT INTO employee_run_budgets (
+            employee_id, max_input_tokens, max_output_tokens, max_cost,
+            max_wall_clock_secs, max_tool_calls, warning_ratio, updated_at
+         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(employee_id) DO UPDATE SET
+            max_input_tokens = excluded.max_input_tokens,
+            max_output_tokens = excluded.max_output_tokens,
+            max_cost = excluded.max_cost,
+            max_wall_clock_secs = excluded.max_wall_clock_secs,
+            max_tool_calls = excluded.max_tool_calls,
+            warning_ratio = excluded.warning_ratio,
+            updated_at = excluded.updated_at",
+    )
+    .bind(employee_id)
+    .bind(to_i64(limits.max_input_tokens))
+    .bind(to_i64(limits.max_output_tokens))
+    .bind(limits.max_cost)
+    .bind(to_i64(limits.max_wall_clock_secs))
+    .bind(to_i64(limits.max_tool_calls))
+    .bind(limits.warning_ratio)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("保存员工运行预算失败: {e}"))?;
+
+    get_employee_run_budget_with_pool(pool, employee_id).await
+}
+
+/// 按会话所属员工读取运行预算；会话未绑定员工或员工未配置时不设限
+pub async fn load_session_run_budget_with_pool(
+    pool: &SqlitePool,
+    session_id: &str,
+) -> Result<RunResourceLimits, String> {
+    let employee_id =
+        sqlx::query_scalar::<_, String>("SELECT employee_id FROM sessions WHERE id = ? LIMIT 1")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("读取会话员工失败: {e}"))?
+            .unwrap_or_default();
+    if employee_id.trim().is_empty() {
+        return Ok(RunResourceLimits::default());
+    }
+    Ok(get_employee_run_budget_with_pool(pool, &employee_id)
+        .await?
+        .map(|budget| budget.limits)
+        .unwrap_or_default())
+}
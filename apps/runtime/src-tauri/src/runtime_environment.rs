@@ -1,5 +1,5 @@
 use crate::runtime_bootstrap::{
-    discover_runtime_root_bootstrap, read_runtime_root_bootstrap,
+    default_runtime_root_bootstrap, discover_runtime_root_bootstrap, read_runtime_root_bootstrap,
     resolve_runtime_bootstrap_location, RuntimeBootstrapLocation, RuntimeRootBootstrap,
 };
 use crate::runtime_paths::{resolve_runtime_root, RuntimePaths};
@@ -119,6 +119,23 @@ pub fn initialize_runtime_environment(app: &AppHandle) -> Result<RuntimeEnvironm
     )
 }
 
+/// 无窗口运行（workclaw 命令行）时的运行时环境：与桌面端读取同一份 bootstrap，
+/// 默认共用同一个数据库和运行时根目录；显式指定 root 时直接使用该目录。
+#[cfg_attr(not(feature = "headless-cli"), allow(dead_code))]
+pub fn initialize_headless_runtime_environment(
+    runtime_root: Option<PathBuf>,
+) -> Result<RuntimeEnvironment, String> {
+    let bootstrap_location = resolve_runtime_bootstrap_location();
+    if let Some(root) = runtime_root {
+        return Ok(RuntimeEnvironment {
+            bootstrap_location,
+            bootstrap: default_runtime_root_bootstrap(&root),
+            paths: RuntimePaths::new(root),
+        });
+    }
+    initialize_runtime_environment_with_inputs(None, bootstrap_location, resolve_runtime_root())
+}
+
 pub fn runtime_paths_from_app(app: &AppHandle) -> Result<RuntimePaths, String> {
     if let Ok(environment) = runtime_environment_from_app(app) {
         return Ok(environment.paths.clone());
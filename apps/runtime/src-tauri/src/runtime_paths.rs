@@ -44,6 +44,8 @@ pub struct RuntimePaths {
     pub market_skills_dir: PathBuf,
     pub plugins: RuntimePluginPaths,
     pub workspace_dir: PathBuf,
+    /// 本机信任的技能包发布者公钥
+    pub trusted_publishers_path: PathBuf,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -103,6 +105,7 @@ impl RuntimePaths {
             skills_vendor_dir: skills_dir.join("vendor"),
         };
         let workspace_dir = root.join("workspace");
+        let trusted_publishers_path = root.join("trusted_publishers.json");
 
         Self {
             root,
@@ -117,6 +120,7 @@ impl RuntimePaths {
             market_skills_dir,
             plugins,
             workspace_dir,
+            trusted_publishers_path,
         }
     }
 }
@@ -210,6 +214,7 @@ mod tests {
         assert!(paths.plugins.root.starts_with(&paths.root));
         assert!(paths.plugins.fixture_dir.starts_with(&paths.root));
         assert!(paths.workspace_dir.starts_with(&paths.root));
+        assert!(paths.trusted_publishers_path.starts_with(&paths.root));
     }
 
     #[test]
@@ -0,0 +1,245 @@
+//! 定时/周期任务：按 cron 表达式或一次性时间点启动员工或技能会话，
+//! 任务与运行历史持久化在 SQLite，结果可投递到已知的 IM 渠道路由。
+
+mod cron;
+mod runner;
+mod store;
+
+pub use cron::CronSchedule;
+pub use runner::{
+    run_scheduled_job_now, spawn_scheduled_job_scheduler, ScheduledJobSchedulerState,
+    SCHEDULED_JOB_RUN_UPDATED_EVENT,
+};
+pub use store::{
+    begin_manual_scheduled_job_run_with_pool, claim_due_scheduled_jobs_with_pool,
+    delete_scheduled_job_with_pool, finish_scheduled_job_run_with_pool,
+    get_scheduled_job_with_pool, list_scheduled_job_runs_with_pool, list_scheduled_jobs_with_pool,
+    record_scheduled_job_run_delivery_with_pool, recover_interrupted_scheduled_job_runs_with_pool,
+    save_scheduled_job_with_pool, set_scheduled_job_enabled_with_pool,
+    set_scheduled_job_run_session_with_pool, ClaimedScheduledJobRun,
+};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 计划时间过后超过该宽限期才算“错过”，避免调度间隔本身被当成错过
+pub const MISSED_RUN_GRACE_SECONDS: i64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub name: String,
+    /// employee | skill
+    pub target_kind: String,
+    pub target_id: String,
+    pub prompt: String,
+    /// 为空时员工任务使用员工默认工作目录
+    pub work_dir: String,
+    /// standard | full_access
+    pub permission_mode: String,
+    /// 为空表示使用默认模型
+    pub model_id: String,
+    /// cron | once
+    pub schedule_kind: String,
+    pub cron_expr: String,
+    /// 一次性任务的运行时间，RFC3339（UTC）
+    pub run_at: String,
+    /// catch_up | skip：错过计划时间（休眠、应用未运行）后补跑一次还是跳过
+    pub missed_run_policy: String,
+    /// channel_delivery_routes.session_key，为空表示不投递
+    pub delivery_route_key: String,
+    pub enabled: i64,
+    /// RFC3339（UTC），为空表示不会再触发
+    pub next_run_at: String,
+    pub last_run_at: String,
+    pub last_status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduledJobInput {
+    /// 为空时新建任务
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub target_kind: String,
+    pub target_id: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub work_dir: String,
+    #[serde(default)]
+    pub permission_mode: String,
+    #[serde(default)]
+    pub model_id: String,
+    pub schedule_kind: String,
+    #[serde(default)]
+    pub cron_expr: String,
+    #[serde(default)]
+    pub run_at: String,
+    #[serde(default)]
+    pub missed_run_policy: String,
+    #[serde(default)]
+    pub delivery_route_key: String,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
+pub struct ScheduledJobRun {
+    pub id: String,
+    pub job_id: String,
+    /// schedule | catch_up | manual
+    pub trigger_kind: String,
+    pub scheduled_for: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// running | succeeded | failed | skipped
+    pub status: String,
+    pub session_id: String,
+    pub output: String,
+    pub error: String,
+    /// 空表示未配置投递；delivered | failed
+    pub delivery_status: String,
+    pub delivery_error: String,
+}
+
+/// 到期的一次计划触发该如何处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueOccurrencePlan {
+    /// schedule | catch_up；None 表示按策略跳过本次
+    pub trigger: Option<&'static str>,
+    /// 下一次触发时间；None 表示任务不再触发（一次性任务或 cron 不再命中）
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+/// 错过的多次 cron 触发只合并为一次补跑，下一次时间从当前时间重新计算
+pub fn plan_due_occurrence(
+    job: &ScheduledJob,
+    scheduled_for: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> DueOccurrencePlan {
+    let missed =
+        now.signed_duration_since(scheduled_for) > Duration::seconds(MISSED_RUN_GRACE_SECONDS);
+    let trigger = match (missed, job.missed_run_policy.as_str()) {
+        (false, _) => Some("schedule"),
+        (true, "skip") => None,
+        (true, _) => Some("catch_up"),
+    };
+    let next_run_at = match job.schedule_kind.as_str() {
+        "cron" => CronSchedule::parse(&job.cron_expr)
+            .ok()
+            .and_then(|schedule| next_cron_run_at(&schedule, now)),
+        _ => None,
+    };
+    DueOccurrencePlan {
+        trigger,
+        next_run_at,
+    }
+}
+
+/// cron 按本机时区解释（“每天 9 点”即本地 9 点），结果统一存为 UTC
+pub fn next_cron_run_at(schedule: &CronSchedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    schedule
+        .next_after(&after.with_timezone(&Local))
+        .map(|value| value.with_timezone(&Utc))
+}
+
+/// 预览 cron 表达式接下来的若干次触发时间
+pub fn preview_cron_schedule(
+    expression: &str,
+    count: usize,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, String> {
+    let schedule = CronSchedule::parse(expression)?;
+    let mut cursor = now;
+    let mut fires = Vec::new();
+    while fires.len() < count {
+        let Some(next) = next_cron_run_at(&schedule, cursor) else {
+            break;
+        };
+        fires.push(next.to_rfc3339());
+        cursor = next;
+    }
+    if fires.is_empty() {
+        return Err(format!("cron 表达式在未来五年内不会触发: {expression}"));
+    }
+    Ok(fires)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(schedule_kind: &str, missed_run_policy: &str) -> ScheduledJob {
+        ScheduledJob {
+            id: "job-1".to_string(),
+            name: "日报".to_string(),
+            target_kind: "skill".to_string(),
+            target_id: "builtin-general".to_string(),
+            prompt: "总结今天的进展".to_string(),
+            work_dir: String::new(),
+            permission_mode: "standard".to_string(),
+            model_id: String::new(),
+            schedule_kind: schedule_kind.to_string(),
+            cron_expr: "* * * * *".to_string(),
+            run_at: String::new(),
+            missed_run_policy: missed_run_policy.to_string(),
+            delivery_route_key: String::new(),
+            enabled: 1,
+            next_run_at: String::new(),
+            last_run_at: String::new(),
+            last_status: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("parse time")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn on_time_occurrence_runs_and_advances_cron() {
+        let plan = plan_due_occurrence(
+            &job("cron", "catch_up"),
+            utc("2026-05-10T08:00:00Z"),
+            utc("2026-05-10T08:00:20Z"),
+        );
+        assert_eq!(plan.trigger, Some("schedule"));
+        assert_eq!(plan.next_run_at, Some(utc("2026-05-10T08:01:00Z")));
+    }
+
+    #[test]
+    fn missed_occurrences_are_coalesced_into_one_catch_up() {
+        let plan = plan_due_occurrence(
+            &job("cron", "catch_up"),
+            utc("2026-05-10T08:00:00Z"),
+            utc("2026-05-10T11:34:00Z"),
+        );
+        assert_eq!(plan.trigger, Some("catch_up"));
+        assert_eq!(plan.next_run_at, Some(utc("2026-05-10T11:35:00Z")));
+    }
+
+    #[test]
+    fn missed_occurrence_is_skipped_by_policy_and_one_shot_does_not_repeat() {
+        let skipped = plan_due_occurrence(
+            &job("cron", "skip"),
+            utc("2026-05-10T08:00:00Z"),
+            utc("2026-05-10T09:00:00Z"),
+        );
+        assert_eq!(skipped.trigger, None);
+        assert!(skipped.next_run_at.is_some());
+
+        let once = plan_due_occurrence(
+            &job("once", "catch_up"),
+            utc("2026-05-10T08:00:00Z"),
+            utc("2026-05-10T08:00:05Z"),
+        );
+        assert_eq!(once.trigger, Some("schedule"));
+        assert_eq!(once.next_run_at, None);
+    }
+}
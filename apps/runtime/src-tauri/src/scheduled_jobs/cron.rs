@@ -0,0 +1,296 @@
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+};
+
+/// 向后最多搜索的天数；超过仍找不到触发时间的表达式（如 2 月 31 日）视为永不触发
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// 标准 5 段 cron 表达式（分 时 日 月 周），支持 `*`、列表、范围、步长、
+/// 月份/星期英文缩写，以及 `@hourly`、`@daily`、`@weekly`、`@monthly`、`@yearly` 宏。
+/// 日与周同时受限时按 Vixie cron 语义取并集。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+struct FieldSpec {
+    label: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+    /// 名称对应的起始数值（月份从 1 开始，星期从 0 开始）
+    name_offset: u32,
+}
+
+const MINUTE_FIELD: FieldSpec = FieldSpec {
+    label: "分钟",
+    min: 0,
+    max: 59,
+    names: &[],
+    name_offset: 0,
+};
+const HOUR_FIELD: FieldSpec = FieldSpec {
+    label: "小时",
+    min: 0,
+    max: 23,
+    names: &[],
+    name_offset: 0,
+};
+const DAY_OF_MONTH_FIELD: FieldSpec = FieldSpec {
+    label: "日期",
+    min: 1,
+    max: 31,
+    names: &[],
+    name_offset: 0,
+};
+const MONTH_FIELD: FieldSpec = FieldSpec {
+    label: "月份",
+    min: 1,
+    max: 12,
+    names: MONTH_NAMES,
+    name_offset: 1,
+};
+// 星期允许写 7 表示周日，解析后统一折算到 0
+const DAY_OF_WEEK_FIELD: FieldSpec = FieldSpec {
+    label: "星期",
+    min: 0,
+    max: 7,
+    names: WEEKDAY_NAMES,
+    name_offset: 0,
+};
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(format!("不支持的 cron 宏: {expression}"));
+            }
+            _ => expression,
+        };
+        let fields = expanded.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(format!(
+                "cron 表达式需要 5 段（分 时 日 月 周），实际为 {} 段: {expression}",
+                fields.len()
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, &DAY_OF_WEEK_FIELD)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, &MINUTE_FIELD)?,
+            hours: parse_field(hour, &HOUR_FIELD)?,
+            days_of_month: parse_field(day_of_month, &DAY_OF_MONTH_FIELD)?,
+            months: parse_field(month, &MONTH_FIELD)?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+
+    /// 严格晚于 `after` 的下一次触发时间（按 `after` 所在时区的本地时间匹配）。
+    /// 夏令时跳过的本地时间不触发，重复的本地时间只触发较早的一次。
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local();
+        let mut candidate = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_SEARCH_DAYS);
+
+        while candidate <= limit {
+            if !contains(self.months, candidate.month()) {
+                candidate = start_of_next_month(candidate)?;
+                continue;
+            }
+            if !self.day_matches(candidate.date()) {
+                candidate = (candidate.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !contains(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !contains(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            let resolved = match timezone.from_local_datetime(&candidate) {
+                LocalResult::Single(value) => Some(value),
+                LocalResult::Ambiguous(earliest, _) => Some(earliest).filter(|value| value > after),
+                LocalResult::None => None,
+            };
+            if let Some(value) = resolved {
+                return Some(value);
+            }
+            candidate += Duration::minutes(1);
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn contains(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn start_of_next_month(value: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (year, month) = if value.month() == 12 {
+        (value.year() + 1, 1)
+    } else {
+        (value.year(), value.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+fn parse_field(raw: &str, spec: &FieldSpec) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for item in raw.split(',') {
+        let invalid = || format!("cron {}字段无效: {raw}", spec.label);
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<u32>().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (item, None),
+        };
+        let (start, end) = if range == "*" {
+            (spec.min, spec.max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, spec)?, parse_value(end, spec)?)
+        } else {
+            let value = parse_value(range, spec)?;
+            // `5/15` 表示从 5 开始每 15 个单位
+            (value, if step.is_some() { spec.max } else { value })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        let step = step.unwrap_or(1) as usize;
+        for value in (start..=end).step_by(step) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, spec: &FieldSpec) -> Result<u32, String> {
+    let lowered = raw.to_ascii_lowercase();
+    let value = match spec.names.iter().position(|name| *name == lowered) {
+        Some(index) => index as u32 + spec.name_offset,
+        None => raw
+            .parse::<u32>()
+            .map_err(|_| format!("cron {}字段无效: {raw}", spec.label))?,
+    };
+    if value < spec.min || value > spec.max {
+        return Err(format!(
+            "cron {}超出范围 {}-{}: {raw}",
+            spec.label, spec.min, spec.max
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CronSchedule;
+    use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("parse time")
+            .with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        CronSchedule::parse(expression)
+            .expect("parse cron")
+            .next_after(&utc(after))
+            .expect("next fire time")
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn next_after_handles_steps_ranges_lists_and_names() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-05-10T08:07:30Z"),
+            "2026-05-10T08:15:00+00:00"
+        );
+        assert_eq!(
+            next("0 9 * * MON-FRI", "2026-05-08T09:00:00Z"),
+            "2026-05-11T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("30 18 1,15 * *", "2026-05-02T00:00:00Z"),
+            "2026-05-15T18:30:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 1 jan *", "2026-05-02T00:00:00Z"),
+            "2027-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("@hourly", "2026-05-10T23:59:00Z"),
+            "2026-05-11T00:00:00+00:00"
+        );
+        // 日与周同时受限时取并集：每月 13 日或每个周五
+        assert_eq!(
+            next("0 12 13 * 5", "2026-05-09T00:00:00Z"),
+            "2026-05-13T12:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-05-09T00:00:00Z"),
+            "2026-05-10T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn next_after_matches_local_time_of_the_given_timezone() {
+        let shanghai = FixedOffset::east_opt(8 * 3600).unwrap();
+        let after = shanghai.with_ymd_and_hms(2026, 5, 10, 9, 30, 0).unwrap();
+        let fired = CronSchedule::parse("0 9 * * *")
+            .unwrap()
+            .next_after(&after)
+            .unwrap();
+        assert_eq!(fired.to_rfc3339(), "2026-05-11T09:00:00+08:00");
+    }
+
+    #[test]
+    fn rejects_invalid_and_impossible_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 * * funday").is_err());
+        assert!(CronSchedule::parse("@every_minute").is_err());
+        let never = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert!(never.next_after(&utc("2026-01-01T00:00:00Z")).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+use super::store::{
+    begin_manual_scheduled_job_run_with_pool, claim_due_scheduled_jobs_with_pool,
+    finish_scheduled_job_run_with_pool, record_scheduled_job_run_delivery_with_pool,
+    recover_interrupted_scheduled_job_runs_with_pool, set_scheduled_job_run_session_with_pool,
+    ClaimedScheduledJobRun,
+};
+use super::{ScheduledJob, ScheduledJobRun};
+use crate::agent::runtime::approval_gate::UnattendedSessionGuard;
+use crate::agent::runtime::runtime_io::{
+    extract_assistant_text_content, insert_session_message_with_pool,
+};
+use crate::agent::runtime::{SessionAdmissionGateState, SessionRuntime};
+use crate::agent::AgentExecutor;
+use crate::session_journal::SessionJournalStateHandle;
+use chrono::Utc;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEDULED_JOB_RUN_UPDATED_EVENT: &str = "scheduled-job-run-updated";
+
+const SCHEDULER_TICK_SECONDS: u64 = 30;
+const SCHEDULER_INITIAL_DELAY_SECONDS: u64 = 15;
+const FALLBACK_SKILL_ID: &str = "builtin-general";
+/// 单次运行的时长上限；超时后请求取消，并给执行器一段时间在检查点收尾
+const SCHEDULED_RUN_TIMEOUT_SECONDS: u64 = 30 * 60;
+const SCHEDULED_RUN_CANCEL_GRACE_SECONDS: u64 = 30;
+
+#[derive(Clone, Default)]
+pub struct ScheduledJobSchedulerState(pub Arc<AtomicBool>);
+
+/// 启动后台调度：先回收上次退出时中断的运行，再按固定间隔认领到期任务。
+/// 间隔内发生的休眠/唤醒由到期判断和错过策略统一处理。
+pub fn spawn_scheduled_job_scheduler(
+    app: AppHandle,
+    pool: SqlitePool,
+    scheduler: ScheduledJobSchedulerState,
+) {
+    tauri::async_runtime::spawn(async move {
+        match recover_interrupted_scheduled_job_runs_with_pool(&pool).await {
+            Ok(0) => {}
+            Ok(count) => eprintln!("[scheduled-jobs] 已标记 {count} 个中断的运行记录"),
+            Err(error) => eprintln!("[scheduled-jobs] {error}"),
+        }
+
+        tokio::time::sleep(Duration::from_secs(SCHEDULER_INITIAL_DELAY_SECONDS)).await;
+        loop {
+            if let Err(error) = run_due_scheduled_jobs(&app, &pool, &scheduler).await {
+                eprintln!("[scheduled-jobs] 调度失败: {error}");
+            }
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECONDS)).await;
+        }
+    });
+}
+
+async fn run_due_scheduled_jobs(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    scheduler: &ScheduledJobSchedulerState,
+) -> Result<usize, String> {
+    if scheduler.0.swap(true, Ordering::SeqCst) {
+        return Ok(0);
+    }
+    let claimed = claim_due_scheduled_jobs_with_pool(pool, Utc::now()).await;
+    scheduler.0.store(false, Ordering::SeqCst);
+
+    let claimed = claimed?;
+    let count = claimed.len();
+    for claimed_run in claimed {
+        spawn_scheduled_job_run(app.clone(), pool.clone(), claimed_run);
+    }
+    Ok(count)
+}
+
+/// 手动立即运行一次，返回刚创建的运行记录；运行本身在后台完成
+pub async fn run_scheduled_job_now(
+    app: AppHandle,
+    pool: SqlitePool,
+    job_id: &str,
+) -> Result<ScheduledJobRun, String> {
+    let claimed = begin_manual_scheduled_job_run_with_pool(&pool, job_id).await?;
+    let run = claimed.run.clone();
+    spawn_scheduled_job_run(app, pool, claimed);
+    Ok(run)
+}
+
+fn spawn_scheduled_job_run(app: AppHandle, pool: SqlitePool, claimed: ClaimedScheduledJobRun) {
+    let _ = app.emit(SCHEDULED_JOB_RUN_UPDATED_EVENT, &claimed.run);
+    tauri::async_runtime::spawn(async move {
+        execute_scheduled_job_run(&app, &pool, claimed).await;
+    });
+}
+
+async fn execute_scheduled_job_run(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    claimed: ClaimedScheduledJobRun,
+) {
+    let ClaimedScheduledJobRun { job, run } = claimed;
+    let result = run_scheduled_job_session(app, pool, &job, &run.id).await;
+    let (status, output, error) = match &result {
+        Ok((_, output)) => ("succeeded", output.as_str(), ""),
+        Err(error) => ("failed", "", error.as_str()),
+    };
+    let finished =
+        match finish_scheduled_job_run_with_pool(pool, &run.id, status, output, error).await {
+            Ok(finished) => finished,
+            Err(error) => {
+                eprintln!("[scheduled-jobs] {error}");
+                return;
+            }
+        };
+
+    let finished = if job.delivery_route_key.trim().is_empty() {
+        finished
+    } else {
+        let text = match &result {
+            Ok((_, output)) => output.clone(),
+            Err(error) => format!("定时任务「{}」运行失败: {error}", job.name),
+        };
+        let (delivery_status, delivery_error) =
+            match deliver_scheduled_job_result(pool, &job, &finished.session_id, &text).await {
+                Ok(()) => ("delivered", String::new()),
+                Err(error) => ("failed", error),
+            };
+        if let Err(error) = record_scheduled_job_run_delivery_with_pool(
+            pool,
+            &finished.id,
+            delivery_status,
+            &delivery_error,
+        )
+        .await
+        {
+            eprintln!("[scheduled-jobs] {error}");
+        }
+        ScheduledJobRun {
+            delivery_status: delivery_status.to_string(),
+            delivery_error,
+            ..finished
+        }
+    };
+    let _ = app.emit(SCHEDULED_JOB_RUN_UPDATED_EVENT, &finished);
+}
+
+/// 与外部 MCP 调用相同的会话启动流程：建会话、写入用户消息、跑一轮 agent，返回最终回复
+async fn run_scheduled_job_session(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    job: &ScheduledJob,
+    run_id: &str,
+) -> Result<(String, String), String> {
+    let model_id = match job.model_id.trim() {
+        "" => crate::commands::models::resolve_default_usable_model_id_with_pool(pool)
+            .await?
+            .ok_or_else(|| "没有可用的模型配置，请先在 WorkClaw 中配置模型".to_string())?,
+        model_id => model_id.to_string(),
+    };
+    let job_work_dir = Some(job.work_dir.trim().to_string()).filter(|dir| !dir.is_empty());
+
+    let (skill_id, work_dir, employee_id, session_mode) = match job.target_kind.as_str() {
+        "employee" => {
+            let employee = crate::commands::employee_agents::list_agent_employees_with_pool(pool)
+                .await?
+                .into_iter()
+                .find(|employee| {
+                    employee.employee_id.eq_ignore_ascii_case(&job.target_id)
+                        || employee.id == job.target_id
+                })
+                .ok_or_else(|| format!("员工不存在: {}", job.target_id))?;
+            if !employee.enabled {
+                return Err(format!("员工已停用: {}", job.target_id));
+            }
+            let skill_id = if employee.primary_skill_id.trim().is_empty() {
+                FALLBACK_SKILL_ID.to_string()
+            } else {
+                employee.primary_skill_id.clone()
+            };
+            let work_dir = job_work_dir.or_else(|| {
+                Some(employee.default_work_dir.trim().to_string()).filter(|dir| !dir.is_empty())
+            });
+            (
+                skill_id,
+                work_dir,
+                Some(employee.employee_id.clone()),
+                "employee_direct",
+            )
+        }
+        _ => {
+            let installed =
+                sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM installed_skills WHERE id = ?")
+                    .bind(&job.target_id)
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            if installed == 0 {
+                return Err(format!("技能不存在: {}", job.target_id));
+            }
+            (job.target_id.clone(), job_work_dir, None, "general")
+        }
+    };
+
+    let session_id = crate::commands::chat::create_session_with_pool(
+        pool,
+        skill_id,
+        model_id,
+        work_dir,
+        employee_id,
+        Some(format!("定时任务: {}", job.name)),
+        Some(job.permission_mode.clone()),
+        Some(session_mode.to_string()),
+        None,
+    )
+    .await?;
+    set_scheduled_job_run_session_with_pool(pool, run_id, &session_id).await?;
+
+    let admission_gate = app
+        .try_state::<SessionAdmissionGateState>()
+        .ok_or_else(|| "SessionAdmissionGateState unavailable".to_string())?;
+    let _admission_lease = admission_gate
+        .0
+        .try_acquire(&session_id)
+        .map_err(|conflict| conflict.to_string())?;
+
+    let parts = vec![json!({ "type": "text", "text": job.prompt })];
+    let parts_json = serde_json::to_string(&parts).map_err(|e| e.to_string())?;
+    let msg_id =
+        insert_session_message_with_pool(pool, &session_id, "user", &job.prompt, Some(&parts_json))
+            .await?;
+
+    let agent_executor = app.state::<Arc<AgentExecutor>>().inner().clone();
+    let journal = app.state::<SessionJournalStateHandle>().0.clone();
+    // 无人值守：需要审批的工具调用直接拒绝，也不占用桌面端的确认通道
+    let _unattended = UnattendedSessionGuard::register(&session_id);
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    // run 借用 session_id，放在块内让借用在返回前结束
+    {
+        let run = SessionRuntime::run_send_message(
+            app,
+            &agent_executor,
+            pool,
+            journal.as_ref(),
+            &session_id,
+            &msg_id,
+            &job.prompt,
+            &parts,
+            None,
+            Arc::clone(&cancel_flag),
+            Arc::new(Mutex::new(None)),
+        );
+        tokio::pin!(run);
+        match tokio::time::timeout(Duration::from_secs(SCHEDULED_RUN_TIMEOUT_SECONDS), &mut run)
+            .await
+        {
+            Ok(result) => result?,
+            Err(_) => {
+                cancel_flag.store(true, Ordering::SeqCst);
+                let _ = tokio::time::timeout(
+                    Duration::from_secs(SCHEDULED_RUN_CANCEL_GRACE_SECONDS),
+                    run,
+                )
+                .await;
+                return Err(format!(
+                    "运行超过 {} 分钟，已取消",
+                    SCHEDULED_RUN_TIMEOUT_SECONDS / 60
+                ));
+            }
+        }
+    }
+
+    let content = sqlx::query_scalar::<_, String>(
+        "SELECT content FROM messages
+         WHERE session_id = ? AND role = 'assistant'
+         ORDER BY created_at DESC
+         LIMIT 1",
+    )
+    .bind(&session_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .unwrap_or_default();
+    Ok((session_id, extract_assistant_text_content(&content)))
+}
+
+async fn deliver_scheduled_job_result(
+    pool: &SqlitePool,
+    job: &ScheduledJob,
+    session_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let route = crate::im::find_channel_delivery_route(pool, job.delivery_route_key.trim())
+        .await?
+        .ok_or_else(|| format!("IM 投递路由不存在: {}", job.delivery_route_key))?;
+    let delivered = crate::commands::im_host::dispatch_im_reply_to_delivery_route_with_pool(
+        pool, &route, session_id, text,
+    )
+    .await?;
+    if !delivered {
+        return Err(format!("渠道 {} 不支持投递或回复内容为空", route.channel));
+    }
+    Ok(())
+}
@@ -0,0 +1,584 @@
+use super::{
+    next_cron_run_at, plan_due_occurrence, CronSchedule, ScheduledJob, ScheduledJobInput,
+    ScheduledJobRun,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use uuid::Uuid;
+
+const SCHEDULED_JOB_COLUMNS: &str =
+    "id, name, target_kind, target_id, prompt, work_dir, permission_mode, model_id,
+                schedule_kind, cron_expr, run_at, missed_run_policy, delivery_route_key,
+                enabled, next_run_at, last_run_at, last_status, created_at, updated_at";
+
+const SCHEDULED_JOB_RUN_COLUMNS: &str =
+    "id, job_id, trigger_kind, scheduled_for, started_at, finished_at, status, session_id,
+                output, error, delivery_status, delivery_error";
+
+/// 调度器认领（或手动触发）后等待执行的一次运行
+#[derive(Debug, Clone)]
+pub struct ClaimedScheduledJobRun {
+    pub job: ScheduledJob,
+    pub run: ScheduledJobRun,
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|parsed| parsed.with_timezone(&Utc))
+}
+
+pub async fn list_scheduled_jobs_with_pool(pool: &SqlitePool) -> Result<Vec<ScheduledJob>, String> {
+    sqlx::query_as::<_, ScheduledJob>(&format!(
+        "SELECT {SCHEDULED_JOB_COLUMNS}
+         FROM scheduled_jobs
+         ORDER BY created_at ASC, id ASC"
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取定时任务失败: {e}"))
+}
+
+pub async fn get_scheduled_job_with_pool(
+    pool: &SqlitePool,
+    job_id: &str,
+) -> Result<ScheduledJob, String> {
+    sqlx::query_as::<_, ScheduledJob>(&format!(
+        "SELECT {SCHEDULED_JOB_COLUMNS} FROM scheduled_jobs WHERE id = ?"
+    ))
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("读取定时任务失败: {e}"))?
+    .ok_or_else(|| format!("定时任务不存在: {job_id}"))
+}
+
+/// 计算启用状态下的下一次运行时间；一次性任务的时间已过或 cron 永不命中时报错
+fn compute_next_run_at(
+    schedule_kind: &str,
+    cron_expr: &str,
+    run_at: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, String> {
+    match schedule_kind {
+        "cron" => {
+            let schedule = CronSchedule::parse(cron_expr)?;
+            next_cron_run_at(&schedule, now)
+                .ok_or_else(|| format!("cron 表达式在未来五年内不会触发: {cron_expr}"))
+        }
+        _ => {
+            let run_at = parse_timestamp(run_at)
+                .ok_or_else(|| format!("一次性任务的运行时间格式无效（需要 RFC3339）: {run_at}"))?;
+            if run_at <= now {
+                return Err("一次性任务的运行时间必须晚于当前时间".to_string());
+            }
+            Ok(run_at)
+        }
+    }
+}
+
+struct NormalizedJobInput {
+    name: String,
+    target_kind: String,
+    target_id: String,
+    prompt: String,
+    work_dir: String,
+    permission_mode: String,
+    model_id: String,
+    schedule_kind: String,
+    cron_expr: String,
+    run_at: String,
+    missed_run_policy: String,
+    delivery_route_key: String,
+    enabled: bool,
+}
+
+fn normalize_job_input(input: &ScheduledJobInput) -> Result<NormalizedJobInput, String> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("定时任务名称不能为空".to_string());
+    }
+    let target_kind = input.target_kind.trim();
+    if !matches!(target_kind, "employee" | "skill") {
+        return Err(format!("不支持的任务目标类型: {target_kind}"));
+    }
+    let target_id = input.target_id.trim();
+    if target_id.is_empty() {
+        return Err("请选择要运行的员工或技能".to_string());
+    }
+    if input.prompt.trim().is_empty() {
+        return Err("定时任务提示词不能为空".to_string());
+    }
+    let schedule_kind = input.schedule_kind.trim();
+    let (cron_expr, run_at) = match schedule_kind {
+        "cron" => {
+            let cron_expr = input.cron_expr.trim().to_string();
+            CronSchedule::parse(&cron_expr)?;
+            (cron_expr, String::new())
+        }
+        "once" => {
+            let run_at = parse_timestamp(&input.run_at).ok_or_else(|| {
+                format!(
+                    "一次性任务的运行时间格式无效（需要 RFC3339）: {}",
+                    input.run_at
+                )
+            })?;
+            (String::new(), run_at.to_rfc3339())
+        }
+        other => return Err(format!("不支持的调度类型: {other}")),
+    };
+    let permission_mode = match input.permission_mode.trim() {
+        "" | "standard" => "standard",
+        "full_access" => "full_access",
+        other => return Err(format!("不支持的权限模式: {other}")),
+    };
+    let missed_run_policy = match input.missed_run_policy.trim() {
+        "" | "catch_up" => "catch_up",
+        "skip" => "skip",
+        other => return Err(format!("不支持的错过运行策略: {other}")),
+    };
+
+    Ok(NormalizedJobInput {
+        name: name.to_string(),
+        target_kind: target_kind.to_string(),
+        target_id: target_id.to_string(),
+        prompt: input.prompt.clone(),
+        work_dir: input.work_dir.trim().to_string(),
+        permission_mode: permission_mode.to_string(),
+        model_id: input.model_id.trim().to_string(),
+        schedule_kind: schedule_kind.to_string(),
+        cron_expr,
+        run_at,
+        missed_run_policy: missed_run_policy.to_string(),
+        delivery_route_key: input.delivery_route_key.trim().to_string(),
+        enabled: input.enabled.unwrap_or(true),
+    })
+}
+
+/// 新建或更新任务；调度配置变化后按当前时间重新计算下一次运行时间
+pub async fn save_scheduled_job_with_pool(
+    pool: &SqlitePool,
+    input: ScheduledJobInput,
+) -> Result<ScheduledJob, String> {
+    let normalized = normalize_job_input(&input)?;
+    let now = Utc::now();
+    let next_run_at = if normalized.enabled {
+        compute_next_run_at(
+            &normalized.schedule_kind,
+            &normalized.cron_expr,
+            &normalized.run_at,
+            now,
+        )?
+        .to_rfc3339()
+    } else {
+        String::new()
+    };
+    let now = now.to_rfc3339();
+    let existing_id = input
+        .id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    let job_id = match existing_id {
+        Some(job_id) => {
+            let result = sqlx::query(
+                "UPDATE scheduled_jobs
+                 SET name = ?, target_kind = ?, target_id = ?, prompt = ?, work_dir = ?,
+                     permission_mode = ?, model_id = ?, schedule_kind = ?, cron_expr = ?,
+                     run_at = ?, missed_run_policy = ?, delivery_route_key = ?, enabled = ?,
+                     next_run_at = ?, updated_at = ?
+                 WHERE id = ?",
+            )
+            .bind(&normalized.name)
+            .bind(&normalized.target_kind)
+            .bind(&normalized.target_id)
+            .bind(&normalized.prompt)
+            .bind(&normalized.work_dir)
+            .bind(&normalized.permission_mode)
+            .bind(&normalized.model_id)
+            .bind(&normalized.schedule_kind)
+            .bind(&normalized.cron_expr)
+            .bind(&normalized.run_at)
+            .bind(&normalized.missed_run_policy)
+            .bind(&normalized.delivery_route_key)
+            .bind(normalized.enabled as i64)
+            .bind(&next_run_at)
+            .bind(&now)
+            .bind(job_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("更新定时任务失败: {e}"))?;
+            if result.rows_affected() == 0 {
+                return Err(format!("定时任务不存在: {job_id}"));
+            }
+            job_id.to_string()
+        }
+        None => {
+            let job_id = Uuid::new_v4().to_string();
+            sqlx::query(
+                "INSERT INTO scheduled_jobs (
+                    id, name, target_kind, target_id, prompt, work_dir, permission_mode,
+                    model_id, schedule_kind, cron_expr, run_at, missed_run_policy,
+                    delivery_route_key, enabled, next_run_at, last_run_at, last_status,
+                    created_at, updated_at
+                 ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, '', '', ?, ?)",
+            )
+            .bind(&job_id)
+            .bind(&normalized.name)
+            .bind(&normalized.target_kind)
+            .bind(&normalized.target_id)
+            .bind(&normalized.prompt)
+            .bind(&normalized.work_dir)
+            .bind(&normalized.permission_mode)
+            .bind(&normalized.model_id)
+            .bind(&normalized.schedule_kind)
+            .bind(&normalized.cron_expr)
+            .bind(&normalized.run_at)
+            .bind(&normalized.missed_run_policy)
+            .bind(&normalized.delivery_route_key)
+            .bind(normalized.enabled as i64)
+            .bind(&next_run_at)
+            .bind(&now)
+            .bind(&now)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("保存定时任务失败: {e}"))?;
+            job_id
+        }
+    };
+
+    get_scheduled_job_with_pool(pool, &job_id).await
+}
+
+/// 删除任务及其运行历史；已创建的会话保留
+pub async fn delete_scheduled_job_with_pool(pool: &SqlitePool, job_id: &str) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM scheduled_job_runs WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("删除定时任务运行记录失败: {e}"))?;
+    sqlx::query("DELETE FROM scheduled_jobs WHERE id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("删除定时任务失败: {e}"))?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 启用时从当前时间重新计算下一次运行，停用期间错过的触发不会补跑
+pub async fn set_scheduled_job_enabled_with_pool(
+    pool: &SqlitePool,
+    job_id: &str,
+    enabled: bool,
+) -> Result<ScheduledJob, String> {
+    let job = get_scheduled_job_with_pool(pool, job_id).await?;
+    let now = Utc::now();
+    let next_run_at = if enabled {
+        compute_next_run_at(&job.schedule_kind, &job.cron_expr, &job.run_at, now)?.to_rfc3339()
+    } else {
+        String::new()
+    };
+    sqlx::query(
+        "UPDATE scheduled_jobs SET enabled = ?, next_run_at = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(enabled as i64)
+    .bind(&next_run_at)
+    .bind(now.to_rfc3339())
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("更新定时任务状态失败: {e}"))?;
+    get_scheduled_job_with_pool(pool, job_id).await
+}
+
+pub async fn list_scheduled_job_runs_with_pool(
+    pool: &SqlitePool,
+    job_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<ScheduledJobRun>, String> {
+    let job_id = job_id.map(str::trim).filter(|value| !value.is_empty());
+    let filter = if job_id.is_some() {
+        "WHERE job_id = ?"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "SELECT {SCHEDULED_JOB_RUN_COLUMNS}
+         FROM scheduled_job_runs
+         {filter}
+         ORDER BY started_at DESC, id DESC
+         LIMIT ?"
+    );
+    let mut query = sqlx::query_as::<_, ScheduledJobRun>(&sql);
+    if let Some(job_id) = job_id {
+        query = query.bind(job_id);
+    }
+    query
+        .bind(limit.clamp(1, 500))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("读取定时任务运行记录失败: {e}"))
+}
+
+async fn has_running_run(tx: &mut Transaction<'_, Sqlite>, job_id: &str) -> Result<bool, String> {
+    let running: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM scheduled_job_runs WHERE job_id = ? AND status = 'running'",
+    )
+    .bind(job_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(running > 0)
+}
+
+async fn insert_run(tx: &mut Transaction<'_, Sqlite>, run: &ScheduledJobRun) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO scheduled_job_runs (
+            id, job_id, trigger_kind, scheduled_for, started_at, finished_at, status, session_id,
+            output, error, delivery_status, delivery_error
+         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&run.id)
+    .bind(&run.job_id)
+    .bind(&run.trigger_kind)
+    .bind(&run.scheduled_for)
+    .bind(&run.started_at)
+    .bind(&run.finished_at)
+    .bind(&run.status)
+    .bind(&run.session_id)
+    .bind(&run.output)
+    .bind(&run.error)
+    .bind(&run.delivery_status)
+    .bind(&run.delivery_error)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| format!("写入定时任务运行记录失败: {e}"))?;
+    Ok(())
+}
+
+fn new_run(job_id: &str, trigger_kind: &str, scheduled_for: &str, now: &str) -> ScheduledJobRun {
+    ScheduledJobRun {
+        id: Uuid::new_v4().to_string(),
+        job_id: job_id.to_string(),
+        trigger_kind: trigger_kind.to_string(),
+        scheduled_for: scheduled_for.to_string(),
+        started_at: now.to_string(),
+        finished_at: String::new(),
+        status: "running".to_string(),
+        session_id: String::new(),
+        output: String::new(),
+        error: String::new(),
+        delivery_status: String::new(),
+        delivery_error: String::new(),
+    }
+}
+
+/// 认领所有到期任务：为每次触发写入运行记录并推进下一次运行时间。
+/// 按策略跳过或上一次仍在运行的触发会记录为 skipped，不返回给调用方执行。
+pub async fn claim_due_scheduled_jobs_with_pool(
+    pool: &SqlitePool,
+    now: DateTime<Utc>,
+) -> Result<Vec<ClaimedScheduledJobRun>, String> {
+    let jobs = sqlx::query_as::<_, ScheduledJob>(&format!(
+        "SELECT {SCHEDULED_JOB_COLUMNS}
+         FROM scheduled_jobs
+         WHERE enabled = 1 AND TRIM(next_run_at) <> ''
+         ORDER BY next_run_at ASC, id ASC"
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取到期定时任务失败: {e}"))?;
+
+    let now_text = now.to_rfc3339();
+    let mut claimed = Vec::new();
+    for job in jobs {
+        let Some(scheduled_for) = parse_timestamp(&job.next_run_at) else {
+            continue;
+        };
+        if scheduled_for > now {
+            continue;
+        }
+        let plan = plan_due_occurrence(&job, scheduled_for, now);
+        let next_run_at = plan
+            .next_run_at
+            .map(|value| value.to_rfc3339())
+            .unwrap_or_default();
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        let mut run = new_run(
+            &job.id,
+            plan.trigger.unwrap_or("schedule"),
+            &job.next_run_at,
+            &now_text,
+        );
+        let skip_reason = match plan.trigger {
+            None => Some("错过计划时间，按任务策略跳过"),
+            Some(_) if has_running_run(&mut tx, &job.id).await? => {
+                Some("上一次运行尚未结束，跳过本次")
+            }
+            Some(_) => None,
+        };
+        if let Some(reason) = skip_reason {
+            run.status = "skipped".to_string();
+            run.finished_at = now_text.clone();
+            run.error = reason.to_string();
+        }
+        // 以读到的 next_run_at 作为条件推进，另一个调度器（或另一个进程）已认领时不会重复运行
+        let advanced = sqlx::query(
+            "UPDATE scheduled_jobs
+             SET next_run_at = ?, enabled = ?, last_run_at = ?, last_status = ?, updated_at = ?
+             WHERE id = ? AND next_run_at = ?",
+        )
+        .bind(&next_run_at)
+        .bind(i64::from(plan.next_run_at.is_some()))
+        .bind(&now_text)
+        .bind(&run.status)
+        .bind(&now_text)
+        .bind(&job.id)
+        .bind(&job.next_run_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("推进定时任务失败: {e}"))?;
+        if advanced.rows_affected() == 0 {
+            continue;
+        }
+        insert_run(&mut tx, &run).await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        if skip_reason.is_none() {
+            claimed.push(ClaimedScheduledJobRun { job, run });
+        }
+    }
+    Ok(claimed)
+}
+
+/// 手动立即运行，不影响下一次计划时间
+pub async fn begin_manual_scheduled_job_run_with_pool(
+    pool: &SqlitePool,
+    job_id: &str,
+) -> Result<ClaimedScheduledJobRun, String> {
+    let job = get_scheduled_job_with_pool(pool, job_id).await?;
+    let now = Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    if has_running_run(&mut tx, job_id).await? {
+        return Err(format!("定时任务「{}」正在运行", job.name));
+    }
+    let run = new_run(job_id, "manual", &now, &now);
+    insert_run(&mut tx, &run).await?;
+    sqlx::query(
+        "UPDATE scheduled_jobs SET last_run_at = ?, last_status = 'running', updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(&now)
+    .bind(&now)
+    .bind(job_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("更新定时任务失败: {e}"))?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(ClaimedScheduledJobRun { job, run })
+}
+
+pub async fn set_scheduled_job_run_session_with_pool(
+    pool: &SqlitePool,
+    run_id: &str,
+    session_id: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE scheduled_job_runs SET session_id = ? WHERE id = ?")
+        .bind(session_id)
+        .bind(run_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("记录定时任务会话失败: {e}"))?;
+    Ok(())
+}
+
+/// 结束一次运行并把结果同步到任务的最近状态
+pub async fn finish_scheduled_job_run_with_pool(
+    pool: &SqlitePool,
+    run_id: &str,
+    status: &str,
+    output: &str,
+    error: &str,
+) -> Result<ScheduledJobRun, String> {
+    let now = Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE scheduled_job_runs
+         SET status = ?, output = ?, error = ?, finished_at = ?
+         WHERE id = ?",
+    )
+    .bind(status)
+    .bind(output)
+    .bind(error)
+    .bind(&now)
+    .bind(run_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("更新定时任务运行记录失败: {e}"))?;
+    sqlx::query(
+        "UPDATE scheduled_jobs
+         SET last_status = ?, updated_at = ?
+         WHERE id = (SELECT job_id FROM scheduled_job_runs WHERE id = ?)",
+    )
+    .bind(status)
+    .bind(&now)
+    .bind(run_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("更新定时任务状态失败: {e}"))?;
+    let run = sqlx::query_as::<_, ScheduledJobRun>(&format!(
+        "SELECT {SCHEDULED_JOB_RUN_COLUMNS} FROM scheduled_job_runs WHERE id = ?"
+    ))
+    .bind(run_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("读取定时任务运行记录失败: {e}"))?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(run)
+}
+
+pub async fn record_scheduled_job_run_delivery_with_pool(
+    pool: &SqlitePool,
+    run_id: &str,
+    delivery_status: &str,
+    delivery_error: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE scheduled_job_runs SET delivery_status = ?, delivery_error = ? WHERE id = ?",
+    )
+    .bind(delivery_status)
+    .bind(delivery_error)
+    .bind(run_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("记录定时任务投递结果失败: {e}"))?;
+    Ok(())
+}
+
+/// 启动时把上次退出前仍在运行的记录标记为失败，避免永远阻塞后续触发
+pub async fn recover_interrupted_scheduled_job_runs_with_pool(
+    pool: &SqlitePool,
+) -> Result<u64, String> {
+    let now = Utc::now().to_rfc3339();
+    let result = sqlx::query(
+        "UPDATE scheduled_job_runs
+         SET status = 'failed', error = '应用退出导致运行中断', finished_at = ?
+         WHERE status = 'running'",
+    )
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("恢复中断的定时任务失败: {e}"))?;
+    sqlx::query(
+        "UPDATE scheduled_jobs SET last_status = 'failed', updated_at = ?
+         WHERE last_status = 'running'",
+    )
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("恢复中断的定时任务失败: {e}"))?;
+    Ok(result.rows_affected())
+}
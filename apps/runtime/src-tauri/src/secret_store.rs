@@ -0,0 +1,499 @@
+//! 敏感配置的静态加密。
+//!
+//! 模型与搜索服务的 API Key、IM 渠道凭据、技能包解锁凭据（用户名或团队口令）在写入 SQLite 前
+//! 用本机密钥（AES-256-GCM）加密，存储格式为 `enc:v1:<base64(nonce + 密文)>`。
+//! 密钥保存在用户配置目录下的独立文件中，不依赖系统钥匙串，无桌面环境的 Linux 上同样可用；
+//! 单独拷走数据库不会泄露明文。
+//! 没有前缀的旧值按明文读取，并由 [`migrate_plaintext_secrets_with_pool`] 在启动时改写。
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use regex::Regex;
+use sqlx::SqlitePool;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 覆盖密钥文件位置（例如在无 HOME 的服务环境中运行时）
+pub const SECRET_KEY_FILE_ENV: &str = "WORKCLAW_SECRET_KEY_FILE";
+const SECRET_KEY_FILE_NAME: &str = "secret.key";
+const SEALED_PREFIX: &str = "enc:v1:";
+const REDACTED: &str = "[REDACTED]";
+
+/// app_settings 中保存凭据的键，读写时透明加解密
+pub const SECRET_SETTING_KEYS: &[&str] = &[
+    "feishu_app_secret",
+    "feishu_encrypt_key",
+    "feishu_ingress_token",
+    "wecom_agent_secret",
+    "openclaw_ingress_token",
+    "mcp_server_token",
+];
+
+pub struct SecretStore {
+    key: [u8; 32],
+}
+
+impl SecretStore {
+    pub fn from_key(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// 读取密钥文件，不存在时生成新密钥并以仅当前用户可读的权限写入。
+    /// 并发启动时只有一个进程的密钥生效，其余进程读取它。
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(key) = read_key_file(path)? {
+            return Ok(Self { key });
+        }
+        let key = skillpack_rs::crypto::generate_content_key();
+        if publish_key_file(path, &key)? {
+            return Ok(Self { key });
+        }
+        read_key_file(path)?
+            .map(|key| Self { key })
+            .ok_or_else(|| format!("读取密钥文件失败: {}", path.display()))
+    }
+
+    /// 空字符串和已加密的值原样返回，保证 `TRIM(col) != ''` 之类的过滤仍然有效。
+    pub fn seal(&self, plaintext: &str) -> Result<String, String> {
+        if plaintext.is_empty() || is_sealed(plaintext) {
+            return Ok(plaintext.to_string());
+        }
+        let sealed = skillpack_rs::crypto::encrypt(plaintext.as_bytes(), &self.key)
+            .map_err(|e| format!("加密敏感配置失败: {e}"))?;
+        Ok(format!("{SEALED_PREFIX}{}", B64.encode(sealed)))
+    }
+
+    pub fn reveal(&self, stored: &str) -> Result<String, String> {
+        let Some(encoded) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let data = B64
+            .decode(encoded)
+            .map_err(|_| "敏感配置已损坏，请重新填写".to_string())?;
+        let plaintext = skillpack_rs::crypto::decrypt(&data, &self.key)
+            .map_err(|_| "无法解密已保存的凭据（本机密钥已变更），请重新填写".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "敏感配置已损坏，请重新填写".to_string())
+    }
+}
+
+fn read_key_file(path: &Path) -> Result<Option<[u8; 32]>, String> {
+    match fs::read(path) {
+        Ok(raw) => B64
+            .decode(String::from_utf8_lossy(&raw).trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .map(Some)
+            .ok_or_else(|| format!("密钥文件格式无效: {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("读取密钥文件失败 {}: {}", path.display(), err)),
+    }
+}
+
+/// 先在同目录写入临时文件并落盘，再硬链接到目标位置，读者不会看到写了一半的密钥。
+/// 不用 rename：它会覆盖其它进程刚发布的密钥。目标已存在时返回 false。
+fn publish_key_file(path: &Path, key: &[u8; 32]) -> Result<bool, String> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)
+        .map_err(|e| format!("创建密钥目录失败 {}: {}", parent.display(), e))?;
+    let temp_path = parent.join(format!(
+        ".{SECRET_KEY_FILE_NAME}.{}.tmp",
+        uuid::Uuid::new_v4()
+    ));
+    write_key_file(&temp_path, key)?;
+    let linked = fs::hard_link(&temp_path, path);
+    let _ = fs::remove_file(&temp_path);
+    match linked {
+        Ok(()) => {
+            #[cfg(unix)]
+            {
+                let _ = fs::File::open(parent).and_then(|dir| dir.sync_all());
+            }
+            Ok(true)
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(err) => Err(format!("创建密钥文件失败 {}: {}", path.display(), err)),
+    }
+}
+
+fn write_key_file(path: &Path, key: &[u8; 32]) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("创建密钥文件失败 {}: {}", path.display(), e))?;
+    file.write_all(B64.encode(key).as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("写入密钥文件失败 {}: {}", path.display(), e))
+}
+
+static SECRET_KEY_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 在首次加解密之前指定密钥文件位置，供集成测试等不应写入用户配置目录的场景使用。
+/// 只能设置一次，返回是否设置成功；密钥加载后再设置不会生效。
+pub fn override_secret_key_path(path: PathBuf) -> bool {
+    SECRET_KEY_PATH_OVERRIDE.set(path).is_ok()
+}
+
+/// 密钥文件默认放在用户配置目录，而不是数据目录，避免与数据库一起被打包或迁移。
+pub fn default_secret_key_path() -> Option<PathBuf> {
+    if let Some(path) = SECRET_KEY_PATH_OVERRIDE.get() {
+        return Some(path.clone());
+    }
+    if let Some(path) = std::env::var_os(SECRET_KEY_FILE_ENV).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    if cfg!(test) {
+        return Some(std::env::temp_dir().join(format!(
+            "{}-test-{SECRET_KEY_FILE_NAME}",
+            crate::branding_generated::BRAND_KEY
+        )));
+    }
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let config_dir = if cfg!(windows) {
+        env_dir("APPDATA").map(|dir| dir.join(crate::branding_generated::PRODUCT_NAME))
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| {
+            home.join("Library/Application Support")
+                .join(crate::branding_generated::PRODUCT_NAME)
+        })
+    } else {
+        env_dir("XDG_CONFIG_HOME")
+            .or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join(crate::branding_generated::BRAND_KEY))
+    }?;
+    Some(config_dir.join(SECRET_KEY_FILE_NAME))
+}
+
+fn global_store() -> Result<&'static SecretStore, String> {
+    static STORE: OnceLock<Result<SecretStore, String>> = OnceLock::new();
+    STORE
+        .get_or_init(|| {
+            let path = default_secret_key_path()
+                .ok_or_else(|| format!("无法确定密钥文件位置，请设置 {SECRET_KEY_FILE_ENV}"))?;
+            SecretStore::open(&path)
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+pub fn is_secret_setting_key(key: &str) -> bool {
+    SECRET_SETTING_KEYS.contains(&key)
+}
+
+/// 用本机密钥加密待写入数据库的凭据
+pub fn seal_secret(plaintext: &str) -> Result<String, String> {
+    if plaintext.is_empty() || is_sealed(plaintext) {
+        return Ok(plaintext.to_string());
+    }
+    global_store()?.seal(plaintext)
+}
+
+/// 解密数据库中读出的凭据；旧版明文原样返回
+pub fn reveal_secret(stored: &str) -> Result<String, String> {
+    if !is_sealed(stored) {
+        return Ok(stored.to_string());
+    }
+    global_store()?.reveal(stored)
+}
+
+fn redaction_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (r"enc:v1:[A-Za-z0-9+/=]+", REDACTED),
+            (r"(?i)(bearer\s+)[A-Za-z0-9._~+/=-]{8,}", "${1}[REDACTED]"),
+            (r"\b(sk|pk|rk)-[A-Za-z0-9_-]{16,}", REDACTED),
+            (r"\bAIza[0-9A-Za-z_-]{30,}", REDACTED),
+            (
+                r#"(?i)("[a-z_]*(?:api_key|apikey|secret|password|token|encrypt_key)[a-z_]*"\s*:\s*")[^"]+(")"#,
+                "${1}[REDACTED]${2}",
+            ),
+            (
+                r"(?i)([?&](?:key|api_key|access_token|token)=)[^&\s]+",
+                "${1}[REDACTED]",
+            ),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| {
+            (
+                Regex::new(pattern).expect("valid redaction pattern"),
+                replacement,
+            )
+        })
+        .collect()
+    })
+}
+
+/// 在写入诊断日志或导出诊断包前抹去文本中的凭据，JSON 文本替换后仍保持合法。
+pub fn redact_secrets(text: &str) -> String {
+    redaction_patterns()
+        .iter()
+        .fold(text.to_string(), |acc, (pattern, replacement)| {
+            pattern.replace_all(&acc, *replacement).into_owned()
+        })
+}
+
+async fn seal_column_rows(
+    pool: &SqlitePool,
+    select_sql: &str,
+    update_sql: &str,
+) -> Result<usize, String> {
+    let rows = match sqlx::query_as::<_, (String, String)>(select_sql)
+        .fetch_all(pool)
+        .await
+    {
+        Ok(rows) => rows,
+        // 旧库可能还没有对应的表
+        Err(e) if e.to_string().contains("no such table") => return Ok(0),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut rewritten = 0;
+    for (id, value) in rows {
+        if value.is_empty() || is_sealed(&value) {
+            continue;
+        }
+        sqlx::query(update_sql)
+            .bind(seal_secret(&value)?)
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
+
+/// 把数据库中遗留的明文凭据改写为密文，返回改写的行数。可重复执行。
+pub async fn migrate_plaintext_secrets_with_pool(pool: &SqlitePool) -> Result<usize, String> {
+    let mut rewritten = 0;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, api_key FROM model_configs WHERE api_key != '' AND api_key NOT LIKE 'enc:v1:%'",
+        "UPDATE model_configs SET api_key = ? WHERE id = ?",
+    )
+    .await?;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, api_key_encrypted FROM provider_configs
+         WHERE api_key_encrypted != '' AND api_key_encrypted NOT LIKE 'enc:v1:%'",
+        "UPDATE provider_configs SET api_key_encrypted = ? WHERE id = ?",
+    )
+    .await?;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, feishu_app_secret FROM agent_employees
+         WHERE feishu_app_secret != '' AND feishu_app_secret NOT LIKE 'enc:v1:%'",
+        "UPDATE agent_employees SET feishu_app_secret = ? WHERE id = ?",
+    )
+    .await?;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, username FROM installed_skills
+         WHERE COALESCE(source_type, 'encrypted') = 'encrypted'
+           AND username != '' AND username NOT LIKE 'enc:v1:%'",
+        "UPDATE installed_skills SET username = ? WHERE id = ?",
+    )
+    .await?;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, env FROM mcp_servers
+         WHERE env NOT IN ('', '{}') AND env NOT LIKE 'enc:v1:%'",
+        "UPDATE mcp_servers SET env = ? WHERE id = ?",
+    )
+    .await?;
+    rewritten += seal_column_rows(
+        pool,
+        "SELECT id, headers FROM mcp_servers
+         WHERE headers NOT IN ('', '{}') AND headers NOT LIKE 'enc:v1:%'",
+        "UPDATE mcp_servers SET headers = ? WHERE id = ?",
+    )
+    .await?;
+    for key in SECRET_SETTING_KEYS {
+        rewritten += seal_column_rows(
+            pool,
+            &format!(
+                "SELECT key, value FROM app_settings
+                 WHERE key = '{key}' AND value != '' AND value NOT LIKE 'enc:v1:%'"
+            ),
+            "UPDATE app_settings SET value = ? WHERE key = ?",
+        )
+        .await?;
+    }
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[test]
+    fn seal_round_trips_and_keeps_empty_and_legacy_values() {
+        let store = SecretStore::from_key([7u8; 32]);
+        let sealed = store.seal("sk-test-1234567890").expect("seal");
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("sk-test"));
+        assert_eq!(store.seal(&sealed).expect("reseal"), sealed);
+        assert_eq!(store.reveal(&sealed).expect("reveal"), "sk-test-1234567890");
+        assert_eq!(store.seal("").expect("seal empty"), "");
+        assert_eq!(
+            store.reveal("legacy-plain").expect("legacy"),
+            "legacy-plain"
+        );
+
+        let other = SecretStore::from_key([8u8; 32]);
+        assert!(other.reveal(&sealed).is_err());
+    }
+
+    #[test]
+    fn open_creates_key_file_once_and_reuses_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join(SECRET_KEY_FILE_NAME);
+        let sealed = SecretStore::open(&path)
+            .expect("create key")
+            .seal("secret-value")
+            .expect("seal");
+        let reopened = SecretStore::open(&path).expect("reopen key");
+        assert_eq!(reopened.reveal(&sealed).expect("reveal"), "secret-value");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).expect("metadata").permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn publish_key_file_keeps_the_first_key() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(SECRET_KEY_FILE_NAME);
+        assert!(publish_key_file(&path, &[1u8; 32]).expect("publish first"));
+        assert!(!publish_key_file(&path, &[2u8; 32]).expect("publish second"));
+        assert_eq!(read_key_file(&path).expect("read"), Some([1u8; 32]));
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .expect("read dir")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(
+            leftovers,
+            vec![std::ffi::OsString::from(SECRET_KEY_FILE_NAME)]
+        );
+    }
+
+    #[test]
+    fn redact_secrets_masks_keys_but_keeps_json_valid() {
+        let text = serde_json::json!({
+            "api_key": "plain-key-value",
+            "feishu_app_secret": "abc",
+            "url": "https://example.com/v1?key=AIzaSyExample&model=x",
+            "headers": "Authorization: Bearer abcdefghijkl",
+            "stored": "enc:v1:QUJDRA==",
+            "message": "using sk-abcdefghijklmnopqrstuv",
+        })
+        .to_string();
+        let redacted = redact_secrets(&text);
+        for leaked in [
+            "plain-key-value",
+            "\"abc\"",
+            "AIzaSyExample",
+            "abcdefghijkl",
+            "QUJDRA",
+            "sk-abcdefghijklmnopqrstuv",
+        ] {
+            assert!(!redacted.contains(leaked), "{leaked} leaked in {redacted}");
+        }
+        let value: serde_json::Value = serde_json::from_str(&redacted).expect("still json");
+        assert_eq!(value["api_key"], "[REDACTED]");
+        assert!(value["url"].as_str().unwrap().ends_with("&model=x"));
+    }
+
+    #[tokio::test]
+    async fn migrate_plaintext_secrets_rewrites_rows_once() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("create sqlite memory pool");
+        sqlx::query("CREATE TABLE model_configs (id TEXT PRIMARY KEY, api_key TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .expect("create model_configs");
+        sqlx::query("CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .expect("create app_settings");
+        sqlx::query("CREATE TABLE mcp_servers (id TEXT PRIMARY KEY, env TEXT, headers TEXT)")
+            .execute(&pool)
+            .await
+            .expect("create mcp_servers");
+        sqlx::query(
+            "INSERT INTO mcp_servers VALUES ('s1', '{}', '{\"Authorization\":\"Bearer t\"}')",
+        )
+        .execute(&pool)
+        .await
+        .expect("seed mcp servers");
+        sqlx::query("INSERT INTO model_configs VALUES ('m1', 'sk-plain'), ('m2', '')")
+            .execute(&pool)
+            .await
+            .expect("seed models");
+        sqlx::query(
+            "INSERT INTO app_settings VALUES ('wecom_agent_secret', 'wecom'), ('runtime_default_work_dir', '/tmp')",
+        )
+        .execute(&pool)
+        .await
+        .expect("seed settings");
+
+        assert_eq!(
+            migrate_plaintext_secrets_with_pool(&pool)
+                .await
+                .expect("migrate"),
+            3
+        );
+        assert_eq!(
+            migrate_plaintext_secrets_with_pool(&pool)
+                .await
+                .expect("migrate again"),
+            0
+        );
+
+        let stored: String =
+            sqlx::query_scalar("SELECT api_key FROM model_configs WHERE id = 'm1'")
+                .fetch_one(&pool)
+                .await
+                .expect("read model key");
+        assert!(is_sealed(&stored));
+        assert_eq!(reveal_secret(&stored).expect("reveal"), "sk-plain");
+        let (env, headers): (String, String) =
+            sqlx::query_as("SELECT env, headers FROM mcp_servers WHERE id = 's1'")
+                .fetch_one(&pool)
+                .await
+                .expect("read mcp server");
+        assert_eq!(env, "{}");
+        assert!(is_sealed(&headers));
+        let work_dir: String = sqlx::query_scalar(
+            "SELECT value FROM app_settings WHERE key = 'runtime_default_work_dir'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("read work dir");
+        assert_eq!(work_dir, "/tmp");
+    }
+}
@@ -435,6 +435,21 @@ pub enum SessionRunEvent {
         tool_recommendation_aligned: Option<bool>,
         tool_plan_summary: Option<EffectiveToolDecisionRecord>,
     },
+    ModelRouteRecorded {
+        run_id: String,
+        capability: String,
+        candidate_count: usize,
+        skipped_count: usize,
+        selected_provider: String,
+        selected_model: String,
+        decision_reason: String,
+    },
+    StructuredOutputRecorded {
+        run_id: String,
+        schema_name: String,
+        output: Value,
+        repair_turns: usize,
+    },
     AssistantChunkAppended {
         run_id: String,
         chunk: String,
@@ -460,6 +475,9 @@ pub enum SessionRunEvent {
         input: Value,
         output: String,
         is_error: bool,
+        /// 本次调用改动工作区时生成的检查点，可用于 diff 与回退
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        checkpoint_id: Option<String>,
     },
     ApprovalRequested {
         run_id: String,
@@ -525,6 +543,8 @@ fn apply_event(state: &mut SessionJournalState, event: &SessionRunEvent) {
         | SessionRunEvent::TaskStatusChanged { run_id, .. }
         | SessionRunEvent::RunStarted { run_id, .. }
         | SessionRunEvent::SkillRouteRecorded { run_id, .. }
+        | SessionRunEvent::ModelRouteRecorded { run_id, .. }
+        | SessionRunEvent::StructuredOutputRecorded { run_id, .. }
         | SessionRunEvent::AssistantChunkAppended { run_id, .. }
         | SessionRunEvent::ToolStarted { run_id, .. }
         | SessionRunEvent::ToolCompleted { run_id, .. }
@@ -575,7 +595,9 @@ fn apply_event(state: &mut SessionJournalState, event: &SessionRunEvent) {
             run.task_continuation_reason = None;
             run.turn_state = None;
         }
-        SessionRunEvent::SkillRouteRecorded { .. } => {}
+        SessionRunEvent::SkillRouteRecorded { .. }
+        | SessionRunEvent::ModelRouteRecorded { .. }
+        | SessionRunEvent::StructuredOutputRecorded { .. } => {}
         SessionRunEvent::AssistantChunkAppended { chunk, .. } => {
             let run = &mut state.runs[run_index];
             run.buffered_text.push_str(chunk);
@@ -1067,6 +1089,69 @@ fn build_observed_session_run_event(
                 .to_string(),
             )),
         },
+        SessionRunEvent::ModelRouteRecorded {
+            run_id,
+            capability,
+            candidate_count,
+            skipped_count,
+            selected_provider,
+            selected_model,
+            decision_reason,
+        } => RuntimeObservedRunEvent {
+            session_id: session_id.to_string(),
+            run_id: run_id.clone(),
+            event_type: "model_route_recorded".to_string(),
+            created_at: recorded_at.to_string(),
+            status: Some(selected_model.clone()),
+            tool_name: None,
+            approval_id: None,
+            warning_kind: Some(decision_reason.clone()),
+            error_kind: None,
+            child_session_id: None,
+            route_latency_ms: None,
+            candidate_count: Some(*candidate_count),
+            selected_skill: None,
+            fallback_reason: None,
+            tool_recommendation_summary: None,
+            tool_recommendation_aligned: None,
+            tool_plan_summary: None,
+            message: Some(
+                json!({
+                    "capability": capability,
+                    "candidate_count": candidate_count,
+                    "skipped_count": skipped_count,
+                    "selected_provider": selected_provider,
+                    "selected_model": selected_model,
+                    "decision_reason": decision_reason,
+                })
+                .to_string(),
+            ),
+        },
+        SessionRunEvent::StructuredOutputRecorded {
+            run_id,
+            schema_name,
+            repair_turns,
+            ..
+        } => RuntimeObservedRunEvent {
+            session_id: session_id.to_string(),
+            run_id: run_id.clone(),
+            event_type: "structured_output_recorded".to_string(),
+            created_at: recorded_at.to_string(),
+            status: Some("validated".to_string()),
+            tool_name: None,
+            approval_id: None,
+            warning_kind: None,
+            error_kind: None,
+            child_session_id: None,
+            route_latency_ms: None,
+            candidate_count: None,
+            selected_skill: Some(schema_name.clone()),
+            fallback_reason: None,
+            tool_recommendation_summary: None,
+            tool_recommendation_aligned: None,
+            tool_plan_summary: None,
+            message: Some(format!("repair_turns={repair_turns}")),
+        },
         SessionRunEvent::AssistantChunkAppended { run_id, chunk } => RuntimeObservedRunEvent {
             session_id: session_id.to_string(),
             run_id: run_id.clone(),
@@ -0,0 +1,950 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// app_settings 中的 shell 沙箱配置（JSON）
+pub const SHELL_SANDBOX_SETTING_KEY: &str = "shell_sandbox_profile";
+
+/// bash / exec / 后台进程共用的沙箱配置，默认关闭
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellSandboxProfile {
+    pub enabled: bool,
+    /// 关闭后命令运行在独立网络命名空间，仅保留回环
+    pub allow_network: bool,
+    pub cpu_time_secs: Option<u64>,
+    /// 按数据段（RLIMIT_DATA）限制；默认不限，避免误伤预留大量地址空间的运行时
+    pub memory_mb: Option<u64>,
+    /// 沙箱命令最多可新增的进程数
+    pub max_processes: Option<u64>,
+    /// 后台进程的最长运行时间；同步命令沿用工具自身的 timeout_ms
+    pub wall_time_secs: Option<u64>,
+    /// 工作目录和任务临时目录之外额外允许写入的绝对路径
+    pub extra_writable_paths: Vec<String>,
+    /// 检测到 bubblewrap 时优先使用，否则直接使用内置命名空间
+    pub prefer_bubblewrap: bool,
+}
+
+impl Default for ShellSandboxProfile {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_network: true,
+            cpu_time_secs: Some(600),
+            memory_mb: None,
+            max_processes: Some(256),
+            wall_time_secs: Some(3600),
+            extra_writable_paths: Vec::new(),
+            prefer_bubblewrap: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShellSandboxBackend {
+    Disabled,
+    Bubblewrap,
+    Namespaces,
+    /// 已开启但当前平台不支持，命令按原方式执行
+    Unavailable,
+}
+
+impl ShellSandboxBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Bubblewrap => "bubblewrap",
+            Self::Namespaces => "namespaces",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// 一次 shell 命令的沙箱请求
+pub struct ShellSandboxRequest<'a> {
+    pub shell: &'a str,
+    pub shell_args: &'a [&'a str],
+    pub command: &'a str,
+    pub work_dir: Option<&'a Path>,
+    /// 工作目录之外需要可写的路径（如任务临时目录）
+    pub writable_paths: Vec<PathBuf>,
+}
+
+/// 已按沙箱配置装配好的子进程命令；调用方只需补充 stdio
+pub struct SandboxedCommand {
+    pub command: Command,
+    pub backend: ShellSandboxBackend,
+    pub details: Value,
+    pub wall_time: Option<Duration>,
+    /// bubblewrap 通过继承的文件描述符读取 seccomp 程序，需保持打开直到 spawn
+    #[cfg(target_os = "linux")]
+    _seccomp_program: Option<std::fs::File>,
+}
+
+impl SandboxedCommand {
+    pub fn spawn(&mut self) -> Result<Child> {
+        self.command.spawn().map_err(|e| match self.backend {
+            ShellSandboxBackend::Bubblewrap | ShellSandboxBackend::Namespaces => {
+                anyhow!("沙箱启动失败（{}）: {}", self.backend.as_str(), e)
+            }
+            _ => e.into(),
+        })
+    }
+}
+
+fn shared_shell_sandbox_profile() -> &'static RwLock<ShellSandboxProfile> {
+    static PROFILE: OnceLock<RwLock<ShellSandboxProfile>> = OnceLock::new();
+    PROFILE.get_or_init(|| RwLock::new(ShellSandboxProfile::default()))
+}
+
+/// 进程内生效的沙箱配置
+pub fn current_shell_sandbox_profile() -> ShellSandboxProfile {
+    shared_shell_sandbox_profile()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+fn replace_shell_sandbox_profile(profile: ShellSandboxProfile) {
+    *shared_shell_sandbox_profile()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = profile;
+}
+
+pub fn validate_shell_sandbox_profile(profile: &ShellSandboxProfile) -> Result<(), String> {
+    let limits = [
+        (profile.cpu_time_secs, "CPU 时间上限"),
+        (profile.memory_mb, "内存上限"),
+        (profile.max_processes, "进程数上限"),
+        (profile.wall_time_secs, "运行时长上限"),
+    ];
+    if let Some((_, label)) = limits.iter().find(|(value, _)| *value == Some(0)) {
+        return Err(format!("{label}必须大于 0"));
+    }
+    if let Some(path) = profile
+        .extra_writable_paths
+        .iter()
+        .find(|path| !Path::new(path.trim()).is_absolute())
+    {
+        return Err(format!("沙箱可写路径必须是绝对路径: {path}"));
+    }
+    Ok(())
+}
+
+pub async fn load_shell_sandbox_profile_with_pool(
+    pool: &SqlitePool,
+) -> Result<ShellSandboxProfile, String> {
+    let value =
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ? LIMIT 1")
+            .bind(SHELL_SANDBOX_SETTING_KEY)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("读取 shell 沙箱设置失败: {e}"))?;
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(ShellSandboxProfile::default()),
+        Some(raw) => serde_json::from_str(raw).map_err(|e| format!("shell 沙箱设置格式无效: {e}")),
+    }
+}
+
+/// 重新读取沙箱设置并刷新进程内配置
+pub async fn reload_shell_sandbox_profile_with_pool(
+    pool: &SqlitePool,
+) -> Result<ShellSandboxProfile, String> {
+    let profile = load_shell_sandbox_profile_with_pool(pool).await?;
+    replace_shell_sandbox_profile(profile.clone());
+    Ok(profile)
+}
+
+pub async fn save_shell_sandbox_profile_with_pool(
+    pool: &SqlitePool,
+    mut profile: ShellSandboxProfile,
+) -> Result<ShellSandboxProfile, String> {
+    profile.extra_writable_paths = profile
+        .extra_writable_paths
+        .iter()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    validate_shell_sandbox_profile(&profile)?;
+    let raw =
+        serde_json::to_string(&profile).map_err(|e| format!("序列化 shell 沙箱设置失败: {e}"))?;
+    sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+        .bind(SHELL_SANDBOX_SETTING_KEY)
+        .bind(raw)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("保存 shell 沙箱设置失败: {e}"))?;
+    replace_shell_sandbox_profile(profile.clone());
+    Ok(profile)
+}
+
+/// 按进程内沙箱配置装配 shell 命令
+pub fn build_shell_command(request: ShellSandboxRequest<'_>) -> Result<SandboxedCommand> {
+    build_shell_command_with_profile(&current_shell_sandbox_profile(), request)
+}
+
+pub fn build_shell_command_with_profile(
+    profile: &ShellSandboxProfile,
+    request: ShellSandboxRequest<'_>,
+) -> Result<SandboxedCommand> {
+    if !profile.enabled {
+        return Ok(unsandboxed_command(
+            &request,
+            ShellSandboxBackend::Disabled,
+            json!({ "enabled": false, "backend": ShellSandboxBackend::Disabled.as_str() }),
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::build(profile, &request)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Ok(unsandboxed_command(
+            &request,
+            ShellSandboxBackend::Unavailable,
+            json!({
+                "enabled": true,
+                "backend": ShellSandboxBackend::Unavailable.as_str(),
+                "reason": "当前平台不支持 shell 沙箱，命令未隔离执行",
+            }),
+        ))
+    }
+}
+
+fn unsandboxed_command(
+    request: &ShellSandboxRequest<'_>,
+    backend: ShellSandboxBackend,
+    details: Value,
+) -> SandboxedCommand {
+    let mut command = Command::new(request.shell);
+    command.args(request.shell_args).arg(request.command);
+    if let Some(work_dir) = request.work_dir {
+        command.current_dir(work_dir);
+    }
+    SandboxedCommand {
+        command,
+        backend,
+        details,
+        wall_time: None,
+        #[cfg(target_os = "linux")]
+        _seccomp_program: None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+/// 工作目录、请求路径和配置路径去重后的可写列表；不存在的路径会被忽略
+fn resolve_writable_paths(
+    profile: &ShellSandboxProfile,
+    request: &ShellSandboxRequest<'_>,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let candidates = request
+        .work_dir
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(request.writable_paths.iter().cloned())
+        .chain(profile.extra_writable_paths.iter().map(PathBuf::from));
+    for candidate in candidates {
+        if let Ok(path) = candidate.canonicalize() {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(target_os = "linux")]
+fn sandbox_details(
+    profile: &ShellSandboxProfile,
+    backend: ShellSandboxBackend,
+    writable_paths: &[PathBuf],
+) -> Value {
+    json!({
+        "enabled": true,
+        "backend": backend.as_str(),
+        "network": if profile.allow_network { "allowed" } else { "denied" },
+        "writable_paths": writable_paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<_>>(),
+        "seccomp": cfg!(any(target_arch = "x86_64", target_arch = "aarch64")),
+        "limits": {
+            "cpu_time_secs": profile.cpu_time_secs,
+            "memory_mb": profile.memory_mb,
+            "max_processes": profile.max_processes,
+            "wall_time_secs": profile.wall_time_secs,
+        },
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(target_os = "linux")]
+/// bubblewrap 参数：根目录只读，/dev、/proc、/tmp 私有，可写路径逐个绑定
+fn bubblewrap_args(
+    profile: &ShellSandboxProfile,
+    request: &ShellSandboxRequest<'_>,
+    writable_paths: &[PathBuf],
+    seccomp_fd: Option<i32>,
+) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--die-with-parent",
+        "--new-session",
+        "--unshare-all",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+    if profile.allow_network {
+        args.push("--share-net".to_string());
+    }
+    for path in writable_paths {
+        let path = path.to_string_lossy().to_string();
+        args.extend(["--bind".to_string(), path.clone(), path]);
+    }
+    if let Some(fd) = seccomp_fd {
+        args.extend(["--seccomp".to_string(), fd.to_string()]);
+    }
+    if let Some(work_dir) = request.work_dir {
+        args.extend([
+            "--chdir".to_string(),
+            work_dir.to_string_lossy().to_string(),
+        ]);
+    }
+    args.push("--".to_string());
+    args.push(request.shell.to_string());
+    args.extend(request.shell_args.iter().map(ToString::to_string));
+    args.push(request.command.to_string());
+    args
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::ffi::{CStr, CString};
+    use std::io::{Seek, SeekFrom, Write};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    // seccomp 过滤器按架构号和系统调用号匹配，只为已核对过调用号的架构生成；
+    // 其他架构不加载过滤器，仅依赖命名空间隔离
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH_X86_64: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH_AARCH64: u32 = 0xC000_00B7;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH_NATIVE: u32 = AUDIT_ARCH_X86_64;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH_NATIVE: u32 = AUDIT_ARCH_AARCH64;
+
+    /// x32 ABI 与 x86_64 共用架构号，系统调用号带此标志位，需单独拦截
+    #[cfg(target_arch = "x86_64")]
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+    #[cfg(target_arch = "x86_64")]
+    const X32_CHECK_LEN: usize = 1;
+    #[cfg(target_arch = "aarch64")]
+    const X32_CHECK_LEN: usize = 0;
+
+    /// 沙箱内禁止的系统调用：挂载与命名空间操作、内核模块、调试注入等
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    const DENIED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+        libc::SYS_open_tree,
+        libc::SYS_move_mount,
+        libc::SYS_fsopen,
+        libc::SYS_fsconfig,
+        libc::SYS_fsmount,
+        libc::SYS_fspick,
+        libc::SYS_mount_setattr,
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_kexec_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_reboot,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_userfaultfd,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_syslog,
+        libc::SYS_acct,
+    ];
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn bpf_stmt(code: u32, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code: code as u16,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn bpf_jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter {
+            code: code as u16,
+            jt,
+            jf,
+            k,
+        }
+    }
+
+    /// 非本机架构、x32 和黑名单调用一律返回 EPERM，其余放行
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    pub(super) fn seccomp_program() -> Option<Vec<libc::sock_filter>> {
+        let deny = libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32);
+        let count = DENIED_SYSCALLS.len();
+        let mut program = vec![
+            bpf_stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 4),
+            // 架构不符时跳过全部判断直达 deny
+            bpf_jump(
+                libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+                AUDIT_ARCH_NATIVE,
+                0,
+                (count + X32_CHECK_LEN + 2) as u8,
+            ),
+            bpf_stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 0),
+        ];
+        #[cfg(target_arch = "x86_64")]
+        program.push(bpf_jump(
+            libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+            X32_SYSCALL_BIT,
+            (count + 1) as u8,
+            0,
+        ));
+        for (index, syscall) in DENIED_SYSCALLS.iter().enumerate() {
+            program.push(bpf_jump(
+                libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+                *syscall as u32,
+                (count - index) as u8,
+                0,
+            ));
+        }
+        program.push(bpf_stmt(
+            libc::BPF_RET | libc::BPF_K,
+            libc::SECCOMP_RET_ALLOW,
+        ));
+        program.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, deny));
+        Some(program)
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    pub(super) fn seccomp_program() -> Option<Vec<libc::sock_filter>> {
+        None
+    }
+
+    fn seccomp_program_bytes(program: &[libc::sock_filter]) -> Vec<u8> {
+        program
+            .iter()
+            .flat_map(|filter| {
+                let mut bytes = Vec::with_capacity(8);
+                bytes.extend_from_slice(&filter.code.to_ne_bytes());
+                bytes.push(filter.jt);
+                bytes.push(filter.jf);
+                bytes.extend_from_slice(&filter.k.to_ne_bytes());
+                bytes
+            })
+            .collect()
+    }
+
+    /// 资源上限：进程数按当前用户已有进程数加配额计算，避免与桌面进程抢额度
+    #[derive(Clone, Copy)]
+    struct ResourceLimits {
+        cpu_time_secs: Option<u64>,
+        memory_bytes: Option<u64>,
+        max_processes: Option<u64>,
+    }
+
+    impl ResourceLimits {
+        fn from_profile(profile: &ShellSandboxProfile) -> Self {
+            Self {
+                cpu_time_secs: profile.cpu_time_secs,
+                memory_bytes: profile.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+                max_processes: profile
+                    .max_processes
+                    .map(|max| max.saturating_add(current_user_process_count())),
+            }
+        }
+
+        fn apply(&self) -> std::io::Result<()> {
+            let limits = [
+                (libc::RLIMIT_CPU, self.cpu_time_secs),
+                (libc::RLIMIT_DATA, self.memory_bytes),
+                (libc::RLIMIT_NPROC, self.max_processes),
+            ];
+            for (resource, value) in limits {
+                if let Some(value) = value {
+                    let limit = libc::rlimit {
+                        rlim_cur: value as libc::rlim_t,
+                        rlim_max: value as libc::rlim_t,
+                    };
+                    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn current_user_process_count() -> u64 {
+        let uid = unsafe { libc::getuid() };
+        std::fs::read_dir("/proc")
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| {
+                        entry
+                            .file_name()
+                            .to_str()
+                            .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()))
+                    })
+                    .filter(|entry| entry.metadata().is_ok_and(|meta| meta.uid() == uid))
+                    .count() as u64
+            })
+            .unwrap_or(0)
+    }
+
+    /// /proc/self/mountinfo 中的挂载点及需要保留的挂载标志
+    pub(super) fn parse_mountinfo(raw: &str) -> Vec<(String, libc::c_ulong)> {
+        raw.lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let mount_point = decode_mountinfo_path(fields.get(4)?);
+                let flags = fields
+                    .get(5)?
+                    .split(',')
+                    .map(|option| match option {
+                        "nosuid" => libc::MS_NOSUID,
+                        "nodev" => libc::MS_NODEV,
+                        "noexec" => libc::MS_NOEXEC,
+                        "noatime" => libc::MS_NOATIME,
+                        "nodiratime" => libc::MS_NODIRATIME,
+                        "relatime" => libc::MS_RELATIME,
+                        _ => 0,
+                    })
+                    .fold(0, |acc, flag| acc | flag);
+                Some((mount_point, flags))
+            })
+            .collect()
+    }
+
+    fn decode_mountinfo_path(raw: &str) -> String {
+        let bytes = raw.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] == b'\\' && index + 3 < bytes.len() {
+                if let Ok(value) = u8::from_str_radix(&raw[index + 1..index + 4], 8) {
+                    decoded.push(value);
+                    index += 4;
+                    continue;
+                }
+            }
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+        String::from_utf8_lossy(&decoded).to_string()
+    }
+
+    fn c_path(path: &Path) -> Result<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| anyhow!("路径包含空字符: {}", path.display()))
+    }
+
+    /// 子进程 exec 前需要的全部数据，fork 前准备好，避免在子进程里分配内存
+    struct NamespacePlan {
+        deny_network: bool,
+        uid_map: CString,
+        gid_map: CString,
+        writable_paths: Vec<CString>,
+        read_only_mounts: Vec<(CString, libc::c_ulong)>,
+        work_dir: Option<CString>,
+        seccomp: Option<Vec<libc::sock_filter>>,
+    }
+
+    fn write_proc_file(path: &CStr, content: &[u8]) -> std::io::Result<()> {
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let written =
+            unsafe { libc::write(fd, content.as_ptr() as *const libc::c_void, content.len()) };
+        unsafe { libc::close(fd) };
+        if written < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn check(result: libc::c_int) -> std::io::Result<()> {
+        if result != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    impl NamespacePlan {
+        fn enter(&self) -> std::io::Result<()> {
+            let mut flags = libc::CLONE_NEWUSER | libc::CLONE_NEWNS;
+            if self.deny_network {
+                flags |= libc::CLONE_NEWNET;
+            }
+            check(unsafe { libc::unshare(flags) })?;
+            write_proc_file(c"/proc/self/setgroups", b"deny")?;
+            write_proc_file(c"/proc/self/uid_map", self.uid_map.as_bytes())?;
+            write_proc_file(c"/proc/self/gid_map", self.gid_map.as_bytes())?;
+
+            let root = c"/".as_ptr();
+            check(unsafe {
+                libc::mount(
+                    std::ptr::null(),
+                    root,
+                    std::ptr::null(),
+                    libc::MS_REC | libc::MS_PRIVATE,
+                    std::ptr::null(),
+                )
+            })?;
+            for path in &self.writable_paths {
+                check(unsafe {
+                    libc::mount(
+                        path.as_ptr(),
+                        path.as_ptr(),
+                        std::ptr::null(),
+                        libc::MS_BIND | libc::MS_REC,
+                        std::ptr::null(),
+                    )
+                })?;
+            }
+            for (mount_point, flags) in &self.read_only_mounts {
+                let result = unsafe {
+                    libc::mount(
+                        std::ptr::null(),
+                        mount_point.as_ptr(),
+                        std::ptr::null(),
+                        libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY | flags,
+                        std::ptr::null(),
+                    )
+                };
+                // 根目录必须只读成功；其余挂载点（如已被覆盖或内核锁定的）尽力而为
+                if result != 0 && mount_point.as_bytes() == b"/" {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(work_dir) = &self.work_dir {
+                check(unsafe { libc::chdir(work_dir.as_ptr()) })?;
+            }
+            match &self.seccomp {
+                Some(program) => apply_seccomp(program),
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn apply_seccomp(program: &[libc::sock_filter]) -> std::io::Result<()> {
+        let fprog = libc::sock_fprog {
+            len: program.len() as libc::c_ushort,
+            filter: program.as_ptr() as *mut libc::sock_filter,
+        };
+        let (enable, unused): (libc::c_ulong, libc::c_ulong) = (1, 0);
+        check(unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, enable, unused, unused, unused) })?;
+        check(unsafe {
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER as libc::c_ulong,
+                &fprog as *const libc::sock_fprog,
+            )
+        })
+    }
+
+    pub(super) fn build(
+        profile: &ShellSandboxProfile,
+        request: &ShellSandboxRequest<'_>,
+    ) -> Result<SandboxedCommand> {
+        let limits = ResourceLimits::from_profile(profile);
+        let bubblewrap = profile
+            .prefer_bubblewrap
+            .then(|| find_in_path("bwrap"))
+            .flatten();
+        let wall_time = profile.wall_time_secs.map(Duration::from_secs);
+
+        if let Some(bwrap) = bubblewrap {
+            let writable_paths = resolve_writable_paths(profile, request);
+            let program_file = seccomp_program()
+                .map(|program| -> std::io::Result<std::fs::File> {
+                    let mut file = tempfile::tempfile()?;
+                    file.write_all(&seccomp_program_bytes(&program))?;
+                    file.seek(SeekFrom::Start(0))?;
+                    Ok(file)
+                })
+                .transpose()?;
+            let fd = program_file.as_ref().map(|file| file.as_raw_fd());
+
+            let mut command = Command::new(bwrap);
+            command.args(bubblewrap_args(profile, request, &writable_paths, fd));
+            unsafe {
+                command.pre_exec(move || {
+                    limits.apply()?;
+                    // 让 bubblewrap 继承 seccomp 程序的描述符
+                    match fd {
+                        Some(fd) => check(libc::fcntl(fd, libc::F_SETFD, 0)),
+                        None => Ok(()),
+                    }
+                });
+            }
+            return Ok(SandboxedCommand {
+                command,
+                backend: ShellSandboxBackend::Bubblewrap,
+                details: sandbox_details(profile, ShellSandboxBackend::Bubblewrap, &writable_paths),
+                wall_time,
+                _seccomp_program: program_file,
+            });
+        }
+
+        // 内置命名空间没有私有 /tmp，临时目录保持可写
+        let mut namespace_request_paths = request.writable_paths.clone();
+        namespace_request_paths.push(std::env::temp_dir());
+        let writable_paths = resolve_writable_paths(
+            profile,
+            &ShellSandboxRequest {
+                shell: request.shell,
+                shell_args: request.shell_args,
+                command: request.command,
+                work_dir: request.work_dir,
+                writable_paths: namespace_request_paths,
+            },
+        );
+        let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+        let read_only_mounts = parse_mountinfo(&mountinfo)
+            .into_iter()
+            .filter(|(mount_point, _)| {
+                !writable_paths
+                    .iter()
+                    .any(|path| path.as_os_str() == std::ffi::OsStr::new(mount_point))
+            })
+            .map(|(mount_point, flags)| {
+                CString::new(mount_point)
+                    .map(|mount_point| (mount_point, flags))
+                    .map_err(|_| anyhow!("挂载点包含空字符"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let plan = NamespacePlan {
+            deny_network: !profile.allow_network,
+            uid_map: CString::new(format!("{uid} {uid} 1"))?,
+            gid_map: CString::new(format!("{gid} {gid} 1"))?,
+            writable_paths: writable_paths
+                .iter()
+                .map(|path| c_path(path))
+                .collect::<Result<Vec<_>>>()?,
+            read_only_mounts,
+            work_dir: request.work_dir.map(c_path).transpose()?,
+            seccomp: seccomp_program(),
+        };
+
+        let mut command = Command::new(request.shell);
+        command.args(request.shell_args).arg(request.command);
+        if let Some(work_dir) = request.work_dir {
+            command.current_dir(work_dir);
+        }
+        unsafe {
+            command.pre_exec(move || {
+                limits.apply()?;
+                plan.enter()
+            });
+        }
+        Ok(SandboxedCommand {
+            command,
+            backend: ShellSandboxBackend::Namespaces,
+            details: sandbox_details(profile, ShellSandboxBackend::Namespaces, &writable_paths),
+            wall_time,
+            _seccomp_program: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request<'a>(work_dir: Option<&'a Path>) -> ShellSandboxRequest<'a> {
+        ShellSandboxRequest {
+            shell: "bash",
+            shell_args: &["-c"],
+            command: "echo hi",
+            work_dir,
+            writable_paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn disabled_profile_runs_shell_directly() {
+        let sandboxed =
+            build_shell_command_with_profile(&ShellSandboxProfile::default(), request(None))
+                .expect("build command");
+        assert_eq!(sandboxed.backend, ShellSandboxBackend::Disabled);
+        assert_eq!(sandboxed.command.get_program(), "bash");
+        assert_eq!(sandboxed.details["enabled"], false);
+        assert!(sandboxed.wall_time.is_none());
+    }
+
+    #[test]
+    fn profile_deserializes_missing_fields_with_defaults() {
+        let profile: ShellSandboxProfile =
+            serde_json::from_str(r#"{"enabled":true,"allow_network":false}"#).expect("parse");
+        assert!(profile.enabled);
+        assert!(!profile.allow_network);
+        assert_eq!(profile.max_processes, Some(256));
+        assert!(profile.prefer_bubblewrap);
+    }
+
+    #[test]
+    fn validate_rejects_zero_limits_and_relative_paths() {
+        let zero_memory = ShellSandboxProfile {
+            memory_mb: Some(0),
+            ..ShellSandboxProfile::default()
+        };
+        assert!(validate_shell_sandbox_profile(&zero_memory)
+            .unwrap_err()
+            .contains("内存上限"));
+        let relative = ShellSandboxProfile {
+            extra_writable_paths: vec!["cache".to_string()],
+            ..ShellSandboxProfile::default()
+        };
+        assert!(validate_shell_sandbox_profile(&relative).is_err());
+        assert!(validate_shell_sandbox_profile(&ShellSandboxProfile::default()).is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn bubblewrap_args_bind_writable_paths_and_deny_network() {
+        let profile = ShellSandboxProfile {
+            enabled: true,
+            allow_network: false,
+            ..ShellSandboxProfile::default()
+        };
+        let work_dir = PathBuf::from("/home/user/project");
+        let args = bubblewrap_args(
+            &profile,
+            &request(Some(&work_dir)),
+            &[work_dir.clone(), PathBuf::from("/tmp/task")],
+            Some(7),
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("--unshare-all --ro-bind / /"));
+        assert!(!args.contains(&"--share-net".to_string()));
+        assert!(joined.contains("--bind /home/user/project /home/user/project"));
+        assert!(joined.contains("--bind /tmp/task /tmp/task"));
+        assert!(joined.contains("--seccomp 7"));
+        assert!(joined.contains("--chdir /home/user/project"));
+        assert!(joined.ends_with("-- bash -c echo hi"));
+
+        let networked = ShellSandboxProfile {
+            allow_network: true,
+            ..profile
+        };
+        let args = bubblewrap_args(&networked, &request(None), &[], None);
+        assert!(args.contains(&"--share-net".to_string()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn sandbox_details_record_backend_network_and_limits() {
+        let profile = ShellSandboxProfile {
+            enabled: true,
+            allow_network: false,
+            ..ShellSandboxProfile::default()
+        };
+        let details = sandbox_details(
+            &profile,
+            ShellSandboxBackend::Namespaces,
+            &[PathBuf::from("/work")],
+        );
+        assert_eq!(details["backend"], "namespaces");
+        assert_eq!(details["network"], "denied");
+        assert_eq!(details["writable_paths"][0], "/work");
+        assert_eq!(details["limits"]["cpu_time_secs"], 600);
+        assert!(details["limits"]["memory_mb"].is_null());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mountinfo_parsing_keeps_locked_flags_and_decodes_paths() {
+        let raw = "22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+                   30 22 0:25 / /mnt/my\\040disk rw,nosuid,nodev,noexec - tmpfs tmpfs rw\n";
+        let mounts = linux::parse_mountinfo(raw);
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0], ("/".to_string(), libc::MS_RELATIME));
+        assert_eq!(mounts[1].0, "/mnt/my disk");
+        assert_eq!(
+            mounts[1].1,
+            libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC
+        );
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn seccomp_program_checks_arch_then_denies_listed_syscalls() {
+        let program = linux::seccomp_program().expect("seccomp filter");
+        let last = program.last().expect("deny return");
+        assert_eq!(last.k, libc::SECCOMP_RET_ERRNO | libc::EPERM as u32);
+        let allow = &program[program.len() - 2];
+        assert_eq!(allow.k, libc::SECCOMP_RET_ALLOW);
+        assert!(program
+            .iter()
+            .any(|filter| filter.k == libc::SYS_mount as u32));
+
+        let deny_index = program.len() - 1;
+        let arch_check = &program[1];
+        assert_eq!(2 + arch_check.jf as usize, deny_index);
+        #[cfg(target_arch = "x86_64")]
+        {
+            let x32_check = &program[3];
+            assert_eq!(x32_check.k, 0x4000_0000);
+            assert_eq!(4 + x32_check.jt as usize, deny_index);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::{OnceLock, RwLock};
+
+/// app_settings 中的 web_fetch 访问策略（JSON）
+pub const WEB_FETCH_POLICY_SETTING_KEY: &str = "web_fetch_policy";
+
+/// web_fetch 的网络访问策略。默认拒绝回环、内网、链路本地等非公网地址。
+///
+/// 主机规则支持：主机名（`intranet.example.com`）、通配后缀（`*.corp.example.com`）、
+/// IP（`10.0.0.8`）和 CIDR 网段（`192.168.1.0/24`）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebFetchPolicy {
+    /// 允许访问的非公网主机，如本机调试服务或公司内网站点
+    pub allowed_private_hosts: Vec<String>,
+    /// 始终拒绝的主机，优先于 allowed_private_hosts
+    pub blocked_hosts: Vec<String>,
+    pub user_agent: String,
+    pub respect_robots_txt: bool,
+    pub max_redirects: u32,
+    pub timeout_secs: u64,
+    /// 响应体读取上限，超出部分直接丢弃
+    pub max_body_bytes: u64,
+    /// 命中磁盘缓存时不再重复请求，分页读取长网页也走缓存
+    pub cache_enabled: bool,
+}
+
+impl Default for WebFetchPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_private_hosts: Vec::new(),
+            blocked_hosts: Vec::new(),
+            user_agent: "WorkClaw/1.0".to_string(),
+            respect_robots_txt: true,
+            max_redirects: 5,
+            timeout_secs: 30,
+            max_body_bytes: 5 * 1024 * 1024,
+            cache_enabled: true,
+        }
+    }
+}
+
+fn shared_web_fetch_policy() -> &'static RwLock<WebFetchPolicy> {
+    static POLICY: OnceLock<RwLock<WebFetchPolicy>> = OnceLock::new();
+    POLICY.get_or_init(|| RwLock::new(WebFetchPolicy::default()))
+}
+
+/// 进程内生效的 web_fetch 策略
+pub fn current_web_fetch_policy() -> WebFetchPolicy {
+    shared_web_fetch_policy()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+fn replace_web_fetch_policy(policy: WebFetchPolicy) {
+    *shared_web_fetch_policy()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+}
+
+pub fn validate_web_fetch_policy(policy: &WebFetchPolicy) -> Result<(), String> {
+    if policy.user_agent.trim().is_empty() {
+        return Err("User-Agent 不能为空".to_string());
+    }
+    if policy.timeout_secs == 0 {
+        return Err("请求超时必须大于 0".to_string());
+    }
+    if policy.max_body_bytes == 0 {
+        return Err("响应体上限必须大于 0".to_string());
+    }
+    if policy.max_redirects > 20 {
+        return Err("重定向次数上限不能超过 20".to_string());
+    }
+    if let Some(rule) = policy
+        .allowed_private_hosts
+        .iter()
+        .chain(policy.blocked_hosts.iter())
+        .find(|rule| rule.contains("://") || rule.contains(char::is_whitespace))
+    {
+        return Err(format!("主机规则格式无效: {rule}"));
+    }
+    Ok(())
+}
+
+fn normalize_host_rules(rules: &[String]) -> Vec<String> {
+    rules
+        .iter()
+        .map(|rule| rule.trim().to_ascii_lowercase())
+        .filter(|rule| !rule.is_empty())
+        .collect()
+}
+
+pub async fn load_web_fetch_policy_with_pool(pool: &SqlitePool) -> Result<WebFetchPolicy, String> {
+    let value =
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ? LIMIT 1")
+            .bind(WEB_FETCH_POLICY_SETTING_KEY)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("读取 web_fetch 策略失败: {e}"))?;
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(WebFetchPolicy::default()),
+        Some(raw) => serde_json::from_str(raw).map_err(|e| format!("web_fetch 策略格式无效: {e}")),
+    }
+}
+
+/// 重新读取 web_fetch 策略并刷新进程内配置
+pub async fn reload_web_fetch_policy_with_pool(
+    pool: &SqlitePool,
+) -> Result<WebFetchPolicy, String> {
+    let policy = load_web_fetch_policy_with_pool(pool).await?;
+    replace_web_fetch_policy(policy.clone());
+    Ok(policy)
+}
+
+pub async fn save_web_fetch_policy_with_pool(
+    pool: &SqlitePool,
+    mut policy: WebFetchPolicy,
+) -> Result<WebFetchPolicy, String> {
+    policy.allowed_private_hosts = normalize_host_rules(&policy.allowed_private_hosts);
+    policy.blocked_hosts = normalize_host_rules(&policy.blocked_hosts);
+    policy.user_agent = policy.user_agent.trim().to_string();
+    validate_web_fetch_policy(&policy)?;
+    let raw =
+        serde_json::to_string(&policy).map_err(|e| format!("序列化 web_fetch 策略失败: {e}"))?;
+    sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+        .bind(WEB_FETCH_POLICY_SETTING_KEY)
+        .bind(raw)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("保存 web_fetch 策略失败: {e}"))?;
+    replace_web_fetch_policy(policy.clone());
+    Ok(policy)
+}
@@ -0,0 +1,746 @@
+use crate::agent::types::ToolContext;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use similar::{Algorithm, ChangeTag, TextDiff};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 会修改工作区文件的工具，执行前后都会做快照
+pub const WORKSPACE_MUTATING_TOOLS: &[&str] = &[
+    "write_file",
+    "edit",
+    "file_delete",
+    "file_move",
+    "file_copy",
+];
+
+/// 单个文件超过此大小时不做快照，避免把大文件复制进检查点目录
+pub const MAX_CHECKPOINT_FILE_BYTES: u64 = 20 * 1024 * 1024;
+/// 单次工具调用最多快照的文件数（目录复制/删除时生效）
+pub const MAX_CHECKPOINT_FILES: usize = 2_000;
+/// 超过此行数的文本不做逐行比对，直接输出整体替换
+const MAX_DIFF_LINES: usize = 20_000;
+/// Myers 比对超时后退化为粗粒度结果，避免大文件卡住调用方
+const DIFF_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub fn is_workspace_mutating_tool(tool_name: &str) -> bool {
+    WORKSPACE_MUTATING_TOOLS.contains(&tool_name)
+}
+
+/// 检查点存储目录：`<runtime_root>/checkpoints`
+pub fn checkpoint_store_root(runtime_root: &Path) -> PathBuf {
+    runtime_root.join("checkpoints")
+}
+
+fn object_path(store_root: &Path, hash: &str) -> PathBuf {
+    store_root
+        .join("objects")
+        .join(&hash[..2.min(hash.len())])
+        .join(hash)
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 把内容写入内容寻址存储，返回 sha256
+pub fn store_object(store_root: &Path, bytes: &[u8]) -> Result<String, String> {
+    let hash = hash_bytes(bytes);
+    let path = object_path(store_root, &hash);
+    if path.exists() {
+        return Ok(hash);
+    }
+    let parent = path
+        .parent()
+        .ok_or_else(|| "检查点对象路径无效".to_string())?;
+    std::fs::create_dir_all(parent).map_err(|e| format!("创建检查点目录失败: {e}"))?;
+    let tmp_path = parent.join(format!("{hash}.{}.tmp", Uuid::new_v4()));
+    std::fs::write(&tmp_path, bytes).map_err(|e| format!("写入检查点对象失败: {e}"))?;
+    if let Err(e) = std::fs::rename(&tmp_path, &path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        if !path.exists() {
+            return Err(format!("写入检查点对象失败: {e}"));
+        }
+    }
+    Ok(hash)
+}
+
+pub fn load_object(store_root: &Path, hash: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(object_path(store_root, hash))
+        .map_err(|e| format!("读取检查点对象 {hash} 失败: {e}"))
+}
+
+/// 从工具参数中解析出会被改动的路径；解析失败的路径交给工具自己报错
+pub fn workspace_mutation_paths(
+    tool_name: &str,
+    input: &Value,
+    tool_ctx: &ToolContext,
+) -> Vec<PathBuf> {
+    let fields: &[&str] = match tool_name {
+        "write_file" | "edit" | "file_delete" => &["path"],
+        "file_move" => &["source", "destination"],
+        "file_copy" => &["destination"],
+        _ => &[],
+    };
+    let mut paths = Vec::new();
+    for field in fields {
+        let Some(raw) = input[*field]
+            .as_str()
+            .filter(|value| !value.trim().is_empty())
+        else {
+            continue;
+        };
+        let checked = tool_ctx
+            .check_path(raw)
+            .and_then(|path| ToolContext::normalize_for_scope_check(&path));
+        if let Ok(path) = checked {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// 一组路径在某一时刻的状态：文件路径 -> 内容哈希。
+/// 不存在的根路径记为 `None`，目录会展开为其中的文件。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceSnapshot {
+    pub roots: Vec<PathBuf>,
+    pub files: BTreeMap<PathBuf, Option<String>>,
+}
+
+/// 目录遍历不跟随符号链接：链接到的目录可能在工作区之外，甚至形成环
+pub fn capture_workspace_snapshot(
+    store_root: &Path,
+    roots: &[PathBuf],
+) -> Result<WorkspaceSnapshot, String> {
+    let mut snapshot = WorkspaceSnapshot {
+        roots: roots.to_vec(),
+        files: BTreeMap::new(),
+    };
+    for root in roots {
+        let Ok(metadata) = std::fs::symlink_metadata(root) else {
+            snapshot.files.entry(root.clone()).or_insert(None);
+            continue;
+        };
+        if metadata.is_dir() {
+            let mut pending = vec![root.clone()];
+            while let Some(dir) = pending.pop() {
+                let entries =
+                    std::fs::read_dir(&dir).map_err(|e| format!("读取目录快照失败: {e}"))?;
+                for entry in entries {
+                    let entry = entry.map_err(|e| format!("读取目录快照失败: {e}"))?;
+                    let file_type = entry
+                        .file_type()
+                        .map_err(|e| format!("读取目录快照失败: {e}"))?;
+                    if file_type.is_dir() {
+                        pending.push(entry.path());
+                    } else if file_type.is_file() {
+                        snapshot_file(store_root, &entry.path(), &mut snapshot.files)?;
+                    }
+                }
+            }
+        } else if root.is_file() {
+            snapshot_file(store_root, root, &mut snapshot.files)?;
+        } else if !root.exists() {
+            snapshot.files.entry(root.clone()).or_insert(None);
+        }
+    }
+    Ok(snapshot)
+}
+
+/// 快照会遍历目录并复制文件内容，放到阻塞线程池执行，不占用异步运行时
+pub async fn capture_workspace_snapshot_blocking(
+    store_root: PathBuf,
+    roots: Vec<PathBuf>,
+) -> Result<WorkspaceSnapshot, String> {
+    tokio::task::spawn_blocking(move || capture_workspace_snapshot(&store_root, &roots))
+        .await
+        .map_err(|e| format!("工作区快照线程异常: {e}"))?
+}
+
+fn snapshot_file(
+    store_root: &Path,
+    path: &Path,
+    files: &mut BTreeMap<PathBuf, Option<String>>,
+) -> Result<(), String> {
+    if files.len() >= MAX_CHECKPOINT_FILES {
+        return Err(format!(
+            "涉及文件超过 {MAX_CHECKPOINT_FILES} 个，跳过检查点"
+        ));
+    }
+    let metadata = std::fs::metadata(path).map_err(|e| format!("读取文件信息失败: {e}"))?;
+    if metadata.len() > MAX_CHECKPOINT_FILE_BYTES {
+        return Err(format!(
+            "文件 {} 超过 {} MB，跳过检查点",
+            path.display(),
+            MAX_CHECKPOINT_FILE_BYTES / 1024 / 1024
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件快照失败: {e}"))?;
+    files.insert(path.to_path_buf(), Some(store_object(store_root, &bytes)?));
+    Ok(())
+}
+
+/// 单个文件在一次工具调用前后的状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceFileChange {
+    pub path: PathBuf,
+    pub before_hash: Option<String>,
+    pub after_hash: Option<String>,
+}
+
+/// 对比前后快照，只保留内容发生变化的文件
+pub fn diff_workspace_snapshots(
+    before: &WorkspaceSnapshot,
+    after: &WorkspaceSnapshot,
+) -> Vec<WorkspaceFileChange> {
+    let paths = before
+        .files
+        .keys()
+        .chain(after.files.keys())
+        .collect::<BTreeSet<_>>();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let before_hash = before.files.get(path).cloned().flatten();
+            let after_hash = after.files.get(path).cloned().flatten();
+            (before_hash != after_hash).then(|| WorkspaceFileChange {
+                path: path.clone(),
+                before_hash,
+                after_hash,
+            })
+        })
+        .collect()
+}
+
+/// 检查点归属：哪个会话、哪次运行、第几轮、哪次工具调用
+#[derive(Debug, Clone)]
+pub struct WorkspaceCheckpointOrigin<'a> {
+    pub session_id: &'a str,
+    pub run_id: &'a str,
+    pub turn_index: i64,
+    pub call_id: &'a str,
+    pub tool_name: &'a str,
+    pub work_dir: Option<&'a Path>,
+}
+
+/// 工具执行完毕后调用：拍下“之后”的快照，有改动时写入检查点并返回其 id
+pub async fn record_workspace_checkpoint_with_pool(
+    pool: &SqlitePool,
+    store_root: &Path,
+    origin: WorkspaceCheckpointOrigin<'_>,
+    before: &WorkspaceSnapshot,
+) -> Result<Option<String>, String> {
+    let after =
+        capture_workspace_snapshot_blocking(store_root.to_path_buf(), before.roots.clone()).await?;
+    let changes = diff_workspace_snapshots(before, &after);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let checkpoint_id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("开启检查点事务失败: {e}"))?;
+    sqlx::query(
+        "INSERT INTO workspace_checkpoints
+         (id, session_id, run_id, turn_index, call_id, tool_name, work_dir, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&checkpoint_id)
+    .bind(origin.session_id)
+    .bind(origin.run_id)
+    .bind(origin.turn_index)
+    .bind(origin.call_id)
+    .bind(origin.tool_name)
+    .bind(
+        origin
+            .work_dir
+            .map(|dir| {
+                ToolContext::normalize_for_scope_check(dir)
+                    .unwrap_or_else(|_| dir.to_path_buf())
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .unwrap_or_default(),
+    )
+    .bind(&now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("写入检查点失败: {e}"))?;
+    for change in &changes {
+        sqlx::query(
+            "INSERT INTO workspace_checkpoint_files (checkpoint_id, path, before_hash, after_hash)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(&checkpoint_id)
+        .bind(change.path.to_string_lossy().to_string())
+        .bind(change.before_hash.as_deref())
+        .bind(change.after_hash.as_deref())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("写入检查点文件失败: {e}"))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交检查点失败: {e}"))?;
+    Ok(Some(checkpoint_id))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceCheckpointFile {
+    pub path: String,
+    pub before_hash: Option<String>,
+    pub after_hash: Option<String>,
+    pub reverted_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceCheckpoint {
+    pub id: String,
+    pub session_id: String,
+    pub run_id: String,
+    pub turn_index: i64,
+    pub call_id: String,
+    pub tool_name: String,
+    pub work_dir: String,
+    pub created_at: String,
+    pub files: Vec<WorkspaceCheckpointFile>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CheckpointFileRow {
+    checkpoint_id: String,
+    session_id: String,
+    run_id: String,
+    turn_index: i64,
+    call_id: String,
+    tool_name: String,
+    work_dir: String,
+    created_at: String,
+    path: String,
+    before_hash: Option<String>,
+    after_hash: Option<String>,
+    reverted_at: Option<String>,
+}
+
+/// 按时间顺序列出某次运行（可限定某一轮）的检查点
+pub async fn list_workspace_checkpoints_with_pool(
+    pool: &SqlitePool,
+    run_id: &str,
+    turn_index: Option<i64>,
+) -> Result<Vec<WorkspaceCheckpoint>, String> {
+    let rows = sqlx::query_as::<_, CheckpointFileRow>(
+        "SELECT c.id AS checkpoint_id, c.session_id, c.run_id, c.turn_index, c.call_id,
+                c.tool_name, c.work_dir, c.created_at,
+                f.path, f.before_hash, f.after_hash, f.reverted_at
+         FROM workspace_checkpoints c
+         JOIN workspace_checkpoint_files f ON f.checkpoint_id = c.id
+         WHERE c.run_id = ? AND (? IS NULL OR c.turn_index = ?)
+         ORDER BY c.created_at ASC, c.rowid ASC, f.path ASC",
+    )
+    .bind(run_id)
+    .bind(turn_index)
+    .bind(turn_index)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("读取检查点失败: {e}"))?;
+
+    let mut checkpoints: Vec<WorkspaceCheckpoint> = Vec::new();
+    for row in rows {
+        let file = WorkspaceCheckpointFile {
+            path: row.path,
+            before_hash: row.before_hash,
+            after_hash: row.after_hash,
+            reverted_at: row.reverted_at,
+        };
+        match checkpoints.last_mut() {
+            Some(last) if last.id == row.checkpoint_id => last.files.push(file),
+            _ => checkpoints.push(WorkspaceCheckpoint {
+                id: row.checkpoint_id,
+                session_id: row.session_id,
+                run_id: row.run_id,
+                turn_index: row.turn_index,
+                call_id: row.call_id,
+                tool_name: row.tool_name,
+                work_dir: row.work_dir,
+                created_at: row.created_at,
+                files: vec![file],
+            }),
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// 某个文件在一段检查点范围内的净变化：最早的“之前”与最晚的“之后”
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NetFileChange {
+    path: String,
+    work_dir: String,
+    before_hash: Option<String>,
+    after_hash: Option<String>,
+}
+
+fn net_file_changes(
+    checkpoints: &[WorkspaceCheckpoint],
+    path_filter: Option<&str>,
+) -> Vec<NetFileChange> {
+    let mut changes: BTreeMap<String, NetFileChange> = BTreeMap::new();
+    for checkpoint in checkpoints {
+        for file in &checkpoint.files {
+            if file.reverted_at.is_some() {
+                continue;
+            }
+            if path_filter
+                .is_some_and(|filter| !same_path(filter, &file.path, &checkpoint.work_dir))
+            {
+                continue;
+            }
+            changes
+                .entry(file.path.clone())
+                .and_modify(|change| change.after_hash = file.after_hash.clone())
+                .or_insert_with(|| NetFileChange {
+                    path: file.path.clone(),
+                    work_dir: checkpoint.work_dir.clone(),
+                    before_hash: file.before_hash.clone(),
+                    after_hash: file.after_hash.clone(),
+                });
+        }
+    }
+    changes.into_values().collect()
+}
+
+/// 路径可以是绝对路径，也可以是相对工作目录的路径
+fn same_path(filter: &str, recorded: &str, work_dir: &str) -> bool {
+    let filter_path = Path::new(filter);
+    if filter_path.is_absolute() || work_dir.is_empty() {
+        return filter_path == Path::new(recorded);
+    }
+    Path::new(work_dir).join(filter_path) == Path::new(recorded)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceFileDiff {
+    pub path: String,
+    /// added / deleted / modified
+    pub status: String,
+    pub before_hash: Option<String>,
+    pub after_hash: Option<String>,
+    pub binary: bool,
+    pub diff: String,
+}
+
+/// 汇总某次运行（可限定某一轮）对工作区造成的净改动
+pub async fn diff_workspace_checkpoints_with_pool(
+    pool: &SqlitePool,
+    store_root: &Path,
+    run_id: &str,
+    turn_index: Option<i64>,
+) -> Result<Vec<WorkspaceFileDiff>, String> {
+    let checkpoints = list_workspace_checkpoints_with_pool(pool, run_id, turn_index).await?;
+    net_file_changes(&checkpoints, None)
+        .into_iter()
+        .filter(|change| change.before_hash != change.after_hash)
+        .map(|change| {
+            let before = change
+                .before_hash
+                .as_deref()
+                .map(|hash| load_object(store_root, hash))
+                .transpose()?;
+            let after = change
+                .after_hash
+                .as_deref()
+                .map(|hash| load_object(store_root, hash))
+                .transpose()?;
+            let status = match (&before, &after) {
+                (None, _) => "added",
+                (_, None) => "deleted",
+                _ => "modified",
+            };
+            let before_text = before.as_deref().map(std::str::from_utf8).transpose();
+            let after_text = after.as_deref().map(std::str::from_utf8).transpose();
+            let (binary, diff) = match (before_text, after_text) {
+                (Ok(before_text), Ok(after_text)) => (
+                    false,
+                    unified_line_diff(
+                        &change.path,
+                        before_text.unwrap_or_default(),
+                        after_text.unwrap_or_default(),
+                    ),
+                ),
+                _ => (true, String::new()),
+            };
+            Ok(WorkspaceFileDiff {
+                path: change.path,
+                status: status.to_string(),
+                before_hash: change.before_hash,
+                after_hash: change.after_hash,
+                binary,
+                diff,
+            })
+        })
+        .collect()
+}
+
+/// 基于 Myers 算法的逐行 diff，输出带 `---/+++` 头的统一格式（不分 hunk）
+pub fn unified_line_diff(path: &str, before: &str, after: &str) -> String {
+    if before == after {
+        return String::new();
+    }
+    let before_lines = before.lines().collect::<Vec<_>>();
+    let after_lines = after.lines().collect::<Vec<_>>();
+    let mut out = vec![format!("--- a/{path}"), format!("+++ b/{path}")];
+
+    if before_lines.len() > MAX_DIFF_LINES || after_lines.len() > MAX_DIFF_LINES {
+        out.extend(before_lines.iter().map(|line| format!("-{line}")));
+        out.extend(after_lines.iter().map(|line| format!("+{line}")));
+        return out.join("\n");
+    }
+
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .timeout(DIFF_TIMEOUT)
+        .diff_slices(&before_lines, &after_lines);
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Equal => ' ',
+            ChangeTag::Delete => '-',
+            ChangeTag::Insert => '+',
+        };
+        out.push(format!("{sign}{}", change.value()));
+    }
+    out.join("\n")
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceRevertResult {
+    pub restored: Vec<String>,
+    pub removed: Vec<String>,
+    /// 当前内容已被后续操作改动、因未设置 force 而跳过的文件
+    pub conflicts: Vec<String>,
+}
+
+fn current_file_hash(path: &Path) -> Result<Option<String>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    std::fs::read(path)
+        .map(|bytes| Some(hash_bytes(&bytes)))
+        .map_err(|e| format!("读取文件 {} 失败: {e}", path.display()))
+}
+
+/// 删除文件后顺带清理变空的父目录，但不越过工作目录
+fn prune_empty_parents(path: &Path, work_dir: &str) {
+    let stop = Path::new(work_dir);
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        if work_dir.is_empty() || dir == stop || !dir.starts_with(stop) {
+            break;
+        }
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+/// 把一段检查点范围内改动过的文件恢复到改动前的内容。
+/// `turn_index` 为空时回退整次运行，`path` 不为空时只回退该文件。
+/// 文件当前内容与检查点记录的“之后”不一致时视为冲突，除非 `force`。
+pub async fn revert_workspace_checkpoints_with_pool(
+    pool: &SqlitePool,
+    store_root: &Path,
+    run_id: &str,
+    turn_index: Option<i64>,
+    path: Option<&str>,
+    force: bool,
+) -> Result<WorkspaceRevertResult, String> {
+    let checkpoints = list_workspace_checkpoints_with_pool(pool, run_id, turn_index).await?;
+    let changes = net_file_changes(&checkpoints, path);
+    if changes.is_empty() {
+        return Err(match path {
+            Some(path) => format!("没有可回退的改动: {path}"),
+            None => "没有可回退的改动".to_string(),
+        });
+    }
+
+    let mut result = WorkspaceRevertResult::default();
+    let mut reverted_paths = Vec::new();
+    for change in changes {
+        let target = PathBuf::from(&change.path);
+        if !force && current_file_hash(&target)? != change.after_hash {
+            result.conflicts.push(change.path);
+            continue;
+        }
+        match change.before_hash.as_deref() {
+            Some(hash) => {
+                let bytes = load_object(store_root, hash)?;
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("创建目录 {} 失败: {e}", parent.display()))?;
+                }
+                std::fs::write(&target, bytes)
+                    .map_err(|e| format!("恢复文件 {} 失败: {e}", change.path))?;
+                result.restored.push(change.path.clone());
+            }
+            None => {
+                if target.is_file() {
+                    std::fs::remove_file(&target)
+                        .map_err(|e| format!("删除文件 {} 失败: {e}", change.path))?;
+                    prune_empty_parents(&target, &change.work_dir);
+                }
+                result.removed.push(change.path.clone());
+            }
+        }
+        reverted_paths.push(change.path);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let checkpoint_ids = checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.id.as_str())
+        .collect::<Vec<_>>();
+    for reverted_path in &reverted_paths {
+        for checkpoint_id in &checkpoint_ids {
+            sqlx::query(
+                "UPDATE workspace_checkpoint_files SET reverted_at = ?
+                 WHERE checkpoint_id = ? AND path = ? AND reverted_at IS NULL",
+            )
+            .bind(&now)
+            .bind(checkpoint_id)
+            .bind(reverted_path)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("更新检查点状态失败: {e}"))?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_line_diff_keeps_context_and_marks_changes() {
+        let diff = unified_line_diff("a.txt", "one\ntwo\nthree\n", "one\n2\nthree\nfour\n");
+        assert_eq!(
+            diff,
+            "--- a/a.txt\n+++ b/a.txt\n one\n-two\n+2\n three\n+four"
+        );
+        assert!(unified_line_diff("a.txt", "same", "same").is_empty());
+    }
+
+    #[test]
+    fn unified_line_diff_handles_large_files() {
+        let before = (0..10_000)
+            .map(|index| format!("line {index}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let after = before.replace("line 5000\n", "line five thousand\n");
+        let diff = unified_line_diff("big.txt", &before, &after);
+        assert!(diff.contains("\n-line 5000\n+line five thousand\n"));
+        assert_eq!(diff.lines().filter(|line| line.starts_with('-')).count(), 2);
+    }
+
+    #[test]
+    fn mutation_paths_follow_each_tool_schema() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let ctx = ToolContext {
+            work_dir: Some(dir.path().to_path_buf()),
+            ..ToolContext::default()
+        };
+        let root = dir.path().canonicalize().expect("canonical root");
+        assert_eq!(
+            workspace_mutation_paths("write_file", &serde_json::json!({"path": "a.txt"}), &ctx),
+            vec![root.join("a.txt")]
+        );
+        assert_eq!(
+            workspace_mutation_paths(
+                "file_move",
+                &serde_json::json!({"source": "a.txt", "destination": "b.txt"}),
+                &ctx
+            ),
+            vec![root.join("a.txt"), root.join("b.txt")]
+        );
+        assert!(
+            workspace_mutation_paths("read_file", &serde_json::json!({"path": "a"}), &ctx)
+                .is_empty()
+        );
+        assert!(
+            workspace_mutation_paths("write_file", &serde_json::json!({"path": "../x"}), &ctx)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn snapshot_diff_reports_added_modified_and_deleted_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = dir.path().join("store");
+        let work = dir.path().join("work");
+        std::fs::create_dir_all(work.join("sub")).expect("mkdir");
+        std::fs::write(work.join("sub/keep.txt"), "keep").expect("write");
+        std::fs::write(work.join("sub/edit.txt"), "v1").expect("write");
+        std::fs::write(work.join("sub/gone.txt"), "bye").expect("write");
+
+        let roots = vec![work.join("sub"), work.join("new.txt")];
+        let before = capture_workspace_snapshot(&store, &roots).expect("before");
+        std::fs::write(work.join("sub/edit.txt"), "v2").expect("write");
+        std::fs::remove_file(work.join("sub/gone.txt")).expect("remove");
+        std::fs::write(work.join("new.txt"), "hi").expect("write");
+        let after = capture_workspace_snapshot(&store, &roots).expect("after");
+
+        let changes = diff_workspace_snapshots(&before, &after);
+        let paths = changes
+            .iter()
+            .map(|change| change.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                work.join("new.txt"),
+                work.join("sub/edit.txt"),
+                work.join("sub/gone.txt")
+            ]
+        );
+        assert!(changes[0].before_hash.is_none());
+        assert!(changes[2].after_hash.is_none());
+        let restored =
+            load_object(&store, changes[1].before_hash.as_deref().unwrap()).expect("load object");
+        assert_eq!(restored, b"v1");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn snapshot_skips_symlinked_directories() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = dir.path().join("store");
+        let work = dir.path().join("work");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(&work).expect("mkdir");
+        std::fs::create_dir_all(&outside).expect("mkdir");
+        std::fs::write(work.join("own.txt"), "own").expect("write");
+        std::fs::write(outside.join("secret.txt"), "secret").expect("write");
+        std::os::unix::fs::symlink(&outside, work.join("linked")).expect("symlink");
+        std::os::unix::fs::symlink(&work, work.join("loop")).expect("symlink");
+
+        let snapshot =
+            capture_workspace_snapshot(&store, std::slice::from_ref(&work)).expect("snapshot");
+        assert_eq!(
+            snapshot.files.keys().cloned().collect::<Vec<_>>(),
+            vec![work.join("own.txt")]
+        );
+        let linked_root = capture_workspace_snapshot(&store, &[work.join("linked")])
+            .expect("snapshot linked root");
+        assert!(linked_root.files.is_empty());
+    }
+}
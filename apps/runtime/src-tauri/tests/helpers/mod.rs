@@ -3,8 +3,16 @@ use sqlx::SqlitePool;
 use std::path::PathBuf;
 use tempfile::TempDir;
 
+/// 让加密凭据使用临时目录下的测试密钥，而不是用户配置目录中的真实密钥
+pub fn isolate_secret_key() {
+    runtime_lib::secret_store::override_secret_key_path(
+        std::env::temp_dir().join("workclaw-integration-test-secret.key"),
+    );
+}
+
 /// 创建临时 SQLite 数据库，复制完整 schema（与 db.rs 保持一致）
 pub async fn setup_test_db() -> (SqlitePool, TempDir) {
+    isolate_secret_key();
     let tmp = TempDir::new().unwrap();
     let db_path = tmp.path().join("test.db");
     let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
@@ -78,6 +86,7 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             buffered_text TEXT NOT NULL DEFAULT '',
             error_kind TEXT NOT NULL DEFAULT '',
             error_message TEXT NOT NULL DEFAULT '',
+            structured_output_json TEXT NOT NULL DEFAULT '',
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL
         )",
@@ -137,6 +146,13 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             created_by_surface TEXT NOT NULL DEFAULT '',
             created_by_user TEXT NOT NULL DEFAULT '',
             enabled INTEGER NOT NULL DEFAULT 1,
+            effect TEXT NOT NULL DEFAULT 'allow',
+            match_kind TEXT NOT NULL DEFAULT 'fingerprint',
+            pattern TEXT NOT NULL DEFAULT '',
+            scope_kind TEXT NOT NULL DEFAULT 'global',
+            scope_value TEXT NOT NULL DEFAULT '',
+            expires_at TEXT NOT NULL DEFAULT '',
+            note TEXT NOT NULL DEFAULT '',
             created_at TEXT NOT NULL,
             updated_at TEXT NOT NULL,
             UNIQUE(tool_name, fingerprint)
@@ -155,7 +171,8 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             model_name TEXT NOT NULL,
             is_default INTEGER DEFAULT 0,
             api_key TEXT NOT NULL DEFAULT '',
-            supports_vision INTEGER NOT NULL DEFAULT 0
+            supports_vision INTEGER NOT NULL DEFAULT 0,
+            reasoning_json TEXT NOT NULL DEFAULT '{}'
         )",
     )
     .execute(&pool)
@@ -169,6 +186,9 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             command TEXT NOT NULL,
             args TEXT NOT NULL DEFAULT '[]',
             env TEXT NOT NULL DEFAULT '{}',
+            transport TEXT NOT NULL DEFAULT 'stdio',
+            url TEXT NOT NULL DEFAULT '',
+            headers TEXT NOT NULL DEFAULT '{}',
             enabled INTEGER DEFAULT 1,
             created_at TEXT NOT NULL
         )",
@@ -244,7 +264,8 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             fallback_chain_json TEXT NOT NULL DEFAULT '[]',
             timeout_ms INTEGER NOT NULL DEFAULT 60000,
             retry_count INTEGER NOT NULL DEFAULT 0,
-            enabled INTEGER NOT NULL DEFAULT 1
+            enabled INTEGER NOT NULL DEFAULT 1,
+            reasoning_json TEXT NOT NULL DEFAULT '{}'
         )",
     )
     .execute(&pool)
@@ -263,6 +284,81 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
             error_kind TEXT NOT NULL DEFAULT '',
             success INTEGER NOT NULL DEFAULT 0,
             error_message TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL,
+            base_url TEXT NOT NULL DEFAULT '',
+            latency_ms INTEGER NOT NULL DEFAULT 0
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS model_usage_records (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            run_id TEXT NOT NULL DEFAULT '',
+            employee_id TEXT NOT NULL DEFAULT '',
+            provider_key TEXT NOT NULL DEFAULT '',
+            api_format TEXT NOT NULL DEFAULT '',
+            model_name TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_model_usage_records_session
+         ON model_usage_records(session_id, created_at)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS model_prices (
+            id TEXT PRIMARY KEY,
+            provider_key TEXT NOT NULL DEFAULT '',
+            model_pattern TEXT NOT NULL,
+            input_per_million REAL NOT NULL DEFAULT 0,
+            output_per_million REAL NOT NULL DEFAULT 0,
+            cache_read_per_million REAL NOT NULL DEFAULT 0,
+            cache_write_per_million REAL NOT NULL DEFAULT 0,
+            currency TEXT NOT NULL DEFAULT 'USD',
+            updated_at TEXT NOT NULL,
+            UNIQUE(provider_key, model_pattern)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS model_catalog_overrides (
+            model TEXT PRIMARY KEY,
+            entry_json TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS workspace_checkpoints (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            run_id TEXT NOT NULL,
+            turn_index INTEGER NOT NULL DEFAULT 0,
+            call_id TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            work_dir TEXT NOT NULL DEFAULT '',
             created_at TEXT NOT NULL
         )",
     )
@@ -270,6 +366,99 @@ pub async fn setup_test_db() -> (SqlitePool, TempDir) {
     .await
     .unwrap();
 
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_workspace_checkpoints_run
+         ON workspace_checkpoints(run_id, turn_index, created_at)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS workspace_checkpoint_files (
+            checkpoint_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            before_hash TEXT,
+            after_hash TEXT,
+            reverted_at TEXT,
+            PRIMARY KEY (checkpoint_id, path)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scheduled_jobs (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            target_kind TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            prompt TEXT NOT NULL,
+            work_dir TEXT NOT NULL DEFAULT '',
+            permission_mode TEXT NOT NULL DEFAULT 'standard',
+            model_id TEXT NOT NULL DEFAULT '',
+            schedule_kind TEXT NOT NULL,
+            cron_expr TEXT NOT NULL DEFAULT '',
+            run_at TEXT NOT NULL DEFAULT '',
+            missed_run_policy TEXT NOT NULL DEFAULT 'catch_up',
+            delivery_route_key TEXT NOT NULL DEFAULT '',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            next_run_at TEXT NOT NULL DEFAULT '',
+            last_run_at TEXT NOT NULL DEFAULT '',
+            last_status TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scheduled_job_runs (
+            id TEXT PRIMARY KEY,
+            job_id TEXT NOT NULL,
+            trigger_kind TEXT NOT NULL,
+            scheduled_for TEXT NOT NULL DEFAULT '',
+            started_at TEXT NOT NULL,
+            finished_at TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL,
+            session_id TEXT NOT NULL DEFAULT '',
+            output TEXT NOT NULL DEFAULT '',
+            error TEXT NOT NULL DEFAULT '',
+            delivery_status TEXT NOT NULL DEFAULT '',
+            delivery_error TEXT NOT NULL DEFAULT ''
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_scheduled_job_runs_job
+         ON scheduled_job_runs(job_id, started_at)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS employee_run_budgets (
+            employee_id TEXT PRIMARY KEY,
+            max_input_tokens INTEGER,
+            max_output_tokens INTEGER,
+            max_cost REAL,
+            max_wall_clock_secs INTEGER,
+            max_tool_calls INTEGER,
+            warning_ratio REAL,
+            updated_at TEXT NOT NULL
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS im_event_dedup (
             event_id TEXT PRIMARY KEY,
@@ -725,6 +914,7 @@ pub async fn seed_default_model_config(pool: &SqlitePool) {
 
 #[allow(dead_code)]
 pub async fn setup_legacy_thread_only_db() -> (SqlitePool, TempDir) {
+    isolate_secret_key();
     let tmp = TempDir::new().unwrap();
     let db_path = tmp.path().join("legacy-thread-only.db");
     let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
@@ -1,5 +1,5 @@
 use runtime_lib::agent::permissions::PermissionMode;
-use runtime_lib::agent::{AgentExecutor, ToolRegistry};
+use runtime_lib::agent::{AgentExecutor, AgentTurnRequest, ToolRegistry};
 use serde_json::json;
 use std::sync::Arc;
 
@@ -15,23 +15,17 @@ async fn test_anthropic_tool_parsing() {
 
     let result = executor
         .execute_turn(
-            "anthropic",
-            "http://mock",
-            "mock-key",
-            "claude-3-5-haiku-20241022",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "anthropic",
+                base_url: "http://mock",
+                api_key: "mock-key",
+                model: "claude-3-5-haiku-20241022",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -48,23 +42,17 @@ async fn test_anthropic_tool_parsing_network_error() {
 
     let result = executor
         .execute_turn(
-            "anthropic",
-            "http://invalid-mock-url-that-does-not-exist",
-            "mock-key",
-            "mock-model",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "anthropic",
+                base_url: "http://invalid-mock-url-that-does-not-exist",
+                api_key: "mock-key",
+                model: "mock-model",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
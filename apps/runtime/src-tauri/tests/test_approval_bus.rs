@@ -5,7 +5,9 @@ use runtime_lib::approval_bus::approval_bus_rollout_enabled_with_pool;
 use runtime_lib::approval_bus::recover_approved_pending_work_with_pool;
 use runtime_lib::approval_bus::{ApprovalDecision, ApprovalManager, ApprovalResolveResult};
 use runtime_lib::approval_rules::{
-    find_matching_approval_rule_with_pool, list_approval_rules_with_pool,
+    explain_approval_rule_match_with_pool, find_matching_approval_rule_with_pool,
+    list_approval_rules_with_pool, save_approval_rule_with_pool,
+    set_approval_rule_enabled_with_pool, ApprovalRuleInput, ApprovalRuleProbe,
 };
 use runtime_lib::commands::approvals::list_pending_approvals_with_pool;
 use runtime_lib::commands::session_runs::{
@@ -353,6 +355,101 @@ async fn allow_always_creates_reusable_rule_and_skips_reapproval() {
     assert!(unmatched_bash.is_none());
 }
 
+#[tokio::test]
+async fn pattern_rules_deny_first_and_explain_matches() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    let allow = save_approval_rule_with_pool(
+        &pool,
+        ApprovalRuleInput {
+            tool_name: "bash".to_string(),
+            effect: "allow".to_string(),
+            match_kind: "command_glob".to_string(),
+            pattern: "git *".to_string(),
+            scope_kind: "global".to_string(),
+            scope_value: String::new(),
+            expires_at: None,
+            note: "git 命令免确认".to_string(),
+        },
+        "desktop",
+    )
+    .await
+    .expect("save allow rule");
+    let deny = save_approval_rule_with_pool(
+        &pool,
+        ApprovalRuleInput {
+            tool_name: "*".to_string(),
+            effect: "deny".to_string(),
+            match_kind: "command_regex".to_string(),
+            pattern: r"git\s+push\s+.*--force".to_string(),
+            scope_kind: "global".to_string(),
+            scope_value: String::new(),
+            expires_at: None,
+            note: String::new(),
+        },
+        "desktop",
+    )
+    .await
+    .expect("save deny rule");
+
+    let probe = |command: &str| ApprovalRuleProbe {
+        tool_name: "bash".to_string(),
+        input: json!({ "command": command }),
+        session_id: None,
+        employee_id: None,
+        work_dir: None,
+    };
+
+    let status = explain_approval_rule_match_with_pool(&pool, probe("git status"))
+        .await
+        .expect("explain git status");
+    assert!(status.is_allow());
+    assert_eq!(
+        status.matched_rule.map(|rule| rule.id),
+        Some(allow.id.clone())
+    );
+
+    let force_push = explain_approval_rule_match_with_pool(&pool, probe("git push origin --force"))
+        .await
+        .expect("explain force push");
+    assert!(force_push.is_deny());
+    assert_eq!(
+        force_push.matched_rule.map(|rule| rule.id),
+        Some(deny.id.clone())
+    );
+    assert_eq!(force_push.shadowed_rules.len(), 1);
+
+    let chained = explain_approval_rule_match_with_pool(&pool, probe("git status && rm -rf /"))
+        .await
+        .expect("explain chained command");
+    assert_eq!(chained.effect, None);
+
+    set_approval_rule_enabled_with_pool(&pool, &deny.id, false)
+        .await
+        .expect("disable deny rule");
+    let force_push = explain_approval_rule_match_with_pool(&pool, probe("git push origin --force"))
+        .await
+        .expect("explain force push after disable");
+    assert!(force_push.is_allow());
+
+    let expired = save_approval_rule_with_pool(
+        &pool,
+        ApprovalRuleInput {
+            tool_name: "bash".to_string(),
+            effect: "allow".to_string(),
+            match_kind: "command_glob".to_string(),
+            pattern: "npm test".to_string(),
+            scope_kind: "global".to_string(),
+            scope_value: String::new(),
+            expires_at: Some("2000-01-01T00:00:00Z".to_string()),
+            note: String::new(),
+        },
+        "desktop",
+    )
+    .await;
+    assert!(expired.is_err());
+}
+
 #[tokio::test]
 async fn approved_pending_work_resumes_after_restart() {
     let (pool, _tmp) = helpers::setup_test_db().await;
@@ -27,6 +27,8 @@ fn test_bash_simple_command() {
         .as_str()
         .unwrap_or_default()
         .contains("Hello"));
+    assert_eq!(parsed["details"]["sandbox"]["enabled"], false);
+    assert_eq!(parsed["details"]["sandbox"]["backend"], "disabled");
 }
 
 #[test]
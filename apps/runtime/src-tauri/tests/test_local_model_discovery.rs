@@ -0,0 +1,96 @@
+mod helpers;
+
+use runtime_lib::commands::models::{
+    discover_local_provider_models_with_pool, list_provider_models_from_pool,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+async fn spawn_ollama_tags_server() -> (String, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock ollama server");
+    let addr = listener.local_addr().expect("local addr");
+    let handle = tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 16 * 1024];
+            let n = socket.read(&mut buf).await.expect("read request");
+            let raw = String::from_utf8_lossy(&buf[..n]).to_string();
+            let request_line = raw.lines().next().unwrap_or_default().to_string();
+            let body = if request_line.starts_with("GET /api/tags") {
+                r#"{"models":[{"name":"qwen2.5:7b","size":1},{"name":"llava:latest","size":2}]}"#
+            } else {
+                r#"{"error":"not found"}"#
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket
+                .write_all(response.as_bytes())
+                .await
+                .expect("write response");
+        }
+    });
+    (format!("http://{addr}"), handle)
+}
+
+async fn insert_provider(pool: &sqlx::SqlitePool, id: &str, provider_key: &str, base_url: &str) {
+    sqlx::query(
+        "INSERT INTO provider_configs (id, provider_key, display_name, protocol_type, base_url, auth_type, api_key_encrypted, org_id, extra_json, enabled, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, 'api_key', '', '', '{}', 1, datetime('now'), datetime('now'))",
+    )
+    .bind(id)
+    .bind(provider_key)
+    .bind(provider_key)
+    .bind(provider_key)
+    .bind(base_url)
+    .execute(pool)
+    .await
+    .expect("insert provider");
+}
+
+#[tokio::test]
+async fn ollama_provider_models_come_from_local_discovery() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let (base_url, server) = spawn_ollama_tags_server().await;
+    insert_provider(&pool, "local-ollama", "ollama", &format!("{base_url}/v1")).await;
+
+    let discovered = discover_local_provider_models_with_pool(&pool, "local-ollama")
+        .await
+        .expect("discover local models");
+    assert_eq!(discovered, vec!["llava:latest", "qwen2.5:7b"]);
+
+    let cached: Vec<String> = sqlx::query_scalar(
+        "SELECT model_id FROM model_catalog_cache WHERE provider_id = 'local-ollama' ORDER BY model_id",
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("read cache");
+    assert_eq!(cached, discovered);
+
+    let vision = list_provider_models_from_pool(&pool, "local-ollama", Some("vision"))
+        .await
+        .expect("list vision models");
+    assert_eq!(vision, vec!["llava:latest"]);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn discovery_rejects_non_local_providers() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    insert_provider(
+        &pool,
+        "deepseek-1",
+        "deepseek",
+        "https://api.deepseek.com/v1",
+    )
+    .await;
+
+    let err = discover_local_provider_models_with_pool(&pool, "deepseek-1")
+        .await
+        .expect_err("non-ollama provider should be rejected");
+    assert!(err.contains("Ollama"));
+}
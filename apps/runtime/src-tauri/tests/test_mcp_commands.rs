@@ -1,10 +1,11 @@
 mod helpers;
 
 use runtime_lib::agent::tool_manifest::{ToolCategory, ToolSource};
+use runtime_lib::agent::tools::NativeMcpTransport;
 use runtime_lib::agent::{ToolContext, ToolRegistry};
 use runtime_lib::commands::mcp::{
-    add_mcp_server_with_registry, remove_mcp_server_with_registry,
-    restore_saved_mcp_servers_with_registry,
+    add_mcp_server_with_registry, add_remote_mcp_server_with_registry, list_mcp_servers_with_pool,
+    remove_mcp_server_with_registry, restore_saved_mcp_servers_with_registry,
 };
 use serde_json::json;
 use std::collections::HashMap;
@@ -45,7 +46,10 @@ TOOLS = [
             "properties": {"message": {"type": "string"}},
             "required": ["message"],
         },
-    }
+    },
+    {"name": "pid", "description": "Return the mock server process id"},
+    {"name": "exit", "description": "Terminate the mock server process"},
+    {"name": "grow", "description": "Add a tool and announce tools/list_changed"},
 ]
 
 def read_message():
@@ -84,12 +88,41 @@ while True:
             "id": request_id,
             "result": {
                 "protocolVersion": "2025-03-26",
-                "capabilities": {"tools": {}},
+                "capabilities": {"tools": {"listChanged": True}, "resources": {}},
                 "serverInfo": {"name": "workclaw-test-mcp", "version": "0.0.1"},
             },
         }
     elif method == "tools/list":
         response = {"jsonrpc": "2.0", "id": request_id, "result": {"tools": TOOLS}}
+    elif method == "resources/list":
+        response = {
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "result": {"resources": [{"uri": "mock://readme", "name": "readme"}]},
+        }
+    elif method == "resources/read":
+        uri = request.get("params", {}).get("uri")
+        response = {
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "result": {"contents": [{"uri": uri, "text": "mock readme"}]},
+        }
+    elif method == "tools/call" and request["params"]["name"] == "exit":
+        break
+    elif method == "tools/call" and request["params"]["name"] == "pid":
+        response = {
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "result": {"content": [{"type": "text", "text": str(os.getpid())}]},
+        }
+    elif method == "tools/call" and request["params"]["name"] == "grow":
+        TOOLS.append({"name": "late", "description": "Tool added after initialize"})
+        write_message({"jsonrpc": "2.0", "method": "notifications/tools/list_changed"})
+        response = {
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "result": {"content": [{"type": "text", "text": "grown"}]},
+        }
     elif method == "tools/call":
         params = request.get("params", {})
         args = params.get("arguments", {})
@@ -144,6 +177,13 @@ async fn add_mcp_server_stores_row_and_registers_native_tool() {
         .await
         .expect("count mcp server row");
     assert_eq!(count, 1);
+    let (stored_env,): (String,) = sqlx::query_as("SELECT env FROM mcp_servers WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .expect("read mcp server env");
+    assert!(runtime_lib::secret_store::is_sealed(&stored_env));
+    assert!(!stored_env.contains("WORKCLAW_MOCK_MCP_LABEL"));
 
     let tool = registry.get("mcp_docs_echo").expect("registered mcp tool");
     let metadata = tool.metadata();
@@ -238,3 +278,139 @@ async fn restore_saved_mcp_servers_registers_enabled_servers_without_sidecar_htt
     assert_eq!(restored, 1);
     assert!(registry.get("mcp_saved_echo").is_some());
 }
+
+#[tokio::test]
+async fn list_mcp_servers_masks_header_values() {
+    let (pool, _db_tmp) = helpers::setup_test_db().await;
+    let headers = runtime_lib::secret_store::seal_secret(r#"{"Authorization":"Bearer sk-live"}"#)
+        .expect("seal headers");
+
+    sqlx::query(
+        "INSERT INTO mcp_servers (id, name, command, args, env, transport, url, headers, enabled, created_at) VALUES (?, ?, '', '[]', '{}', 'streamable_http', ?, ?, 0, ?)",
+    )
+    .bind("remote-id")
+    .bind("remote")
+    .bind("https://mcp.example.com/mcp")
+    .bind(&headers)
+    .bind("2026-05-11T00:00:00Z")
+    .execute(&pool)
+    .await
+    .expect("insert remote server");
+
+    let servers = list_mcp_servers_with_pool(&pool)
+        .await
+        .expect("list mcp servers");
+    assert_eq!(servers[0]["headers"], json!({"Authorization": "******"}));
+    assert!(!servers[0].to_string().contains("sk-live"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn native_mcp_session_is_reused_and_reconnects_after_server_exit() {
+    let (pool, _db_tmp) = helpers::setup_test_db().await;
+    let registry = Arc::new(ToolRegistry::new());
+    let (_server_tmp, script) = write_mock_mcp_server();
+
+    add_mcp_server_with_registry(
+        &pool,
+        Arc::clone(&registry),
+        "session".to_string(),
+        python_command(),
+        vec![script],
+        HashMap::new(),
+    )
+    .await
+    .expect("add native MCP server");
+
+    let pid_tool = registry.get("mcp_session_pid").expect("pid tool");
+    let first = pid_tool
+        .execute(json!({}), &ToolContext::default())
+        .expect("first pid");
+    let second = pid_tool
+        .execute(json!({}), &ToolContext::default())
+        .expect("second pid");
+    assert_eq!(first, second, "calls should share one long-lived session");
+
+    let _ = registry
+        .get("mcp_session_exit")
+        .expect("exit tool")
+        .execute(json!({}), &ToolContext::default());
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let third = pid_tool
+        .execute(json!({}), &ToolContext::default())
+        .expect("pid after reconnect");
+    assert_ne!(first, third, "a new server process should be spawned");
+
+    let servers = list_mcp_servers_with_pool(&pool)
+        .await
+        .expect("list servers");
+    assert_eq!(servers[0]["transport"], json!("stdio"));
+    assert_eq!(servers[0]["health"]["status"], json!("connected"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn native_mcp_resources_and_list_changed_are_surfaced_as_tools() {
+    let (pool, _db_tmp) = helpers::setup_test_db().await;
+    let registry = Arc::new(ToolRegistry::new());
+    let (_server_tmp, script) = write_mock_mcp_server();
+
+    add_mcp_server_with_registry(
+        &pool,
+        Arc::clone(&registry),
+        "res".to_string(),
+        python_command(),
+        vec![script],
+        HashMap::new(),
+    )
+    .await
+    .expect("add native MCP server");
+
+    assert!(registry.get("mcp_res_list_resources").is_some());
+    assert!(registry.get("mcp_res_list_prompts").is_none());
+    let read = registry
+        .get("mcp_res_read_resource")
+        .expect("read resource tool");
+    assert!(read.metadata().read_only);
+    let output = read
+        .execute(json!({"uri": "mock://readme"}), &ToolContext::default())
+        .expect("read resource");
+    assert_eq!(output, "[mock://readme]\nmock readme");
+
+    registry
+        .get("mcp_res_grow")
+        .expect("grow tool")
+        .execute(json!({}), &ToolContext::default())
+        .expect("grow");
+    for _ in 0..50 {
+        if registry.get("mcp_res_late").is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(registry.get("mcp_res_late").is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn add_remote_mcp_server_rolls_back_when_unreachable() {
+    let (pool, _db_tmp) = helpers::setup_test_db().await;
+    let registry = Arc::new(ToolRegistry::new());
+
+    let error = add_remote_mcp_server_with_registry(
+        &pool,
+        Arc::clone(&registry),
+        "remote".to_string(),
+        NativeMcpTransport::StreamableHttp,
+        "http://127.0.0.1:9/mcp".to_string(),
+        HashMap::new(),
+    )
+    .await
+    .expect_err("unreachable server should fail");
+    assert!(error.contains("remote"), "{error}");
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM mcp_servers")
+        .fetch_one(&pool)
+        .await
+        .expect("count mcp server rows");
+    assert_eq!(count, 0);
+    assert!(registry.tools_with_prefix("mcp_remote_").is_empty());
+}
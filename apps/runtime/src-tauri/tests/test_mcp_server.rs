@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use runtime_lib::mcp_server::{
+    ExposedEmployee, ExposedSkill, ExternalTurnOutcome, ExternalTurnRequest, ExternalTurnTarget,
+    McpHttpServer, McpServerBackend, McpServerDispatcher,
+};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TOKEN: &str = "test-token";
+
+#[derive(Default)]
+struct FakeBackend {
+    turns: Mutex<Vec<ExternalTurnRequest>>,
+}
+
+#[async_trait]
+impl McpServerBackend for FakeBackend {
+    async fn list_skills(&self) -> Result<Vec<ExposedSkill>, String> {
+        Ok(vec![ExposedSkill {
+            id: "skill-a".to_string(),
+            name: "Skill A".to_string(),
+            description: "does a".to_string(),
+            version: "1.0.0".to_string(),
+            tags: vec!["demo".to_string()],
+            source_type: "local".to_string(),
+        }])
+    }
+
+    async fn list_employees(&self) -> Result<Vec<ExposedEmployee>, String> {
+        Ok(vec![ExposedEmployee {
+            employee_id: "alice".to_string(),
+            name: "Alice".to_string(),
+            role_id: "analyst".to_string(),
+            persona: String::new(),
+            primary_skill_id: "skill-a".to_string(),
+            skill_ids: vec!["skill-a".to_string()],
+            enabled: true,
+            is_default: true,
+        }])
+    }
+
+    async fn search_sessions(
+        &self,
+        employee_id: &str,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<Value>, String> {
+        Ok(vec![json!({
+            "employee_id": employee_id,
+            "query": query,
+            "limit": limit,
+        })])
+    }
+
+    async fn run_turn(
+        &self,
+        request: ExternalTurnRequest,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<ExternalTurnOutcome, String> {
+        self.turns.lock().unwrap().push(request.clone());
+        if request.prompt == "wait" {
+            for _ in 0..200 {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    return Err("cancelled".to_string());
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        Ok(ExternalTurnOutcome {
+            session_id: "session-1".to_string(),
+            output: format!("done: {}", request.prompt),
+        })
+    }
+}
+
+async fn start_server() -> (McpHttpServer, Arc<FakeBackend>) {
+    let backend = Arc::new(FakeBackend::default());
+    let dispatcher = Arc::new(McpServerDispatcher::new(backend.clone()));
+    let server = McpHttpServer::bind(0, TOKEN.to_string(), dispatcher)
+        .await
+        .expect("bind mcp server");
+    (server, backend)
+}
+
+async fn post(url: &str, token: &str, body: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .expect("post to mcp server")
+}
+
+#[tokio::test]
+async fn mcp_server_lists_tools_and_resources_over_http() {
+    let (server, _) = start_server().await;
+    let url = server.url();
+
+    let init: Value = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2024-11-05"}}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(init["result"]["protocolVersion"], json!("2024-11-05"));
+    assert!(init["result"]["capabilities"]["tools"].is_object());
+
+    let initialized = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+    )
+    .await;
+    assert_eq!(initialized.status(), 202);
+
+    let tools: Value = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let names = tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"workclaw_run_skill".to_string()));
+    assert!(names.contains(&"workclaw_search_sessions".to_string()));
+
+    let resource: Value = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read", "params": {"uri": "workclaw://skills/skill-a"}}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let text = resource["result"]["contents"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Skill A"));
+
+    let search: Value = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+            "name": "workclaw_search_sessions",
+            "arguments": {"employee_id": "alice", "query": "report", "limit": 500}
+        }}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(search["result"]["isError"], json!(false));
+    assert!(search["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("\"limit\": 50"));
+}
+
+#[tokio::test]
+async fn mcp_server_rejects_missing_token_and_foreign_origin() {
+    let (server, backend) = start_server().await;
+    let url = server.url();
+    let call = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {
+        "name": "workclaw_run_skill",
+        "arguments": {"skill_id": "skill-a", "prompt": "hi"}
+    }});
+
+    assert_eq!(post(&url, "wrong", call.clone()).await.status(), 401);
+    let foreign = reqwest::Client::new()
+        .post(&url)
+        .bearer_auth(TOKEN)
+        .header("Origin", "https://evil.example")
+        .json(&call)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(foreign.status(), 403);
+    assert!(backend.turns.lock().unwrap().is_empty());
+
+    let response: Value = post(&url, TOKEN, call).await.json().await.unwrap();
+    assert_eq!(response["result"]["isError"], json!(false));
+    assert!(response["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("done: hi"));
+    assert_eq!(
+        backend.turns.lock().unwrap()[0].target,
+        ExternalTurnTarget::Skill {
+            skill_id: "skill-a".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn mcp_server_cancels_running_turn_on_cancelled_notification() {
+    let (server, _) = start_server().await;
+    let url = server.url();
+
+    let running = tokio::spawn({
+        let url = url.clone();
+        async move {
+            post(
+                &url,
+                TOKEN,
+                json!({"jsonrpc": "2.0", "id": "run-1", "method": "tools/call", "params": {
+                    "name": "workclaw_ask_employee",
+                    "arguments": {"employee_id": "alice", "prompt": "wait"}
+                }}),
+            )
+            .await
+            .json::<Value>()
+            .await
+            .unwrap()
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let cancelled = post(
+        &url,
+        TOKEN,
+        json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": "run-1"}}),
+    )
+    .await;
+    assert_eq!(cancelled.status(), 202);
+
+    let response = running.await.unwrap();
+    assert_eq!(response["result"]["isError"], json!(true));
+    assert_eq!(response["result"]["content"][0]["text"], json!("cancelled"));
+}
+
+#[test]
+fn stdio_bridge_forwards_messages_in_the_callers_framing() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (server, _) = runtime.block_on(start_server());
+
+    let ping = r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#;
+    let input = format!(
+        "{}\n{}\nContent-Length: {}\r\n\r\n{}",
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        ping.len(),
+        ping
+    );
+    let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+    runtime_lib::mcp_server::run_stdio_bridge(
+        &server.url(),
+        TOKEN,
+        std::io::Cursor::new(input.into_bytes()),
+        Arc::clone(&output),
+    )
+    .expect("run stdio bridge");
+
+    let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("workclaw_list_skills"));
+    assert!(output.contains("Content-Length: "));
+    assert!(output.contains(r#""result":{}"#));
+    assert!(!output.contains("notifications/initialized"));
+}
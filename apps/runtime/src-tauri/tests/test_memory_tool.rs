@@ -3,45 +3,55 @@ use runtime_lib::agent::runtime::runtime_io::{
     write_profile_session_manifest, ProfileSessionManifestInput,
 };
 use runtime_lib::agent::tools::MemoryTool;
-use runtime_lib::agent::types::{Tool, ToolContext};
-use serde_json::json;
+use runtime_lib::agent::types::{Tool, ToolContext, ToolExecution};
+use serde_json::{json, Value};
 use std::fs;
+use std::sync::Arc;
 
 /// 创建临时目录并返回 MemoryTool 实例
-fn create_test_memory() -> (MemoryTool, tempfile::TempDir) {
+fn create_test_memory() -> (Arc<MemoryTool>, tempfile::TempDir) {
     let dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(dir.path().to_path_buf());
+    let tool = Arc::new(MemoryTool::new(dir.path().to_path_buf()));
     (tool, dir)
 }
 
+/// MemoryTool 只提供异步入口，测试里用独立 runtime 同步驱动一次调用
+fn exec(tool: &Arc<MemoryTool>, input: Value, ctx: &ToolContext) -> anyhow::Result<String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime")
+        .block_on(Arc::clone(tool).execute_async(input, ctx.clone(), ToolExecution::default()))
+}
+
 #[test]
 fn test_memory_write_and_read() {
     let (tool, _dir) = create_test_memory();
     let ctx = ToolContext::default();
 
     // 写入内容
-    let write_result = tool
-        .execute(
-            json!({
-                "action": "write",
-                "key": "test",
-                "content": "Hello Memory"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let write_result = exec(
+        &tool,
+        json!({
+            "action": "write",
+            "key": "test",
+            "content": "Hello Memory"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(write_result.contains("已写入"));
 
     // 读回并验证内容一致
-    let read_result = tool
-        .execute(
-            json!({
-                "action": "read",
-                "key": "test"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let read_result = exec(
+        &tool,
+        json!({
+            "action": "read",
+            "key": "test"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert_eq!(read_result, "Hello Memory");
 }
 
@@ -51,15 +61,23 @@ fn test_memory_list() {
     let ctx = ToolContext::default();
 
     // 未写入时应返回空提示
-    let result = tool.execute(json!({"action": "list"}), &ctx).unwrap();
+    let result = exec(&tool, json!({"action": "list"}), &ctx).unwrap();
     assert!(result.contains("内存为空"));
 
     // 写入两个键后列表应包含两个键名
-    tool.execute(json!({"action": "write", "key": "a", "content": "1"}), &ctx)
-        .unwrap();
-    tool.execute(json!({"action": "write", "key": "b", "content": "2"}), &ctx)
-        .unwrap();
-    let result = tool.execute(json!({"action": "list"}), &ctx).unwrap();
+    exec(
+        &tool,
+        json!({"action": "write", "key": "a", "content": "1"}),
+        &ctx,
+    )
+    .unwrap();
+    exec(
+        &tool,
+        json!({"action": "write", "key": "b", "content": "2"}),
+        &ctx,
+    )
+    .unwrap();
+    let result = exec(&tool, json!({"action": "list"}), &ctx).unwrap();
     assert!(result.contains("a"));
     assert!(result.contains("b"));
 }
@@ -70,20 +88,17 @@ fn test_memory_delete() {
     let ctx = ToolContext::default();
 
     // 写入后删除
-    tool.execute(
+    exec(
+        &tool,
         json!({"action": "write", "key": "del", "content": "x"}),
         &ctx,
     )
     .unwrap();
-    let result = tool
-        .execute(json!({"action": "delete", "key": "del"}), &ctx)
-        .unwrap();
+    let result = exec(&tool, json!({"action": "delete", "key": "del"}), &ctx).unwrap();
     assert!(result.contains("已删除"));
 
     // 删除后读取应返回不存在提示
-    let read_result = tool
-        .execute(json!({"action": "read", "key": "del"}), &ctx)
-        .unwrap();
+    let read_result = exec(&tool, json!({"action": "read", "key": "del"}), &ctx).unwrap();
     assert!(read_result.contains("不存在"));
 }
 
@@ -93,9 +108,7 @@ fn test_memory_read_nonexistent() {
     let ctx = ToolContext::default();
 
     // 读取不存在的键应返回友好提示而非 error
-    let result = tool
-        .execute(json!({"action": "read", "key": "nope"}), &ctx)
-        .unwrap();
+    let result = exec(&tool, json!({"action": "read", "key": "nope"}), &ctx).unwrap();
     assert!(result.contains("不存在"));
 }
 
@@ -105,7 +118,7 @@ fn test_memory_missing_action() {
     let ctx = ToolContext::default();
 
     // 缺少 action 参数应返回错误
-    let result = tool.execute(json!({}), &ctx);
+    let result = exec(&tool, json!({}), &ctx);
     assert!(result.is_err());
 }
 
@@ -115,19 +128,19 @@ fn test_memory_overwrite() {
     let ctx = ToolContext::default();
 
     // 同一个键多次写入，应以最新内容为准
-    tool.execute(
+    exec(
+        &tool,
         json!({"action": "write", "key": "k", "content": "first"}),
         &ctx,
     )
     .unwrap();
-    tool.execute(
+    exec(
+        &tool,
         json!({"action": "write", "key": "k", "content": "second"}),
         &ctx,
     )
     .unwrap();
-    let result = tool
-        .execute(json!({"action": "read", "key": "k"}), &ctx)
-        .unwrap();
+    let result = exec(&tool, json!({"action": "read", "key": "k"}), &ctx).unwrap();
     assert_eq!(result, "second");
 }
 
@@ -137,9 +150,7 @@ fn test_memory_delete_nonexistent() {
     let ctx = ToolContext::default();
 
     // 删除不存在的键应返回友好提示而非 error
-    let result = tool
-        .execute(json!({"action": "delete", "key": "ghost"}), &ctx)
-        .unwrap();
+    let result = exec(&tool, json!({"action": "delete", "key": "ghost"}), &ctx).unwrap();
     assert!(result.contains("不存在"));
 }
 
@@ -149,7 +160,7 @@ fn test_memory_unknown_action() {
     let ctx = ToolContext::default();
 
     // 未知操作应返回错误
-    let result = tool.execute(json!({"action": "explode"}), &ctx);
+    let result = exec(&tool, json!({"action": "explode"}), &ctx);
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("未知操作"));
 }
@@ -160,14 +171,26 @@ fn test_memory_list_sorted() {
     let ctx = ToolContext::default();
 
     // 写入乱序键，列表结果应按字母排序
-    tool.execute(json!({"action": "write", "key": "c", "content": "3"}), &ctx)
-        .unwrap();
-    tool.execute(json!({"action": "write", "key": "a", "content": "1"}), &ctx)
-        .unwrap();
-    tool.execute(json!({"action": "write", "key": "b", "content": "2"}), &ctx)
-        .unwrap();
-
-    let result = tool.execute(json!({"action": "list"}), &ctx).unwrap();
+    exec(
+        &tool,
+        json!({"action": "write", "key": "c", "content": "3"}),
+        &ctx,
+    )
+    .unwrap();
+    exec(
+        &tool,
+        json!({"action": "write", "key": "a", "content": "1"}),
+        &ctx,
+    )
+    .unwrap();
+    exec(
+        &tool,
+        json!({"action": "write", "key": "b", "content": "2"}),
+        &ctx,
+    )
+    .unwrap();
+
+    let result = exec(&tool, json!({"action": "list"}), &ctx).unwrap();
     // 验证 a 在 b 前面，b 在 c 前面
     let pos_a = result.find('a').unwrap();
     let pos_b = result.find('b').unwrap();
@@ -181,26 +204,26 @@ fn test_profile_memory_add_view_history() {
     let (tool, dir) = create_test_memory();
     let ctx = ToolContext::default();
 
-    let result = tool
-        .execute(
-            json!({
-                "action": "add",
-                "content": "用户偏好先给结论，再给细节。",
-                "source": "session:test"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let result = exec(
+        &tool,
+        json!({
+            "action": "add",
+            "content": "用户偏好先给结论，再给细节。",
+            "source": "session:test"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(result.contains("已追加"));
 
     let memory_path = dir.path().join("MEMORY.md");
     let memory = fs::read_to_string(&memory_path).unwrap();
     assert!(memory.contains("用户偏好先给结论"));
 
-    let viewed = tool.execute(json!({"action": "view"}), &ctx).unwrap();
+    let viewed = exec(&tool, json!({"action": "view"}), &ctx).unwrap();
     assert_eq!(viewed, memory);
 
-    let history = tool.execute(json!({"action": "history"}), &ctx).unwrap();
+    let history = exec(&tool, json!({"action": "history"}), &ctx).unwrap();
     assert!(history.contains("\"action\":\"add\""));
     assert!(history.contains("\"source\":\"session:test\""));
 }
@@ -208,10 +231,11 @@ fn test_profile_memory_add_view_history() {
 #[test]
 fn test_profile_memory_versions_and_rollback() {
     let dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(dir.path().to_path_buf());
+    let tool = Arc::new(MemoryTool::new(dir.path().to_path_buf()));
     let ctx = ToolContext::default();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "add",
             "content": "first memory",
@@ -221,7 +245,8 @@ fn test_profile_memory_versions_and_rollback() {
         &ctx,
     )
     .unwrap();
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "replace",
             "content": "second memory",
@@ -232,7 +257,7 @@ fn test_profile_memory_versions_and_rollback() {
     )
     .unwrap();
 
-    let versions_raw = tool.execute(json!({"action": "versions"}), &ctx).unwrap();
+    let versions_raw = exec(&tool, json!({"action": "versions"}), &ctx).unwrap();
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).unwrap();
     let items = versions.as_array().unwrap();
     assert_eq!(items.len(), 2);
@@ -241,34 +266,35 @@ fn test_profile_memory_versions_and_rollback() {
     assert_eq!(items[1]["action"], "replace");
     let first_version = items[0]["version_id"].as_str().unwrap();
 
-    let first_content = tool
-        .execute(
-            json!({
-                "action": "view_version",
-                "version_id": first_version
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let first_content = exec(
+        &tool,
+        json!({
+            "action": "view_version",
+            "version_id": first_version
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(first_content.contains("first memory"));
     assert!(!first_content.contains("second memory"));
 
-    let refused = tool
-        .execute(
-            json!({
-                "action": "rollback",
-                "version_id": first_version
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let refused = exec(
+        &tool,
+        json!({
+            "action": "rollback",
+            "version_id": first_version
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(refused.contains("confirm=true"));
     assert_eq!(
         fs::read_to_string(dir.path().join("MEMORY.md")).unwrap(),
         "second memory\n"
     );
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "rollback",
             "version_id": first_version,
@@ -283,7 +309,7 @@ fn test_profile_memory_versions_and_rollback() {
         "first memory\n"
     );
 
-    let history = tool.execute(json!({"action": "history"}), &ctx).unwrap();
+    let history = exec(&tool, json!({"action": "history"}), &ctx).unwrap();
     assert!(history.contains("\"action\":\"rollback\""));
     assert!(history.contains(first_version));
 }
@@ -303,14 +329,17 @@ fn test_profile_memory_mutations_write_growth_events() {
         })
         .expect("create sqlite pool");
     let dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(dir.path().to_path_buf())
-        .with_profile_session_search(pool.clone(), "profile-memory-growth".to_string());
+    let tool = Arc::new(
+        MemoryTool::new(dir.path().to_path_buf())
+            .with_profile_session_search(pool.clone(), "profile-memory-growth".to_string()),
+    );
     let ctx = ToolContext {
         session_id: Some("session-memory-growth".to_string()),
         ..ToolContext::default()
     };
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "add",
             "content": "first memory",
@@ -319,11 +348,12 @@ fn test_profile_memory_mutations_write_growth_events() {
         &ctx,
     )
     .unwrap();
-    let versions_raw = tool.execute(json!({"action": "versions"}), &ctx).unwrap();
+    let versions_raw = exec(&tool, json!({"action": "versions"}), &ctx).unwrap();
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).unwrap();
     let first_version = versions[0]["version_id"].as_str().unwrap().to_string();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "replace",
             "content": "second memory",
@@ -332,7 +362,8 @@ fn test_profile_memory_mutations_write_growth_events() {
         &ctx,
     )
     .unwrap();
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "remove",
             "confirm": true,
@@ -341,7 +372,8 @@ fn test_profile_memory_mutations_write_growth_events() {
         &ctx,
     )
     .unwrap();
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "rollback",
             "version_id": first_version,
@@ -420,14 +452,17 @@ fn test_user_correction_memory_source_writes_dedicated_growth_event() {
         })
         .expect("create sqlite pool");
     let dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(dir.path().to_path_buf())
-        .with_profile_session_search(pool.clone(), "profile-correction".to_string());
+    let tool = Arc::new(
+        MemoryTool::new(dir.path().to_path_buf())
+            .with_profile_session_search(pool.clone(), "profile-correction".to_string()),
+    );
     let ctx = ToolContext {
         session_id: Some("session-correction".to_string()),
         ..ToolContext::default()
     };
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "add",
             "content": "用户纠正：日报摘要必须区分已完成和阻塞项。",
@@ -460,11 +495,13 @@ fn test_user_correction_memory_source_writes_dedicated_growth_event() {
 fn test_project_memory_versions_are_isolated_from_profile_versions() {
     let dir = tempfile::tempdir().unwrap();
     let project_memory_file = dir.path().join("PROJECTS").join("workspace-a.md");
-    let tool =
-        MemoryTool::new(dir.path().to_path_buf()).with_project_memory_path(project_memory_file);
+    let tool = Arc::new(
+        MemoryTool::new(dir.path().to_path_buf()).with_project_memory_path(project_memory_file),
+    );
     let ctx = ToolContext::default();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "replace",
             "scope": "project",
@@ -476,9 +513,12 @@ fn test_project_memory_versions_are_isolated_from_profile_versions() {
     )
     .unwrap();
 
-    let project_versions_raw = tool
-        .execute(json!({"action": "versions", "scope": "project"}), &ctx)
-        .unwrap();
+    let project_versions_raw = exec(
+        &tool,
+        json!({"action": "versions", "scope": "project"}),
+        &ctx,
+    )
+    .unwrap();
     let project_versions: serde_json::Value = serde_json::from_str(&project_versions_raw).unwrap();
     assert_eq!(project_versions.as_array().unwrap().len(), 1);
     assert_eq!(project_versions[0]["scope"], "project");
@@ -490,7 +530,7 @@ fn test_project_memory_versions_are_isolated_from_profile_versions() {
         .join("workspace-a")
         .exists());
 
-    let profile_versions_raw = tool.execute(json!({"action": "versions"}), &ctx).unwrap();
+    let profile_versions_raw = exec(&tool, json!({"action": "versions"}), &ctx).unwrap();
     let profile_versions: serde_json::Value = serde_json::from_str(&profile_versions_raw).unwrap();
     assert_eq!(profile_versions.as_array().unwrap().len(), 0);
 }
@@ -498,10 +538,11 @@ fn test_project_memory_versions_are_isolated_from_profile_versions() {
 #[test]
 fn test_profile_memory_remove_creates_tombstone_version() {
     let dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(dir.path().to_path_buf());
+    let tool = Arc::new(MemoryTool::new(dir.path().to_path_buf()));
     let ctx = ToolContext::default();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "replace",
             "content": "temporary memory"
@@ -509,7 +550,8 @@ fn test_profile_memory_remove_creates_tombstone_version() {
         &ctx,
     )
     .unwrap();
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "remove",
             "confirm": true,
@@ -519,7 +561,7 @@ fn test_profile_memory_remove_creates_tombstone_version() {
     )
     .unwrap();
 
-    let versions_raw = tool.execute(json!({"action": "versions"}), &ctx).unwrap();
+    let versions_raw = exec(&tool, json!({"action": "versions"}), &ctx).unwrap();
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).unwrap();
     let items = versions.as_array().unwrap();
     assert_eq!(items.len(), 2);
@@ -528,15 +570,15 @@ fn test_profile_memory_remove_creates_tombstone_version() {
     assert_eq!(items[1]["change_summary"], "delete stale memory");
 
     let removed_version = items[1]["version_id"].as_str().unwrap();
-    let removed_snapshot = tool
-        .execute(
-            json!({
-                "action": "view_version",
-                "version_id": removed_version
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let removed_snapshot = exec(
+        &tool,
+        json!({
+            "action": "view_version",
+            "version_id": removed_version
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert_eq!(removed_snapshot, "");
 }
 
@@ -545,7 +587,8 @@ fn test_profile_memory_replace_and_confirmed_remove() {
     let (tool, dir) = create_test_memory();
     let ctx = ToolContext::default();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "add",
             "content": "旧记忆"
@@ -554,32 +597,30 @@ fn test_profile_memory_replace_and_confirmed_remove() {
     )
     .unwrap();
 
-    let result = tool
-        .execute(
-            json!({
-                "action": "replace",
-                "content": "新记忆",
-                "source": "user-correction"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let result = exec(
+        &tool,
+        json!({
+            "action": "replace",
+            "content": "新记忆",
+            "source": "user-correction"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(result.contains("已替换"));
 
     let memory = fs::read_to_string(dir.path().join("MEMORY.md")).unwrap();
     assert_eq!(memory, "新记忆\n");
 
-    let unconfirmed = tool.execute(json!({"action": "remove"}), &ctx).unwrap();
+    let unconfirmed = exec(&tool, json!({"action": "remove"}), &ctx).unwrap();
     assert!(unconfirmed.contains("confirm=true"));
     assert!(dir.path().join("MEMORY.md").exists());
 
-    let removed = tool
-        .execute(json!({"action": "remove", "confirm": true}), &ctx)
-        .unwrap();
+    let removed = exec(&tool, json!({"action": "remove", "confirm": true}), &ctx).unwrap();
     assert!(removed.contains("已移除"));
     assert!(!dir.path().join("MEMORY.md").exists());
 
-    let history = tool.execute(json!({"action": "history"}), &ctx).unwrap();
+    let history = exec(&tool, json!({"action": "history"}), &ctx).unwrap();
     assert!(history.contains("\"action\":\"replace\""));
     assert!(history.contains("\"action\":\"remove\""));
 }
@@ -588,25 +629,27 @@ fn test_profile_memory_replace_and_confirmed_remove() {
 fn test_im_memory_uses_separate_memory_dir() {
     let profile_dir = tempfile::tempdir().unwrap();
     let im_dir = tempfile::tempdir().unwrap();
-    let tool = MemoryTool::new(profile_dir.path().to_path_buf())
-        .with_im_memory_dir(im_dir.path().to_path_buf());
+    let tool = Arc::new(
+        MemoryTool::new(profile_dir.path().to_path_buf())
+            .with_im_memory_dir(im_dir.path().to_path_buf()),
+    );
     let ctx = ToolContext::default();
 
-    let result = tool
-        .execute(
-            json!({
-                "action": "capture_im",
-                "thread_id": "thread-1",
-                "role_id": "role-1",
-                "category": "fact",
-                "content": "IM 长期事实",
-                "confirmed": true,
-                "confidence": 0.9,
-                "source_msg_id": "msg-1"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let result = exec(
+        &tool,
+        json!({
+            "action": "capture_im",
+            "thread_id": "thread-1",
+            "role_id": "role-1",
+            "category": "fact",
+            "content": "IM 长期事实",
+            "confirmed": true,
+            "confidence": 0.9,
+            "source_msg_id": "msg-1"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(result.contains("IM 记忆写入完成"));
 
     assert!(!profile_dir.path().join("roles").exists());
@@ -623,29 +666,29 @@ fn test_project_memory_scope_uses_project_memory_file() {
     let profile_dir = tempfile::tempdir().unwrap();
     let project_dir = tempfile::tempdir().unwrap();
     let project_memory_file = project_dir.path().join("workspace.md");
-    let tool = MemoryTool::new(profile_dir.path().to_path_buf())
-        .with_project_memory_path(project_memory_file.clone());
+    let tool = Arc::new(
+        MemoryTool::new(profile_dir.path().to_path_buf())
+            .with_project_memory_path(project_memory_file.clone()),
+    );
     let ctx = ToolContext::default();
 
-    let result = tool
-        .execute(
-            json!({
-                "action": "add",
-                "scope": "project",
-                "content": "项目约定：先跑快速验证。"
-            }),
-            &ctx,
-        )
-        .unwrap();
+    let result = exec(
+        &tool,
+        json!({
+            "action": "add",
+            "scope": "project",
+            "content": "项目约定：先跑快速验证。"
+        }),
+        &ctx,
+    )
+    .unwrap();
     assert!(result.contains("Project Memory"));
 
     let project_memory = fs::read_to_string(&project_memory_file).unwrap();
     assert!(project_memory.contains("项目约定"));
     assert!(!profile_dir.path().join("MEMORY.md").exists());
 
-    let viewed = tool
-        .execute(json!({"action": "view", "scope": "project"}), &ctx)
-        .unwrap();
+    let viewed = exec(&tool, json!({"action": "view", "scope": "project"}), &ctx).unwrap();
     assert_eq!(viewed, project_memory);
 }
 
@@ -732,18 +775,20 @@ fn test_memory_search_reads_profile_session_index() {
         pool
     });
 
-    let tool = MemoryTool::new(tmp.path().join("profile-memory"))
-        .with_profile_session_search(pool, "profile-1".to_string());
-    let result = tool
-        .execute(
-            json!({
-                "action": "search",
-                "query": "工具调用摘要",
-                "limit": 5
-            }),
-            &ToolContext::default(),
-        )
-        .unwrap();
+    let tool = Arc::new(
+        MemoryTool::new(tmp.path().join("profile-memory"))
+            .with_profile_session_search(pool, "profile-1".to_string()),
+    );
+    let result = exec(
+        &tool,
+        json!({
+            "action": "search",
+            "query": "工具调用摘要",
+            "limit": 5
+        }),
+        &ToolContext::default(),
+    )
+    .unwrap();
 
     assert!(result.contains("session-memory-search"));
     assert!(result.contains("tool_summary_count"));
@@ -797,21 +842,23 @@ fn test_memory_search_applies_profile_session_filters() {
         pool
     });
 
-    let tool = MemoryTool::new(tmp.path().join("profile-memory"))
-        .with_profile_session_search(pool, "profile-1".to_string());
-    let result = tool
-        .execute(
-            json!({
-                "action": "search",
-                "query": "skill-target",
-                "work_dir": "E:/workspace/acme",
-                "skill_id": "skill-target",
-                "source": "runtime_tool_setup",
-                "limit": 5
-            }),
-            &ToolContext::default(),
-        )
-        .unwrap();
+    let tool = Arc::new(
+        MemoryTool::new(tmp.path().join("profile-memory"))
+            .with_profile_session_search(pool, "profile-1".to_string()),
+    );
+    let result = exec(
+        &tool,
+        json!({
+            "action": "search",
+            "query": "skill-target",
+            "work_dir": "E:/workspace/acme",
+            "skill_id": "skill-target",
+            "source": "runtime_tool_setup",
+            "limit": 5
+        }),
+        &ToolContext::default(),
+    )
+    .unwrap();
 
     assert!(result.contains("session-memory-search-filter-target"));
     assert!(!result.contains("session-memory-search-filter-other"));
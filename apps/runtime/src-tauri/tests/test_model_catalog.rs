@@ -0,0 +1,97 @@
+mod helpers;
+
+use runtime_lib::model_catalog::{
+    current_model_catalog, delete_model_catalog_override_with_pool,
+    list_model_catalog_overrides_with_pool, reload_model_catalog_with_pool,
+    save_model_catalog_override_with_pool,
+};
+use runtime_routing_core::ModelCatalogEntry;
+
+#[tokio::test]
+async fn overrides_persist_and_refresh_the_shared_catalog() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    reload_model_catalog_with_pool(&pool)
+        .await
+        .expect("load bundled catalog");
+    assert_eq!(
+        current_model_catalog().supports("my-finetune", "vision"),
+        None
+    );
+
+    let invalid = save_model_catalog_override_with_pool(
+        &pool,
+        ModelCatalogEntry {
+            model: "my-finetune".to_string(),
+            context_window: Some(8_192),
+            max_output_tokens: Some(8_192),
+            ..ModelCatalogEntry::default()
+        },
+    )
+    .await;
+    assert!(invalid.is_err());
+
+    let catalog = save_model_catalog_override_with_pool(
+        &pool,
+        ModelCatalogEntry {
+            model: " My-Finetune ".to_string(),
+            providers: vec!["openai".to_string()],
+            context_window: Some(32_768),
+            max_output_tokens: Some(4_096),
+            capabilities: vec!["chat".to_string(), "tool_calling".to_string()],
+            ..ModelCatalogEntry::default()
+        },
+    )
+    .await
+    .expect("save override");
+    assert_eq!(catalog.supports("my-finetune", "vision"), Some(false));
+    assert_eq!(catalog.supports("my-finetune", "tool_calling"), Some(true));
+    assert!(catalog
+        .recommended_models_for_provider("openai")
+        .contains(&"My-Finetune".to_string()));
+
+    let overrides = list_model_catalog_overrides_with_pool(&pool)
+        .await
+        .expect("list overrides");
+    assert_eq!(overrides.len(), 1);
+    assert_eq!(overrides[0].entry.context_window, Some(32_768));
+
+    // 覆盖内置条目后，只影响该模型
+    save_model_catalog_override_with_pool(
+        &pool,
+        ModelCatalogEntry {
+            model: "deepseek-chat".to_string(),
+            providers: vec!["deepseek".to_string()],
+            context_window: Some(64_000),
+            capabilities: vec!["chat".to_string()],
+            ..ModelCatalogEntry::default()
+        },
+    )
+    .await
+    .expect("override bundled entry");
+    let catalog = current_model_catalog();
+    assert_eq!(
+        catalog
+            .lookup("deepseek-chat")
+            .and_then(|entry| entry.context_window),
+        Some(64_000)
+    );
+    assert_eq!(
+        catalog.supports("deepseek-reasoner", "reasoning"),
+        Some(true)
+    );
+
+    delete_model_catalog_override_with_pool(&pool, "MY-FINETUNE")
+        .await
+        .expect("delete override");
+    delete_model_catalog_override_with_pool(&pool, "deepseek-chat")
+        .await
+        .expect("delete bundled override");
+    let catalog = current_model_catalog();
+    assert!(catalog.lookup("my-finetune").is_none());
+    assert_eq!(
+        catalog
+            .lookup("deepseek-chat")
+            .and_then(|entry| entry.context_window),
+        Some(128_000)
+    );
+}
@@ -0,0 +1,98 @@
+mod helpers;
+
+use runtime_lib::agent::types::{ReasoningEffort, ReasoningSettings};
+use runtime_lib::model_reasoning::{
+    get_model_reasoning_settings_with_pool, resolve_reasoning_settings_with_pool,
+    save_model_reasoning_settings_with_pool, save_route_reasoning_settings_with_pool,
+    CHAT_REASONING_CAPABILITY,
+};
+
+#[tokio::test]
+async fn route_reasoning_settings_override_model_settings_field_by_field() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    sqlx::query(
+        "INSERT INTO model_configs (id, name, api_format, base_url, model_name, is_default, api_key)
+         VALUES ('m-claude', 'claude', 'anthropic', 'https://api.anthropic.com/v1', 'claude-sonnet-4-5', 1, 'k')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert model config");
+
+    save_model_reasoning_settings_with_pool(
+        &pool,
+        "m-claude",
+        ReasoningSettings {
+            enabled: Some(true),
+            budget_tokens: Some(8_000),
+            ..ReasoningSettings::default()
+        },
+    )
+    .await
+    .expect("save model reasoning");
+
+    let resolved = resolve_reasoning_settings_with_pool(
+        &pool,
+        CHAT_REASONING_CAPABILITY,
+        "https://api.anthropic.com/v1",
+        "claude-sonnet-4-5",
+    )
+    .await
+    .expect("resolve without route");
+    assert_eq!(resolved.budget_tokens, Some(8_000));
+    assert_eq!(resolved.enabled, Some(true));
+
+    let missing_route = save_route_reasoning_settings_with_pool(
+        &pool,
+        CHAT_REASONING_CAPABILITY,
+        ReasoningSettings::default(),
+    )
+    .await;
+    assert!(missing_route.is_err());
+
+    sqlx::query(
+        "INSERT INTO routing_policies (capability, primary_provider_id, primary_model)
+         VALUES ('chat', 'provider-anthropic', 'claude-sonnet-4-5')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert routing policy");
+    save_route_reasoning_settings_with_pool(
+        &pool,
+        CHAT_REASONING_CAPABILITY,
+        ReasoningSettings {
+            effort: Some(ReasoningEffort::Low),
+            budget_tokens: Some(2_048),
+            ..ReasoningSettings::default()
+        },
+    )
+    .await
+    .expect("save route reasoning");
+
+    let resolved = resolve_reasoning_settings_with_pool(
+        &pool,
+        CHAT_REASONING_CAPABILITY,
+        "https://api.anthropic.com/v1",
+        "claude-sonnet-4-5",
+    )
+    .await
+    .expect("resolve with route");
+    assert_eq!(resolved.enabled, Some(true));
+    assert_eq!(resolved.effort, Some(ReasoningEffort::Low));
+    assert_eq!(resolved.budget_tokens, Some(2_048));
+}
+
+#[tokio::test]
+async fn model_reasoning_settings_require_existing_model() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    assert!(get_model_reasoning_settings_with_pool(&pool, "missing")
+        .await
+        .is_err());
+    assert!(save_model_reasoning_settings_with_pool(
+        &pool,
+        "missing",
+        ReasoningSettings::default()
+    )
+    .await
+    .is_err());
+}
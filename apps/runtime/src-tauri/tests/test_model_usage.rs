@@ -0,0 +1,246 @@
+mod helpers;
+
+use runtime_lib::adapters::mock::ScriptedModelAdapter;
+use runtime_lib::adapters::{ModelChatRequest, ModelUsageScope};
+use runtime_lib::agent::TokenUsage;
+use runtime_lib::model_transport::resolve_model_transport;
+use runtime_lib::model_usage::{
+    record_model_usage_with_pool, save_model_price_with_pool, summarize_model_usage_with_pool,
+    ModelPriceInput, ModelUsageGroupBy, ModelUsageQuery, ModelUsageRecordInput,
+};
+use runtime_lib::providers::{model_adapters, register_model_adapter};
+use std::sync::Arc;
+
+async fn seed_session(pool: &sqlx::SqlitePool, session_id: &str, employee_id: &str) {
+    sqlx::query(
+        "INSERT INTO sessions (id, skill_id, title, created_at, model_id, employee_id)
+         VALUES (?, 'builtin-general', 'usage', '2026-01-01T00:00:00Z', 'm1', ?)",
+    )
+    .bind(session_id)
+    .bind(employee_id)
+    .execute(pool)
+    .await
+    .expect("insert session");
+}
+
+async fn record(
+    pool: &sqlx::SqlitePool,
+    session_id: &str,
+    base_url: &str,
+    model_name: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+) {
+    record_model_usage_with_pool(
+        pool,
+        ModelUsageRecordInput {
+            session_id,
+            run_id: Some("run-1"),
+            base_url,
+            api_format: "openai",
+            model_name,
+            usage: TokenUsage {
+                input_tokens,
+                output_tokens,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+            },
+        },
+    )
+    .await
+    .expect("record usage");
+}
+
+#[tokio::test]
+async fn usage_aggregates_by_employee_group_run_and_provider_with_prices() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    seed_session(&pool, "session-main", "pm").await;
+    seed_session(&pool, "session-dev", "dev").await;
+    seed_session(&pool, "session-solo", "dev").await;
+    sqlx::query(
+        "INSERT INTO provider_configs (id, provider_key, display_name, protocol_type, base_url, created_at, updated_at)
+         VALUES ('p1', 'deepseek', 'DeepSeek', 'openai', 'https://api.deepseek.com/v1', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert provider");
+    sqlx::query(
+        "INSERT INTO group_runs (id, group_id, session_id, created_at, updated_at)
+         VALUES ('group-run-1', 'group-1', 'session-main', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert group run");
+    sqlx::query(
+        "INSERT INTO group_run_steps (id, run_id, assignee_employee_id, session_id)
+         VALUES ('step-1', 'group-run-1', 'dev', 'session-dev')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert group run step");
+
+    record(
+        &pool,
+        "session-main",
+        "https://api.deepseek.com/v1/",
+        "deepseek-chat",
+        1_000,
+        200,
+    )
+    .await;
+    record(
+        &pool,
+        "session-dev",
+        "https://api.deepseek.com/v1",
+        "deepseek-chat",
+        3_000,
+        800,
+    )
+    .await;
+    record(
+        &pool,
+        "session-solo",
+        "https://example.invalid/v1",
+        "mystery-model",
+        500,
+        50,
+    )
+    .await;
+
+    save_model_price_with_pool(
+        &pool,
+        ModelPriceInput {
+            provider_key: "deepseek".to_string(),
+            model_pattern: "deepseek-*".to_string(),
+            input_per_million: 2.0,
+            output_per_million: 8.0,
+            cache_read_per_million: 0.5,
+            cache_write_per_million: 0.0,
+            currency: Some("cny".to_string()),
+        },
+    )
+    .await
+    .expect("save price");
+
+    let by_employee = summarize_model_usage_with_pool(
+        &pool,
+        &ModelUsageQuery {
+            group_by: ModelUsageGroupBy::Employee,
+            ..ModelUsageQuery::default()
+        },
+    )
+    .await
+    .expect("summarize by employee");
+    let dev = by_employee
+        .iter()
+        .find(|summary| summary.key == "dev")
+        .expect("dev summary");
+    assert_eq!(dev.call_count, 2);
+    assert_eq!(dev.usage.input_tokens, 3_500);
+    assert_eq!(dev.unpriced_calls, 1);
+    let dev_cost = dev.cost_by_currency.get("CNY").copied().unwrap_or_default();
+    assert!((dev_cost - (3_000.0 * 2.0 + 800.0 * 8.0) / 1_000_000.0).abs() < 1e-12);
+
+    let by_group_run = summarize_model_usage_with_pool(
+        &pool,
+        &ModelUsageQuery {
+            group_by: ModelUsageGroupBy::GroupRun,
+            ..ModelUsageQuery::default()
+        },
+    )
+    .await
+    .expect("summarize by group run");
+    assert_eq!(by_group_run.len(), 1);
+    assert_eq!(by_group_run[0].key, "group-run-1");
+    assert_eq!(by_group_run[0].total_tokens, 5_000);
+
+    let by_provider = summarize_model_usage_with_pool(
+        &pool,
+        &ModelUsageQuery {
+            group_by: ModelUsageGroupBy::Provider,
+            ..ModelUsageQuery::default()
+        },
+    )
+    .await
+    .expect("summarize by provider");
+    let keys = by_provider
+        .iter()
+        .map(|summary| summary.key.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec!["deepseek", "example.invalid"]);
+
+    let single_session = summarize_model_usage_with_pool(
+        &pool,
+        &ModelUsageQuery {
+            session_id: Some("session-main".to_string()),
+            ..ModelUsageQuery::default()
+        },
+    )
+    .await
+    .expect("summarize single session");
+    assert_eq!(single_session.len(), 1);
+    assert_eq!(single_session[0].usage.output_tokens, 200);
+}
+
+#[tokio::test]
+async fn registry_records_usage_for_scoped_model_calls() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    seed_session(&pool, "session-scoped", "dev").await;
+    let adapter = Arc::new(ScriptedModelAdapter::new("http://scripted-usage"));
+    adapter
+        .push_text(
+            "摘要",
+            Some(TokenUsage {
+                input_tokens: 120,
+                output_tokens: 30,
+                ..TokenUsage::default()
+            }),
+        )
+        .push_text("未记账", Some(TokenUsage::default()));
+    register_model_adapter(adapter.clone());
+
+    let transport = resolve_model_transport("anthropic", "http://scripted-usage", None);
+    let request = |usage_scope| ModelChatRequest {
+        transport: &transport,
+        base_url: "http://scripted-usage",
+        api_key: "key",
+        model: "scripted-model",
+        system_prompt: "system",
+        messages: vec![serde_json::json!({ "role": "user", "content": "压缩上下文" })],
+        tools: vec![],
+        reasoning: Default::default(),
+        structured_output: None,
+        usage_scope,
+    };
+    model_adapters()
+        .chat_stream(
+            request(Some(ModelUsageScope {
+                pool: &pool,
+                session_id: "session-scoped",
+                run_id: Some("run-scoped"),
+            })),
+            &mut |_| {},
+        )
+        .await
+        .expect("scoped call");
+    model_adapters()
+        .chat_stream(request(None), &mut |_| {})
+        .await
+        .expect("unscoped call");
+
+    let rows: Vec<(String, String, i64, i64)> = sqlx::query_as(
+        "SELECT session_id, run_id, input_tokens, output_tokens FROM model_usage_records",
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("load usage rows");
+    assert_eq!(
+        rows,
+        vec![(
+            "session-scoped".to_string(),
+            "run-scoped".to_string(),
+            120,
+            30
+        )]
+    );
+}
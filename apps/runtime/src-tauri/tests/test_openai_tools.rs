@@ -1,5 +1,5 @@
 use runtime_lib::agent::permissions::PermissionMode;
-use runtime_lib::agent::{AgentExecutor, ToolRegistry};
+use runtime_lib::agent::{AgentExecutor, AgentTurnRequest, ToolRegistry};
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
@@ -16,7 +16,7 @@ fn setup_work_dir(name: &str) -> PathBuf {
 
 /// 验证 execute_turn 对 OpenAI 格式的行为
 ///
-/// OpenAI 分支已接入 chat_stream_with_tools，
+/// OpenAI 分支已通过 ModelAdapter 注册表分发，
 /// 使用无效 URL 时应返回网络错误（而非 "not yet implemented"）。
 #[tokio::test]
 async fn test_openai_tool_calling_executor_branch() {
@@ -27,23 +27,17 @@ async fn test_openai_tool_calling_executor_branch() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://invalid-openai-mock-url",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://invalid-openai-mock-url",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -69,25 +63,19 @@ async fn test_openai_tool_calling_real() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "https://api.openai.com/v1",
-            &std::env::var("OPENAI_API_KEY").unwrap_or_default(),
-            "gpt-4",
-            "You are a helpful assistant with file tools.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "https://api.openai.com/v1",
+                api_key: &std::env::var("OPENAI_API_KEY").unwrap_or_default(),
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant with file tools.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |token| {
                 eprint!("{:?}", token);
             },
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -111,23 +99,18 @@ async fn test_openai_responses_mock_runs_full_tool_loop() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-responses-read-file-from-user-path",
-            "mock-key",
-            "gpt-5.4",
-            "You are a helpful assistant with file tools.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-responses-read-file-from-user-path",
+                api_key: "mock-key",
+                model: "gpt-5.4",
+                skill_system_prompt: "You are a helpful assistant with file tools.",
+                messages,
+                permission_mode: PermissionMode::AcceptEdits,
+                work_dir: Some(work_dir.to_string_lossy().to_string()),
+                ..AgentTurnRequest::default()
+            },
             |_| {},
-            None,
-            None,
-            None,
-            PermissionMode::AcceptEdits,
-            None,
-            Some(work_dir.to_string_lossy().to_string()),
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -169,23 +152,17 @@ async fn test_openai_responses_malformed_tool_call_does_not_fail_task_start() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-responses-malformed-tool-call-start-task",
-            "mock-key",
-            "gpt-5.4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-responses-malformed-tool-call-start-task",
+                api_key: "mock-key",
+                model: "gpt-5.4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::AcceptEdits,
+                ..AgentTurnRequest::default()
+            },
             |_| {},
-            None,
-            None,
-            None,
-            PermissionMode::AcceptEdits,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
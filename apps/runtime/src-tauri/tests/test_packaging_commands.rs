@@ -1,6 +1,6 @@
 use runtime_lib::commands::packaging::{
     pack_industry_bundle, pack_skill, read_industry_bundle_manifest, read_skill_dir,
-    scan_workclaw_dirs, unpack_industry_bundle, update_skill_dir_tags,
+    scan_workclaw_dirs, unpack_industry_bundle, update_skill_dir_tags, PackSkillRequest,
 };
 
 #[tokio::test]
@@ -42,16 +42,17 @@ async fn pack_skill_creates_skillpack() {
     std::fs::write(tmp.path().join("extra.txt"), "file").expect("write file");
 
     let output = tmp.path().join("out.skillpack");
-    pack_skill(
-        tmp.path().to_string_lossy().to_string(),
-        "test-skill".to_string(),
-        "desc".to_string(),
-        "1.0.0".to_string(),
-        "author".to_string(),
-        "alice".to_string(),
-        "gpt-4o".to_string(),
-        output.to_string_lossy().to_string(),
-    )
+    pack_skill(PackSkillRequest {
+        dir_path: tmp.path().to_string_lossy().to_string(),
+        name: "test-skill".to_string(),
+        description: "desc".to_string(),
+        version: "1.0.0".to_string(),
+        author: "author".to_string(),
+        username: "alice".to_string(),
+        recommended_model: "gpt-4o".to_string(),
+        output_path: output.to_string_lossy().to_string(),
+        ..Default::default()
+    })
     .await
     .expect("pack succeeds");
 
@@ -1,6 +1,6 @@
 use runtime_lib::agent::permissions::PermissionMode;
 use runtime_lib::agent::run_guard::{parse_run_stop_reason, RunStopReasonKind};
-use runtime_lib::agent::{AgentExecutor, ToolRegistry};
+use runtime_lib::agent::{AgentExecutor, AgentTurnRequest, ToolRegistry};
 use runtime_lib::providers::{route_with_fallback, RouteFailureKind, RouteTarget, RoutingPolicy};
 use serde_json::json;
 use std::fs;
@@ -34,23 +34,17 @@ async fn test_react_loop_max_iterations_error() {
 
     let result = executor
         .execute_turn(
-            "anthropic",
-            "http://mock-url",
-            "mock-key",
-            "mock-model",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "anthropic",
+                base_url: "http://mock-url",
+                api_key: "mock-key",
+                model: "mock-model",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -67,23 +61,17 @@ async fn test_react_loop_openai_format_network_error() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://invalid-openai-url",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://invalid-openai-url",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -105,23 +93,17 @@ async fn test_react_loop_stops_repeated_invalid_write_file_calls() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-repeat-invalid-write-file",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-repeat-invalid-write-file",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::Unrestricted,
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::Unrestricted,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -163,23 +145,18 @@ async fn test_react_loop_executes_absolute_nested_write_within_work_dir() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-write-file-from-user-path",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-write-file-from-user-path",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::AcceptEdits,
+                work_dir: Some(work_dir.to_string_lossy().to_string()),
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::AcceptEdits,
-            None,
-            Some(work_dir.to_string_lossy().to_string()),
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -219,23 +196,18 @@ async fn test_react_loop_progress_guard_stops_repeated_successful_tool_calls() {
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-repeat-read-file-loop",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-repeat-read-file-loop",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::AcceptEdits,
+                work_dir: Some(work_dir.to_string_lossy().to_string()),
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::AcceptEdits,
-            None,
-            Some(work_dir.to_string_lossy().to_string()),
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -260,23 +232,18 @@ async fn test_react_loop_does_not_misclassify_interleaved_failures_as_repeated_l
 
     let result = executor
         .execute_turn(
-            "openai",
-            "http://mock-list-dir-interleaved-move-failures",
-            "mock-key",
-            "gpt-4",
-            "You are a helpful assistant.",
-            messages,
+            AgentTurnRequest {
+                api_format: "openai",
+                base_url: "http://mock-list-dir-interleaved-move-failures",
+                api_key: "mock-key",
+                model: "gpt-4",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages,
+                permission_mode: PermissionMode::AcceptEdits,
+                work_dir: Some(work_dir.to_string_lossy().to_string()),
+                ..AgentTurnRequest::default()
+            },
             |_token| {},
-            None,
-            None,
-            None,
-            PermissionMode::AcceptEdits,
-            None,
-            Some(work_dir.to_string_lossy().to_string()),
-            None,
-            None,
-            None,
-            None,
         )
         .await;
 
@@ -1,5 +1,7 @@
+use runtime_lib::adapters::mock::ScriptedModelAdapter;
 use runtime_lib::agent::ToolRegistry;
-use runtime_lib::providers::{ProviderPlugin, ProviderRegistry};
+use runtime_lib::model_transport::resolve_model_transport;
+use runtime_lib::providers::{ModelAdapterRegistry, ProviderPlugin, ProviderRegistry};
 use std::sync::Arc;
 
 #[test]
@@ -91,3 +93,37 @@ fn china_first_registry_contains_p0_plugins() {
     let vision = registry.list_by_capability("vision");
     assert!(vision.iter().any(|p| p.key() == "qwen"));
 }
+
+#[test]
+fn model_adapter_registry_prefers_later_registrations_for_matching_base_url() {
+    let mut registry = ModelAdapterRegistry::with_builtin_adapters();
+    let anthropic = resolve_model_transport("anthropic", "https://api.anthropic.com", None);
+    let openai = resolve_model_transport("openai", "https://api.openai.com/v1", None);
+
+    let resolved = registry
+        .resolve(&anthropic, "https://api.anthropic.com")
+        .expect("anthropic adapter");
+    assert_eq!(resolved.key(), "anthropic_messages");
+    let resolved = registry
+        .resolve(&openai, "https://api.openai.com/v1")
+        .expect("openai adapter");
+    assert_eq!(resolved.key(), "openai_compat");
+    let resolved = registry
+        .resolve(&openai, "http://mock")
+        .expect("mock adapter");
+    assert_eq!(resolved.key(), "mock");
+
+    registry.register(Arc::new(ScriptedModelAdapter::new(
+        "http://scripted-registry",
+    )));
+    let resolved = registry
+        .resolve(&anthropic, "http://scripted-registry")
+        .expect("scripted adapter");
+    assert_eq!(resolved.key(), "scripted");
+    assert!(
+        registry
+            .capabilities(&openai, "https://api.openai.com/v1")
+            .expect("openai capabilities")
+            .usage_reporting
+    );
+}
@@ -0,0 +1,158 @@
+mod helpers;
+
+use chrono::{Duration, Utc};
+use runtime_lib::providers::RouteHealthStatus;
+use runtime_lib::route_health::{
+    list_route_health_with_pool, load_route_health_with_pool, plan_route_candidates_with_pool,
+    set_route_load_balance_enabled_with_pool,
+};
+
+async fn insert_attempt(
+    pool: &sqlx::SqlitePool,
+    base_url: &str,
+    model_name: &str,
+    error_kind: &str,
+    latency_ms: i64,
+    age_secs: i64,
+) {
+    sqlx::query(
+        "INSERT INTO route_attempt_logs (id, session_id, capability, api_format, base_url, model_name, attempt_index, retry_index, error_kind, success, error_message, latency_ms, created_at)
+         VALUES (?, 's1', 'chat', 'openai', ?, ?, 1, 0, ?, ?, '', ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(base_url)
+    .bind(model_name)
+    .bind(error_kind)
+    .bind(error_kind == "ok")
+    .bind(latency_ms)
+    .bind((Utc::now() - Duration::seconds(age_secs)).to_rfc3339())
+    .execute(pool)
+    .await
+    .expect("insert route attempt log");
+}
+
+fn candidate(
+    provider_key: &str,
+    base_url: &str,
+    model_name: &str,
+) -> (String, String, String, String, String) {
+    (
+        provider_key.to_string(),
+        "openai".to_string(),
+        base_url.to_string(),
+        model_name.to_string(),
+        "k".to_string(),
+    )
+}
+
+#[tokio::test]
+async fn open_circuit_moves_primary_behind_healthy_fallback() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    for age in [5, 10, 15] {
+        insert_attempt(
+            &pool,
+            "https://primary.example/v1",
+            "deepseek-chat",
+            "network",
+            0,
+            age,
+        )
+        .await;
+    }
+    insert_attempt(
+        &pool,
+        "https://fallback.example/v1",
+        "qwen-max",
+        "ok",
+        600,
+        20,
+    )
+    .await;
+    // 上下文超限不是供应商问题，不影响健康度
+    insert_attempt(
+        &pool,
+        "https://fallback.example/v1",
+        "qwen-max",
+        "context_overflow",
+        0,
+        1,
+    )
+    .await;
+
+    let primary = load_route_health_with_pool(&pool, "https://primary.example/v1", "deepseek-chat")
+        .await
+        .expect("load primary health")
+        .expect("primary has samples");
+    assert_eq!(primary.status, RouteHealthStatus::CircuitOpen);
+    assert_eq!(primary.consecutive_failures, 3);
+
+    let (ordered, decision) = plan_route_candidates_with_pool(
+        &pool,
+        "chat",
+        vec![
+            candidate("deepseek", "https://primary.example/v1", "deepseek-chat"),
+            candidate("qwen", "https://fallback.example/v1", "qwen-max"),
+        ],
+    )
+    .await
+    .expect("plan route candidates");
+    assert_eq!(ordered.len(), 1);
+    assert_eq!(ordered[0].3, "qwen-max");
+    assert_eq!(decision.decision_reason, "skipped_unhealthy");
+    assert_eq!(decision.skipped_count, 1);
+    assert_eq!(decision.selected_provider, "qwen");
+
+    let entries = list_route_health_with_pool(&pool)
+        .await
+        .expect("list health");
+    assert_eq!(entries.len(), 2);
+    let fallback = entries
+        .iter()
+        .find(|entry| entry.model_name == "qwen-max")
+        .expect("fallback entry");
+    assert_eq!(fallback.health.attempts, 1);
+    assert_eq!(fallback.health.p50_latency_ms, Some(600));
+}
+
+#[tokio::test]
+async fn load_balance_prefers_faster_endpoint_for_same_model() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    insert_attempt(
+        &pool,
+        "https://slow.example/v1",
+        "deepseek-chat",
+        "ok",
+        3_000,
+        30,
+    )
+    .await;
+    insert_attempt(
+        &pool,
+        "https://fast.example/v1",
+        "deepseek-chat",
+        "ok",
+        400,
+        30,
+    )
+    .await;
+    let candidates = vec![
+        candidate("deepseek", "https://slow.example/v1", "deepseek-chat"),
+        candidate("", "https://fast.example/v1", "deepseek-chat"),
+    ];
+
+    let (ordered, decision) = plan_route_candidates_with_pool(&pool, "chat", candidates.clone())
+        .await
+        .expect("plan without load balance");
+    assert_eq!(ordered, candidates);
+    assert_eq!(decision.decision_reason, "configured_order");
+
+    set_route_load_balance_enabled_with_pool(&pool, true)
+        .await
+        .expect("enable load balance");
+    let (ordered, decision) = plan_route_candidates_with_pool(&pool, "chat", candidates)
+        .await
+        .expect("plan with load balance");
+    assert_eq!(ordered[0].2, "https://fast.example/v1");
+    assert_eq!(decision.decision_reason, "latency_balanced");
+    assert_eq!(decision.selected_provider, "https://fast.example/v1");
+}
@@ -0,0 +1,68 @@
+mod helpers;
+
+use runtime_lib::agent::run_guard::RunResourceLimits;
+use runtime_lib::run_budgets::{
+    get_employee_run_budget_with_pool, load_session_run_budget_with_pool,
+    save_employee_run_budget_with_pool,
+};
+
+#[tokio::test]
+async fn employee_run_budget_applies_to_sessions_of_that_employee() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    sqlx::query(
+        "INSERT INTO sessions (id, skill_id, title, created_at, model_id, employee_id)
+         VALUES ('session-dev', 'builtin-general', 'budget', '2026-01-01T00:00:00Z', 'm1', 'dev'),
+                ('session-plain', 'builtin-general', 'budget', '2026-01-01T00:00:00Z', 'm1', '')",
+    )
+    .execute(&pool)
+    .await
+    .expect("insert sessions");
+
+    let saved = save_employee_run_budget_with_pool(
+        &pool,
+        " dev ",
+        RunResourceLimits {
+            max_output_tokens: Some(20_000),
+            max_cost: Some(0.5),
+            max_tool_calls: Some(40),
+            ..RunResourceLimits::default()
+        },
+    )
+    .await
+    .expect("save budget")
+    .expect("budget should be stored");
+    assert_eq!(saved.employee_id, "dev");
+
+    let limits = load_session_run_budget_with_pool(&pool, "session-dev")
+        .await
+        .expect("load session budget");
+    assert_eq!(limits.max_output_tokens, Some(20_000));
+    assert_eq!(limits.max_cost, Some(0.5));
+    assert_eq!(limits.max_tool_calls, Some(40));
+    assert_eq!(limits.max_input_tokens, None);
+
+    let plain = load_session_run_budget_with_pool(&pool, "session-plain")
+        .await
+        .expect("load plain session budget");
+    assert!(plain.is_unlimited());
+
+    let invalid = save_employee_run_budget_with_pool(
+        &pool,
+        "dev",
+        RunResourceLimits {
+            warning_ratio: Some(1.5),
+            ..RunResourceLimits::default()
+        },
+    )
+    .await;
+    assert!(invalid.is_err());
+
+    let cleared = save_employee_run_budget_with_pool(&pool, "dev", RunResourceLimits::default())
+        .await
+        .expect("clear budget");
+    assert!(cleared.is_none());
+    assert!(get_employee_run_budget_with_pool(&pool, "dev")
+        .await
+        .expect("read budget")
+        .is_none());
+}
@@ -0,0 +1,265 @@
+mod helpers;
+
+use chrono::{DateTime, Duration, Utc};
+use runtime_lib::scheduled_jobs::{
+    begin_manual_scheduled_job_run_with_pool, claim_due_scheduled_jobs_with_pool,
+    delete_scheduled_job_with_pool, finish_scheduled_job_run_with_pool,
+    get_scheduled_job_with_pool, list_scheduled_job_runs_with_pool,
+    recover_interrupted_scheduled_job_runs_with_pool, save_scheduled_job_with_pool,
+    set_scheduled_job_enabled_with_pool, ScheduledJob, ScheduledJobInput,
+};
+use sqlx::SqlitePool;
+
+fn cron_input(missed_run_policy: &str) -> ScheduledJobInput {
+    ScheduledJobInput {
+        id: None,
+        name: "每日站会纪要".to_string(),
+        target_kind: "employee".to_string(),
+        target_id: "pm-01".to_string(),
+        prompt: "整理昨天的进展和今天的计划".to_string(),
+        work_dir: String::new(),
+        permission_mode: "full_access".to_string(),
+        model_id: String::new(),
+        schedule_kind: "cron".to_string(),
+        cron_expr: "0 9 * * MON-FRI".to_string(),
+        run_at: String::new(),
+        missed_run_policy: missed_run_policy.to_string(),
+        delivery_route_key: "feishu:default:chat-1".to_string(),
+        enabled: None,
+    }
+}
+
+/// 把下一次运行时间拨回到过去，模拟到期或错过
+async fn set_next_run_at(pool: &SqlitePool, job_id: &str, next_run_at: &str) {
+    sqlx::query("UPDATE scheduled_jobs SET next_run_at = ? WHERE id = ?")
+        .bind(next_run_at)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .expect("update next_run_at");
+}
+
+fn parse_time(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .expect("parse time")
+        .with_timezone(&Utc)
+}
+
+async fn reload(pool: &SqlitePool, job: &ScheduledJob) -> ScheduledJob {
+    get_scheduled_job_with_pool(pool, &job.id)
+        .await
+        .expect("reload job")
+}
+
+#[tokio::test]
+async fn save_normalizes_input_and_computes_next_run_at() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    let job = save_scheduled_job_with_pool(&pool, cron_input(""))
+        .await
+        .expect("save job");
+
+    assert_eq!(job.enabled, 1);
+    assert_eq!(job.name, "每日站会纪要");
+    assert_eq!(job.missed_run_policy, "catch_up");
+    assert!(parse_time(&job.next_run_at) > Utc::now());
+
+    let disabled = set_scheduled_job_enabled_with_pool(&pool, &job.id, false)
+        .await
+        .expect("disable");
+    assert_eq!(disabled.enabled, 0);
+    assert!(disabled.next_run_at.is_empty());
+}
+
+#[tokio::test]
+async fn save_rejects_invalid_schedules() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    let mut bad_cron = cron_input("catch_up");
+    bad_cron.cron_expr = "0 25 * * *".to_string();
+    assert!(save_scheduled_job_with_pool(&pool, bad_cron).await.is_err());
+
+    let mut never = cron_input("catch_up");
+    never.cron_expr = "0 0 30 2 *".to_string();
+    assert!(save_scheduled_job_with_pool(&pool, never).await.is_err());
+
+    let mut past_once = cron_input("catch_up");
+    past_once.schedule_kind = "once".to_string();
+    past_once.run_at = (Utc::now() - Duration::hours(1)).to_rfc3339();
+    let error = save_scheduled_job_with_pool(&pool, past_once)
+        .await
+        .expect_err("past one-shot");
+    assert!(error.contains("晚于当前时间"));
+
+    let bad_policy = cron_input("retry");
+    assert!(save_scheduled_job_with_pool(&pool, bad_policy)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn claim_runs_due_job_once_and_skips_overlapping_runs() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let job = save_scheduled_job_with_pool(&pool, cron_input("catch_up"))
+        .await
+        .expect("save job");
+    let now = Utc::now();
+    set_next_run_at(&pool, &job.id, &(now - Duration::seconds(10)).to_rfc3339()).await;
+
+    let claimed = claim_due_scheduled_jobs_with_pool(&pool, now)
+        .await
+        .expect("claim");
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].run.trigger_kind, "schedule");
+    assert_eq!(claimed[0].run.status, "running");
+    let advanced = reload(&pool, &job).await;
+    assert!(parse_time(&advanced.next_run_at) > now);
+    assert_eq!(advanced.last_status, "running");
+
+    // 同一次触发不会被认领两次
+    assert!(claim_due_scheduled_jobs_with_pool(&pool, now)
+        .await
+        .expect("claim again")
+        .is_empty());
+
+    // 上一次仍在运行时，下一次触发记录为跳过
+    set_next_run_at(&pool, &job.id, &(now - Duration::seconds(5)).to_rfc3339()).await;
+    assert!(claim_due_scheduled_jobs_with_pool(&pool, now)
+        .await
+        .expect("claim overlapping")
+        .is_empty());
+    let runs = list_scheduled_job_runs_with_pool(&pool, Some(&job.id), 10)
+        .await
+        .expect("runs");
+    assert_eq!(runs.len(), 2);
+    assert!(runs
+        .iter()
+        .any(|run| run.status == "skipped" && run.error.contains("尚未结束")));
+
+    let finished = finish_scheduled_job_run_with_pool(
+        &pool,
+        &claimed[0].run.id,
+        "succeeded",
+        "纪要已生成",
+        "",
+    )
+    .await
+    .expect("finish");
+    assert_eq!(finished.status, "succeeded");
+    assert!(!finished.finished_at.is_empty());
+    assert_eq!(reload(&pool, &job).await.last_status, "succeeded");
+}
+
+#[tokio::test]
+async fn concurrent_claims_record_a_single_run() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let job = save_scheduled_job_with_pool(&pool, cron_input("catch_up"))
+        .await
+        .expect("save job");
+    let now = Utc::now();
+    set_next_run_at(&pool, &job.id, &(now - Duration::seconds(10)).to_rfc3339()).await;
+
+    let (first, second) = tokio::join!(
+        claim_due_scheduled_jobs_with_pool(&pool, now),
+        claim_due_scheduled_jobs_with_pool(&pool, now)
+    );
+    let claimed = first.expect("first claim").len() + second.expect("second claim").len();
+    assert_eq!(claimed, 1);
+    let runs = list_scheduled_job_runs_with_pool(&pool, Some(&job.id), 10)
+        .await
+        .expect("runs");
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].status, "running");
+}
+
+#[tokio::test]
+async fn missed_runs_follow_catch_up_or_skip_policy() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let catch_up = save_scheduled_job_with_pool(&pool, cron_input("catch_up"))
+        .await
+        .expect("save catch_up");
+    let skip = save_scheduled_job_with_pool(&pool, cron_input("skip"))
+        .await
+        .expect("save skip");
+    let now = Utc::now();
+    let missed_at = (now - Duration::hours(30)).to_rfc3339();
+    set_next_run_at(&pool, &catch_up.id, &missed_at).await;
+    set_next_run_at(&pool, &skip.id, &missed_at).await;
+
+    let claimed = claim_due_scheduled_jobs_with_pool(&pool, now)
+        .await
+        .expect("claim");
+
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].job.id, catch_up.id);
+    assert_eq!(claimed[0].run.trigger_kind, "catch_up");
+    assert_eq!(claimed[0].run.scheduled_for, missed_at);
+    let skipped_runs = list_scheduled_job_runs_with_pool(&pool, Some(&skip.id), 10)
+        .await
+        .expect("skip runs");
+    assert_eq!(skipped_runs.len(), 1);
+    assert_eq!(skipped_runs[0].status, "skipped");
+    // 两个任务都从当前时间往后重新排期，错过的多次触发不会逐个补跑
+    for job in [&catch_up, &skip] {
+        assert!(parse_time(&reload(&pool, job).await.next_run_at) > now);
+    }
+}
+
+#[tokio::test]
+async fn one_shot_job_disables_itself_after_firing() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let mut input = cron_input("catch_up");
+    input.schedule_kind = "once".to_string();
+    input.run_at = (Utc::now() + Duration::minutes(5)).to_rfc3339();
+    let job = save_scheduled_job_with_pool(&pool, input)
+        .await
+        .expect("save once");
+    assert!(job.cron_expr.is_empty());
+    assert_eq!(job.next_run_at, job.run_at);
+
+    let claimed = claim_due_scheduled_jobs_with_pool(&pool, Utc::now() + Duration::minutes(5))
+        .await
+        .expect("claim");
+    assert_eq!(claimed.len(), 1);
+    let fired = reload(&pool, &job).await;
+    assert_eq!(fired.enabled, 0);
+    assert!(fired.next_run_at.is_empty());
+}
+
+#[tokio::test]
+async fn manual_runs_are_exclusive_and_recovered_after_restart() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let job = save_scheduled_job_with_pool(&pool, cron_input("catch_up"))
+        .await
+        .expect("save job");
+    let next_run_at = job.next_run_at.clone();
+
+    let manual = begin_manual_scheduled_job_run_with_pool(&pool, &job.id)
+        .await
+        .expect("manual run");
+    assert_eq!(manual.run.trigger_kind, "manual");
+    assert_eq!(reload(&pool, &job).await.next_run_at, next_run_at);
+    assert!(begin_manual_scheduled_job_run_with_pool(&pool, &job.id)
+        .await
+        .is_err());
+
+    let recovered = recover_interrupted_scheduled_job_runs_with_pool(&pool)
+        .await
+        .expect("recover");
+    assert_eq!(recovered, 1);
+    let runs = list_scheduled_job_runs_with_pool(&pool, None, 10)
+        .await
+        .expect("runs");
+    assert_eq!(runs[0].status, "failed");
+    assert!(runs[0].error.contains("中断"));
+    assert_eq!(reload(&pool, &job).await.last_status, "failed");
+
+    delete_scheduled_job_with_pool(&pool, &job.id)
+        .await
+        .expect("delete");
+    assert!(get_scheduled_job_with_pool(&pool, &job.id).await.is_err());
+    assert!(list_scheduled_job_runs_with_pool(&pool, None, 10)
+        .await
+        .expect("runs after delete")
+        .is_empty());
+}
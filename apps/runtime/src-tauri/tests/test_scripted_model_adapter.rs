@@ -0,0 +1,64 @@
+use runtime_lib::adapters::mock::ScriptedModelAdapter;
+use runtime_lib::agent::permissions::PermissionMode;
+use runtime_lib::agent::types::ToolCall;
+use runtime_lib::agent::{AgentExecutor, AgentTurnRequest, ToolRegistry};
+use runtime_lib::providers::register_model_adapter;
+use serde_json::json;
+use std::fs;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn executor_runs_tool_loop_against_scripted_adapter() {
+    let work_dir = std::env::temp_dir().join("test_scripted_model_adapter_tool_loop");
+    if work_dir.exists() {
+        fs::remove_dir_all(&work_dir).unwrap();
+    }
+    fs::create_dir_all(&work_dir).unwrap();
+    fs::write(work_dir.join("notes.txt"), "脚本化内容").unwrap();
+
+    let adapter = Arc::new(ScriptedModelAdapter::new("http://scripted-tool-loop"));
+    adapter
+        .push_tool_calls(
+            vec![ToolCall {
+                id: "call-read-1".to_string(),
+                name: "read_file".to_string(),
+                input: json!({ "path": "notes.txt" }),
+            }],
+            None,
+        )
+        .push_text("已读取 notes.txt", None);
+    register_model_adapter(adapter.clone());
+
+    let executor = AgentExecutor::new(Arc::new(ToolRegistry::with_file_tools()));
+    let messages = executor
+        .execute_turn(
+            AgentTurnRequest {
+                api_format: "anthropic",
+                base_url: "http://scripted-tool-loop",
+                api_key: "mock-key",
+                model: "scripted-model",
+                skill_system_prompt: "You are a helpful assistant.",
+                messages: vec![json!({"role": "user", "content": "读取 notes.txt"})],
+                permission_mode: PermissionMode::Unrestricted,
+                work_dir: Some(work_dir.to_string_lossy().to_string()),
+                ..AgentTurnRequest::default()
+            },
+            |_| {},
+        )
+        .await
+        .expect("scripted turn should finish");
+
+    assert_eq!(adapter.remaining_turns(), 0);
+    let requests = adapter.recorded_requests();
+    assert_eq!(requests.len(), 2);
+    assert!(
+        serde_json::to_string(&requests[1].messages)
+            .unwrap()
+            .contains("脚本化内容"),
+        "第二轮请求应携带工具结果"
+    );
+    assert_eq!(
+        messages.last().and_then(|m| m["content"].as_str()),
+        Some("已读取 notes.txt")
+    );
+}
@@ -358,6 +358,7 @@ async fn export_session_includes_tool_events_linked_to_assistant_run() {
                 "工具执行错误：路径 C:\\Users\\36443\\WorkClaw\\workspace\\brief.md 的父目录不存在"
                     .into(),
             is_error: true,
+            checkpoint_id: None,
             task_identity: None,
             task_continuation: None,
         },
@@ -571,6 +572,7 @@ async fn export_session_recovery_includes_tool_events_for_failed_run_without_ass
                 "工具执行错误：路径 C:\\Users\\36443\\WorkClaw\\workspace\\brief.md 的父目录不存在"
                     .into(),
             is_error: true,
+            checkpoint_id: None,
             task_identity: None,
             task_continuation: Some(SessionRunTaskContinuationSnapshot {
                 mode: "parent_rejoin".into(),
@@ -709,6 +711,7 @@ async fn export_session_recovery_renders_structured_tool_event_outputs_readably(
             })
             .to_string(),
             is_error: true,
+            checkpoint_id: None,
             task_identity: None,
             task_continuation: None,
         },
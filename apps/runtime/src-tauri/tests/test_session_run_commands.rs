@@ -188,3 +188,49 @@ async fn runtime_state_projection_includes_task_continuation_contract() {
         Some("approval_resume")
     );
 }
+
+#[tokio::test]
+async fn structured_output_is_stored_on_run_record() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let journal_dir = tempfile::tempdir().expect("create journal dir");
+    let journal = SessionJournalStore::new(journal_dir.path().to_path_buf());
+
+    append_session_run_event_with_pool(
+        &pool,
+        &journal,
+        "sess-structured",
+        SessionRunEvent::RunStarted {
+            run_id: "run-structured".into(),
+            user_message_id: "user-1".into(),
+        },
+    )
+    .await
+    .expect("append run started");
+
+    let runs = list_session_runs_with_pool(&pool, "sess-structured")
+        .await
+        .expect("list session runs");
+    assert!(runs[0].structured_output.is_none());
+
+    append_session_run_event_with_pool(
+        &pool,
+        &journal,
+        "sess-structured",
+        SessionRunEvent::StructuredOutputRecorded {
+            run_id: "run-structured".into(),
+            schema_name: "weekly_report".into(),
+            output: serde_json::json!({ "employee": "谢涛", "daily_count": 6 }),
+            repair_turns: 1,
+        },
+    )
+    .await
+    .expect("append structured output");
+
+    let runs = list_session_runs_with_pool(&pool, "sess-structured")
+        .await
+        .expect("list session runs");
+    assert_eq!(
+        runs[0].structured_output,
+        Some(serde_json::json!({ "employee": "谢涛", "daily_count": 6 }))
+    );
+}
@@ -0,0 +1,64 @@
+mod helpers;
+
+use runtime_lib::shell_sandbox::{
+    current_shell_sandbox_profile, load_shell_sandbox_profile_with_pool,
+    reload_shell_sandbox_profile_with_pool, save_shell_sandbox_profile_with_pool,
+    ShellSandboxProfile, SHELL_SANDBOX_SETTING_KEY,
+};
+
+#[tokio::test]
+async fn shell_sandbox_profile_defaults_to_disabled() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    let profile = load_shell_sandbox_profile_with_pool(&pool)
+        .await
+        .expect("load profile");
+
+    assert_eq!(profile, ShellSandboxProfile::default());
+    assert!(!profile.enabled);
+}
+
+#[tokio::test]
+async fn saving_shell_sandbox_profile_persists_and_applies_in_process() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let profile = ShellSandboxProfile {
+        enabled: true,
+        allow_network: false,
+        memory_mb: Some(1024),
+        extra_writable_paths: vec!["  /opt/cache  ".to_string(), " ".to_string()],
+        ..ShellSandboxProfile::default()
+    };
+
+    let saved = save_shell_sandbox_profile_with_pool(&pool, profile)
+        .await
+        .expect("save profile");
+
+    assert_eq!(saved.extra_writable_paths, vec!["/opt/cache".to_string()]);
+    assert_eq!(current_shell_sandbox_profile(), saved);
+    let reloaded = reload_shell_sandbox_profile_with_pool(&pool)
+        .await
+        .expect("reload profile");
+    assert_eq!(reloaded, saved);
+}
+
+#[tokio::test]
+async fn invalid_shell_sandbox_profile_is_rejected() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let profile = ShellSandboxProfile {
+        enabled: true,
+        extra_writable_paths: vec!["relative/cache".to_string()],
+        ..ShellSandboxProfile::default()
+    };
+
+    let error = save_shell_sandbox_profile_with_pool(&pool, profile)
+        .await
+        .expect_err("relative path rejected");
+
+    assert!(error.contains("绝对路径"));
+    let stored: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(SHELL_SANDBOX_SETTING_KEY)
+        .fetch_optional(&pool)
+        .await
+        .expect("query setting");
+    assert!(stored.is_none());
+}
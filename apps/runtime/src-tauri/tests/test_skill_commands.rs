@@ -5,8 +5,12 @@ use runtime_lib::agent::runtime::runtime_io::{
     build_workspace_skill_command_specs, load_workspace_skill_runtime_entries_with_pool,
 };
 use runtime_lib::commands::skills::{
-    create_local_skill_in_dir, import_local_skills_to_pool, render_local_skill_preview_in_dir,
+    add_trusted_publisher_at, create_local_skill_in_dir, import_local_skills_to_pool,
+    install_skill_to_pool, list_trusted_publishers_at, load_trusted_keyring,
+    remove_trusted_publisher_at, render_local_skill_preview_in_dir,
+    UNTRUSTED_SKILLPACK_ERROR_PREFIX,
 };
+use skillpack_rs::{PublisherKey, TrustedKeyring};
 use std::path::Path;
 
 fn write_skill(dir: &Path, name: &str, body: &str) {
@@ -413,3 +417,222 @@ This skill should not be exposed as a dead slash command.
         "non-dispatch hidden skills should not produce dead slash commands"
     );
 }
+
+fn pack_skill_version(
+    dir: &Path,
+    version: &str,
+    skill_id: Option<String>,
+    signing_key: Option<PublisherKey>,
+) -> String {
+    let output = dir.join(format!("skill-{version}.skillpack"));
+    skillpack_rs::pack(&skillpack_rs::PackConfig {
+        dir_path: dir.join("src").to_string_lossy().to_string(),
+        name: "Versioned Skill".to_string(),
+        description: "test".to_string(),
+        version: version.to_string(),
+        author: "tester".to_string(),
+        username: "alice".to_string(),
+        recommended_model: String::new(),
+        output_path: output.to_string_lossy().to_string(),
+        signing_key,
+        recipients: vec![],
+        skill_id,
+    })
+    .expect("pack skill");
+    output.to_string_lossy().to_string()
+}
+
+#[tokio::test]
+async fn install_skill_upgrades_in_place_and_rejects_downgrade() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let dir = tempfile::tempdir().expect("create temp dir");
+    write_skill(&dir.path().join("src"), "Versioned Skill", "v1");
+
+    let v1 = pack_skill_version(dir.path(), "1.0.0", None, None);
+    let keyring = TrustedKeyring::default();
+    let installed = install_skill_to_pool(
+        v1.clone(),
+        "alice".to_string(),
+        &keyring,
+        true,
+        dir.path(),
+        &pool,
+    )
+    .await
+    .expect("install v1");
+    sqlx::query("UPDATE installed_skills SET last_used_at = 'kept' WHERE id = ?")
+        .bind(&installed.id)
+        .execute(&pool)
+        .await
+        .expect("mark used");
+
+    let v2 = pack_skill_version(dir.path(), "1.1.0", Some(installed.id.clone()), None);
+    let upgraded =
+        install_skill_to_pool(v2, "alice".to_string(), &keyring, true, dir.path(), &pool)
+            .await
+            .expect("upgrade to v2");
+    assert_eq!(upgraded.id, installed.id);
+    assert_eq!(upgraded.version, "1.1.0");
+
+    let (count, last_used_at): (i64, Option<String>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(last_used_at) FROM installed_skills WHERE id = ?")
+            .bind(&installed.id)
+            .fetch_one(&pool)
+            .await
+            .expect("query installed skill");
+    assert_eq!(count, 1);
+    assert_eq!(last_used_at.as_deref(), Some("kept"));
+    let (stored_credential,): (String,) =
+        sqlx::query_as("SELECT username FROM installed_skills WHERE id = ?")
+            .bind(&installed.id)
+            .fetch_one(&pool)
+            .await
+            .expect("query stored credential");
+    assert!(runtime_lib::secret_store::is_sealed(&stored_credential));
+    assert_eq!(
+        runtime_lib::secret_store::reveal_secret(&stored_credential).expect("reveal"),
+        "alice"
+    );
+
+    let err = install_skill_to_pool(v1, "alice".to_string(), &keyring, true, dir.path(), &pool)
+        .await
+        .expect_err("downgrade should fail");
+    assert!(err.contains("1.1.0"), "unexpected error: {err}");
+}
+
+#[tokio::test]
+async fn install_skill_requires_confirmation_for_unsigned_and_untrusted_packs() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let dir = tempfile::tempdir().expect("create temp dir");
+    write_skill(&dir.path().join("src"), "Versioned Skill", "v1");
+    let keyring_path = dir.path().join("trusted_publishers.json");
+
+    let unsigned = pack_skill_version(dir.path(), "1.0.0", None, None);
+    let err = install_skill_to_pool(
+        unsigned,
+        "alice".to_string(),
+        &load_trusted_keyring(&keyring_path).expect("load keyring"),
+        false,
+        dir.path(),
+        &pool,
+    )
+    .await
+    .expect_err("unsigned pack needs confirmation");
+    assert_eq!(err, format!("{UNTRUSTED_SKILLPACK_ERROR_PREFIX}unsigned"));
+
+    let key = PublisherKey::generate();
+    let signed = pack_skill_version(dir.path(), "1.1.0", None, Some(key.clone()));
+    let err = install_skill_to_pool(
+        signed.clone(),
+        "alice".to_string(),
+        &load_trusted_keyring(&keyring_path).expect("load keyring"),
+        false,
+        dir.path(),
+        &pool,
+    )
+    .await
+    .expect_err("untrusted publisher needs confirmation");
+    assert_eq!(
+        err,
+        format!(
+            "{UNTRUSTED_SKILLPACK_ERROR_PREFIX}untrusted:{}",
+            key.key_id()
+        )
+    );
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM installed_skills")
+        .fetch_one(&pool)
+        .await
+        .expect("count installed skills");
+    assert_eq!(count, 0);
+
+    let publisher = add_trusted_publisher_at(&keyring_path, "Acme", &key.public_key_base64())
+        .expect("trust publisher");
+    assert_eq!(publisher.key_id, key.key_id());
+    let installed = install_skill_to_pool(
+        signed,
+        "alice".to_string(),
+        &load_trusted_keyring(&keyring_path).expect("load keyring"),
+        false,
+        dir.path(),
+        &pool,
+    )
+    .await
+    .expect("trusted publisher installs");
+    assert_eq!(installed.publisher_key_id, Some(key.key_id()));
+
+    assert_eq!(
+        list_trusted_publishers_at(&keyring_path)
+            .expect("list publishers")
+            .len(),
+        1
+    );
+    assert!(remove_trusted_publisher_at(&keyring_path, &key.key_id()).expect("remove"));
+    assert!(!remove_trusted_publisher_at(&keyring_path, &key.key_id()).expect("remove again"));
+    assert!(list_trusted_publishers_at(&keyring_path)
+        .expect("list publishers")
+        .is_empty());
+}
+
+#[tokio::test]
+async fn install_delta_pack_merges_into_pack_cache_dir() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let dir = tempfile::tempdir().expect("create temp dir");
+    write_skill(&dir.path().join("src"), "Versioned Skill", "v1");
+    let cache_dir = dir.path().join("cache").join("skillpacks");
+    let keyring = TrustedKeyring::default();
+
+    let v1 = pack_skill_version(dir.path(), "1.0.0", None, None);
+    let installed = install_skill_to_pool(
+        v1.clone(),
+        "alice".to_string(),
+        &keyring,
+        true,
+        &cache_dir,
+        &pool,
+    )
+    .await
+    .expect("install v1");
+
+    write_skill(&dir.path().join("src"), "Versioned Skill", "v2");
+    let delta = dir.path().join("skill-1.1.0.delta.skillpack");
+    skillpack_rs::pack_delta(
+        &skillpack_rs::PackConfig {
+            dir_path: dir.path().join("src").to_string_lossy().to_string(),
+            name: "Versioned Skill".to_string(),
+            description: "test".to_string(),
+            version: "1.1.0".to_string(),
+            author: "tester".to_string(),
+            username: "alice".to_string(),
+            recommended_model: String::new(),
+            output_path: delta.to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: Some(installed.id.clone()),
+        },
+        &v1,
+        "alice",
+    )
+    .expect("pack delta");
+
+    let upgraded = install_skill_to_pool(
+        delta.to_string_lossy().to_string(),
+        "alice".to_string(),
+        &keyring,
+        true,
+        &cache_dir,
+        &pool,
+    )
+    .await
+    .expect("install delta");
+    assert_eq!(upgraded.version, "1.1.0");
+
+    let (pack_path,): (String,) =
+        sqlx::query_as("SELECT pack_path FROM installed_skills WHERE id = ?")
+            .bind(&installed.id)
+            .fetch_one(&pool)
+            .await
+            .expect("query pack path");
+    assert!(Path::new(&pack_path).starts_with(&cache_dir));
+    assert!(Path::new(&pack_path).exists());
+    assert!(!dir.path().join("skill-1.1.0.delta.full.skillpack").exists());
+}
@@ -2,12 +2,22 @@ use runtime_lib::agent::runtime::runtime_io::{
     list_skill_os_index_with_pool, view_skill_os_entry_with_pool,
 };
 use runtime_lib::agent::tools::SkillOsTool;
-use runtime_lib::agent::types::{Tool, ToolContext};
+use runtime_lib::agent::types::{Tool, ToolContext, ToolExecution};
 use runtime_lib::commands::skills::{
     archive_skill_os_with_pool, delete_skill_os_with_pool, patch_skill_os_with_pool,
     reset_skill_os_with_pool, restore_skill_os_with_pool,
 };
-use serde_json::json;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// SkillOsTool 只提供异步入口，测试里用独立 runtime 同步驱动一次调用
+fn exec(tool: &Arc<SkillOsTool>, input: Value, ctx: &ToolContext) -> anyhow::Result<String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime")
+        .block_on(Arc::clone(tool).execute_async(input, ctx.clone(), ToolExecution::default()))
+}
 
 fn skill_manifest(id: &str, name: &str, description: &str, tags: &[&str]) -> String {
     json!({
@@ -252,22 +262,25 @@ fn skill_os_tool_exposes_skills_list_and_skill_view() {
         })
         .expect("seed skill");
 
-    let tool = SkillOsTool::new(pool);
-    let list = tool
-        .execute(json!({"action": "skills_list"}), &ToolContext::default())
-        .expect("list skills");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let list = exec(
+        &tool,
+        json!({"action": "skills_list"}),
+        &ToolContext::default(),
+    )
+    .expect("list skills");
     assert!(list.contains("tool-skill"));
     assert!(list.contains("\"canonical\": \"local\""));
 
-    let view = tool
-        .execute(
-            json!({
-                "action": "skill_view",
-                "skill_id": "tool-skill"
-            }),
-            &ToolContext::default(),
-        )
-        .expect("view skill");
+    let view = exec(
+        &tool,
+        json!({
+            "action": "skill_view",
+            "skill_id": "tool-skill"
+        }),
+        &ToolContext::default(),
+    )
+    .expect("view skill");
     assert!(view.contains("Tool view body"));
 }
 
@@ -311,21 +324,21 @@ fn skill_os_tool_patches_directory_skill_with_versions_and_rollback() {
         })
         .expect("seed session");
 
-    let tool = SkillOsTool::new(pool);
-    let patch = tool
-        .execute(
-            json!({
-                "action": "skill_patch",
-                "skill_id": "mutable-skill",
-                "content": "# Improved Skill\n\nImproved body.",
-                "summary": "tighten instructions"
-            }),
-            &ToolContext {
-                session_id: Some("session-growth".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("patch skill");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let patch = exec(
+        &tool,
+        json!({
+            "action": "skill_patch",
+            "skill_id": "mutable-skill",
+            "content": "# Improved Skill\n\nImproved body.",
+            "summary": "tighten instructions"
+        }),
+        &ToolContext {
+            session_id: Some("session-growth".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("patch skill");
     assert!(patch.contains("skill_patch"));
     assert!(patch.contains("\"diff\""));
     assert!(patch.contains("-Original body."));
@@ -351,60 +364,60 @@ fn skill_os_tool_patches_directory_skill_with_versions_and_rollback() {
         .expect("query growth events");
     assert_eq!(growth_count, 1);
 
-    let versions_raw = tool
-        .execute(
-            json!({
-                "action": "skill_versions",
-                "skill_id": "mutable-skill"
-            }),
-            &ToolContext::default(),
-        )
-        .expect("list versions");
+    let versions_raw = exec(
+        &tool,
+        json!({
+            "action": "skill_versions",
+            "skill_id": "mutable-skill"
+        }),
+        &ToolContext::default(),
+    )
+    .expect("list versions");
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).expect("versions json");
     let items = versions["items"].as_array().expect("versions items");
     assert_eq!(items.len(), 1);
     let version_id = items[0]["version_id"].as_str().expect("version id");
     assert_eq!(items[0]["action"], "patch");
 
-    let view_version = tool
-        .execute(
-            json!({
-                "action": "skill_view_version",
-                "skill_id": "mutable-skill",
-                "version_id": version_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("view version");
+    let view_version = exec(
+        &tool,
+        json!({
+            "action": "skill_view_version",
+            "skill_id": "mutable-skill",
+            "version_id": version_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("view version");
     assert!(view_version.contains("Original body"));
 
-    let rollback_needs_confirm = tool
-        .execute(
-            json!({
-                "action": "skill_rollback",
-                "skill_id": "mutable-skill",
-                "version_id": version_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("rollback requires confirm");
+    let rollback_needs_confirm = exec(
+        &tool,
+        json!({
+            "action": "skill_rollback",
+            "skill_id": "mutable-skill",
+            "version_id": version_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("rollback requires confirm");
     assert!(rollback_needs_confirm.contains("confirm=true"));
     assert!(std::fs::read_to_string(&skill_md)
         .expect("read still patched skill")
         .contains("Improved body"));
 
-    let rollback = tool
-        .execute(
-            json!({
-                "action": "skill_rollback",
-                "skill_id": "mutable-skill",
-                "version_id": version_id,
-                "confirm": true,
-                "summary": "restore original"
-            }),
-            &ToolContext::default(),
-        )
-        .expect("rollback skill");
+    let rollback = exec(
+        &tool,
+        json!({
+            "action": "skill_rollback",
+            "skill_id": "mutable-skill",
+            "version_id": version_id,
+            "confirm": true,
+            "summary": "restore original"
+        }),
+        &ToolContext::default(),
+    )
+    .expect("rollback skill");
     assert!(rollback.contains("skill_rollback"));
     assert!(std::fs::read_to_string(&skill_md)
         .expect("read rolled back skill")
@@ -435,29 +448,29 @@ fn skill_os_tool_blocks_skillpack_mutation() {
         })
         .expect("seed pack skill");
 
-    let tool = SkillOsTool::new(pool);
-    let err = tool
-        .execute(
-            json!({
-                "action": "skill_patch",
-                "skill_id": "pack-skill",
-                "content": "# Mutated"
-            }),
-            &ToolContext::default(),
-        )
-        .expect_err("skillpack mutation must fail");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let err = exec(
+        &tool,
+        json!({
+            "action": "skill_patch",
+            "skill_id": "pack-skill",
+            "content": "# Mutated"
+        }),
+        &ToolContext::default(),
+    )
+    .expect_err("skillpack mutation must fail");
     assert!(err.to_string().contains("not mutable"));
 
-    let delete_err = tool
-        .execute(
-            json!({
-                "action": "skill_delete",
-                "skill_id": "pack-skill",
-                "confirm": true
-            }),
-            &ToolContext::default(),
-        )
-        .expect_err("skillpack delete must fail");
+    let delete_err = exec(
+        &tool,
+        json!({
+            "action": "skill_delete",
+            "skill_id": "pack-skill",
+            "confirm": true
+        }),
+        &ToolContext::default(),
+    )
+    .expect_err("skillpack delete must fail");
     assert!(delete_err.to_string().contains("not mutable"));
 }
 
@@ -490,22 +503,22 @@ fn skill_os_tool_creates_agent_skill_in_profile_home_with_growth_event() {
             .expect("seed session");
         });
 
-    let tool = SkillOsTool::new(pool);
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "Weekly Insight Writer",
-                "description": "Write weekly insight summaries from recurring project evidence",
-                "content": "---\nname: Weekly Insight Writer\ndescription: Write weekly insight summaries from recurring project evidence\ntags: [agent-created, writing]\n---\n# Weekly Insight Writer\n\nUse when recurring weekly insight writing succeeds.",
-                "summary": "Promote recurring weekly insight workflow into a reusable skill"
-            }),
-            &ToolContext {
-                session_id: Some("session-create".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create agent skill");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "Weekly Insight Writer",
+            "description": "Write weekly insight summaries from recurring project evidence",
+            "content": "---\nname: Weekly Insight Writer\ndescription: Write weekly insight summaries from recurring project evidence\ntags: [agent-created, writing]\n---\n# Weekly Insight Writer\n\nUse when recurring weekly insight writing succeeds.",
+            "summary": "Promote recurring weekly insight workflow into a reusable skill"
+        }),
+        &ToolContext {
+            session_id: Some("session-create".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create agent skill");
 
     assert!(created.contains("skill_create"));
     assert!(created.contains("\"growth_event_id\""));
@@ -534,15 +547,15 @@ fn skill_os_tool_creates_agent_skill_in_profile_home_with_growth_event() {
     assert!(std::path::Path::new(&pack_path)
         .starts_with(profile_home.path().join("skills").join("active")));
 
-    let versions_raw = tool
-        .execute(
-            json!({
-                "action": "skill_versions",
-                "skill_id": skill_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("list created skill versions");
+    let versions_raw = exec(
+        &tool,
+        json!({
+            "action": "skill_versions",
+            "skill_id": skill_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("list created skill versions");
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).expect("versions json");
     assert_eq!(versions["items"][0]["action"], "create");
 
@@ -592,22 +605,22 @@ fn skill_os_tool_archives_and_restores_agent_created_skill() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool);
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "Archive Candidate",
-                "description": "Temporary workflow",
-                "content": "---\nname: Archive Candidate\ndescription: Temporary workflow\n---\n# Archive Candidate\n\nTemporary workflow.",
-                "summary": "create archive candidate"
-            }),
-            &ToolContext {
-                session_id: Some("session-archive".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create skill");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "Archive Candidate",
+            "description": "Temporary workflow",
+            "content": "---\nname: Archive Candidate\ndescription: Temporary workflow\n---\n# Archive Candidate\n\nTemporary workflow.",
+            "summary": "create archive candidate"
+        }),
+        &ToolContext {
+            session_id: Some("session-archive".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
@@ -624,31 +637,31 @@ fn skill_os_tool_archives_and_restores_agent_created_skill() {
     assert!(std::path::Path::new(&active_path)
         .starts_with(profile_home.path().join("skills").join("active")));
 
-    let archive_without_confirm = tool
-        .execute(
-            json!({
-                "action": "skill_archive",
-                "skill_id": skill_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("archive asks for confirm");
+    let archive_without_confirm = exec(
+        &tool,
+        json!({
+            "action": "skill_archive",
+            "skill_id": skill_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("archive asks for confirm");
     assert!(archive_without_confirm.contains("confirm=true"));
 
-    let archived = tool
-        .execute(
-            json!({
-                "action": "skill_archive",
-                "skill_id": skill_id,
-                "confirm": true,
-                "summary": "archive stale workflow"
-            }),
-            &ToolContext {
-                session_id: Some("session-archive".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("archive skill");
+    let archived = exec(
+        &tool,
+        json!({
+            "action": "skill_archive",
+            "skill_id": skill_id,
+            "confirm": true,
+            "summary": "archive stale workflow"
+        }),
+        &ToolContext {
+            session_id: Some("session-archive".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("archive skill");
     assert!(archived.contains("skill_archive"));
     assert!(archived.contains("\"growth_event_id\""));
 
@@ -666,24 +679,27 @@ fn skill_os_tool_archives_and_restores_agent_created_skill() {
         .join("SKILL.md")
         .exists());
 
-    let list_after_archive = tool
-        .execute(json!({"action": "skills_list"}), &ToolContext::default())
-        .expect("list after archive");
+    let list_after_archive = exec(
+        &tool,
+        json!({"action": "skills_list"}),
+        &ToolContext::default(),
+    )
+    .expect("list after archive");
     assert!(!list_after_archive.contains(&skill_id));
 
-    let restored = tool
-        .execute(
-            json!({
-                "action": "skill_restore",
-                "skill_id": skill_id,
-                "summary": "restore workflow"
-            }),
-            &ToolContext {
-                session_id: Some("session-archive".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("restore skill");
+    let restored = exec(
+        &tool,
+        json!({
+            "action": "skill_restore",
+            "skill_id": skill_id,
+            "summary": "restore workflow"
+        }),
+        &ToolContext {
+            session_id: Some("session-archive".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("restore skill");
     assert!(restored.contains("skill_restore"));
 
     let restored_path: String = runtime
@@ -700,9 +716,12 @@ fn skill_os_tool_archives_and_restores_agent_created_skill() {
         .join("SKILL.md")
         .exists());
 
-    let list_after_restore = tool
-        .execute(json!({"action": "skills_list"}), &ToolContext::default())
-        .expect("list after restore");
+    let list_after_restore = exec(
+        &tool,
+        json!({"action": "skills_list"}),
+        &ToolContext::default(),
+    )
+    .expect("list after restore");
     assert!(list_after_restore.contains(&skill_id));
 
     let lifecycle_events: i64 = runtime
@@ -751,22 +770,22 @@ fn skill_os_tool_deletes_agent_created_skill_with_evidence() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool);
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "Delete Candidate",
-                "description": "Temporary workflow",
-                "content": "---\nname: Delete Candidate\ndescription: Temporary workflow\n---\n# Delete Candidate\n\nTemporary workflow.",
-                "summary": "create delete candidate"
-            }),
-            &ToolContext {
-                session_id: Some("session-delete".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create skill");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "Delete Candidate",
+            "description": "Temporary workflow",
+            "content": "---\nname: Delete Candidate\ndescription: Temporary workflow\n---\n# Delete Candidate\n\nTemporary workflow.",
+            "summary": "create delete candidate"
+        }),
+        &ToolContext {
+            session_id: Some("session-delete".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
@@ -782,32 +801,32 @@ fn skill_os_tool_deletes_agent_created_skill_with_evidence() {
         .expect("active pack path");
     assert!(std::path::Path::new(&active_path).join("SKILL.md").exists());
 
-    let delete_without_confirm = tool
-        .execute(
-            json!({
-                "action": "skill_delete",
-                "skill_id": skill_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("delete asks for confirm");
+    let delete_without_confirm = exec(
+        &tool,
+        json!({
+            "action": "skill_delete",
+            "skill_id": skill_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("delete asks for confirm");
     assert!(delete_without_confirm.contains("confirm=true"));
     assert!(std::path::Path::new(&active_path).exists());
 
-    let deleted = tool
-        .execute(
-            json!({
-                "action": "skill_delete",
-                "skill_id": skill_id,
-                "confirm": true,
-                "summary": "delete obsolete workflow"
-            }),
-            &ToolContext {
-                session_id: Some("session-delete".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("delete skill");
+    let deleted = exec(
+        &tool,
+        json!({
+            "action": "skill_delete",
+            "skill_id": skill_id,
+            "confirm": true,
+            "summary": "delete obsolete workflow"
+        }),
+        &ToolContext {
+            session_id: Some("session-delete".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("delete skill");
     assert!(deleted.contains("skill_delete"));
     assert!(deleted.contains("\"growth_event_id\""));
     assert!(deleted.contains("\"version_id\""));
@@ -823,20 +842,23 @@ fn skill_os_tool_deletes_agent_created_skill_with_evidence() {
         .expect("query installed skill");
     assert_eq!(installed_count, 0);
 
-    let list_after_delete = tool
-        .execute(json!({"action": "skills_list"}), &ToolContext::default())
-        .expect("list after delete");
+    let list_after_delete = exec(
+        &tool,
+        json!({"action": "skills_list"}),
+        &ToolContext::default(),
+    )
+    .expect("list after delete");
     assert!(!list_after_delete.contains(&skill_id));
 
-    let versions_raw = tool
-        .execute(
-            json!({
-                "action": "skill_versions",
-                "skill_id": skill_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("list versions");
+    let versions_raw = exec(
+        &tool,
+        json!({
+            "action": "skill_versions",
+            "skill_id": skill_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("list versions");
     assert!(versions_raw.contains("\"action\": \"delete\""));
 
     let delete_events: i64 = runtime
@@ -885,29 +907,30 @@ fn skill_os_tool_resets_skill_to_earliest_version_snapshot() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool);
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "Reset Candidate",
-                "description": "Reset baseline",
-                "content": "---\nname: Reset Candidate\ndescription: Reset baseline\n---\n# Reset Candidate\n\nBaseline body.",
-                "summary": "create reset baseline"
-            }),
-            &ToolContext {
-                session_id: Some("session-reset".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create reset skill");
+    let tool = Arc::new(SkillOsTool::new(pool));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "Reset Candidate",
+            "description": "Reset baseline",
+            "content": "---\nname: Reset Candidate\ndescription: Reset baseline\n---\n# Reset Candidate\n\nBaseline body.",
+            "summary": "create reset baseline"
+        }),
+        &ToolContext {
+            session_id: Some("session-reset".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create reset skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
         .expect("skill id")
         .to_string();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "skill_patch",
             "skill_id": skill_id,
@@ -921,46 +944,46 @@ fn skill_os_tool_resets_skill_to_earliest_version_snapshot() {
     )
     .expect("patch reset skill");
 
-    let reset_without_confirm = tool
-        .execute(
-            json!({
-                "action": "skill_reset",
-                "skill_id": skill_id
-            }),
-            &ToolContext::default(),
-        )
-        .expect("reset asks for confirm");
+    let reset_without_confirm = exec(
+        &tool,
+        json!({
+            "action": "skill_reset",
+            "skill_id": skill_id
+        }),
+        &ToolContext::default(),
+    )
+    .expect("reset asks for confirm");
     assert!(reset_without_confirm.contains("confirm=true"));
 
-    let reset = tool
-        .execute(
-            json!({
-                "action": "skill_reset",
-                "skill_id": skill_id,
-                "confirm": true,
-                "summary": "reset to baseline"
-            }),
-            &ToolContext {
-                session_id: Some("session-reset".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("reset skill");
+    let reset = exec(
+        &tool,
+        json!({
+            "action": "skill_reset",
+            "skill_id": skill_id,
+            "confirm": true,
+            "summary": "reset to baseline"
+        }),
+        &ToolContext {
+            session_id: Some("session-reset".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("reset skill");
     assert!(reset.contains("skill_reset"));
     assert!(reset.contains("Baseline body."));
     assert!(reset.contains("\"growth_event_id\""));
     assert!(reset.contains("\"reset_to_version_id\""));
 
-    let versions_raw = tool
-        .execute(
-            json!({
-                "action": "skill_versions",
-                "skill_id": skill_id,
-                "limit": 10
-            }),
-            &ToolContext::default(),
-        )
-        .expect("list reset versions");
+    let versions_raw = exec(
+        &tool,
+        json!({
+            "action": "skill_versions",
+            "skill_id": skill_id,
+            "limit": 10
+        }),
+        &ToolContext::default(),
+    )
+    .expect("list reset versions");
     let versions: serde_json::Value = serde_json::from_str(&versions_raw).expect("versions json");
     let items = versions["items"].as_array().expect("versions items");
     assert!(items.iter().any(|item| item["action"] == "reset"));
@@ -1011,29 +1034,30 @@ fn skill_os_ui_reset_records_profile_growth_event() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool.clone());
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "UI Reset Candidate",
-                "description": "UI reset baseline",
-                "content": "---\nname: UI Reset Candidate\ndescription: UI reset baseline\n---\n# UI Reset Candidate\n\nBaseline body.",
-                "summary": "create ui reset baseline"
-            }),
-            &ToolContext {
-                session_id: Some("session-ui-reset".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create ui reset skill");
+    let tool = Arc::new(SkillOsTool::new(pool.clone()));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "UI Reset Candidate",
+            "description": "UI reset baseline",
+            "content": "---\nname: UI Reset Candidate\ndescription: UI reset baseline\n---\n# UI Reset Candidate\n\nBaseline body.",
+            "summary": "create ui reset baseline"
+        }),
+        &ToolContext {
+            session_id: Some("session-ui-reset".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create ui reset skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
         .expect("skill id")
         .to_string();
 
-    tool.execute(
+    exec(
+        &tool,
         json!({
             "action": "skill_patch",
             "skill_id": skill_id,
@@ -1118,22 +1142,22 @@ fn skill_os_ui_patch_records_profile_growth_event() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool.clone());
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "UI Patch Candidate",
-                "description": "UI patch baseline",
-                "content": "---\nname: UI Patch Candidate\ndescription: UI patch baseline\n---\n# UI Patch Candidate\n\nBaseline body.",
-                "summary": "create ui patch baseline"
-            }),
-            &ToolContext {
-                session_id: Some("session-ui-patch".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create ui patch skill");
+    let tool = Arc::new(SkillOsTool::new(pool.clone()));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "UI Patch Candidate",
+            "description": "UI patch baseline",
+            "content": "---\nname: UI Patch Candidate\ndescription: UI patch baseline\n---\n# UI Patch Candidate\n\nBaseline body.",
+            "summary": "create ui patch baseline"
+        }),
+        &ToolContext {
+            session_id: Some("session-ui-patch".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create ui patch skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
@@ -1213,22 +1237,22 @@ fn skill_os_ui_archive_restore_delete_record_profile_growth_events() {
         .expect("seed session");
     });
 
-    let tool = SkillOsTool::new(pool.clone());
-    let created = tool
-        .execute(
-            json!({
-                "action": "skill_create",
-                "name": "UI Lifecycle Candidate",
-                "description": "UI lifecycle baseline",
-                "content": "---\nname: UI Lifecycle Candidate\ndescription: UI lifecycle baseline\n---\n# UI Lifecycle Candidate\n\nLifecycle body.",
-                "summary": "create ui lifecycle baseline"
-            }),
-            &ToolContext {
-                session_id: Some("session-ui-lifecycle".to_string()),
-                ..ToolContext::default()
-            },
-        )
-        .expect("create ui lifecycle skill");
+    let tool = Arc::new(SkillOsTool::new(pool.clone()));
+    let created = exec(
+        &tool,
+        json!({
+            "action": "skill_create",
+            "name": "UI Lifecycle Candidate",
+            "description": "UI lifecycle baseline",
+            "content": "---\nname: UI Lifecycle Candidate\ndescription: UI lifecycle baseline\n---\n# UI Lifecycle Candidate\n\nLifecycle body.",
+            "summary": "create ui lifecycle baseline"
+        }),
+        &ToolContext {
+            session_id: Some("session-ui-lifecycle".to_string()),
+            ..ToolContext::default()
+        },
+    )
+    .expect("create ui lifecycle skill");
     let created_json: serde_json::Value = serde_json::from_str(&created).expect("created json");
     let skill_id = created_json["skill"]["entry"]["skill_id"]
         .as_str()
@@ -0,0 +1,64 @@
+mod helpers;
+
+use runtime_lib::web_fetch_policy::{
+    current_web_fetch_policy, load_web_fetch_policy_with_pool, reload_web_fetch_policy_with_pool,
+    save_web_fetch_policy_with_pool, WebFetchPolicy, WEB_FETCH_POLICY_SETTING_KEY,
+};
+
+#[tokio::test]
+async fn web_fetch_policy_defaults_to_public_only() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+
+    let policy = load_web_fetch_policy_with_pool(&pool)
+        .await
+        .expect("load policy");
+
+    assert_eq!(policy, WebFetchPolicy::default());
+    assert!(policy.allowed_private_hosts.is_empty());
+    assert!(policy.respect_robots_txt);
+}
+
+#[tokio::test]
+async fn saving_web_fetch_policy_normalizes_rules_and_applies_in_process() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let policy = WebFetchPolicy {
+        allowed_private_hosts: vec!["  LocalHost ".to_string(), " ".to_string()],
+        blocked_hosts: vec!["*.Ads.Example.com".to_string()],
+        user_agent: " WorkClawBot/2.0 ".to_string(),
+        ..WebFetchPolicy::default()
+    };
+
+    let saved = save_web_fetch_policy_with_pool(&pool, policy)
+        .await
+        .expect("save policy");
+
+    assert_eq!(saved.allowed_private_hosts, vec!["localhost".to_string()]);
+    assert_eq!(saved.blocked_hosts, vec!["*.ads.example.com".to_string()]);
+    assert_eq!(saved.user_agent, "WorkClawBot/2.0");
+    assert_eq!(current_web_fetch_policy(), saved);
+    let reloaded = reload_web_fetch_policy_with_pool(&pool)
+        .await
+        .expect("reload policy");
+    assert_eq!(reloaded, saved);
+}
+
+#[tokio::test]
+async fn invalid_web_fetch_policy_is_rejected() {
+    let (pool, _tmp) = helpers::setup_test_db().await;
+    let policy = WebFetchPolicy {
+        allowed_private_hosts: vec!["http://intranet.example.com".to_string()],
+        ..WebFetchPolicy::default()
+    };
+
+    let error = save_web_fetch_policy_with_pool(&pool, policy)
+        .await
+        .expect_err("url rule rejected");
+
+    assert!(error.contains("主机规则格式无效"));
+    let stored: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(WEB_FETCH_POLICY_SETTING_KEY)
+        .fetch_optional(&pool)
+        .await
+        .expect("query setting");
+    assert!(stored.is_none());
+}
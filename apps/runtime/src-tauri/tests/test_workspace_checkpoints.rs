@@ -0,0 +1,320 @@
+mod helpers;
+
+use runtime_lib::agent::types::ToolContext;
+use runtime_lib::workspace_checkpoints::{
+    capture_workspace_snapshot, diff_workspace_checkpoints_with_pool,
+    list_workspace_checkpoints_with_pool, record_workspace_checkpoint_with_pool,
+    revert_workspace_checkpoints_with_pool, workspace_mutation_paths, WorkspaceCheckpointOrigin,
+};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+
+struct Fixture {
+    pool: SqlitePool,
+    store: PathBuf,
+    work: PathBuf,
+    _db: tempfile::TempDir,
+    _tmp: tempfile::TempDir,
+}
+
+async fn setup() -> Fixture {
+    let (pool, db) = helpers::setup_test_db().await;
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let store = tmp.path().join("checkpoints");
+    let work = tmp.path().join("workspace");
+    std::fs::create_dir_all(&work).expect("create workspace");
+    let work = work.canonicalize().expect("canonical workspace");
+    Fixture {
+        pool,
+        store,
+        work,
+        _db: db,
+        _tmp: tmp,
+    }
+}
+
+/// 模拟一次工具调用：执行前快照、执行改动、执行后记录检查点
+async fn run_mutation(
+    fx: &Fixture,
+    turn_index: i64,
+    call_id: &str,
+    tool_name: &str,
+    input: Value,
+    mutate: impl FnOnce(),
+) -> Option<String> {
+    let ctx = ToolContext {
+        work_dir: Some(fx.work.clone()),
+        ..ToolContext::default()
+    };
+    let paths = workspace_mutation_paths(tool_name, &input, &ctx);
+    let before = capture_workspace_snapshot(&fx.store, &paths).expect("before snapshot");
+    mutate();
+    record_workspace_checkpoint_with_pool(
+        &fx.pool,
+        &fx.store,
+        WorkspaceCheckpointOrigin {
+            session_id: "session-1",
+            run_id: "run-1",
+            turn_index,
+            call_id,
+            tool_name,
+            work_dir: Some(&fx.work),
+        },
+        &before,
+    )
+    .await
+    .expect("record checkpoint")
+}
+
+#[tokio::test]
+async fn unchanged_files_do_not_create_checkpoints() {
+    let fx = setup().await;
+    let work = &fx.work;
+    std::fs::write(work.join("a.txt"), "same").expect("seed");
+
+    let checkpoint_id = run_mutation(
+        &fx,
+        1,
+        "call-1",
+        "write_file",
+        json!({ "path": "a.txt" }),
+        || std::fs::write(work.join("a.txt"), "same").expect("write"),
+    )
+    .await;
+
+    assert!(checkpoint_id.is_none());
+    assert!(
+        list_workspace_checkpoints_with_pool(&fx.pool, "run-1", None)
+            .await
+            .expect("list")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn run_diff_reports_net_changes_across_turns() {
+    let fx = setup().await;
+    let work = &fx.work;
+    std::fs::write(work.join("notes.md"), "line one\nline two\n").expect("seed");
+
+    run_mutation(
+        &fx,
+        1,
+        "call-1",
+        "edit",
+        json!({ "path": "notes.md" }),
+        || std::fs::write(work.join("notes.md"), "line one\nline 2\n").expect("edit"),
+    )
+    .await
+    .expect("turn 1 checkpoint");
+    run_mutation(
+        &fx,
+        2,
+        "call-2",
+        "write_file",
+        json!({ "path": "new.txt" }),
+        || std::fs::write(work.join("new.txt"), "hello").expect("write"),
+    )
+    .await
+    .expect("turn 2 checkpoint");
+
+    let checkpoints = list_workspace_checkpoints_with_pool(&fx.pool, "run-1", None)
+        .await
+        .expect("list");
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints[0].turn_index, 1);
+    assert_eq!(checkpoints[1].tool_name, "write_file");
+
+    let diffs = diff_workspace_checkpoints_with_pool(&fx.pool, &fx.store, "run-1", None)
+        .await
+        .expect("run diff");
+    assert_eq!(diffs.len(), 2);
+    let new_file = diffs
+        .iter()
+        .find(|diff| diff.path.ends_with("new.txt"))
+        .expect("new file diff");
+    assert_eq!(new_file.status, "added");
+    let notes = diffs
+        .iter()
+        .find(|diff| diff.path.ends_with("notes.md"))
+        .expect("notes diff");
+    assert_eq!(notes.status, "modified");
+    assert!(notes.diff.contains("-line two\n+line 2"));
+
+    let turn_two = diff_workspace_checkpoints_with_pool(&fx.pool, &fx.store, "run-1", Some(2))
+        .await
+        .expect("turn diff");
+    assert_eq!(turn_two.len(), 1);
+    assert!(turn_two[0].path.ends_with("new.txt"));
+}
+
+#[tokio::test]
+async fn reverting_a_turn_restores_only_that_turn() {
+    let fx = setup().await;
+    let work = &fx.work;
+    std::fs::write(work.join("keep.txt"), "v1").expect("seed");
+    std::fs::write(work.join("drop.txt"), "original").expect("seed");
+
+    run_mutation(
+        &fx,
+        1,
+        "call-1",
+        "write_file",
+        json!({ "path": "keep.txt" }),
+        || std::fs::write(work.join("keep.txt"), "v2").expect("write"),
+    )
+    .await
+    .expect("turn 1 checkpoint");
+    run_mutation(
+        &fx,
+        2,
+        "call-2",
+        "file_delete",
+        json!({ "path": "drop.txt" }),
+        || std::fs::remove_file(work.join("drop.txt")).expect("delete"),
+    )
+    .await
+    .expect("turn 2 checkpoint");
+
+    let result =
+        revert_workspace_checkpoints_with_pool(&fx.pool, &fx.store, "run-1", Some(2), None, false)
+            .await
+            .expect("revert turn");
+
+    assert_eq!(result.restored.len(), 1);
+    assert!(result.conflicts.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(work.join("drop.txt")).expect("restored"),
+        "original"
+    );
+    assert_eq!(
+        std::fs::read_to_string(work.join("keep.txt")).expect("kept"),
+        "v2"
+    );
+
+    let err =
+        revert_workspace_checkpoints_with_pool(&fx.pool, &fx.store, "run-1", Some(2), None, false)
+            .await
+            .expect_err("turn already reverted");
+    assert!(err.contains("没有可回退的改动"));
+}
+
+#[tokio::test]
+async fn reverting_a_run_undoes_moves_and_copies() {
+    let fx = setup().await;
+    let work = &fx.work;
+    std::fs::create_dir_all(work.join("src")).expect("mkdir");
+    std::fs::write(work.join("src/a.txt"), "a").expect("seed");
+    std::fs::write(work.join("src/b.txt"), "b").expect("seed");
+
+    run_mutation(
+        &fx,
+        1,
+        "call-1",
+        "file_copy",
+        json!({ "source": "src", "destination": "copy/src" }),
+        || {
+            std::fs::create_dir_all(work.join("copy/src")).expect("mkdir");
+            std::fs::copy(work.join("src/a.txt"), work.join("copy/src/a.txt")).expect("copy");
+            std::fs::copy(work.join("src/b.txt"), work.join("copy/src/b.txt")).expect("copy");
+        },
+    )
+    .await
+    .expect("copy checkpoint");
+    run_mutation(
+        &fx,
+        1,
+        "call-2",
+        "file_move",
+        json!({ "source": "src/a.txt", "destination": "moved.txt" }),
+        || std::fs::rename(work.join("src/a.txt"), work.join("moved.txt")).expect("move"),
+    )
+    .await
+    .expect("move checkpoint");
+
+    let result =
+        revert_workspace_checkpoints_with_pool(&fx.pool, &fx.store, "run-1", None, None, false)
+            .await
+            .expect("revert run");
+
+    assert!(result.conflicts.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(work.join("src/a.txt")).expect("a"),
+        "a"
+    );
+    assert!(!work.join("moved.txt").exists());
+    assert!(!work.join("copy").exists(), "empty copied dirs are pruned");
+}
+
+#[tokio::test]
+async fn file_revert_detects_later_edits_unless_forced() {
+    let fx = setup().await;
+    let work = &fx.work;
+    std::fs::write(work.join("a.txt"), "before").expect("seed");
+    std::fs::write(work.join("b.txt"), "before").expect("seed");
+
+    run_mutation(
+        &fx,
+        1,
+        "call-1",
+        "write_file",
+        json!({ "path": "a.txt" }),
+        || std::fs::write(work.join("a.txt"), "agent").expect("write"),
+    )
+    .await
+    .expect("a checkpoint");
+    run_mutation(
+        &fx,
+        1,
+        "call-2",
+        "write_file",
+        json!({ "path": "b.txt" }),
+        || std::fs::write(work.join("b.txt"), "agent").expect("write"),
+    )
+    .await
+    .expect("b checkpoint");
+    std::fs::write(work.join("a.txt"), "user edit").expect("user edit");
+
+    let result = revert_workspace_checkpoints_with_pool(
+        &fx.pool,
+        &fx.store,
+        "run-1",
+        None,
+        Some("a.txt"),
+        false,
+    )
+    .await
+    .expect("revert file");
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(work.join("a.txt")).expect("a"),
+        "user edit"
+    );
+
+    let result = revert_workspace_checkpoints_with_pool(
+        &fx.pool,
+        &fx.store,
+        "run-1",
+        None,
+        Some("a.txt"),
+        true,
+    )
+    .await
+    .expect("force revert file");
+    assert_eq!(result.restored.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(work.join("a.txt")).expect("a"),
+        "before"
+    );
+    assert_eq!(
+        std::fs::read_to_string(work.join("b.txt")).expect("b"),
+        "agent"
+    );
+
+    let checkpoints = list_workspace_checkpoints_with_pool(&fx.pool, "run-1", None)
+        .await
+        .expect("list");
+    assert!(checkpoints[0].files[0].reverted_at.is_some());
+    assert!(checkpoints[1].files[0].reverted_at.is_none());
+}
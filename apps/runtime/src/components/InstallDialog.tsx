@@ -5,6 +5,18 @@ import { ClawhubSkillSummary, SkillManifest } from "../types";
 import { RiskConfirmDialog } from "./RiskConfirmDialog";
 
 type InstallMode = "skillpack" | "local" | "clawhub" | "industry";
+const UNTRUSTED_SKILLPACK_PREFIX = "UNTRUSTED_SKILLPACK:";
+
+function describeUntrustedSkillpack(error: string): string | null {
+  const index = error.indexOf(UNTRUSTED_SKILLPACK_PREFIX);
+  if (index < 0) return null;
+  const detail = error.slice(index + UNTRUSTED_SKILLPACK_PREFIX.length);
+  if (detail.startsWith("untrusted:")) {
+    const keyId = detail.slice("untrusted:".length);
+    return `该技能包的发布者（密钥 ${keyId}）不在受信任发布者列表中，无法确认来源。`;
+  }
+  return "该技能包没有发布者签名，无法确认来源和是否被篡改。";
+}
 type LocalImportBatchResult = {
   installed: { dir_path: string; manifest: { id: string; name?: string } }[];
   failed: { dir_path: string; name_hint: string; error: string }[];
@@ -35,6 +47,7 @@ export function InstallDialog({ onInstalled, onClose }: Props) {
   const [clawhubResults, setClawhubResults] = useState<ClawhubSkillSummary[]>([]);
   const [selectedClawhubSlug, setSelectedClawhubSlug] = useState<string>("");
   const [installConfirmOpen, setInstallConfirmOpen] = useState(false);
+  const [untrustedWarning, setUntrustedWarning] = useState("");
 
   async function pickFile() {
     const f = await open({ filters: [{ name: "SkillPack", extensions: ["skillpack"] }] });
@@ -54,6 +67,7 @@ export function InstallDialog({ onInstalled, onClose }: Props) {
   function switchMode(m: InstallMode) {
     setMode(m);
     setError("");
+    setUntrustedWarning("");
     setMcpWarning([]);
     setInstallConfirmOpen(false);
     if (m !== "industry") {
@@ -105,8 +119,9 @@ export function InstallDialog({ onInstalled, onClose }: Props) {
     }
   }
 
-  async function handleInstall() {
+  async function handleInstall(allowUntrusted = false) {
     setError("");
+    setUntrustedWarning("");
     setMcpWarning([]);
     setLoading(true);
 
@@ -117,7 +132,19 @@ export function InstallDialog({ onInstalled, onClose }: Props) {
           setLoading(false);
           return;
         }
-        const manifest = await invoke<SkillManifest>("install_skill", { packPath, username });
+        let manifest: SkillManifest;
+        try {
+          manifest = await invoke<SkillManifest>("install_skill", {
+            packPath,
+            username,
+            allowUntrusted,
+          });
+        } catch (e: unknown) {
+          const warning = describeUntrustedSkillpack(String(e));
+          if (!warning) throw e;
+          setUntrustedWarning(warning);
+          return;
+        }
         onInstalled(manifest.id);
         onClose();
       } else if (mode === "local") {
@@ -381,6 +408,22 @@ export function InstallDialog({ onInstalled, onClose }: Props) {
 
         {error && <div className="text-red-500 text-sm">{error}</div>}
 
+        {untrustedWarning && (
+          <div className="text-amber-700 text-sm bg-amber-50 border border-amber-100 rounded p-2 space-y-2">
+            <div>{untrustedWarning}</div>
+            <div className="text-xs text-gray-500">
+              可在「技能打包 → 受信任发布者」中添加发布者公钥，或确认来源可靠后继续安装。
+            </div>
+            <button
+              onClick={() => void handleInstall(true)}
+              disabled={loading}
+              className="h-7 px-3 rounded bg-amber-100 hover:bg-amber-200 disabled:bg-gray-100 text-amber-800 text-xs transition-colors"
+            >
+              仍然安装
+            </button>
+          </div>
+        )}
+
         {mcpWarning.length > 0 && (
           <div className="text-amber-600 text-sm">
             <div className="font-medium mb-1">此 Skill 需要以下 MCP 服务器：</div>
@@ -5,6 +5,7 @@ import { DesktopSettingsSection } from "./settings/desktop/DesktopSettingsSectio
 import { FeishuSettingsTab } from "./settings/feishu/FeishuSettingsTab";
 import { useFeishuSettingsController } from "./settings/feishu/useFeishuSettingsController";
 import { useChannelRegistryController } from "./settings/channels/useChannelRegistryController";
+import { McpServerExposureSection } from "./settings/mcp/McpServerExposureSection";
 import { McpSettingsSection } from "./settings/mcp/McpSettingsSection";
 import { ModelsSettingsSection } from "./settings/models/ModelsSettingsSection";
 import { RoutingSettingsSection } from "./settings/routing/RoutingSettingsSection";
@@ -288,7 +289,12 @@ export function SettingsView({
         />
       )}
 
-      {SHOW_MCP_SETTINGS && activeTab === "mcp" && <McpSettingsSection />}
+      {SHOW_MCP_SETTINGS && activeTab === "mcp" && (
+        <>
+          <McpSettingsSection />
+          <McpServerExposureSection />
+        </>
+      )}
 
       {activeTab === "search" && <SearchSettingsSection />}
 
@@ -0,0 +1,60 @@
+import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { InstallDialog } from "../InstallDialog";
+
+const invokeMock = vi.fn();
+const openMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: (...args: unknown[]) => invokeMock(...args),
+}));
+
+vi.mock("@tauri-apps/plugin-dialog", () => ({
+  open: (...args: unknown[]) => openMock(...args),
+}));
+
+describe("InstallDialog skillpack trust", () => {
+  beforeEach(() => {
+    invokeMock.mockReset();
+    openMock.mockReset();
+  });
+
+  test("asks before installing a pack from an untrusted publisher", async () => {
+    openMock.mockResolvedValueOnce("C:\\packs\\writer.skillpack");
+    invokeMock.mockImplementation((command: string, args: { allowUntrusted?: boolean }) => {
+      if (command === "install_skill" && !args.allowUntrusted) {
+        return Promise.reject("UNTRUSTED_SKILLPACK:untrusted:0123abcd");
+      }
+      return Promise.resolve({ id: "skill-writer" });
+    });
+
+    const onInstalled = vi.fn();
+    const onClose = vi.fn();
+    render(<InstallDialog onInstalled={onInstalled} onClose={onClose} />);
+
+    fireEvent.click(screen.getByRole("button", { name: "选择 .skillpack 文件" }));
+    await waitFor(() => {
+      expect(screen.getByText("writer.skillpack")).toBeInTheDocument();
+    });
+    fireEvent.change(screen.getByRole("textbox"), { target: { value: "alice" } });
+
+    fireEvent.click(screen.getByRole("button", { name: "安装" }));
+    fireEvent.click(screen.getByRole("button", { name: "确认安装" }));
+
+    await waitFor(() => {
+      expect(screen.getByText(/密钥 0123abcd/)).toBeInTheDocument();
+    });
+    expect(onInstalled).not.toHaveBeenCalled();
+
+    fireEvent.click(screen.getByRole("button", { name: "仍然安装" }));
+
+    await waitFor(() => {
+      expect(invokeMock).toHaveBeenLastCalledWith("install_skill", {
+        packPath: "C:\\packs\\writer.skillpack",
+        username: "alice",
+        allowUntrusted: true,
+      });
+      expect(onInstalled).toHaveBeenCalledWith("skill-writer");
+      expect(onClose).toHaveBeenCalled();
+    });
+  });
+});
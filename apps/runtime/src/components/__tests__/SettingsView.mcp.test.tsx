@@ -100,6 +100,18 @@ function installDefaultInvokeMock() {
       mcpServers = [...mcpServers, { id: `mcp-${mcpId}`, name, command: cmd, args, env }];
       return Promise.resolve(null);
     }
+    if (command === "get_mcp_server_settings" || command === "save_mcp_server_settings") {
+      const enabled = command === "save_mcp_server_settings" ? (payload as { input: { enabled: boolean } }).input.enabled : false;
+      return Promise.resolve({
+        enabled,
+        port: 47821,
+        token: "local-token",
+        running: enabled,
+        url: "http://127.0.0.1:47821/mcp",
+        last_error: null,
+        client_config: { mcpServers: {} },
+      });
+    }
     if (command === "remove_mcp_server") {
       const { id } = payload as { id: string };
       mcpServers = mcpServers.filter((server) => server.id !== id);
@@ -124,7 +136,7 @@ describe("SettingsView MCP settings", () => {
       expect(screen.getByRole("button", { name: "MCP 服务器" })).toHaveClass("text-[var(--sm-primary-strong)]");
     });
 
-    fireEvent.change(screen.getByRole("combobox"), { target: { value: "brave-search" } });
+    fireEvent.change(screen.getAllByRole("combobox")[0], { target: { value: "brave-search" } });
     expect(screen.getByPlaceholderText("请输入 BRAVE_API_KEY")).toBeInTheDocument();
 
     fireEvent.change(screen.getByPlaceholderText("请输入 BRAVE_API_KEY"), {
@@ -166,4 +178,22 @@ describe("SettingsView MCP settings", () => {
       expect.objectContaining({ name: "broken-json-server" }),
     );
   });
+
+  test("enables WorkClaw as a local MCP server", async () => {
+    render(<SettingsView onClose={() => {}} initialTab="mcp" />);
+
+    await waitFor(() => {
+      expect(screen.getByText("将 WorkClaw 作为 MCP 服务端")).toBeInTheDocument();
+    });
+    expect(screen.getByText("未运行")).toBeInTheDocument();
+
+    fireEvent.click(screen.getByRole("checkbox"));
+
+    await waitFor(() => {
+      expect(screen.getByText("运行中 · http://127.0.0.1:47821/mcp")).toBeInTheDocument();
+    });
+    expect(invokeMock).toHaveBeenCalledWith("save_mcp_server_settings", {
+      input: { enabled: true, port: 47821 },
+    });
+  });
 });
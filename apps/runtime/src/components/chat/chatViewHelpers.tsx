@@ -276,6 +276,15 @@ export function getRunFailureDisplay(run: SessionRunProjection) {
     };
   }
 
+  if (run.error_kind === "budget_exceeded") {
+    return {
+      title: "任务达到资源预算上限",
+      message:
+        run.error_message || "本轮任务的 token、费用、耗时或工具调用次数已达到预算上限，系统已自动停止。",
+      rawMessage: null as string | null,
+    };
+  }
+
   if (run.error_kind === "policy_blocked") {
     return {
       title: "当前任务无法继续执行",
@@ -1,8 +1,25 @@
 import { useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { save } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { FrontMatter } from "../../types";
 
+interface PublisherKeyInfo {
+  signing_key: string;
+  public_key: string;
+  key_id: string;
+}
+
+interface BasePackInfo {
+  path: string;
+  id: string;
+  version: string;
+}
+
+interface DeltaPackSummary {
+  changed_files: string[];
+  removed_files: string[];
+}
+
 interface PackFormProps {
   dirPath: string;
   frontMatter: FrontMatter;
@@ -16,10 +33,46 @@ export function PackForm({ dirPath, frontMatter, fileCount }: PackFormProps) {
   const [author, setAuthor] = useState("");
   const [username, setUsername] = useState("");
   const [recommendedModel, setRecommendedModel] = useState(frontMatter.model ?? "claude-3-5-sonnet-20241022");
+  const [signingKey, setSigningKey] = useState("");
+  const [generatedKey, setGeneratedKey] = useState<PublisherKeyInfo | null>(null);
+  const [basePack, setBasePack] = useState<BasePackInfo | null>(null);
+  const [deltaOnly, setDeltaOnly] = useState(false);
+  const [deltaSummary, setDeltaSummary] = useState<DeltaPackSummary | null>(null);
   const [status, setStatus] = useState<"idle" | "packing" | "done" | "error">("idle");
   const [errorMsg, setErrorMsg] = useState("");
   const packInFlightRef = useRef(false);
 
+  async function handleGenerateKey() {
+    try {
+      const key = await invoke<PublisherKeyInfo>("generate_publisher_key");
+      setGeneratedKey(key);
+      setSigningKey(key.signing_key);
+    } catch (e: unknown) {
+      setStatus("error");
+      setErrorMsg(String(e));
+    }
+  }
+
+  async function handleSelectBasePack() {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "SkillPack", extensions: ["skillpack"] }],
+    });
+    if (!selected || Array.isArray(selected)) return;
+    try {
+      const manifest = await invoke<{ id: string; version: string }>("preview_skillpack", { packPath: selected });
+      setBasePack({ path: selected, id: manifest.id, version: manifest.version });
+    } catch (e: unknown) {
+      setStatus("error");
+      setErrorMsg(String(e));
+    }
+  }
+
+  function clearBasePack() {
+    setBasePack(null);
+    setDeltaOnly(false);
+  }
+
   async function handlePack() {
     if (packInFlightRef.current || status === "packing") return;
     if (!username.trim()) {
@@ -39,8 +92,9 @@ export function PackForm({ dirPath, frontMatter, fileCount }: PackFormProps) {
     }
 
     packInFlightRef.current = true;
+    const packDelta = deltaOnly && basePack !== null;
     const outputPath = await save({
-      defaultPath: `${name.trim().replace(/\s+/g, "-")}.skillpack`,
+      defaultPath: `${name.trim().replace(/\s+/g, "-")}${packDelta ? ".delta" : ""}.skillpack`,
       filters: [{ name: "SkillPack", extensions: ["skillpack"] }],
     });
     if (!outputPath) {
@@ -50,17 +104,28 @@ export function PackForm({ dirPath, frontMatter, fileCount }: PackFormProps) {
 
     setStatus("packing");
     setErrorMsg("");
+    setDeltaSummary(null);
+    const packArgs = {
+      dirPath,
+      name,
+      description,
+      version,
+      author,
+      username,
+      recommendedModel,
+      outputPath,
+      signingKey: signingKey.trim() || null,
+    };
     try {
-      await invoke("pack_skill", {
-        dirPath,
-        name,
-        description,
-        version,
-        author,
-        username,
-        recommendedModel,
-        outputPath,
-      });
+      if (packDelta && basePack) {
+        setDeltaSummary(
+          await invoke<DeltaPackSummary>("pack_skill_delta", {
+            request: { ...packArgs, basePackPath: basePack.path },
+          }),
+        );
+      } else {
+        await invoke("pack_skill", { request: { ...packArgs, skillId: basePack?.id ?? null } });
+      }
       setStatus("done");
     } catch (e: unknown) {
       setStatus("error");
@@ -103,6 +168,74 @@ export function PackForm({ dirPath, frontMatter, fileCount }: PackFormProps) {
           使用方需输入该用户名才能安装此技能包，请妥善保管。
         </p>
       </div>
+      <div>
+        <label className={labelCls}>发布者签名密钥</label>
+        <div className="flex gap-2">
+          <input
+            className={inputCls}
+            type="password"
+            value={signingKey}
+            onChange={(e) => {
+              setSigningKey(e.target.value);
+              setGeneratedKey(null);
+            }}
+            placeholder="留空则不签名"
+          />
+          <button
+            onClick={() => void handleGenerateKey()}
+            className="shrink-0 h-9 px-3 rounded-md bg-blue-50 hover:bg-blue-100 text-blue-700 text-xs transition-colors"
+          >
+            生成密钥
+          </button>
+        </div>
+        {generatedKey ? (
+          <div className="text-xs text-gray-500 mt-1.5 leading-relaxed space-y-0.5">
+            <div>请妥善保存签名密钥，后续版本需使用同一密钥签名。</div>
+            <div>
+              公钥（发给安装方加入受信任发布者）：
+              <span className="font-mono break-all text-gray-700">{generatedKey.public_key}</span>
+            </div>
+          </div>
+        ) : (
+          <p className="text-xs text-gray-500 mt-1.5 leading-relaxed">
+            签名后安装方可确认技能包来源，且能发现发布后被篡改的技能包。
+          </p>
+        )}
+      </div>
+
+      <div>
+        <label className={labelCls}>上一版本技能包</label>
+        {basePack ? (
+          <div className="flex items-center justify-between gap-2 bg-gray-50 border border-gray-200 rounded-md px-3 py-2">
+            <div className="min-w-0 text-xs text-gray-600">
+              <div className="truncate">{basePack.path.split(/[\\/]/).pop()}</div>
+              <div className="text-gray-400">版本 {basePack.version}</div>
+            </div>
+            <button
+              onClick={clearBasePack}
+              className="shrink-0 h-7 px-3 rounded bg-gray-100 hover:bg-gray-200 text-gray-600 text-xs transition-colors"
+            >
+              清除
+            </button>
+          </div>
+        ) : (
+          <button
+            onClick={() => void handleSelectBasePack()}
+            className="h-9 px-3 rounded-md bg-gray-100 hover:bg-gray-200 text-gray-700 text-xs transition-colors"
+          >
+            选择上一版本
+          </button>
+        )}
+        <p className="text-xs text-gray-500 mt-1.5 leading-relaxed">
+          发布新版本时选择上一版本的技能包，安装方即可原地升级；首次发布留空。
+        </p>
+        {basePack && (
+          <label className="flex items-center gap-2 mt-2 text-xs text-gray-600">
+            <input type="checkbox" checked={deltaOnly} onChange={(e) => setDeltaOnly(e.target.checked)} />
+            仅导出变更文件（增量包）
+          </label>
+        )}
+      </div>
 
       {status === "error" && errorMsg && (
         <div className="text-red-600 text-sm bg-red-50 border border-red-200 rounded-md p-3">{errorMsg}</div>
@@ -115,6 +248,11 @@ export function PackForm({ dirPath, frontMatter, fileCount }: PackFormProps) {
             <div>技能：{name}</div>
             <div>版本：{version}</div>
             <div>文件数：{fileCount}</div>
+            {deltaSummary && (
+              <div>
+                增量：变更 {deltaSummary.changed_files.length} 个，删除 {deltaSummary.removed_files.length} 个
+              </div>
+            )}
           </div>
         </div>
       )}
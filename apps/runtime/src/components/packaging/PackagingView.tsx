@@ -5,9 +5,10 @@ import { SkillDirInfo } from "../../types";
 import { FileTree } from "./FileTree";
 import { PackForm } from "./PackForm";
 import { IndustryPackView } from "./IndustryPackView";
+import { TrustedPublishersView } from "./TrustedPublishersView";
 
 export function PackagingView() {
-  const [mode, setMode] = useState<"single" | "industry">("single");
+  const [mode, setMode] = useState<"single" | "industry" | "trust">("single");
   const [dirPath, setDirPath] = useState<string | null>(null);
   const [skillInfo, setSkillInfo] = useState<SkillDirInfo | null>(null);
   const [error, setError] = useState("");
@@ -65,6 +66,16 @@ export function PackagingView() {
         >
           行业包打包
         </button>
+        <button
+          onClick={() => setMode("trust")}
+          className={`h-7 px-3 rounded text-xs transition-colors ${
+            mode === "trust"
+              ? "bg-blue-500 text-white"
+              : "bg-gray-100 text-gray-600 hover:bg-gray-200"
+          }`}
+        >
+          受信任发布者
+        </button>
       </div>
 
       {mode === "single" && dirPath && (
@@ -115,6 +126,14 @@ export function PackagingView() {
           </div>
         </div>
       )}
+
+      {mode === "trust" && (
+        <div className="flex-1 overflow-y-auto">
+          <div className="p-5 max-w-xl">
+            <TrustedPublishersView />
+          </div>
+        </div>
+      )}
     </div>
   );
 }
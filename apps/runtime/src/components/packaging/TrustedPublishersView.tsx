@@ -0,0 +1,119 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface TrustedPublisher {
+  key_id: string;
+  name: string;
+  public_key: string;
+  added_at: string;
+}
+
+export function TrustedPublishersView() {
+  const [publishers, setPublishers] = useState<TrustedPublisher[]>([]);
+  const [name, setName] = useState("");
+  const [publicKey, setPublicKey] = useState("");
+  const [error, setError] = useState("");
+  const [saving, setSaving] = useState(false);
+
+  async function loadPublishers() {
+    try {
+      setPublishers(await invoke<TrustedPublisher[]>("list_trusted_publishers"));
+    } catch (e: unknown) {
+      setError(String(e));
+    }
+  }
+
+  useEffect(() => {
+    void loadPublishers();
+  }, []);
+
+  async function handleAdd() {
+    if (saving) return;
+    if (!name.trim() || !publicKey.trim()) {
+      setError("请填写发布者名称和公钥");
+      return;
+    }
+    setSaving(true);
+    setError("");
+    try {
+      await invoke<TrustedPublisher>("add_trusted_publisher", { name, publicKey });
+      setName("");
+      setPublicKey("");
+      await loadPublishers();
+    } catch (e: unknown) {
+      setError(String(e));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function handleRemove(keyId: string) {
+    setError("");
+    try {
+      await invoke<boolean>("remove_trusted_publisher", { keyId });
+      await loadPublishers();
+    } catch (e: unknown) {
+      setError(String(e));
+    }
+  }
+
+  const inputCls =
+    "w-full bg-gray-50 border border-gray-200 rounded-md px-3 py-2 text-sm text-gray-900 focus:outline-none focus:border-blue-500 focus:ring-1 focus:ring-blue-500/30 transition-colors";
+  const labelCls = "block text-xs font-medium text-gray-500 mb-1.5";
+
+  return (
+    <div className="space-y-4">
+      <p className="text-xs text-gray-500 leading-relaxed">
+        由这些发布者签名的技能包可直接安装；未签名或发布者不在列表中的技能包，安装前需要再次确认。
+      </p>
+
+      <div className="space-y-3">
+        <div>
+          <label className={labelCls}>发布者名称</label>
+          <input className={inputCls} value={name} onChange={(e) => setName(e.target.value)} placeholder="例如：Acme 技能团队" />
+        </div>
+        <div>
+          <label className={labelCls}>发布者公钥</label>
+          <input
+            className={inputCls}
+            value={publicKey}
+            onChange={(e) => setPublicKey(e.target.value)}
+            placeholder="发布者提供的 Base64 公钥"
+          />
+        </div>
+        <button
+          onClick={() => void handleAdd()}
+          disabled={saving}
+          className="bg-blue-500 hover:bg-blue-600 disabled:bg-gray-200 disabled:text-gray-400 text-white text-sm px-4 py-1.5 rounded-md transition-colors"
+        >
+          {saving ? "保存中..." : "添加发布者"}
+        </button>
+      </div>
+
+      {error && (
+        <div className="text-red-600 text-sm bg-red-50 border border-red-200 rounded-md p-3">{error}</div>
+      )}
+
+      {publishers.length === 0 ? (
+        <div className="text-xs text-gray-400">暂无受信任发布者</div>
+      ) : (
+        <div className="border border-gray-200 rounded-md divide-y divide-gray-100">
+          {publishers.map((publisher) => (
+            <div key={publisher.key_id} className="flex items-center justify-between px-3 py-2">
+              <div className="min-w-0">
+                <div className="text-sm text-gray-800 font-medium truncate">{publisher.name}</div>
+                <div className="text-[11px] text-gray-400 font-mono truncate">{publisher.key_id}</div>
+              </div>
+              <button
+                onClick={() => void handleRemove(publisher.key_id)}
+                className="h-7 px-3 rounded bg-gray-100 hover:bg-red-50 hover:text-red-600 text-gray-600 text-xs transition-colors"
+              >
+                移除
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -3,6 +3,7 @@ import { PackForm } from "../PackForm";
 
 const invokeMock = vi.fn();
 const saveMock = vi.fn();
+const openMock = vi.fn();
 
 vi.mock("@tauri-apps/api/core", () => ({
   invoke: (...args: unknown[]) => invokeMock(...args),
@@ -10,12 +11,14 @@ vi.mock("@tauri-apps/api/core", () => ({
 
 vi.mock("@tauri-apps/plugin-dialog", () => ({
   save: (...args: unknown[]) => saveMock(...args),
+  open: (...args: unknown[]) => openMock(...args),
 }));
 
 describe("PackForm risk flow", () => {
   beforeEach(() => {
     invokeMock.mockReset();
     saveMock.mockReset();
+    openMock.mockReset();
   });
 
   test("export action locks button while packing and shows completion message", async () => {
@@ -60,4 +63,42 @@ describe("PackForm risk flow", () => {
       expect(screen.getByText("打包成功")).toBeInTheDocument();
     });
   });
+
+  test("chains the new version to the selected base pack", async () => {
+    openMock.mockResolvedValue("C:\\packs\\contract-helper-1.0.0.skillpack");
+    saveMock.mockResolvedValue("C:\\packs\\contract-helper.skillpack");
+    invokeMock.mockImplementation((command: string) => {
+      if (command === "preview_skillpack") {
+        return Promise.resolve({ id: "skill-contract", version: "1.0.0" });
+      }
+      return Promise.resolve(null);
+    });
+
+    render(
+      <PackForm
+        dirPath="C:\\skills\\contract-helper"
+        frontMatter={{ name: "合同助手", description: "desc", version: "1.1.0", model: "gpt-4o-mini" }}
+        fileCount={3}
+      />
+    );
+
+    fireEvent.change(screen.getByPlaceholderText("例如：alice"), {
+      target: { value: "alice" },
+    });
+    fireEvent.click(screen.getByRole("button", { name: "选择上一版本" }));
+    await waitFor(() => {
+      expect(screen.getByText("版本 1.0.0")).toBeInTheDocument();
+    });
+
+    fireEvent.click(screen.getByRole("button", { name: "导出技能包" }));
+
+    await waitFor(() => {
+      expect(invokeMock).toHaveBeenCalledWith(
+        "pack_skill",
+        expect.objectContaining({
+          request: expect.objectContaining({ skillId: "skill-contract", version: "1.1.0" }),
+        }),
+      );
+    });
+  });
 });
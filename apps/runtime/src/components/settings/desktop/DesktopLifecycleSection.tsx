@@ -2,6 +2,7 @@ import type {
   DesktopDiagnosticsStatus,
   DesktopLifecyclePaths,
 } from "./desktopSettingsService";
+import { MCP_STATUS_LABELS } from "../mcp/mcpSettingsService";
 
 interface DesktopLifecycleSectionProps {
   desktopDiagnosticsStatus: DesktopDiagnosticsStatus | null;
@@ -155,6 +156,16 @@ export function DesktopLifecycleSection({
                   最近崩溃：{desktopDiagnosticsStatus.latest_crash.timestamp} {desktopDiagnosticsStatus.latest_crash.message}
                 </div>
               )}
+              {desktopDiagnosticsStatus.mcp_servers?.map((server) => (
+                <div
+                  key={server.server_id}
+                  className={`text-xs break-all ${server.status === "connected" ? "text-blue-700" : "text-amber-700"}`}
+                >
+                  MCP {server.server_name}：{MCP_STATUS_LABELS[server.status]}，{server.tool_count} 个工具
+                  {server.consecutive_failures > 0 ? `，连续失败 ${server.consecutive_failures} 次` : ""}
+                  {server.last_error ? `，${server.last_error}` : ""}
+                </div>
+              ))}
             </div>
           )}
         </div>
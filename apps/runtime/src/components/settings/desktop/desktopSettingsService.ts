@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { isTauriRuntimeAvailable } from "../../../lib/tauriRuntime";
 import type { RuntimePreferences } from "../../../types";
+import type { McpServerHealth } from "../mcp/mcpSettingsService";
 
 export interface DesktopLifecyclePaths {
   runtime_root_dir: string;
@@ -28,6 +29,7 @@ export interface DesktopDiagnosticsStatus {
     message: string;
     run_id?: string | null;
   } | null;
+  mcp_servers?: McpServerHealth[];
 }
 
 export interface RuntimeLanguagePreferencesInput {
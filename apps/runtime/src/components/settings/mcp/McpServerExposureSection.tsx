@@ -0,0 +1,111 @@
+import { useEffect, useState } from "react";
+import {
+  getMcpServerExposureSettings,
+  regenerateMcpServerToken,
+  saveMcpServerExposureSettings,
+  type McpServerExposureSettings,
+} from "./mcpSettingsService";
+
+export function McpServerExposureSection() {
+  const [settings, setSettings] = useState<McpServerExposureSettings | null>(null);
+  const [portInput, setPortInput] = useState("");
+  const [error, setError] = useState("");
+  const [saving, setSaving] = useState(false);
+
+  function applySettings(next: McpServerExposureSettings) {
+    setSettings(next);
+    setPortInput(String(next.port));
+  }
+
+  useEffect(() => {
+    let cancelled = false;
+    getMcpServerExposureSettings()
+      .then((next) => {
+        if (!cancelled) applySettings(next);
+      })
+      .catch((cause) => console.error("加载 MCP 服务端设置失败:", cause));
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  async function handleSave(enabled: boolean) {
+    setError("");
+    setSaving(true);
+    try {
+      const port = Number.parseInt(portInput, 10);
+      applySettings(await saveMcpServerExposureSettings(enabled, Number.isFinite(port) ? port : undefined));
+    } catch (cause) {
+      setError(String(cause));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function handleRegenerateToken() {
+    setError("");
+    try {
+      applySettings(await regenerateMcpServerToken());
+    } catch (cause) {
+      setError(String(cause));
+    }
+  }
+
+  if (!settings) return null;
+
+  const inputCls = "sm-input w-full text-sm py-1.5";
+  const labelCls = "sm-field-label";
+
+  return (
+    <div className="bg-white rounded-lg p-4 space-y-3 mt-3">
+      <div className="flex items-center justify-between">
+        <div className="text-xs font-medium text-gray-500">将 WorkClaw 作为 MCP 服务端</div>
+        <label className="flex items-center gap-2 text-xs text-gray-600">
+          <input type="checkbox" checked={settings.enabled} disabled={saving} onChange={(event) => void handleSave(event.target.checked)} />
+          启用
+        </label>
+      </div>
+      <div className="text-[11px] text-gray-400">
+        外部客户端可以列出技能与员工、搜索会话并发起运行；运行中的工具审批仍在 WorkClaw 桌面端确认。仅监听本机 127.0.0.1。
+      </div>
+      <div>
+        <label className={labelCls}>端口</label>
+        <div className="flex gap-2">
+          <input className={inputCls} value={portInput} onChange={(event) => setPortInput(event.target.value)} />
+          <button
+            onClick={() => void handleSave(settings.enabled)}
+            disabled={saving}
+            className="shrink-0 text-xs px-3 rounded bg-gray-100 hover:bg-gray-200 text-gray-700"
+          >
+            保存
+          </button>
+        </div>
+      </div>
+      <div className={`text-[11px] ${settings.running ? "text-green-600" : "text-gray-500"}`}>
+        {settings.running ? `运行中 · ${settings.url}` : "未运行"}
+        {settings.last_error ? ` · ${settings.last_error}` : ""}
+      </div>
+      <div>
+        <label className={labelCls}>访问令牌（HTTP 请求头 Authorization: Bearer &lt;令牌&gt;）</label>
+        <div className="flex gap-2">
+          <input className={inputCls} readOnly type="password" value={settings.token} />
+          <button
+            onClick={() => void handleRegenerateToken()}
+            className="shrink-0 text-xs px-3 rounded bg-gray-100 hover:bg-gray-200 text-gray-700"
+          >
+            重新生成
+          </button>
+        </div>
+      </div>
+      <div>
+        <label className={labelCls}>stdio 客户端配置</label>
+        <textarea
+          className={`${inputCls} font-mono text-[11px] h-32`}
+          readOnly
+          value={JSON.stringify(settings.client_config, null, 2)}
+        />
+      </div>
+      {error && <div className="bg-red-50 text-red-600 text-xs px-2 py-1 rounded">{error}</div>}
+    </div>
+  );
+}
@@ -1,7 +1,19 @@
 import { useEffect, useState } from "react";
-import { MCP_PRESETS, addMcpServer, listMcpServers, parseMcpEnvJson, removeMcpServer, type McpFormState, type McpServerRecord } from "./mcpSettingsService";
+import {
+  MCP_PRESETS,
+  MCP_STATUS_LABELS,
+  MCP_TRANSPORT_OPTIONS,
+  addMcpServer,
+  listMcpServers,
+  parseMcpEnvJson,
+  parseMcpHeadersJson,
+  removeMcpServer,
+  type McpFormState,
+  type McpServerRecord,
+  type McpTransport,
+} from "./mcpSettingsService";
 
-const EMPTY_MCP_FORM: McpFormState = { name: "", command: "", args: "", env: "" };
+const EMPTY_MCP_FORM: McpFormState = { name: "", transport: "stdio", command: "", args: "", env: "", url: "", headers: "" };
 
 export function McpSettingsSection() {
   const [mcpServers, setMcpServers] = useState<McpServerRecord[]>([]);
@@ -43,6 +55,7 @@ export function McpSettingsSection() {
     if (!preset || !preset.value) return;
     setShowMcpEnvJson(false);
     setMcpForm({
+      ...EMPTY_MCP_FORM,
       name: preset.name,
       command: preset.command,
       args: preset.args,
@@ -74,6 +87,9 @@ export function McpSettingsSection() {
   }
 
   const parsedMcpEnv = parseMcpEnvJson(mcpForm.env);
+  const parsedMcpHeaders = parseMcpHeadersJson(mcpForm.headers);
+  const isRemoteTransport = mcpForm.transport !== "stdio";
+  const canAddMcp = Boolean(mcpForm.name) && (isRemoteTransport ? Boolean(mcpForm.url.trim()) : Boolean(mcpForm.command));
   const mcpApiKeyEnvKeys = Object.keys(parsedMcpEnv.env).filter((key) => key.toUpperCase().includes("API_KEY"));
   const inputCls = "sm-input w-full text-sm py-1.5";
   const labelCls = "sm-field-label";
@@ -86,9 +102,17 @@ export function McpSettingsSection() {
         <div className="space-y-2 mb-3">
           {mcpServers.map((server) => (
             <div key={server.id} className="flex items-center justify-between bg-gray-100 rounded px-3 py-2 text-sm">
-              <div>
+              <div className="min-w-0">
                 <span className="font-medium">{server.name}</span>
-                <span className="text-gray-500 ml-2 text-xs">{server.command} {server.args?.join(" ")}</span>
+                <span className="text-gray-500 ml-2 text-xs">
+                  {server.transport && server.transport !== "stdio" ? server.url : `${server.command} ${server.args?.join(" ") ?? ""}`}
+                </span>
+                {server.health && (
+                  <div className={`text-[11px] mt-0.5 ${server.health.status === "connected" ? "text-green-600" : "text-amber-600"}`}>
+                    {MCP_STATUS_LABELS[server.health.status]} · {server.health.tool_count} 个工具
+                    {server.health.last_error ? ` · ${server.health.last_error}` : ""}
+                  </div>
+                )}
               </div>
               <button onClick={() => void handleRemoveMcp(server.id)} className="text-red-400 hover:text-red-300 text-xs">
                 删除
@@ -113,51 +137,91 @@ export function McpSettingsSection() {
         <input className={inputCls} placeholder="例: filesystem" value={mcpForm.name} onChange={(event) => setMcpForm({ ...mcpForm, name: event.target.value })} />
       </div>
       <div>
-        <label className={labelCls}>命令</label>
-        <input className={inputCls} placeholder="例: npx" value={mcpForm.command} onChange={(event) => setMcpForm({ ...mcpForm, command: event.target.value })} />
-      </div>
-      <div>
-        <label className={labelCls}>参数（空格分隔）</label>
-        <input className={inputCls} placeholder="例: @anthropic/mcp-server-filesystem /tmp" value={mcpForm.args} onChange={(event) => setMcpForm({ ...mcpForm, args: event.target.value })} />
-      </div>
-      {mcpApiKeyEnvKeys.map((envKey) => (
-        <div key={envKey}>
-          <label className={labelCls}>API Key（可选）</label>
-          <input
-            className={inputCls}
-            type="password"
-            placeholder={`请输入 ${envKey}`}
-            value={parsedMcpEnv.env[envKey] || ""}
-            onChange={(event) => updateMcpEnvField(envKey, event.target.value)}
-          />
-          <div className="text-[11px] text-gray-400 mt-1">变量名：{envKey}</div>
-        </div>
-      ))}
-      <div className="space-y-2">
-        <button
-          type="button"
-          onClick={() => setShowMcpEnvJson((value) => !value)}
-          className="text-xs text-blue-500 hover:text-blue-600"
+        <label className={labelCls}>连接方式</label>
+        <select
+          className={inputCls}
+          value={mcpForm.transport}
+          onChange={(event) => setMcpForm({ ...mcpForm, transport: event.target.value as McpTransport })}
         >
-          {showMcpEnvJson ? "收起高级 JSON 配置" : "高级：环境变量 JSON 配置"}
-        </button>
-        {showMcpEnvJson && (
+          {MCP_TRANSPORT_OPTIONS.map((option) => (
+            <option key={option.value} value={option.value}>
+              {option.label}
+            </option>
+          ))}
+        </select>
+      </div>
+      {isRemoteTransport ? (
+        <>
           <div>
-            <label className={labelCls}>环境变量（JSON 格式，可选）</label>
+            <label className={labelCls}>服务地址</label>
             <input
               className={inputCls}
-              placeholder='例: {"API_KEY": "xxx"}'
-              value={mcpForm.env}
-              onChange={(event) => setMcpForm({ ...mcpForm, env: event.target.value })}
+              placeholder={mcpForm.transport === "sse" ? "例: https://example.com/sse" : "例: https://example.com/mcp"}
+              value={mcpForm.url}
+              onChange={(event) => setMcpForm({ ...mcpForm, url: event.target.value })}
             />
-            {parsedMcpEnv.error && <div className="text-[11px] text-red-500 mt-1">{parsedMcpEnv.error}</div>}
           </div>
-        )}
-      </div>
+          <div>
+            <label className={labelCls}>请求头（JSON 格式，可选）</label>
+            <input
+              className={inputCls}
+              placeholder='例: {"Authorization": "Bearer xxx"}'
+              value={mcpForm.headers}
+              onChange={(event) => setMcpForm({ ...mcpForm, headers: event.target.value })}
+            />
+            {parsedMcpHeaders.error && <div className="text-[11px] text-red-500 mt-1">{parsedMcpHeaders.error}</div>}
+          </div>
+        </>
+      ) : (
+        <>
+          <div>
+            <label className={labelCls}>命令</label>
+            <input className={inputCls} placeholder="例: npx" value={mcpForm.command} onChange={(event) => setMcpForm({ ...mcpForm, command: event.target.value })} />
+          </div>
+          <div>
+            <label className={labelCls}>参数（空格分隔）</label>
+            <input className={inputCls} placeholder="例: @anthropic/mcp-server-filesystem /tmp" value={mcpForm.args} onChange={(event) => setMcpForm({ ...mcpForm, args: event.target.value })} />
+          </div>
+          {mcpApiKeyEnvKeys.map((envKey) => (
+            <div key={envKey}>
+              <label className={labelCls}>API Key（可选）</label>
+              <input
+                className={inputCls}
+                type="password"
+                placeholder={`请输入 ${envKey}`}
+                value={parsedMcpEnv.env[envKey] || ""}
+                onChange={(event) => updateMcpEnvField(envKey, event.target.value)}
+              />
+              <div className="text-[11px] text-gray-400 mt-1">变量名：{envKey}</div>
+            </div>
+          ))}
+          <div className="space-y-2">
+            <button
+              type="button"
+              onClick={() => setShowMcpEnvJson((value) => !value)}
+              className="text-xs text-blue-500 hover:text-blue-600"
+            >
+              {showMcpEnvJson ? "收起高级 JSON 配置" : "高级：环境变量 JSON 配置"}
+            </button>
+            {showMcpEnvJson && (
+              <div>
+                <label className={labelCls}>环境变量（JSON 格式，可选）</label>
+                <input
+                  className={inputCls}
+                  placeholder='例: {"API_KEY": "xxx"}'
+                  value={mcpForm.env}
+                  onChange={(event) => setMcpForm({ ...mcpForm, env: event.target.value })}
+                />
+                {parsedMcpEnv.error && <div className="text-[11px] text-red-500 mt-1">{parsedMcpEnv.error}</div>}
+              </div>
+            )}
+          </div>
+        </>
+      )}
       {mcpError && <div className="bg-red-50 text-red-600 text-xs px-2 py-1 rounded">{mcpError}</div>}
       <button
         onClick={() => void handleAddMcp()}
-        disabled={!mcpForm.name || !mcpForm.command}
+        disabled={!canAddMcp}
         className="w-full bg-blue-500 hover:bg-blue-600 disabled:bg-gray-200 disabled:text-gray-400 text-white text-sm py-1.5 rounded-lg transition-all active:scale-[0.97]"
       >
         添加 MCP 服务器
@@ -1,19 +1,55 @@
 import { invoke } from "@tauri-apps/api/core";
 
+export type McpTransport = "stdio" | "streamable_http" | "sse";
+
+export type McpServerStatus = "connecting" | "connected" | "disconnected" | "backing_off";
+
+export interface McpServerHealth {
+  server_id: string;
+  server_name: string;
+  transport: McpTransport;
+  status: McpServerStatus;
+  consecutive_failures: number;
+  last_error?: string | null;
+  connected_at?: string | null;
+  last_success_at?: string | null;
+  next_retry_at?: string | null;
+  tool_count: number;
+}
+
 export interface McpServerRecord {
   id: string;
   name: string;
   command: string;
   args?: string[];
+  transport?: McpTransport;
+  url?: string;
+  health?: McpServerHealth | null;
 }
 
 export interface McpFormState {
   name: string;
+  transport: McpTransport;
   command: string;
   args: string;
   env: string;
+  url: string;
+  headers: string;
 }
 
+export const MCP_TRANSPORT_OPTIONS: { value: McpTransport; label: string }[] = [
+  { value: "stdio", label: "本地进程（stdio）" },
+  { value: "streamable_http", label: "远程 HTTP（Streamable HTTP）" },
+  { value: "sse", label: "远程 SSE" },
+];
+
+export const MCP_STATUS_LABELS: Record<McpServerStatus, string> = {
+  connecting: "连接中",
+  connected: "已连接",
+  disconnected: "已断开",
+  backing_off: "等待重连",
+};
+
 export const MCP_PRESETS = [
   { label: "— 快速选择 —", value: "", name: "", command: "", args: "", env: "" },
   { label: "Filesystem", value: "filesystem", name: "filesystem", command: "npx", args: "-y @anthropic/mcp-server-filesystem /tmp", env: "" },
@@ -23,30 +59,57 @@ export const MCP_PRESETS = [
   { label: "Fetch", value: "fetch", name: "fetch", command: "npx", args: "-y @anthropic/mcp-server-fetch", env: "" },
 ] as const;
 
-export function parseMcpEnvJson(text: string): { env: Record<string, string>; error: string | null } {
+function parseStringMapJson(text: string, label: string): { map: Record<string, string>; error: string | null } {
   if (!text.trim()) {
-    return { env: {}, error: null };
+    return { map: {}, error: null };
   }
   try {
     const parsed = JSON.parse(text) as unknown;
     if (!parsed || typeof parsed !== "object" || Array.isArray(parsed)) {
-      return { env: {}, error: "环境变量 JSON 必须是对象格式" };
+      return { map: {}, error: `${label} JSON 必须是对象格式` };
     }
     const normalized: Record<string, string> = {};
     for (const [key, value] of Object.entries(parsed as Record<string, unknown>)) {
       normalized[key] = typeof value === "string" ? value : String(value ?? "");
     }
-    return { env: normalized, error: null };
+    return { map: normalized, error: null };
   } catch {
-    return { env: {}, error: "环境变量 JSON 格式错误" };
+    return { map: {}, error: `${label} JSON 格式错误` };
   }
 }
 
+export function parseMcpEnvJson(text: string): { env: Record<string, string>; error: string | null } {
+  const { map, error } = parseStringMapJson(text, "环境变量");
+  return { env: map, error };
+}
+
+export function parseMcpHeadersJson(text: string): { headers: Record<string, string>; error: string | null } {
+  const { map, error } = parseStringMapJson(text, "请求头");
+  return { headers: map, error };
+}
+
 export async function listMcpServers() {
   return invoke<McpServerRecord[]>("list_mcp_servers");
 }
 
 export async function addMcpServer(form: McpFormState) {
+  if (form.transport !== "stdio") {
+    const parsedHeaders = parseMcpHeadersJson(form.headers);
+    if (parsedHeaders.error) {
+      throw new Error(parsedHeaders.error);
+    }
+    await invoke("add_mcp_server", {
+      name: form.name,
+      command: "",
+      args: [],
+      env: {},
+      transport: form.transport,
+      url: form.url.trim(),
+      headers: parsedHeaders.headers,
+    });
+    return;
+  }
+
   const args = form.args.split(/\s+/).filter(Boolean);
   const parsedEnv = parseMcpEnvJson(form.env);
   if (parsedEnv.error) {
@@ -64,3 +127,27 @@ export async function addMcpServer(form: McpFormState) {
 export async function removeMcpServer(id: string) {
   await invoke("remove_mcp_server", { id });
 }
+
+export interface McpServerExposureSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+  running: boolean;
+  url: string;
+  last_error?: string | null;
+  client_config: Record<string, unknown>;
+}
+
+export async function getMcpServerExposureSettings() {
+  return invoke<McpServerExposureSettings>("get_mcp_server_settings");
+}
+
+export async function saveMcpServerExposureSettings(enabled: boolean, port?: number) {
+  return invoke<McpServerExposureSettings>("save_mcp_server_settings", {
+    input: { enabled, port },
+  });
+}
+
+export async function regenerateMcpServerToken() {
+  return invoke<McpServerExposureSettings>("regenerate_mcp_server_token");
+}
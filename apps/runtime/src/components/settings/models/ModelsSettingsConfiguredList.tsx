@@ -1,3 +1,4 @@
+import { MODEL_PROVIDER_API_FORMAT_LABELS, normalizeModelProviderApiFormat } from "../../../model-provider-catalog";
 import type { ModelConfig } from "../../../types";
 
 type ModelsSettingsConfiguredListProps = {
@@ -39,7 +40,7 @@ export function ModelsSettingsConfiguredList({
               )}
             </div>
             <div className="text-xs text-gray-400 mt-0.5 truncate">
-              {model.model_name} · {model.api_format === "anthropic" ? "Anthropic" : "OpenAI 兼容"} · {model.base_url}
+              {model.model_name} · {MODEL_PROVIDER_API_FORMAT_LABELS[normalizeModelProviderApiFormat(model.api_format)]} · {model.base_url}
             </div>
           </div>
           <div className="flex items-center gap-2 flex-shrink-0 ml-3">
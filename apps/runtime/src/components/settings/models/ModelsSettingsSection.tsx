@@ -1,6 +1,12 @@
 import { useEffect, useState } from "react";
 import { Eye, EyeOff } from "lucide-react";
-import { DEFAULT_MODEL_PROVIDER_ID, MODEL_PROVIDER_CATALOG, getModelProviderCatalogItem } from "../../../model-provider-catalog";
+import {
+  DEFAULT_MODEL_PROVIDER_ID,
+  MODEL_PROVIDER_API_FORMAT_LABELS,
+  MODEL_PROVIDER_CATALOG,
+  getModelProviderCatalogItem,
+  normalizeModelProviderApiFormat,
+} from "../../../model-provider-catalog";
 import { getModelErrorDisplay } from "../../../lib/model-error-display";
 import { openExternalUrl } from "../../../utils/openExternalUrl";
 import type { ModelConfig, ProviderConfig } from "../../../types";
@@ -122,7 +128,7 @@ export function ModelsSettingsSection({
         const provider = resolveModelProviderForEdit(model, providers);
         setForm({
           name: model.name,
-          api_format: model.api_format === "anthropic" ? "anthropic" : "openai",
+          api_format: normalizeModelProviderApiFormat(model.api_format),
           base_url: model.base_url,
           model_name: model.model_name,
           api_key: apiKey,
@@ -294,8 +300,11 @@ export function ModelsSettingsSection({
         <div>
           <label className="block text-sm font-medium text-gray-700 mb-1">API 格式</label>
           <select className="w-full rounded-lg border border-gray-200 px-3 py-2 text-sm outline-none" value={form.api_format} disabled>
-            <option value="openai">OpenAI 兼容</option>
-            <option value="anthropic">Anthropic (Claude)</option>
+            {Object.entries(MODEL_PROVIDER_API_FORMAT_LABELS).map(([value, label]) => (
+              <option key={value} value={value}>
+                {label}
+              </option>
+            ))}
           </select>
         </div>
         <div>
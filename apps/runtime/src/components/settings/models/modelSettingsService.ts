@@ -4,7 +4,9 @@ import {
   DEFAULT_MODEL_PROVIDER_ID,
   buildModelFormFromCatalogItem,
   getModelProviderCatalogItem,
+  normalizeModelProviderApiFormat,
   resolveCatalogItemForProviderIdentity,
+  type ModelProviderApiFormat,
 } from "../../../model-provider-catalog";
 import type {
   ModelConfig,
@@ -14,7 +16,7 @@ import type {
 
 export interface ModelFormState {
   name: string;
-  api_format: "openai" | "anthropic";
+  api_format: ModelProviderApiFormat;
   base_url: string;
   model_name: string;
   api_key: string;
@@ -91,7 +93,8 @@ export function validateModelForm(form: ModelFormState): string | null {
   if (!form.model_name.trim()) {
     return "请输入模型名称";
   }
-  if (!form.api_key.trim()) {
+  // 本地 Ollama 服务不需要 API Key
+  if (!form.api_key.trim() && form.api_format !== "ollama") {
     return "请输入 API Key";
   }
   return null;
@@ -104,10 +107,12 @@ export function inferConnectionKey(baseUrl: string, apiFormat: string): string {
   if (normalized.includes("moonshot") || normalized.includes("kimi")) return "moonshot";
   if (normalized.includes("bigmodel") || normalized.includes("open.bigmodel")) return "zhipu";
   if (normalized.includes("anthropic")) return "anthropic";
+  if (normalized.includes("generativelanguage.googleapis.com")) return "gemini";
+  if (normalized.includes("ollama") || normalized.includes(":11434")) return "ollama";
   if (normalized.includes("minimax")) return "minimax";
   if (normalized.includes("lingyiwanwu")) return "yi";
   if (normalized.includes("openai")) return "openai";
-  if (apiFormat === "anthropic") return "anthropic";
+  if (apiFormat === "anthropic" || apiFormat === "gemini" || apiFormat === "ollama") return apiFormat;
   return "openai";
 }
 
@@ -115,7 +120,7 @@ export function resolveModelProviderForEdit(
   model: ModelConfig,
   providers: ProviderConfig[],
 ) {
-  const apiFormat = model.api_format === "anthropic" ? "anthropic" : "openai";
+  const apiFormat = normalizeModelProviderApiFormat(model.api_format);
   const providerConfig = providers.find((item) => item.id === model.id);
   return resolveCatalogItemForProviderIdentity({
     providerKey: providerConfig?.provider_key,
@@ -134,7 +139,7 @@ export async function syncConnectionToRouting(
       id: model.id,
       provider_key: preferredProviderKey || inferConnectionKey(model.base_url, model.api_format),
       display_name: model.name || model.model_name || model.id,
-      protocol_type: model.api_format === "anthropic" ? "anthropic" : "openai",
+      protocol_type: normalizeModelProviderApiFormat(model.api_format),
       base_url: model.base_url,
       auth_type: "api_key",
       api_key_encrypted: apiKey,
@@ -29,6 +29,7 @@ export type AssistantReasoningInterruptedEvent = {
 
 export type ToolCallEvent = {
   session_id: string;
+  call_id?: string;
   tool_name: string;
   tool_input: Record<string, unknown>;
   tool_output: string | null;
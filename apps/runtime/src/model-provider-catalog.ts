@@ -1,4 +1,19 @@
-export type ModelProviderApiFormat = "openai" | "anthropic";
+export type ModelProviderApiFormat = "openai" | "anthropic" | "gemini" | "ollama";
+
+export const MODEL_PROVIDER_API_FORMAT_LABELS: Record<ModelProviderApiFormat, string> = {
+  openai: "OpenAI 兼容",
+  anthropic: "Anthropic (Claude)",
+  gemini: "Google Gemini",
+  ollama: "Ollama 本地模型",
+};
+
+export function normalizeModelProviderApiFormat(value: string | null | undefined): ModelProviderApiFormat {
+  const normalized = (value || "").trim().toLowerCase();
+  if (normalized === "anthropic" || normalized === "gemini" || normalized === "ollama") {
+    return normalized;
+  }
+  return "openai";
+}
 
 export interface ModelProviderCatalogItem {
   id: string;
@@ -146,6 +161,21 @@ export const MODEL_PROVIDER_CATALOG: ModelProviderCatalogItem[] = [
     officialConsoleUrl: "https://console.anthropic.com/settings/keys",
     officialDocsUrl: "https://docs.anthropic.com/en/api/getting-started",
   }),
+  createOfficialProvider({
+    id: "gemini",
+    label: "Google Gemini",
+    name: "Gemini",
+    providerKey: "gemini",
+    apiFormat: "gemini",
+    protocolLabel: "Google Gemini",
+    baseUrl: "https://generativelanguage.googleapis.com/v1beta",
+    defaultModel: "gemini-2.5-flash",
+    models: ["gemini-2.5-pro", "gemini-2.5-flash", "gemini-2.0-flash"],
+    badge: "多模态",
+    helper: "原生 generateContent 接入，支持函数调用与图片输入。",
+    officialConsoleUrl: "https://aistudio.google.com/app/apikey",
+    officialDocsUrl: "https://ai.google.dev/gemini-api/docs",
+  }),
   createOfficialProvider({
     id: "minimax-openai",
     label: "MiniMax (OpenAI 兼容)",
@@ -290,6 +320,27 @@ export const MODEL_PROVIDER_CATALOG: ModelProviderCatalogItem[] = [
       "通常应填写带 /v1 的接口根地址。",
     ],
   }),
+  createCustomProvider({
+    id: "ollama-local",
+    label: "本地模型 (Ollama)",
+    name: "本地 Ollama",
+    providerKey: "ollama",
+    apiFormat: "ollama",
+    protocolLabel: "Ollama 本地模型",
+    baseUrl: "http://localhost:11434",
+    baseUrlPlaceholder: "http://localhost:11434",
+    defaultModel: "qwen2.5:7b",
+    modelNamePlaceholder: "输入已拉取的模型名，例如 qwen2.5:7b",
+    models: ["qwen2.5:7b", "llama3.1:8b", "llava:7b"],
+    badge: "离线可用",
+    helper: "数据不出本机，适合处理敏感文档；无需 API Key。",
+    customGuidanceTitle: "本地模型 (Ollama)",
+    customGuidanceLines: [
+      "请先在本机安装并启动 Ollama。",
+      "模型列表会从本地服务自动发现，也可以在此拉取新模型。",
+      "Base URL 填写 Ollama 服务地址，默认 http://localhost:11434。",
+    ],
+  }),
   createCustomProvider({
     id: "custom-anthropic",
     label: "自定义 Claude (Anthropic)",
@@ -357,6 +408,10 @@ export function resolveCatalogItemForConfig(
     return getModelProviderCatalogItem("custom-anthropic");
   }
 
+  if (config.api_format === "ollama") {
+    return getModelProviderCatalogItem("ollama-local");
+  }
+
   return getModelProviderCatalogItem("custom-openai");
 }
 
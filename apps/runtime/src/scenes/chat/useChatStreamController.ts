@@ -360,7 +360,7 @@ export function useChatStreamController({
         items.push({
           type: "tool_call",
           toolCall: {
-            id: `${payload.tool_name}-${Date.now()}`,
+            id: payload.call_id ?? `${payload.tool_name}-${Date.now()}`,
             name: payload.tool_name,
             input: payload.tool_input,
             status: "running" as const,
@@ -370,8 +370,22 @@ export function useChatStreamController({
         setStreamItems([...items]);
         return;
       }
+      const matchesCall = (item: StreamItem) =>
+        item.type === "tool_call" &&
+        item.toolCall?.status === "running" &&
+        (payload.call_id ? item.toolCall.id === payload.call_id : item.toolCall?.name === payload.tool_name);
+      if (payload.status === "progress") {
+        const items = streamItemsRef.current.map((item) =>
+          matchesCall(item) && item.toolCall
+            ? { ...item, toolCall: { ...item.toolCall, output: payload.tool_output ?? item.toolCall.output } }
+            : item,
+        );
+        streamItemsRef.current = items;
+        setStreamItems([...items]);
+        return;
+      }
       const items = streamItemsRef.current.map((item) => {
-        if (item.type === "tool_call" && item.toolCall?.name === payload.tool_name && item.toolCall?.status === "running") {
+        if (matchesCall(item) && item.toolCall) {
           return {
             ...item,
             toolCall: {
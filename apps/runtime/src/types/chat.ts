@@ -107,6 +107,7 @@ export interface SessionRunProjection {
   task_continuation_mode?: string | null;
   task_continuation_source?: string | null;
   task_continuation_reason?: string | null;
+  structured_output?: unknown;
 }
 
 export interface SessionRunTaskIdentitySnapshot {
@@ -13,7 +13,7 @@ pub use preparation::{
 };
 pub use prompt_assembly::{
     build_system_prompt_sections, compose_system_prompt, compose_system_prompt_from_sections,
-    compose_system_prompt_from_tool_names, SystemPromptSections,
+    compose_system_prompt_from_tool_names, SystemPromptSections, PROMPT_CACHE_BOUNDARY,
 };
 pub use routing::{
     classify_model_route_error, parse_fallback_chain_targets, retry_backoff_ms,
@@ -1,5 +1,9 @@
 use crate::types::{ChatEmployeeSnapshot, ChatExecutionGuidance};
 
+/// 系统提示词中稳定前缀与易变后缀的分界标记。
+/// 模型 adapter 在此处放置提示词缓存断点，发送前会移除该标记。
+pub const PROMPT_CACHE_BOUNDARY: &str = "<!-- prompt-cache-boundary -->";
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SystemPromptSections {
     pub base_prompt: String,
@@ -142,6 +146,9 @@ pub fn compose_system_prompt_from_sections(sections: &SystemPromptSections) -> S
         prompt_sections.push(format!("---\n{}", collaboration));
     }
 
+    // 以下章节随记忆、日期、工具和联网状态变化，放在缓存分界之后，保证前缀逐轮一致
+    let stable_len = prompt_sections.len();
+
     if let Some(memory_content) = sections
         .memory_content
         .as_deref()
@@ -171,6 +178,9 @@ pub fn compose_system_prompt_from_sections(sections: &SystemPromptSections) -> S
         ));
     }
 
+    if prompt_sections.len() > stable_len {
+        prompt_sections.insert(stable_len, PROMPT_CACHE_BOUNDARY.to_string());
+    }
     prompt_sections.join("\n\n")
 }
 
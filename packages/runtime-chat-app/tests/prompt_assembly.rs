@@ -1,6 +1,6 @@
 use runtime_chat_app::{
     build_system_prompt_sections, compose_system_prompt, compose_system_prompt_from_sections,
-    compose_system_prompt_from_tool_names, ChatExecutionGuidance,
+    compose_system_prompt_from_tool_names, ChatExecutionGuidance, PROMPT_CACHE_BOUNDARY,
 };
 
 #[test]
@@ -224,3 +224,57 @@ fn compose_system_prompt_from_sections_matches_legacy_builder() {
 
     assert_eq!(prompt_from_sections, legacy_prompt);
 }
+
+#[test]
+fn compose_system_prompt_places_volatile_sections_after_cache_boundary() {
+    let guidance = ChatExecutionGuidance {
+        effective_work_dir: "E:/workspace/demo".to_string(),
+        local_timezone: "Asia/Shanghai".to_string(),
+        local_date: "2026-03-20".to_string(),
+        local_tomorrow: "2026-03-21".to_string(),
+        local_month_range: "2026-03-01 ~ 2026-03-31".to_string(),
+    };
+    let sections = build_system_prompt_sections(
+        "Base skill prompt",
+        "bash, read, browser",
+        "gpt-4.1",
+        8,
+        &guidance,
+        Some("<available_skills />"),
+        None,
+        Some("Remember previous delivery constraints."),
+        &["当前未配置搜索引擎".to_string()],
+    );
+
+    let prompt = compose_system_prompt_from_sections(&sections);
+    let (stable, volatile) = prompt
+        .split_once(PROMPT_CACHE_BOUNDARY)
+        .expect("cache boundary");
+
+    assert!(stable.contains("Base skill prompt"));
+    assert!(stable.contains("<available_skills />"));
+    assert!(!stable.contains("2026-03-20"));
+    assert!(volatile.contains("持久内存:\nRemember previous delivery constraints."));
+    assert!(volatile.contains("今天: 2026-03-20"));
+    assert!(volatile.contains("WorkClaw 内置本地 browser sidecar"));
+    assert!(volatile.contains("当前未配置搜索引擎"));
+
+    let stable_only = compose_system_prompt_from_sections(&build_system_prompt_sections(
+        "Base skill prompt",
+        "read",
+        "gpt-4.1",
+        8,
+        &ChatExecutionGuidance {
+            effective_work_dir: String::new(),
+            local_timezone: String::new(),
+            local_date: String::new(),
+            local_tomorrow: String::new(),
+            local_month_range: String::new(),
+        },
+        None,
+        None,
+        None,
+        &[],
+    ));
+    assert!(!stable_only.contains(PROMPT_CACHE_BOUNDARY));
+}
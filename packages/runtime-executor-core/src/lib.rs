@@ -2,6 +2,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashSet;
 
+mod tokenizer;
+
+pub use tokenizer::{
+    is_cjk_char, tokenizer_for_model, BpeEstimator, CalibratedEstimator, ContextBudget, Tokenizer,
+    AUTO_COMPACT_PERCENT, CHINESE_VOCAB_ESTIMATOR, CL100K_BPE, CLAUDE_ESTIMATOR, GEMINI_ESTIMATOR,
+    GENERIC_ESTIMATOR, LLAMA_ESTIMATOR, MESSAGE_OVERHEAD_TOKENS, MICRO_COMPACT_PERCENT, O200K_BPE,
+    TRIM_TARGET_PERCENT,
+};
+
 pub const MAX_TOOL_OUTPUT_CHARS: usize = 30_000;
 pub const REPEATED_TOOL_FAILURE_THRESHOLD: usize = 3;
 pub const TOOL_CALL_PARSE_ERROR_KEY: &str = "__tool_call_parse_error";
@@ -126,20 +135,13 @@ pub fn update_tool_failure_streak(
     }
 }
 
+/// Model-agnostic estimate; prefer `estimate_tokens_for_model` when the model is known.
 pub fn estimate_tokens(messages: &[Value]) -> usize {
-    let total_chars: usize = messages
-        .iter()
-        .map(|m| {
-            let text_len = m["content"].as_str().map_or(0, |s| s.len());
-            let array_len = m["content"].as_array().map_or(0, |arr| {
-                arr.iter()
-                    .map(|v| serde_json::to_string(v).map_or(0, |s| s.len()))
-                    .sum()
-            });
-            text_len + array_len
-        })
-        .sum();
-    total_chars / CHARS_PER_TOKEN
+    GENERIC_ESTIMATOR.count_messages(messages)
+}
+
+pub fn estimate_tokens_for_model(messages: &[Value], model: &str) -> usize {
+    tokenizer_for_model(model).count_messages(messages)
 }
 
 pub fn micro_compact(messages: &[Value], keep_recent: usize) -> Vec<Value> {
@@ -206,31 +208,34 @@ pub fn micro_compact(messages: &[Value], keep_recent: usize) -> Vec<Value> {
 }
 
 pub fn trim_messages(messages: &[Value], token_budget: usize) -> Vec<Value> {
-    if messages.len() <= 2 || estimate_tokens(messages) <= token_budget {
+    trim_messages_with(messages, token_budget, &GENERIC_ESTIMATOR)
+}
+
+/// Keeps the first and last message plus as much recent history as fits in
+/// `TRIM_TARGET_PERCENT` of `token_budget`, counted with `tokenizer`.
+pub fn trim_messages_with(
+    messages: &[Value],
+    token_budget: usize,
+    tokenizer: &dyn Tokenizer,
+) -> Vec<Value> {
+    if messages.len() <= 2 || tokenizer.count_messages(messages) <= token_budget {
         return messages.to_vec();
     }
 
     let first = &messages[0];
     let last = &messages[messages.len() - 1];
 
-    let budget_chars = token_budget * CHARS_PER_TOKEN * 70 / 100;
-    let first_chars = first["content"].as_str().map_or(0, |s| s.len());
-    let last_chars = last["content"].as_str().map_or(0, |s| s.len());
-    let mut char_count = first_chars + last_chars;
+    let target_tokens = token_budget * TRIM_TARGET_PERCENT / 100;
+    let mut token_count = tokenizer.count_message(first) + tokenizer.count_message(last);
 
     let mut keep_from_end: Vec<&Value> = Vec::new();
 
     for msg in messages[1..messages.len() - 1].iter().rev() {
-        let msg_chars = msg["content"].as_str().map_or(0, |s| s.len())
-            + msg["content"].as_array().map_or(0, |arr| {
-                arr.iter()
-                    .map(|v| serde_json::to_string(v).map_or(0, |s| s.len()))
-                    .sum()
-            });
-        if char_count + msg_chars > budget_chars {
+        let msg_tokens = tokenizer.count_message(msg);
+        if token_count + msg_tokens > target_tokens {
             break;
         }
-        char_count += msg_chars;
+        token_count += msg_tokens;
         keep_from_end.push(msg);
     }
     keep_from_end.reverse();
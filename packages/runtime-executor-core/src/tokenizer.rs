@@ -0,0 +1,301 @@
+use serde_json::Value;
+
+/// Fixed per-message framing cost (role markers, separators) added by chat templates.
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Counts tokens for a model family. Implementations only need `count_text`;
+/// message accounting walks the chat payload the same way for every family.
+pub trait Tokenizer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn count_text(&self, text: &str) -> usize;
+
+    fn count_message(&self, message: &Value) -> usize {
+        let content = &message["content"];
+        let content_tokens = match content {
+            Value::String(text) => self.count_text(text),
+            Value::Array(blocks) => blocks.iter().map(|block| self.count_block(block)).sum(),
+            _ => 0,
+        };
+        let tool_call_tokens = message["tool_calls"].as_array().map_or(0, |calls| {
+            calls
+                .iter()
+                .map(|call| self.count_text(&serde_json::to_string(call).unwrap_or_default()))
+                .sum()
+        });
+        MESSAGE_OVERHEAD_TOKENS + content_tokens + tool_call_tokens
+    }
+
+    fn count_block(&self, block: &Value) -> usize {
+        match block["type"].as_str() {
+            Some("text") => self.count_text(block["text"].as_str().unwrap_or_default()),
+            Some("thinking") => self.count_text(block["thinking"].as_str().unwrap_or_default()),
+            Some("tool_result") if block["content"].is_string() => {
+                self.count_text(block["content"].as_str().unwrap_or_default())
+                    + MESSAGE_OVERHEAD_TOKENS
+            }
+            _ => self.count_text(&serde_json::to_string(block).unwrap_or_default()),
+        }
+    }
+
+    fn count_messages(&self, messages: &[Value]) -> usize {
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum()
+    }
+}
+
+/// Han, kana, hangul and full-width forms: scripts that BPE vocabularies and
+/// SentencePiece models encode at roughly one token per character or worse.
+pub fn is_cjk_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3000..=0x303F
+            | 0x3040..=0x30FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xAC00..=0xD7AF
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFFEF
+            | 0x20000..=0x2A6DF
+    )
+}
+
+/// Per-script ratio estimator calibrated against a family's real tokenizer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibratedEstimator {
+    pub name: &'static str,
+    pub cjk_tokens_per_char: f64,
+    pub other_chars_per_token: f64,
+}
+
+impl Tokenizer for CalibratedEstimator {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn count_text(&self, text: &str) -> usize {
+        let (cjk, other) = text.chars().fold((0usize, 0usize), |(cjk, other), c| {
+            if is_cjk_char(c) {
+                (cjk + 1, other)
+            } else {
+                (cjk, other + 1)
+            }
+        });
+        let cjk_tokens = (cjk as f64 * self.cjk_tokens_per_char).ceil() as usize;
+        let other_tokens = (other as f64 / self.other_chars_per_token).ceil() as usize;
+        cjk_tokens + other_tokens
+    }
+}
+
+/// Estimator for tiktoken-style BPE encodings. It reproduces the encoding's
+/// pre-tokenizer splits (words with a leading space, 1-3 digit groups,
+/// punctuation runs, whitespace runs) and charges each piece what the merge
+/// table typically yields, so it tracks the real count without shipping a vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BpeEstimator {
+    pub name: &'static str,
+    pub cjk_tokens_per_char: f64,
+    /// ASCII words up to this length are usually a single merged token.
+    pub single_token_word_len: usize,
+    pub long_word_chars_per_token: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PieceKind {
+    AsciiWord,
+    Word,
+    Digits,
+    Cjk,
+    Whitespace,
+    Symbol,
+}
+
+fn piece_kind(c: char) -> PieceKind {
+    if is_cjk_char(c) {
+        PieceKind::Cjk
+    } else if c.is_ascii_alphabetic() {
+        PieceKind::AsciiWord
+    } else if c.is_alphabetic() {
+        PieceKind::Word
+    } else if c.is_numeric() {
+        PieceKind::Digits
+    } else if c.is_whitespace() {
+        PieceKind::Whitespace
+    } else {
+        PieceKind::Symbol
+    }
+}
+
+impl BpeEstimator {
+    fn piece_tokens(&self, kind: PieceKind, len: usize) -> usize {
+        match kind {
+            PieceKind::AsciiWord if len <= self.single_token_word_len => 1,
+            PieceKind::AsciiWord => len.div_ceil(self.long_word_chars_per_token),
+            PieceKind::Word => len.div_ceil(2),
+            PieceKind::Digits => len.div_ceil(3),
+            PieceKind::Cjk => (len as f64 * self.cjk_tokens_per_char).ceil() as usize,
+            PieceKind::Whitespace => 1,
+            PieceKind::Symbol => len.div_ceil(2),
+        }
+    }
+}
+
+impl Tokenizer for BpeEstimator {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn count_text(&self, text: &str) -> usize {
+        let chars: Vec<char> = text.chars().collect();
+        let mut tokens = 0;
+        let mut index = 0;
+        while index < chars.len() {
+            let kind = piece_kind(chars[index]);
+            let mut end = index + 1;
+            while end < chars.len() && piece_kind(chars[end]) == kind {
+                end += 1;
+            }
+            // A single space is merged into the word that follows it.
+            let absorbed_by_next = kind == PieceKind::Whitespace
+                && end - index == 1
+                && chars[index] == ' '
+                && chars.get(end).is_some_and(|next| {
+                    matches!(
+                        piece_kind(*next),
+                        PieceKind::AsciiWord | PieceKind::Word | PieceKind::Symbol
+                    )
+                });
+            if !absorbed_by_next {
+                tokens += self.piece_tokens(kind, end - index);
+            }
+            index = end;
+        }
+        tokens
+    }
+}
+
+/// `o200k_base`: GPT-4o, GPT-4.1, GPT-5 and the o-series.
+pub const O200K_BPE: BpeEstimator = BpeEstimator {
+    name: "o200k_base",
+    cjk_tokens_per_char: 0.9,
+    single_token_word_len: 7,
+    long_word_chars_per_token: 4,
+};
+
+/// `cl100k_base`: GPT-4, GPT-3.5 and the embedding models.
+pub const CL100K_BPE: BpeEstimator = BpeEstimator {
+    name: "cl100k_base",
+    cjk_tokens_per_char: 1.3,
+    single_token_word_len: 6,
+    long_word_chars_per_token: 4,
+};
+
+pub const CLAUDE_ESTIMATOR: CalibratedEstimator = CalibratedEstimator {
+    name: "claude",
+    cjk_tokens_per_char: 1.2,
+    other_chars_per_token: 3.5,
+};
+
+pub const GEMINI_ESTIMATOR: CalibratedEstimator = CalibratedEstimator {
+    name: "gemini",
+    cjk_tokens_per_char: 0.8,
+    other_chars_per_token: 4.0,
+};
+
+/// Qwen, DeepSeek, GLM, Kimi and Doubao vocabularies carry large Chinese merges.
+pub const CHINESE_VOCAB_ESTIMATOR: CalibratedEstimator = CalibratedEstimator {
+    name: "chinese_vocab",
+    cjk_tokens_per_char: 0.7,
+    other_chars_per_token: 4.0,
+};
+
+pub const LLAMA_ESTIMATOR: CalibratedEstimator = CalibratedEstimator {
+    name: "llama",
+    cjk_tokens_per_char: 1.5,
+    other_chars_per_token: 4.0,
+};
+
+/// Unknown models: one token per CJK character keeps the estimate on the safe side.
+pub const GENERIC_ESTIMATOR: CalibratedEstimator = CalibratedEstimator {
+    name: "generic",
+    cjk_tokens_per_char: 1.0,
+    other_chars_per_token: crate::CHARS_PER_TOKEN as f64,
+};
+
+/// Picks the tokenizer for a model name; gateway prefixes such as `openai/` are ignored.
+pub fn tokenizer_for_model(model: &str) -> &'static dyn Tokenizer {
+    let lower = model.trim().to_ascii_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or_default();
+    let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|p| name.starts_with(p));
+
+    if starts_with_any(&[
+        "gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "chatgpt-", "o1", "o3", "o4",
+    ]) {
+        &O200K_BPE
+    } else if starts_with_any(&["gpt-4", "gpt-3.5", "text-embedding", "davinci"]) {
+        &CL100K_BPE
+    } else if name.starts_with("claude") {
+        &CLAUDE_ESTIMATOR
+    } else if starts_with_any(&["gemini", "gemma"]) {
+        &GEMINI_ESTIMATOR
+    } else if starts_with_any(&[
+        "qwen", "qwq", "deepseek", "glm", "chatglm", "moonshot", "kimi", "doubao", "yi-", "ernie",
+        "hunyuan", "minimax", "abab",
+    ]) {
+        &CHINESE_VOCAB_ESTIMATOR
+    } else if starts_with_any(&["llama", "llava", "mistral", "mixtral", "phi"]) {
+        &LLAMA_ESTIMATOR
+    } else {
+        &GENERIC_ESTIMATOR
+    }
+}
+
+/// Compaction kicks in once history fills this share of the usable input window.
+pub const AUTO_COMPACT_PERCENT: usize = 75;
+/// Old tool results are collapsed once history fills this share of the input window.
+pub const MICRO_COMPACT_PERCENT: usize = 50;
+/// Hard trimming keeps history under this share of the budget it was given.
+pub const TRIM_TARGET_PERCENT: usize = 70;
+
+/// A model's context window minus the tokens reserved for its reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextBudget {
+    pub context_window: usize,
+    pub reserved_output_tokens: usize,
+}
+
+impl ContextBudget {
+    pub fn new(context_window: usize, reserved_output_tokens: usize) -> Self {
+        Self {
+            context_window,
+            reserved_output_tokens,
+        }
+    }
+
+    pub fn input_window(&self) -> usize {
+        self.context_window
+            .saturating_sub(self.reserved_output_tokens)
+    }
+
+    pub fn percent_of_input(&self, percent: usize) -> usize {
+        self.input_window() * percent / 100
+    }
+
+    /// Share of the input window used by `tokens`, as a whole percentage.
+    pub fn usage_percent(&self, tokens: usize) -> usize {
+        match self.input_window() {
+            0 => 100,
+            window => tokens * 100 / window,
+        }
+    }
+
+    pub fn auto_compact_threshold(&self) -> usize {
+        self.percent_of_input(AUTO_COMPACT_PERCENT)
+    }
+
+    pub fn micro_compact_threshold(&self) -> usize {
+        self.percent_of_input(MICRO_COMPACT_PERCENT)
+    }
+}
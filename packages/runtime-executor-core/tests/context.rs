@@ -1,4 +1,7 @@
-use runtime_executor_core::{micro_compact, trim_messages};
+use runtime_executor_core::{
+    estimate_tokens, estimate_tokens_for_model, micro_compact, tokenizer_for_model, trim_messages,
+    trim_messages_with, ContextBudget, Tokenizer, CL100K_BPE, MESSAGE_OVERHEAD_TOKENS,
+};
 use serde_json::json;
 
 #[test]
@@ -47,3 +50,81 @@ fn micro_compact_replaces_old_tool_results() {
     let r3 = serde_json::to_string(&result[3]).expect("serialize r3");
     assert!(r3.contains("recent output"));
 }
+
+#[test]
+fn estimate_counts_chinese_per_character() {
+    let chinese = "上下文压缩".repeat(200);
+    let messages = vec![json!({"role": "user", "content": &chinese})];
+    // 1000 个汉字按字节 / 4 只有 750，按字计数不应低于字数
+    assert!(estimate_tokens(&messages) >= 1_000);
+    assert_eq!(estimate_tokens(&messages), 1_000 + MESSAGE_OVERHEAD_TOKENS);
+}
+
+#[test]
+fn tokenizer_selection_follows_model_family() {
+    assert_eq!(tokenizer_for_model("gpt-4o-mini").name(), "o200k_base");
+    assert_eq!(tokenizer_for_model("openai/gpt-4.1").name(), "o200k_base");
+    assert_eq!(tokenizer_for_model("gpt-4-turbo").name(), "cl100k_base");
+    assert_eq!(tokenizer_for_model("claude-sonnet-4-5").name(), "claude");
+    assert_eq!(tokenizer_for_model("deepseek-chat").name(), "chinese_vocab");
+    assert_eq!(tokenizer_for_model("Qwen-Max").name(), "chinese_vocab");
+    assert_eq!(tokenizer_for_model("gemini-2.5-flash").name(), "gemini");
+    assert_eq!(tokenizer_for_model("llama3.1:8b").name(), "llama");
+    assert_eq!(tokenizer_for_model("my-local-model").name(), "generic");
+}
+
+#[test]
+fn bpe_estimator_follows_pretokenizer_splits() {
+    // cl100k_base 编码该句为 10 个 token
+    assert_eq!(
+        CL100K_BPE.count_text("The quick brown fox jumps over the lazy dog."),
+        10
+    );
+    assert_eq!(CL100K_BPE.count_text("1234567"), 3);
+    assert!(CL100K_BPE.count_text("你好世界") >= 4);
+}
+
+#[test]
+fn chinese_vocab_models_count_fewer_tokens_than_cl100k() {
+    let messages = vec![json!({"role": "user", "content": "请帮我整理本周的销售数据并生成报告"})];
+    assert!(
+        estimate_tokens_for_model(&messages, "qwen-max")
+            < estimate_tokens_for_model(&messages, "gpt-4")
+    );
+}
+
+#[test]
+fn estimate_counts_block_text_and_tool_calls() {
+    let messages = vec![
+        json!({"role": "assistant", "content": null, "tool_calls": [{"id": "call_1", "type": "function", "function": {"name": "read_file", "arguments": "{\"path\":\"a.txt\"}"}}]}),
+        json!({"role": "user", "content": [{"type": "text", "text": "你好"}]}),
+    ];
+    let tokens = estimate_tokens(&messages);
+    assert!(tokens > 2 * MESSAGE_OVERHEAD_TOKENS + 2);
+}
+
+#[test]
+fn context_budget_uses_percentages_of_input_window() {
+    let budget = ContextBudget::new(128_000, 8_192);
+    assert_eq!(budget.input_window(), 119_808);
+    assert_eq!(budget.auto_compact_threshold(), 89_856);
+    assert_eq!(budget.micro_compact_threshold(), 59_904);
+    assert_eq!(budget.usage_percent(59_904), 50);
+    assert_eq!(ContextBudget::new(4_096, 8_192).usage_percent(1), 100);
+}
+
+#[test]
+fn trim_with_tokenizer_accounts_for_chinese_text() {
+    let chinese = "上下文".repeat(1_000);
+    let messages = vec![
+        json!({"role": "user", "content": "start"}),
+        json!({"role": "assistant", "content": &chinese}),
+        json!({"role": "user", "content": &chinese}),
+        json!({"role": "assistant", "content": &chinese}),
+        json!({"role": "user", "content": "latest question"}),
+    ];
+    // 按字节 / 4 约 6_750 token，会被误判为未超出 8_000 的预算
+    let trimmed = trim_messages_with(&messages, 8_000, tokenizer_for_model("gpt-4"));
+    assert!(trimmed.len() < messages.len());
+    assert_eq!(trimmed.last().unwrap()["content"], "latest question");
+}
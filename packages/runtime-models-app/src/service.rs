@@ -7,13 +7,15 @@ use crate::types::{
 };
 use runtime_routing_core::{
     builtin_capability_route_templates, cache_row_is_fresh, default_model_for_protocol,
-    filter_models_by_capability, recommended_models_for_provider,
+    ModelCatalog,
 };
+use std::sync::Arc;
 
 pub struct ModelsAppService<R, C, P = NoopProviderHealthProbe> {
     repo: R,
     catalog: C,
     probe: P,
+    model_catalog: Option<Arc<ModelCatalog>>,
 }
 
 pub struct NoopProviderHealthProbe;
@@ -37,6 +39,7 @@ impl<R, C> ModelsAppService<R, C, NoopProviderHealthProbe> {
             repo,
             catalog,
             probe: NoopProviderHealthProbe,
+            model_catalog: None,
         }
     }
 }
@@ -47,8 +50,21 @@ impl<R, C, P> ModelsAppService<R, C, P> {
             repo,
             catalog,
             probe,
+            model_catalog: None,
         }
     }
+
+    /// 使用叠加了用户覆盖的模型目录；未设置时使用内置目录
+    pub fn with_model_catalog(mut self, model_catalog: Arc<ModelCatalog>) -> Self {
+        self.model_catalog = Some(model_catalog);
+        self
+    }
+
+    fn model_catalog(&self) -> &ModelCatalog {
+        self.model_catalog
+            .as_deref()
+            .unwrap_or_else(|| ModelCatalog::bundled())
+    }
 }
 
 impl<R, C, P> ModelsAppService<R, C, P>
@@ -249,7 +265,9 @@ where
         let models = if let Some(models) = cached_models {
             models
         } else {
-            let fresh_models = recommended_models_for_provider(&provider_key);
+            let fresh_models = self
+                .model_catalog()
+                .recommended_models_for_provider(&provider_key);
             let now = chrono::Utc::now().to_rfc3339();
             self.repo
                 .replace_model_catalog_cache(provider_id, &fresh_models, &now, 3600)
@@ -257,7 +275,9 @@ where
             fresh_models
         };
 
-        let mut out = filter_models_by_capability(models, capability);
+        let mut out = self
+            .model_catalog()
+            .filter_models_by_capability(models, capability);
         out.sort();
         Ok(out)
     }
@@ -0,0 +1,689 @@
+use crate::permissions::{approval_rule_fingerprint, normalize_tool_name};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Tool name wildcard: the rule applies to every tool exposing the matched field.
+pub const ANY_TOOL: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleEffect {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleMatchKind {
+    /// Exact `approval_rule_fingerprint`, as written by "allow always".
+    Fingerprint,
+    /// Glob over the leading words of a shell command segment (`*`, `?`).
+    CommandGlob,
+    /// Regex anchored at the start of a shell command segment.
+    CommandRegex,
+    /// Glob over the path arguments (`path`, `source`, `destination`, `base_dir`;
+    /// `*` within a segment, `**` across segments),
+    /// resolved against the work dir the same way tool paths are.
+    PathGlob,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ApprovalRuleScope {
+    Global,
+    Employee(String),
+    Session(String),
+    Workspace(String),
+}
+
+impl ApprovalRuleEffect {
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.trim() {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl ApprovalRuleMatchKind {
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            Self::Fingerprint => "fingerprint",
+            Self::CommandGlob => "command_glob",
+            Self::CommandRegex => "command_regex",
+            Self::PathGlob => "path_glob",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.trim() {
+            "fingerprint" => Some(Self::Fingerprint),
+            "command_glob" => Some(Self::CommandGlob),
+            "command_regex" => Some(Self::CommandRegex),
+            "path_glob" => Some(Self::PathGlob),
+            _ => None,
+        }
+    }
+}
+
+impl ApprovalRuleScope {
+    pub fn from_parts(kind: &str, value: &str) -> Option<Self> {
+        let value = value.trim().to_string();
+        match (kind.trim(), value.is_empty()) {
+            ("global" | "", _) => Some(Self::Global),
+            ("employee", false) => Some(Self::Employee(value)),
+            ("session", false) => Some(Self::Session(value)),
+            ("workspace", false) => Some(Self::Workspace(value)),
+            _ => None,
+        }
+    }
+
+    pub fn kind_key(&self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Employee(_) => "employee",
+            Self::Session(_) => "session",
+            Self::Workspace(_) => "workspace",
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Global => "",
+            Self::Employee(value) | Self::Session(value) | Self::Workspace(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRuleSpec {
+    pub tool_name: String,
+    pub effect: ApprovalRuleEffect,
+    pub match_kind: ApprovalRuleMatchKind,
+    pub pattern: String,
+    pub scope: ApprovalRuleScope,
+}
+
+/// The tool call a rule is evaluated against, with the context scopes refer to.
+#[derive(Debug, Clone, Copy)]
+pub struct ApprovalRuleTarget<'a> {
+    pub tool_name: &'a str,
+    pub input: &'a Value,
+    pub employee_id: Option<&'a str>,
+    pub session_id: Option<&'a str>,
+    pub work_dir: Option<&'a Path>,
+}
+
+impl ApprovalRuleSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tool_name.trim().is_empty() {
+            return Err("approval rule tool_name is required".to_string());
+        }
+        if self.pattern.trim().is_empty() {
+            return Err("approval rule pattern is required".to_string());
+        }
+        if self.match_kind == ApprovalRuleMatchKind::Fingerprint
+            && self.effect == ApprovalRuleEffect::Deny
+        {
+            return Err("fingerprint rules can only allow".to_string());
+        }
+        self.compile(None).map(|_| ())
+    }
+
+    /// Key used for de-duplication in storage; identical rules collapse into one row.
+    pub fn storage_fingerprint(&self) -> String {
+        if self.match_kind == ApprovalRuleMatchKind::Fingerprint {
+            return self.pattern.clone();
+        }
+        json!({
+            "effect": self.effect.as_key(),
+            "match_kind": self.match_kind.as_key(),
+            "pattern": self.pattern.trim(),
+            "scope": self.scope.kind_key(),
+            "scope_value": self.scope.value(),
+        })
+        .to_string()
+    }
+
+    pub fn matches(&self, target: &ApprovalRuleTarget<'_>) -> bool {
+        if !self.tool_matches(target.tool_name) || !self.scope_matches(target) {
+            return false;
+        }
+        if self.match_kind == ApprovalRuleMatchKind::Fingerprint {
+            return approval_rule_fingerprint(target.tool_name, target.input).as_deref()
+                == Some(self.pattern.as_str());
+        }
+        let Ok(Some(regex)) = self.compile(target.work_dir) else {
+            return false;
+        };
+        if self.match_kind == ApprovalRuleMatchKind::PathGlob {
+            return path_rule_matches(&regex, self.effect, target);
+        }
+        let command = target
+            .input
+            .get("command")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        match self.effect {
+            ApprovalRuleEffect::Deny => {
+                let glob_flags = (self.match_kind == ApprovalRuleMatchKind::CommandGlob)
+                    .then(|| CommandShape::parse(&self.pattern).flags);
+                deny_command_matches(&regex, glob_flags.as_ref(), command)
+            }
+            ApprovalRuleEffect::Allow => allow_command_matches(&regex, command),
+        }
+    }
+
+    fn tool_matches(&self, tool_name: &str) -> bool {
+        let rule_tool = self.tool_name.trim();
+        rule_tool == ANY_TOOL || normalize_tool_name(rule_tool) == normalize_tool_name(tool_name)
+    }
+
+    fn scope_matches(&self, target: &ApprovalRuleTarget<'_>) -> bool {
+        match &self.scope {
+            ApprovalRuleScope::Global => true,
+            ApprovalRuleScope::Employee(employee_id) => target
+                .employee_id
+                .is_some_and(|value| value.trim().eq_ignore_ascii_case(employee_id)),
+            ApprovalRuleScope::Session(session_id) => target
+                .session_id
+                .is_some_and(|value| value.trim() == session_id),
+            ApprovalRuleScope::Workspace(root) => target.work_dir.is_some_and(|work_dir| {
+                let root = normalize_path_text(root).to_lowercase();
+                let work_dir = normalize_path_text(&work_dir.to_string_lossy()).to_lowercase();
+                let root = root.trim_end_matches('/');
+                !root.is_empty() && (work_dir == root || work_dir.starts_with(&format!("{root}/")))
+            }),
+        }
+    }
+
+    fn compile(&self, work_dir: Option<&Path>) -> Result<Option<Regex>, String> {
+        let pattern = self.pattern.trim();
+        let source = match self.match_kind {
+            ApprovalRuleMatchKind::Fingerprint => return Ok(None),
+            ApprovalRuleMatchKind::CommandGlob => {
+                // deny globs compare short flags as a set, see `deny_command_matches`
+                let words = match self.effect {
+                    ApprovalRuleEffect::Deny => CommandShape::parse(pattern).words.join(" "),
+                    ApprovalRuleEffect::Allow => normalize_command(pattern),
+                };
+                format!("^{}(?:\\s.*)?$", glob_to_regex(&words, false))
+            }
+            ApprovalRuleMatchKind::CommandRegex => format!("^(?:{pattern})"),
+            ApprovalRuleMatchKind::PathGlob => format!(
+                "^{}$",
+                glob_to_regex(&resolve_path_pattern(pattern, work_dir), true)
+            ),
+        };
+        RegexBuilder::new(&source)
+            .case_insensitive(true)
+            .size_limit(1 << 20)
+            .build()
+            .map(Some)
+            .map_err(|e| format!("invalid approval rule pattern `{pattern}`: {e}"))
+    }
+}
+
+/// An allow rule must cover every segment of a compound command and cannot
+/// vouch for command substitution.
+fn allow_command_matches(regex: &Regex, command: &str) -> bool {
+    let segments = split_command_segments(command);
+    !segments.is_empty()
+        && !command.contains('`')
+        && !command.contains("$(")
+        && segments.iter().all(|segment| regex.is_match(segment))
+}
+
+/// A deny rule fires if any segment matches, including commands nested in
+/// substitutions, `sh -c` scripts and launchers such as `sudo`, `env` or
+/// `xargs`. A glob's short flags compare as a set: `rm -rf` also denies
+/// `rm -fr`, `rm -r -f` and `rm -rfv`. Regex rules see each segment as
+/// written and with its flags merged (`rm -r -f` as `rm -rf`).
+fn deny_command_matches(regex: &Regex, glob_flags: Option<&BTreeSet<char>>, command: &str) -> bool {
+    expand_command_segments(command).iter().any(|segment| {
+        let shape = CommandShape::parse(segment);
+        match glob_flags {
+            Some(flags) => {
+                flags.is_subset(&shape.flags)
+                    && (regex.is_match(&shape.words.join(" "))
+                        || (flags.is_empty() && regex.is_match(segment)))
+            }
+            None => regex.is_match(segment) || regex.is_match(&shape.merged_text()),
+        }
+    })
+}
+
+/// A command segment split into its words and the short flags it sets,
+/// wherever they appear before `--`.
+struct CommandShape {
+    words: Vec<String>,
+    flags: BTreeSet<char>,
+    /// Flags in order of appearance, for regex rules written against `-rf`.
+    flag_order: String,
+}
+
+impl CommandShape {
+    fn parse(segment: &str) -> Self {
+        let mut shape = Self {
+            words: Vec::new(),
+            flags: BTreeSet::new(),
+            flag_order: String::new(),
+        };
+        let mut options_ended = false;
+        for (index, word) in segment.split_whitespace().enumerate() {
+            if index == 0 {
+                shape.words.push(program_name(word).to_string());
+                continue;
+            }
+            if word == "--" {
+                options_ended = true;
+            }
+            let cluster = word
+                .strip_prefix('-')
+                .filter(|cluster| !cluster.is_empty())
+                .filter(|cluster| cluster.chars().all(|ch| ch.is_ascii_alphanumeric()));
+            match cluster {
+                Some(cluster) if !options_ended => {
+                    for flag in cluster.chars().map(|ch| ch.to_ascii_lowercase()) {
+                        if shape.flags.insert(flag) {
+                            shape.flag_order.push(flag);
+                        }
+                    }
+                }
+                _ => shape.words.push(word.to_string()),
+            }
+        }
+        shape
+    }
+
+    /// `rm -r -f /` as `rm -rf /`: the program, its flags merged into one word, then the rest.
+    fn merged_text(&self) -> String {
+        let mut words = self.words.clone();
+        if !self.flag_order.is_empty() && !words.is_empty() {
+            words.insert(1, format!("-{}", self.flag_order));
+        }
+        words.join(" ")
+    }
+}
+
+pub fn split_command_segments(command: &str) -> Vec<String> {
+    split_segments(command, false)
+}
+
+/// Every command a deny rule has to see: each segment, the bodies of `$(...)`
+/// and backtick substitutions, `bash -c`/`sh -c` scripts and commands run
+/// through launchers (`sudo`, `env`, `xargs`, `timeout`, ...), unwrapped
+/// recursively, with the program reduced to its basename.
+pub fn expand_command_segments(command: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_command_segments(command, 0, &mut out);
+    out.dedup();
+    out
+}
+
+const MAX_COMMAND_NESTING: usize = 8;
+const WRAPPER_SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh"];
+
+fn collect_command_segments(command: &str, depth: usize, out: &mut Vec<String>) {
+    if depth > MAX_COMMAND_NESTING {
+        return;
+    }
+    for body in substitution_bodies(command) {
+        collect_command_segments(&body, depth + 1, out);
+    }
+    let mut segments = split_segments(command, false);
+    segments.extend(split_segments(command, true));
+    for segment in segments {
+        if let Some(inner) = unwrap_command_wrapper(&segment) {
+            collect_command_segments(&inner, depth + 1, out);
+        }
+        if !out.contains(&segment) {
+            out.push(segment);
+        }
+    }
+}
+
+fn substitution_bodies(command: &str) -> Vec<String> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut bodies = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '$' && chars.get(i + 1) == Some(&'(') {
+            let start = i + 2;
+            let mut depth = 1;
+            let mut j = start;
+            while j < chars.len() {
+                match chars[j] {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                j += 1;
+            }
+            bodies.push(chars[start..j].iter().collect());
+            i = j + 1;
+        } else if chars[i] == '`' {
+            let start = i + 1;
+            let end = chars[start..]
+                .iter()
+                .position(|ch| *ch == '`')
+                .map_or(chars.len(), |offset| start + offset);
+            bodies.push(chars[start..end].iter().collect());
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+    bodies
+}
+
+/// The command hidden behind launchers, `VAR=value` prefixes or `bash -c '...'`,
+/// or the segment with its program path reduced to the basename.
+fn unwrap_command_wrapper(segment: &str) -> Option<String> {
+    let words: Vec<&str> = segment.split_whitespace().collect();
+    let start = skip_launchers(&words);
+    let rest = &words[start..];
+    let program = program_name(rest.first()?);
+    if WRAPPER_SHELLS.contains(&program) {
+        let mut index = 1;
+        while let Some(word) = rest.get(index).filter(|word| word.starts_with('-')) {
+            if !word.starts_with("--") && word.contains('c') {
+                let script = rest.get(index + 1..)?.join(" ");
+                return Some(strip_matching_quotes(&script).to_string());
+            }
+            index += 1;
+        }
+    }
+    if start == 0 && program == rest[0] {
+        return None;
+    }
+    let mut inner = vec![program];
+    inner.extend_from_slice(&rest[1..]);
+    Some(strip_matching_quotes(&inner.join(" ")).to_string())
+}
+
+/// Index of the first word after any chain of launchers and their options.
+/// Launchers are stripped in one pass so a long chain cannot exhaust
+/// `MAX_COMMAND_NESTING`.
+fn skip_launchers(words: &[&str]) -> usize {
+    let mut index = 0;
+    loop {
+        while words.get(index).is_some_and(|word| is_env_assignment(word)) {
+            index += 1;
+        }
+        let Some(word) = words.get(index) else {
+            return index;
+        };
+        let program = program_name(word);
+        let Some(arg_options) = launcher_arg_options(program) else {
+            return index;
+        };
+        index += 1;
+        while let Some(option) = words.get(index).filter(|word| word.starts_with('-')) {
+            index += 1;
+            if *option == "--" {
+                break;
+            }
+            if arg_options.contains(option) {
+                index += 1;
+            }
+        }
+        if program == "timeout" {
+            index += 1;
+        }
+    }
+}
+
+/// Options of a launcher (a program that runs the rest of its arguments as
+/// a command) that consume the following word; `None` if `program` is not one.
+fn launcher_arg_options(program: &str) -> Option<&'static [&'static str]> {
+    match program {
+        "sudo" => Some(&["-u", "-g", "-U", "-C", "-D", "-h", "-p", "-r", "-t"]),
+        "doas" => Some(&["-u", "-C"]),
+        "env" => Some(&["-u", "-C", "--unset", "--chdir"]),
+        "nice" => Some(&["-n", "--adjustment"]),
+        // `timeout` also takes a duration, skipped by `skip_launchers`
+        "timeout" => Some(&["-s", "-k", "--signal", "--kill-after"]),
+        "xargs" => Some(&["-a", "-d", "-E", "-I", "-L", "-n", "-P", "-s"]),
+        "exec" => Some(&["-a"]),
+        "command" | "nohup" => Some(&[]),
+        _ => None,
+    }
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+            && name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    })
+}
+
+/// `/usr/bin/rm`, `\rm` and `'rm'` all name `rm`.
+fn program_name(word: &str) -> &str {
+    let word = word.trim_start_matches('\\');
+    let word = strip_matching_quotes(word);
+    word.rsplit(['/', '\\']).next().unwrap_or(word)
+}
+
+fn strip_matching_quotes(text: &str) -> &str {
+    let text = text.trim();
+    for quote in ['\'', '"'] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    text
+}
+
+fn split_segments(command: &str, respect_quotes: bool) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if respect_quotes {
+            match quote {
+                Some(open) if ch == open => quote = None,
+                None if ch == '\'' || ch == '"' => quote = Some(ch),
+                _ => {}
+            }
+            if quote.is_some() || ch == '\'' || ch == '"' {
+                current.push(ch);
+                continue;
+            }
+        }
+        let separator = match ch {
+            ';' | '\n' | '\r' => true,
+            '&' | '|' => {
+                if chars.peek() == Some(&ch) {
+                    chars.next();
+                }
+                true
+            }
+            _ => false,
+        };
+        if separator {
+            segments.push(std::mem::take(&mut current));
+        } else {
+            current.push(ch);
+        }
+    }
+    segments.push(current);
+    segments
+        .into_iter()
+        .map(|segment| normalize_command(&segment))
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn normalize_command(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize_path_text(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    match path.strip_prefix("//?/") {
+        Some(verbatim) => verbatim.to_string(),
+        None => path,
+    }
+}
+
+fn is_absolute_path_text(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Input fields that carry a filesystem path in the runtime's tools.
+const PATH_INPUT_KEYS: &[&str] = &["path", "source", "destination", "base_dir"];
+
+/// Tools whose input always names at least one of `PATH_INPUT_KEYS`.
+const PATH_INPUT_TOOLS: &[&str] = &[
+    "read_file",
+    "write_file",
+    "edit",
+    "file_delete",
+    "file_copy",
+    "file_move",
+    "file_stat",
+    "list_dir",
+    "open_in_folder",
+    "glob",
+    "grep",
+    "screenshot",
+];
+
+/// A deny rule fires if any path the call touches matches; an allow rule must
+/// cover all of them. A path tool that supplies none of the known keys fails
+/// closed: deny rules fire and allow rules do not.
+fn path_rule_matches(
+    regex: &Regex,
+    effect: ApprovalRuleEffect,
+    target: &ApprovalRuleTarget<'_>,
+) -> bool {
+    let paths = PATH_INPUT_KEYS
+        .iter()
+        .filter_map(|key| target.input.get(*key).and_then(Value::as_str))
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| resolve_rule_path(path, target.work_dir))
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return effect == ApprovalRuleEffect::Deny
+            && PATH_INPUT_TOOLS.contains(&normalize_tool_name(target.tool_name).as_str());
+    }
+    match effect {
+        ApprovalRuleEffect::Deny => paths.iter().any(|path| regex.is_match(path)),
+        ApprovalRuleEffect::Allow => paths.iter().all(|path| regex.is_match(path)),
+    }
+}
+
+/// Resolves a tool path like the runtime's `check_path`: relative paths join
+/// the work dir, `.`/`..` are folded and the longest existing ancestor is
+/// canonicalized, so `..` or a symlink cannot step around a rule.
+fn resolve_rule_path(path: &str, work_dir: Option<&Path>) -> String {
+    let text = normalize_path_text(path);
+    let joined = if is_absolute_path_text(&text) {
+        PathBuf::from(&text)
+    } else if let Some(work_dir) = work_dir {
+        work_dir.join(&text)
+    } else {
+        match std::env::current_dir() {
+            Ok(current_dir) => current_dir.join(&text),
+            Err(_) => PathBuf::from(&text),
+        }
+    };
+    normalize_path_text(&canonicalize_existing_prefix(&joined).to_string_lossy())
+}
+
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    let existing = path
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .find_map(|ancestor| Some((ancestor.canonicalize().ok()?, ancestor)));
+    let (mut resolved, remainder) = match existing {
+        Some((canonical, ancestor)) => (
+            canonical,
+            path.strip_prefix(ancestor)
+                .unwrap_or_else(|_| Path::new("")),
+        ),
+        None => (PathBuf::new(), path),
+    };
+    for component in remainder.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other.as_os_str()),
+        }
+    }
+    resolved
+}
+
+/// Resolves the literal directory prefix of a path glob; the wildcard tail is kept as is.
+fn resolve_path_pattern(pattern: &str, work_dir: Option<&Path>) -> String {
+    let pattern = normalize_path_text(pattern);
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let split = segments
+        .iter()
+        .position(|segment| segment.contains(['*', '?']))
+        .unwrap_or(segments.len());
+    let literal = match segments[..split].join("/") {
+        literal if literal.is_empty() && split > 0 => "/".to_string(),
+        literal if literal.is_empty() => ".".to_string(),
+        literal => literal,
+    };
+    let base = resolve_rule_path(&literal, work_dir);
+    let rest = segments[split..].join("/");
+    if rest.is_empty() {
+        base
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), rest)
+    }
+}
+
+fn glob_to_regex(glob: &str, path_mode: bool) -> String {
+    let mut out = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if path_mode && chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches zero directories
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' if path_mode => out.push_str("[^/]*"),
+            '*' => out.push_str(".*"),
+            '?' if path_mode => out.push_str("[^/]"),
+            '?' => out.push('.'),
+            other => out.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    out
+}
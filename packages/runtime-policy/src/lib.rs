@@ -1,6 +1,11 @@
+mod approval_rules;
 mod permissions;
 mod tool_decision;
 
+pub use approval_rules::{
+    expand_command_segments, split_command_segments, ApprovalRuleEffect, ApprovalRuleMatchKind,
+    ApprovalRuleScope, ApprovalRuleSpec, ApprovalRuleTarget, ANY_TOOL,
+};
 pub use permissions::{
     approval_rule_fingerprint, classify_action_risk, matches_approval_rule_fingerprint,
     narrow_allowed_tools, normalize_tool_name, tool_permission_decision, ActionRisk,
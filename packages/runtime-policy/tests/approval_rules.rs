@@ -0,0 +1,402 @@
+use runtime_policy::{
+    approval_rule_fingerprint, expand_command_segments, split_command_segments, ApprovalRuleEffect,
+    ApprovalRuleMatchKind, ApprovalRuleScope, ApprovalRuleSpec, ApprovalRuleTarget,
+};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn rule(
+    tool_name: &str,
+    effect: ApprovalRuleEffect,
+    match_kind: ApprovalRuleMatchKind,
+    pattern: &str,
+) -> ApprovalRuleSpec {
+    ApprovalRuleSpec {
+        tool_name: tool_name.to_string(),
+        effect,
+        match_kind,
+        pattern: pattern.to_string(),
+        scope: ApprovalRuleScope::Global,
+    }
+}
+
+fn target<'a>(tool_name: &'a str, input: &'a Value) -> ApprovalRuleTarget<'a> {
+    ApprovalRuleTarget {
+        tool_name,
+        input,
+        employee_id: None,
+        session_id: None,
+        work_dir: None,
+    }
+}
+
+#[test]
+fn command_glob_allow_covers_every_segment() {
+    let allow = rule(
+        "bash",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::CommandGlob,
+        "git status*",
+    );
+    let plain = json!({ "command": "git   status --short" });
+    let chained = json!({ "command": "git status && rm -rf /" });
+    let substituted = json!({ "command": "git status $(rm -rf /)" });
+
+    assert!(allow.matches(&target("bash", &plain)));
+    assert!(!allow.matches(&target("exec", &plain)));
+    assert!(!allow.matches(&target("bash", &chained)));
+    assert!(!allow.matches(&target("bash", &substituted)));
+}
+
+#[test]
+fn command_glob_matches_on_word_prefix() {
+    let allow = rule(
+        "bash",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::CommandGlob,
+        "npm test",
+    );
+    assert!(allow.matches(&target("bash", &json!({ "command": "npm test" }))));
+    assert!(allow.matches(&target(
+        "bash",
+        &json!({ "command": "npm test -- --watch" })
+    )));
+    assert!(!allow.matches(&target("bash", &json!({ "command": "npm testx" }))));
+    assert!(!allow.matches(&target("bash", &json!({ "command": "sudo npm test" }))));
+}
+
+#[test]
+fn deny_rules_see_through_substitutions_shell_wrappers_and_sudo() {
+    let deny = rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandGlob,
+        "rm -rf",
+    );
+    for command in [
+        "echo $(rm -rf /)",
+        "echo `rm -rf /`",
+        "bash -c 'cd /tmp; rm -rf build'",
+        "/bin/sh -lc \"rm -rf /\"",
+        "sudo -u root rm -rf /",
+        "sudo bash -c \"echo $(rm -rf /)\"",
+    ] {
+        assert!(
+            deny.matches(&target("bash", &json!({ "command": command }))),
+            "deny should fire for {command}"
+        );
+    }
+    assert!(!deny.matches(&target("bash", &json!({ "command": "bash -c 'ls -la'" }))));
+    assert!(expand_command_segments("sudo git push").contains(&"git push".to_string()));
+}
+
+#[test]
+fn deny_rules_see_through_launchers_and_program_paths() {
+    let deny = rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandGlob,
+        "rm -rf",
+    );
+    for command in [
+        "/bin/rm -rf /",
+        "\\rm -rf /",
+        "env rm -rf /",
+        "env -i -u HOME PATH=/bin rm -rf /",
+        "env -S 'rm -rf /'",
+        "FOO=1 rm -rf /",
+        "command rm -rf /",
+        "command -p rm -rf /",
+        "cat dirs | xargs rm -rf",
+        "find . -name build | xargs -0 -n 1 -I {} rm -rf {}",
+        "nohup rm -rf / &",
+        "timeout 5 rm -rf /",
+        "timeout -s KILL -k 10 5s rm -rf /",
+        "nice rm -rf /",
+        "nice -n 10 rm -rf /",
+        "sudo -u root /usr/bin/rm -rf /",
+        "sh -c 'rm -rf /'",
+        "nohup nohup nohup nohup nohup nohup nohup nohup nohup nohup rm -rf /",
+        "sudo env FOO=1 nice -n 5 timeout 9 bash -c 'rm -rf /'",
+    ] {
+        assert!(
+            deny.matches(&target("bash", &json!({ "command": command }))),
+            "deny should fire for {command}"
+        );
+    }
+    for command in ["timeout 5 ls -la", "env FOO=1 rm build", "xargs echo rm"] {
+        assert!(
+            !deny.matches(&target("bash", &json!({ "command": command }))),
+            "deny should not fire for {command}"
+        );
+    }
+    assert!(expand_command_segments("/usr/bin/git push").contains(&"git push".to_string()));
+}
+
+#[test]
+fn deny_globs_compare_short_flags_as_a_set() {
+    let deny = rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandGlob,
+        "rm -rf",
+    );
+    for command in [
+        "rm -fr /",
+        "rm -r -f /",
+        "rm -f -r /",
+        "rm -rfv /",
+        "rm -r / -f",
+        "RM -R -F /",
+    ] {
+        assert!(
+            deny.matches(&target("bash", &json!({ "command": command }))),
+            "deny should fire for {command}"
+        );
+    }
+    for command in ["rm -r build", "rm -f a.txt", "rm -- -rf", "rmdir -rf"] {
+        assert!(
+            !deny.matches(&target("bash", &json!({ "command": command }))),
+            "deny should not fire for {command}"
+        );
+    }
+
+    let regex = rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandRegex,
+        r"rm\s+-rf",
+    );
+    assert!(regex.matches(&target("bash", &json!({ "command": "rm -r -f /" }))));
+    assert!(regex.matches(&target("bash", &json!({ "command": "env rm -rf /" }))));
+
+    let allow = rule(
+        "bash",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::CommandGlob,
+        "rm -rf build",
+    );
+    assert!(allow.matches(&target("bash", &json!({ "command": "rm -rf build" }))));
+    assert!(!allow.matches(&target("bash", &json!({ "command": "env rm -rf build" }))));
+}
+
+#[test]
+fn deny_rules_fire_on_any_segment_and_any_tool() {
+    let deny = rule(
+        "*",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandRegex,
+        r"(sudo\s+)?rm\s+-[a-z]*r[a-z]*f",
+    );
+    let chained = json!({ "command": "cd /tmp; RM -rf build" });
+    let safe = json!({ "command": "ls -la" });
+
+    assert!(deny.matches(&target("bash", &chained)));
+    assert!(deny.matches(&target("exec", &chained)));
+    assert!(!deny.matches(&target("bash", &safe)));
+    assert!(!deny.matches(&target("read_file", &json!({ "path": "rm -rf" }))));
+}
+
+#[test]
+fn path_glob_respects_segment_boundaries() {
+    let allow = rule(
+        "file_delete",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::PathGlob,
+        "E:/workspace/**/*.tmp",
+    );
+    assert!(allow.matches(&target(
+        "file_delete",
+        &json!({ "path": "E:\\workspace\\build\\cache\\a.tmp" })
+    )));
+    assert!(allow.matches(&target(
+        "file_delete",
+        &json!({ "path": "e:/workspace/a.tmp" })
+    )));
+    assert!(!allow.matches(&target(
+        "file_delete",
+        &json!({ "path": "E:/workspace/a.tmp.bak" })
+    )));
+
+    let single = rule(
+        "file_delete",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::PathGlob,
+        "/tmp/*",
+    );
+    assert!(single.matches(&target("file_delete", &json!({ "path": "/tmp/a" }))));
+    assert!(!single.matches(&target("file_delete", &json!({ "path": "/tmp/a/b" }))));
+}
+
+#[test]
+fn path_glob_resolves_relative_and_dot_dot_paths_against_work_dir() {
+    let work_dir = std::env::temp_dir().join(format!("approval-rules-{}", std::process::id()));
+    std::fs::create_dir_all(work_dir.join("build")).expect("create build");
+    let deny = rule(
+        "file_delete",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::PathGlob,
+        "src/**",
+    );
+    let matches = |path: Value| {
+        let input = json!({ "path": path });
+        let mut call = target("file_delete", &input);
+        call.work_dir = Some(&work_dir);
+        deny.matches(&call)
+    };
+
+    assert!(matches(json!("src/main.rs")));
+    assert!(matches(json!("./build/../src/lib.rs")));
+    assert!(matches(json!(work_dir.join("src").join("a.rs"))));
+    assert!(!matches(json!("build/out.rs")));
+    let _ = std::fs::remove_dir_all(&work_dir);
+}
+
+#[test]
+fn path_glob_checks_every_path_field() {
+    let deny = rule(
+        "*",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::PathGlob,
+        "/etc/**",
+    );
+    assert!(deny.matches(&target(
+        "file_move",
+        &json!({ "source": "/etc/passwd", "destination": "/tmp/passwd" })
+    )));
+    assert!(deny.matches(&target(
+        "file_copy",
+        &json!({ "source": "/tmp/hosts", "destination": "/etc/hosts" })
+    )));
+    assert!(deny.matches(&target(
+        "glob",
+        &json!({ "pattern": "*.conf", "base_dir": "/etc/ssl" })
+    )));
+    assert!(!deny.matches(&target(
+        "file_copy",
+        &json!({ "source": "/tmp/a", "destination": "/tmp/b" })
+    )));
+
+    let allow = rule(
+        "file_copy",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::PathGlob,
+        "/tmp/**",
+    );
+    assert!(allow.matches(&target(
+        "file_copy",
+        &json!({ "source": "/tmp/a", "destination": "/tmp/b" })
+    )));
+    assert!(!allow.matches(&target(
+        "file_copy",
+        &json!({ "source": "/tmp/a", "destination": "/etc/b" })
+    )));
+}
+
+#[test]
+fn path_glob_fails_closed_when_a_path_tool_names_no_path() {
+    let deny = rule(
+        "*",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::PathGlob,
+        "/etc/**",
+    );
+    let allow = rule(
+        "*",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::PathGlob,
+        "**",
+    );
+    for input in [
+        json!({ "file_path": "/etc/passwd" }),
+        json!({ "path": "" }),
+        json!({ "source": ["/etc/passwd"] }),
+    ] {
+        assert!(deny.matches(&target("file_delete", &input)), "{input}");
+        assert!(!allow.matches(&target("file_delete", &input)), "{input}");
+    }
+
+    assert!(!deny.matches(&target("bash", &json!({ "command": "ls" }))));
+}
+
+#[test]
+fn scopes_limit_rules_to_employee_session_or_workspace() {
+    let input = json!({ "command": "npm test" });
+    let mut scoped = rule(
+        "bash",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::CommandGlob,
+        "npm test",
+    );
+
+    scoped.scope = ApprovalRuleScope::Employee("alice".to_string());
+    let mut call = target("bash", &input);
+    assert!(!scoped.matches(&call));
+    call.employee_id = Some("Alice");
+    assert!(scoped.matches(&call));
+
+    scoped.scope = ApprovalRuleScope::Session("sess-1".to_string());
+    call.session_id = Some("sess-2");
+    assert!(!scoped.matches(&call));
+
+    scoped.scope = ApprovalRuleScope::Workspace("C:\\work\\repo".to_string());
+    call.work_dir = Some(Path::new("C:/work/repo/packages/app"));
+    assert!(scoped.matches(&call));
+    call.work_dir = Some(Path::new("C:/work/repo-other"));
+    assert!(!scoped.matches(&call));
+}
+
+#[test]
+fn fingerprint_rules_keep_exact_allow_always_semantics() {
+    let input = json!({ "path": "E:\\workspace\\danger.txt", "recursive": true });
+    let fingerprint = approval_rule_fingerprint("file_delete", &input).expect("fingerprint");
+    let allow = rule(
+        "file_delete",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::Fingerprint,
+        &fingerprint,
+    );
+    assert_eq!(allow.storage_fingerprint(), fingerprint);
+    assert!(allow.matches(&target("file_delete", &input)));
+    assert!(!allow.matches(&target(
+        "file_delete",
+        &json!({ "path": "E:\\workspace\\other.txt", "recursive": true })
+    )));
+}
+
+#[test]
+fn validate_rejects_bad_patterns_and_fingerprint_denies() {
+    assert!(rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::CommandRegex,
+        "rm ("
+    )
+    .validate()
+    .is_err());
+    assert!(rule(
+        "bash",
+        ApprovalRuleEffect::Deny,
+        ApprovalRuleMatchKind::Fingerprint,
+        "{}"
+    )
+    .validate()
+    .is_err());
+    assert!(rule(
+        "",
+        ApprovalRuleEffect::Allow,
+        ApprovalRuleMatchKind::PathGlob,
+        "*"
+    )
+    .validate()
+    .is_err());
+}
+
+#[test]
+fn split_command_segments_handles_shell_separators() {
+    assert_eq!(
+        split_command_segments("a && b || c; d | e\nf & g"),
+        vec!["a", "b", "c", "d", "e", "f", "g"]
+    );
+    assert!(split_command_segments("  ").is_empty());
+}
@@ -1,6 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+mod model_catalog;
+
+pub use model_catalog::{ModelCatalog, ModelCatalogEntry, ModelCatalogPricing};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapabilityRouteTemplateInfo {
     pub template_id: String,
@@ -125,90 +129,21 @@ pub fn list_capability_route_templates_for(
 }
 
 pub fn default_model_for_protocol(protocol_type: &str) -> &'static str {
-    if protocol_type == "anthropic" {
-        "claude-3-5-haiku-20241022"
-    } else {
-        "gpt-4o-mini"
+    match protocol_type {
+        "anthropic" => "claude-3-5-haiku-20241022",
+        "gemini" => "gemini-2.5-flash",
+        "ollama" => "qwen2.5:7b",
+        _ => "gpt-4o-mini",
     }
 }
 
+/// 基于内置模型目录的推荐列表；需要叠加用户覆盖时使用 `ModelCatalog::recommended_models_for_provider`
 pub fn recommended_models_for_provider(provider_key: &str) -> Vec<String> {
-    match provider_key {
-        "doubao" => vec!["doubao-seed-1.6".to_string()],
-        "deepseek" => vec!["deepseek-chat".to_string(), "deepseek-reasoner".to_string()],
-        "qwen" => vec![
-            "qwen-max".to_string(),
-            "qwen-plus".to_string(),
-            "qwen-vl-max".to_string(),
-            "qwen-vl-plus".to_string(),
-            "qwen-tts".to_string(),
-            "qwen-omni".to_string(),
-        ],
-        "moonshot" => vec![
-            "kimi-k2".to_string(),
-            "moonshot-v1-32k".to_string(),
-            "moonshot-v1-128k".to_string(),
-        ],
-        "anthropic" => vec![
-            "claude-3-5-haiku-20241022".to_string(),
-            "claude-3-5-sonnet-20241022".to_string(),
-            "claude-sonnet-4-5-20250929".to_string(),
-        ],
-        _ => vec![
-            "gpt-4o-mini".to_string(),
-            "gpt-4.1-mini".to_string(),
-            "gpt-image-1".to_string(),
-            "whisper-1".to_string(),
-            "tts-1".to_string(),
-        ],
-    }
+    ModelCatalog::bundled().recommended_models_for_provider(provider_key)
 }
 
 pub fn filter_models_by_capability(models: Vec<String>, capability: Option<&str>) -> Vec<String> {
-    let Some(capability) = capability else {
-        return models;
-    };
-    let original = models.clone();
-    let filtered = if capability == "vision" {
-        models
-            .into_iter()
-            .filter(|m| {
-                m.contains("vl") || m.contains("4o") || m.contains("claude") || m.contains("omni")
-            })
-            .collect::<Vec<_>>()
-    } else if capability == "reasoning" {
-        models
-            .into_iter()
-            .filter(|m| m.contains("reasoner") || m.contains("k2") || m.contains("sonnet"))
-            .collect::<Vec<_>>()
-    } else if capability == "image_gen" {
-        models
-            .into_iter()
-            .filter(|m| {
-                m.contains("image")
-                    || m.contains("vl")
-                    || m.contains("omni")
-                    || m.contains("cogview")
-            })
-            .collect::<Vec<_>>()
-    } else if capability == "audio_stt" {
-        models
-            .into_iter()
-            .filter(|m| m.contains("whisper") || m.contains("stt") || m.contains("omni"))
-            .collect::<Vec<_>>()
-    } else if capability == "audio_tts" {
-        models
-            .into_iter()
-            .filter(|m| m.contains("tts") || m.contains("omni"))
-            .collect::<Vec<_>>()
-    } else {
-        models
-    };
-    if filtered.is_empty() {
-        original
-    } else {
-        filtered
-    }
+    ModelCatalog::bundled().filter_models_by_capability(models, capability)
 }
 
 pub fn cache_row_is_fresh(fetched_at: &str, ttl_seconds: i64) -> bool {
@@ -0,0 +1,242 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+const BUNDLED_MODEL_CATALOG: &str = include_str!("../data/model_catalog.json");
+
+/// 未知 provider 时推荐的模型沿用 OpenAI 兼容列表
+const DEFAULT_RECOMMENDED_PROVIDER: &str = "openai";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalogPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    #[serde(default)]
+    pub cache_read_per_million: f64,
+    #[serde(default)]
+    pub cache_write_per_million: f64,
+    #[serde(default = "default_pricing_currency")]
+    pub currency: String,
+}
+
+fn default_pricing_currency() -> String {
+    "USD".to_string()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalogEntry {
+    /// 精确模型名，或以 `*` 结尾的前缀（如 `gpt-4o*`）
+    pub model: String,
+    /// 推荐该模型的 provider_key；前缀条目通常留空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    /// chat / vision / reasoning / image_gen / audio_stt / audio_tts / tool_calling / json_mode
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelCatalogPricing>,
+    /// 停用日期（YYYY-MM-DD），过期后不再出现在推荐列表
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated_at: Option<String>,
+}
+
+impl ModelCatalogEntry {
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|item| item.eq_ignore_ascii_case(capability))
+    }
+
+    pub fn is_deprecated_on(&self, today: NaiveDate) -> bool {
+        self.deprecated_at
+            .as_deref()
+            .and_then(|value| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok())
+            .is_some_and(|deprecated_at| deprecated_at <= today)
+    }
+
+    fn is_pattern(&self) -> bool {
+        self.model.trim_end().ends_with('*')
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    pub version: u32,
+    pub models: Vec<ModelCatalogEntry>,
+}
+
+impl ModelCatalog {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        serde_json::from_str(raw).map_err(|e| format!("解析模型目录失败: {e}"))
+    }
+
+    /// 随应用打包的内置目录
+    pub fn bundled() -> &'static ModelCatalog {
+        static BUNDLED: OnceLock<ModelCatalog> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            Self::parse(BUNDLED_MODEL_CATALOG).expect("bundled model catalog must be valid")
+        })
+    }
+
+    /// 叠加用户覆盖：同名条目整体替换，新模型追加在末尾
+    pub fn with_overrides(
+        mut self,
+        overrides: impl IntoIterator<Item = ModelCatalogEntry>,
+    ) -> Self {
+        for entry in overrides {
+            match self
+                .models
+                .iter_mut()
+                .find(|existing| existing.model.eq_ignore_ascii_case(&entry.model))
+            {
+                Some(existing) => *existing = entry,
+                None => self.models.push(entry),
+            }
+        }
+        self
+    }
+
+    /// 匹配优先级：精确模型名优先于前缀，前缀越长越优先
+    pub fn lookup(&self, model_name: &str) -> Option<&ModelCatalogEntry> {
+        let model_name = model_name.trim().to_ascii_lowercase();
+        self.models
+            .iter()
+            .filter_map(|entry| {
+                let pattern = entry.model.trim().to_ascii_lowercase();
+                match pattern.strip_suffix('*') {
+                    Some(prefix) if model_name.starts_with(prefix) => {
+                        Some(((false, prefix.len()), entry))
+                    }
+                    Some(_) => None,
+                    None if pattern == model_name => Some(((true, pattern.len()), entry)),
+                    None => None,
+                }
+            })
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, entry)| entry)
+    }
+
+    /// 目录未收录该模型时返回 None，由调用方决定默认行为
+    pub fn supports(&self, model_name: &str, capability: &str) -> Option<bool> {
+        self.lookup(model_name)
+            .map(|entry| entry.supports(capability))
+    }
+
+    pub fn recommended_models_for_provider(&self, provider_key: &str) -> Vec<String> {
+        let today = Utc::now().date_naive();
+        let recommended = |provider_key: &str| {
+            self.models
+                .iter()
+                .filter(|entry| !entry.is_pattern() && !entry.is_deprecated_on(today))
+                .filter(|entry| {
+                    entry
+                        .providers
+                        .iter()
+                        .any(|item| item.eq_ignore_ascii_case(provider_key))
+                })
+                .map(|entry| entry.model.clone())
+                .collect::<Vec<_>>()
+        };
+        let models = recommended(provider_key);
+        if models.is_empty() {
+            recommended(DEFAULT_RECOMMENDED_PROVIDER)
+        } else {
+            models
+        }
+    }
+
+    /// 按目录声明的能力过滤模型；没有任何模型匹配时保留原列表，避免下拉框为空
+    pub fn filter_models_by_capability(
+        &self,
+        models: Vec<String>,
+        capability: Option<&str>,
+    ) -> Vec<String> {
+        let Some(capability) = capability.filter(|value| !value.trim().is_empty()) else {
+            return models;
+        };
+        if capability == "chat" {
+            return models;
+        }
+        let filtered = models
+            .iter()
+            .filter(|model| self.supports(model, capability) == Some(true))
+            .cloned()
+            .collect::<Vec<_>>();
+        if filtered.is_empty() {
+            models
+        } else {
+            filtered
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_prefers_exact_entries_over_prefix_patterns() {
+        let catalog = ModelCatalog::bundled();
+
+        let exact = catalog.lookup("gpt-4o-mini-tts").expect("exact entry");
+        assert!(exact.supports("audio_tts"));
+        assert!(!exact.supports("vision"));
+
+        let prefixed = catalog.lookup("gpt-4o-2024-08-06").expect("prefix entry");
+        assert_eq!(prefixed.model, "gpt-4o*");
+        assert!(prefixed.supports("vision"));
+        assert!(catalog.lookup("totally-unknown-model").is_none());
+    }
+
+    #[test]
+    fn overrides_replace_bundled_entries_and_add_new_models() {
+        let catalog = ModelCatalog::bundled().clone().with_overrides([
+            ModelCatalogEntry {
+                model: "qwen-max".to_string(),
+                providers: vec!["qwen".to_string()],
+                context_window: Some(262_144),
+                capabilities: vec!["chat".to_string(), "vision".to_string()],
+                ..ModelCatalogEntry::default()
+            },
+            ModelCatalogEntry {
+                model: "my-local-model".to_string(),
+                providers: vec!["ollama".to_string()],
+                context_window: Some(8_192),
+                capabilities: vec!["chat".to_string()],
+                ..ModelCatalogEntry::default()
+            },
+        ]);
+
+        assert_eq!(
+            catalog
+                .lookup("qwen-max")
+                .and_then(|entry| entry.context_window),
+            Some(262_144)
+        );
+        assert_eq!(catalog.supports("qwen-max", "vision"), Some(true));
+        assert!(catalog
+            .recommended_models_for_provider("ollama")
+            .contains(&"my-local-model".to_string()));
+    }
+
+    #[test]
+    fn deprecated_models_are_not_recommended() {
+        let entry = ModelCatalogEntry {
+            model: "old-model".to_string(),
+            deprecated_at: Some("2025-01-01".to_string()),
+            ..ModelCatalogEntry::default()
+        };
+        let before = NaiveDate::from_ymd_opt(2024, 12, 31).expect("date");
+        let after = NaiveDate::from_ymd_opt(2025, 1, 1).expect("date");
+        assert!(!entry.is_deprecated_on(before));
+        assert!(entry.is_deprecated_on(after));
+
+        let anthropic = ModelCatalog::bundled().recommended_models_for_provider("anthropic");
+        assert!(!anthropic.contains(&"claude-3-5-sonnet-20241022".to_string()));
+        assert!(anthropic.contains(&"claude-sonnet-4-5-20250929".to_string()));
+    }
+}
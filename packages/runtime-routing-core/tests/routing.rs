@@ -19,6 +19,7 @@ fn default_model_depends_on_protocol() {
         "claude-3-5-haiku-20241022"
     );
     assert_eq!(default_model_for_protocol("openai"), "gpt-4o-mini");
+    assert_eq!(default_model_for_protocol("gemini"), "gemini-2.5-flash");
 }
 
 #[test]
@@ -31,6 +32,16 @@ fn recommended_models_cover_known_providers() {
 
     let doubao = recommended_models_for_provider("doubao");
     assert!(doubao.iter().any(|m| m == "doubao-seed-1.6"));
+
+    let gemini = recommended_models_for_provider("gemini");
+    assert!(gemini.iter().any(|m| m == "gemini-2.5-flash"));
+
+    let ollama = recommended_models_for_provider("ollama");
+    assert!(ollama.iter().any(|m| m == "qwen2.5:7b"));
+    assert_eq!(
+        filter_models_by_capability(ollama, Some("vision")),
+        vec!["llava:7b".to_string()]
+    );
 }
 
 #[test]
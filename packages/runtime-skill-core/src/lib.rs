@@ -12,4 +12,5 @@ pub use builtin_skills::{
 pub use skill_config::{
     McpServerDep, OpenClawSkillMetadata, OpenClawSkillMetadataRequires, SkillCommandArgMode,
     SkillCommandDispatchKind, SkillCommandDispatchSpec, SkillConfig, SkillInvocationPolicy,
+    SkillRunBudget,
 };
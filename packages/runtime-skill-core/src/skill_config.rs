@@ -12,6 +12,25 @@ pub struct McpServerDep {
     pub env: Option<Vec<String>>,
 }
 
+/// Per-run resource budget declared in front matter. Every limit is optional;
+/// an omitted limit means the run is not capped on that dimension.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[serde(default)]
+pub struct SkillRunBudget {
+    #[serde(alias = "max-input-tokens")]
+    pub max_input_tokens: Option<u64>,
+    #[serde(alias = "max-output-tokens")]
+    pub max_output_tokens: Option<u64>,
+    #[serde(alias = "max-cost")]
+    pub max_cost: Option<f64>,
+    #[serde(alias = "max-wall-clock-secs", alias = "max_duration_secs")]
+    pub max_wall_clock_secs: Option<u64>,
+    #[serde(alias = "max-tool-calls")]
+    pub max_tool_calls: Option<u64>,
+    #[serde(alias = "warning-ratio")]
+    pub warning_ratio: Option<f64>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
 enum AllowedToolsValue {
@@ -64,6 +83,7 @@ impl AllowedToolsValue {
 pub struct SkillConfig {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub version: Option<String>,
     pub allowed_tools: Option<Vec<String>>,
     pub denied_tools: Option<Vec<String>>,
     pub requires_toolsets: Option<Vec<String>>,
@@ -75,6 +95,9 @@ pub struct SkillConfig {
     pub denied_tool_categories: Option<Vec<String>>,
     pub model: Option<String>,
     pub max_iterations: Option<usize>,
+    pub budget: Option<SkillRunBudget>,
+    /// JSON Schema the final answer must satisfy, declared as `output_schema`.
+    pub output_schema: Option<JsonValue>,
     pub argument_hint: Option<String>,
     pub disable_model_invocation: bool,
     pub user_invocable: bool,
@@ -211,6 +234,7 @@ impl Default for SkillConfig {
         Self {
             name: None,
             description: None,
+            version: None,
             allowed_tools: None,
             denied_tools: None,
             requires_toolsets: None,
@@ -222,6 +246,8 @@ impl Default for SkillConfig {
             denied_tool_categories: None,
             model: None,
             max_iterations: None,
+            budget: None,
+            output_schema: None,
             argument_hint: None,
             disable_model_invocation: false,
             user_invocable: true,
@@ -240,6 +266,7 @@ impl Default for SkillConfig {
 struct FrontMatter {
     name: Option<String>,
     description: Option<String>,
+    version: Option<serde_yaml::Value>,
     allowed_tools: Option<AllowedToolsValue>,
     denied_tools: Option<AllowedToolsValue>,
     #[serde(alias = "requires-toolsets", alias = "required_toolsets")]
@@ -254,6 +281,10 @@ struct FrontMatter {
     denied_tool_categories: Option<AllowedToolsValue>,
     model: Option<String>,
     max_iterations: Option<usize>,
+    #[serde(alias = "run_budget", alias = "run-budget")]
+    budget: Option<SkillRunBudget>,
+    #[serde(alias = "output-schema")]
+    output_schema: Option<serde_yaml::Value>,
     #[serde(alias = "argument-hint")]
     argument_hint: Option<String>,
     #[serde(alias = "disable-model-invocation", default)]
@@ -289,33 +320,33 @@ fn resolve_frontmatter_bool(value: Option<FrontMatterBoolValue>, default: bool)
 
 impl SkillConfig {
     pub fn parse(content: &str) -> Self {
-        if !content.starts_with("---") {
+        let Some((yaml_str, system_prompt)) = split_front_matter(content) else {
             return Self {
                 system_prompt: content.to_string(),
                 ..Default::default()
             };
-        }
-
-        let rest = &content[3..];
-        let end_pos = match rest.find("\n---") {
-            Some(pos) => pos,
-            None => {
-                return Self {
-                    system_prompt: content.to_string(),
-                    ..Default::default()
-                };
-            }
         };
+        let fm: FrontMatter = serde_yaml::from_str(yaml_str).unwrap_or_default();
+        Self::from_front_matter(fm, system_prompt)
+    }
 
-        let yaml_str = &rest[..end_pos];
-        let prompt_start = 3 + end_pos + 4;
-        let system_prompt = if prompt_start < content.len() {
-            content[prompt_start..].trim_start_matches('\n').to_string()
+    /// Strict variant of [`SkillConfig::parse`] that reports malformed front
+    /// matter instead of silently falling back to defaults.
+    pub fn try_parse(content: &str) -> Result<Self, String> {
+        if !content.starts_with("---") {
+            return Ok(Self::parse(content));
+        }
+        let (yaml_str, system_prompt) =
+            split_front_matter(content).ok_or_else(|| "front matter is not closed".to_string())?;
+        let fm: FrontMatter = if yaml_str.trim().is_empty() {
+            FrontMatter::default()
         } else {
-            String::new()
+            serde_yaml::from_str(yaml_str).map_err(|e| format!("invalid front matter: {e}"))?
         };
+        Ok(Self::from_front_matter(fm, system_prompt))
+    }
 
-        let fm: FrontMatter = serde_yaml::from_str(yaml_str).unwrap_or_default();
+    fn from_front_matter(fm: FrontMatter, system_prompt: String) -> Self {
         let user_invocable = resolve_frontmatter_bool(fm.user_invocable, true);
         let disable_model_invocation = resolve_frontmatter_bool(fm.disable_model_invocation, false);
         let invocation = SkillInvocationPolicy {
@@ -332,6 +363,7 @@ impl SkillConfig {
         Self {
             name: fm.name,
             description: fm.description,
+            version: fm.version.as_ref().and_then(yaml_scalar_string),
             allowed_tools: fm.allowed_tools.map(|v| v.into_vec()),
             denied_tools: fm.denied_tools.map(|v| v.into_vec()),
             requires_toolsets: fm.requires_toolsets.map(|v| v.into_vec()),
@@ -343,6 +375,8 @@ impl SkillConfig {
             denied_tool_categories: fm.denied_tool_categories.map(|v| v.into_vec()),
             model: fm.model,
             max_iterations: fm.max_iterations,
+            budget: fm.budget,
+            output_schema: fm.output_schema.as_ref().and_then(parse_output_schema),
             argument_hint: fm.argument_hint,
             disable_model_invocation,
             user_invocable,
@@ -377,6 +411,29 @@ impl SkillConfig {
     }
 }
 
+/// Split `---` delimited front matter into its YAML text and the prompt body.
+fn split_front_matter(content: &str) -> Option<(&str, String)> {
+    let rest = content.strip_prefix("---")?;
+    let end_pos = rest.find("\n---")?;
+    let prompt_start = 3 + end_pos + 4;
+    let system_prompt = if prompt_start < content.len() {
+        content[prompt_start..].trim_start_matches('\n').to_string()
+    } else {
+        String::new()
+    };
+    Some((&rest[..end_pos], system_prompt))
+}
+
+/// Versions are often written unquoted (`version: 1.0`), so accept numbers too.
+fn yaml_scalar_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(raw) => Some(raw.trim().to_string()),
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+    .filter(|value| !value.is_empty())
+}
+
 fn yaml_string_list(value: Option<&JsonValue>) -> Vec<String> {
     match value {
         Some(JsonValue::Array(values)) => values
@@ -477,6 +534,16 @@ fn parse_metadata_json_value(value: &serde_yaml::Value) -> Option<JsonValue> {
     }
 }
 
+/// Accepts the schema either as a YAML mapping or as an inline JSON string;
+/// anything that is not an object is ignored.
+fn parse_output_schema(value: &serde_yaml::Value) -> Option<JsonValue> {
+    let schema = match value {
+        serde_yaml::Value::String(raw) => json5::from_str::<JsonValue>(raw).ok()?,
+        _ => serde_json::to_value(value).ok()?,
+    };
+    schema.is_object().then_some(schema)
+}
+
 fn parse_openclaw_metadata_block(value: &serde_yaml::Value) -> Option<OpenClawSkillMetadata> {
     let JsonValue::Object(metadata) = parse_metadata_json_value(value)? else {
         return None;
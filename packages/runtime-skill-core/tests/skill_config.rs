@@ -409,3 +409,63 @@ fn parse_allowed_tool_sources_from_comma_separated_frontmatter() {
         ])
     );
 }
+
+#[test]
+fn parse_version_and_multiline_description() {
+    let content =
+        "---\nname: multi\nversion: 1.2\ndescription: |\n  First line.\n  Second line.\n---\nBody";
+    let config = SkillConfig::parse(content);
+    assert_eq!(config.version.as_deref(), Some("1.2"));
+    assert_eq!(
+        config.description.as_deref(),
+        Some("First line.\nSecond line.")
+    );
+}
+
+#[test]
+fn try_parse_rejects_malformed_front_matter() {
+    assert!(SkillConfig::try_parse("---\nname: [unclosed\n---\nBody").is_err());
+    assert!(SkillConfig::try_parse("---\nname: open\nBody without closing").is_err());
+    let config = SkillConfig::try_parse("No front matter").unwrap();
+    assert_eq!(config.system_prompt, "No front matter");
+}
+
+#[test]
+fn parse_run_budget_block_with_kebab_case_aliases() {
+    let content = "---\nname: budgeted\nbudget:\n  max_input_tokens: 200000\n  max-output-tokens: 20000\n  max_cost: 0.5\n  max-wall-clock-secs: 600\n  max_tool_calls: 40\n---\nBody";
+    let config = SkillConfig::parse(content);
+    let budget = config.budget.expect("budget should parse");
+    assert_eq!(budget.max_input_tokens, Some(200_000));
+    assert_eq!(budget.max_output_tokens, Some(20_000));
+    assert_eq!(budget.max_cost, Some(0.5));
+    assert_eq!(budget.max_wall_clock_secs, Some(600));
+    assert_eq!(budget.max_tool_calls, Some(40));
+    assert_eq!(budget.warning_ratio, None);
+
+    assert!(SkillConfig::parse("---\nname: plain\n---\nBody")
+        .budget
+        .is_none());
+}
+
+#[test]
+fn parse_output_schema_from_mapping_or_inline_json() {
+    let content = "---\nname: report\noutput_schema:\n  type: object\n  required: [employee, daily_count]\n  properties:\n    employee:\n      type: string\n    daily_count:\n      type: integer\n---\nBody";
+    let schema = SkillConfig::parse(content)
+        .output_schema
+        .expect("schema should parse");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"][1], "daily_count");
+    assert_eq!(schema["properties"]["daily_count"]["type"], "integer");
+
+    let inline = "---\nname: inline\noutput-schema: '{\"type\": \"object\", \"properties\": {\"ok\": {\"type\": \"boolean\"}}}'\n---\nBody";
+    let schema = SkillConfig::parse(inline)
+        .output_schema
+        .expect("inline schema should parse");
+    assert_eq!(schema["properties"]["ok"]["type"], "boolean");
+
+    assert!(
+        SkillConfig::parse("---\nname: bad\noutput_schema: not json\n---\nBody")
+            .output_schema
+            .is_none()
+    );
+}
@@ -9,19 +9,58 @@ use sha2::Sha256;
 
 const PBKDF2_ITERATIONS: u32 = 100_000;
 const VERIFY_PLAINTEXT: &[u8] = b"SKILLMINT_OK";
+/// Bytes [`encrypt`] adds to the plaintext: 12-byte nonce + 16-byte GCM tag
+pub const CIPHERTEXT_OVERHEAD: u64 = 12 + 16;
 
+/// Username-derived key of single-user packs built before envelope
+/// encryption; the salt covers both the skill id and the skill name.
 pub fn derive_key(username: &str, skill_id: &str, skill_name: &str) -> [u8; 32] {
+    pbkdf2_key(username, &[skill_id.as_bytes(), skill_name.as_bytes()])
+}
+
+/// Key-encryption key for a username recipient. Salted with the stable skill
+/// id only, so renaming a skill between versions keeps recipients working.
+pub fn derive_user_key(username: &str, skill_id: &str) -> [u8; 32] {
+    pbkdf2_key(username, &[b"user:", skill_id.as_bytes()])
+}
+
+/// Key-encryption key for a shared team passphrase. Salted separately from
+/// [`derive_user_key`] so a passphrase never collides with a username.
+pub fn derive_team_key(passphrase: &str, skill_id: &str) -> [u8; 32] {
+    pbkdf2_key(passphrase, &[b"team:", skill_id.as_bytes()])
+}
+
+fn pbkdf2_key(secret: &str, salt_parts: &[&[u8]]) -> [u8; 32] {
     use sha2::Digest;
     let mut hasher = sha2::Sha256::new();
-    hasher.update(skill_id.as_bytes());
-    hasher.update(skill_name.as_bytes());
+    for part in salt_parts {
+        hasher.update(part);
+    }
     let salt = hasher.finalize();
 
     let mut key = [0u8; 32];
-    pbkdf2_hmac::<Sha256>(username.as_bytes(), &salt, PBKDF2_ITERATIONS, &mut key);
+    pbkdf2_hmac::<Sha256>(secret.as_bytes(), &salt, PBKDF2_ITERATIONS, &mut key);
     key
 }
 
+/// Random per-pack content key used to encrypt the skill files.
+pub fn generate_content_key() -> [u8; 32] {
+    Aes256Gcm::generate_key(&mut OsRng).into()
+}
+
+pub fn wrap_key(content_key: &[u8; 32], kek: &[u8; 32]) -> Result<String> {
+    Ok(B64.encode(encrypt(content_key, kek)?))
+}
+
+pub fn unwrap_key(wrapped: &str, kek: &[u8; 32]) -> Result<[u8; 32]> {
+    let data = B64
+        .decode(wrapped)
+        .map_err(|e| anyhow!("invalid wrapped key: {e}"))?;
+    decrypt(&data, kek)?
+        .try_into()
+        .map_err(|_| anyhow!("wrapped key has wrong length"))
+}
+
 pub fn encrypt(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
     let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
@@ -95,6 +134,39 @@ mod tests {
         assert!(decrypt(&encrypted, &key2).is_err());
     }
 
+    #[test]
+    fn test_team_key_differs_from_user_key() {
+        let user = derive_user_key("team-secret", "id");
+        let team = derive_team_key("team-secret", "id");
+        assert_ne!(user, team);
+    }
+
+    #[test]
+    fn test_recipient_keys_depend_on_skill_id_only() {
+        assert_eq!(
+            derive_user_key("alice", "id"),
+            derive_user_key("alice", "id")
+        );
+        assert_ne!(
+            derive_user_key("alice", "id"),
+            derive_user_key("alice", "other")
+        );
+        assert_ne!(
+            derive_team_key("team-secret", "id"),
+            derive_team_key("team-secret", "other")
+        );
+    }
+
+    #[test]
+    fn test_wrap_unwrap_content_key() {
+        let content_key = generate_content_key();
+        let kek = derive_user_key("alice", "id");
+        let wrapped = wrap_key(&content_key, &kek).unwrap();
+        assert_eq!(unwrap_key(&wrapped, &kek).unwrap(), content_key);
+        let other = derive_user_key("bob", "id");
+        assert!(unwrap_key(&wrapped, &other).is_err());
+    }
+
     #[test]
     fn test_verify_token_roundtrip() {
         let key = derive_key("alice", "id", "name");
@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::crypto::{decrypt, encrypt};
+use crate::envelope::unlock_content_key;
+use crate::pack::{build_manifest, read_skill_files, write_pack};
+use crate::signing::sha256_hex;
+use crate::types::{PackConfig, SkillManifest};
+use crate::unpack::read_manifest;
+
+#[derive(Debug, Clone)]
+pub struct DeltaSummary {
+    pub manifest: SkillManifest,
+    /// Files shipped in the delta because they are new or changed
+    pub changed_files: Vec<String>,
+    /// Files present in the base version but not in the new one
+    pub removed_files: Vec<String>,
+}
+
+fn encrypted_entry_name(rel: &str) -> String {
+    format!("encrypted/{rel}.enc")
+}
+
+/// Build a delta pack for `config` that only carries files changed since the
+/// pack at `base_pack_path`. Unchanged files reuse the base ciphertext, so the
+/// delta manifest lists the complete file set of the new version.
+///
+/// `credential` must unlock the base pack; the delta keeps its content key
+/// and recipients.
+pub fn pack_delta(
+    config: &PackConfig,
+    base_pack_path: &str,
+    credential: &str,
+) -> Result<DeltaSummary> {
+    let base = read_manifest(base_pack_path)?;
+    if let Some(id) = config.skill_id.as_deref() {
+        if id != base.id {
+            return Err(anyhow!(
+                "skill id {id} does not match base pack {}",
+                base.id
+            ));
+        }
+    }
+    if base.delta_base_version.is_some() {
+        return Err(anyhow!("base pack must be a full skillpack, not a delta"));
+    }
+    if config.version == base.version {
+        return Err(anyhow!(
+            "delta version must differ from base {}",
+            base.version
+        ));
+    }
+    let key = unlock_content_key(&base, credential)?;
+
+    let mut base_zip = zip::ZipArchive::new(fs::File::open(base_pack_path)?)?;
+    let mut base_ciphertexts = HashMap::new();
+    for rel in base.file_hashes.keys() {
+        let mut entry = base_zip.by_name(&encrypted_entry_name(rel))?;
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf)?;
+        base_ciphertexts.insert(rel.clone(), buf);
+    }
+
+    let (skill_config, files) = read_skill_files(config)?;
+    let mut all_files = Vec::new();
+    let mut changed_files = Vec::new();
+    for (rel, plaintext) in files {
+        let reused = base_ciphertexts
+            .get(&rel)
+            .filter(|ciphertext| decrypt(ciphertext, &key).ok().as_ref() == Some(&plaintext));
+        match reused {
+            Some(ciphertext) => all_files.push((rel, ciphertext.clone())),
+            None => {
+                changed_files.push(rel.clone());
+                all_files.push((rel, encrypt(&plaintext, &key)?));
+            }
+        }
+    }
+    let removed_files = base
+        .file_hashes
+        .keys()
+        .filter(|rel| !all_files.iter().any(|(r, _)| r == *rel))
+        .cloned()
+        .collect();
+
+    let manifest = SkillManifest {
+        id: base.id.clone(),
+        encrypted_verify: base.encrypted_verify.clone(),
+        username_hint: base.username_hint.clone(),
+        recipients: base.recipients.clone(),
+        delta_base_version: Some(base.version.clone()),
+        ..build_manifest(config, &skill_config, &all_files)
+    };
+    let delta_files: Vec<_> = all_files
+        .into_iter()
+        .filter(|(rel, _)| changed_files.contains(rel))
+        .collect();
+    write_pack(config, &manifest, &delta_files)?;
+
+    Ok(DeltaSummary {
+        manifest,
+        changed_files,
+        removed_files,
+    })
+}
+
+/// Combine a base pack and a delta into a full pack at `output_path`.
+///
+/// The delta's manifest and signature are copied verbatim, so the result
+/// verifies exactly like a freshly built pack of the new version.
+pub fn apply_delta(
+    base_pack_path: &str,
+    delta_pack_path: &str,
+    output_path: &str,
+) -> Result<SkillManifest> {
+    let base = read_manifest(base_pack_path)?;
+    let delta = read_manifest(delta_pack_path)?;
+    let Some(delta_base_version) = delta.delta_base_version.as_deref() else {
+        return Err(anyhow!("{delta_pack_path} is not a delta skillpack"));
+    };
+    if delta.id != base.id {
+        return Err(anyhow!("delta is for skill {}, not {}", delta.id, base.id));
+    }
+    if delta_base_version != base.version {
+        return Err(anyhow!(
+            "delta requires version {delta_base_version}, installed pack is {}",
+            base.version
+        ));
+    }
+
+    let mut base_zip = zip::ZipArchive::new(fs::File::open(base_pack_path)?)?;
+    let mut delta_zip = zip::ZipArchive::new(fs::File::open(delta_pack_path)?)?;
+    let tmp_path = Path::new(output_path).with_extension("skillpack.tmp");
+    let mut out = zip::ZipWriter::new(fs::File::create(&tmp_path)?);
+
+    for name in ["manifest.json", "signature.json"] {
+        if let Some(index) = delta_zip.index_for_name(name) {
+            out.raw_copy_file(delta_zip.by_index_raw(index)?)?;
+        }
+    }
+    for (rel, hash) in &delta.file_hashes {
+        let name = encrypted_entry_name(rel);
+        if let Some(index) = delta_zip.index_for_name(&name) {
+            out.raw_copy_file(delta_zip.by_index_raw(index)?)?;
+            continue;
+        }
+        if base.file_hashes.get(rel) != Some(hash) {
+            return Err(anyhow!("base pack is missing unchanged file {rel}"));
+        }
+        let index = base_zip
+            .index_for_name(&name)
+            .ok_or_else(|| anyhow!("base pack is missing unchanged file {rel}"))?;
+        let mut buf = Vec::new();
+        base_zip.by_index(index)?.read_to_end(&mut buf)?;
+        if sha256_hex(&buf) != *hash {
+            return Err(anyhow!("skillpack file {rel} was modified"));
+        }
+        out.raw_copy_file(base_zip.by_index_raw(index)?)?;
+    }
+    out.finish()?;
+
+    fs::rename(&tmp_path, output_path)?;
+    Ok(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::pack;
+    use crate::unpack::verify_and_unpack;
+    use tempfile::tempdir;
+
+    fn config(dir: &Path, version: &str, skill_id: Option<String>, output: &str) -> PackConfig {
+        PackConfig {
+            dir_path: dir.join("skill").to_string_lossy().to_string(),
+            name: "Delta".to_string(),
+            description: "".to_string(),
+            version: version.to_string(),
+            author: "".to_string(),
+            username: "alice".to_string(),
+            recommended_model: "".to_string(),
+            output_path: dir.join(output).to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id,
+        }
+    }
+
+    #[test]
+    fn test_delta_roundtrip_only_ships_changed_files() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("skill");
+        fs::create_dir_all(skill_dir.join("data")).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: Delta\n---\nv1").unwrap();
+        fs::write(skill_dir.join("data/big.csv"), "a,b\n1,2\n").unwrap();
+        fs::write(skill_dir.join("old.md"), "gone soon").unwrap();
+
+        let v1 = config(dir.path(), "1.0.0", None, "v1.skillpack");
+        pack(&v1).unwrap();
+        let base = read_manifest(&v1.output_path).unwrap();
+
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: Delta\n---\nv2").unwrap();
+        fs::remove_file(skill_dir.join("old.md")).unwrap();
+        let mut v2 = config(dir.path(), "1.1.0", Some(base.id.clone()), "v2.delta");
+        // Recipient keys only depend on the skill id, so a rename keeps them valid
+        v2.name = "Delta Renamed".to_string();
+        let summary = pack_delta(&v2, &v1.output_path, "alice").unwrap();
+        assert_eq!(summary.changed_files, vec!["SKILL.md"]);
+        assert_eq!(summary.removed_files, vec!["old.md"]);
+
+        // A delta alone is incomplete
+        assert!(verify_and_unpack(&v2.output_path, "alice").is_err());
+
+        let full = dir
+            .path()
+            .join("v2.skillpack")
+            .to_string_lossy()
+            .to_string();
+        apply_delta(&v1.output_path, &v2.output_path, &full).unwrap();
+        let unpacked = verify_and_unpack(&full, "alice").unwrap();
+        assert_eq!(unpacked.manifest.id, base.id);
+        assert_eq!(unpacked.manifest.version, "1.1.0");
+        assert_eq!(unpacked.manifest.name, "Delta Renamed");
+        assert_eq!(unpacked.files["data/big.csv"], b"a,b\n1,2\n");
+        assert!(String::from_utf8_lossy(&unpacked.files["SKILL.md"]).contains("v2"));
+        assert!(!unpacked.files.contains_key("old.md"));
+    }
+
+    #[test]
+    fn test_apply_delta_rejects_wrong_base_version() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: Delta\n---\nv1").unwrap();
+
+        let v1 = config(dir.path(), "1.0.0", None, "v1.skillpack");
+        pack(&v1).unwrap();
+        let id = read_manifest(&v1.output_path).unwrap().id;
+        let v2 = config(dir.path(), "2.0.0", Some(id.clone()), "v2.skillpack");
+        pack(&v2).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: Delta\n---\nv3").unwrap();
+        let v3 = config(dir.path(), "3.0.0", Some(id), "v3.delta");
+        pack_delta(&v3, &v2.output_path, "alice").unwrap();
+
+        let out = dir
+            .path()
+            .join("out.skillpack")
+            .to_string_lossy()
+            .to_string();
+        let err = apply_delta(&v1.output_path, &v3.output_path, &out).unwrap_err();
+        assert!(err.to_string().contains("requires version 2.0.0"));
+    }
+}
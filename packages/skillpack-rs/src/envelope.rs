@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+use crate::crypto::{
+    check_verify_token, derive_key, derive_team_key, derive_user_key, unwrap_key, wrap_key,
+};
+use crate::signing::PublisherKey;
+use crate::types::{Recipient, RecipientEntry, RecipientKind, SkillManifest};
+use crate::unpack::read_manifest;
+
+pub const WRONG_CREDENTIAL_ERROR: &str = "用户名错误，无法解密此 Skill";
+
+pub fn wrap_for_recipient(
+    content_key: &[u8; 32],
+    recipient: &Recipient,
+    skill_id: &str,
+) -> Result<RecipientEntry> {
+    let (kind, hint, kek) = match recipient {
+        Recipient::User(username) => (
+            RecipientKind::User,
+            username.clone(),
+            derive_user_key(username, skill_id),
+        ),
+        Recipient::Team { label, passphrase } => (
+            RecipientKind::Team,
+            label.clone(),
+            derive_team_key(passphrase, skill_id),
+        ),
+    };
+    Ok(RecipientEntry {
+        kind,
+        hint,
+        wrapped_key: wrap_key(content_key, &kek)?,
+    })
+}
+
+/// Recover the content key for `credential`, which may be a username or a
+/// team passphrase. Legacy packs without recipients use the username-derived
+/// key directly.
+pub fn unlock_content_key(manifest: &SkillManifest, credential: &str) -> Result<[u8; 32]> {
+    if manifest.recipients.is_empty() {
+        let legacy_key = derive_key(credential, &manifest.id, &manifest.name);
+        if check_verify_token(&manifest.encrypted_verify, &legacy_key) {
+            return Ok(legacy_key);
+        }
+        return Err(anyhow!(WRONG_CREDENTIAL_ERROR));
+    }
+
+    let user_kek = derive_user_key(credential, &manifest.id);
+    let mut team_kek = None;
+    for entry in &manifest.recipients {
+        let kek = match entry.kind {
+            RecipientKind::User => &user_kek,
+            RecipientKind::Team => {
+                team_kek.get_or_insert_with(|| derive_team_key(credential, &manifest.id))
+            }
+        };
+        if let Ok(content_key) = unwrap_key(&entry.wrapped_key, kek) {
+            if check_verify_token(&manifest.encrypted_verify, &content_key) {
+                return Ok(content_key);
+            }
+        }
+    }
+    Err(anyhow!(WRONG_CREDENTIAL_ERROR))
+}
+
+/// Grant `recipient` access to an existing pack. `credential` must unlock the
+/// pack already; files are not re-encrypted.
+///
+/// Signed packs must be re-signed, so `signing_key` is required for them.
+pub fn add_recipient(
+    pack_path: &str,
+    credential: &str,
+    recipient: &Recipient,
+    signing_key: Option<&PublisherKey>,
+) -> Result<SkillManifest> {
+    let mut manifest = read_manifest(pack_path)?;
+    if manifest.recipients.is_empty() {
+        return Err(anyhow!(
+            "skillpack uses single-user encryption; repack it to add recipients"
+        ));
+    }
+    let content_key = unlock_content_key(&manifest, credential)?;
+    let entry = wrap_for_recipient(&content_key, recipient, &manifest.id)?;
+    manifest
+        .recipients
+        .retain(|e| !(e.kind == entry.kind && e.hint == entry.hint));
+    manifest.recipients.push(entry);
+    rewrite_manifest(pack_path, &mut manifest, signing_key)?;
+    Ok(manifest)
+}
+
+/// Remove every recipient entry with the given hint.
+///
+/// This only stops future unlocks from this pack file: anyone who already
+/// unwrapped the content key can still decrypt it. Repack to rotate the key.
+pub fn revoke_recipient(
+    pack_path: &str,
+    hint: &str,
+    signing_key: Option<&PublisherKey>,
+) -> Result<SkillManifest> {
+    let mut manifest = read_manifest(pack_path)?;
+    let before = manifest.recipients.len();
+    manifest.recipients.retain(|e| e.hint != hint);
+    if manifest.recipients.len() == before {
+        return Err(anyhow!("recipient {hint} not found in skillpack"));
+    }
+    if manifest.recipients.is_empty() {
+        return Err(anyhow!("cannot revoke the last recipient of a skillpack"));
+    }
+    if manifest.username_hint.as_deref() == Some(hint) {
+        manifest.username_hint = manifest
+            .recipients
+            .iter()
+            .find(|e| e.kind == RecipientKind::User)
+            .map(|e| e.hint.clone());
+    }
+    rewrite_manifest(pack_path, &mut manifest, signing_key)?;
+    Ok(manifest)
+}
+
+/// Replace manifest.json (and signature.json) while copying encrypted entries verbatim.
+fn rewrite_manifest(
+    pack_path: &str,
+    manifest: &mut SkillManifest,
+    signing_key: Option<&PublisherKey>,
+) -> Result<()> {
+    match signing_key {
+        Some(key) => manifest.publisher_key_id = Some(key.key_id()),
+        None if manifest.publisher_key_id.is_some() => {
+            return Err(anyhow!(
+                "skillpack is signed; a signing key is required to update it"
+            ));
+        }
+        None => {}
+    }
+    let manifest_bytes = serde_json::to_vec_pretty(manifest)?;
+
+    let path = Path::new(pack_path);
+    let tmp_path = path.with_extension("skillpack.tmp");
+    let mut source = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut zip = zip::ZipWriter::new(fs::File::create(&tmp_path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(&manifest_bytes)?;
+    if let Some(key) = signing_key {
+        zip.start_file("signature.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&key.sign(&manifest_bytes))?.as_bytes())?;
+    }
+    for i in 0..source.len() {
+        let entry = source.by_index_raw(i)?;
+        if entry.name() == "manifest.json" || entry.name() == "signature.json" {
+            continue;
+        }
+        zip.raw_copy_file(entry)?;
+    }
+    zip.finish()?;
+    drop(source);
+
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
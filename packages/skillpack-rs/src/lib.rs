@@ -1,8 +1,20 @@
 pub mod crypto;
+pub mod delta;
+pub mod envelope;
 pub mod pack;
+pub mod signing;
 pub mod types;
 pub mod unpack;
 
+pub use delta::{apply_delta, pack_delta, DeltaSummary};
+pub use envelope::{add_recipient, revoke_recipient};
 pub use pack::pack;
-pub use types::{FrontMatter, PackConfig, SkillManifest};
-pub use unpack::verify_and_unpack;
+pub use signing::{PublisherKey, SignatureReport, TrustStatus, TrustedKeyring, TrustedPublisher};
+pub use types::{
+    FrontMatter, PackConfig, PackSignature, Recipient, RecipientEntry, RecipientKind,
+    SkillManifest, SkillRequirements,
+};
+pub use unpack::{
+    read_manifest, unpack_to_dir, verify_and_unpack, verify_and_unpack_with_keyring, UnpackLimits,
+    UnpackProgress, UnpackReport,
+};
@@ -1,34 +1,37 @@
 use anyhow::{anyhow, Result};
 use chrono::Utc;
+use runtime_skill_core::SkillConfig;
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 
-use crate::crypto::{derive_key, encrypt, make_verify_token};
-use crate::types::{PackConfig, SkillManifest};
+use crate::crypto::{encrypt, generate_content_key, make_verify_token};
+use crate::envelope::wrap_for_recipient;
+use crate::signing::sha256_hex;
+use crate::types::{PackConfig, Recipient, SkillManifest, SkillRequirements};
 
-fn has_root_skill_markdown(skill_dir: &Path) -> bool {
-    if skill_dir.join("SKILL.md").exists() || skill_dir.join("skill.md").exists() {
-        return true;
+fn root_skill_markdown(skill_dir: &Path) -> Option<PathBuf> {
+    for name in ["SKILL.md", "skill.md"] {
+        let path = skill_dir.join(name);
+        if path.is_file() {
+            return Some(path);
+        }
     }
 
-    let entries = match fs::read_dir(skill_dir) {
-        Ok(entries) => entries,
-        Err(_) => return false,
-    };
-
-    entries
+    fs::read_dir(skill_dir)
+        .ok()?
         .filter_map(|entry| entry.ok())
         .filter(|entry| entry.path().is_file())
-        .any(|entry| {
+        .find(|entry| {
             entry
                 .file_name()
                 .to_string_lossy()
                 .eq_ignore_ascii_case("skill.md")
         })
+        .map(|entry| entry.path())
 }
 
 fn canonical_rel_path(rel: &Path) -> String {
@@ -47,51 +50,48 @@ fn canonical_rel_path(rel: &Path) -> String {
     rel_str
 }
 
-/// Parse SKILL.md front matter (---\n...\n---\n)
+/// Parse SKILL.md front matter (---\n...\n---\n) with the shared YAML skill parser
 pub fn parse_front_matter(content: &str) -> crate::types::FrontMatter {
-    let mut fm = crate::types::FrontMatter {
-        name: None,
-        description: None,
-        version: None,
-        model: None,
-    };
-    let mut in_fm = false;
-    for line in content.lines() {
-        if line == "---" {
-            if !in_fm {
-                in_fm = true;
-                continue;
-            } else {
-                break;
-            }
-        }
-        if !in_fm {
-            continue;
-        }
-        if let Some(rest) = line.strip_prefix("name:") {
-            fm.name = Some(rest.trim().to_string());
-        } else if let Some(rest) = line.strip_prefix("description:") {
-            fm.description = Some(rest.trim().to_string());
-        } else if let Some(rest) = line.strip_prefix("version:") {
-            fm.version = Some(rest.trim().to_string());
-        } else if let Some(rest) = line.strip_prefix("model:") {
-            fm.model = Some(rest.trim().to_string());
-        }
+    let config = SkillConfig::parse(content);
+    crate::types::FrontMatter {
+        name: config.name,
+        description: config.description.map(|d| d.trim().to_string()),
+        version: config.version,
+        model: config.model,
     }
-    fm
 }
 
-pub fn pack(config: &PackConfig) -> Result<()> {
+/// (relative path, bytes) of one file in a skill directory or pack
+pub(crate) type PackFile = (String, Vec<u8>);
+
+/// SKILL.md config plus every file of the skill directory as plaintext
+pub(crate) fn read_skill_files(config: &PackConfig) -> Result<(SkillConfig, Vec<PackFile>)> {
     let skill_dir = Path::new(&config.dir_path);
-    if !has_root_skill_markdown(skill_dir) {
-        return Err(anyhow!("SKILL.md not found in {:?}", skill_dir));
-    }
+    let skill_md = root_skill_markdown(skill_dir)
+        .ok_or_else(|| anyhow!("SKILL.md not found in {:?}", skill_dir))?;
+    let skill_config = SkillConfig::try_parse(&fs::read_to_string(&skill_md)?)
+        .map_err(|e| anyhow!("SKILL.md front matter error: {e}"))?;
 
-    let skill_id = Uuid::new_v4().to_string();
-    let key = derive_key(&config.username, &skill_id, &config.name);
+    let mut files = Vec::new();
+    for entry in WalkDir::new(skill_dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let abs_path = entry.path();
+        let rel = abs_path.strip_prefix(skill_dir)?;
+        files.push((canonical_rel_path(rel), fs::read(abs_path)?));
+    }
+    Ok((skill_config, files))
+}
 
-    let manifest = SkillManifest {
-        id: skill_id,
+/// Manifest for `config` listing the hashes of all `encrypted_files`.
+/// Key material (id, verify token, recipients) is filled in by the caller.
+pub(crate) fn build_manifest(
+    config: &PackConfig,
+    skill_config: &SkillConfig,
+    encrypted_files: &[PackFile],
+) -> SkillManifest {
+    SkillManifest {
         name: config.name.clone(),
         description: config.description.clone(),
         version: config.version.clone(),
@@ -99,9 +99,24 @@ pub fn pack(config: &PackConfig) -> Result<()> {
         recommended_model: config.recommended_model.clone(),
         tags: vec![],
         created_at: Utc::now(),
-        username_hint: Some(config.username.clone()),
-        encrypted_verify: make_verify_token(&key)?,
-    };
+        username_hint: Some(config.username.clone()).filter(|u| !u.trim().is_empty()),
+        file_hashes: encrypted_files
+            .iter()
+            .map(|(rel, ciphertext)| (rel.clone(), sha256_hex(ciphertext)))
+            .collect(),
+        publisher_key_id: config.signing_key.as_ref().map(|k| k.key_id()),
+        requirements: Some(SkillRequirements::from_config(skill_config)),
+        ..Default::default()
+    }
+}
+
+/// Write manifest.json, the optional signature and the given encrypted entries.
+pub(crate) fn write_pack(
+    config: &PackConfig,
+    manifest: &SkillManifest,
+    encrypted_files: &[PackFile],
+) -> Result<()> {
+    let manifest_bytes = serde_json::to_vec_pretty(manifest)?;
 
     let output_file = fs::File::create(&config.output_path)?;
     let mut zip = zip::ZipWriter::new(output_file);
@@ -109,29 +124,65 @@ pub fn pack(config: &PackConfig) -> Result<()> {
 
     // Write manifest.json (plaintext)
     zip.start_file("manifest.json", options)?;
-    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
-
-    // Encrypt and write all files under encrypted/
-    for entry in WalkDir::new(skill_dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_dir() {
-            continue;
-        }
-        let abs_path = entry.path();
-        let rel = abs_path.strip_prefix(skill_dir)?;
-        let rel_str = canonical_rel_path(rel);
+    zip.write_all(&manifest_bytes)?;
 
-        let plaintext = fs::read(abs_path)?;
-        let ciphertext = encrypt(&plaintext, &key)?;
+    // Detached signature over the exact manifest bytes
+    if let Some(signing_key) = &config.signing_key {
+        let signature = signing_key.sign(&manifest_bytes);
+        zip.start_file("signature.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&signature)?.as_bytes())?;
+    }
 
+    // Write all files under encrypted/
+    for (rel_str, ciphertext) in encrypted_files {
         let enc_path = format!("encrypted/{}.enc", rel_str);
         zip.start_file(&enc_path, options)?;
-        zip.write_all(&ciphertext)?;
+        zip.write_all(ciphertext)?;
     }
 
     zip.finish()?;
     Ok(())
 }
 
+pub fn pack(config: &PackConfig) -> Result<()> {
+    let (skill_config, files) = read_skill_files(config)?;
+
+    let mut recipients = Vec::new();
+    if !config.username.trim().is_empty() {
+        recipients.push(Recipient::User(config.username.clone()));
+    }
+    recipients.extend(config.recipients.iter().cloned());
+    if recipients.is_empty() {
+        return Err(anyhow!("skillpack needs at least one recipient"));
+    }
+
+    // Keep the id of earlier versions so installed copies can be upgraded in place
+    let skill_id = config
+        .skill_id
+        .clone()
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let key = generate_content_key();
+    let recipient_entries = recipients
+        .iter()
+        .map(|r| wrap_for_recipient(&key, r, &skill_id))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Encrypt all files up front so the manifest can list their hashes
+    let encrypted_files = files
+        .iter()
+        .map(|(rel, plaintext)| Ok((rel.clone(), encrypt(plaintext, &key)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let manifest = SkillManifest {
+        id: skill_id,
+        encrypted_verify: make_verify_token(&key)?,
+        recipients: recipient_entries,
+        ..build_manifest(config, &skill_config, &encrypted_files)
+    };
+    write_pack(config, &manifest, &encrypted_files)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +217,9 @@ mod tests {
             username: "alice".to_string(),
             recommended_model: "claude-3-5-sonnet-20241022".to_string(),
             output_path: output.to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
         };
         pack(&config).unwrap();
         assert!(output.exists());
@@ -188,6 +242,9 @@ mod tests {
                 .join("out.skillpack")
                 .to_string_lossy()
                 .to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
         };
         assert!(pack(&config).is_err());
     }
@@ -209,6 +266,9 @@ mod tests {
             username: "alice".to_string(),
             recommended_model: "claude-3-5-sonnet-20241022".to_string(),
             output_path: output.to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
         };
         pack(&config).unwrap();
 
@@ -229,4 +289,83 @@ mod tests {
         skill_entry.read_to_end(&mut encrypted_bytes).unwrap();
         assert!(!encrypted_bytes.is_empty());
     }
+
+    #[test]
+    fn test_parse_front_matter_multiline_description() {
+        let fm = parse_front_matter(
+            "---\nname: Multi\ndescription: >\n  Reviews contracts\n  and flags risks\nversion: 2.0.0\n---\nBody",
+        );
+        assert_eq!(fm.name.as_deref(), Some("Multi"));
+        assert_eq!(
+            fm.description.as_deref(),
+            Some("Reviews contracts and flags risks")
+        );
+        assert_eq!(fm.version.as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn test_pack_rejects_invalid_front_matter() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("skill");
+        fs::create_dir(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: [broken\n---\n").unwrap();
+        let config = PackConfig {
+            dir_path: skill_dir.to_string_lossy().to_string(),
+            name: "Broken".to_string(),
+            description: "".to_string(),
+            version: "1.0.0".to_string(),
+            author: "".to_string(),
+            username: "alice".to_string(),
+            recommended_model: "".to_string(),
+            output_path: dir
+                .path()
+                .join("out.skillpack")
+                .to_string_lossy()
+                .to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
+        };
+        let err = pack(&config).unwrap_err();
+        assert!(err.to_string().contains("front matter"));
+    }
+
+    #[test]
+    fn test_pack_embeds_requirements_in_manifest() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("skill");
+        fs::create_dir(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: Req\nallowed_tools: read_file, bash\nrequires_toolsets:\n  - web\nmcp_servers:\n  - name: brave-search\n    command: npx\n---\nBody",
+        )
+        .unwrap();
+        let output = dir.path().join("req.skillpack");
+        pack(&PackConfig {
+            dir_path: skill_dir.to_string_lossy().to_string(),
+            name: "Req".to_string(),
+            description: "".to_string(),
+            version: "1.0.0".to_string(),
+            author: "".to_string(),
+            username: "alice".to_string(),
+            recommended_model: "".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            signing_key: None,
+            recipients: vec![],
+            skill_id: None,
+        })
+        .unwrap();
+
+        let mut zip = zip::ZipArchive::new(fs::File::open(output).unwrap()).unwrap();
+        let mut raw = String::new();
+        zip.by_name("manifest.json")
+            .unwrap()
+            .read_to_string(&mut raw)
+            .unwrap();
+        let manifest: SkillManifest = serde_json::from_str(&raw).unwrap();
+        let requirements = manifest.requirements.unwrap();
+        assert_eq!(requirements.allowed_tools, vec!["read_file", "bash"]);
+        assert_eq!(requirements.requires_toolsets, vec!["web"]);
+        assert_eq!(requirements.mcp_servers[0].name, "brave-search");
+    }
 }
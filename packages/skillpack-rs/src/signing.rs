@@ -0,0 +1,317 @@
+use aes_gcm::aead::OsRng;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+use crate::types::{PackSignature, SkillManifest};
+
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Ed25519 signing key owned by a skill publisher.
+#[derive(Debug, Clone)]
+pub struct PublisherKey {
+    signing_key: SigningKey,
+}
+
+impl PublisherKey {
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    pub fn from_base64(secret: &str) -> Result<Self> {
+        let bytes = B64
+            .decode(secret.trim())
+            .map_err(|e| anyhow!("invalid signing key: {e}"))?;
+        let secret: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("signing key must be 32 bytes"))?;
+        Ok(Self::from_bytes(&secret))
+    }
+
+    pub fn to_base64(&self) -> String {
+        B64.encode(self.signing_key.to_bytes())
+    }
+
+    pub fn public_key_base64(&self) -> String {
+        B64.encode(self.signing_key.verifying_key().to_bytes())
+    }
+
+    pub fn key_id(&self) -> String {
+        key_id_for(&self.signing_key.verifying_key().to_bytes())
+    }
+
+    pub fn sign(&self, manifest_bytes: &[u8]) -> PackSignature {
+        let signature = self.signing_key.sign(manifest_bytes);
+        PackSignature {
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            key_id: self.key_id(),
+            public_key: self.public_key_base64(),
+            signature: B64.encode(signature.to_bytes()),
+        }
+    }
+}
+
+/// Short, stable identifier for a public key: first 8 bytes of its SHA-256, hex encoded.
+pub fn key_id_for(public_key: &[u8]) -> String {
+    to_hex(&Sha256::digest(public_key)[..8])
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_public_key(public_key: &str) -> Result<VerifyingKey> {
+    let bytes = B64
+        .decode(public_key.trim())
+        .map_err(|e| anyhow!("invalid public key: {e}"))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow!("public key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("invalid public key: {e}"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedPublisher {
+    pub key_id: String,
+    pub name: String,
+    pub public_key: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Local list of publisher keys whose packs are considered trusted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustedKeyring {
+    pub publishers: Vec<TrustedPublisher>,
+}
+
+impl TrustedKeyring {
+    /// Load a keyring from disk; a missing file yields an empty keyring.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Trust a base64 encoded public key under a display name. Returns the key id.
+    pub fn add(&mut self, name: &str, public_key: &str) -> Result<String> {
+        let verifying_key = decode_public_key(public_key)?;
+        let key_id = key_id_for(&verifying_key.to_bytes());
+        self.publishers.retain(|p| p.key_id != key_id);
+        self.publishers.push(TrustedPublisher {
+            key_id: key_id.clone(),
+            name: name.to_string(),
+            public_key: B64.encode(verifying_key.to_bytes()),
+            added_at: Utc::now(),
+        });
+        Ok(key_id)
+    }
+
+    pub fn remove(&mut self, key_id: &str) -> bool {
+        let before = self.publishers.len();
+        self.publishers.retain(|p| p.key_id != key_id);
+        self.publishers.len() != before
+    }
+
+    pub fn find(&self, key_id: &str) -> Option<&TrustedPublisher> {
+        self.publishers.iter().find(|p| p.key_id == key_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustStatus {
+    /// The pack carries no publisher signature
+    Unsigned,
+    /// Signature is valid but the key is not in the local keyring
+    Untrusted,
+    /// Signature is valid and the key is in the local keyring
+    Trusted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureReport {
+    pub status: TrustStatus,
+    pub key_id: Option<String>,
+    pub publisher: Option<String>,
+}
+
+impl SignatureReport {
+    pub fn unsigned() -> Self {
+        Self {
+            status: TrustStatus::Unsigned,
+            key_id: None,
+            publisher: None,
+        }
+    }
+
+    pub fn is_trusted(&self) -> bool {
+        self.status == TrustStatus::Trusted
+    }
+}
+
+/// Verify the detached signature over the raw manifest bytes.
+///
+/// A bad signature, a key id that does not match the embedded public key, or a
+/// manifest that names a publisher but ships without a signature is an error.
+/// A valid signature is reported as trusted only when its key is in `keyring`.
+pub fn verify_signature(
+    manifest_bytes: &[u8],
+    manifest: &SkillManifest,
+    signature: Option<&PackSignature>,
+    keyring: &TrustedKeyring,
+) -> Result<SignatureReport> {
+    let Some(signature) = signature else {
+        if manifest.publisher_key_id.is_some() {
+            return Err(anyhow!("skillpack signature missing"));
+        }
+        return Ok(SignatureReport::unsigned());
+    };
+
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        return Err(anyhow!(
+            "unsupported signature algorithm: {}",
+            signature.algorithm
+        ));
+    }
+    let verifying_key = decode_public_key(&signature.public_key)?;
+    let key_id = key_id_for(&verifying_key.to_bytes());
+    if key_id != signature.key_id {
+        return Err(anyhow!("signature key id does not match public key"));
+    }
+    if manifest.publisher_key_id.as_deref() != Some(key_id.as_str()) {
+        return Err(anyhow!("manifest publisher does not match signature"));
+    }
+
+    let sig_bytes = B64
+        .decode(signature.signature.trim())
+        .map_err(|e| anyhow!("invalid signature encoding: {e}"))?;
+    let sig_bytes: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow!("signature must be 64 bytes"))?;
+    verifying_key
+        .verify(manifest_bytes, &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| anyhow!("skillpack signature invalid — manifest was modified"))?;
+
+    // Only trust the keyring entry if it holds the very same public key.
+    let trusted = keyring
+        .find(&key_id)
+        .filter(|p| decode_public_key(&p.public_key).ok() == Some(verifying_key));
+    Ok(SignatureReport {
+        status: if trusted.is_some() {
+            TrustStatus::Trusted
+        } else {
+            TrustStatus::Untrusted
+        },
+        key_id: Some(key_id),
+        publisher: trusted.map(|p| p.name.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_manifest(key: &PublisherKey) -> (Vec<u8>, SkillManifest, PackSignature) {
+        let manifest = SkillManifest {
+            id: "skill-1".to_string(),
+            name: "Signed".to_string(),
+            publisher_key_id: Some(key.key_id()),
+            ..Default::default()
+        };
+        let bytes = serde_json::to_vec_pretty(&manifest).unwrap();
+        let signature = key.sign(&bytes);
+        (bytes, manifest, signature)
+    }
+
+    #[test]
+    fn test_valid_signature_untrusted_without_keyring() {
+        let key = PublisherKey::generate();
+        let (bytes, manifest, signature) = signed_manifest(&key);
+        let report = verify_signature(
+            &bytes,
+            &manifest,
+            Some(&signature),
+            &TrustedKeyring::default(),
+        )
+        .unwrap();
+        assert_eq!(report.status, TrustStatus::Untrusted);
+        assert_eq!(report.key_id, Some(key.key_id()));
+    }
+
+    #[test]
+    fn test_keyring_marks_signature_trusted() {
+        let key = PublisherKey::generate();
+        let (bytes, manifest, signature) = signed_manifest(&key);
+        let mut keyring = TrustedKeyring::default();
+        keyring
+            .add("WorkClaw Team", &key.public_key_base64())
+            .unwrap();
+        let report = verify_signature(&bytes, &manifest, Some(&signature), &keyring).unwrap();
+        assert!(report.is_trusted());
+        assert_eq!(report.publisher.as_deref(), Some("WorkClaw Team"));
+    }
+
+    #[test]
+    fn test_modified_manifest_fails() {
+        let key = PublisherKey::generate();
+        let (mut bytes, manifest, signature) = signed_manifest(&key);
+        bytes.extend_from_slice(b" ");
+        let err = verify_signature(
+            &bytes,
+            &manifest,
+            Some(&signature),
+            &TrustedKeyring::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("signature invalid"));
+    }
+
+    #[test]
+    fn test_stripped_signature_fails() {
+        let key = PublisherKey::generate();
+        let (bytes, manifest, _) = signed_manifest(&key);
+        assert!(verify_signature(&bytes, &manifest, None, &TrustedKeyring::default()).is_err());
+    }
+
+    #[test]
+    fn test_keyring_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.json");
+        let key = PublisherKey::generate();
+        let mut keyring = TrustedKeyring::default();
+        let key_id = keyring.add("Team", &key.public_key_base64()).unwrap();
+        keyring.save(&path).unwrap();
+
+        let mut loaded = TrustedKeyring::load(&path).unwrap();
+        assert!(loaded.find(&key_id).is_some());
+        assert!(loaded.remove(&key_id));
+        assert!(loaded.find(&key_id).is_none());
+    }
+}
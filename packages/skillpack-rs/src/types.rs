@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
+use runtime_skill_core::{McpServerDep, SkillConfig};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::signing::PublisherKey;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SkillManifest {
     pub id: String,
     pub name: String,
@@ -13,6 +17,88 @@ pub struct SkillManifest {
     pub created_at: DateTime<Utc>,
     pub username_hint: Option<String>,
     pub encrypted_verify: String,
+    /// Relative path -> SHA-256 (hex) of the encrypted entry bytes
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_hashes: BTreeMap<String, String>,
+    /// Key id of the publisher that signed this manifest, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher_key_id: Option<String>,
+    /// Content key wrapped once per recipient; empty for legacy single-user packs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<RecipientEntry>,
+    /// Tool / toolset / MCP requirements parsed from SKILL.md at pack time,
+    /// readable without decrypting the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<SkillRequirements>,
+    /// Set on delta packs: the version this delta must be applied on top of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_base_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillRequirements {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_toolsets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub optional_toolsets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServerDep>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_bins: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_tool: Option<String>,
+}
+
+impl SkillRequirements {
+    pub fn from_config(config: &SkillConfig) -> Self {
+        let metadata = config.metadata.as_ref();
+        Self {
+            allowed_tools: config.allowed_tools.clone().unwrap_or_default(),
+            denied_tools: config.denied_tools.clone().unwrap_or_default(),
+            requires_toolsets: config.requires_toolsets.clone().unwrap_or_default(),
+            optional_toolsets: config.optional_toolsets.clone().unwrap_or_default(),
+            mcp_servers: config.mcp_servers.clone(),
+            required_bins: metadata
+                .and_then(|m| m.requires.as_ref())
+                .map(|r| r.bins.clone())
+                .unwrap_or_default(),
+            primary_env: metadata.and_then(|m| m.primary_env.clone()),
+            command_tool: config
+                .command_dispatch
+                .as_ref()
+                .map(|d| d.tool_name.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientKind {
+    User,
+    Team,
+}
+
+/// One wrapped copy of the pack content key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecipientEntry {
+    pub kind: RecipientKind,
+    /// Username for `user` recipients, team label for `team` recipients
+    pub hint: String,
+    /// Base64 of the content key encrypted with the recipient's key
+    pub wrapped_key: String,
+}
+
+/// Someone who should be able to open a pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    User(String),
+    Team { label: String, passphrase: String },
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +111,24 @@ pub struct PackConfig {
     pub username: String,
     pub recommended_model: String,
     pub output_path: String,
+    /// When set, the pack is signed with this publisher key
+    pub signing_key: Option<PublisherKey>,
+    /// Additional recipients besides `username` (left empty for a single-user pack)
+    pub recipients: Vec<Recipient>,
+    /// Id of a previously released version; `None` mints a new skill id
+    pub skill_id: Option<String>,
+}
+
+/// Detached Ed25519 signature over the raw `manifest.json` bytes,
+/// stored as `signature.json` next to the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackSignature {
+    pub algorithm: String,
+    pub key_id: String,
+    /// Base64 encoded 32-byte Ed25519 public key
+    pub public_key: String,
+    /// Base64 encoded 64-byte Ed25519 signature
+    pub signature: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
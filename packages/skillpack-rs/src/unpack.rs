@@ -1,58 +1,312 @@
 use anyhow::{anyhow, Result};
-use std::io::Read;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
 
-use crate::crypto::{check_verify_token, decrypt, derive_key};
-use crate::types::SkillManifest;
+use crate::crypto::{decrypt, CIPHERTEXT_OVERHEAD};
+use crate::envelope::unlock_content_key;
+use crate::signing::{to_hex, verify_signature, SignatureReport, TrustedKeyring};
+use crate::types::{PackSignature, SkillManifest};
+
+/// Chunk size used when reading encrypted entries out of the archive.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
 
 #[derive(Debug)]
 pub struct UnpackedSkill {
     pub manifest: SkillManifest,
     /// Map of relative path -> decrypted content bytes
     /// e.g. "SKILL.md" -> b"..."
-    pub files: std::collections::HashMap<String, Vec<u8>>,
+    pub files: HashMap<String, Vec<u8>>,
+    /// Publisher signature status of the pack
+    pub signature: SignatureReport,
+}
+
+/// Read the plaintext manifest without unlocking the pack, e.g. to show
+/// requirements and recipients before asking for a username.
+pub fn read_manifest(pack_path: &str) -> Result<SkillManifest> {
+    let file = fs::File::open(pack_path)?;
+    let mut zip = zip::ZipArchive::new(file)?;
+    let mut entry = zip
+        .by_name("manifest.json")
+        .map_err(|_| anyhow!("manifest.json not found in skillpack"))?;
+    let mut buf = String::new();
+    entry.read_to_string(&mut buf)?;
+    Ok(serde_json::from_str(&buf)?)
 }
 
 pub fn verify_and_unpack(pack_path: &str, username: &str) -> Result<UnpackedSkill> {
-    let file = std::fs::File::open(pack_path)?;
+    verify_and_unpack_with_keyring(pack_path, username, &TrustedKeyring::default())
+}
+
+/// Like [`verify_and_unpack`], but reports packs signed by a key in `keyring` as trusted.
+pub fn verify_and_unpack_with_keyring(
+    pack_path: &str,
+    username: &str,
+    keyring: &TrustedKeyring,
+) -> Result<UnpackedSkill> {
+    let mut files = HashMap::new();
+    let (manifest, signature) = unpack_entries(
+        pack_path,
+        username,
+        keyring,
+        &UnpackLimits::default(),
+        |rel, plain| {
+            files.insert(rel.to_string(), plain);
+            Ok(())
+        },
+        |_| {},
+    )?;
+
+    Ok(UnpackedSkill {
+        manifest,
+        files,
+        signature,
+    })
+}
+
+/// Caps applied while unpacking so a hostile or oversized pack cannot
+/// exhaust memory or disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnpackLimits {
+    pub max_entries: usize,
+    /// Largest single decrypted file, in bytes
+    pub max_file_size: u64,
+    /// Sum of all decrypted files, in bytes
+    pub max_total_size: u64,
+}
+
+impl Default for UnpackLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_file_size: 256 * 1024 * 1024,
+            max_total_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnpackProgress {
+    /// Relative path of the file just written
+    pub path: String,
+    /// 1-based index of the file just written
+    pub completed: usize,
+    pub total: usize,
+    pub bytes_written: u64,
+}
+
+#[derive(Debug)]
+pub struct UnpackReport {
+    pub manifest: SkillManifest,
+    pub signature: SignatureReport,
+    /// Relative paths written under the target directory
+    pub files: Vec<String>,
+    pub bytes_written: u64,
+}
+
+/// Decrypt a pack straight into `target_dir`, one entry at a time, so only a
+/// single file is ever held in memory. `progress` is called after each file.
+///
+/// Entries are written to a fresh sibling directory that replaces
+/// `target_dir` only once every file has been verified, so a failed or
+/// tampered pack never leaves a half-written skill behind.
+pub fn unpack_to_dir(
+    pack_path: &str,
+    username: &str,
+    target_dir: &Path,
+    keyring: &TrustedKeyring,
+    limits: &UnpackLimits,
+    mut progress: impl FnMut(&UnpackProgress),
+) -> Result<UnpackReport> {
+    let staging = sibling_path(target_dir, "unpack")?;
+    fs::create_dir_all(staging.parent().unwrap_or(Path::new(".")))?;
+    fs::create_dir(&staging)?;
+    let mut files = Vec::new();
+    let mut bytes_written = 0u64;
+    let unpacked = unpack_entries(
+        pack_path,
+        username,
+        keyring,
+        limits,
+        |rel, plain| {
+            write_new_file(&staging, &safe_relative_path(rel)?, &plain)
+                .map_err(|e| anyhow!("failed to write skillpack entry {rel}: {e}"))?;
+            files.push(rel.to_string());
+            bytes_written += plain.len() as u64;
+            Ok(())
+        },
+        |p| progress(p),
+    )
+    .and_then(|unpacked| replace_dir(&staging, target_dir).map(|()| unpacked));
+    let (manifest, signature) = match unpacked {
+        Ok(unpacked) => unpacked,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+    };
+
+    Ok(UnpackReport {
+        manifest,
+        signature,
+        files,
+        bytes_written,
+    })
+}
+
+/// A hidden, uniquely named path next to `target`, on the same filesystem so
+/// the final rename is atomic.
+fn sibling_path(target: &Path, purpose: &str) -> Result<PathBuf> {
+    let name = target
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid unpack target: {}", target.display()))?;
+    Ok(target.with_file_name(format!(
+        ".{}.{purpose}-{}",
+        name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    )))
+}
+
+/// Create `root/rel` and its parents without following symlinks: every
+/// directory on the way must be a real directory and the file must not exist.
+fn write_new_file(root: &Path, rel: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut dir = root.to_path_buf();
+    if let Some(parent) = rel.parent() {
+        for component in parent.components() {
+            dir.push(component);
+            match fs::symlink_metadata(&dir) {
+                Ok(meta) if meta.is_dir() => {}
+                Ok(_) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        "path component is not a directory",
+                    ))
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => fs::create_dir(&dir)?,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    let mut file = options.open(root.join(rel))?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Swap `staging` into place at `target`, keeping the previous contents
+/// until the rename has succeeded.
+fn replace_dir(staging: &Path, target: &Path) -> Result<()> {
+    match fs::symlink_metadata(target) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            fs::rename(staging, target)?;
+        }
+        Err(err) => return Err(err.into()),
+        Ok(meta) if !meta.is_dir() => {
+            return Err(anyhow!(
+                "unpack target is not a directory: {}",
+                target.display()
+            ));
+        }
+        Ok(_) => {
+            let previous = sibling_path(target, "old")?;
+            fs::rename(target, &previous)?;
+            if let Err(err) = fs::rename(staging, target) {
+                let _ = fs::rename(&previous, target);
+                return Err(err.into());
+            }
+            let _ = fs::remove_dir_all(&previous);
+        }
+    }
+    Ok(())
+}
+
+/// Reject absolute paths, `..`, drive prefixes and empty names.
+pub fn safe_relative_path(rel: &str) -> Result<PathBuf> {
+    if rel.is_empty() || rel.contains('\\') {
+        return Err(anyhow!("unsafe skillpack entry path: {rel:?}"));
+    }
+    let path = Path::new(rel);
+    if !path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(anyhow!("unsafe skillpack entry path: {rel:?}"));
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Verify a pack and hand each decrypted file to `sink` in archive order.
+fn unpack_entries(
+    pack_path: &str,
+    username: &str,
+    keyring: &TrustedKeyring,
+    limits: &UnpackLimits,
+    mut sink: impl FnMut(&str, Vec<u8>) -> Result<()>,
+    mut progress: impl FnMut(&UnpackProgress),
+) -> Result<(SkillManifest, SignatureReport)> {
+    let file = fs::File::open(pack_path)?;
     let mut zip = zip::ZipArchive::new(file)?;
 
-    // Read manifest
-    let manifest: SkillManifest = {
+    // Read manifest (raw bytes are what the publisher signed)
+    let manifest_bytes = {
         let mut entry = zip
             .by_name("manifest.json")
             .map_err(|_| anyhow!("manifest.json not found in skillpack"))?;
-        let mut buf = String::new();
-        entry.read_to_string(&mut buf)?;
-        serde_json::from_str(&buf)?
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf)?;
+        buf
     };
+    let manifest: SkillManifest = serde_json::from_slice(&manifest_bytes)?;
 
-    // Derive key and verify username
-    let key = derive_key(username, &manifest.id, &manifest.name);
-    if !check_verify_token(&manifest.encrypted_verify, &key) {
-        return Err(anyhow!("用户名错误，无法解密此 Skill"));
-    }
+    let pack_signature: Option<PackSignature> = match zip.by_name("signature.json") {
+        Ok(mut entry) => {
+            let mut buf = String::new();
+            entry.read_to_string(&mut buf)?;
+            Some(serde_json::from_str(&buf)?)
+        }
+        Err(_) => None,
+    };
+    let signature = verify_signature(&manifest_bytes, &manifest, pack_signature.as_ref(), keyring)?;
+
+    // Unlock the content key with the username or team passphrase
+    let key = unlock_content_key(&manifest, username)?;
 
     // Collect encrypted file names first
-    let names: Vec<String> = (0..zip.len())
-        .filter_map(|i| {
-            let entry = zip.by_index(i).ok()?;
-            let name = entry.name().to_string();
-            if name.starts_with("encrypted/") && name.ends_with(".enc") {
-                Some(name)
-            } else {
-                None
-            }
-        })
+    let names: Vec<String> = zip
+        .file_names()
+        .filter(|name| name.starts_with("encrypted/") && name.ends_with(".enc"))
+        .map(ToString::to_string)
         .collect();
+    if names.len() > limits.max_entries {
+        return Err(anyhow!(
+            "skillpack has {} files, limit is {}",
+            names.len(),
+            limits.max_entries
+        ));
+    }
 
-    // Decrypt all files in encrypted/
-    let mut files = std::collections::HashMap::new();
-    for enc_name in names {
-        let mut entry = zip.by_name(&enc_name)?;
-        let mut buf = Vec::new();
-        entry.read_to_end(&mut buf)?;
-        let plain = decrypt(&buf, &key)?;
+    // Every encrypted entry must match the hash list, and none may be missing
+    if !manifest.file_hashes.is_empty() && names.len() != manifest.file_hashes.len() {
+        if let Some(base_version) = &manifest.delta_base_version {
+            return Err(anyhow!(
+                "delta skillpack must be applied to version {base_version} first"
+            ));
+        }
+        return Err(anyhow!("skillpack file list does not match manifest"));
+    }
 
+    // Decrypt files in encrypted/ one at a time
+    let total = names.len();
+    let mut total_size = 0u64;
+    for (index, enc_name) in names.into_iter().enumerate() {
         // Strip "encrypted/" prefix and ".enc" suffix to get original path
         let rel = enc_name
             .strip_prefix("encrypted/")
@@ -60,22 +314,86 @@ pub fn verify_and_unpack(pack_path: &str, username: &str) -> Result<UnpackedSkil
             .strip_suffix(".enc")
             .unwrap()
             .to_string();
-        files.insert(rel, plain);
+        safe_relative_path(&rel)?;
+
+        // Ciphertext is nonce + plaintext + tag; never trust the header size alone.
+        // Chunks feed the hasher as they are read so oversized entries stop early.
+        let max_ciphertext = limits.max_file_size + CIPHERTEXT_OVERHEAD;
+        let mut entry = zip.by_name(&enc_name)?;
+        let mut hasher = Sha256::new();
+        let mut buf = Vec::new();
+        let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+        loop {
+            let read = entry.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            if (buf.len() + read) as u64 > max_ciphertext {
+                return Err(anyhow!(
+                    "skillpack file {rel} exceeds {} bytes",
+                    limits.max_file_size
+                ));
+            }
+            hasher.update(&chunk[..read]);
+            buf.extend_from_slice(&chunk[..read]);
+        }
+
+        if !manifest.file_hashes.is_empty()
+            && manifest.file_hashes.get(&rel) != Some(&to_hex(&hasher.finalize()))
+        {
+            return Err(anyhow!("skillpack file {rel} was modified"));
+        }
+
+        let plain = decrypt(&buf, &key)?;
+        total_size += plain.len() as u64;
+        if total_size > limits.max_total_size {
+            return Err(anyhow!(
+                "skillpack exceeds {} bytes when unpacked",
+                limits.max_total_size
+            ));
+        }
+        sink(&rel, plain)?;
+        progress(&UnpackProgress {
+            path: rel,
+            completed: index + 1,
+            total,
+            bytes_written: total_size,
+        });
     }
 
-    Ok(UnpackedSkill { manifest, files })
+    Ok((manifest, signature))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::envelope::{add_recipient, revoke_recipient};
     use crate::pack::pack;
-    use crate::types::PackConfig;
+    use crate::signing::{PublisherKey, TrustStatus};
+    use crate::types::{PackConfig, Recipient};
     use std::fs;
+    use std::io::{Read, Write};
     use std::path::Path;
     use tempfile::tempdir;
 
     fn setup_and_pack(dir: &Path, username: &str) -> String {
+        setup_and_pack_signed(dir, username, None)
+    }
+
+    fn setup_and_pack_signed(
+        dir: &Path,
+        username: &str,
+        signing_key: Option<PublisherKey>,
+    ) -> String {
+        setup_and_pack_with(dir, username, signing_key, vec![])
+    }
+
+    fn setup_and_pack_with(
+        dir: &Path,
+        username: &str,
+        signing_key: Option<PublisherKey>,
+        recipients: Vec<Recipient>,
+    ) -> String {
         let skill_dir = dir.join("skill");
         fs::create_dir(&skill_dir).unwrap();
         fs::write(
@@ -93,6 +411,9 @@ mod tests {
             username: username.to_string(),
             recommended_model: "claude-3-5-sonnet-20241022".to_string(),
             output_path: output.to_string_lossy().to_string(),
+            signing_key,
+            recipients,
+            skill_id: None,
         })
         .unwrap();
         output.to_string_lossy().to_string()
@@ -115,4 +436,245 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("用户名错误"));
     }
+
+    /// Rewrite the archive, replacing the bytes of one entry.
+    fn rewrite_entry(pack_path: &str, target: &str, edit: impl Fn(Vec<u8>) -> Vec<u8>) {
+        let original = fs::read(pack_path).unwrap();
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(original)).unwrap();
+        let mut writer = zip::ZipWriter::new(fs::File::create(pack_path).unwrap());
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).unwrap();
+            let name = entry.name().to_string();
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf).unwrap();
+            if name == target {
+                buf = edit(buf);
+            }
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(&buf).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_unsigned_pack_reports_unsigned() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack(dir.path(), "alice");
+        let result = verify_and_unpack(&pack_path, "alice").unwrap();
+        assert_eq!(result.signature.status, TrustStatus::Unsigned);
+    }
+
+    #[test]
+    fn test_signed_pack_trusted_with_keyring() {
+        let dir = tempdir().unwrap();
+        let key = PublisherKey::generate();
+        let pack_path = setup_and_pack_signed(dir.path(), "alice", Some(key.clone()));
+
+        let untrusted = verify_and_unpack(&pack_path, "alice").unwrap();
+        assert_eq!(untrusted.signature.status, TrustStatus::Untrusted);
+        assert_eq!(untrusted.signature.key_id, Some(key.key_id()));
+
+        let mut keyring = TrustedKeyring::default();
+        keyring.add("Team", &key.public_key_base64()).unwrap();
+        let trusted = verify_and_unpack_with_keyring(&pack_path, "alice", &keyring).unwrap();
+        assert!(trusted.signature.is_trusted());
+    }
+
+    #[test]
+    fn test_edited_manifest_rejected() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack_signed(dir.path(), "alice", Some(PublisherKey::generate()));
+        rewrite_entry(&pack_path, "manifest.json", |bytes| {
+            String::from_utf8(bytes)
+                .unwrap()
+                .replace("\"desc\"", "\"tampered\"")
+                .into_bytes()
+        });
+        let err = verify_and_unpack(&pack_path, "alice").unwrap_err();
+        assert!(err.to_string().contains("signature invalid"));
+    }
+
+    #[test]
+    fn test_modified_file_rejected() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack_signed(dir.path(), "alice", Some(PublisherKey::generate()));
+        rewrite_entry(&pack_path, "encrypted/SKILL.md.enc", |mut bytes| {
+            let last = bytes.len() - 1;
+            bytes[last] ^= 0xff;
+            bytes
+        });
+        let err = verify_and_unpack(&pack_path, "alice").unwrap_err();
+        assert!(err.to_string().contains("was modified"));
+    }
+
+    #[test]
+    fn test_multiple_recipients_and_team_passphrase_unpack() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack_with(
+            dir.path(),
+            "alice",
+            None,
+            vec![
+                Recipient::User("bob".to_string()),
+                Recipient::Team {
+                    label: "legal-team".to_string(),
+                    passphrase: "s3cret".to_string(),
+                },
+            ],
+        );
+        for credential in ["alice", "bob", "s3cret"] {
+            let result = verify_and_unpack(&pack_path, credential).unwrap();
+            assert!(result.files.contains_key("SKILL.md"));
+        }
+        assert!(verify_and_unpack(&pack_path, "carol").is_err());
+        assert!(verify_and_unpack(&pack_path, "legal-team").is_err());
+    }
+
+    #[test]
+    fn test_add_and_revoke_recipient_without_reencrypting() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack(dir.path(), "alice");
+        let files_before = verify_and_unpack(&pack_path, "alice")
+            .unwrap()
+            .manifest
+            .file_hashes;
+
+        add_recipient(
+            &pack_path,
+            "alice",
+            &Recipient::User("bob".to_string()),
+            None,
+        )
+        .unwrap();
+        let unpacked = verify_and_unpack(&pack_path, "bob").unwrap();
+        assert_eq!(unpacked.manifest.file_hashes, files_before);
+
+        revoke_recipient(&pack_path, "bob", None).unwrap();
+        assert!(verify_and_unpack(&pack_path, "bob").is_err());
+        assert!(verify_and_unpack(&pack_path, "alice").is_ok());
+    }
+
+    #[test]
+    fn test_update_signed_pack_requires_signing_key() {
+        let dir = tempdir().unwrap();
+        let key = PublisherKey::generate();
+        let pack_path = setup_and_pack_signed(dir.path(), "alice", Some(key.clone()));
+        let bob = Recipient::User("bob".to_string());
+        assert!(add_recipient(&pack_path, "alice", &bob, None).is_err());
+
+        add_recipient(&pack_path, "alice", &bob, Some(&key)).unwrap();
+        let result = verify_and_unpack(&pack_path, "bob").unwrap();
+        assert_eq!(result.signature.status, TrustStatus::Untrusted);
+    }
+
+    #[test]
+    fn test_unpack_to_dir_writes_files_and_reports_progress() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack(dir.path(), "alice");
+        let target = dir.path().join("out");
+        let mut seen = Vec::new();
+        let report = unpack_to_dir(
+            &pack_path,
+            "alice",
+            &target,
+            &TrustedKeyring::default(),
+            &UnpackLimits::default(),
+            |p| seen.push((p.path.clone(), p.completed, p.total)),
+        )
+        .unwrap();
+        assert_eq!(report.files, vec!["SKILL.md"]);
+        assert_eq!(seen, vec![("SKILL.md".to_string(), 1, 1)]);
+        assert!(fs::read_to_string(target.join("SKILL.md"))
+            .unwrap()
+            .contains("You are a test."));
+    }
+
+    #[test]
+    fn test_unpack_enforces_size_and_entry_limits() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack(dir.path(), "alice");
+        let target = dir.path().join("out");
+        let keyring = TrustedKeyring::default();
+
+        let tiny_file = UnpackLimits {
+            max_file_size: 4,
+            ..UnpackLimits::default()
+        };
+        let err =
+            unpack_to_dir(&pack_path, "alice", &target, &keyring, &tiny_file, |_| {}).unwrap_err();
+        assert!(err.to_string().contains("exceeds 4 bytes"));
+
+        let no_entries = UnpackLimits {
+            max_entries: 0,
+            ..UnpackLimits::default()
+        };
+        let err =
+            unpack_to_dir(&pack_path, "alice", &target, &keyring, &no_entries, |_| {}).unwrap_err();
+        assert!(err.to_string().contains("limit is 0"));
+    }
+
+    #[test]
+    fn test_unpack_to_dir_replaces_target_only_on_success() {
+        let dir = tempdir().unwrap();
+        let pack_path = setup_and_pack(dir.path(), "alice");
+        let target = dir.path().join("out");
+        let keyring = TrustedKeyring::default();
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("stale.md"), "old").unwrap();
+
+        let tiny_file = UnpackLimits {
+            max_file_size: 4,
+            ..UnpackLimits::default()
+        };
+        assert!(unpack_to_dir(&pack_path, "alice", &target, &keyring, &tiny_file, |_| {}).is_err());
+        assert!(target.join("stale.md").exists());
+        assert!(!target.join("SKILL.md").exists());
+
+        unpack_to_dir(
+            &pack_path,
+            "alice",
+            &target,
+            &keyring,
+            &UnpackLimits::default(),
+            |_| {},
+        )
+        .unwrap();
+        assert!(target.join("SKILL.md").exists());
+        assert!(!target.join("stale.md").exists());
+        let leftovers = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".out."))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_new_file_refuses_symlinked_destination() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("root");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("templates")).unwrap();
+        std::os::unix::fs::symlink(outside.join("x.md"), root.join("SKILL.md")).unwrap();
+
+        assert!(write_new_file(&root, Path::new("templates/a.md"), b"x").is_err());
+        assert!(write_new_file(&root, Path::new("SKILL.md"), b"x").is_err());
+        assert!(!outside.join("a.md").exists());
+        assert!(!outside.join("x.md").exists());
+    }
+
+    #[test]
+    fn test_safe_relative_path_rejects_traversal() {
+        assert!(safe_relative_path("templates/a.md").is_ok());
+        assert!(safe_relative_path("../evil").is_err());
+        assert!(safe_relative_path("a/../../evil").is_err());
+        assert!(safe_relative_path("/etc/passwd").is_err());
+        assert!(safe_relative_path("..\\evil").is_err());
+        assert!(safe_relative_path("").is_err());
+    }
 }